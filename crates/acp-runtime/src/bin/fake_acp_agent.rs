@@ -0,0 +1,26 @@
+//! Fake ACP agent for deterministic integration tests.
+//!
+//! Speaks ACP over stdio like `stakpak acp`, but plays back a YAML script or
+//! a JSONL recording instead of calling a model:
+//!
+//! ```text
+//! fake-acp-agent <script.yaml|recording.jsonl>
+//! FAKE_ACP_SCRIPT=script.yaml fake-acp-agent
+//! ```
+
+use acp_runtime::fake_agent::{FakeAgent, FakeAgentScript};
+use acp_runtime::serve_stdio_agent_with;
+use anyhow::Context;
+
+const SCRIPT_ENV: &str = "FAKE_ACP_SCRIPT";
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let path = std::env::args()
+        .nth(1)
+        .or_else(|| std::env::var(SCRIPT_ENV).ok())
+        .with_context(|| format!("usage: fake-acp-agent <script> (or set {SCRIPT_ENV})"))?;
+    let script = FakeAgentScript::load(&path)?;
+
+    serve_stdio_agent_with(|connection| FakeAgent::new(script, connection)).await
+}
@@ -1,4 +1,5 @@
 use crate::interrupt_bridge::{spawn_interrupt_bridge, DEFAULT_INTERRUPT_PATH};
+use crate::recorder::{AcpRecorder, RecorderSide, RECORD_PATH_ENV};
 use crate::types::{AcpImplementationInfo, AcpPermissionPolicy, AcpPromptRequest, AcpPromptResult};
 use agent_client_protocol::{
     Agent, CancelNotification, Client, ClientCapabilities, ClientSideConnection, ContentBlock,
@@ -12,6 +13,7 @@ use async_trait::async_trait;
 use cto_config::{AcpRuntimeConfig, AcpTransport};
 use std::process::Stdio;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::process::Command;
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 use tracing::warn;
@@ -163,6 +165,18 @@ pub async fn run_oneshot_prompt(
                     tokio::task::spawn_local(future);
                 },
             );
+            let recorder_handle = match std::env::var(RECORD_PATH_ENV) {
+                Ok(path) if !path.is_empty() => {
+                    match AcpRecorder::create(&path, RecorderSide::Client) {
+                        Ok(recorder) => Some(recorder.spawn(connection.subscribe())),
+                        Err(err) => {
+                            warn!(error = %err, "failed to start ACP recorder");
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
             let connection = Arc::new(connection);
 
             tokio::task::spawn_local(async move {
@@ -239,6 +253,11 @@ pub async fn run_oneshot_prompt(
             }
             let _ = child.start_kill();
             let _ = child.wait().await;
+            drop(connection);
+            if let Some(handle) = recorder_handle {
+                // The recorder drains once the connection and IO task are gone.
+                let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+            }
 
             Ok(AcpPromptResult {
                 runtime_id: request.runtime_id,
@@ -0,0 +1,627 @@
+//! Scriptable fake ACP agent for tests.
+//!
+//! [`FakeAgent`] implements the ACP `Agent` trait and plays back a
+//! [`FakeAgentScript`]: one [`FakeTurn`] per `session/prompt`, each made of
+//! session updates, permission requests, sleeps and cancellation waits,
+//! ending with a scripted stop reason. Scripts are written as YAML or derived
+//! from an [`crate::recorder`] JSONL recording of a real runtime.
+//!
+//! The `fake-acp-agent` binary hosts the agent with
+//! [`crate::serve_stdio_agent_with`] so it can stand in for `stakpak acp`
+//! in an `AcpRuntimeConfig`; tests can also serve it in-process with
+//! [`crate::server::serve_agent_io`].
+
+use std::cell::{Cell, RefCell};
+use std::path::Path;
+use std::time::Duration;
+
+use agent_client_protocol::{
+    Agent, AgentCapabilities, AuthenticateRequest, AuthenticateResponse, CancelNotification,
+    Client, ContentBlock, ContentChunk, Error, Implementation, InitializeRequest,
+    InitializeResponse, LoadSessionRequest, LoadSessionResponse, NewSessionRequest,
+    NewSessionResponse, PermissionOption, PermissionOptionKind, PromptRequest, PromptResponse,
+    RequestPermissionOutcome, RequestPermissionRequest, Result, SessionNotification, SessionUpdate,
+    StopReason, TextContent, ToolCallUpdate, ToolCallUpdateFields,
+};
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tracing::debug;
+
+use crate::recorder::{RecordedDirection, RecordedMessage, RecordedPayload};
+use crate::server::AgentConnectionHandle;
+
+/// Session ID handed out when a script does not specify one.
+pub const DEFAULT_FAKE_SESSION_ID: &str = "fake-session";
+
+/// Full script for a fake agent run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FakeAgentScript {
+    /// Implementation info reported from `initialize`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_info: Option<FakeAgentInfo>,
+
+    /// Session ID returned from `session/new`.
+    #[serde(default = "default_session_id")]
+    pub session_id: String,
+
+    /// Whether `initialize` advertises `loadSession` support.
+    #[serde(default = "default_true")]
+    pub load_session: bool,
+
+    /// Prompt turns, consumed in order by successive `session/prompt` calls.
+    #[serde(default)]
+    pub turns: Vec<FakeTurn>,
+}
+
+impl Default for FakeAgentScript {
+    fn default() -> Self {
+        Self {
+            agent_info: None,
+            session_id: default_session_id(),
+            load_session: true,
+            turns: Vec::new(),
+        }
+    }
+}
+
+/// Implementation info reported by the fake agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FakeAgentInfo {
+    /// Implementation name.
+    pub name: String,
+    /// Version string.
+    #[serde(default = "default_version")]
+    pub version: String,
+    /// Optional UI title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// A single scripted prompt turn.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FakeTurn {
+    /// Steps executed in order while the prompt is running.
+    #[serde(default)]
+    pub steps: Vec<FakeStep>,
+
+    /// Stop reason returned once every step has run.
+    #[serde(default = "default_stop_reason")]
+    pub stop_reason: StopReason,
+}
+
+/// One step of a scripted turn.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FakeStep {
+    /// Send an `agent_message_chunk` update with the given text.
+    Message(String),
+    /// Send an `agent_thought_chunk` update with the given text.
+    Thought(String),
+    /// Send an arbitrary `session/update` payload.
+    Update(SessionUpdate),
+    /// Ask the client for permission and branch on the outcome.
+    RequestPermission(FakePermissionStep),
+    /// Pause for the given number of milliseconds.
+    SleepMs(u64),
+    /// Block until the client sends `session/cancel`, then end the turn with
+    /// `cancelled`. Without a timeout the wait is unbounded.
+    WaitForCancel {
+        /// Give up waiting after this many milliseconds and continue.
+        #[serde(default, rename = "timeoutMs", skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+}
+
+/// A scripted `session/request_permission` call.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FakePermissionStep {
+    /// Tool call the permission is requested for.
+    pub tool_call: ToolCallUpdate,
+
+    /// Options offered to the client. Defaults to allow-once / reject-once.
+    #[serde(default = "default_permission_options")]
+    pub options: Vec<PermissionOption>,
+
+    /// Stop reason used to end the turn when the client cancels the request
+    /// or selects a reject option.
+    #[serde(default = "default_denied_stop_reason")]
+    pub on_denied: StopReason,
+}
+
+impl FakePermissionStep {
+    /// Permission step for a tool call with the default options.
+    #[must_use]
+    pub fn new(tool_call_id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            tool_call: ToolCallUpdate::new(
+                tool_call_id.into(),
+                ToolCallUpdateFields::new().title(title.into()),
+            ),
+            options: default_permission_options(),
+            on_denied: default_denied_stop_reason(),
+        }
+    }
+}
+
+/// Outcome of a permission request observed by the fake agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FakePermissionOutcome {
+    /// The client selected the option with this ID.
+    Selected {
+        /// Selected option ID.
+        option_id: String,
+        /// Whether the option was allow-like.
+        allowed: bool,
+    },
+    /// The client cancelled the request.
+    Cancelled,
+}
+
+impl FakeAgentScript {
+    /// Parse a YAML (or JSON) script.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document does not match the script schema.
+    pub fn from_yaml(source: &str) -> anyhow::Result<Self> {
+        // Go through a JSON value so steps use the `- message: text` map form
+        // rather than serde_yaml's `!tag` enum encoding.
+        let value: serde_json::Value =
+            serde_yaml::from_str(source).context("invalid fake ACP agent script YAML")?;
+        serde_json::from_value(value).context("invalid fake ACP agent script")
+    }
+
+    /// Load a script from disk. Files ending in `.jsonl` are treated as
+    /// recordings and converted with [`FakeAgentScript::from_recording`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|ext| ext == "jsonl") {
+            let recording = crate::recorder::read_recording(path)?;
+            return Ok(Self::from_recording(&recording));
+        }
+
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read fake ACP agent script {}", path.display()))?;
+        Self::from_yaml(&source)
+    }
+
+    /// Build a replay script from a recorded exchange.
+    ///
+    /// Every client `session/prompt` opens a turn. Agent `session/update`
+    /// notifications and `session/request_permission` requests observed
+    /// before the prompt response become the turn's steps, and the response's
+    /// stop reason ends it. Entries that do not parse are skipped.
+    #[must_use]
+    pub fn from_recording(messages: &[RecordedMessage]) -> Self {
+        let mut script = Self::default();
+        let mut initialize_id = None;
+        let mut new_session_id = None;
+        let mut prompt_id = None;
+        let mut current: Option<FakeTurn> = None;
+
+        for message in messages {
+            match (&message.direction, &message.payload) {
+                (RecordedDirection::ClientToAgent, RecordedPayload::Request { id, method, .. }) => {
+                    match method.as_str() {
+                        "initialize" => initialize_id = Some(id.clone()),
+                        "session/new" => new_session_id = Some(id.clone()),
+                        "session/prompt" => {
+                            prompt_id = Some(id.clone());
+                            current = Some(FakeTurn {
+                                steps: Vec::new(),
+                                stop_reason: default_stop_reason(),
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+                (
+                    RecordedDirection::AgentToClient,
+                    RecordedPayload::Response {
+                        id,
+                        result: Some(result),
+                        ..
+                    },
+                ) => {
+                    if initialize_id.as_ref() == Some(id) {
+                        if let Some(info) = result.get("agentInfo").and_then(|info| {
+                            serde_json::from_value::<Implementation>(info.clone()).ok()
+                        }) {
+                            script.agent_info = Some(FakeAgentInfo {
+                                name: info.name,
+                                version: info.version,
+                                title: info.title,
+                            });
+                        }
+                        script.load_session = result
+                            .pointer("/agentCapabilities/loadSession")
+                            .and_then(serde_json::Value::as_bool)
+                            .unwrap_or(script.load_session);
+                    } else if new_session_id.as_ref() == Some(id) {
+                        if let Some(session_id) =
+                            result.get("sessionId").and_then(serde_json::Value::as_str)
+                        {
+                            script.session_id = session_id.to_string();
+                        }
+                    } else if prompt_id.as_ref() == Some(id) {
+                        if let Some(mut turn) = current.take() {
+                            if let Some(stop_reason) = result
+                                .get("stopReason")
+                                .and_then(|reason| serde_json::from_value(reason.clone()).ok())
+                            {
+                                turn.stop_reason = stop_reason;
+                            }
+                            script.turns.push(turn);
+                        }
+                        prompt_id = None;
+                    }
+                }
+                (
+                    RecordedDirection::AgentToClient,
+                    RecordedPayload::Notification {
+                        method,
+                        params: Some(params),
+                    },
+                ) if method == "session/update" => {
+                    if let (Some(turn), Ok(notification)) = (
+                        current.as_mut(),
+                        serde_json::from_value::<SessionNotification>(params.clone()),
+                    ) {
+                        turn.steps.push(FakeStep::Update(notification.update));
+                    }
+                }
+                (
+                    RecordedDirection::AgentToClient,
+                    RecordedPayload::Request {
+                        method,
+                        params: Some(params),
+                        ..
+                    },
+                ) if method == "session/request_permission" => {
+                    if let (Some(turn), Ok(request)) = (
+                        current.as_mut(),
+                        serde_json::from_value::<RequestPermissionRequest>(params.clone()),
+                    ) {
+                        turn.steps
+                            .push(FakeStep::RequestPermission(FakePermissionStep {
+                                tool_call: request.tool_call,
+                                options: request.options,
+                                on_denied: default_denied_stop_reason(),
+                            }));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        script
+    }
+}
+
+/// ACP agent that plays back a [`FakeAgentScript`].
+#[derive(Debug)]
+pub struct FakeAgent {
+    script: FakeAgentScript,
+    connection: AgentConnectionHandle,
+    next_turn: Cell<usize>,
+    cancel_requested: Cell<bool>,
+    cancel_notify: Notify,
+    prompts: RefCell<Vec<String>>,
+    permission_outcomes: RefCell<Vec<FakePermissionOutcome>>,
+}
+
+impl FakeAgent {
+    /// Create a fake agent bound to the connection that will serve it.
+    #[must_use]
+    pub fn new(script: FakeAgentScript, connection: AgentConnectionHandle) -> Self {
+        Self {
+            script,
+            connection,
+            next_turn: Cell::new(0),
+            cancel_requested: Cell::new(false),
+            cancel_notify: Notify::new(),
+            prompts: RefCell::new(Vec::new()),
+            permission_outcomes: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Text of every prompt received so far.
+    #[must_use]
+    pub fn prompts(&self) -> Vec<String> {
+        self.prompts.borrow().clone()
+    }
+
+    /// Permission outcomes observed so far, in request order.
+    #[must_use]
+    pub fn permission_outcomes(&self) -> Vec<FakePermissionOutcome> {
+        self.permission_outcomes.borrow().clone()
+    }
+
+    async fn send_update(&self, session_id: &str, update: SessionUpdate) -> Result<()> {
+        let connection = self.connection()?;
+        connection
+            .session_notification(SessionNotification::new(session_id.to_string(), update))
+            .await
+    }
+
+    async fn request_permission(
+        &self,
+        session_id: &str,
+        step: &FakePermissionStep,
+    ) -> Result<bool> {
+        let connection = self.connection()?;
+        let response = connection
+            .request_permission(RequestPermissionRequest::new(
+                session_id.to_string(),
+                step.tool_call.clone(),
+                step.options.clone(),
+            ))
+            .await?;
+
+        let outcome = match response.outcome {
+            RequestPermissionOutcome::Selected(selected) => {
+                let option_id = selected.option_id.to_string();
+                let allowed = step.options.iter().any(|option| {
+                    option.option_id.to_string() == option_id
+                        && matches!(
+                            option.kind,
+                            PermissionOptionKind::AllowOnce | PermissionOptionKind::AllowAlways
+                        )
+                });
+                FakePermissionOutcome::Selected { option_id, allowed }
+            }
+            _ => FakePermissionOutcome::Cancelled,
+        };
+        let allowed = matches!(
+            outcome,
+            FakePermissionOutcome::Selected { allowed: true, .. }
+        );
+        self.permission_outcomes.borrow_mut().push(outcome);
+        Ok(allowed)
+    }
+
+    async fn wait_for_cancel(&self, timeout_ms: Option<u64>) -> bool {
+        let notified = self.cancel_notify.notified();
+        if self.cancel_requested.get() {
+            return true;
+        }
+        if let Some(timeout_ms) = timeout_ms {
+            return tokio::time::timeout(Duration::from_millis(timeout_ms), notified)
+                .await
+                .is_ok();
+        }
+        notified.await;
+        true
+    }
+
+    fn connection(&self) -> Result<&agent_client_protocol::AgentSideConnection> {
+        self.connection
+            .get()
+            .ok_or_else(|| Error::internal_error().data("fake agent connection not established"))
+    }
+}
+
+#[async_trait(?Send)]
+impl Agent for FakeAgent {
+    async fn initialize(&self, args: InitializeRequest) -> Result<InitializeResponse> {
+        let mut response = InitializeResponse::new(args.protocol_version)
+            .agent_capabilities(AgentCapabilities::new().load_session(self.script.load_session));
+        if let Some(info) = &self.script.agent_info {
+            let mut implementation = Implementation::new(info.name.clone(), info.version.clone());
+            if let Some(title) = &info.title {
+                implementation = implementation.title(title.clone());
+            }
+            response = response.agent_info(implementation);
+        }
+        Ok(response)
+    }
+
+    async fn authenticate(&self, _args: AuthenticateRequest) -> Result<AuthenticateResponse> {
+        Ok(AuthenticateResponse::new())
+    }
+
+    async fn new_session(&self, _args: NewSessionRequest) -> Result<NewSessionResponse> {
+        Ok(NewSessionResponse::new(self.script.session_id.clone()))
+    }
+
+    async fn load_session(&self, _args: LoadSessionRequest) -> Result<LoadSessionResponse> {
+        if self.script.load_session {
+            Ok(LoadSessionResponse::new())
+        } else {
+            Err(Error::method_not_found())
+        }
+    }
+
+    async fn prompt(&self, args: PromptRequest) -> Result<PromptResponse> {
+        let index = self.next_turn.get();
+        let turn = self.script.turns.get(index).cloned().ok_or_else(|| {
+            Error::internal_error().data(format!("fake agent script has no turn {}", index + 1))
+        })?;
+        self.next_turn.set(index + 1);
+        self.cancel_requested.set(false);
+
+        let text = args
+            .prompt
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text(text) => Some(text.text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.prompts.borrow_mut().push(text);
+
+        let session_id = args.session_id.to_string();
+        debug!(session_id, turn = index + 1, "fake ACP agent running turn");
+
+        for step in &turn.steps {
+            if self.cancel_requested.get() {
+                return Ok(PromptResponse::new(StopReason::Cancelled));
+            }
+            match step {
+                FakeStep::Message(text) => {
+                    self.send_update(
+                        &session_id,
+                        SessionUpdate::AgentMessageChunk(text_chunk(text)),
+                    )
+                    .await?;
+                }
+                FakeStep::Thought(text) => {
+                    self.send_update(
+                        &session_id,
+                        SessionUpdate::AgentThoughtChunk(text_chunk(text)),
+                    )
+                    .await?;
+                }
+                FakeStep::Update(update) => {
+                    self.send_update(&session_id, update.clone()).await?;
+                }
+                FakeStep::RequestPermission(permission) => {
+                    if !self.request_permission(&session_id, permission).await? {
+                        return Ok(PromptResponse::new(permission.on_denied));
+                    }
+                }
+                FakeStep::SleepMs(millis) => {
+                    tokio::time::sleep(Duration::from_millis(*millis)).await;
+                }
+                FakeStep::WaitForCancel { timeout_ms } => {
+                    if self.wait_for_cancel(*timeout_ms).await {
+                        return Ok(PromptResponse::new(StopReason::Cancelled));
+                    }
+                }
+            }
+        }
+
+        if self.cancel_requested.get() {
+            return Ok(PromptResponse::new(StopReason::Cancelled));
+        }
+        Ok(PromptResponse::new(turn.stop_reason))
+    }
+
+    async fn cancel(&self, args: CancelNotification) -> Result<()> {
+        debug!(session_id = %args.session_id, "fake ACP agent received cancel");
+        self.cancel_requested.set(true);
+        self.cancel_notify.notify_waiters();
+        Ok(())
+    }
+}
+
+fn text_chunk(text: &str) -> ContentChunk {
+    ContentChunk::new(ContentBlock::Text(TextContent::new(text.to_string())))
+}
+
+fn default_session_id() -> String {
+    DEFAULT_FAKE_SESSION_ID.to_string()
+}
+
+fn default_version() -> String {
+    "0.0.0".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_stop_reason() -> StopReason {
+    StopReason::EndTurn
+}
+
+fn default_denied_stop_reason() -> StopReason {
+    StopReason::Refusal
+}
+
+fn default_permission_options() -> Vec<PermissionOption> {
+    vec![
+        PermissionOption::new("allow", "Allow", PermissionOptionKind::AllowOnce),
+        PermissionOption::new("reject", "Reject", PermissionOptionKind::RejectOnce),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_yaml_script() {
+        let script = FakeAgentScript::from_yaml(
+            r"
+agentInfo:
+  name: fake-stakpak
+  version: 1.2.3
+sessionId: sess-42
+turns:
+  - steps:
+      - message: working on it
+      - request_permission:
+          toolCall:
+            toolCallId: call-1
+            title: Run tests
+      - sleep_ms: 5
+      - wait_for_cancel:
+          timeoutMs: 10
+    stopReason: max_tokens
+",
+        )
+        .unwrap();
+
+        assert_eq!(script.session_id, "sess-42");
+        assert_eq!(script.agent_info.as_ref().unwrap().name, "fake-stakpak");
+        assert_eq!(script.turns.len(), 1);
+        assert_eq!(script.turns[0].stop_reason, StopReason::MaxTokens);
+        assert_eq!(
+            script.turns[0].steps[0],
+            FakeStep::Message("working on it".to_string())
+        );
+        let FakeStep::RequestPermission(permission) = &script.turns[0].steps[1] else {
+            panic!("expected permission step");
+        };
+        assert_eq!(permission.options.len(), 2);
+        assert_eq!(permission.on_denied, StopReason::Refusal);
+        assert_eq!(
+            script.turns[0].steps[3],
+            FakeStep::WaitForCancel {
+                timeout_ms: Some(10)
+            }
+        );
+    }
+
+    #[test]
+    fn builds_script_from_recording() {
+        let lines = [
+            r#"{"seq":0,"elapsedMs":0,"direction":"client_to_agent","type":"request","id":0,"method":"initialize","params":{}}"#,
+            r#"{"seq":1,"elapsedMs":1,"direction":"agent_to_client","type":"response","id":0,"result":{"protocolVersion":1,"agentInfo":{"name":"stakpak","version":"0.9.1"}}}"#,
+            r#"{"seq":2,"elapsedMs":2,"direction":"client_to_agent","type":"request","id":1,"method":"session/new","params":{}}"#,
+            r#"{"seq":3,"elapsedMs":3,"direction":"agent_to_client","type":"response","id":1,"result":{"sessionId":"real-1"}}"#,
+            r#"{"seq":4,"elapsedMs":4,"direction":"client_to_agent","type":"request","id":2,"method":"session/prompt","params":{}}"#,
+            r#"{"seq":5,"elapsedMs":5,"direction":"agent_to_client","type":"notification","method":"session/update","params":{"sessionId":"real-1","update":{"sessionUpdate":"agent_message_chunk","content":{"type":"text","text":"hi"}}}}"#,
+            r#"{"seq":6,"elapsedMs":6,"direction":"agent_to_client","type":"request","id":0,"method":"session/request_permission","params":{"sessionId":"real-1","toolCall":{"toolCallId":"t1","title":"rm"},"options":[{"optionId":"ok","name":"OK","kind":"allow_once"}]}}"#,
+            r#"{"seq":7,"elapsedMs":7,"direction":"client_to_agent","type":"response","id":0,"result":{"outcome":{"outcome":"cancelled"}}}"#,
+            r#"{"seq":8,"elapsedMs":8,"direction":"agent_to_client","type":"response","id":2,"result":{"stopReason":"refusal"}}"#,
+        ];
+        let messages: Vec<RecordedMessage> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        let script = FakeAgentScript::from_recording(&messages);
+        assert_eq!(script.session_id, "real-1");
+        assert_eq!(script.agent_info.unwrap().name, "stakpak");
+        assert_eq!(script.turns.len(), 1);
+        assert_eq!(script.turns[0].stop_reason, StopReason::Refusal);
+        assert_eq!(script.turns[0].steps.len(), 2);
+        assert!(matches!(script.turns[0].steps[0], FakeStep::Update(_)));
+        assert!(matches!(
+            script.turns[0].steps[1],
+            FakeStep::RequestPermission(_)
+        ));
+    }
+}
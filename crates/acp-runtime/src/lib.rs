@@ -1,7 +1,9 @@
 //! Shared ACP runtime helpers for CTO services.
 
 pub mod client;
+pub mod fake_agent;
 pub mod interrupt_bridge;
+pub mod recorder;
 pub mod registry;
 pub mod server;
 pub mod types;
@@ -10,8 +12,12 @@ pub use client::{run_oneshot_prompt, AcpClientProfile};
 pub use interrupt_bridge::{
     spawn_interrupt_bridge, AcpInterruptSink, InterruptEvent, DEFAULT_INTERRUPT_PATH,
 };
+pub use recorder::{read_recording, AcpRecorder, RecordedMessage, RecorderSide};
 pub use registry::{AcpRuntimeRegistry, RuntimeSelection};
-pub use server::{caller_from_meta, ensure_allowed_caller, serve_stdio_agent, CallerContext};
+pub use server::{
+    caller_from_meta, ensure_allowed_caller, serve_agent_io, serve_stdio_agent,
+    serve_stdio_agent_with, AgentConnectionHandle, CallerContext,
+};
 pub use types::{
     AcpImplementationInfo, AcpPermissionPolicy, AcpPromptRequest, AcpPromptResult, AcpRunState,
     AcpSessionMetadata,
@@ -0,0 +1,343 @@
+//! ACP exchange recorder.
+//!
+//! Subscribes to the message stream of an ACP connection and appends every
+//! JSON-RPC request, response and notification to a JSONL file. Recordings
+//! are replayed by [`crate::fake_agent`] to drive deterministic tests of the
+//! client-side ACP integration without a real runtime such as `stakpak acp`.
+//!
+//! Recording is opt-in for [`crate::run_oneshot_prompt`]: set
+//! `ACP_RECORD_PATH` to the JSONL file that should receive the exchange.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use agent_client_protocol::{
+    StreamMessage, StreamMessageContent, StreamMessageDirection, StreamReceiver,
+};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Environment variable that enables recording in `run_oneshot_prompt`.
+pub const RECORD_PATH_ENV: &str = "ACP_RECORD_PATH";
+
+/// Which end of the ACP link the recorder is attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecorderSide {
+    /// Attached to a `ClientSideConnection` (outgoing = client → agent).
+    Client,
+    /// Attached to an `AgentSideConnection` (outgoing = agent → client).
+    Agent,
+}
+
+/// Direction of a recorded message, independent of the recording side.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedDirection {
+    /// Sent by the client (CTO service) to the agent runtime.
+    ClientToAgent,
+    /// Sent by the agent runtime to the client.
+    AgentToClient,
+}
+
+/// JSON-RPC payload of a recorded message.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedPayload {
+    /// A request expecting a response.
+    Request {
+        /// JSON-RPC request ID.
+        id: Value,
+        /// Method name, for example `session/prompt`.
+        method: String,
+        /// Request parameters.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        params: Option<Value>,
+    },
+    /// A response to an earlier request.
+    Response {
+        /// JSON-RPC request ID this response answers.
+        id: Value,
+        /// Successful result payload.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<Value>,
+        /// Error payload, if the request failed.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<Value>,
+    },
+    /// A one-way notification.
+    Notification {
+        /// Method name, for example `session/update`.
+        method: String,
+        /// Notification parameters.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        params: Option<Value>,
+    },
+}
+
+/// A single line of an ACP recording.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecordedMessage {
+    /// Monotonic sequence number within the recording.
+    pub seq: u64,
+    /// Milliseconds since the recorder started.
+    #[serde(rename = "elapsedMs")]
+    pub elapsed_ms: u64,
+    /// Who sent the message.
+    pub direction: RecordedDirection,
+    /// JSON-RPC payload.
+    #[serde(flatten)]
+    pub payload: RecordedPayload,
+}
+
+impl RecordedMessage {
+    /// Convert an upstream stream message into a recording entry.
+    #[must_use]
+    pub fn from_stream(
+        seq: u64,
+        elapsed_ms: u64,
+        side: RecorderSide,
+        message: StreamMessage,
+    ) -> Self {
+        let direction = match (side, message.direction) {
+            (RecorderSide::Client, StreamMessageDirection::Outgoing)
+            | (RecorderSide::Agent, StreamMessageDirection::Incoming) => {
+                RecordedDirection::ClientToAgent
+            }
+            (RecorderSide::Client, StreamMessageDirection::Incoming)
+            | (RecorderSide::Agent, StreamMessageDirection::Outgoing) => {
+                RecordedDirection::AgentToClient
+            }
+        };
+
+        let payload = match message.message {
+            StreamMessageContent::Request { id, method, params } => RecordedPayload::Request {
+                id: serde_json::to_value(&id).unwrap_or(Value::Null),
+                method: method.to_string(),
+                params,
+            },
+            StreamMessageContent::Response { id, result } => {
+                let id = serde_json::to_value(&id).unwrap_or(Value::Null);
+                match result {
+                    Ok(result) => RecordedPayload::Response {
+                        id,
+                        result,
+                        error: None,
+                    },
+                    Err(error) => RecordedPayload::Response {
+                        id,
+                        result: None,
+                        error: serde_json::to_value(&error).ok(),
+                    },
+                }
+            }
+            StreamMessageContent::Notification { method, params } => {
+                RecordedPayload::Notification {
+                    method: method.to_string(),
+                    params,
+                }
+            }
+        };
+
+        Self {
+            seq,
+            elapsed_ms,
+            direction,
+            payload,
+        }
+    }
+
+    /// Method name for requests and notifications.
+    #[must_use]
+    pub fn method(&self) -> Option<&str> {
+        match &self.payload {
+            RecordedPayload::Request { method, .. }
+            | RecordedPayload::Notification { method, .. } => Some(method),
+            RecordedPayload::Response { .. } => None,
+        }
+    }
+}
+
+/// Appends recorded ACP messages to a JSONL file.
+#[derive(Debug)]
+pub struct AcpRecorder {
+    path: PathBuf,
+    file: File,
+    side: RecorderSide,
+    started: Instant,
+    next_seq: u64,
+}
+
+impl AcpRecorder {
+    /// Create (or truncate) the recording file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent directory or file cannot be created.
+    pub fn create(path: impl Into<PathBuf>, side: RecorderSide) -> anyhow::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent).with_context(|| {
+                    format!("failed to create recording dir {}", parent.display())
+                })?;
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .with_context(|| format!("failed to open ACP recording {}", path.display()))?;
+
+        Ok(Self {
+            path,
+            file,
+            side,
+            started: Instant::now(),
+            next_seq: 0,
+        })
+    }
+
+    /// Path of the recording file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a single stream message. Each line is flushed immediately so
+    /// a recording survives the process being killed mid-exchange.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line cannot be serialized or written.
+    pub fn record(&mut self, message: StreamMessage) -> anyhow::Result<()> {
+        let elapsed_ms = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        let entry = RecordedMessage::from_stream(self.next_seq, elapsed_ms, self.side, message);
+        self.next_seq += 1;
+
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.flush()?;
+        Ok(())
+    }
+
+    /// Spawn a local task that records every message from `receiver` until
+    /// the connection is dropped.
+    ///
+    /// Must be invoked from inside a `tokio::task::LocalSet`, alongside the
+    /// connection being observed.
+    pub fn spawn(mut self, mut receiver: StreamReceiver) -> JoinHandle<()> {
+        tokio::task::spawn_local(async move {
+            while let Ok(message) = receiver.recv().await {
+                if let Err(err) = self.record(message) {
+                    warn!(error = %err, path = %self.path.display(), "failed to record ACP message");
+                }
+            }
+            debug!(
+                path = %self.path.display(),
+                messages = self.next_seq,
+                "ACP recording finished"
+            );
+        })
+    }
+}
+
+/// Load a JSONL recording produced by [`AcpRecorder`].
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or a line is not a valid
+/// recording entry.
+pub fn read_recording(path: impl AsRef<Path>) -> anyhow::Result<Vec<RecordedMessage>> {
+    let path = path.as_ref();
+    let file = File::open(path)
+        .with_context(|| format!("failed to open ACP recording {}", path.display()))?;
+
+    let mut messages = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message = serde_json::from_str(&line).with_context(|| {
+            format!(
+                "invalid recording entry at {}:{}",
+                path.display(),
+                index + 1
+            )
+        })?;
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn maps_direction_relative_to_side() {
+        let message = StreamMessage {
+            direction: StreamMessageDirection::Outgoing,
+            message: StreamMessageContent::Notification {
+                method: Arc::from("session/cancel"),
+                params: Some(serde_json::json!({"sessionId": "s1"})),
+            },
+        };
+
+        let client = RecordedMessage::from_stream(0, 0, RecorderSide::Client, message.clone());
+        assert_eq!(client.direction, RecordedDirection::ClientToAgent);
+        assert_eq!(client.method(), Some("session/cancel"));
+
+        let agent = RecordedMessage::from_stream(0, 0, RecorderSide::Agent, message);
+        assert_eq!(agent.direction, RecordedDirection::AgentToClient);
+    }
+
+    #[test]
+    fn recording_round_trips_through_jsonl() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("nested/exchange.jsonl");
+        let mut recorder = AcpRecorder::create(&path, RecorderSide::Client).unwrap();
+
+        recorder
+            .record(StreamMessage {
+                direction: StreamMessageDirection::Outgoing,
+                message: StreamMessageContent::Request {
+                    id: 1.into(),
+                    method: Arc::from("session/prompt"),
+                    params: Some(serde_json::json!({"sessionId": "s1", "prompt": []})),
+                },
+            })
+            .unwrap();
+        recorder
+            .record(StreamMessage {
+                direction: StreamMessageDirection::Incoming,
+                message: StreamMessageContent::Response {
+                    id: 1.into(),
+                    result: Ok(Some(serde_json::json!({"stopReason": "end_turn"}))),
+                },
+            })
+            .unwrap();
+
+        let messages = read_recording(&path).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].seq, 0);
+        assert_eq!(messages[1].seq, 1);
+        assert_eq!(messages[1].direction, RecordedDirection::AgentToClient);
+        assert_eq!(
+            messages[1].payload,
+            RecordedPayload::Response {
+                id: serde_json::json!(1),
+                result: Some(serde_json::json!({"stopReason": "end_turn"})),
+                error: None,
+            }
+        );
+    }
+}
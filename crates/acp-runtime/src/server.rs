@@ -1,5 +1,8 @@
 use agent_client_protocol::{Agent, AgentSideConnection, Error, Meta, Result};
 use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+use std::rc::Rc;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 use tracing::warn;
 
@@ -65,6 +68,31 @@ pub fn ensure_allowed_caller(
     }
 }
 
+/// Late-bound handle to the connection serving an ACP agent.
+///
+/// Agents that push `session/update` notifications or permission requests
+/// back to the client need the `AgentSideConnection`, but the connection can
+/// only be built once the agent exists. The handle is populated as soon as
+/// the connection is created and before any request is dispatched.
+#[derive(Clone, Default)]
+pub struct AgentConnectionHandle(Rc<OnceCell<AgentSideConnection>>);
+
+impl AgentConnectionHandle {
+    /// Borrow the connection, if it has been established.
+    #[must_use]
+    pub fn get(&self) -> Option<&AgentSideConnection> {
+        self.0.get()
+    }
+}
+
+impl std::fmt::Debug for AgentConnectionHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentConnectionHandle")
+            .field("connected", &self.0.get().is_some())
+            .finish()
+    }
+}
+
 /// Serve an ACP agent over stdio using the upstream ACP transport.
 ///
 /// This is the standard hosting mode for harness-managed ACP agents such as
@@ -74,22 +102,64 @@ pub fn ensure_allowed_caller(
 ///
 /// Returns an error if the IO task encounters a transport failure.
 pub async fn serve_stdio_agent(agent: impl Agent + 'static) -> anyhow::Result<()> {
+    serve_stdio_agent_with(|_| agent).await
+}
+
+/// Serve an ACP agent over stdio, handing the agent a connection handle.
+///
+/// Use this instead of [`serve_stdio_agent`] when the agent needs to call
+/// back into the client (notifications, permission requests).
+///
+/// # Errors
+///
+/// Returns an error if the IO task encounters a transport failure.
+pub async fn serve_stdio_agent_with<A, F>(build: F) -> anyhow::Result<()>
+where
+    A: Agent + 'static,
+    F: FnOnce(AgentConnectionHandle) -> A,
+{
     let local = tokio::task::LocalSet::new();
     local
-        .run_until(async move {
-            let stdin = tokio::io::stdin();
-            let stdout = tokio::io::stdout();
-            let (_connection, io_task) =
-                AgentSideConnection::new(agent, stdout.compat_write(), stdin.compat(), |future| {
-                    tokio::task::spawn_local(future);
-                });
+        .run_until(serve_agent_io(
+            build,
+            tokio::io::stdout(),
+            tokio::io::stdin(),
+        ))
+        .await
+}
+
+/// Serve an ACP agent over an arbitrary byte stream pair.
+///
+/// Must be called from inside a `tokio::task::LocalSet`. Tests use this with
+/// `tokio::io::duplex` to run an agent in-process against a
+/// `ClientSideConnection`.
+///
+/// # Errors
+///
+/// Returns an error if the IO task encounters a transport failure.
+pub async fn serve_agent_io<A, F, W, R>(build: F, outgoing: W, incoming: R) -> anyhow::Result<()>
+where
+    A: Agent + 'static,
+    F: FnOnce(AgentConnectionHandle) -> A,
+    W: AsyncWrite + Unpin + 'static,
+    R: AsyncRead + Unpin + 'static,
+{
+    let handle = AgentConnectionHandle::default();
+    let agent = build(handle.clone());
+    let (connection, io_task) = AgentSideConnection::new(
+        agent,
+        outgoing.compat_write(),
+        incoming.compat(),
+        |future| {
+            tokio::task::spawn_local(future);
+        },
+    );
+    let _ = handle.0.set(connection);
 
-            if let Err(error) = io_task.await {
-                warn!(error = %error, "ACP agent stdio task exited with error");
-                return Err(anyhow::Error::new(error));
-            }
+    if let Err(error) = io_task.await {
+        warn!(error = %error, "ACP agent stdio task exited with error");
+        return Err(anyhow::Error::new(error));
+    }
 
-            Ok(())
-        })
-        .await
+    Ok(())
 }
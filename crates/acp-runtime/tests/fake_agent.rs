@@ -0,0 +1,321 @@
+//! Integration tests driving ACP clients against the scripted fake agent.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use acp_runtime::fake_agent::{FakeAgent, FakeAgentScript, FakePermissionOutcome};
+use acp_runtime::{
+    read_recording, run_oneshot_prompt, serve_agent_io, spawn_interrupt_bridge, AcpClientProfile,
+    AcpPermissionPolicy, AcpPromptRequest, AcpRecorder, AgentConnectionHandle, RecorderSide,
+};
+use agent_client_protocol::{
+    Agent, Client, ClientSideConnection, ContentBlock, InitializeRequest, NewSessionRequest,
+    PromptRequest, ProtocolVersion, RequestPermissionOutcome, RequestPermissionRequest,
+    RequestPermissionResponse, SessionNotification, SessionUpdate, StopReason, TextContent,
+};
+use async_trait::async_trait;
+use cto_config::AcpRuntimeConfig;
+use tokio::io::AsyncWriteExt;
+use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+const PERMISSION_SCRIPT: &str = r"
+agentInfo:
+  name: fake-stakpak
+  version: 9.9.9
+sessionId: sess-perm
+turns:
+  - steps:
+      - message: planning
+      - request_permission:
+          toolCall:
+            toolCallId: call-1
+            title: cargo test
+      - message: tests passed
+";
+
+const INTERRUPT_SCRIPT: &str = r"
+sessionId: sess-int
+turns:
+  - steps:
+      - message: long running task
+      - wait_for_cancel:
+          timeoutMs: 5000
+  - steps:
+      - message: restarted
+";
+
+fn write_script(dir: &tempfile::TempDir, source: &str) -> String {
+    let path = dir.path().join("script.yaml");
+    std::fs::write(&path, source).unwrap();
+    path.display().to_string()
+}
+
+async fn prompt_fake_agent(
+    script_path: String,
+    policy: AcpPermissionPolicy,
+) -> acp_runtime::AcpPromptResult {
+    let runtime = AcpRuntimeConfig::stdio(env!("CARGO_BIN_EXE_fake-acp-agent"), [script_path]);
+    let profile = AcpClientProfile {
+        permission_policy: policy,
+        ..AcpClientProfile::default()
+    };
+    let request = AcpPromptRequest {
+        runtime_id: "fake".to_string(),
+        cwd: std::env::temp_dir(),
+        prompt: "fix the build".to_string(),
+        session_id: None,
+    };
+    run_oneshot_prompt(&runtime, request, profile)
+        .await
+        .unwrap()
+}
+
+fn message_texts(notifications: &[SessionNotification]) -> Vec<String> {
+    notifications
+        .iter()
+        .filter_map(|notification| match &notification.update {
+            SessionUpdate::AgentMessageChunk(chunk) => match &chunk.content {
+                ContentBlock::Text(text) => Some(text.text.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn oneshot_prompt_allows_permission_with_allow_all() {
+    let tmp = tempfile::tempdir().unwrap();
+    let script = write_script(&tmp, PERMISSION_SCRIPT);
+
+    let result = prompt_fake_agent(script, AcpPermissionPolicy::AllowAll).await;
+
+    assert_eq!(result.session_id, "sess-perm");
+    assert_eq!(result.stop_reason, StopReason::EndTurn);
+    assert_eq!(result.agent_info.unwrap().name, "fake-stakpak");
+    assert_eq!(
+        message_texts(&result.notifications),
+        vec!["planning".to_string(), "tests passed".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn oneshot_prompt_denies_permission_with_deny_all() {
+    let tmp = tempfile::tempdir().unwrap();
+    let script = write_script(&tmp, PERMISSION_SCRIPT);
+
+    let result = prompt_fake_agent(script, AcpPermissionPolicy::DenyAll).await;
+
+    assert_eq!(result.stop_reason, StopReason::Refusal);
+    assert_eq!(
+        message_texts(&result.notifications),
+        vec!["planning".to_string()]
+    );
+}
+
+#[derive(Default)]
+struct CollectingClient {
+    notifications: RefCell<Vec<SessionNotification>>,
+}
+
+#[async_trait(?Send)]
+impl Client for CollectingClient {
+    async fn request_permission(
+        &self,
+        _args: RequestPermissionRequest,
+    ) -> agent_client_protocol::Result<RequestPermissionResponse> {
+        Ok(RequestPermissionResponse::new(
+            RequestPermissionOutcome::Cancelled,
+        ))
+    }
+
+    async fn session_notification(
+        &self,
+        args: SessionNotification,
+    ) -> agent_client_protocol::Result<()> {
+        self.notifications.borrow_mut().push(args);
+        Ok(())
+    }
+}
+
+/// Serve a fake agent in-process and return a connected client.
+fn connect_in_process(
+    script: FakeAgentScript,
+    client: Rc<CollectingClient>,
+) -> (ClientSideConnection, Rc<RefCell<Option<Rc<FakeAgent>>>>) {
+    let (client_io, agent_io) = tokio::io::duplex(64 * 1024);
+    let (agent_read, agent_write) = tokio::io::split(agent_io);
+    let (client_read, client_write) = tokio::io::split(client_io);
+
+    let agent_slot: Rc<RefCell<Option<Rc<FakeAgent>>>> = Rc::default();
+    let slot = agent_slot.clone();
+    tokio::task::spawn_local(async move {
+        let _ = serve_agent_io(
+            move |connection: AgentConnectionHandle| {
+                let agent = Rc::new(FakeAgent::new(script, connection));
+                *slot.borrow_mut() = Some(agent.clone());
+                agent
+            },
+            agent_write,
+            agent_read,
+        )
+        .await;
+    });
+
+    let (connection, io_task) = ClientSideConnection::new(
+        SharedClient(client),
+        client_write.compat_write(),
+        client_read.compat(),
+        |future| {
+            tokio::task::spawn_local(future);
+        },
+    );
+    tokio::task::spawn_local(async move {
+        let _ = io_task.await;
+    });
+    (connection, agent_slot)
+}
+
+struct SharedClient(Rc<CollectingClient>);
+
+#[async_trait(?Send)]
+impl Client for SharedClient {
+    async fn request_permission(
+        &self,
+        args: RequestPermissionRequest,
+    ) -> agent_client_protocol::Result<RequestPermissionResponse> {
+        self.0.request_permission(args).await
+    }
+
+    async fn session_notification(
+        &self,
+        args: SessionNotification,
+    ) -> agent_client_protocol::Result<()> {
+        self.0.session_notification(args).await
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn interrupt_bridge_cancels_turn_and_reprompts() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async move {
+            let tmp = tempfile::tempdir().unwrap();
+            let interrupt_path = tmp.path().join("interrupt.jsonl");
+            let client = Rc::new(CollectingClient::default());
+            let script = FakeAgentScript::from_yaml(INTERRUPT_SCRIPT).unwrap();
+            let (connection, agent_slot) = connect_in_process(script, client.clone());
+            let connection = Arc::new(connection);
+
+            connection
+                .initialize(InitializeRequest::new(ProtocolVersion::LATEST))
+                .await
+                .unwrap();
+            let session_id = connection
+                .new_session(NewSessionRequest::new(tmp.path()))
+                .await
+                .unwrap()
+                .session_id
+                .to_string();
+
+            // The bridge tails from end-of-file, so the log must exist first.
+            tokio::fs::write(&interrupt_path, "").await.unwrap();
+            let bridge = spawn_interrupt_bridge(connection.clone(), &interrupt_path).unwrap();
+            tokio::time::sleep(Duration::from_millis(250)).await;
+
+            let prompt = connection.prompt(PromptRequest::new(
+                session_id.clone(),
+                vec![ContentBlock::Text(TextContent::new("start"))],
+            ));
+            let interrupt = async {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                let mut writer = tokio::fs::OpenOptions::new()
+                    .append(true)
+                    .open(&interrupt_path)
+                    .await
+                    .unwrap();
+                writer
+                    .write_all(
+                        format!("{{\"session_id\":\"{session_id}\",\"text\":\"change course\"}}\n")
+                            .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+                writer.flush().await.unwrap();
+            };
+            let (response, ()) = tokio::join!(prompt, interrupt);
+            assert_eq!(response.unwrap().stop_reason, StopReason::Cancelled);
+
+            let agent = agent_slot.borrow().clone().unwrap();
+            for _ in 0..40 {
+                if agent.prompts().len() >= 2 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            bridge.abort();
+
+            assert_eq!(
+                agent.prompts(),
+                vec!["start".to_string(), "change course".to_string()]
+            );
+            assert_eq!(
+                message_texts(&client.notifications.borrow()),
+                vec!["long running task".to_string(), "restarted".to_string()]
+            );
+        })
+        .await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn recorded_exchange_replays_as_script() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async move {
+            let tmp = tempfile::tempdir().unwrap();
+            let recording_path = tmp.path().join("exchange.jsonl");
+            let client = Rc::new(CollectingClient::default());
+            let script = FakeAgentScript::from_yaml(PERMISSION_SCRIPT).unwrap();
+            let (connection, agent_slot) = connect_in_process(script.clone(), client);
+
+            let recorder = AcpRecorder::create(&recording_path, RecorderSide::Client).unwrap();
+            let recording = recorder.spawn(connection.subscribe());
+
+            connection
+                .initialize(InitializeRequest::new(ProtocolVersion::LATEST))
+                .await
+                .unwrap();
+            let session_id = connection
+                .new_session(NewSessionRequest::new(tmp.path()))
+                .await
+                .unwrap()
+                .session_id;
+            let response = connection
+                .prompt(PromptRequest::new(
+                    session_id,
+                    vec![ContentBlock::Text(TextContent::new("go"))],
+                ))
+                .await
+                .unwrap();
+            assert_eq!(response.stop_reason, StopReason::Refusal);
+            assert_eq!(
+                agent_slot.borrow().as_ref().unwrap().permission_outcomes(),
+                vec![FakePermissionOutcome::Cancelled]
+            );
+
+            // Let the recorder catch up with the final response.
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            recording.abort();
+
+            let replay = FakeAgentScript::from_recording(&read_recording(&recording_path).unwrap());
+            assert_eq!(replay.session_id, script.session_id);
+            assert_eq!(replay.agent_info, script.agent_info);
+            assert_eq!(replay.turns.len(), 1);
+            assert_eq!(replay.turns[0].stop_reason, StopReason::Refusal);
+            // Updates are replayed verbatim; the permission step is kept.
+            assert_eq!(replay.turns[0].steps.len(), 2);
+        })
+        .await;
+}
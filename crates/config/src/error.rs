@@ -0,0 +1,91 @@
+//! Error types for loading CTO configuration.
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Errors raised while loading, migrating or validating a `cto-config.json`.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// The document is not valid JSON.
+    #[error("invalid JSON at line {line}, column {column}: {message}")]
+    Syntax {
+        /// 1-based line of the error.
+        line: usize,
+        /// 1-based column of the error.
+        column: usize,
+        /// Parser message.
+        message: String,
+    },
+
+    /// The document is valid JSON but does not match the config schema.
+    #[error("{path}: {message}")]
+    Schema {
+        /// Dotted path to the offending value, e.g. `defaults.play.watcher.cli`.
+        path: String,
+        /// Deserializer message.
+        message: String,
+    },
+
+    /// The config declares a version this build cannot read.
+    #[error("unsupported config version {found:?} (this build supports up to {supported})")]
+    UnsupportedVersion {
+        /// Version found in the document.
+        found: String,
+        /// Newest version this build understands.
+        supported: &'static str,
+    },
+
+    /// A migration step could not be applied.
+    #[error("migration {from} -> {to} failed: {message}")]
+    Migration {
+        /// Source version of the failed step.
+        from: &'static str,
+        /// Target version of the failed step.
+        to: &'static str,
+        /// What went wrong.
+        message: String,
+    },
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Syntax {
+            line: error.line(),
+            column: error.column(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Errors raised while loading an agent roster directory.
+#[derive(Debug, Error)]
+pub enum RosterError {
+    /// A roster directory or file could not be read.
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        /// File or directory that failed.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A definition file is not valid YAML/JSON or has unknown fields.
+    #[error("{}: {message}", path.display())]
+    Parse {
+        /// Definition file.
+        path: PathBuf,
+        /// Parser message, prefixed with the offending field path.
+        message: String,
+    },
+
+    /// A definition parsed but is not usable.
+    #[error("{}: {message}", path.display())]
+    Invalid {
+        /// Definition file.
+        path: PathBuf,
+        /// What is wrong with it.
+        message: String,
+    },
+}
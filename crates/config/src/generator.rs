@@ -3,12 +3,16 @@
 //! Provides functions to generate `CtoConfig` for projects.
 
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
-use crate::agents::{capitalize, get_agent_config, workflow_agents};
-use crate::tools::{analyze_agent_tasks_for_tools, analyze_all_tasks_for_tools, ToolAnalyzable};
+use crate::roster::{AgentRole, AgentRoster};
+use crate::tools::{
+    analyze_agent_tasks_for_tools, analyze_all_tasks_for_tools, infer_tools_from_repository,
+    ToolAnalyzable, ToolInference,
+};
 use crate::types::{
-    AcpDefaults, CtoConfig, Defaults, IntakeDefaults, IntakeModels, LinearDefaults,
-    LinearIntakeSettings, MultiModelConfig, PlayDefaults, CTO_CONFIG_VERSION,
+    make_agent_name, AcpDefaults, CtoConfig, Defaults, IntakeDefaults, IntakeModels,
+    LinearDefaults, LinearIntakeSettings, MultiModelConfig, PlayDefaults, CTO_CONFIG_VERSION,
 };
 
 /// Input for generating a project CTO config.
@@ -73,6 +77,19 @@ pub fn derive_service_name(name: &str) -> String {
 /// This creates a config with all standard agents and project-specific defaults.
 #[must_use]
 pub fn generate_project_config(input: &ProjectConfigInput) -> CtoConfig {
+    generate_project_config_with_roster(input, AgentRoster::built_in())
+}
+
+/// Generate a CTO config for a project from an agent roster.
+///
+/// Includes the roster's intake, implementation and frontend agents plus its
+/// workflow agents. Roles the roster assigns to non-default agents are pinned
+/// in `defaults.play`.
+#[must_use]
+pub fn generate_project_config_with_roster(
+    input: &ProjectConfigInput,
+    roster: &AgentRoster,
+) -> CtoConfig {
     let repository = input.repository();
     let service = input.service();
 
@@ -126,18 +143,40 @@ pub fn generate_project_config(input: &ProjectConfigInput) -> CtoConfig {
         agents: HashMap::new(),
     };
 
-    // Add all standard agents
-    let standard_agents = [
-        "morgan", "rex", "blaze", "cleo", "tess", "cipher", "atlas", "bolt",
-    ];
-    for agent_name in standard_agents {
-        let agent_config = get_agent_config(agent_name);
+    // Add the primary role agents and the workflow agents
+    let role_agents = [
+        AgentRole::Intake,
+        AgentRole::Implementation,
+        AgentRole::Frontend,
+    ]
+    .into_iter()
+    .filter_map(|role| roster.agent_for_role(role))
+    .map(|agent| agent.name.as_str());
+    for agent_name in role_agents.chain(roster.workflow_agents()) {
+        let agent_config = roster.agent_config(agent_name, &config.org_name);
         config.agents.insert(agent_name.to_string(), agent_config);
     }
+    pin_roster_roles(&mut config, roster);
 
     config
 }
 
+/// Pin every role the roster assigns differently from the built-in roster,
+/// since `PlayDefaults` alone only knows the built-in assignments.
+fn pin_roster_roles(config: &mut CtoConfig, roster: &AgentRoster) {
+    for role in AgentRole::ALL {
+        let agent = roster.github_app_for_role(role, &config.org_name);
+        if agent == AgentRoster::built_in().github_app_for_role(role, &config.org_name) {
+            continue;
+        }
+        if let Some(slot) = config.defaults.play.agent_override_mut(role) {
+            slot.get_or_insert(agent);
+        } else if role == AgentRole::Intake {
+            config.defaults.intake.github_app = agent;
+        }
+    }
+}
+
 /// Generate a CTO config with task-based tool analysis.
 ///
 /// Analyzes the provided tasks to determine which agents are needed
@@ -146,6 +185,57 @@ pub fn generate_project_config(input: &ProjectConfigInput) -> CtoConfig {
 pub fn generate_config_with_tasks<T: ToolAnalyzable>(
     input: &ProjectConfigInput,
     tasks: &[T],
+) -> CtoConfig {
+    generate_config_with_roster(input, tasks, None, AgentRoster::built_in())
+}
+
+/// Generate a CTO config from tasks and the manifests of a repository checkout.
+///
+/// Technologies declared as dependencies in `repo_root` (Cargo.toml,
+/// package.json, go.mod, pyproject.toml, docker-compose) add their tools to
+/// every agent with assigned tasks and to the support agents, alongside the
+/// tools inferred from task text.
+#[must_use]
+pub fn generate_config_with_repository<T: ToolAnalyzable>(
+    input: &ProjectConfigInput,
+    tasks: &[T],
+    repo_root: &Path,
+) -> CtoConfig {
+    generate_config_with_roster(input, tasks, Some(repo_root), AgentRoster::built_in())
+}
+
+/// Generate a CTO config from tasks using the agents of `roster`.
+///
+/// Same as [`generate_config_with_repository`] (or
+/// [`generate_config_with_tasks`] when `repo_root` is `None`), but agent
+/// settings, workflow agents and role assignments come from the roster.
+#[must_use]
+pub fn generate_config_with_roster<T: ToolAnalyzable>(
+    input: &ProjectConfigInput,
+    tasks: &[T],
+    repo_root: Option<&Path>,
+    roster: &AgentRoster,
+) -> CtoConfig {
+    let repo_inference = repo_root.map_or_else(ToolInference::default, |root| {
+        let inference = infer_tools_from_repository(root);
+        for detected in &inference.matches {
+            let evidence: Vec<String> = detected.evidence.iter().map(ToString::to_string).collect();
+            tracing::debug!(
+                "Detected {} from manifests: {}",
+                detected.technology,
+                evidence.join(", ")
+            );
+        }
+        inference
+    });
+    build_config_with_tasks(input, tasks, &repo_inference, roster)
+}
+
+fn build_config_with_tasks<T: ToolAnalyzable>(
+    input: &ProjectConfigInput,
+    tasks: &[T],
+    repo_inference: &ToolInference,
+    roster: &AgentRoster,
 ) -> CtoConfig {
     let repository = input.repository();
     let service = input.service();
@@ -154,7 +244,7 @@ pub fn generate_config_with_tasks<T: ToolAnalyzable>(
     let mut needed_agents: HashSet<String> = HashSet::new();
 
     // Always include workflow agents
-    for agent in workflow_agents() {
+    for agent in roster.workflow_agents() {
         needed_agents.insert(agent.to_string());
     }
 
@@ -166,15 +256,31 @@ pub fn generate_config_with_tasks<T: ToolAnalyzable>(
     }
 
     // Analyze all tasks for global technology requirements
-    let global_tech_tools = analyze_all_tasks_for_tools(tasks);
+    let repo_tools = repo_inference.tools();
+    let mut global_tech_tools = analyze_all_tasks_for_tools(tasks);
+    global_tech_tools.extend(repo_tools.iter().cloned());
+
+    let agents_with_tasks: HashSet<String> = tasks
+        .iter()
+        .filter_map(|task| task.agent_hint().map(str::to_lowercase))
+        .collect();
+
+    let role_agent = |role| roster.agent_for_role(role).map(|agent| agent.name.as_str());
+    let support_agents: Vec<&str> = [AgentRole::Quality, AgentRole::Security, AgentRole::Testing]
+        .into_iter()
+        .filter_map(role_agent)
+        .collect();
 
     // Build agent configurations with task-specific tools
     let mut agents = HashMap::new();
     for agent_name in &needed_agents {
-        let mut agent_config = get_agent_config(agent_name);
+        let mut agent_config = roster.agent_config(agent_name, "5DLabs");
 
         // Analyze tasks assigned to this agent for additional tools
-        let task_tools = analyze_agent_tasks_for_tools(tasks, agent_name);
+        let mut task_tools = analyze_agent_tasks_for_tools(tasks, agent_name);
+        if agents_with_tasks.contains(agent_name) {
+            task_tools.extend(repo_tools.iter().cloned());
+        }
 
         // Add task-specific tools to the agent's remote tools
         for tool in task_tools {
@@ -184,7 +290,7 @@ pub fn generate_config_with_tasks<T: ToolAnalyzable>(
         }
 
         // Support agents get global tech tools for context
-        if ["cleo", "cipher", "tess"].contains(&agent_name.as_str()) {
+        if support_agents.contains(&agent_name.as_str()) {
             for tool in &global_tech_tools {
                 if !agent_config.tools.remote.contains(tool) {
                     agent_config.tools.remote.push(tool.clone());
@@ -200,20 +306,25 @@ pub fn generate_config_with_tasks<T: ToolAnalyzable>(
     }
 
     // Determine primary agents
-    let implementation_agents = ["rex", "grizz", "nova"];
-    let frontend_agents = ["blaze", "tap", "spark"];
-
-    let primary_impl = implementation_agents
-        .iter()
-        .find(|a| needed_agents.contains(**a))
-        .unwrap_or(&"rex");
-
-    let primary_frontend = frontend_agents
-        .iter()
-        .find(|a| needed_agents.contains(**a))
-        .unwrap_or(&"blaze");
+    let primary_agent = |roles: [AgentRole; 3]| {
+        let agents: Vec<_> = roles
+            .into_iter()
+            .filter_map(|role| roster.agent_for_role(role))
+            .collect();
+        agents
+            .iter()
+            .find(|agent| needed_agents.contains(&agent.name))
+            .or_else(|| agents.first())
+            .map_or_else(
+                || make_agent_name("5DLabs", roles[0].default_suffix()),
+                |agent| agent.github_app_for("5DLabs"),
+            )
+    };
+    let primary_impl = primary_agent([AgentRole::Implementation, AgentRole::Go, AgentRole::Node]);
+    let primary_frontend =
+        primary_agent([AgentRole::Frontend, AgentRole::Mobile, AgentRole::Desktop]);
 
-    CtoConfig {
+    let mut config = CtoConfig {
         version: CTO_CONFIG_VERSION.to_string(),
         org_name: "5DLabs".to_string(),
         defaults: Defaults {
@@ -245,8 +356,8 @@ pub fn generate_config_with_tasks<T: ToolAnalyzable>(
             acp: AcpDefaults::default(),
             play: PlayDefaults {
                 // Override implementation/frontend agents based on task analysis
-                implementation_agent: Some(format!("5DLabs-{}", capitalize(primary_impl))),
-                frontend_agent: Some(format!("5DLabs-{}", capitalize(primary_frontend))),
+                implementation_agent: Some(primary_impl),
+                frontend_agent: Some(primary_frontend),
                 // Other agents use defaults derived from orgName
                 repository: repository.clone(),
                 service,
@@ -264,7 +375,9 @@ pub fn generate_config_with_tasks<T: ToolAnalyzable>(
             skills_project: None,
         },
         agents,
-    }
+    };
+    pin_roster_roles(&mut config, roster);
+    config
 }
 
 /// Generate a CTO config JSON string for a project.
@@ -279,6 +392,8 @@ pub fn generate_project_config_json(input: &ProjectConfigInput) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::PlayDefaults;
+    use tempfile::TempDir;
 
     struct TestTask {
         title: String,
@@ -407,4 +522,76 @@ mod tests {
         assert_eq!(parsed["version"], "1.0");
         assert!(parsed["agents"]["rex"].is_object());
     }
+
+    #[test]
+    fn test_generate_config_with_repository_uses_manifests() {
+        let root = TempDir::new().unwrap();
+        std::fs::write(
+            root.path().join("Cargo.toml"),
+            "[package]\nname = \"api\"\n\n[dependencies]\nasync-nats = \"0.38\"\n",
+        )
+        .unwrap();
+
+        let tasks = vec![TestTask {
+            title: "Publish order events".to_string(),
+            description: "Emit an event when an order ships".to_string(),
+            details: String::new(),
+            agent: Some("rex".to_string()),
+        }];
+        let config =
+            generate_config_with_repository(&ProjectConfigInput::default(), &tasks, root.path());
+
+        let rex = config.agents.get("rex").unwrap();
+        assert!(rex.tools.remote.contains(&"nats_publish".to_string()));
+        let cleo = config.agents.get("cleo").unwrap();
+        assert!(cleo.tools.remote.contains(&"nats_publish".to_string()));
+        let blaze = config.agents.get("blaze");
+        assert!(blaze.is_none_or(|blaze| !blaze.tools.remote.contains(&"nats_publish".to_string())));
+    }
+
+    #[test]
+    fn test_generate_project_config_with_roster_pins_reassigned_roles() {
+        let root = TempDir::new().unwrap();
+        std::fs::write(
+            root.path().join("forge.yaml"),
+            "roles: [implementation]\nmodel: gpt-5.5-codex\ncli: codex\n",
+        )
+        .unwrap();
+        let roster = AgentRoster::load(&[root.path().to_path_buf()]).unwrap();
+
+        let config = generate_project_config_with_roster(&ProjectConfigInput::default(), &roster);
+        assert_eq!(config.agents["forge"].model, "gpt-5.5-codex");
+        assert_eq!(config.agents["forge"].github_app, "5DLabs-Forge");
+        assert!(!config.agents.contains_key("rex"));
+        assert_eq!(
+            config
+                .defaults
+                .play
+                .get_implementation_agent(&config.org_name, &roster),
+            "5DLabs-Forge"
+        );
+        // A reassigned role resolves through the loaded roster even unpinned.
+        let unpinned = PlayDefaults::default();
+        assert_eq!(
+            unpinned.get_implementation_agent("Acme", &roster),
+            "Acme-Forge"
+        );
+        assert_eq!(
+            unpinned.get_implementation_agent("Acme", AgentRoster::built_in()),
+            "Acme-Rex"
+        );
+        // Roles the roster leaves alone stay derived from orgName.
+        assert!(config.defaults.play.frontend_agent.is_none());
+
+        let config = generate_config_with_roster(
+            &ProjectConfigInput::default(),
+            &Vec::<TestTask>::new(),
+            None,
+            &roster,
+        );
+        assert_eq!(
+            config.defaults.play.implementation_agent.as_deref(),
+            Some("5DLabs-Forge")
+        );
+    }
 }
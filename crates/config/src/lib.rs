@@ -5,8 +5,11 @@
 //! This crate provides:
 //! - Configuration types (`CtoConfig`, `AgentConfig`, etc.)
 //! - Agent definitions with default tools
-//! - Tool mappings for task-based analysis
+//! - Tool mappings for task-based and manifest-based analysis
 //! - Config generation functions
+//! - Versioned migrations, JSON Schema export and validation
+//! - Layered resolution of effective agent settings with provenance
+//! - A file-based agent roster layered over the built-in agents
 //!
 //! # Example
 //!
@@ -25,22 +28,43 @@
 //! ```
 
 pub mod agents;
+pub mod error;
 pub mod generator;
+pub mod manifests;
+pub mod migration;
+pub mod resolve;
+pub mod roster;
+pub mod schema;
 pub mod tools;
 pub mod types;
+pub mod validate;
 
 // Re-export main types for convenience
 pub use agents::{
     all_agent_names, capitalize, default_remote_tools, get_agent_config, workflow_agents,
     DEFAULT_CLI, DEFAULT_MODEL,
 };
+pub use error::{ConfigError, RosterError};
 pub use generator::{
-    derive_service_name, generate_config_with_tasks, generate_project_config,
-    generate_project_config_json, ProjectConfigInput,
+    derive_service_name, generate_config_with_repository, generate_config_with_roster,
+    generate_config_with_tasks, generate_project_config, generate_project_config_json,
+    generate_project_config_with_roster, ProjectConfigInput,
 };
+pub use manifests::{read_manifest_dependencies, ManifestDependency, ManifestKind};
+pub use migration::{migrate_value, MigrationReport, LEGACY_CONFIG_VERSION, MIGRATIONS};
+pub use resolve::{
+    is_unset, Candidate, ConfigLayer, ConfigResolver, EffectiveConfig, ResolvedValue, ValueSource,
+    BUILT_IN_ORIGIN,
+};
+pub use roster::{
+    AgentDefinition, AgentRole, AgentRoster, RosterAgent, RosterSource, AGENT_ROSTER_DIRS_ENV,
+};
+pub use schema::{cto_config_schema, cto_config_schema_json};
 pub use tools::{
     analyze_agent_tasks_for_tools, analyze_all_tasks_for_tools, analyze_content_for_tools,
-    analyze_task_for_tools, ToolAnalyzable, TECH_TOOL_MAPPINGS,
+    analyze_task_for_tools, infer_tools, infer_tools_from_content, infer_tools_from_repository,
+    Evidence, Keyword, TechToolMapping, TechnologyMatch, ToolAnalyzable, ToolInference,
+    DETECTION_THRESHOLD, TECH_TOOL_MAPPINGS,
 };
 pub use types::{
     AcpDefaults, AcpRuntimeConfig, AcpServerConfig, AcpServiceConfig, AcpServicesConfig,
@@ -48,3 +72,6 @@ pub use types::{
     Defaults, IntakeDefaults, IntakeModels, LinearDefaults, LinearIntakeSettings, MultiModelConfig,
     PlayDefaults, SubagentConfig, CTO_CONFIG_VERSION,
 };
+pub use validate::{
+    validate_config, validate_json, Severity, ValidationIssue, ValidationReport, KNOWN_CLIS,
+};
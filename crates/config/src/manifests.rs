@@ -0,0 +1,450 @@
+//! Dependency extraction from repository manifests.
+//!
+//! Tool inference from task text is a guess; a dependency declared in
+//! `Cargo.toml`, `package.json`, `go.mod`, `pyproject.toml` or a
+//! docker-compose file is proof. This module reads those manifests and
+//! returns normalized dependency names for [`crate::tools::infer_tools`].
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+/// Kind of manifest a dependency was declared in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ManifestKind {
+    /// Rust `Cargo.toml`.
+    Cargo,
+    /// Node `package.json`.
+    PackageJson,
+    /// Go `go.mod`.
+    GoMod,
+    /// Python `pyproject.toml` (PEP 621 or Poetry).
+    Pyproject,
+    /// `docker-compose.yml` / `compose.yaml` service images.
+    DockerCompose,
+}
+
+impl ManifestKind {
+    /// Manifest kind for a file name, if it is one we understand.
+    #[must_use]
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        match name {
+            "Cargo.toml" => Some(Self::Cargo),
+            "package.json" => Some(Self::PackageJson),
+            "go.mod" => Some(Self::GoMod),
+            "pyproject.toml" => Some(Self::Pyproject),
+            "docker-compose.yml" | "docker-compose.yaml" | "compose.yml" | "compose.yaml" => {
+                Some(Self::DockerCompose)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A dependency declared in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestDependency {
+    /// Manifest the dependency came from.
+    pub file: PathBuf,
+    /// Kind of that manifest.
+    pub kind: ManifestKind,
+    /// Normalized (lowercase) dependency name, e.g. `sqlx`, `@radix-ui/react-dialog`,
+    /// `github.com/jackc/pgx`, or the image name `postgres` for compose services.
+    pub name: String,
+}
+
+impl fmt::Display for ManifestDependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in {}", self.name, self.file.display())
+    }
+}
+
+/// Directories never scanned for nested manifests.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build"];
+
+/// Read dependencies from the manifests in `repo_root` and its immediate
+/// subdirectories (to cover `frontend/`, `services/api/`-style layouts).
+///
+/// Unreadable or malformed manifests are skipped with a warning.
+#[must_use]
+pub fn read_manifest_dependencies(repo_root: &Path) -> Vec<ManifestDependency> {
+    let mut dirs = vec![repo_root.to_path_buf()];
+    if let Ok(entries) = std::fs::read_dir(repo_root) {
+        let mut children: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+            .filter(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref())
+            })
+            .map(|entry| entry.path())
+            .collect();
+        children.sort();
+        dirs.extend(children);
+    }
+
+    let mut dependencies = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut files: Vec<(PathBuf, ManifestKind)> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let kind = ManifestKind::from_file_name(&entry.file_name().to_string_lossy())?;
+                Some((entry.path(), kind))
+            })
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (file, kind) in files {
+            let names = std::fs::read_to_string(&file)
+                .map_err(|err| err.to_string())
+                .and_then(|content| parse_manifest(kind, &content));
+            match names {
+                Ok(names) => {
+                    dependencies.extend(names.into_iter().map(|name| ManifestDependency {
+                        file: file.clone(),
+                        kind,
+                        name,
+                    }))
+                }
+                Err(err) => {
+                    tracing::warn!("Skipping manifest {}: {err}", file.display());
+                }
+            }
+        }
+    }
+    dependencies
+}
+
+/// Extract normalized dependency names from manifest content.
+///
+/// # Errors
+/// Returns an error if the manifest cannot be parsed.
+pub fn parse_manifest(kind: ManifestKind, content: &str) -> Result<Vec<String>, String> {
+    let mut names = match kind {
+        ManifestKind::Cargo => parse_cargo(content)?,
+        ManifestKind::PackageJson => parse_package_json(content)?,
+        ManifestKind::GoMod => parse_go_mod(content),
+        ManifestKind::Pyproject => parse_pyproject(content)?,
+        ManifestKind::DockerCompose => parse_docker_compose(content)?,
+    };
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+const CARGO_DEPENDENCY_TABLES: &[&str] =
+    &["dependencies", "dev-dependencies", "build-dependencies"];
+
+fn parse_cargo(content: &str) -> Result<Vec<String>, String> {
+    let manifest: toml::Table = toml::from_str(content).map_err(|err| err.to_string())?;
+    let mut names = Vec::new();
+
+    let mut tables: Vec<&toml::Table> = Vec::new();
+    for key in CARGO_DEPENDENCY_TABLES {
+        if let Some(table) = manifest.get(*key).and_then(toml::Value::as_table) {
+            tables.push(table);
+        }
+    }
+    if let Some(table) = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("dependencies"))
+        .and_then(toml::Value::as_table)
+    {
+        tables.push(table);
+    }
+    if let Some(targets) = manifest.get("target").and_then(toml::Value::as_table) {
+        for target in targets.values() {
+            for key in CARGO_DEPENDENCY_TABLES {
+                if let Some(table) = target.get(*key).and_then(toml::Value::as_table) {
+                    tables.push(table);
+                }
+            }
+        }
+    }
+
+    for table in tables {
+        for (name, spec) in table {
+            // `foo = { package = "bar" }` depends on crate `bar`.
+            let name = spec
+                .get("package")
+                .and_then(toml::Value::as_str)
+                .unwrap_or(name);
+            names.push(name.to_lowercase());
+        }
+    }
+    Ok(names)
+}
+
+const PACKAGE_JSON_DEPENDENCY_KEYS: &[&str] = &[
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+fn parse_package_json(content: &str) -> Result<Vec<String>, String> {
+    let manifest: Value = serde_json::from_str(content).map_err(|err| err.to_string())?;
+    Ok(PACKAGE_JSON_DEPENDENCY_KEYS
+        .iter()
+        .filter_map(|key| manifest.get(*key).and_then(Value::as_object))
+        .flat_map(|deps| deps.keys().map(|name| name.to_lowercase()))
+        .collect())
+}
+
+fn parse_go_mod(content: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut in_require_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let module = if in_require_block {
+            if line == ")" {
+                in_require_block = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_require_block = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        if let Some(path) = module.split_whitespace().next() {
+            names.push(strip_go_major_version(&path.to_lowercase()));
+        }
+    }
+    names
+}
+
+/// `github.com/jackc/pgx/v5` -> `github.com/jackc/pgx`.
+fn strip_go_major_version(path: &str) -> String {
+    match path.rsplit_once('/') {
+        Some((base, suffix))
+            if suffix.len() > 1
+                && suffix.starts_with('v')
+                && suffix[1..].chars().all(|c| c.is_ascii_digit()) =>
+        {
+            base.to_string()
+        }
+        _ => path.to_string(),
+    }
+}
+
+fn parse_pyproject(content: &str) -> Result<Vec<String>, String> {
+    let manifest: toml::Table = toml::from_str(content).map_err(|err| err.to_string())?;
+    let mut names = Vec::new();
+
+    if let Some(project) = manifest.get("project") {
+        let requirements = project
+            .get("dependencies")
+            .and_then(toml::Value::as_array)
+            .into_iter()
+            .flatten();
+        let optional = project
+            .get("optional-dependencies")
+            .and_then(toml::Value::as_table)
+            .into_iter()
+            .flat_map(|groups| groups.values())
+            .filter_map(toml::Value::as_array)
+            .flatten();
+        for requirement in requirements.chain(optional) {
+            if let Some(name) = requirement.as_str().and_then(pep508_name) {
+                names.push(name);
+            }
+        }
+    }
+
+    if let Some(poetry) = manifest
+        .get("tool")
+        .and_then(|tool| tool.get("poetry"))
+        .and_then(toml::Value::as_table)
+    {
+        let mut tables: Vec<&toml::Table> = Vec::new();
+        for key in ["dependencies", "dev-dependencies"] {
+            if let Some(table) = poetry.get(key).and_then(toml::Value::as_table) {
+                tables.push(table);
+            }
+        }
+        if let Some(groups) = poetry.get("group").and_then(toml::Value::as_table) {
+            tables.extend(
+                groups
+                    .values()
+                    .filter_map(|group| group.get("dependencies"))
+                    .filter_map(toml::Value::as_table),
+            );
+        }
+        for table in tables {
+            names.extend(
+                table
+                    .keys()
+                    .filter(|name| name.as_str() != "python")
+                    .map(|name| normalize_python_name(name)),
+            );
+        }
+    }
+
+    Ok(names)
+}
+
+/// Name part of a PEP 508 requirement, e.g. `psycopg[binary]>=3.1` -> `psycopg`.
+fn pep508_name(requirement: &str) -> Option<String> {
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let name = requirement[..end].trim();
+    (!name.is_empty()).then(|| normalize_python_name(name))
+}
+
+fn normalize_python_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+fn parse_docker_compose(content: &str) -> Result<Vec<String>, String> {
+    let manifest: Value = serde_yaml::from_str(content).map_err(|err| err.to_string())?;
+    Ok(manifest
+        .get("services")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|services| services.values())
+        .filter_map(|service| service.get("image").and_then(Value::as_str))
+        .map(image_name)
+        .filter(|name| !name.is_empty())
+        .collect())
+}
+
+/// `docker.io/bitnami/redis:7.2@sha256:...` -> `redis`.
+fn image_name(image: &str) -> String {
+    let image = image.split('@').next().unwrap_or_default();
+    let last = image.rsplit('/').next().unwrap_or_default();
+    last.split(':').next().unwrap_or_default().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cargo_manifest() {
+        let content = r#"
+[package]
+name = "api"
+
+[dependencies]
+sqlx = { version = "0.8", features = ["postgres"] }
+Redis = "0.27"
+pg = { package = "tokio-postgres", version = "0.7" }
+
+[target.'cfg(unix)'.dev-dependencies]
+tonic = "0.12"
+
+[workspace.dependencies]
+async-nats = "0.38"
+"#;
+        let names = parse_manifest(ManifestKind::Cargo, content).unwrap();
+        assert_eq!(
+            names,
+            vec!["async-nats", "redis", "sqlx", "tokio-postgres", "tonic"]
+        );
+    }
+
+    #[test]
+    fn test_parse_package_json() {
+        let content = r#"{
+            "name": "web",
+            "dependencies": {"@radix-ui/react-dialog": "^1", "ioredis": "^5"},
+            "devDependencies": {"@playwright/test": "^1"}
+        }"#;
+        let names = parse_manifest(ManifestKind::PackageJson, content).unwrap();
+        assert_eq!(
+            names,
+            vec!["@playwright/test", "@radix-ui/react-dialog", "ioredis"]
+        );
+    }
+
+    #[test]
+    fn test_parse_go_mod() {
+        let content = "module example.com/api\n\ngo 1.22\n\n\
+                       require github.com/nats-io/nats.go v1.37.0\n\n\
+                       require (\n\
+                       \tgithub.com/jackc/pgx/v5 v5.7.1\n\
+                       \tgithub.com/redis/go-redis/v9 v9.7.0 // indirect\n\
+                       )\n";
+        let names = parse_manifest(ManifestKind::GoMod, content).unwrap();
+        assert_eq!(
+            names,
+            vec![
+                "github.com/jackc/pgx",
+                "github.com/nats-io/nats.go",
+                "github.com/redis/go-redis"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pyproject() {
+        let content = r#"
+[project]
+dependencies = ["psycopg[binary]>=3.1", "FastAPI"]
+
+[project.optional-dependencies]
+queue = ["confluent_kafka ; python_version >= '3.10'"]
+
+[tool.poetry.dependencies]
+python = "^3.12"
+boto3 = "^1.35"
+"#;
+        let names = parse_manifest(ManifestKind::Pyproject, content).unwrap();
+        assert_eq!(
+            names,
+            vec!["boto3", "confluent-kafka", "fastapi", "psycopg"]
+        );
+    }
+
+    #[test]
+    fn test_parse_docker_compose() {
+        let content = "services:\n  db:\n    image: postgres:16\n  cache:\n    image: docker.io/bitnami/redis:7.2\n  app:\n    build: .\n";
+        let names = parse_manifest(ManifestKind::DockerCompose, content).unwrap();
+        assert_eq!(names, vec!["postgres", "redis"]);
+    }
+
+    #[test]
+    fn test_read_manifest_dependencies_scans_subdirectories() {
+        let root = std::env::temp_dir().join(format!("cto-manifests-{}", std::process::id()));
+        let web = root.join("web");
+        let ignored = root.join("node_modules").join("left-pad");
+        std::fs::create_dir_all(&web).unwrap();
+        std::fs::create_dir_all(&ignored).unwrap();
+        std::fs::write(
+            root.join("go.mod"),
+            "module x\nrequire github.com/lib/pq v1.10.9\n",
+        )
+        .unwrap();
+        std::fs::write(
+            web.join("package.json"),
+            r#"{"dependencies": {"kafkajs": "2"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            ignored.join("package.json"),
+            r#"{"dependencies": {"mongodb": "6"}}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join("Cargo.toml"), "not [valid toml").unwrap();
+
+        let deps = read_manifest_dependencies(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let names: Vec<&str> = deps.iter().map(|dep| dep.name.as_str()).collect();
+        assert_eq!(names, vec!["github.com/lib/pq", "kafkajs"]);
+        assert_eq!(deps[1].kind, ManifestKind::PackageJson);
+        assert!(deps[1].file.ends_with("web/package.json"));
+    }
+}
@@ -0,0 +1,335 @@
+//! Versioned migrations for `cto-config.json`.
+//!
+//! Each [`Migration`] rewrites the raw JSON document from one version to the
+//! next. [`migrate_value`] applies the chain step by step until the document
+//! reaches [`CTO_CONFIG_VERSION`], recording what moved or was dropped so
+//! callers can surface it instead of silently losing fields.
+//!
+//! Documents without a `version` field are treated as [`LEGACY_CONFIG_VERSION`],
+//! the MCP-era shape with `defaults.code` / `defaults.docs` sections.
+
+use serde_json::{Map, Value};
+
+use crate::agents::{capitalize, DEFAULT_CLI, DEFAULT_MODEL};
+use crate::error::ConfigError;
+use crate::types::{make_agent_name, IntakeDefaults, CTO_CONFIG_VERSION, DEFAULT_ORG_NAME};
+
+/// Version assumed for documents that carry no `version` field.
+pub const LEGACY_CONFIG_VERSION: &str = "0.9";
+
+/// Rewrites a config document in place, appending notes about what changed.
+type MigrationFn = fn(&mut Map<String, Value>, &mut Vec<String>) -> Result<(), String>;
+
+/// A single upgrade step between two adjacent config versions.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Version this step reads.
+    pub from: &'static str,
+    /// Version this step produces.
+    pub to: &'static str,
+    /// Short human-readable summary.
+    pub description: &'static str,
+    apply: MigrationFn,
+}
+
+/// All known migrations, ordered oldest first.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    from: "0.9",
+    to: "1.0",
+    description: "fold defaults.code/defaults.docs into play/intake and fill required agent fields",
+    apply: migrate_0_9_to_1_0,
+}];
+
+/// Result of running the migration chain over a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    /// Version the document declared (or [`LEGACY_CONFIG_VERSION`]).
+    pub from_version: String,
+    /// Version after migration.
+    pub to_version: String,
+    /// Applied steps, e.g. `0.9 -> 1.0`.
+    pub steps: Vec<String>,
+    /// Path-qualified notes about moved, defaulted or dropped values.
+    pub notes: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Whether any migration step ran.
+    #[must_use]
+    pub fn migrated(&self) -> bool {
+        !self.steps.is_empty()
+    }
+}
+
+/// Upgrade a raw config document to [`CTO_CONFIG_VERSION`].
+///
+/// # Errors
+/// Returns an error if the root is not an object, the version is newer than
+/// this build supports or unknown, or a migration step fails.
+pub fn migrate_value(mut value: Value) -> Result<(Value, MigrationReport), ConfigError> {
+    let Some(root) = value.as_object_mut() else {
+        return Err(ConfigError::Schema {
+            path: "$".to_string(),
+            message: "config root must be a JSON object".to_string(),
+        });
+    };
+
+    let from_version = match root.get("version") {
+        None | Some(Value::Null) => LEGACY_CONFIG_VERSION.to_string(),
+        Some(Value::String(version)) => version.clone(),
+        Some(other) => {
+            return Err(ConfigError::Schema {
+                path: "version".to_string(),
+                message: format!("expected a version string, found {other}"),
+            })
+        }
+    };
+
+    let Some(found) = parse_version(&from_version) else {
+        return Err(unsupported(&from_version));
+    };
+    let current = parse_version(CTO_CONFIG_VERSION).unwrap_or_default();
+    if found > current {
+        return Err(unsupported(&from_version));
+    }
+
+    let mut report = MigrationReport {
+        from_version: from_version.clone(),
+        to_version: from_version.clone(),
+        steps: Vec::new(),
+        notes: Vec::new(),
+    };
+
+    let mut version = from_version;
+    while version != CTO_CONFIG_VERSION {
+        let Some(step) = MIGRATIONS.iter().find(|step| step.from == version) else {
+            return Err(unsupported(&version));
+        };
+        (step.apply)(root, &mut report.notes).map_err(|message| ConfigError::Migration {
+            from: step.from,
+            to: step.to,
+            message,
+        })?;
+        root.insert("version".to_string(), Value::String(step.to.to_string()));
+        report.steps.push(format!("{} -> {}", step.from, step.to));
+        version = step.to.to_string();
+    }
+    report.to_version = version;
+
+    Ok((value, report))
+}
+
+fn unsupported(found: &str) -> ConfigError {
+    ConfigError::UnsupportedVersion {
+        found: found.to_string(),
+        supported: CTO_CONFIG_VERSION,
+    }
+}
+
+/// Parse a `major.minor` version string.
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+    Some((major.trim().parse().ok()?, minor.trim().parse().ok()?))
+}
+
+/// Play fields that used to live under `defaults.code`.
+const LEGACY_CODE_PLAY_FIELDS: &[&str] = &[
+    "repository",
+    "service",
+    "docsRepository",
+    "docsProjectDirectory",
+    "workingDirectory",
+];
+
+/// Intake fields that used to live under `defaults.docs`.
+const LEGACY_DOCS_INTAKE_FIELDS: &[&str] = &["githubApp", "includeCodebase", "sourceBranch"];
+
+fn migrate_0_9_to_1_0(
+    root: &mut Map<String, Value>,
+    notes: &mut Vec<String>,
+) -> Result<(), String> {
+    if root.remove("mcpServers").is_some() {
+        notes.push(
+            "mcpServers: dropped; configure local servers per agent under agents.<name>.tools.localServers"
+                .to_string(),
+        );
+    }
+
+    let org_name = root
+        .get("orgName")
+        .and_then(Value::as_str)
+        .unwrap_or(DEFAULT_ORG_NAME)
+        .to_string();
+
+    let defaults = object_entry(root, "defaults").ok_or("defaults must be an object")?;
+
+    if let Some(Value::Object(code)) = defaults.remove("code") {
+        let play = object_entry(defaults, "play").ok_or("defaults.play must be an object")?;
+        for (key, value) in code {
+            if LEGACY_CODE_PLAY_FIELDS.contains(&key.as_str()) {
+                if play.contains_key(&key) {
+                    notes.push(format!(
+                        "defaults.code.{key}: dropped; defaults.play.{key} already set"
+                    ));
+                } else {
+                    play.insert(key.clone(), value);
+                    notes.push(format!("defaults.code.{key}: moved to defaults.play.{key}"));
+                }
+            } else {
+                notes.push(format!(
+                    "defaults.code.{key}: dropped; set it per agent under agents.<name>"
+                ));
+            }
+        }
+    }
+
+    if let Some(Value::Object(docs)) = defaults.remove("docs") {
+        let intake = object_entry(defaults, "intake").ok_or("defaults.intake must be an object")?;
+        for (key, value) in docs {
+            if LEGACY_DOCS_INTAKE_FIELDS.contains(&key.as_str()) && !intake.contains_key(&key) {
+                intake.insert(key.clone(), value);
+                notes.push(format!(
+                    "defaults.docs.{key}: moved to defaults.intake.{key}"
+                ));
+            } else if key == "model" {
+                let models = object_entry(intake, "models")
+                    .ok_or("defaults.intake.models must be an object")?;
+                if models.contains_key("primary") {
+                    notes.push(
+                        "defaults.docs.model: dropped; defaults.intake.models.primary already set"
+                            .to_string(),
+                    );
+                } else {
+                    models.insert("primary".to_string(), value);
+                    notes.push(
+                        "defaults.docs.model: moved to defaults.intake.models.primary".to_string(),
+                    );
+                }
+            } else {
+                notes.push(format!("defaults.docs.{key}: dropped"));
+            }
+        }
+    }
+
+    if let Some(Value::Object(intake)) = defaults.get_mut("intake") {
+        let fallback = IntakeDefaults::default();
+        for (key, value) in [
+            ("githubApp", Value::String(fallback.github_app)),
+            ("cli", Value::String(fallback.cli)),
+            (
+                "models",
+                serde_json::to_value(&fallback.models).map_err(|err| err.to_string())?,
+            ),
+        ] {
+            if !intake.contains_key(key) {
+                intake.insert(key.to_string(), value);
+                notes.push(format!(
+                    "defaults.intake.{key}: missing; filled with default"
+                ));
+            }
+        }
+    }
+
+    let agents = object_entry(root, "agents").ok_or("agents must be an object")?;
+    for (name, agent) in agents.iter_mut() {
+        let Value::Object(agent) = agent else {
+            continue;
+        };
+        let defaults = [
+            ("githubApp", make_agent_name(&org_name, &capitalize(name))),
+            ("cli", DEFAULT_CLI.to_string()),
+            ("model", DEFAULT_MODEL.to_string()),
+        ];
+        for (key, value) in defaults {
+            if !agent.contains_key(key) {
+                notes.push(format!(
+                    "agents.{name}.{key}: missing; defaulted to {value:?}"
+                ));
+                agent.insert(key.to_string(), Value::String(value));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get `map[key]` as an object, inserting an empty object when absent.
+/// Returns `None` when the key holds a non-object value.
+fn object_entry<'a>(
+    map: &'a mut Map<String, Value>,
+    key: &str,
+) -> Option<&'a mut Map<String, Value>> {
+    map.entry(key.to_string())
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_current_version_is_untouched() {
+        let input = json!({"version": CTO_CONFIG_VERSION, "defaults": {}, "agents": {}});
+        let (output, report) = migrate_value(input.clone()).unwrap();
+        assert_eq!(output, input);
+        assert!(!report.migrated());
+    }
+
+    #[test]
+    fn test_unversioned_legacy_config_is_migrated() {
+        let input = json!({
+            "orgName": "Acme",
+            "mcpServers": {"context7": {}},
+            "defaults": {
+                "code": {
+                    "repository": "acme/app",
+                    "service": "app",
+                    "model": "claude-opus-4-5-20251101"
+                },
+                "docs": {
+                    "githubApp": "Acme-Morgan",
+                    "model": "gpt-5.5",
+                    "sourceBranch": "develop"
+                }
+            },
+            "agents": {
+                "rex": {"tools": {"remote": ["context7_get_library_docs"]}}
+            }
+        });
+
+        let (output, report) = migrate_value(input).unwrap();
+
+        assert_eq!(report.from_version, LEGACY_CONFIG_VERSION);
+        assert_eq!(report.to_version, CTO_CONFIG_VERSION);
+        assert_eq!(report.steps, vec!["0.9 -> 1.0".to_string()]);
+        assert_eq!(output["version"], CTO_CONFIG_VERSION);
+        assert!(output.get("mcpServers").is_none());
+        assert_eq!(output["defaults"]["play"]["repository"], "acme/app");
+        assert_eq!(output["defaults"]["intake"]["githubApp"], "Acme-Morgan");
+        assert_eq!(output["defaults"]["intake"]["sourceBranch"], "develop");
+        assert_eq!(output["defaults"]["intake"]["models"]["primary"], "gpt-5.5");
+        assert_eq!(output["agents"]["rex"]["githubApp"], "Acme-Rex");
+        assert!(report.notes.contains(
+            &"defaults.code.model: dropped; set it per agent under agents.<name>".to_string()
+        ));
+
+        // The migrated document must parse into the typed config.
+        let config: crate::CtoConfig = serde_json::from_value(output).unwrap();
+        assert_eq!(config.defaults.play.service, "app");
+        assert_eq!(config.agents["rex"].cli, DEFAULT_CLI);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let err = migrate_value(json!({"version": "9.0"})).unwrap_err();
+        assert!(matches!(err, ConfigError::UnsupportedVersion { .. }));
+    }
+
+    #[test]
+    fn test_unknown_older_version_is_rejected() {
+        let err = migrate_value(json!({"version": "0.1"})).unwrap_err();
+        assert!(matches!(err, ConfigError::UnsupportedVersion { .. }));
+    }
+}
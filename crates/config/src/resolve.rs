@@ -0,0 +1,666 @@
+//! Layered resolution of effective agent settings, with provenance.
+//!
+//! An agent's settings are assembled from several sources: built-in
+//! defaults, `defaults.*` and `defaults.play` in `cto-config.json`, the
+//! agent's own `agents.<name>` entry, the per-repo `.tasks/play-config.yaml`
+//! and finally per-run overrides (`CodeRun` spec fields or CLI flags).
+//!
+//! [`ConfigResolver`] collects candidate values from each [`ConfigLayer`] and
+//! [`ConfigResolver::resolve`] picks the highest layer for every key. Each
+//! [`ResolvedValue`] remembers which file and path it came from and which
+//! lower-layer values it shadowed, so [`EffectiveConfig::explain`] can answer
+//! "why is this agent running with that model?".
+//!
+//! Keys are dotted camelCase paths shared across layers, e.g. `cli`,
+//! `model`, `repository`, `watcher.model`, `tools.remote`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::agents::{capitalize, get_agent_config};
+use crate::error::ConfigError;
+use crate::migration::migrate_value;
+use crate::types::{make_agent_name, PlayDefaults, DEFAULT_ORG_NAME};
+
+/// Origin label used for values compiled into this crate.
+pub const BUILT_IN_ORIGIN: &str = "built-in";
+
+/// Sections of `defaults` that describe other workflows and are not part of
+/// an agent's effective settings.
+const NON_AGENT_DEFAULT_SECTIONS: &[&str] = &["play", "intake", "linear"];
+
+/// A configuration source, ordered from lowest to highest precedence.
+///
+/// Per-agent settings are more specific than the workflow-wide play defaults,
+/// so [`ConfigLayer::Agent`] sits above [`ConfigLayer::PlayDefaults`] (e.g.
+/// `agents.rex.watcher` replaces `defaults.play.watcher`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigLayer {
+    /// Fallbacks compiled into this crate.
+    BuiltIn,
+    /// Top-level `defaults` in `cto-config.json`.
+    Defaults,
+    /// `defaults.play` in `cto-config.json`.
+    PlayDefaults,
+    /// `agents.<name>` in `cto-config.json`.
+    Agent,
+    /// Per-repo `.tasks/play-config.yaml`.
+    RepoConfig,
+    /// Per-run overrides from a `CodeRun` spec or CLI flags.
+    Override,
+}
+
+impl ConfigLayer {
+    /// Stable, human-readable layer name.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BuiltIn => "built-in",
+            Self::Defaults => "defaults",
+            Self::PlayDefaults => "play-defaults",
+            Self::Agent => "agent",
+            Self::RepoConfig => "repo-config",
+            Self::Override => "override",
+        }
+    }
+}
+
+impl fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where a value came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValueSource {
+    /// Layer that supplied the value.
+    pub layer: ConfigLayer,
+    /// File path, `CodeRun` reference or flag name, e.g. `/etc/cto/config.json`.
+    pub origin: String,
+    /// Location of the value inside `origin`, e.g. `agents.rex.model`.
+    pub path: String,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Built-in values have no file; don't repeat the layer name.
+        let origin = (self.origin != self.layer.as_str()).then_some(self.origin.as_str());
+        match (origin, self.path.is_empty()) {
+            (None, true) => write!(f, "{}", self.layer),
+            (None, false) => write!(f, "{} ({})", self.layer, self.path),
+            (Some(origin), true) => write!(f, "{} ({origin})", self.layer),
+            (Some(origin), false) => write!(f, "{} ({origin}: {})", self.layer, self.path),
+        }
+    }
+}
+
+/// A value supplied by one layer.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Candidate {
+    /// The supplied value.
+    pub value: Value,
+    /// Where it came from.
+    pub source: ValueSource,
+}
+
+/// The winning value for a key, plus the candidates it shadowed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResolvedValue {
+    /// Effective value.
+    pub value: Value,
+    /// Where the effective value came from.
+    pub source: ValueSource,
+    /// Lower-precedence values for the same key, highest first.
+    pub shadowed: Vec<Candidate>,
+}
+
+/// Collects candidate values from every layer.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigResolver {
+    candidates: BTreeMap<String, Vec<Candidate>>,
+}
+
+impl ConfigResolver {
+    /// Create an empty resolver.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a resolver pre-populated with the built-in defaults for `agent`.
+    #[must_use]
+    pub fn for_agent(agent: &str, org_name: &str) -> Self {
+        let mut resolver = Self::new();
+        resolver.add_built_in_defaults(agent, org_name);
+        resolver
+    }
+
+    /// Record a single value for `key`.
+    ///
+    /// `null` and blank strings mean "not set" (see [`is_unset`]) and are
+    /// ignored, matching how the config types default optional strings.
+    pub fn set(
+        &mut self,
+        layer: ConfigLayer,
+        origin: &str,
+        key: &str,
+        path: &str,
+        value: impl Into<Value>,
+    ) -> &mut Self {
+        let value = value.into();
+        if is_unset(&value) {
+            return self;
+        }
+        self.candidates
+            .entry(key.to_string())
+            .or_default()
+            .push(Candidate {
+                value,
+                source: ValueSource {
+                    layer,
+                    origin: origin.to_string(),
+                    path: path.to_string(),
+                },
+            });
+        self
+    }
+
+    /// Record every leaf of `value`, keyed relative to `key_prefix`.
+    ///
+    /// Objects are flattened into dotted keys; arrays are kept whole. `path`
+    /// is the location of `value` inside `origin` and is used for provenance.
+    pub fn set_tree(
+        &mut self,
+        layer: ConfigLayer,
+        origin: &str,
+        key_prefix: &str,
+        path: &str,
+        value: &Value,
+    ) -> &mut Self {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    self.set_tree(
+                        layer,
+                        origin,
+                        &join_key(key_prefix, key),
+                        &join_key(path, key),
+                        child,
+                    );
+                }
+            }
+            leaf => {
+                self.set(layer, origin, key_prefix, path, leaf.clone());
+            }
+        }
+        self
+    }
+
+    /// Record the built-in agent and play defaults.
+    pub fn add_built_in_defaults(&mut self, agent: &str, org_name: &str) -> &mut Self {
+        let play = serde_json::to_value(PlayDefaults::default()).unwrap_or(Value::Null);
+        self.set_tree(ConfigLayer::BuiltIn, BUILT_IN_ORIGIN, "", "", &play);
+
+        let agent_defaults = serde_json::to_value(get_agent_config(agent)).unwrap_or(Value::Null);
+        self.set_tree(
+            ConfigLayer::BuiltIn,
+            BUILT_IN_ORIGIN,
+            "",
+            "",
+            &agent_defaults,
+        );
+
+        // The hardcoded GitHub App names assume the default org.
+        self.set(
+            ConfigLayer::BuiltIn,
+            BUILT_IN_ORIGIN,
+            "githubApp",
+            "",
+            make_agent_name(org_name, &capitalize(agent)),
+        );
+        self.set(
+            ConfigLayer::BuiltIn,
+            BUILT_IN_ORIGIN,
+            "orgName",
+            "",
+            DEFAULT_ORG_NAME,
+        )
+    }
+
+    /// Record the layers a `cto-config.json` document contributes for `agent`.
+    ///
+    /// Works on the raw document so provenance only names keys that were
+    /// actually written in the file, not values filled in by serde defaults.
+    /// Agent entries are matched case-insensitively.
+    pub fn add_cto_config(&mut self, document: &Value, origin: &str, agent: &str) -> &mut Self {
+        if let Some(org_name) = document.get("orgName") {
+            self.set(
+                ConfigLayer::Defaults,
+                origin,
+                "orgName",
+                "orgName",
+                org_name.clone(),
+            );
+        }
+
+        if let Some(Value::Object(defaults)) = document.get("defaults") {
+            for (section, value) in defaults {
+                if NON_AGENT_DEFAULT_SECTIONS.contains(&section.as_str()) {
+                    continue;
+                }
+                self.set_tree(
+                    ConfigLayer::Defaults,
+                    origin,
+                    section,
+                    &format!("defaults.{section}"),
+                    value,
+                );
+            }
+            if let Some(play) = defaults.get("play") {
+                self.set_tree(ConfigLayer::PlayDefaults, origin, "", "defaults.play", play);
+            }
+        }
+
+        if let Some(Value::Object(agents)) = document.get("agents") {
+            if let Some((name, entry)) = agents
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(agent))
+            {
+                self.set_tree(
+                    ConfigLayer::Agent,
+                    origin,
+                    "",
+                    &format!("agents.{name}"),
+                    entry,
+                );
+            }
+        }
+        self
+    }
+
+    /// Parse, migrate and record a `cto-config.json` document for `agent`.
+    ///
+    /// Provenance paths refer to the migrated layout, so values moved by a
+    /// migration are reported at their current location.
+    ///
+    /// # Errors
+    /// Returns an error if the JSON is malformed or cannot be migrated.
+    pub fn add_cto_config_json(
+        &mut self,
+        json: &str,
+        origin: &str,
+        agent: &str,
+    ) -> Result<&mut Self, ConfigError> {
+        let document: Value = serde_json::from_str(json)?;
+        let (document, _) = migrate_value(document)?;
+        Ok(self.add_cto_config(&document, origin, agent))
+    }
+
+    /// Pick the highest-precedence value for every key.
+    ///
+    /// Within one layer the value recorded last wins.
+    #[must_use]
+    pub fn resolve(&self) -> EffectiveConfig {
+        let values = self
+            .candidates
+            .iter()
+            .filter_map(|(key, candidates)| {
+                let mut ordered: Vec<&Candidate> = candidates.iter().collect();
+                // Stable sort keeps insertion order within a layer.
+                ordered.sort_by_key(|candidate| candidate.source.layer);
+                let winner = ordered.pop()?;
+                let resolved = ResolvedValue {
+                    value: winner.value.clone(),
+                    source: winner.source.clone(),
+                    shadowed: ordered.into_iter().rev().cloned().collect(),
+                };
+                Some((key.clone(), resolved))
+            })
+            .collect();
+        EffectiveConfig { values }
+    }
+}
+
+/// Effective settings after layering.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EffectiveConfig {
+    values: BTreeMap<String, ResolvedValue>,
+}
+
+impl EffectiveConfig {
+    /// Resolved value and provenance for `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&ResolvedValue> {
+        self.values.get(key)
+    }
+
+    /// Effective value for `key`.
+    #[must_use]
+    pub fn value(&self, key: &str) -> Option<&Value> {
+        self.get(key).map(|resolved| &resolved.value)
+    }
+
+    /// Effective string value for `key`.
+    #[must_use]
+    pub fn str(&self, key: &str) -> Option<&str> {
+        self.value(key).and_then(Value::as_str)
+    }
+
+    /// Effective boolean value for `key`.
+    ///
+    /// Accepts `"true"`/`"false"` strings, since CLI and `CodeRun` overrides
+    /// are often stringly typed.
+    #[must_use]
+    pub fn bool(&self, key: &str) -> Option<bool> {
+        match self.value(key)? {
+            Value::Bool(value) => Some(*value),
+            Value::String(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Iterate over `(key, resolved)` pairs in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ResolvedValue)> {
+        self.values.iter().map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Keys with an effective value, in key order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// Rebuild a nested JSON document from the effective values.
+    ///
+    /// Dotted keys become nested objects, so the result can be deserialized
+    /// back into the config type the layers were recorded from.
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        let mut root = serde_json::Map::new();
+        for (key, resolved) in &self.values {
+            let mut segments: Vec<&str> = key.split('.').collect();
+            let Some(leaf) = segments.pop() else {
+                continue;
+            };
+            let mut node = &mut root;
+            for segment in segments {
+                let child = node
+                    .entry(segment.to_string())
+                    .or_insert_with(|| Value::Object(serde_json::Map::new()));
+                if !child.is_object() {
+                    *child = Value::Object(serde_json::Map::new());
+                }
+                let Value::Object(map) = child else {
+                    unreachable!("child was just made an object");
+                };
+                node = map;
+            }
+            node.insert(leaf.to_string(), resolved.value.clone());
+        }
+        Value::Object(root)
+    }
+
+    /// Render every effective value with the layer and file it came from.
+    ///
+    /// Shadowed lower-layer values are listed underneath so it is obvious
+    /// which layer won and what it replaced.
+    #[must_use]
+    pub fn explain(&self) -> String {
+        self.explain_keys(self.keys())
+    }
+
+    /// Like [`EffectiveConfig::explain`], restricted to `keys`.
+    ///
+    /// Keys without a value are reported as unset.
+    #[must_use]
+    pub fn explain_keys<'a>(&self, keys: impl IntoIterator<Item = &'a str>) -> String {
+        let rows: Vec<(&str, Option<&ResolvedValue>)> =
+            keys.into_iter().map(|key| (key, self.get(key))).collect();
+        let key_width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+
+        let mut out = String::new();
+        for (key, resolved) in rows {
+            let Some(resolved) = resolved else {
+                let _ = writeln!(out, "{key:<key_width$}  <unset>");
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "{key:<key_width$}  {}  <- {}",
+                resolved.value, resolved.source
+            );
+            for shadowed in &resolved.shadowed {
+                let _ = writeln!(
+                    out,
+                    "{:key_width$}    overrides {} from {}",
+                    "", shadowed.value, shadowed.source
+                );
+            }
+        }
+        out
+    }
+}
+
+/// Whether `value` means "not set": `null` or a blank string.
+#[must_use]
+pub fn is_unset(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(value) => value.trim().is_empty(),
+        _ => false,
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_config() -> Value {
+        json!({
+            "version": "1.0",
+            "orgName": "Acme",
+            "defaults": {
+                "skillsRepo": "https://github.com/acme/skills",
+                "intake": {"githubApp": "Acme-Morgan"},
+                "play": {
+                    "repository": "acme/app",
+                    "watcher": {"enabled": true, "model": "gpt-5-mini"}
+                }
+            },
+            "agents": {
+                "rex": {
+                    "githubApp": "Acme-Rex",
+                    "cli": "codex",
+                    "model": "gpt-5.5",
+                    "watcher": {"model": "gpt-5.5-mini"}
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_higher_layers_win_and_record_shadowed_values() {
+        let mut resolver = ConfigResolver::for_agent("rex", "Acme");
+        resolver.add_cto_config(&sample_config(), "cto-config.json", "Rex");
+        resolver.set(
+            ConfigLayer::RepoConfig,
+            ".tasks/play-config.yaml",
+            "model",
+            "defaults.model",
+            "kimi-k2",
+        );
+        resolver.set(ConfigLayer::Override, "--model", "model", "", "gpt-5.5-pro");
+
+        let effective = resolver.resolve();
+
+        let model = effective.get("model").unwrap();
+        assert_eq!(model.value, "gpt-5.5-pro");
+        assert_eq!(model.source.layer, ConfigLayer::Override);
+        let shadowed_layers: Vec<ConfigLayer> = model
+            .shadowed
+            .iter()
+            .map(|candidate| candidate.source.layer)
+            .collect();
+        assert_eq!(
+            shadowed_layers,
+            vec![
+                ConfigLayer::RepoConfig,
+                ConfigLayer::Agent,
+                ConfigLayer::BuiltIn
+            ]
+        );
+
+        let cli = effective.get("cli").unwrap();
+        assert_eq!(cli.value, "codex");
+        assert_eq!(cli.source.path, "agents.rex.cli");
+        assert_eq!(effective.str("repository"), Some("acme/app"));
+        assert_eq!(
+            effective.str("skillsRepo"),
+            Some("https://github.com/acme/skills")
+        );
+        assert_eq!(effective.str("orgName"), Some("Acme"));
+        assert!(effective.get("intake.githubApp").is_none());
+    }
+
+    #[test]
+    fn test_agent_watcher_overrides_play_watcher_per_field() {
+        let mut resolver = ConfigResolver::for_agent("rex", "Acme");
+        resolver.add_cto_config(&sample_config(), "cto-config.json", "rex");
+        let effective = resolver.resolve();
+
+        assert_eq!(effective.str("watcher.model"), Some("gpt-5.5-mini"));
+        assert_eq!(
+            effective.get("watcher.model").unwrap().source.layer,
+            ConfigLayer::Agent
+        );
+        assert_eq!(effective.bool("watcher.enabled"), Some(true));
+        assert_eq!(
+            effective.get("watcher.enabled").unwrap().source.layer,
+            ConfigLayer::PlayDefaults
+        );
+    }
+
+    #[test]
+    fn test_built_in_github_app_uses_org_name() {
+        let effective = ConfigResolver::for_agent("blaze", "Acme").resolve();
+        assert_eq!(effective.str("githubApp"), Some("Acme-Blaze"));
+        assert_eq!(
+            effective.get("githubApp").unwrap().source.origin,
+            BUILT_IN_ORIGIN
+        );
+        assert_eq!(effective.str("workingDirectory"), Some("."));
+    }
+
+    #[test]
+    fn test_empty_values_do_not_shadow() {
+        let mut resolver = ConfigResolver::new();
+        resolver.set(ConfigLayer::BuiltIn, BUILT_IN_ORIGIN, "service", "", "app");
+        resolver.set(
+            ConfigLayer::RepoConfig,
+            "play-config.yaml",
+            "service",
+            "project.service",
+            "",
+        );
+        resolver.set(
+            ConfigLayer::Agent,
+            "cto-config.json",
+            "service",
+            "agents.rex.service",
+            "  ",
+        );
+        resolver.set(
+            ConfigLayer::Override,
+            "--service",
+            "service",
+            "",
+            Value::Null,
+        );
+
+        let effective = resolver.resolve();
+        assert_eq!(effective.str("service"), Some("app"));
+        assert!(effective.get("service").unwrap().shadowed.is_empty());
+    }
+
+    #[test]
+    fn test_string_booleans() {
+        let mut resolver = ConfigResolver::new();
+        resolver.set(
+            ConfigLayer::Override,
+            "--auto-merge",
+            "autoMerge",
+            "",
+            "false",
+        );
+        assert_eq!(resolver.resolve().bool("autoMerge"), Some(false));
+    }
+
+    #[test]
+    fn test_cto_config_json_is_migrated_first() {
+        let legacy = r#"{"defaults": {"code": {"repository": "acme/legacy"}}, "agents": {}}"#;
+        let mut resolver = ConfigResolver::new();
+        resolver
+            .add_cto_config_json(legacy, "cto-config.json", "rex")
+            .unwrap();
+        let effective = resolver.resolve();
+        let repository = effective.get("repository").unwrap();
+        assert_eq!(repository.value, "acme/legacy");
+        assert_eq!(repository.source.path, "defaults.play.repository");
+    }
+
+    #[test]
+    fn test_to_value_nests_dotted_keys() {
+        let mut resolver = ConfigResolver::new();
+        resolver.set(ConfigLayer::Agent, "agents.rex", "model", "model", "gpt-5");
+        resolver.set(
+            ConfigLayer::Agent,
+            "agents.rex",
+            "settings.reasoningEffort",
+            "settings.reasoningEffort",
+            "high",
+        );
+        resolver.set(
+            ConfigLayer::Override,
+            "CodeRun cto/rex-1",
+            "settings.approvalPolicy",
+            "settings.approvalPolicy",
+            "never",
+        );
+
+        assert_eq!(
+            resolver.resolve().to_value(),
+            json!({
+                "model": "gpt-5",
+                "settings": {"approvalPolicy": "never", "reasoningEffort": "high"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_explain_lists_provenance() {
+        let mut resolver = ConfigResolver::for_agent("rex", "Acme");
+        resolver.add_cto_config(&sample_config(), "/etc/cto/config.json", "rex");
+        let explained = resolver.resolve().explain_keys(["cli", "service"]);
+
+        assert_eq!(
+            explained,
+            "cli      \"codex\"  <- agent (/etc/cto/config.json: agents.rex.cli)\n\
+             \x20          overrides \"claude\" from built-in (cli)\n\
+             service  <unset>\n"
+        );
+    }
+}
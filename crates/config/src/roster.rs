@@ -0,0 +1,646 @@
+//! File-based agent roster.
+//!
+//! The agents shipped with CTO (see [`crate::agents`]) form the built-in
+//! roster. An organisation can add agents or change existing ones by
+//! dropping one YAML or JSON file per agent into a roster directory:
+//!
+//! ```yaml
+//! # agents/rex.yaml — only the listed fields change
+//! model: gpt-5.5
+//! tools:
+//!   remote: [github_create_pull_request, postgres_query]
+//! ```
+//!
+//! ```yaml
+//! # agents/forge.yaml — a new agent that takes over Rust implementation
+//! displayName: Forge
+//! roles: [implementation]
+//! cli: codex
+//! model: gpt-5.5-codex
+//! ```
+//!
+//! Directories are applied in order on top of the built-ins, field by field.
+//! Roles decide which agent the `PlayDefaults::get_*_agent` helpers and
+//! [`crate::generator`] pick; a role claimed in a later directory wins.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::agents::{
+    all_agent_names, capitalize, default_remote_tools, get_agent_config, workflow_agents,
+    DEFAULT_CLI, DEFAULT_MODEL,
+};
+use crate::error::RosterError;
+use crate::types::{
+    make_agent_name, AgentConfig, AgentSkills, AgentTools, SubagentConfig, WatcherDefaults,
+    AGENT_BLAZE, AGENT_BOLT, AGENT_CIPHER, AGENT_CLEO, AGENT_GRIZZ, AGENT_MORGAN, AGENT_NOVA,
+    AGENT_REX, AGENT_SPARK, AGENT_TAP, AGENT_TESS, AGENT_VEX,
+};
+use crate::validate::KNOWN_CLIS;
+
+/// Environment variable listing roster directories, separated like `PATH`.
+pub const AGENT_ROSTER_DIRS_ENV: &str = "CTO_AGENT_ROSTER_DIRS";
+
+/// A slot in the Play/intake workflows that an agent can fill.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum AgentRole {
+    /// PRD intake and task generation.
+    Intake,
+    /// Default (Rust) implementation.
+    Implementation,
+    /// Web frontend.
+    Frontend,
+    /// Go implementation.
+    Go,
+    /// Node implementation.
+    Node,
+    /// Mobile apps.
+    Mobile,
+    /// Desktop apps.
+    Desktop,
+    /// VR apps.
+    Vr,
+    /// Infrastructure and deployment.
+    Infrastructure,
+    /// Code quality review.
+    Quality,
+    /// Security review.
+    Security,
+    /// Testing.
+    Testing,
+}
+
+impl AgentRole {
+    /// Every role, in declaration order.
+    pub const ALL: [Self; 12] = [
+        Self::Intake,
+        Self::Implementation,
+        Self::Frontend,
+        Self::Go,
+        Self::Node,
+        Self::Mobile,
+        Self::Desktop,
+        Self::Vr,
+        Self::Infrastructure,
+        Self::Quality,
+        Self::Security,
+        Self::Testing,
+    ];
+
+    /// Agent suffix used when no roster agent claims the role.
+    #[must_use]
+    pub fn default_suffix(self) -> &'static str {
+        match self {
+            Self::Intake => AGENT_MORGAN,
+            Self::Implementation => AGENT_REX,
+            Self::Frontend => AGENT_BLAZE,
+            Self::Go => AGENT_GRIZZ,
+            Self::Node => AGENT_NOVA,
+            Self::Mobile => AGENT_TAP,
+            Self::Desktop => AGENT_SPARK,
+            Self::Vr => AGENT_VEX,
+            Self::Infrastructure => AGENT_BOLT,
+            Self::Quality => AGENT_CLEO,
+            Self::Security => AGENT_CIPHER,
+            Self::Testing => AGENT_TESS,
+        }
+    }
+}
+
+/// Roles held by the built-in agents.
+const BUILT_IN_ROLES: &[(&str, AgentRole)] = &[
+    ("morgan", AgentRole::Intake),
+    ("rex", AgentRole::Implementation),
+    ("blaze", AgentRole::Frontend),
+    ("grizz", AgentRole::Go),
+    ("nova", AgentRole::Node),
+    ("tap", AgentRole::Mobile),
+    ("spark", AgentRole::Desktop),
+    ("bolt", AgentRole::Infrastructure),
+    ("cleo", AgentRole::Quality),
+    ("cipher", AgentRole::Security),
+    ("tess", AgentRole::Testing),
+];
+
+/// Contents of one agent definition file. Every field is optional so a
+/// file can change a single setting of a built-in agent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AgentDefinition {
+    /// Agent id; defaults to the file stem and must match it when set.
+    pub name: Option<String>,
+    /// Suffix for the GitHub App name (`{orgName}-{displayName}`).
+    pub display_name: Option<String>,
+    /// Explicit GitHub App name, bypassing `{orgName}-{displayName}`.
+    pub github_app: Option<String>,
+    /// Workflow roles this agent fills; replaces the previous list.
+    pub roles: Option<Vec<AgentRole>>,
+    /// Always include this agent in generated configs.
+    pub workflow: Option<bool>,
+    /// CLI to use.
+    pub cli: Option<String>,
+    /// Model to use.
+    pub model: Option<String>,
+    /// MCP tools; replaces the previous tools.
+    pub tools: Option<AgentTools>,
+    /// Skills by job type.
+    pub skills: Option<AgentSkills>,
+    /// Frontend stack.
+    pub frontend_stack: Option<String>,
+    /// Feature flags.
+    pub features: Option<HashMap<String, bool>>,
+    /// Subagent configuration.
+    pub subagents: Option<SubagentConfig>,
+    /// Watcher override.
+    pub watcher: Option<WatcherDefaults>,
+}
+
+/// Where a roster entry's settings came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RosterSource {
+    /// The definitions compiled into this crate.
+    BuiltIn,
+    /// A definition file.
+    File(PathBuf),
+}
+
+impl fmt::Display for RosterSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BuiltIn => f.write_str("built-in"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A fully resolved agent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RosterAgent {
+    /// Agent id, e.g. `rex`.
+    pub name: String,
+    /// GitHub App suffix, e.g. `Rex`.
+    pub display_name: String,
+    /// Explicit GitHub App name, if one was configured.
+    pub github_app: Option<String>,
+    /// Roles this agent fills.
+    pub roles: Vec<AgentRole>,
+    /// Whether generated configs always include this agent.
+    pub workflow: bool,
+    /// Agent settings; `github_app` is filled in by [`AgentRoster::agent_config`].
+    pub config: AgentConfig,
+    /// Layers that contributed, lowest first.
+    pub sources: Vec<RosterSource>,
+    /// Index of the layer that last set `roles`.
+    roles_layer: usize,
+}
+
+impl RosterAgent {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            display_name: capitalize(name),
+            github_app: None,
+            roles: Vec::new(),
+            workflow: false,
+            config: AgentConfig {
+                github_app: String::new(),
+                cli: DEFAULT_CLI.to_string(),
+                model: DEFAULT_MODEL.to_string(),
+                tools: AgentTools {
+                    remote: default_remote_tools(),
+                    local_servers: HashMap::new(),
+                },
+                skills: None,
+                frontend_stack: None,
+                features: None,
+                subagents: None,
+                watcher: None,
+            },
+            sources: Vec::new(),
+            roles_layer: 0,
+        }
+    }
+
+    /// GitHub App name for this agent under `org_name`.
+    #[must_use]
+    pub fn github_app_for(&self, org_name: &str) -> String {
+        self.github_app
+            .clone()
+            .unwrap_or_else(|| make_agent_name(org_name, &self.display_name))
+    }
+
+    fn apply(&mut self, definition: AgentDefinition, source: RosterSource, layer: usize) {
+        if let Some(display_name) = definition.display_name {
+            self.display_name = display_name;
+        }
+        if let Some(github_app) = definition.github_app {
+            self.github_app = Some(github_app);
+        }
+        if let Some(roles) = definition.roles {
+            self.roles = roles;
+            self.roles_layer = layer;
+        }
+        if let Some(workflow) = definition.workflow {
+            self.workflow = workflow;
+        }
+        if let Some(cli) = definition.cli {
+            self.config.cli = cli;
+        }
+        if let Some(model) = definition.model {
+            self.config.model = model;
+        }
+        if let Some(tools) = definition.tools {
+            self.config.tools = tools;
+        }
+        if definition.skills.is_some() {
+            self.config.skills = definition.skills;
+        }
+        if definition.frontend_stack.is_some() {
+            self.config.frontend_stack = definition.frontend_stack;
+        }
+        if definition.features.is_some() {
+            self.config.features = definition.features;
+        }
+        if definition.subagents.is_some() {
+            self.config.subagents = definition.subagents;
+        }
+        if definition.watcher.is_some() {
+            self.config.watcher = definition.watcher;
+        }
+        self.sources.push(source);
+    }
+}
+
+/// The set of known agents: built-ins plus any roster directories.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentRoster {
+    agents: BTreeMap<String, RosterAgent>,
+    layers: usize,
+}
+
+impl Default for AgentRoster {
+    fn default() -> Self {
+        Self::built_in().clone()
+    }
+}
+
+impl AgentRoster {
+    /// The built-in roster, equivalent to [`get_agent_config`] and
+    /// [`all_agent_names`].
+    #[must_use]
+    pub fn built_in() -> &'static Self {
+        static BUILT_IN: OnceLock<AgentRoster> = OnceLock::new();
+        BUILT_IN.get_or_init(|| {
+            let workflow = workflow_agents();
+            let agents = all_agent_names()
+                .into_iter()
+                .map(|name| {
+                    let mut agent = RosterAgent::new(name);
+                    agent.config = get_agent_config(name);
+                    agent.roles = BUILT_IN_ROLES
+                        .iter()
+                        .filter(|(agent_name, _)| *agent_name == name)
+                        .map(|(_, role)| *role)
+                        .collect();
+                    agent.workflow = workflow.contains(&name);
+                    agent.sources.push(RosterSource::BuiltIn);
+                    (name.to_string(), agent)
+                })
+                .collect();
+            Self { agents, layers: 1 }
+        })
+    }
+
+    /// Built-ins overlaid with each directory in order.
+    ///
+    /// # Errors
+    /// Returns the first unreadable, malformed or invalid definition.
+    pub fn load(dirs: &[PathBuf]) -> Result<Self, RosterError> {
+        let mut roster = Self::default();
+        for dir in dirs {
+            roster.load_dir(dir)?;
+        }
+        Ok(roster)
+    }
+
+    /// Built-ins overlaid with the directories in [`AGENT_ROSTER_DIRS_ENV`].
+    ///
+    /// # Errors
+    /// Returns the first unreadable, malformed or invalid definition.
+    pub fn from_env() -> Result<Self, RosterError> {
+        let dirs: Vec<PathBuf> = std::env::var_os(AGENT_ROSTER_DIRS_ENV)
+            .map(|value| std::env::split_paths(&value).collect())
+            .unwrap_or_default();
+        Self::load(&dirs)
+    }
+
+    /// Overlay every `*.yaml`, `*.yml` and `*.json` file in `dir`.
+    ///
+    /// The directory is validated as a whole before anything is applied, so
+    /// a bad file leaves the roster unchanged.
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be read, a file does not
+    /// parse, or a definition is invalid (bad name, unknown CLI, empty
+    /// model, or two files in the directory defining the same agent or role).
+    pub fn load_dir(&mut self, dir: &Path) -> Result<&mut Self, RosterError> {
+        let entries = std::fs::read_dir(dir).map_err(|source| RosterError::Io {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| matches!(ext, "yaml" | "yml" | "json"))
+            })
+            .collect();
+        files.sort();
+
+        let mut parsed: Vec<(String, AgentDefinition, PathBuf)> = Vec::new();
+        for path in files {
+            let content = std::fs::read_to_string(&path).map_err(|source| RosterError::Io {
+                path: path.clone(),
+                source,
+            })?;
+            let definition = parse_definition(&path, &content)?;
+            let name = validate_definition(&path, &definition)?;
+
+            if let Some((_, _, other)) = parsed.iter().find(|(existing, _, _)| *existing == name) {
+                return Err(invalid(
+                    &path,
+                    format!("agent {name:?} is also defined in {}", other.display()),
+                ));
+            }
+            for role in definition.roles.iter().flatten() {
+                if let Some((other_name, _, other)) = parsed.iter().find(|(_, other, _)| {
+                    other
+                        .roles
+                        .iter()
+                        .flatten()
+                        .any(|other_role| other_role == role)
+                }) {
+                    return Err(invalid(
+                        &path,
+                        format!(
+                            "role {role:?} is also claimed by {other_name:?} in {}",
+                            other.display()
+                        ),
+                    ));
+                }
+            }
+            parsed.push((name, definition, path));
+        }
+
+        let layer = self.layers;
+        self.layers += 1;
+        for (name, definition, path) in parsed {
+            self.agents
+                .entry(name.clone())
+                .or_insert_with(|| RosterAgent::new(&name))
+                .apply(definition, RosterSource::File(path), layer);
+        }
+        Ok(self)
+    }
+
+    /// Look up an agent by name (case-insensitive).
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&RosterAgent> {
+        self.agents.get(&name.to_lowercase())
+    }
+
+    /// All agent names, sorted.
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.agents.keys().map(String::as_str).collect()
+    }
+
+    /// Agents that are always included in generated configs.
+    #[must_use]
+    pub fn workflow_agents(&self) -> Vec<&str> {
+        self.agents
+            .values()
+            .filter(|agent| agent.workflow)
+            .map(|agent| agent.name.as_str())
+            .collect()
+    }
+
+    /// The agent filling `role`; when several do, the one whose roles were
+    /// set by the latest directory wins.
+    #[must_use]
+    pub fn agent_for_role(&self, role: AgentRole) -> Option<&RosterAgent> {
+        self.agents
+            .values()
+            .filter(|agent| agent.roles.contains(&role))
+            .max_by_key(|agent| agent.roles_layer)
+    }
+
+    /// GitHub App name for `role` under `org_name`, falling back to the
+    /// canonical agent for the role when the roster has none.
+    #[must_use]
+    pub fn github_app_for_role(&self, role: AgentRole, org_name: &str) -> String {
+        self.agent_for_role(role).map_or_else(
+            || make_agent_name(org_name, role.default_suffix()),
+            |agent| agent.github_app_for(org_name),
+        )
+    }
+
+    /// Effective config for `name` with its GitHub App name derived from
+    /// `org_name`. Unknown agents get the generic defaults.
+    #[must_use]
+    pub fn agent_config(&self, name: &str, org_name: &str) -> AgentConfig {
+        let fallback;
+        let agent = if let Some(agent) = self.get(name) {
+            agent
+        } else {
+            fallback = RosterAgent::new(&name.to_lowercase());
+            &fallback
+        };
+        AgentConfig {
+            github_app: agent.github_app_for(org_name),
+            ..agent.config.clone()
+        }
+    }
+}
+
+fn parse_definition(path: &Path, content: &str) -> Result<AgentDefinition, RosterError> {
+    let result = if path.extension().is_some_and(|ext| ext == "json") {
+        let mut deserializer = serde_json::Deserializer::from_str(content);
+        serde_path_to_error::deserialize(&mut deserializer).map_err(|err| err.to_string())
+    } else {
+        serde_path_to_error::deserialize(serde_yaml::Deserializer::from_str(content))
+            .map_err(|err| err.to_string())
+    };
+    result.map_err(|message| RosterError::Parse {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+/// Check a definition and return its agent name.
+fn validate_definition(path: &Path, definition: &AgentDefinition) -> Result<String, RosterError> {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let name = definition
+        .name
+        .as_deref()
+        .map_or_else(|| stem.clone(), str::to_lowercase);
+
+    let valid_name = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid_name {
+        return Err(invalid(
+            path,
+            format!("name {name:?} must be lowercase letters, digits and dashes"),
+        ));
+    }
+    if name != stem {
+        return Err(invalid(
+            path,
+            format!("name {name:?} does not match the file name {stem:?}"),
+        ));
+    }
+    if let Some(cli) = &definition.cli {
+        if !KNOWN_CLIS.contains(&cli.as_str()) {
+            return Err(invalid(
+                path,
+                format!("cli: unknown CLI {cli:?} (expected one of {KNOWN_CLIS:?})"),
+            ));
+        }
+    }
+    for (field, value) in [
+        ("model", &definition.model),
+        ("githubApp", &definition.github_app),
+        ("displayName", &definition.display_name),
+    ] {
+        if value
+            .as_deref()
+            .is_some_and(|value| value.trim().is_empty())
+        {
+            return Err(invalid(path, format!("{field}: must not be empty")));
+        }
+    }
+    Ok(name)
+}
+
+fn invalid(path: &Path, message: String) -> RosterError {
+    RosterError::Invalid {
+        path: path.to_path_buf(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn roster_dir(files: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for (file, content) in files {
+            std::fs::write(dir.path().join(file), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_built_in_roster_matches_agent_table() {
+        let roster = AgentRoster::built_in();
+        assert_eq!(roster.names().len(), all_agent_names().len());
+        assert_eq!(
+            roster.agent_config("rex", "5DLabs"),
+            get_agent_config("rex")
+        );
+        assert_eq!(
+            roster.github_app_for_role(AgentRole::Frontend, "Acme"),
+            "Acme-Blaze"
+        );
+        // No built-in VR agent; the canonical name is still used.
+        assert_eq!(
+            roster.github_app_for_role(AgentRole::Vr, "Acme"),
+            "Acme-Vex"
+        );
+        let mut workflow = roster.workflow_agents();
+        workflow.sort_unstable();
+        let mut expected = workflow_agents();
+        expected.sort_unstable();
+        assert_eq!(workflow, expected);
+    }
+
+    #[test]
+    fn test_org_files_override_and_extend_built_ins() {
+        let dir = roster_dir(&[
+            ("rex.yaml", "model: gpt-5.5\n"),
+            (
+                "forge.json",
+                r#"{"displayName": "Forge", "roles": ["implementation"], "cli": "codex", "model": "gpt-5.5-codex"}"#,
+            ),
+        ]);
+        let roster = AgentRoster::load(&[dir.path().to_path_buf()]).unwrap();
+
+        let rex = roster.agent_config("rex", "Acme");
+        assert_eq!(rex.model, "gpt-5.5");
+        assert_eq!(rex.github_app, "Acme-Rex");
+        // Untouched fields keep their built-in values.
+        assert!(rex
+            .tools
+            .remote
+            .contains(&"github_create_pull_request".to_string()));
+        assert_eq!(roster.get("rex").unwrap().sources.len(), 2);
+
+        assert_eq!(
+            roster.github_app_for_role(AgentRole::Implementation, "Acme"),
+            "Acme-Forge"
+        );
+        let forge = roster.agent_config("forge", "Acme");
+        assert_eq!(forge.cli, "codex");
+        assert_eq!(forge.tools.remote, default_remote_tools());
+    }
+
+    #[test]
+    fn test_invalid_definitions_are_rejected() {
+        let cases: &[(&str, &str, &str)] = &[
+            ("bad-cli", "rex.yaml", "cli: vim\n"),
+            ("unknown-field", "rex.yaml", "modle: gpt-5.5\n"),
+            ("name-mismatch", "rex.yaml", "name: blaze\n"),
+            ("empty-model", "rex.yaml", "model: \"\"\n"),
+            ("bad-name", "Rex_2.yaml", "model: gpt-5.5\n"),
+        ];
+        for (case, file, content) in cases {
+            let dir = roster_dir(&[(file, content)]);
+            let result = AgentRoster::load(&[dir.path().to_path_buf()]);
+            assert!(result.is_err(), "{case} should fail");
+        }
+    }
+
+    #[test]
+    fn test_duplicate_roles_in_one_directory_are_rejected() {
+        let dir = roster_dir(&[
+            ("anvil.yaml", "roles: [implementation]\n"),
+            ("forge.yaml", "roles: [implementation]\n"),
+        ]);
+        let mut roster = AgentRoster::default();
+        let err = roster.load_dir(dir.path()).unwrap_err();
+
+        assert!(err.to_string().contains("role Implementation"));
+        // Nothing from the bad directory was applied.
+        assert!(roster.get("anvil").is_none());
+    }
+}
@@ -0,0 +1,128 @@
+//! JSON Schema export for `cto-config.json`.
+//!
+//! The schema is generated from the types in [`crate::types`], so it stays in
+//! sync with what the services actually deserialize. Point an editor at the
+//! exported file (e.g. via `"$schema"` or a workspace setting) to get
+//! completion and validation while editing configs.
+
+use schemars::schema_for;
+use serde_json::Value;
+
+use crate::types::CtoConfig;
+
+/// Generate the JSON Schema for [`CtoConfig`].
+#[must_use]
+pub fn cto_config_schema() -> Value {
+    let mut schema = serde_json::to_value(schema_for!(CtoConfig)).unwrap_or(Value::Null);
+    if let Some(root) = schema.as_object_mut() {
+        root.insert(
+            "$id".to_string(),
+            Value::String("https://github.com/5dlabs/cto/cto-config.schema.json".to_string()),
+        );
+        // Editors honour `$schema` inside the document; let it through.
+        if let Some(Value::Object(properties)) = root.get_mut("properties") {
+            properties.insert(
+                "$schema".to_string(),
+                serde_json::json!({ "type": "string" }),
+            );
+        }
+    }
+    schema
+}
+
+/// Pretty-printed JSON Schema for [`CtoConfig`].
+///
+/// # Errors
+/// Returns an error if serialization fails.
+pub fn cto_config_schema_json() -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&cto_config_schema())
+}
+
+/// Resolve the object schemas that describe a value at some location.
+///
+/// Follows local `$ref`s and the `allOf`/`anyOf`/`oneOf` wrappers schemars
+/// emits for `Option<T>` and documented references.
+pub(crate) fn object_schemas<'a>(root: &'a Value, schema: &'a Value) -> Vec<&'a Value> {
+    let mut resolved = Vec::new();
+    collect_object_schemas(root, schema, &mut resolved, 0);
+    resolved
+}
+
+fn collect_object_schemas<'a>(
+    root: &'a Value,
+    schema: &'a Value,
+    out: &mut Vec<&'a Value>,
+    depth: usize,
+) {
+    if depth > 16 {
+        return;
+    }
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        if let Some(target) = reference
+            .strip_prefix("#/")
+            .and_then(|pointer| root.pointer(&format!("/{pointer}")))
+        {
+            collect_object_schemas(root, target, out, depth + 1);
+        }
+    }
+
+    for combinator in ["allOf", "anyOf", "oneOf"] {
+        if let Some(Value::Array(options)) = schema.get(combinator) {
+            for option in options {
+                collect_object_schemas(root, option, out, depth + 1);
+            }
+        }
+    }
+
+    if schema.get("properties").is_some() || schema.get("additionalProperties").is_some() {
+        out.push(schema);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_describes_top_level_fields() {
+        let schema = cto_config_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        for key in ["version", "orgName", "defaults", "agents"] {
+            assert!(properties.contains_key(key), "missing {key}");
+        }
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        assert!(required.contains(&"version"));
+    }
+
+    /// Keeps the checked-in schema in sync with the types.
+    /// Regenerate with `CTO_CONFIG_SCHEMA_UPDATE=1 cargo test -p config`.
+    #[test]
+    fn test_checked_in_schema_is_current() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../cto-config.schema.json");
+        let generated = format!("{}\n", cto_config_schema_json().unwrap());
+        if std::env::var_os("CTO_CONFIG_SCHEMA_UPDATE").is_some() {
+            std::fs::write(path, &generated).unwrap();
+        }
+        let checked_in = std::fs::read_to_string(path).unwrap_or_default();
+        assert!(
+            checked_in == generated,
+            "cto-config.schema.json is stale; rerun with CTO_CONFIG_SCHEMA_UPDATE=1"
+        );
+    }
+
+    #[test]
+    fn test_object_schemas_follows_refs() {
+        let schema = cto_config_schema();
+        let defaults = &schema["properties"]["defaults"];
+        let resolved = object_schemas(&schema, defaults);
+        assert!(resolved
+            .iter()
+            .any(|candidate| candidate["properties"].get("play").is_some()));
+    }
+}
@@ -1,70 +1,297 @@
 //! Tool mappings for task-based config generation.
 //!
-//! Maps technology keywords to relevant MCP tools.
+//! Maps technologies to relevant MCP tools. A technology is detected when
+//! the weighted score of its keywords in task text, minus any negative
+//! keywords, reaches [`DETECTION_THRESHOLD`], or when a repository manifest
+//! declares one of its dependencies. Keywords only match on word boundaries,
+//! so "sessions" no longer implies Redis and "schemas" no longer implies
+//! GraphQL. Every detection carries the [`Evidence`] behind it.
 
 use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::manifests::{read_manifest_dependencies, ManifestDependency};
+
+/// Score at which a technology counts as detected.
+pub const DETECTION_THRESHOLD: f32 = 1.0;
+
+/// A keyword and how strongly it indicates (or argues against) a technology.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyword {
+    /// Lowercase term; may contain spaces or punctuation (`socket.io`).
+    /// Terms ending in punctuation (`pg_`, `ws://`) match as prefixes.
+    pub term: &'static str,
+    /// Contribution to the technology score.
+    pub weight: f32,
+}
+
+const fn kw(term: &'static str, weight: f32) -> Keyword {
+    Keyword { term, weight }
+}
 
 /// Technology to tool mapping.
 pub struct TechToolMapping {
+    /// Technology name, reported in evidence.
+    pub technology: &'static str,
     /// Keywords that indicate this technology.
-    pub keywords: &'static [&'static str],
+    pub keywords: &'static [Keyword],
+    /// Keywords whose presence lowers the score (e.g. "json schema" for GraphQL).
+    pub negative_keywords: &'static [Keyword],
+    /// Manifest dependency names that prove this technology. A name also
+    /// matches its sub-paths, so `@radix-ui` covers `@radix-ui/react-dialog`.
+    pub dependencies: &'static [&'static str],
     /// Tools to add when this technology is detected.
     pub tools: &'static [&'static str],
 }
 
-/// Tool mappings based on technology keywords in task content.
+/// Tool mappings based on technology keywords and dependencies.
 pub const TECH_TOOL_MAPPINGS: &[TechToolMapping] = &[
     // Database tools
     TechToolMapping {
-        keywords: &["postgresql", "postgres", "pg_", "psql", "sqlx"],
+        technology: "postgres",
+        keywords: &[
+            kw("postgresql", 1.0),
+            kw("postgres", 1.0),
+            kw("pg_", 1.0),
+            kw("psql", 1.0),
+            kw("pgvector", 1.0),
+            kw("sqlx", 0.6),
+        ],
+        negative_keywords: &[],
+        dependencies: &[
+            "postgres",
+            "tokio-postgres",
+            "pg",
+            "pgvector",
+            "psycopg",
+            "psycopg2",
+            "psycopg2-binary",
+            "asyncpg",
+            "github.com/jackc/pgx",
+            "github.com/lib/pq",
+        ],
         tools: &["postgres_query", "postgres_execute"],
     },
     TechToolMapping {
-        keywords: &["redis", "valkey", "cache", "session"],
+        technology: "redis",
+        keywords: &[
+            kw("redis", 1.0),
+            kw("valkey", 1.0),
+            kw("cache", 0.3),
+            kw("caching", 0.3),
+            kw("session store", 0.5),
+        ],
+        negative_keywords: &[
+            kw("browser cache", 0.3),
+            kw("http cache", 0.3),
+            kw("cache-control", 0.3),
+        ],
+        dependencies: &[
+            "redis",
+            "valkey",
+            "ioredis",
+            "fred",
+            "deadpool-redis",
+            "bb8-redis",
+            "github.com/redis/go-redis",
+            "github.com/gomodule/redigo",
+        ],
         tools: &["redis_get", "redis_set", "redis_del"],
     },
     TechToolMapping {
-        keywords: &["mongodb", "mongo", "document store"],
+        technology: "mongodb",
+        keywords: &[
+            kw("mongodb", 1.0),
+            kw("mongo", 1.0),
+            kw("mongoose", 1.0),
+            kw("document store", 0.6),
+        ],
+        negative_keywords: &[],
+        dependencies: &[
+            "mongodb",
+            "mongo",
+            "mongoose",
+            "pymongo",
+            "motor",
+            "go.mongodb.org/mongo-driver",
+        ],
         tools: &["mongodb_query", "mongodb_aggregate"],
     },
     TechToolMapping {
-        keywords: &["elasticsearch", "opensearch", "full-text search"],
+        technology: "elasticsearch",
+        keywords: &[
+            kw("elasticsearch", 1.0),
+            kw("opensearch", 1.0),
+            kw("full-text search", 0.6),
+        ],
+        negative_keywords: &[],
+        dependencies: &[
+            "elasticsearch",
+            "opensearch",
+            "@elastic/elasticsearch",
+            "@opensearch-project/opensearch",
+            "github.com/elastic/go-elasticsearch",
+        ],
         tools: &["elasticsearch_search", "elasticsearch_index"],
     },
     // Storage tools
     TechToolMapping {
-        keywords: &["s3", "object storage", "file upload", "seaweedfs", "minio"],
+        technology: "s3",
+        keywords: &[
+            kw("s3", 1.0),
+            kw("seaweedfs", 1.0),
+            kw("minio", 1.0),
+            kw("object storage", 0.8),
+            kw("file upload", 0.4),
+        ],
+        negative_keywords: &[],
+        dependencies: &[
+            "aws-sdk-s3",
+            "rust-s3",
+            "@aws-sdk/client-s3",
+            "boto3",
+            "minio",
+            "seaweedfs",
+            "github.com/minio/minio-go",
+        ],
         tools: &["s3_list", "s3_get", "s3_put"],
     },
     // Messaging tools
     TechToolMapping {
-        keywords: &["kafka", "event stream", "message queue"],
+        technology: "kafka",
+        keywords: &[
+            kw("kafka", 1.0),
+            kw("redpanda", 1.0),
+            kw("event stream", 0.5),
+            kw("event streaming", 0.5),
+            kw("message queue", 0.4),
+        ],
+        negative_keywords: &[],
+        dependencies: &[
+            "kafka",
+            "cp-kafka",
+            "redpanda",
+            "rdkafka",
+            "kafkajs",
+            "confluent-kafka",
+            "github.com/segmentio/kafka-go",
+            "github.com/ibm/sarama",
+        ],
         tools: &["kafka_produce", "kafka_consume"],
     },
     TechToolMapping {
-        keywords: &["rabbitmq", "amqp", "message broker"],
+        technology: "rabbitmq",
+        keywords: &[
+            kw("rabbitmq", 1.0),
+            kw("amqp", 1.0),
+            kw("message broker", 0.4),
+        ],
+        negative_keywords: &[],
+        dependencies: &[
+            "rabbitmq",
+            "lapin",
+            "amqplib",
+            "pika",
+            "aio-pika",
+            "github.com/rabbitmq/amqp091-go",
+        ],
         tools: &["rabbitmq_publish", "rabbitmq_consume"],
     },
     TechToolMapping {
-        keywords: &["nats", "jetstream"],
+        technology: "nats",
+        keywords: &[kw("nats", 1.0), kw("jetstream", 1.0)],
+        negative_keywords: &[],
+        dependencies: &[
+            "nats",
+            "async-nats",
+            "nats-py",
+            "github.com/nats-io/nats.go",
+        ],
         tools: &["nats_publish", "nats_subscribe"],
     },
     // API tools
     TechToolMapping {
-        keywords: &["graphql", "apollo", "schema"],
+        technology: "graphql",
+        keywords: &[kw("graphql", 1.0), kw("apollo", 1.0), kw("schema", 0.3)],
+        negative_keywords: &[
+            kw("json schema", 0.3),
+            kw("database schema", 0.3),
+            kw("schema migration", 0.3),
+        ],
+        dependencies: &[
+            "graphql",
+            "@apollo/client",
+            "@apollo/server",
+            "async-graphql",
+            "juniper",
+            "strawberry-graphql",
+            "graphene",
+            "github.com/99designs/gqlgen",
+        ],
         tools: &["graphql_query", "graphql_introspect"],
     },
     TechToolMapping {
-        keywords: &["websocket", "real-time", "socket.io", "ws://"],
+        technology: "websocket",
+        keywords: &[
+            kw("websocket", 1.0),
+            kw("websockets", 1.0),
+            kw("socket.io", 1.0),
+            kw("ws://", 1.0),
+            kw("wss://", 1.0),
+            kw("real-time", 0.4),
+            kw("realtime", 0.4),
+        ],
+        negative_keywords: &[],
+        dependencies: &[
+            "ws",
+            "socket.io",
+            "socket.io-client",
+            "tungstenite",
+            "tokio-tungstenite",
+            "websockets",
+            "github.com/gorilla/websocket",
+        ],
         tools: &["websocket_connect", "websocket_send"],
     },
     TechToolMapping {
-        keywords: &["grpc", "protobuf", "proto"],
+        technology: "grpc",
+        keywords: &[
+            kw("grpc", 1.0),
+            kw("protobuf", 1.0),
+            kw("tonic", 1.0),
+            kw("proto", 0.6),
+        ],
+        negative_keywords: &[],
+        dependencies: &[
+            "tonic",
+            "prost",
+            "grpcio",
+            "@grpc/grpc-js",
+            "google.golang.org/grpc",
+        ],
         tools: &["grpc_call", "grpc_stream"],
     },
     // Infrastructure tools (for Bolt)
     TechToolMapping {
-        keywords: &["kubernetes", "k8s", "deployment", "service", "ingress"],
+        technology: "kubernetes",
+        keywords: &[
+            kw("kubernetes", 1.0),
+            kw("k8s", 1.0),
+            kw("kubectl", 1.0),
+            kw("ingress", 0.6),
+            kw("deployment", 0.3),
+            kw("service", 0.2),
+        ],
+        negative_keywords: &[],
+        dependencies: &[
+            "kube",
+            "k8s-openapi",
+            "kubernetes",
+            "@kubernetes/client-node",
+            "k8s.io/client-go",
+            "sigs.k8s.io/controller-runtime",
+        ],
         tools: &[
             "kubernetes_applyResource",
             "kubernetes_listResources",
@@ -73,20 +300,46 @@ pub const TECH_TOOL_MAPPINGS: &[TechToolMapping] = &[
         ],
     },
     TechToolMapping {
-        keywords: &["helm", "chart"],
+        technology: "helm",
+        keywords: &[kw("helm", 1.0), kw("chart", 0.3), kw("charts", 0.3)],
+        negative_keywords: &[
+            kw("bar chart", 0.3),
+            kw("line chart", 0.3),
+            kw("pie chart", 0.3),
+            kw("chart.js", 0.3),
+        ],
+        dependencies: &["helm.sh/helm"],
         tools: &["helm_install", "helm_upgrade", "helm_list"],
     },
     TechToolMapping {
-        keywords: &["cloudnative-pg", "cnpg", "postgresql operator"],
+        technology: "cloudnative-pg",
+        keywords: &[
+            kw("cloudnative-pg", 1.0),
+            kw("cnpg", 1.0),
+            kw("postgresql operator", 1.0),
+        ],
+        negative_keywords: &[],
+        dependencies: &["github.com/cloudnative-pg/cloudnative-pg"],
         tools: &["kubernetes_applyResource", "kubernetes_getPodsLogs"],
     },
     TechToolMapping {
-        keywords: &["redis operator", "redis cluster"],
+        technology: "redis-operator",
+        keywords: &[kw("redis operator", 1.0), kw("redis cluster", 1.0)],
+        negative_keywords: &[],
+        dependencies: &[],
         tools: &["kubernetes_applyResource", "kubernetes_getPodsLogs"],
     },
     // Frontend tools (for Blaze)
     TechToolMapping {
-        keywords: &["shadcn", "radix", "ui component"],
+        technology: "shadcn",
+        keywords: &[
+            kw("shadcn", 1.0),
+            kw("radix", 1.0),
+            kw("ui component", 0.5),
+            kw("ui components", 0.5),
+        ],
+        negative_keywords: &[],
+        dependencies: &["@radix-ui", "shadcn", "shadcn-ui"],
         tools: &[
             "shadcn_list_components",
             "shadcn_get_component",
@@ -95,20 +348,51 @@ pub const TECH_TOOL_MAPPINGS: &[TechToolMapping] = &[
         ],
     },
     TechToolMapping {
-        keywords: &["tanstack", "react-query", "react-table"],
+        technology: "tanstack",
+        keywords: &[
+            kw("tanstack", 1.0),
+            kw("react-query", 1.0),
+            kw("react-table", 1.0),
+        ],
+        negative_keywords: &[],
+        dependencies: &["@tanstack"],
         tools: &["context7_get_library_docs"],
     },
     TechToolMapping {
-        keywords: &["tailwind", "css", "styling"],
+        technology: "tailwind",
+        keywords: &[
+            kw("tailwind", 1.0),
+            kw("tailwindcss", 1.0),
+            kw("css", 0.6),
+            kw("styling", 0.4),
+        ],
+        negative_keywords: &[],
+        dependencies: &["tailwindcss"],
         tools: &["context7_get_library_docs"],
     },
     // Mobile tools (for Tap)
     TechToolMapping {
-        keywords: &["expo", "react-native", "mobile"],
+        technology: "react-native",
+        keywords: &[
+            kw("expo", 1.0),
+            kw("react-native", 1.0),
+            kw("react native", 1.0),
+            kw("mobile", 0.5),
+        ],
+        negative_keywords: &[kw("mobile-friendly", 0.5), kw("mobile responsive", 0.5)],
+        dependencies: &["expo", "react-native"],
         tools: &["xcodebuild_simulator_build", "xcodebuild_run_tests"],
     },
     TechToolMapping {
-        keywords: &["ios", "swift", "xcode"],
+        technology: "ios",
+        keywords: &[
+            kw("ios", 1.0),
+            kw("swift", 1.0),
+            kw("swiftui", 1.0),
+            kw("xcode", 1.0),
+        ],
+        negative_keywords: &[],
+        dependencies: &[],
         tools: &[
             "xcodebuild_simulator_build",
             "xcodebuild_device_build",
@@ -117,17 +401,38 @@ pub const TECH_TOOL_MAPPINGS: &[TechToolMapping] = &[
     },
     // Desktop tools (for Spark)
     TechToolMapping {
-        keywords: &["electron", "desktop app"],
+        technology: "electron",
+        keywords: &[kw("electron", 1.0), kw("desktop app", 1.0)],
+        negative_keywords: &[],
+        dependencies: &["electron"],
         tools: &["xcodebuild_macos_build"],
     },
     // Auth tools
     TechToolMapping {
-        keywords: &["authentication", "auth", "oauth", "jwt", "better-auth"],
+        technology: "better-auth",
+        keywords: &[
+            kw("better-auth", 1.0),
+            kw("authentication", 0.5),
+            kw("oauth", 0.5),
+            kw("jwt", 0.5),
+            kw("auth", 0.4),
+        ],
+        negative_keywords: &[],
+        dependencies: &["better-auth"],
         tools: &["better_auth_generate_schema", "better_auth_add_plugin"],
     },
     // Testing tools
     TechToolMapping {
-        keywords: &["playwright", "e2e test", "browser test"],
+        technology: "playwright",
+        keywords: &[
+            kw("playwright", 1.0),
+            kw("e2e test", 1.0),
+            kw("e2e tests", 1.0),
+            kw("browser test", 1.0),
+            kw("browser tests", 1.0),
+        ],
+        negative_keywords: &[],
+        dependencies: &["playwright", "@playwright/test"],
         tools: &[
             "browser_navigate",
             "browser_click",
@@ -136,32 +441,260 @@ pub const TECH_TOOL_MAPPINGS: &[TechToolMapping] = &[
         ],
     },
     TechToolMapping {
-        keywords: &["vitest", "jest", "unit test"],
+        technology: "js-unit-tests",
+        keywords: &[
+            kw("vitest", 1.0),
+            kw("jest", 1.0),
+            kw("unit test", 0.5),
+            kw("unit tests", 0.5),
+        ],
+        negative_keywords: &[],
+        dependencies: &["vitest", "jest"],
         tools: &["shell_execute"],
     },
     // Search tools
     TechToolMapping {
-        keywords: &["web search", "research", "documentation"],
+        technology: "web-research",
+        keywords: &[
+            kw("web search", 1.0),
+            kw("research", 0.5),
+            kw("documentation", 0.3),
+        ],
+        negative_keywords: &[],
+        dependencies: &[],
         tools: &["firecrawl_scrape", "firecrawl_search", "brave_search"],
     },
 ];
 
-/// Analyze text content and return tools needed based on technology keywords.
+/// Why a technology was (or was not) detected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Evidence {
+    /// A keyword found in task text.
+    Keyword {
+        /// Matched term.
+        term: &'static str,
+        /// Score contribution.
+        weight: f32,
+    },
+    /// A negative keyword found in task text.
+    NegativeKeyword {
+        /// Matched term.
+        term: &'static str,
+        /// Score deduction.
+        weight: f32,
+    },
+    /// A dependency declared in a repository manifest.
+    Dependency {
+        /// Manifest path.
+        file: PathBuf,
+        /// Declared dependency name.
+        name: String,
+    },
+}
+
+impl fmt::Display for Evidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keyword { term, weight } => write!(f, "keyword {term:?} (+{weight})"),
+            Self::NegativeKeyword { term, weight } => {
+                write!(f, "negative keyword {term:?} (-{weight})")
+            }
+            Self::Dependency { file, name } => {
+                write!(f, "dependency {name:?} in {}", file.display())
+            }
+        }
+    }
+}
+
+/// A detected technology with its score and evidence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TechnologyMatch {
+    /// Technology name from [`TechToolMapping::technology`].
+    pub technology: &'static str,
+    /// Combined score; at least [`DETECTION_THRESHOLD`].
+    pub score: f32,
+    /// Tools the technology contributes.
+    pub tools: &'static [&'static str],
+    /// Keywords and dependencies that produced the score.
+    pub evidence: Vec<Evidence>,
+}
+
+/// Result of tool inference: detected technologies, strongest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolInference {
+    /// Detected technologies.
+    pub matches: Vec<TechnologyMatch>,
+}
+
+impl ToolInference {
+    /// All tools contributed by the detected technologies.
+    #[must_use]
+    pub fn tools(&self) -> HashSet<String> {
+        self.matches
+            .iter()
+            .flat_map(|m| m.tools.iter().map(|tool| (*tool).to_string()))
+            .collect()
+    }
+
+    /// Technologies (with evidence) that contributed `tool`.
+    pub fn matches_for_tool<'a>(
+        &'a self,
+        tool: &'a str,
+    ) -> impl Iterator<Item = &'a TechnologyMatch> + 'a {
+        self.matches.iter().filter(move |m| m.tools.contains(&tool))
+    }
+
+    /// Whether `technology` was detected.
+    #[must_use]
+    pub fn detected(&self, technology: &str) -> bool {
+        self.matches.iter().any(|m| m.technology == technology)
+    }
+
+    /// Fold another inference in, keeping the higher score per technology
+    /// and the union of evidence.
+    pub fn merge(&mut self, other: Self) {
+        for incoming in other.matches {
+            if let Some(existing) = self
+                .matches
+                .iter_mut()
+                .find(|m| m.technology == incoming.technology)
+            {
+                existing.score = existing.score.max(incoming.score);
+                for evidence in incoming.evidence {
+                    if !existing.evidence.contains(&evidence) {
+                        existing.evidence.push(evidence);
+                    }
+                }
+            } else {
+                self.matches.push(incoming);
+            }
+        }
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        self.matches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.technology.cmp(b.technology))
+        });
+    }
+}
+
+/// Infer tools from task text and manifest dependencies.
 #[must_use]
-pub fn analyze_content_for_tools(content: &str) -> HashSet<String> {
-    let mut tools = HashSet::new();
-    let content_lower = content.to_lowercase();
+pub fn infer_tools(content: &str, dependencies: &[ManifestDependency]) -> ToolInference {
+    let text = normalize_text(content);
+    let mut inference = ToolInference::default();
 
     for mapping in TECH_TOOL_MAPPINGS {
-        let has_keyword = mapping.keywords.iter().any(|kw| content_lower.contains(kw));
-        if has_keyword {
-            for tool in mapping.tools {
-                tools.insert((*tool).to_string());
+        let mut score = 0.0;
+        let mut evidence = Vec::new();
+
+        for keyword in mapping.keywords {
+            if contains_term(&text, keyword.term) {
+                score += keyword.weight;
+                evidence.push(Evidence::Keyword {
+                    term: keyword.term,
+                    weight: keyword.weight,
+                });
+            }
+        }
+        for keyword in mapping.negative_keywords {
+            if contains_term(&text, keyword.term) {
+                score -= keyword.weight;
+                evidence.push(Evidence::NegativeKeyword {
+                    term: keyword.term,
+                    weight: keyword.weight,
+                });
+            }
+        }
+        for dependency in dependencies {
+            if mapping
+                .dependencies
+                .iter()
+                .any(|pattern| dependency_matches(&dependency.name, pattern))
+            {
+                score += DETECTION_THRESHOLD;
+                evidence.push(Evidence::Dependency {
+                    file: dependency.file.clone(),
+                    name: dependency.name.clone(),
+                });
             }
         }
+
+        if score >= DETECTION_THRESHOLD {
+            inference.matches.push(TechnologyMatch {
+                technology: mapping.technology,
+                score,
+                tools: mapping.tools,
+                evidence,
+            });
+        }
     }
 
-    tools
+    inference.sort();
+    inference
+}
+
+/// Infer tools from task text alone.
+#[must_use]
+pub fn infer_tools_from_content(content: &str) -> ToolInference {
+    infer_tools(content, &[])
+}
+
+/// Infer tools from the manifests in a repository checkout.
+#[must_use]
+pub fn infer_tools_from_repository(repo_root: &Path) -> ToolInference {
+    infer_tools("", &read_manifest_dependencies(repo_root))
+}
+
+/// Analyze text content and return tools needed based on technology keywords.
+#[must_use]
+pub fn analyze_content_for_tools(content: &str) -> HashSet<String> {
+    infer_tools_from_content(content).tools()
+}
+
+/// Lowercase and collapse whitespace so multi-word terms match across
+/// line breaks and repeated spaces.
+fn normalize_text(content: &str) -> String {
+    content
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `term` occurs in `text` on word boundaries.
+///
+/// A boundary is only required next to alphanumeric ends of the term, so
+/// `pg_` matches `pg_dump` and `socket.io` matches `socket.io-client`.
+fn contains_term(text: &str, term: &str) -> bool {
+    let check_start = term.chars().next().is_some_and(char::is_alphanumeric);
+    let check_end = term.chars().last().is_some_and(char::is_alphanumeric);
+
+    text.match_indices(term).any(|(start, _)| {
+        let end = start + term.len();
+        let before_ok = !check_start
+            || text[..start]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric());
+        let after_ok = !check_end
+            || text[end..]
+                .chars()
+                .next()
+                .is_none_or(|c| !c.is_alphanumeric());
+        before_ok && after_ok
+    })
+}
+
+/// Whether a dependency name is `pattern` or one of its sub-paths.
+fn dependency_matches(name: &str, pattern: &str) -> bool {
+    name == pattern
+        || name
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.starts_with('/'))
 }
 
 /// Task-like trait for tool analysis.
@@ -305,4 +838,88 @@ mod tests {
         assert!(blaze_tools.contains("shadcn_list_components"));
         assert!(!blaze_tools.contains("postgres_query"));
     }
+
+    #[test]
+    fn test_words_inside_other_words_do_not_match() {
+        // "sessions" / "cached" / "schemas" used to pull in Redis and GraphQL.
+        let tools = analyze_content_for_tools(
+            "Persist user sessions, return cached responses and validate request schemas",
+        );
+        assert!(!tools.contains("redis_get"));
+        assert!(!tools.contains("graphql_query"));
+
+        let inference = infer_tools_from_content("Write the protocol handler");
+        assert!(!inference.detected("grpc"));
+    }
+
+    #[test]
+    fn test_weak_keywords_need_corroboration() {
+        assert!(!infer_tools_from_content("Define the schema").detected("graphql"));
+        assert!(infer_tools_from_content("Expose the schema over GraphQL").detected("graphql"));
+        assert!(!infer_tools_from_content("Add a cache").detected("redis"));
+    }
+
+    #[test]
+    fn test_negative_keywords_lower_the_score() {
+        let inference =
+            infer_tools_from_content("Render a bar chart and a pie chart with helm-like styling");
+        assert!(!inference.detected("helm"));
+
+        let inference = infer_tools_from_content("Publish the Helm chart");
+        let helm = inference
+            .matches
+            .iter()
+            .find(|m| m.technology == "helm")
+            .unwrap();
+        assert!(helm.score >= DETECTION_THRESHOLD);
+        assert!(helm.evidence.contains(&Evidence::Keyword {
+            term: "chart",
+            weight: 0.3
+        }));
+    }
+
+    #[test]
+    fn test_prefix_and_punctuated_terms() {
+        assert!(infer_tools_from_content("Run pg_dump nightly").detected("postgres"));
+        assert!(infer_tools_from_content("Connect to ws://localhost:8080").detected("websocket"));
+        assert!(infer_tools_from_content("Use socket.io rooms").detected("websocket"));
+    }
+
+    #[test]
+    fn test_dependencies_are_evidence() {
+        let deps = vec![
+            ManifestDependency {
+                file: PathBuf::from("Cargo.toml"),
+                kind: crate::manifests::ManifestKind::Cargo,
+                name: "async-nats".to_string(),
+            },
+            ManifestDependency {
+                file: PathBuf::from("web/package.json"),
+                kind: crate::manifests::ManifestKind::PackageJson,
+                name: "@radix-ui/react-dialog".to_string(),
+            },
+        ];
+        let inference = infer_tools("Build the settings page", &deps);
+
+        assert!(inference.tools().contains("nats_publish"));
+        let shadcn: Vec<_> = inference.matches_for_tool("shadcn_get_component").collect();
+        assert_eq!(shadcn.len(), 1);
+        assert_eq!(
+            shadcn[0].evidence,
+            vec![Evidence::Dependency {
+                file: PathBuf::from("web/package.json"),
+                name: "@radix-ui/react-dialog".to_string(),
+            }]
+        );
+        assert!(!inference.detected("redis"));
+    }
+
+    #[test]
+    fn test_merge_keeps_best_score_and_all_evidence() {
+        let mut inference = infer_tools_from_content("Use Redis");
+        inference.merge(infer_tools_from_content("Redis and valkey caching"));
+        let redis = &inference.matches[0];
+        assert_eq!(redis.technology, "redis");
+        assert_eq!(redis.evidence.len(), 3);
+    }
 }
@@ -2,14 +2,20 @@
 //!
 //! Defines the structure of `cto-config.json` files used by Play workflows.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::error::ConfigError;
+use crate::migration::{migrate_value, MigrationReport};
+use crate::roster::{AgentRole, AgentRoster};
+use crate::validate::deserialize_with_path;
+
 /// CTO Config version.
 pub const CTO_CONFIG_VERSION: &str = "1.0";
 
 /// Agent tool configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
 pub struct AgentTools {
     /// Remote tools from platform tools-server.
     #[serde(default)]
@@ -26,7 +32,7 @@ pub struct AgentTools {
 /// and job-type-specific skills are merged when the agent performs that job type.
 ///
 /// This replaces the legacy `skill-mappings.yaml` file with a unified config.
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
 pub struct AgentSkills {
     /// Default skills always loaded for this agent.
     #[serde(default)]
@@ -131,7 +137,7 @@ fn default_max_concurrent() -> u8 {
 /// When enabled, the agent operates as a coordinator that can spawn
 /// parallel subagents to work on subtasks concurrently. This is only
 /// supported when `cli: "claude"`.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct SubagentConfig {
     /// Enable subagent parallel execution.
     /// When true, the agent receives coordinator instructions and can
@@ -184,7 +190,7 @@ impl SubagentConfig {
 ///
 /// `a2a` is the HTTP JSON-RPC path used by `OpenClaw` today. `acp` remains a
 /// deprecated config alias for backward compatibility when deserializing.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum AgentCommunicationMode {
     /// Native `OpenClaw` subagent hook invocation.
@@ -207,7 +213,7 @@ impl AgentCommunicationMode {
 }
 
 /// ACP runtime transport type.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum AcpTransport {
     /// ACP over stdio.
@@ -216,7 +222,7 @@ pub enum AcpTransport {
 }
 
 /// Shared ACP runtime definition.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct AcpRuntimeConfig {
     /// Whether this runtime is available for selection.
     #[serde(default = "default_true")]
@@ -267,7 +273,7 @@ impl Default for AcpRuntimeConfig {
 }
 
 /// ACP service-level configuration.
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct AcpServiceConfig {
     /// Whether ACP delegation is enabled for this service.
     #[serde(default)]
@@ -302,7 +308,7 @@ impl AcpServiceConfig {
 }
 
 /// Per-service ACP defaults across CTO services.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct AcpServicesConfig {
     /// Healer ACP client/server settings.
     #[serde(default)]
@@ -347,7 +353,7 @@ fn default_acp_bind() -> String {
 }
 
 /// Shared ACP server defaults for internal-only services.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct AcpServerConfig {
     /// Whether the ACP server surface is enabled.
     #[serde(default)]
@@ -378,7 +384,7 @@ impl Default for AcpServerConfig {
 }
 
 /// Shared ACP defaults for CTO services.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct AcpDefaults {
     /// Global ACP feature flag for the workspace.
     #[serde(default)]
@@ -438,7 +444,7 @@ fn default_circuit_breaker_threshold() -> u32 {
 /// file for the executor to self-correct.
 ///
 /// CLI-agnostic: supports any CLI (claude, codex, factory, droid, gemini, opencode, cursor).
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct WatcherDefaults {
     /// Enable watcher mode for play workflows.
     /// When true, a paired watcher `CodeRun` is created alongside the executor.
@@ -522,7 +528,7 @@ impl WatcherDefaults {
 }
 
 /// Individual agent configuration.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct AgentConfig {
     /// GitHub App name for this agent.
     #[serde(rename = "githubApp")]
@@ -567,7 +573,7 @@ pub struct AgentConfig {
 ///
 /// Agent fields are optional - when not specified, they are constructed from
 /// the top-level `orgName` and the hardcoded agent suffix (e.g., "Rex", "Blaze").
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct PlayDefaults {
     /// Override implementation agent (defaults to {orgName}-Rex).
     #[serde(
@@ -715,97 +721,116 @@ impl Default for PlayDefaults {
 }
 
 impl PlayDefaults {
-    /// Get the implementation agent name, using the org name if not overridden.
+    /// Get the agent for `role`: the explicit override if set, otherwise the
+    /// roster agent holding the role, otherwise the canonical agent.
+    #[must_use]
+    pub fn agent_for_role(&self, role: AgentRole, org_name: &str, roster: &AgentRoster) -> String {
+        let explicit = match role {
+            AgentRole::Intake => None,
+            AgentRole::Implementation => self.implementation_agent.as_ref(),
+            AgentRole::Frontend => self.frontend_agent.as_ref(),
+            AgentRole::Go => self.go_agent.as_ref(),
+            AgentRole::Node => self.node_agent.as_ref(),
+            AgentRole::Mobile => self.mobile_agent.as_ref(),
+            AgentRole::Desktop => self.desktop_agent.as_ref(),
+            AgentRole::Vr => self.vr_agent.as_ref(),
+            AgentRole::Infrastructure => self.infrastructure_agent.as_ref(),
+            AgentRole::Quality => self.quality_agent.as_ref(),
+            AgentRole::Security => self.security_agent.as_ref(),
+            AgentRole::Testing => self.testing_agent.as_ref(),
+        };
+        explicit
+            .cloned()
+            .unwrap_or_else(|| roster.github_app_for_role(role, org_name))
+    }
+
+    /// Mutable access to the override field for `role`, if it has one.
+    pub(crate) fn agent_override_mut(&mut self, role: AgentRole) -> Option<&mut Option<String>> {
+        match role {
+            AgentRole::Intake => None,
+            AgentRole::Implementation => Some(&mut self.implementation_agent),
+            AgentRole::Frontend => Some(&mut self.frontend_agent),
+            AgentRole::Go => Some(&mut self.go_agent),
+            AgentRole::Node => Some(&mut self.node_agent),
+            AgentRole::Mobile => Some(&mut self.mobile_agent),
+            AgentRole::Desktop => Some(&mut self.desktop_agent),
+            AgentRole::Vr => Some(&mut self.vr_agent),
+            AgentRole::Infrastructure => Some(&mut self.infrastructure_agent),
+            AgentRole::Quality => Some(&mut self.quality_agent),
+            AgentRole::Security => Some(&mut self.security_agent),
+            AgentRole::Testing => Some(&mut self.testing_agent),
+        }
+    }
+
+    /// Get the implementation agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_implementation_agent(&self, org_name: &str) -> String {
-        self.implementation_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_REX))
+    pub fn get_implementation_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Implementation, org_name, roster)
     }
 
-    /// Get the frontend agent name, using the org name if not overridden.
+    /// Get the frontend agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_frontend_agent(&self, org_name: &str) -> String {
-        self.frontend_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_BLAZE))
+    pub fn get_frontend_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Frontend, org_name, roster)
     }
 
-    /// Get the Go agent name, using the org name if not overridden.
+    /// Get the Go agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_go_agent(&self, org_name: &str) -> String {
-        self.go_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_GRIZZ))
+    pub fn get_go_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Go, org_name, roster)
     }
 
-    /// Get the Node agent name, using the org name if not overridden.
+    /// Get the Node agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_node_agent(&self, org_name: &str) -> String {
-        self.node_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_NOVA))
+    pub fn get_node_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Node, org_name, roster)
     }
 
-    /// Get the mobile agent name, using the org name if not overridden.
+    /// Get the mobile agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_mobile_agent(&self, org_name: &str) -> String {
-        self.mobile_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_TAP))
+    pub fn get_mobile_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Mobile, org_name, roster)
     }
 
-    /// Get the desktop agent name, using the org name if not overridden.
+    /// Get the desktop agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_desktop_agent(&self, org_name: &str) -> String {
-        self.desktop_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_SPARK))
+    pub fn get_desktop_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Desktop, org_name, roster)
     }
 
-    /// Get the VR agent name, using the org name if not overridden.
+    /// Get the VR agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_vr_agent(&self, org_name: &str) -> String {
-        self.vr_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_VEX))
+    pub fn get_vr_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Vr, org_name, roster)
     }
 
-    /// Get the infrastructure agent name, using the org name if not overridden.
+    /// Get the infrastructure agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_infrastructure_agent(&self, org_name: &str) -> String {
-        self.infrastructure_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_BOLT))
+    pub fn get_infrastructure_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Infrastructure, org_name, roster)
     }
 
-    /// Get the quality agent name, using the org name if not overridden.
+    /// Get the quality agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_quality_agent(&self, org_name: &str) -> String {
-        self.quality_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_CLEO))
+    pub fn get_quality_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Quality, org_name, roster)
     }
 
-    /// Get the security agent name, using the org name if not overridden.
+    /// Get the security agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_security_agent(&self, org_name: &str) -> String {
-        self.security_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_CIPHER))
+    pub fn get_security_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Security, org_name, roster)
     }
 
-    /// Get the testing agent name, using the org name if not overridden.
+    /// Get the testing agent name from `roster`, using the org name if not overridden.
     #[must_use]
-    pub fn get_testing_agent(&self, org_name: &str) -> String {
-        self.testing_agent
-            .clone()
-            .unwrap_or_else(|| make_agent_name(org_name, AGENT_TESS))
+    pub fn get_testing_agent(&self, org_name: &str, roster: &AgentRoster) -> String {
+        self.agent_for_role(AgentRole::Testing, org_name, roster)
     }
 }
 
 /// Simplified model configuration for intake.
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
 pub struct IntakeModels {
     /// Primary model for task generation.
     #[serde(default)]
@@ -864,7 +889,7 @@ fn default_critic() -> String {
 ///
 /// The generator refines content based on critic feedback until approved
 /// or max refinements is reached.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct MultiModelConfig {
     /// Enable multi-model collaboration.
     /// When false, uses single-model generation (default Claude).
@@ -927,7 +952,7 @@ impl MultiModelConfig {
 }
 
 /// Intake workflow defaults.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct IntakeDefaults {
     /// GitHub App for intake.
     #[serde(rename = "githubApp")]
@@ -984,7 +1009,7 @@ impl Default for IntakeDefaults {
 }
 
 /// Linear integration settings.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct LinearIntakeSettings {
     /// Whether to create a project.
     #[serde(rename = "createProject", default = "default_true")]
@@ -1013,7 +1038,7 @@ impl Default for LinearIntakeSettings {
 }
 
 /// Linear integration defaults.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct LinearDefaults {
     /// Linear team ID.
     #[serde(rename = "teamId")]
@@ -1043,7 +1068,7 @@ impl Default for LinearDefaults {
 }
 
 /// All default configurations.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
 pub struct Defaults {
     /// Intake workflow defaults.
     #[serde(default)]
@@ -1112,7 +1137,7 @@ fn default_org_name() -> String {
 }
 
 /// Complete CTO Config structure.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct CtoConfig {
     /// Config version.
     pub version: String,
@@ -1170,6 +1195,22 @@ impl CtoConfig {
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
     }
+
+    /// Parse a CTO config, upgrading older config versions first.
+    ///
+    /// Unlike [`CtoConfig::from_json`], schema errors name the offending
+    /// field path and the returned report lists every value that was moved,
+    /// defaulted or dropped by the migration chain.
+    ///
+    /// # Errors
+    /// Returns an error if the JSON is malformed, the version is unsupported,
+    /// or the migrated document does not match the schema.
+    pub fn from_json_migrated(json: &str) -> Result<(Self, MigrationReport), ConfigError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let (value, report) = migrate_value(value)?;
+        let config = deserialize_with_path(value)?;
+        Ok((config, report))
+    }
 }
 
 #[cfg(test)]
@@ -1194,19 +1235,29 @@ mod tests {
     #[test]
     fn test_play_defaults() {
         let defaults = PlayDefaults::default();
+        let roster = AgentRoster::built_in();
         // Agent fields are None by default, resolved via org_name
         assert!(defaults.implementation_agent.is_none());
         assert!(defaults.frontend_agent.is_none());
         assert!(defaults.quality_agent.is_none());
 
         // Test the getter methods with org name
-        assert_eq!(defaults.get_implementation_agent("5DLabs"), "5DLabs-Rex");
-        assert_eq!(defaults.get_frontend_agent("5DLabs"), "5DLabs-Blaze");
-        assert_eq!(defaults.get_quality_agent("5DLabs"), "5DLabs-Cleo");
+        assert_eq!(
+            defaults.get_implementation_agent("5DLabs", roster),
+            "5DLabs-Rex"
+        );
+        assert_eq!(
+            defaults.get_frontend_agent("5DLabs", roster),
+            "5DLabs-Blaze"
+        );
+        assert_eq!(defaults.get_quality_agent("5DLabs", roster), "5DLabs-Cleo");
 
         // Test with custom org name
-        assert_eq!(defaults.get_implementation_agent("Acme"), "Acme-Rex");
-        assert_eq!(defaults.get_frontend_agent("Acme"), "Acme-Blaze");
+        assert_eq!(
+            defaults.get_implementation_agent("Acme", roster),
+            "Acme-Rex"
+        );
+        assert_eq!(defaults.get_frontend_agent("Acme", roster), "Acme-Blaze");
 
         // Test fresh_start_threshold default
         assert_eq!(defaults.fresh_start_threshold, 3);
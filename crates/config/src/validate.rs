@@ -0,0 +1,515 @@
+//! Validation for `cto-config.json` documents.
+//!
+//! [`validate_json`] runs the full pipeline a service goes through when it
+//! loads a config — parse, migrate, deserialize — and then layers structural
+//! and semantic checks on top. Every finding carries a dotted path such as
+//! `defaults.acp.services.healer.defaultRuntime` so it can be fixed without
+//! guessing which section serde was unhappy about.
+
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::agents::all_agent_names;
+use crate::error::ConfigError;
+use crate::migration::{migrate_value, MigrationReport};
+use crate::schema::{cto_config_schema, object_schemas};
+use crate::types::{AcpDefaults, AcpServiceConfig, CtoConfig};
+
+/// CLIs the controller knows how to launch.
+pub const KNOWN_CLIS: &[&str] = &[
+    "claude", "codex", "cursor", "droid", "factory", "gemini", "opencode",
+];
+
+/// How serious a validation finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The config loads but something is probably wrong.
+    Warning,
+    /// The config cannot be used as-is.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => f.write_str("warning"),
+            Self::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// A single path-qualified validation finding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Severity of the finding.
+    pub severity: Severity,
+    /// Dotted path to the offending value (`$` for the document root).
+    pub path: String,
+    /// Human-readable explanation.
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.path, self.message)
+    }
+}
+
+impl From<ConfigError> for ValidationIssue {
+    fn from(error: ConfigError) -> Self {
+        match error {
+            ConfigError::Schema { path, message } => Self::error(path, message),
+            ConfigError::UnsupportedVersion { .. } => Self::error("version", error.to_string()),
+            other => Self::error("$", other.to_string()),
+        }
+    }
+}
+
+/// Outcome of validating a config document.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    /// The parsed config, when it could be deserialized.
+    pub config: Option<CtoConfig>,
+    /// Migration applied before deserializing, if parsing got that far.
+    pub migration: Option<MigrationReport>,
+    /// All findings, errors first.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Findings with [`Severity::Error`].
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// Findings with [`Severity::Warning`].
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    /// Whether the config is usable (no errors).
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.config.is_some() && self.errors().next().is_none()
+    }
+}
+
+/// Validate a `cto-config.json` document.
+///
+/// Parses and migrates the document, reports keys the schema does not know
+/// about (they would otherwise be dropped silently), deserializes it with
+/// path-qualified errors and finally runs [`validate_config`].
+#[must_use]
+pub fn validate_json(json: &str) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let value: Value = match serde_json::from_str(json) {
+        Ok(value) => value,
+        Err(err) => {
+            report.issues.push(ConfigError::from(err).into());
+            return report;
+        }
+    };
+
+    let (value, migration) = match migrate_value(value) {
+        Ok(migrated) => migrated,
+        Err(err) => {
+            report.issues.push(err.into());
+            return report;
+        }
+    };
+    report.migration = Some(migration);
+
+    let schema = cto_config_schema();
+    collect_unknown_fields(&schema, &schema, &value, "", &mut report.issues);
+
+    match deserialize_with_path(value) {
+        Ok(config) => {
+            report.issues.extend(validate_config(&config));
+            report.config = Some(config);
+        }
+        Err(err) => report.issues.push(err.into()),
+    }
+
+    report
+        .issues
+        .sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    report
+}
+
+/// Deserialize a migrated document, reporting the failing field path.
+pub(crate) fn deserialize_with_path(value: Value) -> Result<CtoConfig, ConfigError> {
+    serde_path_to_error::deserialize(value).map_err(|err| {
+        let path = err.path().to_string();
+        ConfigError::Schema {
+            path: if path == "." { "$".to_string() } else { path },
+            message: err.into_inner().to_string(),
+        }
+    })
+}
+
+/// Semantic checks on an already-parsed config.
+#[must_use]
+pub fn validate_config(config: &CtoConfig) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let known_agents = all_agent_names();
+
+    let mut agent_names: Vec<&String> = config.agents.keys().collect();
+    agent_names.sort();
+    for name in agent_names {
+        let agent = &config.agents[name];
+        let path = format!("agents.{name}");
+        if !known_agents.contains(&name.to_lowercase().as_str()) {
+            issues.push(ValidationIssue::warning(
+                &path,
+                format!(
+                    "unknown agent {name:?}; known agents: {}",
+                    known_agents.join(", ")
+                ),
+            ));
+        }
+        if !KNOWN_CLIS.contains(&agent.cli.as_str()) {
+            issues.push(ValidationIssue::warning(
+                format!("{path}.cli"),
+                format!(
+                    "unknown CLI {:?}; expected one of {}",
+                    agent.cli,
+                    KNOWN_CLIS.join(", ")
+                ),
+            ));
+        }
+        if agent.model.trim().is_empty() {
+            issues.push(ValidationIssue::error(
+                format!("{path}.model"),
+                "model must not be empty",
+            ));
+        }
+        if agent.github_app.trim().is_empty() {
+            issues.push(ValidationIssue::error(
+                format!("{path}.githubApp"),
+                "githubApp must not be empty",
+            ));
+        }
+    }
+
+    let play = &config.defaults.play;
+    if !config.agents.is_empty() {
+        let overrides = [
+            ("implementationAgent", &play.implementation_agent),
+            ("frontendAgent", &play.frontend_agent),
+            ("goAgent", &play.go_agent),
+            ("nodeAgent", &play.node_agent),
+            ("mobileAgent", &play.mobile_agent),
+            ("desktopAgent", &play.desktop_agent),
+            ("vrAgent", &play.vr_agent),
+            ("infrastructureAgent", &play.infrastructure_agent),
+            ("qualityAgent", &play.quality_agent),
+            ("securityAgent", &play.security_agent),
+            ("testingAgent", &play.testing_agent),
+        ];
+        for (field, value) in overrides {
+            let Some(app) = value else { continue };
+            if !config.agents.values().any(|agent| &agent.github_app == app) {
+                issues.push(ValidationIssue::warning(
+                    format!("defaults.play.{field}"),
+                    format!("{app:?} does not match the githubApp of any configured agent"),
+                ));
+            }
+        }
+    }
+    if play.watcher.enabled && play.watcher.check_interval_secs == 0 {
+        issues.push(ValidationIssue::error(
+            "defaults.play.watcher.checkIntervalSecs",
+            "must be greater than zero when the watcher is enabled",
+        ));
+    }
+
+    let threshold = config.defaults.intake.multi_model.critic_threshold;
+    if !(0.0..=1.0).contains(&threshold) {
+        issues.push(ValidationIssue::error(
+            "defaults.intake.multiModel.criticThreshold",
+            format!("{threshold} is outside 0.0..=1.0"),
+        ));
+    }
+
+    validate_acp(&config.defaults.acp, &mut issues);
+    issues
+}
+
+fn validate_acp(acp: &AcpDefaults, issues: &mut Vec<ValidationIssue>) {
+    // Missing runtimes only break things once ACP is switched on.
+    let missing = |path: String, message: String| {
+        if acp.enabled {
+            ValidationIssue::error(path, message)
+        } else {
+            ValidationIssue::warning(path, message)
+        }
+    };
+
+    if let Some(runtime) = &acp.default_runtime {
+        if !acp.runtimes.contains_key(runtime) {
+            issues.push(missing(
+                "defaults.acp.defaultRuntime".to_string(),
+                format!("runtime {runtime:?} is not defined under defaults.acp.runtimes"),
+            ));
+        }
+    }
+
+    let mut runtime_ids: Vec<&String> = acp.runtimes.keys().collect();
+    runtime_ids.sort();
+    for id in runtime_ids {
+        if acp.runtimes[id].command.trim().is_empty() {
+            issues.push(ValidationIssue::error(
+                format!("defaults.acp.runtimes.{id}.command"),
+                "command must not be empty",
+            ));
+        }
+    }
+
+    let services: [(&str, &AcpServiceConfig); 5] = [
+        ("healer", &acp.services.healer),
+        ("pm", &acp.services.pm),
+        ("controller", &acp.services.controller),
+        ("mcp", &acp.services.mcp),
+        ("mcpLite", &acp.services.mcp_lite),
+    ];
+    for (name, service) in services {
+        let path = format!("defaults.acp.services.{name}");
+        for (index, runtime) in service.runtime_ids.iter().enumerate() {
+            if !acp.runtimes.contains_key(runtime) {
+                issues.push(ValidationIssue::warning(
+                    format!("{path}.runtimeIds[{index}]"),
+                    format!("runtime {runtime:?} is not defined under defaults.acp.runtimes"),
+                ));
+            }
+        }
+
+        let selected = service
+            .default_runtime
+            .as_ref()
+            .or(acp.default_runtime.as_ref());
+        if let Some(runtime) = &service.default_runtime {
+            if !acp.runtimes.contains_key(runtime) {
+                issues.push(missing(
+                    format!("{path}.defaultRuntime"),
+                    format!("runtime {runtime:?} is not defined under defaults.acp.runtimes"),
+                ));
+            } else if !service.runtime_ids.is_empty() && !service.runtime_ids.contains(runtime) {
+                issues.push(ValidationIssue::warning(
+                    format!("{path}.defaultRuntime"),
+                    format!("runtime {runtime:?} is not listed in runtimeIds"),
+                ));
+            }
+        }
+
+        if acp.enabled && service.enabled {
+            let usable = selected
+                .and_then(|runtime| acp.runtimes.get(runtime))
+                .is_some_and(|runtime| runtime.enabled);
+            if !usable {
+                issues.push(ValidationIssue::error(
+                    format!("{path}.enabled"),
+                    "service is enabled but has no enabled ACP runtime to select",
+                ));
+            }
+        }
+    }
+}
+
+/// Walk `value` alongside its schema and flag keys the schema does not
+/// declare. Keys starting with `_` are treated as comments.
+fn collect_unknown_fields(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    match value {
+        Value::Object(map) => {
+            let candidates = object_schemas(root, schema);
+            if candidates.is_empty() {
+                return;
+            }
+            for (key, child) in map {
+                if key.starts_with('_') {
+                    continue;
+                }
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+
+                let declared = candidates
+                    .iter()
+                    .find_map(|candidate| candidate.get("properties")?.get(key));
+                let additional = candidates.iter().find_map(|candidate| {
+                    candidate
+                        .get("additionalProperties")
+                        .filter(|schema| schema.is_object())
+                });
+                let open = candidates.iter().any(|candidate| {
+                    candidate.get("properties").is_none()
+                        && candidate.get("additionalProperties").is_none()
+                        || candidate.get("additionalProperties") == Some(&Value::Bool(true))
+                });
+
+                if let Some(child_schema) = declared.or(additional) {
+                    collect_unknown_fields(root, child_schema, child, &child_path, issues);
+                } else if !open {
+                    issues.push(ValidationIssue::warning(
+                        child_path,
+                        "unknown field; it will be ignored",
+                    ));
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    let child_path = format!("{path}[{index}]");
+                    collect_unknown_fields(root, item_schema, item, &child_path, issues);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{generate_project_config, ProjectConfigInput};
+
+    fn paths(report: &ValidationReport, severity: Severity) -> Vec<String> {
+        report
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .map(|issue| issue.path.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_generated_config_is_valid() {
+        let config = generate_project_config(&ProjectConfigInput {
+            team_id: "team-123".to_string(),
+            ..Default::default()
+        });
+        let report = validate_json(&config.to_json().unwrap());
+        assert!(report.is_valid(), "{:?}", report.issues);
+        assert!(report.warnings().next().is_none(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_syntax_error_reports_position() {
+        let report = validate_json("{\"version\": \"1.0\",}");
+        assert!(!report.is_valid());
+        assert_eq!(report.issues[0].path, "$");
+        assert!(report.issues[0].message.contains("line 1"));
+    }
+
+    #[test]
+    fn test_type_error_is_path_qualified() {
+        let report = validate_json(
+            r#"{"version":"1.0","defaults":{"play":{"freshStartThreshold":"three"}},"agents":{}}"#,
+        );
+        assert!(!report.is_valid());
+        assert_eq!(
+            paths(&report, Severity::Error),
+            vec!["defaults.play.freshStartThreshold".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_and_agents_are_warned() {
+        let report = validate_json(
+            r#"{
+                "version": "1.0",
+                "_comment": "ignored",
+                "defaults": {"play": {"repo": "typo"}},
+                "agents": {
+                    "zed": {"githubApp": "5DLabs-Zed", "cli": "emacs", "model": "m"}
+                }
+            }"#,
+        );
+        assert!(report.is_valid());
+        let warnings = paths(&report, Severity::Warning);
+        assert!(warnings.contains(&"defaults.play.repo".to_string()));
+        assert!(warnings.contains(&"agents.zed".to_string()));
+        assert!(warnings.contains(&"agents.zed.cli".to_string()));
+        assert!(!warnings.iter().any(|path| path.contains("_comment")));
+    }
+
+    #[test]
+    fn test_missing_acp_runtime_is_error_when_enabled() {
+        let report = validate_json(
+            r#"{
+                "version": "1.0",
+                "defaults": {"acp": {
+                    "enabled": true,
+                    "defaultRuntime": "stakpak",
+                    "runtimes": {},
+                    "services": {"healer": {"enabled": true, "runtimeIds": ["stakpak"]}}
+                }},
+                "agents": {}
+            }"#,
+        );
+        assert!(!report.is_valid());
+        let errors = paths(&report, Severity::Error);
+        assert!(errors.contains(&"defaults.acp.defaultRuntime".to_string()));
+        assert!(errors.contains(&"defaults.acp.services.healer.enabled".to_string()));
+        assert!(paths(&report, Severity::Warning)
+            .contains(&"defaults.acp.services.healer.runtimeIds[0]".to_string()));
+    }
+
+    #[test]
+    fn test_legacy_config_reports_migration() {
+        let report =
+            validate_json(r#"{"defaults": {"code": {"repository": "acme/app"}}, "agents": {}}"#);
+        assert!(report.is_valid(), "{:?}", report.issues);
+        let migration = report.migration.unwrap();
+        assert!(migration.migrated());
+        assert_eq!(report.config.unwrap().defaults.play.repository, "acme/app");
+    }
+
+    #[test]
+    fn test_repo_config_files_validate() {
+        let root = concat!(env!("CARGO_MANIFEST_DIR"), "/../../");
+        for file in ["cto-config.json", "cto-config.template.json"] {
+            let Ok(json) = std::fs::read_to_string(format!("{root}{file}")) else {
+                continue;
+            };
+            let report = validate_json(&json);
+            assert!(
+                report.errors().next().is_none(),
+                "{file}: {:?}",
+                report.errors().collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -38,6 +38,7 @@ use controller::tasks::{
     label::{override_detector::OverrideDetector, schema::WorkflowState, LabelOrchestrator},
     run_task_controller,
 };
+use cto_cost::tracking::{CostTracker, TrackedCall};
 use serde_json::{json, Value};
 use std::path::Path;
 use std::sync::Arc;
@@ -58,8 +59,19 @@ struct AppState {
     client: kube::Client,
     namespace: String,
     config: Arc<ControllerConfig>,
+    /// Cost ledger that agent sidecars report token usage into
+    ledger: Option<CostTracker>,
+    /// Bearer token sidecars must send with usage reports
+    usage_token: Option<Arc<str>>,
 }
 
+/// Environment variable holding the usage endpoint's bearer token.
+const USAGE_TOKEN_ENV: &str = "CTO_COST_LEDGER_TOKEN";
+
+/// Most calls accepted per usage report (the sidecar queues no more than
+/// this while the ledger is unreachable).
+const MAX_USAGE_BATCH: usize = 1000;
+
 /// Default path for agent templates (embedded in Docker image)
 const DEFAULT_AGENT_TEMPLATES_PATH: &str = "/app/templates";
 
@@ -231,10 +243,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     verify_templates_directory()?;
     info!("✅ Agent templates verified");
 
+    let ledger = open_usage_ledger(&controller_config);
+    let usage_token = std::env::var(USAGE_TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+        .map(Arc::from);
+    if ledger.is_some() && usage_token.is_none() {
+        error!(
+            "{} is not set; token usage reports will be rejected",
+            USAGE_TOKEN_ENV
+        );
+    }
+
     let state = AppState {
         client: client.clone(),
         namespace: namespace.clone(),
         config: controller_config.clone(),
+        ledger,
+        usage_token,
     };
 
     // Start the controller in the background
@@ -255,6 +281,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/ready", get(readiness_check))
         .route("/metrics", get(metrics))
         .route("/webhook", post(webhook_handler))
+        .route("/api/usage", post(usage_handler))
         .layer(
             ServiceBuilder::new()
                 .layer(
@@ -335,6 +362,78 @@ fn load_controller_config() -> ControllerConfig {
     }
 }
 
+/// Open the cost ledger that budgets are enforced against, for usage ingest.
+fn open_usage_ledger(config: &ControllerConfig) -> Option<CostTracker> {
+    let path = config.budgets.ledger_path.as_deref()?;
+    match CostTracker::open(path) {
+        Ok(ledger) => {
+            info!("Accepting token usage into cost ledger {}", path);
+            Some(ledger)
+        }
+        Err(e) => {
+            error!(
+                "Token usage will not be recorded, cannot open {}: {}",
+                path, e
+            );
+            None
+        }
+    }
+}
+
+/// Record token usage posted by agent sidecars into the cost ledger.
+///
+/// Budgets hold CodeRuns on these numbers, so reports must carry the shared
+/// bearer token. Calls whose ID is already in the ledger are ignored, so
+/// sidecars can resend after a failure.
+async fn usage_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(calls): Json<Vec<TrackedCall>>,
+) -> Result<Json<Value>, StatusCode> {
+    let (Some(ledger), Some(token)) = (state.ledger.clone(), state.usage_token.as_deref()) else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    let presented = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !presented.is_some_and(|presented| tokens_match(presented, token)) {
+        warn!("Rejected token usage report without a valid token");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    if calls.len() > MAX_USAGE_BATCH {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    // SQLite writes block, so they stay off the async workers
+    let recorded = calls.len();
+    tokio::task::spawn_blocking(move || {
+        for call in &calls {
+            ledger.try_record(call).map_err(|e| {
+                error!("Failed to record usage {}: {}", call.id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        }
+        Ok::<(), StatusCode>(())
+    })
+    .await
+    .map_err(|e| {
+        error!("Usage recording task failed: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })??;
+    Ok(Json(json!({ "recorded": recorded })))
+}
+
+/// Compare tokens in time independent of where they differ.
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
 #[allow(clippy::too_many_lines)] // Complex function not easily split
 async fn webhook_handler(
     State(_state): State<AppState>,
@@ -1,11 +1,12 @@
 use super::naming::ResourceNaming;
-use super::resources::CodeResourceManager;
+use super::resources::{CodeResourceManager, EffectiveProviderConfig};
 use super::watcher::{cleanup_watcher, is_watcher_coderun, spawn_watcher_if_enabled};
 use crate::crds::CodeRun;
 use crate::tasks::cleanup;
 use crate::tasks::tool_inventory::log_tool_inventory;
 use crate::tasks::types::{Context, Result, CODE_FINALIZER_NAME};
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use cto_cost::tracking::TrackingContext;
 use k8s_openapi::api::{
     batch::v1::Job,
     core::v1::{ConfigMap, PersistentVolumeClaim},
@@ -17,7 +18,13 @@ use kube::{Api, Error as KubeError, ResourceExt};
 use notify::NotifyEvent;
 use serde_json::json;
 use std::sync::Arc;
-use tracing::{debug, info, instrument, warn};
+use tracing::{debug, error, info, instrument, warn};
+
+/// Upper bound on how long a budget-blocked CodeRun waits before re-checking.
+const BUDGET_RECHECK_SECS: u64 = 600;
+
+/// How long a CodeRun waits before re-checking a budget ledger that could not be read.
+const BUDGET_LEDGER_RETRY_SECS: u64 = 60;
 
 enum ExpireAtUpdate {
     Unchanged,
@@ -312,6 +319,12 @@ async fn reconcile_code_create_or_update(code_run: Arc<CodeRun>, ctx: &Context)
                 }
             }
 
+            // Hold new jobs while a covering budget is exhausted; the CodeRun
+            // stays Pending and is admitted once the budget window rolls over.
+            if let Some(requeue) = check_budget_gate(&code_run, ctx).await? {
+                return Ok(requeue);
+            }
+
             // STEP 3: Optimistic job creation with conflict handling (copied from working docs controller)
             let ctx_arc = Arc::new(ctx.clone());
             let resource_manager =
@@ -897,6 +910,88 @@ fn determine_code_job_state(status: &k8s_openapi::api::batch::v1::JobStatus) ->
     CodeJobState::Running
 }
 
+/// Block job creation when a budget covering this CodeRun is exhausted.
+///
+/// Returns the requeue action to use while blocked, or `None` when the run
+/// may proceed (including when budgets are not configured). Budgets fail
+/// closed: a ledger that cannot be read blocks the run until it can.
+async fn check_budget_gate(code_run: &CodeRun, ctx: &Context) -> Result<Option<Action>> {
+    let Some(monitor) = &ctx.budgets else {
+        return Ok(None);
+    };
+
+    let mut tracking = TrackingContext::new().with_project(code_run.spec.service.clone());
+    if let Some(agent) = &code_run.spec.github_app {
+        tracking = tracking.with_agent(agent.clone());
+    }
+    let provider = EffectiveProviderConfig::resolve(code_run, &ctx.config)
+        .provider
+        .to_string();
+
+    let now = Utc::now();
+    let monitor = Arc::clone(monitor);
+    let exhausted = tokio::task::spawn_blocking(move || {
+        monitor
+            .exhausted_for(&tracking, Some(&provider), now)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("budget check panicked: {e}")));
+
+    let status = match exhausted {
+        Ok(Some(status)) => status,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            let message = format!("Blocked: budget ledger unavailable: {e}");
+            error!(code_run = %code_run.name_any(), "{}", message);
+            update_code_status_with_completion(
+                code_run,
+                ctx,
+                "Pending",
+                &message,
+                false,
+                None,
+                None,
+                ExpireAtUpdate::Unchanged,
+            )
+            .await?;
+            return Ok(Some(Action::requeue(std::time::Duration::from_secs(
+                BUDGET_LEDGER_RETRY_SECS,
+            ))));
+        }
+    };
+
+    let message = format!(
+        "Blocked: budget {} exhausted (${:.2} of ${:.2} {}) until {}",
+        status.budget.name,
+        status.spent_usd,
+        status.budget.hard_limit_usd(),
+        status.budget.period,
+        status.window_end.to_rfc3339()
+    );
+    warn!(code_run = %code_run.name_any(), "{}", message);
+    update_code_status_with_completion(
+        code_run,
+        ctx,
+        "Pending",
+        &message,
+        false,
+        None,
+        None,
+        ExpireAtUpdate::Unchanged,
+    )
+    .await?;
+
+    let wait = (status.window_end - now)
+        .to_std()
+        .unwrap_or_default()
+        .clamp(
+            std::time::Duration::from_secs(30),
+            std::time::Duration::from_secs(BUDGET_RECHECK_SECS),
+        );
+    Ok(Some(Action::requeue(wait)))
+}
+
 #[allow(clippy::too_many_arguments)] // Status update requires all completion fields
 async fn update_code_status_with_completion(
     code_run: &CodeRun,
@@ -9,6 +9,7 @@ use crate::tasks::cleanup::{
 };
 use crate::tasks::config::{ControllerConfig, MorganSidecarConfig, PresenceConfig, ResolvedSecretBinding};
 use crate::tasks::types::{github_app_secret_name, Context, Error, Result};
+use cto_config::{is_unset, ConfigLayer, ConfigResolver, EffectiveConfig};
 use k8s_openapi::api::{
     batch::v1::Job,
     core::v1::{ConfigMap, PersistentVolumeClaim, Pod},
@@ -20,7 +21,7 @@ use kube::ResourceExt;
 use serde_json::json;
 use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 // ─── Shared code-server config ────────────────────────────────────────────
 //
@@ -33,6 +34,10 @@ const CODE_SERVER_SETTINGS_JSON: &str =
 const CODE_SERVER_STORAGE_JSON: &str =
     include_str!("../../../../../shared/code-server-config/storage.json");
 
+/// Provenance labels for the CLI config layers merged per `CodeRun`.
+const AGENT_CLI_ORIGIN: &str = "controller agents config";
+const CODERUN_CLI_ORIGIN: &str = "CodeRun spec.cliConfig";
+
 // ─── Effective Provider Resolution ────────────────────────────────────────
 //
 // Single source of truth for how a CodeRun resolves its provider, base URL,
@@ -2198,6 +2203,56 @@ impl<'a> CodeResourceManager<'a> {
                     }
                 }));
 
+                // Run context for recording CLI token usage into the cost ledger
+                sidecar_env.extend([
+                    json!({ "name": "CODERUN_NAME", "value": code_run.name_any() }),
+                    json!({ "name": "CTO_SERVICE", "value": code_run.spec.service }),
+                    json!({
+                        "name": "TASK_ID",
+                        "value": code_run.spec.task_id.map_or(String::new(), |id| id.to_string())
+                    }),
+                    json!({
+                        "name": "CTO_GITHUB_APP",
+                        "value": code_run.spec.github_app.clone().unwrap_or_default()
+                    }),
+                    json!({
+                        "name": "CTO_ATTEMPT_NUMBER",
+                        "value": code_run
+                            .status
+                            .as_ref()
+                            .and_then(|s| s.retry_count)
+                            .map_or(1, |retries| retries + 1)
+                            .to_string()
+                    }),
+                    json!({ "name": "CLI_TYPE", "value": cli_type_str }),
+                    json!({
+                        "name": "RESOLVED_PROVIDER",
+                        "value": provider_config.provider.to_string()
+                    }),
+                ]);
+                let budgets = &self.config.budgets;
+                if let Some(usage_endpoint) = &budgets.usage_endpoint {
+                    sidecar_env.push(json!({
+                        "name": "CTO_COST_LEDGER_URL",
+                        "value": usage_endpoint
+                    }));
+                    if let (Some(secret_name), Some(secret_key)) = (
+                        budgets.usage_token_secret_name.as_deref(),
+                        budgets.usage_token_secret_key.as_deref(),
+                    ) {
+                        sidecar_env.push(json!({
+                            "name": "CTO_COST_LEDGER_TOKEN",
+                            "valueFrom": {
+                                "secretKeyRef": {
+                                    "name": secret_name,
+                                    "key": secret_key,
+                                    "optional": false
+                                }
+                            }
+                        }));
+                    }
+                }
+
                 let sidecar_image = self.config.linear.sidecar_image.clone().unwrap_or_else(|| {
                     "registry.5dlabs.ai/5dlabs/linear-sidecar:latest".to_string()
                 });
@@ -3640,7 +3695,12 @@ done"#
         let mut new_code_run = (**code_run).clone();
 
         if let Some(existing) = new_code_run.spec.cli_config.as_mut() {
-            Self::merge_cli_config(existing, agent_cli_config);
+            let effective = Self::merge_cli_config(existing, agent_cli_config);
+            debug!(
+                "Effective CLI config for {} ({github_app}):\n{}",
+                code_run.name_any(),
+                effective.explain()
+            );
             self.apply_cli_provider(existing);
         } else {
             info!(
@@ -3656,44 +3716,79 @@ done"#
         Arc::new(new_code_run)
     }
 
-    fn merge_cli_config(existing: &mut CLIConfig, defaults: &CLIConfig) {
-        if existing.model.trim().is_empty() {
-            existing.model.clone_from(&defaults.model);
-        }
-
-        if existing.max_tokens.is_none() {
-            existing.max_tokens = defaults.max_tokens;
-        }
-
-        if existing.temperature.is_none() {
-            existing.temperature = defaults.temperature;
-        }
-
-        if existing.model_rotation.is_none() {
-            existing.model_rotation.clone_from(&defaults.model_rotation);
-        }
+    /// Layer the `CodeRun`'s CLI config over the agent's configured defaults.
+    ///
+    /// Values set on the `CodeRun` win; `settings` are merged per key. Fields
+    /// are merged on the typed config with the resolver's notion of "unset",
+    /// so every value survives whatever its JSON shape. The returned
+    /// [`EffectiveConfig`] records which layer supplied each value.
+    fn merge_cli_config(existing: &mut CLIConfig, defaults: &CLIConfig) -> EffectiveConfig {
+        let mut resolver = ConfigResolver::new();
+        Self::record_cli_config(
+            &mut resolver,
+            ConfigLayer::Agent,
+            AGENT_CLI_ORIGIN,
+            defaults,
+        );
+        Self::record_cli_config(
+            &mut resolver,
+            ConfigLayer::Override,
+            CODERUN_CLI_ORIGIN,
+            existing,
+        );
+        let effective = resolver.resolve();
 
-        if existing.provider.is_none() {
-            existing.provider = defaults.provider;
+        fn layered<T: Clone + serde::Serialize>(value: &T, default: &T) -> T {
+            let set = serde_json::to_value(value).is_ok_and(|value| !is_unset(&value));
+            if set { value } else { default }.clone()
         }
-
-        if existing.provider_base_url.is_none() {
-            existing
-                .provider_base_url
-                .clone_from(&defaults.provider_base_url);
-        }
-
-        if existing.api_key_env_var.is_none() {
-            existing
-                .api_key_env_var
-                .clone_from(&defaults.api_key_env_var);
-        }
-
-        for (key, value) in &defaults.settings {
+        let mut settings = defaults.settings.clone();
+        settings.retain(|_, value| !is_unset(value));
+        settings.extend(
             existing
                 .settings
-                .entry(key.clone())
-                .or_insert_with(|| value.clone());
+                .drain()
+                .filter(|(_, value)| !is_unset(value)),
+        );
+        *existing = CLIConfig {
+            cli_type: existing.cli_type,
+            model: layered(&existing.model, &defaults.model),
+            provider: layered(&existing.provider, &defaults.provider),
+            provider_base_url: layered(&existing.provider_base_url, &defaults.provider_base_url),
+            api_key_env_var: layered(&existing.api_key_env_var, &defaults.api_key_env_var),
+            settings,
+            max_tokens: layered(&existing.max_tokens, &defaults.max_tokens),
+            temperature: layered(&existing.temperature, &defaults.temperature),
+            model_rotation: layered(&existing.model_rotation, &defaults.model_rotation),
+        };
+        effective
+    }
+
+    /// Record the fields of a CLI config as resolver candidates.
+    ///
+    /// `settings` entries are recorded whole, so a `CodeRun` setting replaces
+    /// the agent's value for that key rather than being merged into it.
+    fn record_cli_config(
+        resolver: &mut ConfigResolver,
+        layer: ConfigLayer,
+        origin: &str,
+        config: &CLIConfig,
+    ) {
+        let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(config) else {
+            return;
+        };
+        for (key, value) in fields {
+            match value {
+                serde_json::Value::Object(settings) if key == "settings" => {
+                    for (name, value) in settings {
+                        let key = format!("settings.{name}");
+                        resolver.set(layer, origin, &key, &key, value);
+                    }
+                }
+                value => {
+                    resolver.set(layer, origin, &key, &key, value);
+                }
+            }
         }
     }
 
@@ -4639,6 +4734,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn merge_cli_config_keeps_values_that_do_not_round_trip() {
+        // Dotted setting names would nest when rebuilt from resolved keys,
+        // and a blank model is unset on both sides
+        let mut existing_settings = HashMap::new();
+        existing_settings.insert("sandbox.mode".to_string(), json!("workspace-write"));
+        existing_settings.insert("approvalPolicy".to_string(), json!(" "));
+        let mut existing = CLIConfig {
+            model: "  ".to_string(),
+            ..cli_config_with_settings(existing_settings)
+        };
+
+        let mut defaults_settings = HashMap::new();
+        defaults_settings.insert("approvalPolicy".to_string(), json!("never"));
+        let defaults = CLIConfig {
+            max_tokens: Some(16_000),
+            ..cli_config_with_settings(defaults_settings)
+        };
+
+        CodeResourceManager::merge_cli_config(&mut existing, &defaults);
+
+        assert_eq!(existing.max_tokens, Some(16_000));
+        assert_eq!(
+            existing.settings.get("sandbox.mode"),
+            Some(&json!("workspace-write"))
+        );
+        assert_eq!(
+            existing.settings.get("approvalPolicy"),
+            Some(&json!("never"))
+        );
+        assert!(!existing.settings.contains_key("sandbox"));
+
+        // A whitespace-only model falls back to the agent's
+        let mut existing = CLIConfig {
+            model: "  ".to_string(),
+            ..cli_config_with_settings(HashMap::new())
+        };
+        let defaults = CLIConfig {
+            model: "gpt-5-codex".to_string(),
+            ..cli_config_with_settings(HashMap::new())
+        };
+        let effective = CodeResourceManager::merge_cli_config(&mut existing, &defaults);
+        assert_eq!(existing.model, "gpt-5-codex");
+        assert_eq!(effective.str("model"), Some("gpt-5-codex"));
+    }
+
     #[test]
     fn merge_cli_config_handles_model_rotation() {
         // Test that model_rotation is merged when None
@@ -56,6 +56,61 @@ pub struct ControllerConfig {
     /// Tools sidecar configuration (per-pod tools-server on localhost)
     #[serde(default)]
     pub tools_sidecar: ToolsSidecarConfig,
+
+    /// Spending budgets enforced before new CodeRuns start
+    #[serde(default)]
+    pub budgets: BudgetsConfig,
+}
+
+/// Spending budget configuration.
+///
+/// Budgets are evaluated against the cost ledger at `ledgerPath`; a CodeRun
+/// whose project (service), agent (GitHub App) or provider has an exhausted
+/// budget is held in `Pending` until the budget window resets. Agent pods
+/// report token usage to the ledger through `usageEndpoint`, authenticated
+/// with the token in `usageTokenSecretName`/`usageTokenSecretKey`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct BudgetsConfig {
+    /// Path to the SQLite cost ledger in the controller pod
+    #[serde(rename = "ledgerPath")]
+    pub ledger_path: Option<String>,
+
+    /// URL of the controller's `/api/usage` route that agent sidecars post
+    /// token usage to (usage is not recorded when unset)
+    #[serde(default, rename = "usageEndpoint")]
+    pub usage_endpoint: Option<String>,
+
+    /// Secret holding the bearer token agent sidecars send to
+    /// `usageEndpoint` (the controller reads it from `CTO_COST_LEDGER_TOKEN`)
+    #[serde(default, rename = "usageTokenSecretName")]
+    pub usage_token_secret_name: Option<String>,
+
+    /// Key in `usageTokenSecretName` for the usage bearer token
+    #[serde(default, rename = "usageTokenSecretKey")]
+    pub usage_token_secret_key: Option<String>,
+
+    /// Seconds between threshold checks that send notifications
+    #[serde(
+        rename = "checkIntervalSeconds",
+        default = "default_budget_check_interval"
+    )]
+    pub check_interval_seconds: u64,
+
+    /// Budget definitions
+    #[serde(default)]
+    pub limits: Vec<cto_cost::Budget>,
+}
+
+fn default_budget_check_interval() -> u64 {
+    300
+}
+
+impl BudgetsConfig {
+    /// Whether budgets should be enforced.
+    #[must_use]
+    pub fn enabled(&self) -> bool {
+        self.ledger_path.is_some() && !self.limits.is_empty()
+    }
 }
 
 /// Tools sidecar configuration — runs tools-server as a per-pod sidecar
@@ -860,6 +915,7 @@ impl Default for ControllerConfig {
             presence: PresenceConfig::default(),
             morgan_sidecar: MorganSidecarConfig::default(),
             tools_sidecar: ToolsSidecarConfig::default(),
+            budgets: BudgetsConfig::default(),
         }
     }
 }
@@ -34,6 +34,51 @@ pub use types::{Error, Result};
 // Context is crate-internal only
 use types::Context;
 
+/// Open the cost ledger and start periodic budget threshold checks.
+///
+/// Returns `None` when budgets are not configured or the ledger cannot be
+/// opened; CodeRuns are then admitted without a budget check.
+fn init_budget_monitor(
+    config: &ControllerConfig,
+    notifier: &Arc<Notifier>,
+) -> Option<Arc<cto_cost::BudgetMonitor>> {
+    let budgets = &config.budgets;
+    let ledger_path = budgets
+        .ledger_path
+        .as_deref()
+        .filter(|_| budgets.enabled())?;
+
+    let monitor = cto_cost::tracking::CostTracker::open(ledger_path)
+        .and_then(|tracker| cto_cost::BudgetMonitor::new(tracker, budgets.limits.clone()));
+    let monitor = match monitor {
+        Ok(monitor) => Arc::new(monitor.with_notifier(Arc::clone(notifier))),
+        Err(e) => {
+            error!("Budget enforcement disabled: {}", e);
+            return None;
+        }
+    };
+    info!(
+        "Budget enforcement enabled with {} budget(s) from ledger {}",
+        monitor.budgets().len(),
+        ledger_path
+    );
+
+    let interval = std::time::Duration::from_secs(budgets.check_interval_seconds.max(1));
+    let checker = Arc::clone(&monitor);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let checker = Arc::clone(&checker);
+            if let Err(e) = tokio::task::spawn_blocking(move || checker.check()).await {
+                warn!("Budget check panicked: {}", e);
+            }
+        }
+    });
+
+    Some(monitor)
+}
+
 /// Main entry point for the separated task controllers
 #[instrument(skip(client), fields(namespace = %namespace))]
 #[allow(clippy::too_many_lines)] // Complex controller startup flow not easily split
@@ -92,12 +137,16 @@ pub async fn run_task_controller(client: Client, namespace: String) -> Result<()
         debug!("Notification system disabled (no channels configured)");
     }
 
+    let notifier = Arc::new(notifier);
+    let budgets = init_budget_monitor(&config, &notifier);
+
     // Create shared context
     let context = Arc::new(Context {
         client: client.clone(),
         namespace: namespace.clone(),
         config: Arc::new(config),
-        notifier: Arc::new(notifier),
+        notifier,
+        budgets,
     });
 
     debug!("Controller context created successfully");
@@ -1,4 +1,5 @@
 use super::config::ControllerConfig;
+use cto_cost::BudgetMonitor;
 use kube::Client;
 use notify::Notifier;
 use std::sync::Arc;
@@ -35,6 +36,7 @@ pub struct Context {
     pub namespace: String,
     pub config: Arc<ControllerConfig>,
     pub notifier: Arc<Notifier>,
+    pub budgets: Option<Arc<BudgetMonitor>>,
 }
 
 // Finalizer names for cleanup
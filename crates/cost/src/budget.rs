@@ -0,0 +1,635 @@
+//! Spending budgets with soft and hard thresholds.
+//!
+//! A [`Budget`] caps spend for one scope (a project, an agent or a
+//! provider) over a calendar window (UTC day, ISO week or month). Crossing
+//! the soft threshold raises a warning; crossing the hard threshold marks
+//! the scope as exhausted until the window rolls over. The controller uses
+//! [`BudgetMonitor::exhausted_for`] to refuse new `CodeRun`s for exhausted
+//! scopes, and [`BudgetMonitor::check`] to send a [`NotifyEvent`] whenever
+//! a budget crosses a threshold.
+//!
+//! ```yaml
+//! - name: cto-monthly
+//!   scope: { project: cto }
+//!   period: monthly
+//!   limitUsd: 500
+//!   softThreshold: 0.8   # warn at $400
+//!   hardThreshold: 1.0   # block at $500
+//! ```
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use notify::{Notifier, NotifyEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::error::CostError;
+use crate::tracking::{AgentId, CostTracker, ProjectId, TrackingContext, TrackingFilter};
+
+/// Calendar window a budget resets on. Windows are aligned to UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    /// Midnight to midnight.
+    Daily,
+    /// Monday to Monday.
+    Weekly,
+    /// First of the month to first of the next month.
+    Monthly,
+}
+
+impl BudgetPeriod {
+    /// Name used in config and notifications.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+        }
+    }
+
+    /// The `[start, end)` window containing `at`.
+    #[must_use]
+    pub fn window(self, at: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let day = at.date_naive();
+        let (start, end) = match self {
+            Self::Daily => (day, day + Duration::days(1)),
+            Self::Weekly => {
+                let start = day - Duration::days(i64::from(day.weekday().num_days_from_monday()));
+                (start, start + Duration::days(7))
+            }
+            Self::Monthly => {
+                let start = day.with_day(1).unwrap_or(day);
+                let end = if start.month() == 12 {
+                    NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+                } else {
+                    NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+                }
+                .unwrap_or(start);
+                (start, end)
+            }
+        };
+        let midnight = |date: NaiveDate| Utc.from_utc_datetime(&date.and_time(Default::default()));
+        (midnight(start), midnight(end))
+    }
+}
+
+impl fmt::Display for BudgetPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a budget counts spend for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BudgetScope {
+    /// All calls tagged with this project.
+    Project(ProjectId),
+    /// All calls made by this agent.
+    Agent(AgentId),
+    /// All calls to this provider, e.g. `anthropic`.
+    Provider(String),
+}
+
+impl BudgetScope {
+    /// Filter selecting the calls this scope counts.
+    #[must_use]
+    pub fn filter(&self) -> TrackingFilter {
+        match self {
+            Self::Project(id) => TrackingFilter::new().with_project(id.clone()),
+            Self::Agent(id) => TrackingFilter::new().with_agent(id.clone()),
+            Self::Provider(provider) => TrackingFilter::new().with_provider(provider.clone()),
+        }
+    }
+
+    /// Whether work with this context and provider falls under the scope.
+    #[must_use]
+    pub fn covers(&self, context: &TrackingContext, provider: Option<&str>) -> bool {
+        match self {
+            Self::Project(id) => context.project_id.as_ref() == Some(id),
+            Self::Agent(id) => context
+                .agent_id
+                .as_ref()
+                .is_some_and(|agent| agent.0.eq_ignore_ascii_case(&id.0)),
+            Self::Provider(name) => provider.is_some_and(|p| p.eq_ignore_ascii_case(name)),
+        }
+    }
+}
+
+impl fmt::Display for BudgetScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Project(id) => write!(f, "project {id}"),
+            Self::Agent(id) => write!(f, "agent {id}"),
+            Self::Provider(provider) => write!(f, "provider {provider}"),
+        }
+    }
+}
+
+fn default_soft_threshold() -> f64 {
+    0.8
+}
+
+fn default_hard_threshold() -> f64 {
+    1.0
+}
+
+/// A spending limit for one scope and period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Budget {
+    /// Unique name, used in notifications and logs.
+    pub name: String,
+    /// What the budget counts.
+    pub scope: BudgetScope,
+    /// Window the budget resets on.
+    pub period: BudgetPeriod,
+    /// Spend limit in USD per window.
+    pub limit_usd: f64,
+    /// Fraction of the limit that triggers a warning (default 0.8).
+    #[serde(default = "default_soft_threshold")]
+    pub soft_threshold: f64,
+    /// Fraction of the limit at which the scope is exhausted (default 1.0).
+    #[serde(default = "default_hard_threshold")]
+    pub hard_threshold: f64,
+}
+
+impl Budget {
+    /// A budget with the default thresholds.
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        scope: BudgetScope,
+        period: BudgetPeriod,
+        limit_usd: f64,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            scope,
+            period,
+            limit_usd,
+            soft_threshold: default_soft_threshold(),
+            hard_threshold: default_hard_threshold(),
+        }
+    }
+
+    /// Set the soft and hard thresholds as fractions of the limit.
+    #[must_use]
+    pub fn with_thresholds(mut self, soft: f64, hard: f64) -> Self {
+        self.soft_threshold = soft;
+        self.hard_threshold = hard;
+        self
+    }
+
+    /// Spend in USD at which the soft threshold is crossed.
+    #[must_use]
+    pub fn soft_limit_usd(&self) -> f64 {
+        self.limit_usd * self.soft_threshold
+    }
+
+    /// Spend in USD at which the scope is exhausted.
+    #[must_use]
+    pub fn hard_limit_usd(&self) -> f64 {
+        self.limit_usd * self.hard_threshold
+    }
+
+    /// Level reached by `spent_usd`.
+    #[must_use]
+    pub fn level(&self, spent_usd: f64) -> BudgetLevel {
+        if spent_usd >= self.hard_limit_usd() {
+            BudgetLevel::Exhausted
+        } else if spent_usd >= self.soft_limit_usd() {
+            BudgetLevel::Warning
+        } else {
+            BudgetLevel::Ok
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("budget name must not be empty".to_string());
+        }
+        if !self.limit_usd.is_finite() || self.limit_usd < 0.0 {
+            return Err(format!(
+                "{}: limitUsd must be a non-negative number",
+                self.name
+            ));
+        }
+        if !(self.soft_threshold > 0.0 && self.soft_threshold <= self.hard_threshold) {
+            return Err(format!(
+                "{}: softThreshold must be positive and at most hardThreshold",
+                self.name
+            ));
+        }
+        if !self.hard_threshold.is_finite() {
+            return Err(format!("{}: hardThreshold must be finite", self.name));
+        }
+        Ok(())
+    }
+}
+
+/// How far into its budget a scope is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetLevel {
+    /// Below the soft threshold.
+    Ok,
+    /// At or above the soft threshold.
+    Warning,
+    /// At or above the hard threshold; new work should be refused.
+    Exhausted,
+}
+
+/// A budget's spend in its current window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetStatus {
+    /// The budget.
+    pub budget: Budget,
+    /// Spend in the current window.
+    pub spent_usd: f64,
+    /// Level reached.
+    pub level: BudgetLevel,
+    /// Start of the window.
+    pub window_start: DateTime<Utc>,
+    /// When the window resets.
+    pub window_end: DateTime<Utc>,
+}
+
+impl BudgetStatus {
+    /// Whether new work in this scope should be refused.
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.level == BudgetLevel::Exhausted
+    }
+
+    /// Notification for this status, or `None` below the soft threshold.
+    #[must_use]
+    pub fn to_notify_event(&self, timestamp: DateTime<Utc>) -> Option<NotifyEvent> {
+        let threshold_usd = match self.level {
+            BudgetLevel::Ok => return None,
+            BudgetLevel::Warning => self.budget.soft_limit_usd(),
+            BudgetLevel::Exhausted => self.budget.hard_limit_usd(),
+        };
+        Some(NotifyEvent::BudgetThreshold {
+            budget: self.budget.name.clone(),
+            scope: self.budget.scope.to_string(),
+            period: self.budget.period.to_string(),
+            spent_usd: self.spent_usd,
+            limit_usd: self.budget.limit_usd,
+            threshold_usd,
+            exhausted: self.is_exhausted(),
+            timestamp,
+        })
+    }
+}
+
+/// Evaluates budgets against a cost ledger.
+///
+/// Threshold notifications are deduplicated per budget and window in
+/// memory, so a restarted monitor may repeat the current window's alert
+/// once.
+pub struct BudgetMonitor {
+    tracker: CostTracker,
+    budgets: Vec<Budget>,
+    notifier: Option<Arc<Notifier>>,
+    notified: Mutex<HashMap<String, (DateTime<Utc>, BudgetLevel)>>,
+}
+
+impl fmt::Debug for BudgetMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BudgetMonitor")
+            .field("tracker", &self.tracker)
+            .field("budgets", &self.budgets)
+            .field("notifier", &self.notifier.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl BudgetMonitor {
+    /// Create a monitor over `tracker`'s ledger.
+    ///
+    /// # Errors
+    /// Returns an error if a budget is invalid or two budgets share a name.
+    pub fn new(tracker: CostTracker, budgets: Vec<Budget>) -> Result<Self, CostError> {
+        for (index, budget) in budgets.iter().enumerate() {
+            budget.validate().map_err(CostError::InvalidBudget)?;
+            if budgets[..index].iter().any(|b| b.name == budget.name) {
+                return Err(CostError::InvalidBudget(format!(
+                    "duplicate budget name {:?}",
+                    budget.name
+                )));
+            }
+        }
+        Ok(Self {
+            tracker,
+            budgets,
+            notifier: None,
+            notified: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Send threshold notifications through `notifier`.
+    #[must_use]
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Configured budgets.
+    #[must_use]
+    pub fn budgets(&self) -> &[Budget] {
+        &self.budgets
+    }
+
+    /// Status of every budget at `now`. Budgets whose spend cannot be read
+    /// from the ledger are logged and left out.
+    #[must_use]
+    pub fn status_at(&self, now: DateTime<Utc>) -> Vec<BudgetStatus> {
+        self.budgets
+            .iter()
+            .filter_map(|budget| match self.evaluate(budget, now) {
+                Ok(status) => Some(status),
+                Err(e) => {
+                    tracing::warn!(budget = %budget.name, "Failed to evaluate budget: {e}");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn evaluate(&self, budget: &Budget, now: DateTime<Utc>) -> Result<BudgetStatus, CostError> {
+        let (window_start, window_end) = budget.period.window(now);
+        let filter = budget
+            .scope
+            .filter()
+            .with_time_range(window_start, window_end);
+        let spent_usd = self.tracker.try_metrics(&filter)?.total_cost_usd;
+        Ok(BudgetStatus {
+            budget: budget.clone(),
+            spent_usd,
+            level: budget.level(spent_usd),
+            window_start,
+            window_end,
+        })
+    }
+
+    /// Evaluate budgets now and notify about newly crossed thresholds.
+    pub fn check(&self) -> Vec<BudgetStatus> {
+        self.check_at(Utc::now())
+    }
+
+    /// Evaluate budgets at `now` and return those that crossed a higher
+    /// threshold since the last check in the same window, sending a
+    /// [`NotifyEvent::BudgetThreshold`] for each.
+    pub fn check_at(&self, now: DateTime<Utc>) -> Vec<BudgetStatus> {
+        let mut notified = self
+            .notified
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut crossed = Vec::new();
+        for status in self.status_at(now) {
+            let previous = notified
+                .get(&status.budget.name)
+                .filter(|(window_start, _)| *window_start == status.window_start)
+                .map_or(BudgetLevel::Ok, |(_, level)| *level);
+            if status.level <= previous {
+                continue;
+            }
+            notified.insert(
+                status.budget.name.clone(),
+                (status.window_start, status.level),
+            );
+            tracing::warn!(
+                budget = %status.budget.name,
+                scope = %status.budget.scope,
+                spent_usd = status.spent_usd,
+                limit_usd = status.budget.limit_usd,
+                "Budget crossed {:?} threshold",
+                status.level
+            );
+            if let (Some(notifier), Some(event)) = (&self.notifier, status.to_notify_event(now)) {
+                notifier.notify(event);
+            }
+            crossed.push(status);
+        }
+        crossed
+    }
+
+    /// The exhausted budget blocking work with this context and provider,
+    /// if any. When several are exhausted, the one resetting last is
+    /// returned.
+    ///
+    /// # Errors
+    /// Returns an error if the spend of a covering budget cannot be read
+    /// from the ledger; callers decide whether that blocks work.
+    pub fn exhausted_for(
+        &self,
+        context: &TrackingContext,
+        provider: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<BudgetStatus>, CostError> {
+        let mut exhausted: Option<BudgetStatus> = None;
+        for budget in self
+            .budgets
+            .iter()
+            .filter(|budget| budget.scope.covers(context, provider))
+        {
+            let status = self.evaluate(budget, now)?;
+            if status.is_exhausted()
+                && exhausted
+                    .as_ref()
+                    .is_none_or(|current| status.window_end >= current.window_end)
+            {
+                exhausted = Some(status);
+            }
+        }
+        Ok(exhausted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn spend(tracker: &CostTracker, project: &str, agent: &str, cost: f64, when: &str) {
+        let _ = tracker
+            .builder()
+            .project(project)
+            .agent(agent)
+            .provider("anthropic")
+            .cost(cost)
+            .timestamp(at(when))
+            .record();
+    }
+
+    #[test]
+    fn test_windows_align_to_calendar() {
+        let now = at("2026-03-18T15:30:00Z"); // a Wednesday
+        assert_eq!(
+            BudgetPeriod::Daily.window(now),
+            (at("2026-03-18T00:00:00Z"), at("2026-03-19T00:00:00Z"))
+        );
+        assert_eq!(
+            BudgetPeriod::Weekly.window(now),
+            (at("2026-03-16T00:00:00Z"), at("2026-03-23T00:00:00Z"))
+        );
+        assert_eq!(
+            BudgetPeriod::Monthly.window(at("2026-12-31T23:59:59Z")),
+            (at("2026-12-01T00:00:00Z"), at("2027-01-01T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_thresholds_notify_once_per_window() {
+        let tracker = CostTracker::new();
+        let budget = Budget::new(
+            "cto-daily",
+            BudgetScope::Project(ProjectId::new("cto")),
+            BudgetPeriod::Daily,
+            10.0,
+        );
+        let monitor = BudgetMonitor::new(tracker.clone(), vec![budget]).unwrap();
+
+        spend(&tracker, "cto", "rex", 5.0, "2026-03-18T09:00:00Z");
+        spend(&tracker, "other", "rex", 50.0, "2026-03-18T09:00:00Z");
+        assert!(monitor.check_at(at("2026-03-18T10:00:00Z")).is_empty());
+
+        spend(&tracker, "cto", "rex", 3.5, "2026-03-18T10:30:00Z");
+        let crossed = monitor.check_at(at("2026-03-18T11:00:00Z"));
+        assert_eq!(crossed.len(), 1);
+        assert_eq!(crossed[0].level, BudgetLevel::Warning);
+        assert!(monitor.check_at(at("2026-03-18T11:05:00Z")).is_empty());
+
+        spend(&tracker, "cto", "rex", 2.0, "2026-03-18T12:00:00Z");
+        let crossed = monitor.check_at(at("2026-03-18T12:05:00Z"));
+        assert_eq!(crossed[0].level, BudgetLevel::Exhausted);
+        let event = crossed[0]
+            .to_notify_event(at("2026-03-18T12:05:00Z"))
+            .unwrap();
+        assert!(matches!(
+            event,
+            NotifyEvent::BudgetThreshold {
+                exhausted: true,
+                ..
+            }
+        ));
+
+        // A new day starts a fresh window.
+        assert!(monitor.check_at(at("2026-03-19T08:00:00Z")).is_empty());
+    }
+
+    /// A ledger whose every read fails.
+    #[derive(Debug)]
+    struct BrokenStore;
+
+    impl crate::tracking::CallStore for BrokenStore {
+        fn insert_many(&self, _: &[crate::tracking::TrackedCall]) -> Result<(), CostError> {
+            Err(rusqlite::Error::InvalidQuery.into())
+        }
+
+        fn query(
+            &self,
+            _: &TrackingFilter,
+        ) -> Result<Vec<crate::tracking::TrackedCall>, CostError> {
+            Err(rusqlite::Error::InvalidQuery.into())
+        }
+
+        fn count(&self, _: &TrackingFilter) -> Result<usize, CostError> {
+            Err(rusqlite::Error::InvalidQuery.into())
+        }
+
+        fn total_cost(&self, _: &TrackingFilter) -> Result<f64, CostError> {
+            Err(rusqlite::Error::InvalidQuery.into())
+        }
+
+        fn distinct(&self, _: crate::tracking::ContextKey) -> Result<Vec<String>, CostError> {
+            Err(rusqlite::Error::InvalidQuery.into())
+        }
+
+        fn clear(&self) -> Result<(), CostError> {
+            Err(rusqlite::Error::InvalidQuery.into())
+        }
+    }
+
+    #[test]
+    fn test_unreadable_ledger_is_an_error_not_zero_spend() {
+        let tracker = CostTracker::with_store(Arc::new(BrokenStore));
+        let budgets = vec![Budget::new(
+            "cto-daily",
+            BudgetScope::Project(ProjectId::new("cto")),
+            BudgetPeriod::Daily,
+            10.0,
+        )];
+        let monitor = BudgetMonitor::new(tracker, budgets).unwrap();
+        let now = at("2026-03-18T09:00:00Z");
+
+        let cto = TrackingContext::new().with_project("cto");
+        assert!(monitor.exhausted_for(&cto, None, now).is_err());
+        // Budgets that do not cover the run never touch the ledger
+        let web = TrackingContext::new().with_project("web");
+        assert!(monitor.exhausted_for(&web, None, now).unwrap().is_none());
+        assert!(monitor.status_at(now).is_empty());
+    }
+
+    #[test]
+    fn test_exhausted_signal_matches_scope() {
+        let tracker = CostTracker::new();
+        let budgets = vec![
+            Budget::new(
+                "rex-weekly",
+                BudgetScope::Agent(AgentId::new("5DLabs-Rex")),
+                BudgetPeriod::Weekly,
+                20.0,
+            ),
+            Budget::new(
+                "anthropic-monthly",
+                BudgetScope::Provider("anthropic".to_string()),
+                BudgetPeriod::Monthly,
+                1000.0,
+            ),
+        ];
+        let monitor = BudgetMonitor::new(tracker.clone(), budgets).unwrap();
+        spend(&tracker, "cto", "5DLabs-Rex", 25.0, "2026-03-17T09:00:00Z");
+
+        let now = at("2026-03-18T09:00:00Z");
+        let rex = TrackingContext::new().with_agent("5dlabs-rex");
+        let blocked = monitor
+            .exhausted_for(&rex, Some("anthropic"), now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(blocked.budget.name, "rex-weekly");
+        assert_eq!(blocked.window_end, at("2026-03-23T00:00:00Z"));
+
+        let blaze = TrackingContext::new().with_agent("5DLabs-Blaze");
+        assert!(monitor
+            .exhausted_for(&blaze, Some("anthropic"), now)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_budgets_deserialize_and_validate() {
+        let budgets: Vec<Budget> = serde_json::from_str(
+            r#"[{"name": "cto", "scope": {"project": "cto"}, "period": "monthly", "limitUsd": 500}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            budgets[0].scope,
+            BudgetScope::Project(ProjectId::new("cto"))
+        );
+        assert!((budgets[0].soft_limit_usd() - 400.0).abs() < 1e-9);
+
+        let duplicate = vec![budgets[0].clone(), budgets[0].clone()];
+        assert!(BudgetMonitor::new(CostTracker::new(), duplicate).is_err());
+        let inverted = budgets[0].clone().with_thresholds(1.2, 1.0);
+        assert!(BudgetMonitor::new(CostTracker::new(), vec![inverted]).is_err());
+    }
+}
@@ -0,0 +1,32 @@
+//! Error types for the cost ledger.
+
+use thiserror::Error;
+
+/// Errors raised by the cost ledger and price catalog.
+#[derive(Debug, Error)]
+pub enum CostError {
+    /// The SQLite store failed.
+    #[error("cost store error: {0}")]
+    Store(#[from] rusqlite::Error),
+
+    /// A ledger file was written by a newer schema than this build knows.
+    #[error("cost store schema version {found} is newer than supported version {supported}")]
+    UnsupportedSchema {
+        /// Version found in the database.
+        found: i64,
+        /// Latest version this build can migrate to.
+        supported: i64,
+    },
+
+    /// JSON (de)serialization failed.
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// A budget definition is invalid.
+    #[error("invalid budget: {0}")]
+    InvalidBudget(String),
+
+    /// A price catalog is malformed.
+    #[error("invalid price catalog: {0}")]
+    InvalidCatalog(String),
+}
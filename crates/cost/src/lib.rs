@@ -0,0 +1,48 @@
+//! Usage ledger and cost analytics for CTO agent runs.
+//!
+//! This crate provides:
+//! - [`tracking`]: recording API calls with task/agent/project context and
+//!   computing efficiency metrics over them
+//! - [`pricing`]: a versioned model price catalog that turns token counts
+//!   into USD
+//! - A SQLite-backed ledger so tracked calls survive restarts
+//! - [`budget`]: spending budgets with soft/hard thresholds, notifications
+//!   and an exhausted signal for admission control
+//!
+//! # Example
+//!
+//! ```rust
+//! use cto_cost::tracking::{CostTracker, TrackingFilter};
+//!
+//! # fn main() -> Result<(), cto_cost::CostError> {
+//! // Persist to a file; `CostTracker::new()` keeps calls in memory instead
+//! # let dir = tempfile::tempdir().unwrap();
+//! # let path = dir.path().join("costs.db");
+//! let tracker = CostTracker::open(&path)?;
+//!
+//! // Cost is derived from the catalog when not supplied
+//! let call = tracker
+//!     .builder()
+//!     .task("task-1")
+//!     .agent("rex")
+//!     .provider("anthropic")
+//!     .model("claude-sonnet-4-5-20250929")
+//!     .input_tokens(10_000)
+//!     .output_tokens(2_000)
+//!     .record();
+//! assert!((call.estimated_cost_usd - 0.06).abs() < 1e-9);
+//!
+//! let metrics = tracker.metrics(&TrackingFilter::new().with_agent("rex"));
+//! assert_eq!(metrics.total_calls, 1);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod budget;
+pub mod error;
+pub mod pricing;
+pub mod tracking;
+
+pub use budget::{Budget, BudgetLevel, BudgetMonitor, BudgetPeriod, BudgetScope, BudgetStatus};
+pub use error::CostError;
+pub use pricing::{ModelPrice, PriceCatalog, PriceQuote, PriceTable};
@@ -0,0 +1,328 @@
+//! Versioned model price catalog.
+//!
+//! Prices are grouped into [`PriceTable`]s, each with a version and the
+//! time it took effect. A call is priced with the newest table in effect at
+//! the call's timestamp that lists its model, so re-pricing history after a
+//! provider changes rates only needs a new table, not a data migration.
+//!
+//! Rates are USD per million tokens. Model names match exactly or by prefix
+//! at a `-` boundary, so `claude-sonnet-4-5` also prices
+//! `claude-sonnet-4-5-20250929`; the longest matching name wins.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::error::CostError;
+
+/// Catalog shipped with the crate.
+const BUILT_IN_CATALOG: &str = include_str!("../prices.json");
+
+/// Per-token rates for one provider/model pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPrice {
+    /// Provider name, e.g. `anthropic`.
+    pub provider: String,
+    /// Model name or name prefix, e.g. `claude-opus-4-5`.
+    pub model: String,
+    /// USD per million uncached input tokens.
+    pub input_per_mtok: f64,
+    /// USD per million output tokens.
+    pub output_per_mtok: f64,
+    /// USD per million cached input tokens; defaults to the input rate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input_per_mtok: Option<f64>,
+}
+
+impl ModelPrice {
+    /// Cost in USD for the given token counts.
+    ///
+    /// `cached_tokens` are a subset of `input_tokens` and are billed at the
+    /// cached rate instead of the input rate.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Token counts are far below 2^52
+    pub fn cost(&self, input_tokens: i64, output_tokens: i64, cached_tokens: i64) -> f64 {
+        let cached = cached_tokens.clamp(0, input_tokens.max(0));
+        let uncached = input_tokens.max(0) - cached;
+        let cached_rate = self.cached_input_per_mtok.unwrap_or(self.input_per_mtok);
+        (uncached as f64 * self.input_per_mtok
+            + cached as f64 * cached_rate
+            + output_tokens.max(0) as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+
+    /// Length of the matched model name, or `None` if this price does not
+    /// apply. An empty `provider` matches any provider.
+    fn match_len(&self, provider: &str, model: &str) -> Option<usize> {
+        if !provider.is_empty() && !self.provider.eq_ignore_ascii_case(provider) {
+            return None;
+        }
+        let model = model.to_ascii_lowercase();
+        let name = self.model.to_ascii_lowercase();
+        let rest = model.strip_prefix(&name)?;
+        (rest.is_empty() || rest.starts_with('-')).then_some(name.len())
+    }
+}
+
+/// A set of prices that took effect at one point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceTable {
+    /// Version label recorded on every call priced with this table.
+    pub version: String,
+    /// When these prices took effect.
+    pub effective_from: DateTime<Utc>,
+    /// Model prices.
+    pub models: Vec<ModelPrice>,
+}
+
+impl PriceTable {
+    /// Best price for `provider`/`model` in this table.
+    #[must_use]
+    pub fn find(&self, provider: &str, model: &str) -> Option<&ModelPrice> {
+        self.models
+            .iter()
+            .filter_map(|price| price.match_len(provider, model).map(|len| (len, price)))
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, price)| price)
+    }
+}
+
+/// A derived cost and the catalog version that produced it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceQuote {
+    /// Price table version.
+    pub version: String,
+    /// Cost in USD.
+    pub cost_usd: f64,
+}
+
+/// All known price tables, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceCatalog {
+    tables: Vec<PriceTable>,
+}
+
+impl Default for PriceCatalog {
+    fn default() -> Self {
+        Self::built_in().clone()
+    }
+}
+
+impl PriceCatalog {
+    /// The catalog shipped with this crate.
+    ///
+    /// # Panics
+    /// Panics if the embedded `prices.json` is invalid, which the tests rule out.
+    #[must_use]
+    pub fn built_in() -> &'static Self {
+        static BUILT_IN: OnceLock<PriceCatalog> = OnceLock::new();
+        BUILT_IN.get_or_init(|| {
+            Self::from_json(BUILT_IN_CATALOG).expect("built-in price catalog is valid")
+        })
+    }
+
+    /// Build a catalog from tables in any order.
+    ///
+    /// # Errors
+    /// Returns an error if a version is empty or repeated, or a rate is
+    /// negative or not finite.
+    pub fn from_tables(tables: Vec<PriceTable>) -> Result<Self, CostError> {
+        let mut catalog = Self { tables: Vec::new() };
+        for table in tables {
+            catalog.add_table(table)?;
+        }
+        Ok(catalog)
+    }
+
+    /// Parse a catalog from JSON (`{"tables": [...]}`).
+    ///
+    /// # Errors
+    /// Returns an error if the JSON is malformed or fails validation.
+    pub fn from_json(json: &str) -> Result<Self, CostError> {
+        let catalog: Self = serde_json::from_str(json)?;
+        Self::from_tables(catalog.tables)
+    }
+
+    /// Serialize the catalog to JSON.
+    ///
+    /// # Errors
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, CostError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Add a price table, keeping tables ordered by effective time.
+    ///
+    /// # Errors
+    /// Returns an error if the version is empty or already present, or a
+    /// rate is negative or not finite.
+    pub fn add_table(&mut self, table: PriceTable) -> Result<(), CostError> {
+        if table.version.trim().is_empty() {
+            return Err(CostError::InvalidCatalog(
+                "price table version must not be empty".to_string(),
+            ));
+        }
+        if self.version(&table.version).is_some() {
+            return Err(CostError::InvalidCatalog(format!(
+                "duplicate price table version {:?}",
+                table.version
+            )));
+        }
+        for price in &table.models {
+            let rates = [
+                price.input_per_mtok,
+                price.output_per_mtok,
+                price.cached_input_per_mtok.unwrap_or(0.0),
+            ];
+            if rates.iter().any(|rate| !rate.is_finite() || *rate < 0.0) {
+                return Err(CostError::InvalidCatalog(format!(
+                    "{}: invalid rate for {}/{}",
+                    table.version, price.provider, price.model
+                )));
+            }
+        }
+        let index = self
+            .tables
+            .partition_point(|existing| existing.effective_from <= table.effective_from);
+        self.tables.insert(index, table);
+        Ok(())
+    }
+
+    /// All tables, oldest first.
+    #[must_use]
+    pub fn tables(&self) -> &[PriceTable] {
+        &self.tables
+    }
+
+    /// The most recent table.
+    #[must_use]
+    pub fn latest(&self) -> Option<&PriceTable> {
+        self.tables.last()
+    }
+
+    /// Look up a table by version.
+    #[must_use]
+    pub fn version(&self, version: &str) -> Option<&PriceTable> {
+        self.tables.iter().find(|table| table.version == version)
+    }
+
+    /// Newest table in effect at `at` that prices `provider`/`model`.
+    #[must_use]
+    pub fn price_for(
+        &self,
+        provider: &str,
+        model: &str,
+        at: DateTime<Utc>,
+    ) -> Option<(&PriceTable, &ModelPrice)> {
+        self.tables
+            .iter()
+            .rev()
+            .filter(|table| table.effective_from <= at)
+            .find_map(|table| table.find(provider, model).map(|price| (table, price)))
+    }
+
+    /// Derive the cost of a call from its token counts.
+    ///
+    /// Returns `None` if no table in effect at `at` prices the model.
+    #[must_use]
+    pub fn quote(
+        &self,
+        provider: &str,
+        model: &str,
+        input_tokens: i64,
+        output_tokens: i64,
+        cached_tokens: i64,
+        at: DateTime<Utc>,
+    ) -> Option<PriceQuote> {
+        self.price_for(provider, model, at)
+            .map(|(table, price)| PriceQuote {
+                version: table.version.clone(),
+                cost_usd: price.cost(input_tokens, output_tokens, cached_tokens),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(version: &str, effective_from: &str, input: f64) -> PriceTable {
+        PriceTable {
+            version: version.to_string(),
+            effective_from: effective_from.parse().unwrap(),
+            models: vec![ModelPrice {
+                provider: "anthropic".to_string(),
+                model: "claude-opus-4-5".to_string(),
+                input_per_mtok: input,
+                output_per_mtok: 25.0,
+                cached_input_per_mtok: Some(0.5),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_built_in_catalog_parses() {
+        let catalog = PriceCatalog::built_in();
+        assert!(catalog.latest().is_some());
+        let (_, price) = catalog
+            .price_for("openai", "gpt-5-mini-2025-08-07", Utc::now())
+            .unwrap();
+        assert_eq!(price.model, "gpt-5-mini");
+        assert!(catalog.price_for("openai", "gpt-50", Utc::now()).is_none());
+    }
+
+    #[test]
+    fn test_cost_bills_cached_tokens_at_cached_rate() {
+        let price = &table("v1", "2025-01-01T00:00:00Z", 5.0).models[0];
+        // 800k uncached * $5 + 200k cached * $0.50 + 100k output * $25
+        let cost = price.cost(1_000_000, 100_000, 200_000);
+        assert!((cost - (4.0 + 0.1 + 2.5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_quote_uses_table_in_effect_at_call_time() {
+        let catalog = PriceCatalog::from_tables(vec![
+            table("v2", "2025-06-01T00:00:00Z", 4.0),
+            table("v1", "2025-01-01T00:00:00Z", 5.0),
+        ])
+        .unwrap();
+
+        let march = "2025-03-01T00:00:00Z".parse().unwrap();
+        let quote = catalog
+            .quote(
+                "anthropic",
+                "claude-opus-4-5-20251101",
+                1_000_000,
+                0,
+                0,
+                march,
+            )
+            .unwrap();
+        assert_eq!(quote.version, "v1");
+        assert!((quote.cost_usd - 5.0).abs() < 1e-9);
+
+        let july = "2025-07-01T00:00:00Z".parse().unwrap();
+        let quote = catalog
+            .quote("anthropic", "claude-opus-4-5", 1_000_000, 0, 0, july)
+            .unwrap();
+        assert_eq!(quote.version, "v2");
+
+        let before = "2024-12-01T00:00:00Z".parse().unwrap();
+        assert!(catalog
+            .quote("anthropic", "claude-opus-4-5", 1, 1, 0, before)
+            .is_none());
+    }
+
+    #[test]
+    fn test_invalid_catalogs_are_rejected() {
+        let duplicate = PriceCatalog::from_tables(vec![
+            table("v1", "2025-01-01T00:00:00Z", 5.0),
+            table("v1", "2025-02-01T00:00:00Z", 4.0),
+        ]);
+        assert!(duplicate.is_err());
+        let negative = PriceCatalog::from_tables(vec![table("v1", "2025-01-01T00:00:00Z", -1.0)]);
+        assert!(negative.is_err());
+    }
+}
@@ -340,23 +340,3 @@ impl AgentComparison {
         }
     }
 }
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
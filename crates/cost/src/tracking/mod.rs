@@ -42,30 +42,12 @@
 
 mod metrics;
 mod models;
+mod store;
 mod tracker;
 
 pub use metrics::{AgentComparison, AgentMetrics, AggregatedMetrics, ProjectMetrics, TaskMetrics};
 pub use models::{
     AgentId, ProjectId, SessionId, TaskId, TrackedCall, TrackingContext, TrackingFilter,
 };
+pub use store::{CallStore, ContextKey, MemoryStore, SqliteStore};
 pub use tracker::{CostTracker, TrackedCallBuilder};
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
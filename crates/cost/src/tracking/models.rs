@@ -207,6 +207,10 @@ pub struct TrackedCall {
     pub cached_tokens: i64,
     /// Estimated cost in USD.
     pub estimated_cost_usd: f64,
+    /// Price table version the cost was derived from; `None` when the cost
+    /// was supplied by the caller or the model is not in the catalog.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_version: Option<String>,
     /// Duration of the call in milliseconds.
     pub duration_ms: Option<u64>,
     /// Whether the call succeeded.
@@ -384,23 +388,3 @@ impl TrackingFilter {
         true
     }
 }
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
@@ -0,0 +1,591 @@
+//! Storage backends for tracked calls.
+
+use chrono::{DateTime, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use super::models::{
+    AgentId, ProjectId, SessionId, TaskId, TrackedCall, TrackingContext, TrackingFilter,
+};
+use crate::error::CostError;
+
+/// Context field whose distinct values can be listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextKey {
+    /// `context.project_id`.
+    Project,
+    /// `context.task_id`.
+    Task,
+    /// `context.agent_id`.
+    Agent,
+}
+
+impl ContextKey {
+    fn column(self) -> &'static str {
+        match self {
+            Self::Project => "project_id",
+            Self::Task => "task_id",
+            Self::Agent => "agent_id",
+        }
+    }
+
+    fn get(self, context: &TrackingContext) -> Option<&str> {
+        match self {
+            Self::Project => context.project_id.as_ref().map(|id| id.0.as_str()),
+            Self::Task => context.task_id.as_ref().map(|id| id.0.as_str()),
+            Self::Agent => context.agent_id.as_ref().map(|id| id.0.as_str()),
+        }
+    }
+}
+
+/// A place to keep tracked calls.
+pub trait CallStore: Send + Sync + std::fmt::Debug {
+    /// Append a call. Re-inserting a call with a known ID is a no-op.
+    ///
+    /// # Errors
+    /// Returns an error if the backend fails.
+    fn insert(&self, call: &TrackedCall) -> Result<(), CostError> {
+        self.insert_many(std::slice::from_ref(call))
+    }
+
+    /// Append several calls atomically.
+    ///
+    /// # Errors
+    /// Returns an error if the backend fails; no call is stored then.
+    fn insert_many(&self, calls: &[TrackedCall]) -> Result<(), CostError>;
+
+    /// Calls matching `filter`, oldest first.
+    ///
+    /// # Errors
+    /// Returns an error if the backend fails.
+    fn query(&self, filter: &TrackingFilter) -> Result<Vec<TrackedCall>, CostError>;
+
+    /// Number of calls matching `filter`.
+    ///
+    /// # Errors
+    /// Returns an error if the backend fails.
+    fn count(&self, filter: &TrackingFilter) -> Result<usize, CostError>;
+
+    /// Sum of `estimated_cost_usd` over calls matching `filter`.
+    ///
+    /// # Errors
+    /// Returns an error if the backend fails.
+    fn total_cost(&self, filter: &TrackingFilter) -> Result<f64, CostError>;
+
+    /// Distinct non-empty values of a context field, sorted.
+    ///
+    /// # Errors
+    /// Returns an error if the backend fails.
+    fn distinct(&self, key: ContextKey) -> Result<Vec<String>, CostError>;
+
+    /// Remove every call.
+    ///
+    /// # Errors
+    /// Returns an error if the backend fails.
+    fn clear(&self) -> Result<(), CostError>;
+}
+
+/// Keeps calls in process memory.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    calls: RwLock<Vec<TrackedCall>>,
+}
+
+impl MemoryStore {
+    /// Create an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CallStore for MemoryStore {
+    fn insert_many(&self, calls: &[TrackedCall]) -> Result<(), CostError> {
+        if let Ok(mut existing) = self.calls.write() {
+            for call in calls {
+                if !existing.iter().any(|c| c.id == call.id) {
+                    existing.push(call.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn query(&self, filter: &TrackingFilter) -> Result<Vec<TrackedCall>, CostError> {
+        Ok(self
+            .calls
+            .read()
+            .map(|calls| {
+                calls
+                    .iter()
+                    .filter(|c| filter.matches(c))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn count(&self, filter: &TrackingFilter) -> Result<usize, CostError> {
+        Ok(self
+            .calls
+            .read()
+            .map(|calls| calls.iter().filter(|c| filter.matches(c)).count())
+            .unwrap_or(0))
+    }
+
+    fn total_cost(&self, filter: &TrackingFilter) -> Result<f64, CostError> {
+        Ok(self
+            .calls
+            .read()
+            .map(|calls| {
+                calls
+                    .iter()
+                    .filter(|c| filter.matches(c))
+                    .map(|c| c.estimated_cost_usd)
+                    .sum()
+            })
+            .unwrap_or(0.0))
+    }
+
+    fn distinct(&self, key: ContextKey) -> Result<Vec<String>, CostError> {
+        let mut values: Vec<String> = self
+            .calls
+            .read()
+            .map(|calls| {
+                calls
+                    .iter()
+                    .filter_map(|c| key.get(&c.context).map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        values.sort();
+        values.dedup();
+        Ok(values)
+    }
+
+    fn clear(&self) -> Result<(), CostError> {
+        if let Ok(mut calls) = self.calls.write() {
+            calls.clear();
+        }
+        Ok(())
+    }
+}
+
+/// Schema migrations, applied in order; `PRAGMA user_version` records how
+/// many have run.
+const MIGRATIONS: &[&str] = &[
+    // 1: calls and their tags
+    "CREATE TABLE calls (
+        id            TEXT PRIMARY KEY,
+        timestamp_us  INTEGER NOT NULL,
+        project_id    TEXT,
+        task_id       TEXT,
+        agent_id      TEXT,
+        session_id    TEXT,
+        iteration     INTEGER,
+        provider      TEXT NOT NULL,
+        model         TEXT NOT NULL,
+        input_tokens  INTEGER NOT NULL,
+        output_tokens INTEGER NOT NULL,
+        cached_tokens INTEGER NOT NULL,
+        cost_usd      REAL NOT NULL,
+        price_version TEXT,
+        duration_ms   INTEGER,
+        success       INTEGER NOT NULL,
+        error         TEXT
+    );
+    CREATE INDEX calls_timestamp ON calls (timestamp_us);
+    CREATE INDEX calls_project ON calls (project_id);
+    CREATE INDEX calls_task ON calls (task_id);
+    CREATE INDEX calls_agent ON calls (agent_id);
+    CREATE TABLE call_tags (
+        call_id TEXT NOT NULL REFERENCES calls (id) ON DELETE CASCADE,
+        key     TEXT NOT NULL,
+        value   TEXT NOT NULL,
+        PRIMARY KEY (call_id, key)
+    );",
+];
+
+const CALL_COLUMNS: &str = "id, timestamp_us, project_id, task_id, agent_id, session_id, \
+     iteration, provider, model, input_tokens, output_tokens, cached_tokens, cost_usd, \
+     price_version, duration_ms, success, error";
+
+/// Keeps calls in a SQLite database.
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open (or create) a ledger file and bring its schema up to date.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or was written by a
+    /// newer schema version.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, CostError> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        Self::init(conn)
+    }
+
+    /// Create a throwaway in-memory ledger.
+    ///
+    /// # Errors
+    /// Returns an error if SQLite fails to initialise.
+    pub fn in_memory() -> Result<Self, CostError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self, CostError> {
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Current schema version.
+    ///
+    /// # Errors
+    /// Returns an error if SQLite fails.
+    pub fn schema_version(&self) -> Result<i64, CostError> {
+        let conn = self.lock();
+        Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Latest schema version this build knows.
+#[allow(clippy::cast_possible_wrap)] // A handful of migrations
+const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+fn migrate(conn: &mut Connection) -> Result<(), CostError> {
+    let current: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if current > SCHEMA_VERSION {
+        return Err(CostError::UnsupportedSchema {
+            found: current,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    for (version, sql) in (1..).zip(MIGRATIONS).skip_while(|(v, _)| *v <= current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+        tracing::debug!("Migrated cost store to schema version {version}");
+    }
+    Ok(())
+}
+
+/// Build a `WHERE` clause equivalent to [`TrackingFilter::matches`].
+fn where_clause(filter: &TrackingFilter) -> (String, Vec<Value>) {
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    let mut push = |condition, value: Value| {
+        conditions.push(condition);
+        values.push(value);
+    };
+
+    if let Some(ref id) = filter.project_id {
+        push("project_id = ?", Value::Text(id.0.clone()));
+    }
+    if let Some(ref id) = filter.task_id {
+        push("task_id = ?", Value::Text(id.0.clone()));
+    }
+    if let Some(ref id) = filter.agent_id {
+        push("agent_id = ?", Value::Text(id.0.clone()));
+    }
+    if let Some(ref id) = filter.session_id {
+        push("session_id = ?", Value::Text(id.0.clone()));
+    }
+    if let Some(ref provider) = filter.provider {
+        push("provider = ?", Value::Text(provider.clone()));
+    }
+    if let Some(ref model) = filter.model {
+        push("model = ?", Value::Text(model.clone()));
+    }
+    if let Some(start) = filter.start_time {
+        push(
+            "timestamp_us >= ?",
+            Value::Integer(start.timestamp_micros()),
+        );
+    }
+    if let Some(end) = filter.end_time {
+        push("timestamp_us < ?", Value::Integer(end.timestamp_micros()));
+    }
+    if let Some(success) = filter.success {
+        push("success = ?", Value::Integer(i64::from(success)));
+    }
+    if let Some((ref key, ref value)) = filter.tag {
+        conditions.push(
+            "EXISTS (SELECT 1 FROM call_tags t WHERE t.call_id = calls.id AND t.key = ? AND t.value = ?)",
+        );
+        values.push(Value::Text(key.clone()));
+        values.push(Value::Text(value.clone()));
+    }
+
+    if conditions.is_empty() {
+        (String::new(), values)
+    } else {
+        (format!(" WHERE {}", conditions.join(" AND ")), values)
+    }
+}
+
+fn call_from_row(row: &Row<'_>) -> rusqlite::Result<TrackedCall> {
+    let timestamp_us: i64 = row.get("timestamp_us")?;
+    let iteration: Option<i64> = row.get("iteration")?;
+    let duration_ms: Option<i64> = row.get("duration_ms")?;
+    Ok(TrackedCall {
+        id: row.get("id")?,
+        timestamp: DateTime::<Utc>::from_timestamp_micros(timestamp_us).unwrap_or_default(),
+        context: TrackingContext {
+            project_id: row.get::<_, Option<String>>("project_id")?.map(ProjectId),
+            task_id: row.get::<_, Option<String>>("task_id")?.map(TaskId),
+            agent_id: row.get::<_, Option<String>>("agent_id")?.map(AgentId),
+            session_id: row.get::<_, Option<String>>("session_id")?.map(SessionId),
+            iteration: iteration.and_then(|i| u32::try_from(i).ok()),
+            tags: HashMap::new(),
+        },
+        provider: row.get("provider")?,
+        model: row.get("model")?,
+        input_tokens: row.get("input_tokens")?,
+        output_tokens: row.get("output_tokens")?,
+        cached_tokens: row.get("cached_tokens")?,
+        estimated_cost_usd: row.get("cost_usd")?,
+        price_version: row.get("price_version")?,
+        duration_ms: duration_ms.and_then(|d| u64::try_from(d).ok()),
+        success: row.get("success")?,
+        error: row.get("error")?,
+    })
+}
+
+impl CallStore for SqliteStore {
+    fn insert_many(&self, calls: &[TrackedCall]) -> Result<(), CostError> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        {
+            let mut insert_call = tx.prepare_cached(&format!(
+                "INSERT OR IGNORE INTO calls ({CALL_COLUMNS}) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            ))?;
+            let mut insert_tag = tx.prepare_cached(
+                "INSERT OR IGNORE INTO call_tags (call_id, key, value) VALUES (?, ?, ?)",
+            )?;
+            for call in calls {
+                let inserted = insert_call.execute(params![
+                    call.id,
+                    call.timestamp.timestamp_micros(),
+                    call.context.project_id.as_ref().map(|id| &id.0),
+                    call.context.task_id.as_ref().map(|id| &id.0),
+                    call.context.agent_id.as_ref().map(|id| &id.0),
+                    call.context.session_id.as_ref().map(|id| &id.0),
+                    call.context.iteration,
+                    call.provider,
+                    call.model,
+                    call.input_tokens,
+                    call.output_tokens,
+                    call.cached_tokens,
+                    call.estimated_cost_usd,
+                    call.price_version,
+                    call.duration_ms.and_then(|d| i64::try_from(d).ok()),
+                    call.success,
+                    call.error,
+                ])?;
+                if inserted > 0 {
+                    for (key, value) in &call.context.tags {
+                        insert_tag.execute(params![call.id, key, value])?;
+                    }
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn query(&self, filter: &TrackingFilter) -> Result<Vec<TrackedCall>, CostError> {
+        let conn = self.lock();
+        let (clause, values) = where_clause(filter);
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {CALL_COLUMNS} FROM calls{clause} ORDER BY timestamp_us, rowid"
+        ))?;
+        let mut calls = stmt
+            .query_map(params_from_iter(values.iter()), call_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut tags: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT call_id, key, value FROM call_tags \
+             WHERE call_id IN (SELECT id FROM calls{clause})"
+        ))?;
+        let mut rows = stmt.query(params_from_iter(values.iter()))?;
+        while let Some(row) = rows.next()? {
+            tags.entry(row.get(0)?)
+                .or_default()
+                .insert(row.get(1)?, row.get(2)?);
+        }
+        for call in &mut calls {
+            if let Some(call_tags) = tags.remove(&call.id) {
+                call.context.tags = call_tags;
+            }
+        }
+        Ok(calls)
+    }
+
+    fn count(&self, filter: &TrackingFilter) -> Result<usize, CostError> {
+        let conn = self.lock();
+        let (clause, values) = where_clause(filter);
+        let count: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM calls{clause}"),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+        Ok(usize::try_from(count).unwrap_or(0))
+    }
+
+    fn total_cost(&self, filter: &TrackingFilter) -> Result<f64, CostError> {
+        let conn = self.lock();
+        let (clause, values) = where_clause(filter);
+        let total: Option<f64> = conn
+            .query_row(
+                &format!("SELECT SUM(cost_usd) FROM calls{clause}"),
+                params_from_iter(values.iter()),
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(total.unwrap_or(0.0))
+    }
+
+    fn distinct(&self, key: ContextKey) -> Result<Vec<String>, CostError> {
+        let conn = self.lock();
+        let column = key.column();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT DISTINCT {column} FROM calls WHERE {column} IS NOT NULL ORDER BY {column}"
+        ))?;
+        let values = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(values)
+    }
+
+    fn clear(&self) -> Result<(), CostError> {
+        self.lock()
+            .execute_batch("DELETE FROM call_tags; DELETE FROM calls;")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(id: &str, task: &str, minutes: i64) -> TrackedCall {
+        TrackedCall {
+            id: id.to_string(),
+            timestamp: DateTime::<Utc>::from_timestamp(1_750_000_000 + minutes * 60, 0).unwrap(),
+            context: TrackingContext::new()
+                .with_project("proj")
+                .with_task(task)
+                .with_agent("rex")
+                .with_iteration(1)
+                .with_tag("env", if minutes % 2 == 0 { "prod" } else { "dev" }),
+            provider: "anthropic".to_string(),
+            model: "claude-opus-4-5".to_string(),
+            input_tokens: 100,
+            output_tokens: 50,
+            cached_tokens: 10,
+            estimated_cost_usd: 0.25,
+            price_version: Some("v1".to_string()),
+            duration_ms: Some(1200),
+            success: minutes != 3,
+            error: (minutes == 3).then(|| "timeout".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sqlite_filters_match_in_memory_filters() {
+        let sqlite = SqliteStore::in_memory().unwrap();
+        let memory = MemoryStore::new();
+        let calls: Vec<_> = (0..6)
+            .map(|i| call(&format!("c{i}"), if i < 4 { "t1" } else { "t2" }, i))
+            .collect();
+        sqlite.insert_many(&calls).unwrap();
+        memory.insert_many(&calls).unwrap();
+
+        let start = calls[1].timestamp;
+        let end = calls[4].timestamp;
+        let filters = [
+            TrackingFilter::new(),
+            TrackingFilter::new().with_task("t1"),
+            TrackingFilter::new().with_success(false),
+            TrackingFilter::new().with_time_range(start, end),
+            TrackingFilter {
+                tag: Some(("env".to_string(), "prod".to_string())),
+                ..TrackingFilter::new().with_task("t2")
+            },
+        ];
+        for filter in &filters {
+            let ids = |calls: Vec<TrackedCall>| calls.into_iter().map(|c| c.id).collect::<Vec<_>>();
+            assert_eq!(
+                ids(sqlite.query(filter).unwrap()),
+                ids(memory.query(filter).unwrap()),
+                "{filter:?}"
+            );
+            assert_eq!(sqlite.count(filter).unwrap(), memory.count(filter).unwrap());
+        }
+        assert_eq!(
+            sqlite.distinct(ContextKey::Task).unwrap(),
+            vec!["t1".to_string(), "t2".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_sqlite_round_trips_calls_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("costs.db");
+        let original = call("c1", "t1", 3);
+
+        {
+            let store = SqliteStore::open(&path).unwrap();
+            store.insert(&original).unwrap();
+            // Duplicate IDs are ignored.
+            store.insert(&original).unwrap();
+        }
+
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+        let calls = store.query(&TrackingFilter::new()).unwrap();
+        assert_eq!(calls.len(), 1);
+        let loaded = &calls[0];
+        assert_eq!(loaded.timestamp, original.timestamp);
+        assert_eq!(loaded.context.tags, original.context.tags);
+        assert_eq!(loaded.error.as_deref(), Some("timeout"));
+        assert_eq!(loaded.price_version.as_deref(), Some("v1"));
+        assert!((store.total_cost(&TrackingFilter::new()).unwrap() - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("costs.db");
+        Connection::open(&path)
+            .unwrap()
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        assert!(matches!(
+            SqliteStore::open(&path),
+            Err(CostError::UnsupportedSchema { .. })
+        ));
+    }
+}
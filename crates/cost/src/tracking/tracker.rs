@@ -1,8 +1,9 @@
 //! Cost tracker for recording and querying API calls.
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::path::Path;
+use std::sync::Arc;
 
 use super::metrics::{
     AgentComparison, AgentMetrics, AggregatedMetrics, ProjectMetrics, TaskMetrics,
@@ -10,11 +11,19 @@ use super::metrics::{
 use super::models::{
     AgentId, ProjectId, SessionId, TaskId, TrackedCall, TrackingContext, TrackingFilter,
 };
+use super::store::{CallStore, ContextKey, MemoryStore, SqliteStore};
+use crate::error::CostError;
+use crate::pricing::PriceCatalog;
 
 /// A thread-safe cost tracker for recording API calls with context.
+///
+/// Calls are kept in a [`CallStore`]: in memory by default, or in a SQLite
+/// ledger via [`CostTracker::open`]. Calls recorded without an explicit cost
+/// are priced from the tracker's [`PriceCatalog`].
 #[derive(Debug, Clone)]
 pub struct CostTracker {
-    calls: Arc<RwLock<Vec<TrackedCall>>>,
+    store: Arc<dyn CallStore>,
+    catalog: Arc<PriceCatalog>,
 }
 
 impl Default for CostTracker {
@@ -24,55 +33,108 @@ impl Default for CostTracker {
 }
 
 impl CostTracker {
-    /// Create a new cost tracker.
+    /// Create a new in-memory cost tracker.
     #[must_use]
     pub fn new() -> Self {
+        Self::with_store(Arc::new(MemoryStore::new()))
+    }
+
+    /// Open a tracker backed by a SQLite ledger at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ledger cannot be opened or migrated.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, CostError> {
+        Ok(Self::with_store(Arc::new(SqliteStore::open(path)?)))
+    }
+
+    /// Create a tracker over an existing store.
+    #[must_use]
+    pub fn with_store(store: Arc<dyn CallStore>) -> Self {
         Self {
-            calls: Arc::new(RwLock::new(Vec::new())),
+            store,
+            catalog: Arc::new(PriceCatalog::default()),
         }
     }
 
-    /// Record a new API call.
+    /// Use `catalog` to price calls recorded without an explicit cost.
+    #[must_use]
+    pub fn with_catalog(mut self, catalog: PriceCatalog) -> Self {
+        self.catalog = Arc::new(catalog);
+        self
+    }
+
+    /// The price catalog used for derived costs.
+    #[must_use]
+    pub fn catalog(&self) -> &PriceCatalog {
+        &self.catalog
+    }
+
+    /// Record a new API call, logging a warning if the store fails.
     pub fn record(&self, call: TrackedCall) {
-        if let Ok(mut calls) = self.calls.write() {
-            calls.push(call);
+        if let Err(e) = self.try_record(&call) {
+            tracing::warn!("Failed to record call {}: {e}", call.id);
         }
     }
 
+    /// Record a new API call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store fails.
+    pub fn try_record(&self, call: &TrackedCall) -> Result<(), CostError> {
+        self.store.insert(call)
+    }
+
     /// Create a builder for recording a new call.
     #[must_use]
     pub fn builder(&self) -> TrackedCallBuilder {
         TrackedCallBuilder::new(self.clone())
     }
 
-    /// Query tracked calls with a filter.
+    /// Query tracked calls with a filter, logging a warning and returning
+    /// nothing if the store fails.
     #[must_use]
     pub fn query(&self, filter: &TrackingFilter) -> Vec<TrackedCall> {
-        self.calls
-            .read()
-            .map(|calls| {
-                calls
-                    .iter()
-                    .filter(|c| filter.matches(c))
-                    .cloned()
-                    .collect()
-            })
-            .unwrap_or_default()
+        self.try_query(filter).unwrap_or_else(|e| {
+            tracing::warn!("Failed to query cost store: {e}");
+            Vec::new()
+        })
+    }
+
+    /// Query tracked calls with a filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store fails.
+    pub fn try_query(&self, filter: &TrackingFilter) -> Result<Vec<TrackedCall>, CostError> {
+        self.store.query(filter)
     }
 
     /// Get all tracked calls.
     #[must_use]
     pub fn all(&self) -> Vec<TrackedCall> {
-        self.calls.read().map(|c| c.clone()).unwrap_or_default()
+        self.query(&TrackingFilter::new())
     }
 
-    /// Get aggregated metrics for calls matching a filter.
+    /// Get aggregated metrics for calls matching a filter. A failing store
+    /// yields empty metrics; use [`Self::try_metrics`] where that matters.
     #[must_use]
     pub fn metrics(&self, filter: &TrackingFilter) -> AggregatedMetrics {
         let calls = self.query(filter);
         AggregatedMetrics::from_calls(&calls)
     }
 
+    /// Get aggregated metrics for calls matching a filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store fails.
+    pub fn try_metrics(&self, filter: &TrackingFilter) -> Result<AggregatedMetrics, CostError> {
+        let calls = self.try_query(filter)?;
+        Ok(AggregatedMetrics::from_calls(&calls))
+    }
+
     /// Get metrics for a specific task.
     #[must_use]
     pub fn task_metrics(&self, task_id: impl Into<TaskId>) -> TaskMetrics {
@@ -121,74 +183,62 @@ impl CostTracker {
         AgentComparison::from_metrics(agent_metrics)
     }
 
+    fn distinct(&self, key: ContextKey) -> Vec<String> {
+        self.store.distinct(key).unwrap_or_else(|e| {
+            tracing::warn!("Failed to query cost store: {e}");
+            Vec::new()
+        })
+    }
+
     /// Get all unique task IDs.
     #[must_use]
     pub fn task_ids(&self) -> Vec<TaskId> {
-        self.calls
-            .read()
-            .map(|calls| {
-                calls
-                    .iter()
-                    .filter_map(|c| c.context.task_id.clone())
-                    .collect::<std::collections::HashSet<_>>()
-                    .into_iter()
-                    .collect()
-            })
-            .unwrap_or_default()
+        self.distinct(ContextKey::Task)
+            .into_iter()
+            .map(TaskId)
+            .collect()
     }
 
     /// Get all unique agent IDs.
     #[must_use]
     pub fn agent_ids(&self) -> Vec<AgentId> {
-        self.calls
-            .read()
-            .map(|calls| {
-                calls
-                    .iter()
-                    .filter_map(|c| c.context.agent_id.clone())
-                    .collect::<std::collections::HashSet<_>>()
-                    .into_iter()
-                    .collect()
-            })
-            .unwrap_or_default()
+        self.distinct(ContextKey::Agent)
+            .into_iter()
+            .map(AgentId)
+            .collect()
     }
 
     /// Get all unique project IDs.
     #[must_use]
     pub fn project_ids(&self) -> Vec<ProjectId> {
-        self.calls
-            .read()
-            .map(|calls| {
-                calls
-                    .iter()
-                    .filter_map(|c| c.context.project_id.clone())
-                    .collect::<std::collections::HashSet<_>>()
-                    .into_iter()
-                    .collect()
-            })
-            .unwrap_or_default()
+        self.distinct(ContextKey::Project)
+            .into_iter()
+            .map(ProjectId)
+            .collect()
     }
 
     /// Get total cost in USD.
     #[must_use]
     pub fn total_cost(&self) -> f64 {
-        self.calls
-            .read()
-            .map(|calls| calls.iter().map(|c| c.estimated_cost_usd).sum())
-            .unwrap_or(0.0)
+        self.store
+            .total_cost(&TrackingFilter::new())
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to query cost store: {e}");
+                0.0
+            })
     }
 
     /// Clear all tracked calls.
     pub fn clear(&self) {
-        if let Ok(mut calls) = self.calls.write() {
-            calls.clear();
+        if let Err(e) = self.store.clear() {
+            tracing::warn!("Failed to clear cost store: {e}");
         }
     }
 
     /// Get count of tracked calls.
     #[must_use]
     pub fn len(&self) -> usize {
-        self.calls.read().map(|c| c.len()).unwrap_or(0)
+        self.store.count(&TrackingFilter::new()).unwrap_or(0)
     }
 
     /// Check if tracker is empty.
@@ -202,35 +252,34 @@ impl CostTracker {
     /// # Errors
     ///
     /// Returns an error if serialization fails.
-    pub fn export_json(&self) -> Result<String, serde_json::Error> {
+    pub fn export_json(&self) -> Result<String, CostError> {
         let calls = self.all();
-        serde_json::to_string_pretty(&calls)
+        Ok(serde_json::to_string_pretty(&calls)?)
     }
 
-    /// Import calls from JSON.
+    /// Import calls from JSON. Calls whose IDs are already tracked are skipped.
     ///
     /// # Errors
     ///
-    /// Returns an error if deserialization fails.
-    pub fn import_json(&self, json: &str) -> Result<(), serde_json::Error> {
+    /// Returns an error if deserialization or the store fails.
+    pub fn import_json(&self, json: &str) -> Result<(), CostError> {
         let calls: Vec<TrackedCall> = serde_json::from_str(json)?;
-        if let Ok(mut existing) = self.calls.write() {
-            existing.extend(calls);
-        }
-        Ok(())
+        self.store.insert_many(&calls)
     }
 }
 
 /// Builder for creating tracked calls.
 pub struct TrackedCallBuilder {
     tracker: CostTracker,
+    id: Option<String>,
     context: TrackingContext,
     provider: String,
     model: String,
     input_tokens: i64,
     output_tokens: i64,
     cached_tokens: i64,
-    estimated_cost_usd: f64,
+    estimated_cost_usd: Option<f64>,
+    timestamp: Option<DateTime<Utc>>,
     duration_ms: Option<u64>,
     success: bool,
     error: Option<String>,
@@ -240,13 +289,15 @@ impl TrackedCallBuilder {
     fn new(tracker: CostTracker) -> Self {
         Self {
             tracker,
+            id: None,
             context: TrackingContext::new(),
             provider: String::new(),
             model: String::new(),
             input_tokens: 0,
             output_tokens: 0,
             cached_tokens: 0,
-            estimated_cost_usd: 0.0,
+            estimated_cost_usd: None,
+            timestamp: None,
             duration_ms: None,
             success: true,
             error: None,
@@ -330,10 +381,27 @@ impl TrackedCallBuilder {
         self
     }
 
-    /// Set the estimated cost in USD.
+    /// Set the estimated cost in USD instead of deriving it from the catalog.
     #[must_use]
     pub fn cost(mut self, cost_usd: f64) -> Self {
-        self.estimated_cost_usd = cost_usd;
+        self.estimated_cost_usd = Some(cost_usd);
+        self
+    }
+
+    /// Set the call ID (defaults to a random UUID).
+    ///
+    /// Stores ignore calls whose ID they already hold, so a stable ID lets
+    /// replayed streams be ingested idempotently.
+    #[must_use]
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Set when the call was made (defaults to now).
+    #[must_use]
+    pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = Some(timestamp);
         self
     }
 
@@ -361,18 +429,40 @@ impl TrackedCallBuilder {
     }
 
     /// Record the call and return the tracked call.
+    ///
+    /// Without an explicit [`cost`](Self::cost), the cost is derived from the
+    /// tracker's price catalog, or left at zero for unknown models.
     #[must_use]
     pub fn record(self) -> TrackedCall {
+        let timestamp = self.timestamp.unwrap_or_else(Utc::now);
+        let (estimated_cost_usd, price_version) = match self.estimated_cost_usd {
+            Some(cost) => (cost, None),
+            None => match self.tracker.catalog.quote(
+                &self.provider,
+                &self.model,
+                self.input_tokens,
+                self.output_tokens,
+                self.cached_tokens,
+                timestamp,
+            ) {
+                Some(quote) => (quote.cost_usd, Some(quote.version)),
+                None => {
+                    tracing::debug!("No price for {}/{}", self.provider, self.model);
+                    (0.0, None)
+                }
+            },
+        };
         let call = TrackedCall {
-            id: uuid::Uuid::new_v4().to_string(),
-            timestamp: Utc::now(),
+            id: self.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            timestamp,
             context: self.context,
             provider: self.provider,
             model: self.model,
             input_tokens: self.input_tokens,
             output_tokens: self.output_tokens,
             cached_tokens: self.cached_tokens,
-            estimated_cost_usd: self.estimated_cost_usd,
+            estimated_cost_usd,
+            price_version,
             duration_ms: self.duration_ms,
             success: self.success,
             error: self.error,
@@ -504,24 +594,60 @@ mod tests {
 
         assert_eq!(tracker2.len(), 1);
     }
-}
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
 
+    #[test]
+    fn test_cost_derived_from_catalog() {
+        let tracker = CostTracker::new();
 
+        let call = tracker
+            .builder()
+            .provider("anthropic")
+            .model("claude-opus-4-5-20251101")
+            .input_tokens(1_000_000)
+            .cached_tokens(400_000)
+            .output_tokens(100_000)
+            .record();
+        // 600k * $5 + 400k * $0.50 + 100k * $25 per million
+        assert!((call.estimated_cost_usd - 5.7).abs() < 1e-9);
+        assert_eq!(
+            call.price_version.as_deref(),
+            Some(tracker.catalog().latest().unwrap().version.as_str())
+        );
 
+        let unknown = tracker
+            .builder()
+            .model("local-llama")
+            .input_tokens(10)
+            .record();
+        assert!(unknown.estimated_cost_usd.abs() < f64::EPSILON);
+        assert!(unknown.price_version.is_none());
+    }
 
+    #[test]
+    fn test_sqlite_tracker_persists_metrics() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("costs.db");
+
+        {
+            let tracker = CostTracker::open(&path).unwrap();
+            for i in 1..=2 {
+                let _ = tracker
+                    .builder()
+                    .project("proj-1")
+                    .task("task-1")
+                    .agent("rex")
+                    .iteration(i)
+                    .cost(0.05)
+                    .record();
+            }
+        }
 
+        let tracker = CostTracker::open(&path).unwrap();
+        assert_eq!(tracker.len(), 2);
+        let metrics = tracker.task_metrics("task-1");
+        assert_eq!(metrics.iterations, 2);
+        assert!((metrics.total_cost_usd - 0.10).abs() < 1e-9);
+        assert_eq!(tracker.agent_ids(), vec![AgentId::new("rex")]);
+        assert!((tracker.total_cost() - 0.10).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,47 @@
+-- Spaces, sessions and tasks for the experience learning pipeline.
+
+CREATE TABLE IF NOT EXISTS experience_spaces (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    project_id TEXT,
+    user_id TEXT,
+    metadata JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS experience_spaces_project_idx
+    ON experience_spaces (project_id, created_at)
+    WHERE project_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS experience_sessions (
+    id UUID PRIMARY KEY,
+    play_id TEXT NOT NULL,
+    space_id UUID NOT NULL REFERENCES experience_spaces (id) ON DELETE CASCADE,
+    status TEXT NOT NULL,
+    messages JSONB NOT NULL DEFAULT '[]'::jsonb,
+    repository TEXT,
+    service TEXT,
+    metadata JSONB NOT NULL DEFAULT '{}'::jsonb,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS experience_sessions_play_idx
+    ON experience_sessions (play_id, started_at DESC);
+
+CREATE TABLE IF NOT EXISTS experience_tasks (
+    uuid UUID PRIMARY KEY,
+    session_id UUID NOT NULL REFERENCES experience_sessions (id) ON DELETE CASCADE,
+    task_id TEXT NOT NULL,
+    description TEXT NOT NULL,
+    status TEXT NOT NULL,
+    progresses JSONB NOT NULL DEFAULT '[]'::jsonb,
+    user_preferences JSONB NOT NULL DEFAULT '[]'::jsonb,
+    tool_calls JSONB NOT NULL DEFAULT '[]'::jsonb,
+    agent TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS experience_tasks_session_idx
+    ON experience_tasks (session_id, started_at);
@@ -0,0 +1,24 @@
+-- Learned skills with pgvector embeddings.
+--
+-- The embedding column is dimensionless so the embedding model can change
+-- without a migration. Searches are scoped to one space and use an exact
+-- cosine-distance scan, which stays cheap at per-space skill counts.
+
+CREATE EXTENSION IF NOT EXISTS vector;
+
+CREATE TABLE IF NOT EXISTS experience_skills (
+    id UUID PRIMARY KEY,
+    space_id UUID NOT NULL REFERENCES experience_spaces (id) ON DELETE CASCADE,
+    use_when TEXT NOT NULL,
+    agent TEXT NOT NULL,
+    preferences JSONB NOT NULL DEFAULT '[]'::jsonb,
+    tool_sops JSONB NOT NULL DEFAULT '[]'::jsonb,
+    complexity_score REAL NOT NULL,
+    success_count INTEGER NOT NULL DEFAULT 1,
+    embedding vector,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS experience_skills_space_idx
+    ON experience_skills (space_id, success_count DESC, updated_at DESC);
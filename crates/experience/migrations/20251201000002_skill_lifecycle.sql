@@ -0,0 +1,40 @@
+-- Skill lifecycle: versions, outcome feedback and deduplication.
+--
+-- Each skill keeps a snapshot per SOP version, and every session that used
+-- a skill can record whether it succeeded. Skills merged into a canonical
+-- skill keep their row (and outcomes) but point at it via merged_into.
+
+ALTER TABLE experience_skills
+    ADD COLUMN IF NOT EXISTS failure_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN IF NOT EXISTS last_outcome_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS merged_into UUID REFERENCES experience_skills (id) ON DELETE SET NULL;
+
+CREATE TABLE IF NOT EXISTS experience_skill_versions (
+    skill_id UUID NOT NULL REFERENCES experience_skills (id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    use_when TEXT NOT NULL,
+    preferences JSONB NOT NULL DEFAULT '[]'::jsonb,
+    tool_sops JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (skill_id, version)
+);
+
+CREATE TABLE IF NOT EXISTS experience_skill_outcomes (
+    id UUID PRIMARY KEY,
+    skill_id UUID NOT NULL REFERENCES experience_skills (id) ON DELETE CASCADE,
+    skill_version INTEGER NOT NULL,
+    session_id UUID NOT NULL,
+    success BOOLEAN NOT NULL,
+    notes TEXT,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS experience_skill_outcomes_skill_idx
+    ON experience_skill_outcomes (skill_id, recorded_at);
+
+-- Backfill version 1 snapshots for skills learned before versioning.
+INSERT INTO experience_skill_versions (skill_id, version, use_when, preferences, tool_sops, created_at)
+SELECT id, version, use_when, preferences, tool_sops, created_at
+FROM experience_skills
+ON CONFLICT DO NOTHING;
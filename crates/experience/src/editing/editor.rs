@@ -0,0 +1,494 @@
+//! Applies edit strategies to a message history.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use tracing::{debug, warn};
+
+use super::strategies::{EditResult, EditStrategy};
+use super::token_counter::TokenCounter;
+use crate::learning::LlmClient;
+use crate::models::MessageRecord;
+
+/// Per-message framing overhead (role, separators) added to content tokens.
+const MESSAGE_OVERHEAD_TOKENS: u32 = 4;
+
+/// Content left in place of a removed tool result.
+const REMOVED_TOOL_RESULT: &str = "[tool result removed]";
+
+/// Marker appended to truncated content.
+const TRUNCATED_MARKER: &str = " [truncated]";
+
+/// Messages after editing, with statistics.
+#[derive(Debug, Clone)]
+pub struct EditedContext {
+    /// Edited messages, with `token_count` recomputed.
+    pub messages: Vec<MessageRecord>,
+
+    /// Statistics for the edit.
+    pub result: EditResult,
+}
+
+/// Applies [`EditStrategy`] lists to message histories.
+///
+/// Edits never separate a tool call from its results: strategies that drop
+/// or summarize messages work on whole call/result groups, and strategies
+/// that would drop a lone tool result blank its content instead.
+pub struct ContextEditor {
+    counter: TokenCounter,
+    summarizer: Option<Arc<dyn LlmClient>>,
+    summary_cache: Mutex<HashMap<u64, String>>,
+}
+
+impl ContextEditor {
+    /// Create an editor counting tokens with `counter`.
+    #[must_use]
+    pub fn new(counter: TokenCounter) -> Self {
+        Self {
+            counter,
+            summarizer: None,
+            summary_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create an editor counting tokens for `model`.
+    #[must_use]
+    pub fn for_model(model: &str) -> Self {
+        Self::new(TokenCounter::for_model(model))
+    }
+
+    /// Use `client` for [`EditStrategy::SummarizeOlder`].
+    ///
+    /// Without a summarizer that strategy is skipped.
+    #[must_use]
+    pub fn with_summarizer(mut self, client: Arc<dyn LlmClient>) -> Self {
+        self.summarizer = Some(client);
+        self
+    }
+
+    /// Tokens a message occupies in the context.
+    #[must_use]
+    pub fn message_tokens(&self, message: &MessageRecord) -> u32 {
+        self.counter.count(&message.content) + MESSAGE_OVERHEAD_TOKENS
+    }
+
+    /// Tokens the messages occupy in the context.
+    #[must_use]
+    pub fn total_tokens(&self, messages: &[MessageRecord]) -> u32 {
+        messages.iter().map(|m| self.message_tokens(m)).sum()
+    }
+
+    /// Apply `strategies` in order.
+    ///
+    /// A strategy that cannot run (no summarizer, summarizer failure) is
+    /// skipped and left out of [`EditResult::strategies_applied`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a [`EditStrategy::RemovePattern`] regex is
+    /// invalid.
+    pub async fn apply(
+        &self,
+        messages: &[MessageRecord],
+        strategies: &[EditStrategy],
+    ) -> Result<EditedContext> {
+        let mut result = EditResult::new(self.total_tokens(messages), messages.len());
+        let mut edited = messages.to_vec();
+
+        for strategy in strategies {
+            let applied = match strategy {
+                EditStrategy::TokenLimit { limit } => {
+                    self.token_limit(&mut edited, *limit);
+                    true
+                }
+                EditStrategy::RemoveToolResult { keep_recent } => {
+                    remove_tool_results(&mut edited, *keep_recent);
+                    true
+                }
+                EditStrategy::SummarizeOlder {
+                    older_than_messages,
+                } => {
+                    self.summarize_older(&mut edited, *older_than_messages)
+                        .await
+                }
+                EditStrategy::RemovePattern { pattern } => {
+                    let regex = Regex::new(pattern)
+                        .with_context(|| format!("invalid RemovePattern regex '{pattern}'"))?;
+                    remove_pattern(&mut edited, &regex);
+                    true
+                }
+                EditStrategy::KeepRecent { count } => {
+                    let start =
+                        group_start_at_or_before(&edited, edited.len().saturating_sub(*count));
+                    edited.drain(..start);
+                    true
+                }
+                EditStrategy::TruncateLong { max_chars } => {
+                    truncate_long(&mut edited, *max_chars);
+                    true
+                }
+            };
+            if applied {
+                result.strategies_applied.push(strategy.description());
+            }
+        }
+
+        for message in &mut edited {
+            message.token_count = self.message_tokens(message);
+        }
+        result.edited_tokens = self.total_tokens(&edited);
+        result.edited_messages = edited.len();
+        Ok(EditedContext {
+            messages: edited,
+            result,
+        })
+    }
+
+    /// Drop the oldest groups until the total fits in `limit`.
+    ///
+    /// The most recent group is always kept, even if it alone exceeds the
+    /// limit; combine with `TruncateLong` to bound it.
+    fn token_limit(&self, messages: &mut Vec<MessageRecord>, limit: u32) {
+        let mut total = self.total_tokens(messages);
+        let groups = tool_groups(messages);
+        let mut drop_until = 0;
+        for group in &groups[..groups.len().saturating_sub(1)] {
+            if total <= limit {
+                break;
+            }
+            total -= self.total_tokens(&messages[group.clone()]);
+            drop_until = group.end;
+        }
+        messages.drain(..drop_until);
+    }
+
+    /// Replace all but the last `keep_recent` messages with one summary.
+    async fn summarize_older(&self, messages: &mut Vec<MessageRecord>, keep_recent: usize) -> bool {
+        let Some(client) = &self.summarizer else {
+            warn!("SummarizeOlder requested without a summarizer, skipping");
+            return false;
+        };
+        let split = group_start_at_or_before(messages, messages.len().saturating_sub(keep_recent));
+        if split == 0 {
+            return true;
+        }
+
+        let older = &messages[..split];
+        let key = cache_key(older);
+        let cached = self
+            .summary_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+            .cloned();
+        let summary = if let Some(summary) = cached {
+            debug!(messages = split, "Using cached summary");
+            summary
+        } else {
+            match client.summarize_messages(older).await {
+                Ok(summary) => {
+                    self.summary_cache
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .insert(key, summary.clone());
+                    summary
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to summarize older messages, skipping");
+                    return false;
+                }
+            }
+        };
+
+        let mut summary_message =
+            MessageRecord::user(format!("[Summary of {split} earlier messages]\n{summary}"));
+        summary_message.timestamp = messages[split - 1].timestamp;
+        messages.splice(..split, [summary_message]);
+        true
+    }
+}
+
+impl Default for ContextEditor {
+    fn default() -> Self {
+        Self::new(TokenCounter::default())
+    }
+}
+
+/// Whether a message is a tool call (as opposed to a tool result).
+fn is_tool_call(message: &MessageRecord) -> bool {
+    message.role != "tool" && message.tool_call_id.is_some()
+}
+
+/// Split messages into contiguous groups that must be kept or dropped
+/// together: a tool call through its last result (merging overlapping
+/// calls), or a single message.
+fn tool_groups(messages: &[MessageRecord]) -> Vec<Range<usize>> {
+    let mut last_result: HashMap<&str, usize> = HashMap::new();
+    for (index, message) in messages.iter().enumerate() {
+        if message.role == "tool" {
+            if let Some(id) = &message.tool_call_id {
+                last_result.insert(id, index);
+            }
+        }
+    }
+
+    let mut groups = Vec::new();
+    let mut start = 0;
+    while start < messages.len() {
+        let mut end = start;
+        let mut index = start;
+        while index <= end {
+            let message = &messages[index];
+            if is_tool_call(message) {
+                let id = message.tool_call_id.as_deref().unwrap_or_default();
+                if let Some(&result) = last_result.get(id) {
+                    end = end.max(result);
+                }
+            }
+            index += 1;
+        }
+        groups.push(start..end + 1);
+        start = end + 1;
+    }
+    groups
+}
+
+/// The latest group boundary at or before `index`.
+fn group_start_at_or_before(messages: &[MessageRecord], index: usize) -> usize {
+    tool_groups(messages)
+        .iter()
+        .map(|group| group.start)
+        .chain(std::iter::once(messages.len()))
+        .take_while(|start| *start <= index)
+        .last()
+        .unwrap_or(0)
+}
+
+fn remove_tool_results(messages: &mut [MessageRecord], keep_recent: usize) {
+    let results: Vec<usize> = messages
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.role == "tool")
+        .map(|(i, _)| i)
+        .collect();
+    for &index in &results[..results.len().saturating_sub(keep_recent)] {
+        messages[index].content = REMOVED_TOOL_RESULT.to_string();
+    }
+}
+
+/// Remove groups whose leading message matches `regex`.
+///
+/// Tool results travel with their call, so a matching result on its own is
+/// blanked rather than removed.
+fn remove_pattern(messages: &mut Vec<MessageRecord>, regex: &Regex) {
+    let mut kept = Vec::with_capacity(messages.len());
+    for group in tool_groups(messages) {
+        let lead = &messages[group.start];
+        if regex.is_match(&lead.content) && lead.role != "tool" {
+            continue;
+        }
+        for message in &messages[group] {
+            let mut message = message.clone();
+            if message.role == "tool" && regex.is_match(&message.content) {
+                message.content = REMOVED_TOOL_RESULT.to_string();
+            }
+            kept.push(message);
+        }
+    }
+    *messages = kept;
+}
+
+fn truncate_long(messages: &mut [MessageRecord], max_chars: usize) {
+    for message in messages {
+        if let Some((cut, _)) = message.content.char_indices().nth(max_chars) {
+            message.content.truncate(cut);
+            message.content.push_str(TRUNCATED_MARKER);
+        }
+    }
+}
+
+fn cache_key(messages: &[MessageRecord]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for message in messages {
+        message.role.hash(&mut hasher);
+        message.content.hash(&mut hasher);
+        message.tool_call_id.hash(&mut hasher);
+        message.tool_name.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskRecord, ToolStep};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingSummarizer {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LlmClient for CountingSummarizer {
+        async fn generate_use_when(
+            &self,
+            _task: &TaskRecord,
+            _steps: &[ToolStep],
+        ) -> Result<String> {
+            Ok(String::new())
+        }
+
+        async fn generate_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+            Ok(Vec::new())
+        }
+
+        async fn summarize_messages(&self, messages: &[MessageRecord]) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("{} messages about the handler", messages.len()))
+        }
+    }
+
+    fn tool_call(id: &str) -> MessageRecord {
+        let mut message = MessageRecord::assistant(format!("calling {id}"));
+        message.tool_call_id = Some(id.to_string());
+        message.tool_name = Some("read_file".to_string());
+        message
+    }
+
+    fn conversation() -> Vec<MessageRecord> {
+        vec![
+            MessageRecord::user("implement the handler"),
+            tool_call("a"),
+            MessageRecord::tool("a", "fn main() {}"),
+            MessageRecord::assistant("looks good"),
+            tool_call("b"),
+            tool_call("c"),
+            MessageRecord::tool("b", "Cargo.toml contents"),
+            MessageRecord::tool("c", "README contents"),
+            MessageRecord::assistant("done"),
+        ]
+    }
+
+    #[test]
+    fn test_tool_groups_keep_calls_with_results() {
+        let groups = tool_groups(&conversation());
+        assert_eq!(groups, vec![0..1, 1..3, 3..4, 4..8, 8..9]);
+    }
+
+    #[tokio::test]
+    async fn test_keep_recent_does_not_split_tool_pairs() {
+        let editor = ContextEditor::default();
+        let edited = editor
+            .apply(&conversation(), &[EditStrategy::KeepRecent { count: 3 }])
+            .await
+            .unwrap();
+        // Keeping 3 would start at result "b"; back up to its call.
+        assert_eq!(edited.messages.len(), 5);
+        assert_eq!(edited.messages[0].tool_call_id.as_deref(), Some("b"));
+    }
+
+    #[tokio::test]
+    async fn test_summarize_older_uses_llm_and_caches() {
+        let summarizer = Arc::new(CountingSummarizer::default());
+        let editor = ContextEditor::default().with_summarizer(summarizer.clone());
+        let strategy = [EditStrategy::SummarizeOlder {
+            older_than_messages: 4,
+        }];
+
+        let edited = editor.apply(&conversation(), &strategy).await.unwrap();
+        assert_eq!(edited.messages.len(), 6);
+        assert!(edited.messages[0]
+            .content
+            .contains("4 messages about the handler"));
+        assert_eq!(edited.messages[1].tool_call_id.as_deref(), Some("b"));
+        assert_eq!(edited.result.strategies_applied.len(), 1);
+
+        editor.apply(&conversation(), &strategy).await.unwrap();
+        assert_eq!(summarizer.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_older_skipped_without_summarizer() {
+        let edited = ContextEditor::default()
+            .apply(
+                &conversation(),
+                &[EditStrategy::SummarizeOlder {
+                    older_than_messages: 2,
+                }],
+            )
+            .await
+            .unwrap();
+        assert_eq!(edited.messages.len(), 9);
+        assert!(edited.result.strategies_applied.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_token_limit_fits_and_keeps_latest() {
+        let editor = ContextEditor::for_model("claude-sonnet-4-5");
+        let mut messages = conversation();
+        messages[0].content = "word ".repeat(200);
+        let limit = editor.total_tokens(&messages[1..]);
+
+        let edited = editor
+            .apply(&messages, &[EditStrategy::TokenLimit { limit }])
+            .await
+            .unwrap();
+        assert!(edited.result.edited_tokens <= limit);
+        assert_eq!(edited.messages.len(), 8);
+
+        let edited = editor
+            .apply(&messages, &[EditStrategy::TokenLimit { limit: 1 }])
+            .await
+            .unwrap();
+        assert_eq!(edited.messages.len(), 1);
+        assert_eq!(edited.messages[0].content, "done");
+    }
+
+    #[tokio::test]
+    async fn test_tool_result_strategies_keep_pairs() {
+        let editor = ContextEditor::default();
+        let edited = editor
+            .apply(
+                &conversation(),
+                &[
+                    EditStrategy::RemoveToolResult { keep_recent: 1 },
+                    EditStrategy::RemovePattern {
+                        pattern: "^calling a$".to_string(),
+                    },
+                    EditStrategy::TruncateLong { max_chars: 6 },
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(edited.messages.len(), 7);
+        let results: Vec<&str> = edited
+            .messages
+            .iter()
+            .filter(|m| m.role == "tool")
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(results, vec!["[tool  [truncated]", "README [truncated]"]);
+        assert!(edited.messages.iter().all(|m| m.token_count > 0));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_pattern_errors() {
+        let result = ContextEditor::default()
+            .apply(
+                &conversation(),
+                &[EditStrategy::RemovePattern {
+                    pattern: "(".to_string(),
+                }],
+            )
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -3,8 +3,10 @@
 //! Declarative strategies for managing LLM context windows without
 //! modifying the original session data.
 
+mod editor;
 mod strategies;
 mod token_counter;
 
+pub use editor::{ContextEditor, EditedContext};
 pub use strategies::{EditParams, EditResult, EditStrategy};
-pub use token_counter::TokenCounter;
+pub use token_counter::{ModelFamily, TokenCounter};
@@ -1,26 +1,117 @@
 //! Token counting utilities.
+//!
+//! `OpenAI` models are counted exactly with their own BPE. Other families have
+//! no public tokenizer, so their counts are calibrated estimates from a
+//! close BPE, scaled up so `TokenLimit` edits err on the side of fitting.
 
-use tiktoken_rs::cl100k_base;
+use tiktoken_rs::{cl100k_base_singleton, o200k_base_singleton, CoreBPE};
+
+/// Model family, which determines how tokens are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelFamily {
+    /// GPT-4o and later, o-series and Codex models (`o200k_base`).
+    OpenAi,
+    /// GPT-4 and GPT-3.5 (`cl100k_base`).
+    OpenAiLegacy,
+    /// Claude models.
+    Anthropic,
+    /// Gemini and Gemma models.
+    Gemini,
+    /// Anything else, e.g. open-weight models behind compatible endpoints.
+    Other,
+}
+
+impl ModelFamily {
+    /// Infer the family from a model name.
+    ///
+    /// Provider prefixes such as `anthropic/` or
+    /// `accounts/fireworks/models/` are ignored.
+    #[must_use]
+    pub fn from_model(model: &str) -> Self {
+        let lowered = model.to_lowercase();
+        let name = lowered.rsplit('/').next().unwrap_or_default();
+
+        if name.starts_with("claude") {
+            Self::Anthropic
+        } else if name.starts_with("gemini") || name.starts_with("gemma") {
+            Self::Gemini
+        } else if ["gpt-4o", "gpt-4.1", "gpt-4.5", "gpt-5", "chatgpt", "codex"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+            || is_o_series(name)
+        {
+            Self::OpenAi
+        } else if name.starts_with("gpt-4") || name.starts_with("gpt-3.5") {
+            Self::OpenAiLegacy
+        } else {
+            Self::Other
+        }
+    }
+
+    /// BPE used for counting and the factor applied to its counts.
+    fn tokenizer(self) -> (&'static CoreBPE, f32) {
+        match self {
+            Self::OpenAi => (o200k_base_singleton(), 1.0),
+            Self::OpenAiLegacy => (cl100k_base_singleton(), 1.0),
+            // Claude's tokenizer yields roughly 15-20% more tokens than
+            // cl100k on mixed code and prose.
+            Self::Anthropic => (cl100k_base_singleton(), 1.2),
+            // Gemini's 256k vocabulary is close to o200k in density.
+            Self::Gemini => (o200k_base_singleton(), 1.1),
+            Self::Other => (cl100k_base_singleton(), 1.1),
+        }
+    }
+}
+
+/// `o1`, `o3-mini`, `o4-mini` and friends.
+fn is_o_series(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next() == Some('o') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
 
 /// Token counter using tiktoken.
 pub struct TokenCounter {
-    bpe: tiktoken_rs::CoreBPE,
+    bpe: &'static CoreBPE,
+    scale: f32,
 }
 
 impl TokenCounter {
-    /// Create a new token counter.
+    /// Create a token counter using `cl100k_base` without calibration.
     #[must_use]
     pub fn new() -> Self {
         Self {
-            bpe: cl100k_base().expect("Failed to load tiktoken"),
+            bpe: cl100k_base_singleton(),
+            scale: 1.0,
         }
     }
 
+    /// Create a token counter for a model family.
+    #[must_use]
+    pub fn for_family(family: ModelFamily) -> Self {
+        let (bpe, scale) = family.tokenizer();
+        Self { bpe, scale }
+    }
+
+    /// Create a token counter for a model name.
+    #[must_use]
+    pub fn for_model(model: &str) -> Self {
+        Self::for_family(ModelFamily::from_model(model))
+    }
+
     /// Count tokens in a string.
     #[must_use]
-    #[allow(clippy::cast_possible_truncation)] // Token count won't exceed u32::MAX for any practical input
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )] // Token count won't exceed u32::MAX for any practical input
     pub fn count(&self, text: &str) -> u32 {
-        self.bpe.encode_ordinary(text).len() as u32
+        let raw = self.bpe.encode_ordinary(text).len() as u32;
+        if (self.scale - 1.0).abs() < f32::EPSILON {
+            raw
+        } else {
+            (raw as f32 * self.scale).ceil() as u32
+        }
     }
 
     /// Count tokens in multiple strings.
@@ -77,4 +168,40 @@ mod tests {
             1
         ));
     }
+
+    #[test]
+    fn test_model_family_detection() {
+        assert_eq!(
+            ModelFamily::from_model("claude-sonnet-4-5"),
+            ModelFamily::Anthropic
+        );
+        assert_eq!(
+            ModelFamily::from_model("anthropic/claude-opus-4"),
+            ModelFamily::Anthropic
+        );
+        assert_eq!(ModelFamily::from_model("gpt-5-codex"), ModelFamily::OpenAi);
+        assert_eq!(ModelFamily::from_model("o4-mini"), ModelFamily::OpenAi);
+        assert_eq!(
+            ModelFamily::from_model("gpt-4-turbo"),
+            ModelFamily::OpenAiLegacy
+        );
+        assert_eq!(
+            ModelFamily::from_model("gemini-2.5-pro"),
+            ModelFamily::Gemini
+        );
+        assert_eq!(
+            ModelFamily::from_model("accounts/fireworks/models/kimi-k2"),
+            ModelFamily::Other
+        );
+        assert_eq!(ModelFamily::from_model("opus"), ModelFamily::Other);
+    }
+
+    #[test]
+    fn test_calibrated_families_count_high() {
+        let text = "fn main() { println!(\"estimating tokens for a Claude model\"); }";
+        let exact = TokenCounter::new().count(text);
+        let claude = TokenCounter::for_model("claude-sonnet-4-5").count(text);
+        assert!(claude > exact);
+        assert_eq!(TokenCounter::for_model("gpt-4").count(text), exact);
+    }
 }
@@ -0,0 +1,605 @@
+//! Export learned skills as `SKILL.md` packages for the controller.
+//!
+//! Agents only consume skills through the controller's skills cache, which
+//! downloads `<agent>-<project>.tar.gz` release assets listed in `hashes.txt`
+//! and extracts `<agent>/<skill_name>/SKILL.md` from them. This module renders
+//! learned [`Skill`]s into that layout.
+//!
+//! Those tarballs also carry the curated `_default` skills and project
+//! overrides, so [`write_release`] merges learned skills into the current
+//! release assets rather than replacing them.
+//!
+//! Nothing is exported without review: a [`SkillExporter`] only packages
+//! skills whose current version has been approved, so a skill that changes
+//! after review drops out until it is approved again.
+//!
+//! Learned skills are named `learned-<slug>-<id>` so they never collide with
+//! hand-written skills when the tree is copied into the skills repo.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::learning::LifecycleConfig;
+use crate::models::{AgentType, Skill, Space};
+use crate::storage::ExperienceStore;
+
+/// Project label used for skills not scoped to a project.
+pub const DEFAULT_PROJECT: &str = "default";
+
+/// Name of the release manifest listing each tarball's sha256.
+pub const HASHES_FILE: &str = "hashes.txt";
+
+/// Longest slug taken from `use_when` for a skill directory name.
+const MAX_SLUG_LEN: usize = 48;
+
+/// Directory name for a learned skill.
+#[must_use]
+pub fn skill_name(skill: &Skill) -> String {
+    let mut slug = String::new();
+    for word in skill
+        .use_when
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        if slug.len() + word.len() + 1 > MAX_SLUG_LEN {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(word);
+    }
+    let id = skill.id.simple().to_string();
+    if slug.is_empty() {
+        format!("learned-{}", &id[..8])
+    } else {
+        format!("learned-{slug}-{}", &id[..8])
+    }
+}
+
+/// Render a skill as `SKILL.md` with frontmatter.
+#[must_use]
+pub fn render_skill_md(skill: &Skill) -> String {
+    let metadata = json!({
+        "experience": {
+            "skill_id": skill.id,
+            "version": skill.version,
+            "success_count": skill.success_count,
+            "failure_count": skill.failure_count,
+        }
+    });
+
+    let mut out = String::from("---\n");
+    let _ = writeln!(out, "name: {}", skill_name(skill));
+    // JSON strings are valid YAML double-quoted scalars.
+    let _ = writeln!(
+        out,
+        "description: {}",
+        serde_json::Value::String(format!("Use when {}", skill.use_when))
+    );
+    let _ = writeln!(out, "metadata: {metadata}");
+    out.push_str("---\n\n");
+    out.push_str(&skill.to_prompt_summary().replacen("### ", "# ", 1));
+    out
+}
+
+/// Skills for one agent and project, ready to be packaged.
+#[derive(Debug, Clone)]
+pub struct SkillPackage {
+    /// Agent the skills belong to.
+    pub agent: AgentType,
+
+    /// Project label, [`DEFAULT_PROJECT`] when unscoped.
+    pub project: String,
+
+    /// Rendered `SKILL.md` content keyed by skill name.
+    pub skills: BTreeMap<String, String>,
+}
+
+impl SkillPackage {
+    /// Release asset name, e.g. `rex-default.tar.gz`.
+    #[must_use]
+    pub fn asset_name(&self) -> String {
+        format!("{}-{}.tar.gz", self.agent, self.project)
+    }
+
+    /// Build the gzipped tarball.
+    ///
+    /// Entries are sorted and carry no timestamps, so the same skills always
+    /// produce the same bytes and the controller only re-downloads on change.
+    pub fn to_tarball(&self) -> Result<Vec<u8>> {
+        build_tarball(&[], &self.agent.to_string(), &self.skills)
+    }
+
+    /// Write `<agent>/<skill_name>/SKILL.md` files under `root`, e.g. for a
+    /// pull request against the skills repo.
+    pub fn write_tree(&self, root: &Path) -> Result<()> {
+        for (name, content) in &self.skills {
+            let dir = root.join(self.agent.to_string()).join(name);
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+            fs::write(dir.join("SKILL.md"), content)
+                .with_context(|| format!("failed to write SKILL.md for '{name}'"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders approved skills into per-agent packages.
+#[derive(Debug, Clone, Default)]
+pub struct SkillExporter {
+    approved: HashSet<(Uuid, u32)>,
+    lifecycle: LifecycleConfig,
+}
+
+impl SkillExporter {
+    /// Create an exporter with nothing approved.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use custom confidence settings when filtering.
+    #[must_use]
+    pub fn with_lifecycle(mut self, lifecycle: LifecycleConfig) -> Self {
+        self.lifecycle = lifecycle;
+        self
+    }
+
+    /// Approve the skill at its current version.
+    pub fn approve(&mut self, skill: &Skill) {
+        self.approve_version(skill.id, skill.version);
+    }
+
+    /// Approve a specific version of a skill.
+    pub fn approve_version(&mut self, skill_id: Uuid, version: u32) {
+        self.approved.insert((skill_id, version));
+    }
+
+    /// Whether the skill's current version has been approved.
+    #[must_use]
+    pub fn is_approved(&self, skill: &Skill) -> bool {
+        self.approved.contains(&(skill.id, skill.version))
+    }
+
+    /// Package the skills of one project, grouped by agent.
+    ///
+    /// Unapproved and merged skills, and skills whose confidence has fallen
+    /// below the search threshold, are left out. `project` is `None` for
+    /// skills that apply to every project.
+    #[must_use]
+    pub fn package(
+        &self,
+        project: Option<&str>,
+        skills: &[Skill],
+        now: DateTime<Utc>,
+    ) -> Vec<SkillPackage> {
+        let project = project.unwrap_or(DEFAULT_PROJECT);
+        let mut by_agent: BTreeMap<String, SkillPackage> = BTreeMap::new();
+
+        for skill in skills {
+            if !self.is_approved(skill) || skill.is_merged() {
+                debug!(skill_id = %skill.id, "Skipping unapproved or merged skill");
+                continue;
+            }
+            if self.lifecycle.confidence(skill, now) < self.lifecycle.min_confidence {
+                debug!(skill_id = %skill.id, "Skipping low-confidence skill");
+                continue;
+            }
+            by_agent
+                .entry(skill.agent.to_string())
+                .or_insert_with(|| SkillPackage {
+                    agent: skill.agent,
+                    project: project.to_string(),
+                    skills: BTreeMap::new(),
+                })
+                .skills
+                .insert(skill_name(skill), render_skill_md(skill));
+        }
+
+        by_agent.into_values().collect()
+    }
+
+    /// Package every approved skill stored in `space`, using its project as
+    /// the package's project.
+    pub async fn package_space(
+        &self,
+        store: &dyn ExperienceStore,
+        space: &Space,
+    ) -> Result<Vec<SkillPackage>> {
+        let skills = store.get_skills_for_space(space.id, usize::MAX).await?;
+        Ok(self.package(space.project_id.as_deref(), &skills, Utc::now()))
+    }
+}
+
+/// Merge `packages` into the release assets in `dir` and rewrite
+/// `hashes.txt` in `sha256sum` format.
+///
+/// `dir` must hold the current release as published by the skills repo.
+/// Curated entries in each tarball are kept and only `learned-*` skills are
+/// replaced. Learned skills for [`DEFAULT_PROJECT`] are also added to every
+/// project tarball of the agent, since those carry `_default` merged with the
+/// project overrides. A project tarball missing from the release starts from
+/// the agent's default tarball.
+///
+/// Returns the paths written, `hashes.txt` last.
+pub fn write_release(dir: &Path, packages: &[SkillPackage]) -> Result<Vec<PathBuf>> {
+    let hashes_path = dir.join(HASHES_FILE);
+    let manifest = fs::read_to_string(&hashes_path).with_context(|| {
+        format!(
+            "failed to read {}; download the current release first",
+            hashes_path.display()
+        )
+    })?;
+    let mut hashes: BTreeMap<String, String> = manifest
+        .lines()
+        .filter_map(|line| {
+            let (hash, asset) = line.split_once(char::is_whitespace)?;
+            Some((asset.trim().to_string(), hash.to_string()))
+        })
+        .collect();
+
+    let mut learned: BTreeMap<(String, String), &BTreeMap<String, String>> = BTreeMap::new();
+    for package in packages {
+        learned.insert(
+            (package.agent.to_string(), package.project.clone()),
+            &package.skills,
+        );
+    }
+
+    let mut targets: BTreeMap<String, (String, String)> = hashes
+        .keys()
+        .filter_map(|asset| {
+            let (agent, project) = asset.strip_suffix(".tar.gz")?.split_once('-')?;
+            Some((asset.clone(), (agent.to_string(), project.to_string())))
+        })
+        .collect();
+    for package in packages {
+        targets.insert(
+            package.asset_name(),
+            (package.agent.to_string(), package.project.clone()),
+        );
+    }
+
+    let mut written = Vec::new();
+    for (asset, (agent, project)) in targets {
+        let mut skills = BTreeMap::new();
+        if let Some(defaults) = learned.get(&(agent.clone(), DEFAULT_PROJECT.to_string())) {
+            skills.extend(defaults.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        if let Some(overrides) = learned.get(&(agent.clone(), project.clone())) {
+            skills.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        let base_asset = if hashes.contains_key(&asset) {
+            Some(asset.clone())
+        } else {
+            Some(format!("{agent}-{DEFAULT_PROJECT}.tar.gz"))
+                .filter(|default| hashes.contains_key(default))
+        };
+        let (base, dropped) = match &base_asset {
+            Some(base_asset) => read_curated_entries(&dir.join(base_asset))?,
+            None => (Vec::new(), 0),
+        };
+        if skills.is_empty() && dropped == 0 {
+            continue;
+        }
+
+        let bytes = build_tarball(&base, &agent, &skills)?;
+        let path = dir.join(&asset);
+        fs::write(&path, &bytes).with_context(|| format!("failed to write {asset}"))?;
+        hashes.insert(asset.clone(), hex_encode(&Sha256::digest(&bytes)));
+        info!(
+            asset = %asset,
+            curated = base.len(),
+            learned = skills.len(),
+            "Merged learned skills into release asset"
+        );
+        written.push(path);
+    }
+
+    let manifest = hashes.iter().fold(String::new(), |mut out, (asset, hash)| {
+        let _ = writeln!(out, "{hash}  {asset}");
+        out
+    });
+    fs::write(&hashes_path, manifest).with_context(|| format!("failed to write {HASHES_FILE}"))?;
+    written.push(hashes_path);
+    Ok(written)
+}
+
+/// Whether a tarball entry belongs to a learned skill.
+fn is_learned_entry(path: &Path) -> bool {
+    path.components()
+        .nth(1)
+        .and_then(|c| c.as_os_str().to_str())
+        .is_some_and(|name| name.starts_with("learned-"))
+}
+
+/// A tarball entry copied from the current release.
+type CuratedEntry = (PathBuf, tar::Header, Vec<u8>);
+
+/// Read every entry of a release tarball except previously exported learned
+/// skills. Returns the kept entries and how many were dropped.
+fn read_curated_entries(path: &Path) -> Result<(Vec<CuratedEntry>, usize)> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&bytes[..]));
+    let mut kept = Vec::new();
+    let mut dropped = 0;
+    for entry in archive
+        .entries()
+        .with_context(|| format!("failed to read {}", path.display()))?
+    {
+        let mut entry = entry.with_context(|| format!("corrupt entry in {}", path.display()))?;
+        let entry_path = entry.path()?.into_owned();
+        if is_learned_entry(&entry_path) {
+            dropped += 1;
+            continue;
+        }
+        let header = entry.header().clone();
+        let mut data = Vec::with_capacity(usize::try_from(entry.size()).unwrap_or_default());
+        std::io::Read::read_to_end(&mut entry, &mut data)
+            .with_context(|| format!("failed to read entry in {}", path.display()))?;
+        kept.push((entry_path, header, data));
+    }
+    Ok((kept, dropped))
+}
+
+/// Build a gzipped tarball of `base` entries followed by learned skills.
+///
+/// Learned entries are sorted and carry no timestamps, so the same input
+/// always produces the same bytes and the controller only re-downloads on
+/// change.
+fn build_tarball(
+    base: &[CuratedEntry],
+    agent: &str,
+    skills: &BTreeMap<String, String>,
+) -> Result<Vec<u8>> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    for (path, header, data) in base {
+        builder
+            .append_data(&mut header.clone(), path, &data[..])
+            .context("failed to copy curated entry into tarball")?;
+    }
+    for (name, content) in skills {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        header.set_cksum();
+        builder
+            .append_data(
+                &mut header,
+                format!("{agent}/{name}/SKILL.md"),
+                content.as_bytes(),
+            )
+            .with_context(|| format!("failed to add skill '{name}' to tarball"))?;
+    }
+    let encoder = builder.into_inner().context("failed to finish tarball")?;
+    encoder.finish().context("failed to compress tarball")
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(64), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::models::ToolStep;
+
+    fn skill(use_when: &str, agent: AgentType) -> Skill {
+        let mut skill = Skill::new(
+            use_when,
+            agent,
+            vec![
+                ToolStep::new(1, "read_file", "Read the existing handlers"),
+                ToolStep::new(2, "write_file", "Add the handler").with_hint("src/handlers/*.rs"),
+            ],
+            Uuid::new_v4(),
+        );
+        skill.add_preference("Use axum extractors");
+        skill
+    }
+
+    #[test]
+    fn test_render_skill_md() {
+        let skill = skill("implementing a \"Rust\" HTTP handler", AgentType::Rex);
+        let md = render_skill_md(&skill);
+
+        assert!(md.starts_with("---\nname: learned-implementing-a-rust-http-handler-"));
+        assert!(md.contains("description: \"Use when implementing a \\\"Rust\\\" HTTP handler\""));
+        assert!(md.contains("# implementing a \"Rust\" HTTP handler\n"));
+        assert!(md.contains("**Preferences**: Use axum extractors"));
+        assert!(md.contains("2. `write_file`: Add the handler\n   - Parameters: src/handlers/*.rs"));
+    }
+
+    #[test]
+    fn test_only_approved_current_versions_are_packaged() {
+        let mut approved = skill("implementing HTTP handler", AgentType::Rex);
+        let mut changed = skill("writing integration tests", AgentType::Rex);
+        let unreviewed = skill("styling a form", AgentType::Blaze);
+
+        let mut exporter = SkillExporter::new();
+        exporter.approve(&approved);
+        exporter.approve(&changed);
+        changed.bump_version();
+        approved.success_count = 3;
+
+        let packages = exporter.package(
+            Some("cto"),
+            &[approved.clone(), changed, unreviewed],
+            Utc::now(),
+        );
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].asset_name(), "rex-cto.tar.gz");
+        assert_eq!(
+            packages[0].skills.keys().collect::<Vec<_>>(),
+            vec![&skill_name(&approved)]
+        );
+    }
+
+    /// Write a curated release asset holding `paths`, each with its own
+    /// path as content.
+    fn write_curated(dir: &Path, asset: &str, paths: &[&str]) -> String {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for path in paths {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(path.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, path.as_bytes())
+                .unwrap();
+        }
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+        fs::write(dir.join(asset), &bytes).unwrap();
+        format!("{}  {asset}\n", hex_encode(&Sha256::digest(&bytes)))
+    }
+
+    fn tarball_entries(path: &Path) -> BTreeMap<String, String> {
+        let bytes = fs::read(path).unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&bytes[..]));
+        archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let path = entry.path().unwrap().to_str().unwrap().to_string();
+                let mut content = String::new();
+                entry.read_to_string(&mut content).unwrap();
+                (path, content)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_release_merges_into_curated_assets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let stale = "rex/learned-old-skill-deadbeef/SKILL.md";
+        let mut manifest = write_curated(
+            tmp.path(),
+            "rex-default.tar.gz",
+            &["rex/rust-patterns/SKILL.md", stale],
+        );
+        manifest += &write_curated(
+            tmp.path(),
+            "rex-test-sandbox.tar.gz",
+            &[
+                "rex/rust-patterns/SKILL.md",
+                "rex/sandbox-override/SKILL.md",
+            ],
+        );
+        let morgan = write_curated(
+            tmp.path(),
+            "morgan-default.tar.gz",
+            &["morgan/intake/SKILL.md"],
+        );
+        manifest += &morgan;
+        fs::write(tmp.path().join(HASHES_FILE), &manifest).unwrap();
+
+        let default_skill = skill("implementing HTTP handler", AgentType::Rex);
+        let cto_skill = skill("wiring the CTO controller", AgentType::Rex);
+        let blaze_skill = skill("styling a form", AgentType::Blaze);
+        let mut exporter = SkillExporter::new();
+        for skill in [&default_skill, &cto_skill, &blaze_skill] {
+            exporter.approve(skill);
+        }
+        let mut packages = exporter.package(
+            None,
+            &[default_skill.clone(), blaze_skill.clone()],
+            Utc::now(),
+        );
+        packages.extend(exporter.package(
+            Some("cto"),
+            std::slice::from_ref(&cto_skill),
+            Utc::now(),
+        ));
+
+        let written = write_release(tmp.path(), &packages).unwrap();
+        // blaze-default, rex-cto, rex-default, rex-test-sandbox, hashes.txt
+        assert_eq!(written.len(), 5);
+
+        let learned_default = format!("rex/{}/SKILL.md", skill_name(&default_skill));
+        let learned_cto = format!("rex/{}/SKILL.md", skill_name(&cto_skill));
+
+        let rex_default = tarball_entries(&tmp.path().join("rex-default.tar.gz"));
+        assert_eq!(
+            rex_default.keys().map(String::as_str).collect::<Vec<_>>(),
+            vec![learned_default.as_str(), "rex/rust-patterns/SKILL.md"]
+        );
+        assert_eq!(
+            rex_default[&learned_default],
+            render_skill_md(&default_skill)
+        );
+
+        // Project tarballs keep their overrides and gain the default skills.
+        let sandbox = tarball_entries(&tmp.path().join("rex-test-sandbox.tar.gz"));
+        assert!(sandbox.contains_key("rex/sandbox-override/SKILL.md"));
+        assert!(sandbox.contains_key(&learned_default));
+        assert!(!sandbox.contains_key(&learned_cto));
+
+        // A new project tarball starts from the curated default skills.
+        let cto = tarball_entries(&tmp.path().join("rex-cto.tar.gz"));
+        assert!(cto.contains_key("rex/rust-patterns/SKILL.md"));
+        assert!(cto.contains_key(&learned_default));
+        assert!(cto.contains_key(&learned_cto));
+        assert!(!cto.contains_key(stale));
+
+        let hashes = fs::read_to_string(tmp.path().join(HASHES_FILE)).unwrap();
+        assert!(hashes.contains(&morgan), "untouched assets keep their hash");
+        for asset in [
+            "rex-default.tar.gz",
+            "rex-test-sandbox.tar.gz",
+            "rex-cto.tar.gz",
+            "blaze-default.tar.gz",
+        ] {
+            let bytes = fs::read(tmp.path().join(asset)).unwrap();
+            assert!(hashes.contains(&format!(
+                "{}  {asset}\n",
+                hex_encode(&Sha256::digest(&bytes))
+            )));
+        }
+
+        // Re-exporting the same skills yields identical bytes, so the
+        // controller does not re-download unchanged tarballs.
+        let before = fs::read(tmp.path().join("rex-default.tar.gz")).unwrap();
+        write_release(tmp.path(), &packages).unwrap();
+        assert_eq!(
+            fs::read(tmp.path().join("rex-default.tar.gz")).unwrap(),
+            before
+        );
+        assert_eq!(
+            fs::read_to_string(tmp.path().join(HASHES_FILE)).unwrap(),
+            hashes
+        );
+    }
+
+    #[test]
+    fn test_release_requires_current_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill = skill("implementing HTTP handler", AgentType::Rex);
+        let mut exporter = SkillExporter::new();
+        exporter.approve(&skill);
+        let packages = exporter.package(None, &[skill], Utc::now());
+
+        assert!(write_release(tmp.path(), &packages).is_err());
+        assert!(!tmp.path().join("rex-default.tar.gz").exists());
+    }
+}
@@ -1,14 +1,17 @@
 //! Skill learner - extracts SOPs from successful task executions.
 
+use std::sync::Arc;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use tracing::{debug, info, warn};
 
-use crate::models::{AgentType, Skill, TaskRecord, ToolStep};
+use crate::models::{AgentType, MessageRecord, Skill, TaskRecord, ToolStep};
+use crate::search::Embedder;
 
 use super::complexity::ComplexityFilter;
 
-/// LLM client trait for SOP extraction.
+/// LLM client trait for SOP extraction and context summarization.
 #[async_trait]
 pub trait LlmClient: Send + Sync {
     /// Generate a description of when this skill should be used.
@@ -20,19 +23,41 @@ pub trait LlmClient: Send + Sync {
 
     /// Generate an embedding vector for a skill.
     async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Summarize a run of conversation messages.
+    ///
+    /// The summary replaces the messages in the context, so it should keep
+    /// decisions, open questions and file paths the agent still needs.
+    async fn summarize_messages(&self, messages: &[MessageRecord]) -> Result<String>;
 }
 
 /// Skill learner that converts successful tasks to reusable skills.
 pub struct SkillLearner {
     /// Complexity filter for determining learnability.
     complexity_filter: ComplexityFilter,
+
+    /// Embedder for skill vectors; takes precedence over the LLM client.
+    embedder: Option<Arc<dyn Embedder>>,
 }
 
 impl SkillLearner {
     /// Create a new skill learner.
     #[must_use]
     pub fn new(complexity_filter: ComplexityFilter) -> Self {
-        Self { complexity_filter }
+        Self {
+            complexity_filter,
+            embedder: None,
+        }
+    }
+
+    /// Embed skills with `embedder` instead of the LLM client.
+    ///
+    /// Use the same embedder as the [`SkillSearcher`](crate::SkillSearcher)
+    /// that will query the skills.
+    #[must_use]
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
     }
 
     /// Create with default complexity filter.
@@ -102,17 +127,19 @@ impl SkillLearner {
         let complexity = self.complexity_filter.calculate_complexity(task);
         skill = skill.with_complexity(complexity);
 
-        // Generate embedding if LLM client available
-        if let Some(client) = llm_client {
-            let embed_text = format!("{} {}", skill.use_when, task.description);
-            match client.generate_embedding(&embed_text).await {
-                Ok(embedding) => {
-                    skill = skill.with_embedding(embedding);
-                }
-                Err(e) => {
-                    warn!(error = %e, "Failed to generate embedding");
-                }
-            }
+        // Generate embedding with the configured embedder, else the LLM client
+        let embed_text = format!("{} {}", skill.use_when, task.description);
+        let embedding = if let Some(embedder) = &self.embedder {
+            Some(embedder.embed(&embed_text).await)
+        } else if let Some(client) = llm_client {
+            Some(client.generate_embedding(&embed_text).await)
+        } else {
+            None
+        };
+        match embedding {
+            Some(Ok(embedding)) => skill = skill.with_embedding(embedding),
+            Some(Err(e)) => warn!(error = %e, "Failed to generate embedding"),
+            None => {}
         }
 
         info!(
@@ -241,6 +268,10 @@ impl LlmClient for MockLlmClient {
     async fn generate_embedding(&self, _text: &str) -> Result<Vec<f32>> {
         Ok(vec![0.1; 1536])
     }
+
+    async fn summarize_messages(&self, messages: &[MessageRecord]) -> Result<String> {
+        Ok(format!("Summary of {} messages", messages.len()))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,371 @@
+//! Skill lifecycle - outcome feedback, confidence decay and deduplication.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::models::{Skill, SkillOutcome, ToolStep};
+use crate::search::cosine_similarity;
+use crate::storage::ExperienceStore;
+
+/// Tuning for skill confidence and deduplication.
+#[derive(Debug, Clone)]
+pub struct LifecycleConfig {
+    /// Time for an unconfirmed skill's confidence to halve.
+    pub half_life: Duration,
+
+    /// Skills below this confidence are left out of search results.
+    pub min_confidence: f32,
+
+    /// Minimum embedding cosine similarity for two skills to be duplicates.
+    pub duplicate_similarity: f32,
+
+    /// Minimum step-sequence overlap (0.0 - 1.0) for two skills to be
+    /// duplicates.
+    pub duplicate_step_overlap: f32,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        Self {
+            half_life: Duration::days(180),
+            min_confidence: 0.2,
+            duplicate_similarity: 0.9,
+            duplicate_step_overlap: 0.6,
+        }
+    }
+}
+
+impl LifecycleConfig {
+    /// Confidence of `skill` at `now`.
+    #[must_use]
+    pub fn confidence(&self, skill: &Skill, now: DateTime<Utc>) -> f32 {
+        skill.confidence(now, self.half_life)
+    }
+
+    /// Whether two skills are near-duplicates.
+    ///
+    /// Both must target the same agent, have embeddings of the same size
+    /// that are similar enough, and share enough of their step sequence.
+    #[must_use]
+    pub fn are_duplicates(&self, a: &Skill, b: &Skill) -> bool {
+        let (Some(a_embedding), Some(b_embedding)) = (&a.embedding, &b.embedding) else {
+            return false;
+        };
+        a.agent == b.agent
+            && a_embedding.len() == b_embedding.len()
+            && cosine_similarity(a_embedding, b_embedding) >= self.duplicate_similarity
+            && step_overlap(&a.tool_sops, &b.tool_sops) >= self.duplicate_step_overlap
+    }
+}
+
+/// Result of merging a cluster of duplicate skills.
+#[derive(Debug, Clone)]
+pub struct SkillMerge {
+    /// Skill the others were merged into.
+    pub canonical_id: Uuid,
+
+    /// Skills marked as merged.
+    pub merged_ids: Vec<Uuid>,
+}
+
+/// Applies outcome feedback and deduplication to stored skills.
+pub struct SkillLifecycle {
+    store: Arc<dyn ExperienceStore>,
+    config: LifecycleConfig,
+}
+
+impl SkillLifecycle {
+    /// Create a lifecycle manager with the default configuration.
+    #[must_use]
+    pub fn new(store: Arc<dyn ExperienceStore>) -> Self {
+        Self::with_config(store, LifecycleConfig::default())
+    }
+
+    /// Create a lifecycle manager with a custom configuration.
+    #[must_use]
+    pub fn with_config(store: Arc<dyn ExperienceStore>, config: LifecycleConfig) -> Self {
+        Self { store, config }
+    }
+
+    /// The active configuration.
+    #[must_use]
+    pub fn config(&self) -> &LifecycleConfig {
+        &self.config
+    }
+
+    /// Record the outcome of a session that used a skill.
+    ///
+    /// Outcomes for a merged skill are credited to its canonical skill.
+    /// Returns the updated skill.
+    pub async fn record_outcome(
+        &self,
+        skill_id: Uuid,
+        session_id: Uuid,
+        success: bool,
+        notes: Option<&str>,
+    ) -> Result<Skill> {
+        let mut skill = self.resolve(skill_id).await?;
+
+        let mut outcome = SkillOutcome::new(&skill, session_id, success);
+        if let Some(notes) = notes {
+            outcome = outcome.with_notes(notes);
+        }
+        self.store.record_skill_outcome(&outcome).await?;
+
+        skill.record_outcome(success, outcome.recorded_at);
+        self.store.update_skill(&skill).await?;
+        debug!(
+            skill_id = %skill.id,
+            version = skill.version,
+            success,
+            "Recorded skill outcome"
+        );
+        Ok(skill)
+    }
+
+    /// Merge near-duplicate skills in a space.
+    ///
+    /// Skills are clustered greedily around the most confident one. Each
+    /// cluster keeps its canonical skill, which absorbs the others'
+    /// preferences and outcome counts; the rest are marked as merged so
+    /// searches skip them.
+    pub async fn merge_duplicates(&self, space_id: Uuid) -> Result<Vec<SkillMerge>> {
+        let now = Utc::now();
+        let mut skills = self
+            .store
+            .get_skills_for_space(space_id, usize::MAX)
+            .await?;
+        skills.sort_by(|a, b| {
+            self.config
+                .confidence(b, now)
+                .total_cmp(&self.config.confidence(a, now))
+        });
+
+        let mut absorbed = vec![false; skills.len()];
+        let mut merges = Vec::new();
+        for i in 0..skills.len() {
+            if absorbed[i] {
+                continue;
+            }
+            let duplicates: Vec<usize> = (i + 1..skills.len())
+                .filter(|&j| !absorbed[j] && self.config.are_duplicates(&skills[i], &skills[j]))
+                .collect();
+            if duplicates.is_empty() {
+                continue;
+            }
+
+            let mut canonical = skills[i].clone();
+            let preferences_before = canonical.preferences.len();
+            let mut merged_ids = Vec::with_capacity(duplicates.len());
+            for &j in &duplicates {
+                absorbed[j] = true;
+                let duplicate = &mut skills[j];
+                absorb(&mut canonical, duplicate);
+                duplicate.merged_into = Some(canonical.id);
+                duplicate.updated_at = now;
+                self.store.update_skill(duplicate).await?;
+                merged_ids.push(duplicate.id);
+            }
+            if canonical.preferences.len() == preferences_before {
+                canonical.updated_at = now;
+            } else {
+                canonical.bump_version();
+            }
+            self.store.update_skill(&canonical).await?;
+
+            info!(
+                canonical = %canonical.id,
+                merged = merged_ids.len(),
+                version = canonical.version,
+                "Merged duplicate skills"
+            );
+            merges.push(SkillMerge {
+                canonical_id: canonical.id,
+                merged_ids,
+            });
+        }
+        Ok(merges)
+    }
+
+    /// Load a skill, following merges to its canonical skill.
+    async fn resolve(&self, skill_id: Uuid) -> Result<Skill> {
+        let mut id = skill_id;
+        // Merges are one level deep in practice; the bound guards cycles.
+        for _ in 0..8 {
+            let Some(skill) = self.store.get_skill(id).await? else {
+                bail!("skill {id} not found");
+            };
+            match skill.merged_into {
+                Some(canonical) => id = canonical,
+                None => return Ok(skill),
+            }
+        }
+        bail!("skill {skill_id} has a merge cycle")
+    }
+}
+
+/// Fold a duplicate's evidence and preferences into the canonical skill.
+fn absorb(canonical: &mut Skill, duplicate: &Skill) {
+    canonical.success_count += duplicate.success_count;
+    canonical.failure_count += duplicate.failure_count;
+    canonical.last_outcome_at = canonical.last_outcome_at.max(duplicate.last_outcome_at);
+
+    let mut seen: HashSet<String> = canonical
+        .preferences
+        .iter()
+        .map(|p| normalize_preference(p))
+        .collect();
+    for preference in &duplicate.preferences {
+        if seen.insert(normalize_preference(preference)) {
+            canonical.preferences.push(preference.clone());
+        }
+    }
+}
+
+fn normalize_preference(preference: &str) -> String {
+    preference.trim().to_lowercase()
+}
+
+/// Overlap of two step sequences: twice their longest common subsequence of
+/// tool names over their combined length.
+#[must_use]
+#[allow(clippy::cast_precision_loss)] // Step counts are small
+pub fn step_overlap(a: &[ToolStep], b: &[ToolStep]) -> f32 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let mut previous = vec![0usize; b.len() + 1];
+    for step_a in a {
+        let mut current = vec![0usize; b.len() + 1];
+        for (j, step_b) in b.iter().enumerate() {
+            current[j + 1] = if step_a.tool_name == step_b.tool_name {
+                previous[j] + 1
+            } else {
+                current[j].max(previous[j + 1])
+            };
+        }
+        previous = current;
+    }
+    let common = previous[b.len()];
+    (2 * common) as f32 / (a.len() + b.len()) as f32
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::models::{AgentType, Space};
+    use crate::storage::SqliteStore;
+
+    fn steps(tools: &[&str]) -> Vec<ToolStep> {
+        (1..)
+            .zip(tools)
+            .map(|(order, tool)| ToolStep::new(order, *tool, "step"))
+            .collect()
+    }
+
+    fn skill(space_id: Uuid, tools: &[&str], embedding: Vec<f32>, preference: &str) -> Skill {
+        let mut skill = Skill::new("implement handler", AgentType::Rex, steps(tools), space_id)
+            .with_embedding(embedding);
+        skill.add_preference(preference);
+        skill
+    }
+
+    async fn lifecycle() -> (SkillLifecycle, Arc<SqliteStore>, Uuid) {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let space = store.create_space(&Space::new("test")).await.unwrap();
+        (SkillLifecycle::new(store.clone()), store, space.id)
+    }
+
+    #[test]
+    fn test_step_overlap() {
+        let a = steps(&["read_file", "write_file", "git_diff"]);
+        let b = steps(&["read_file", "search_files", "write_file", "git_diff"]);
+        assert!((step_overlap(&a, &b) - 6.0 / 7.0).abs() < 1e-6);
+        assert!(step_overlap(&a, &steps(&["helm"])).abs() < f32::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_outcomes_update_counts_and_history() {
+        let (lifecycle, store, space_id) = lifecycle().await;
+        let skill = skill(space_id, &["read_file"], vec![1.0, 0.0], "Use anyhow");
+        store.create_skill(&skill).await.unwrap();
+
+        let session = Uuid::new_v4();
+        lifecycle
+            .record_outcome(skill.id, session, false, Some("clippy failed"))
+            .await
+            .unwrap();
+        let updated = lifecycle
+            .record_outcome(skill.id, Uuid::new_v4(), true, None)
+            .await
+            .unwrap();
+
+        assert_eq!((updated.success_count, updated.failure_count), (2, 1));
+        let stored = store.get_skill(skill.id).await.unwrap().unwrap();
+        assert_eq!(stored.failure_count, 1);
+        assert!(stored.last_outcome_at.is_some());
+
+        let outcomes = store.get_skill_outcomes(skill.id).await.unwrap();
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].session_id, session);
+        assert_eq!(outcomes[0].notes.as_deref(), Some("clippy failed"));
+        assert_eq!(outcomes[0].skill_version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_merge_duplicates_combines_into_canonical() {
+        let (lifecycle, store, space_id) = lifecycle().await;
+        let mut strong = skill(
+            space_id,
+            &["read_file", "write_file", "git_diff"],
+            vec![1.0, 0.0, 0.0],
+            "Use anyhow",
+        );
+        strong.success_count = 5;
+        let near = skill(
+            space_id,
+            &["read_file", "search_files", "write_file", "git_diff"],
+            vec![0.98, 0.05, 0.0],
+            "Prefer tracing over println",
+        );
+        let other = skill(
+            space_id,
+            &["helm"],
+            vec![0.0, 0.0, 1.0],
+            "Pin chart versions",
+        );
+        for s in [&strong, &near, &other] {
+            store.create_skill(s).await.unwrap();
+        }
+
+        let merges = lifecycle.merge_duplicates(space_id).await.unwrap();
+        assert_eq!(merges.len(), 1);
+        assert_eq!(merges[0].canonical_id, strong.id);
+        assert_eq!(merges[0].merged_ids, vec![near.id]);
+
+        let canonical = store.get_skill(strong.id).await.unwrap().unwrap();
+        assert_eq!(canonical.success_count, 6);
+        assert_eq!(canonical.version, 2);
+        assert_eq!(canonical.preferences.len(), 2);
+        let versions = store.get_skill_versions(strong.id).await.unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            [1, 2]
+        );
+
+        let remaining = store.get_skills_for_space(space_id, 10).await.unwrap();
+        assert_eq!(remaining.len(), 2);
+
+        // Feedback on the merged skill lands on the canonical one.
+        let updated = lifecycle
+            .record_outcome(near.id, Uuid::new_v4(), true, None)
+            .await
+            .unwrap();
+        assert_eq!(updated.id, strong.id);
+    }
+}
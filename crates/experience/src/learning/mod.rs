@@ -5,13 +5,16 @@
 //! - Task extraction and analysis
 //! - Complexity filtering
 //! - SOP extraction and skill learning
+//! - Skill lifecycle: outcome feedback, confidence decay and deduplication
 
 mod collector;
 mod complexity;
 mod extractor;
 mod learner;
+mod lifecycle;
 
 pub use collector::SessionCollector;
 pub use complexity::ComplexityFilter;
 pub use extractor::TaskExtractor;
-pub use learner::SkillLearner;
+pub use learner::{LlmClient, SkillLearner};
+pub use lifecycle::{step_overlap, LifecycleConfig, SkillLifecycle, SkillMerge};
@@ -47,21 +47,25 @@
 #![allow(clippy::missing_panics_doc)]
 
 pub mod editing;
+pub mod export;
 pub mod learning;
 pub mod models;
 pub mod search;
-#[cfg(feature = "postgres")]
 pub mod storage;
 pub mod tools;
 
 // Re-export primary types
-pub use editing::{EditParams, EditResult, EditStrategy};
-pub use learning::{ComplexityFilter, SessionCollector, SkillLearner, TaskExtractor};
+pub use editing::{ContextEditor, EditParams, EditResult, EditStrategy, EditedContext};
+pub use export::{SkillExporter, SkillPackage};
+pub use learning::{
+    ComplexityFilter, LifecycleConfig, LlmClient, SessionCollector, SkillLearner, SkillLifecycle,
+    TaskExtractor,
+};
 pub use models::{
-    AgentType, MessageRecord, SearchMode, SessionRecord, SessionStatus, Skill, Space, TaskRecord,
-    TaskStatus, ToolCallRecord, ToolStep,
+    AgentType, MessageRecord, SearchMode, SessionRecord, SessionStatus, Skill, SkillOutcome,
+    SkillVersion, Space, TaskRecord, TaskStatus, ToolCallRecord, ToolStep,
 };
-pub use search::SkillSearcher;
+pub use search::{Embedder, EmbeddingSearcher, HashedEmbedder, SkillSearcher};
 
 /// Experience client configuration.
 #[derive(Debug, Clone)]
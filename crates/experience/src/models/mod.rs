@@ -6,7 +6,7 @@ mod space;
 mod task;
 
 pub use session::{MessageRecord, SessionRecord, SessionStatus};
-pub use skill::{AgentType, Skill, ToolStep};
+pub use skill::{AgentType, Skill, SkillOutcome, SkillVersion, ToolStep};
 pub use space::Space;
 pub use task::{TaskRecord, TaskStatus, ToolCallRecord};
 
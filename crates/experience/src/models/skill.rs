@@ -117,6 +117,10 @@ impl ToolStep {
     }
 }
 
+fn default_version() -> u32 {
+    1
+}
+
 /// A learned skill (Standard Operating Procedure) extracted from successful task execution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
@@ -143,6 +147,22 @@ pub struct Skill {
     /// Number of times this pattern has succeeded.
     pub success_count: u32,
 
+    /// Number of times a session using this skill failed.
+    #[serde(default)]
+    pub failure_count: u32,
+
+    /// Version of the SOP, bumped whenever steps or preferences change.
+    #[serde(default = "default_version")]
+    pub version: u32,
+
+    /// When an outcome was last recorded against this skill.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_outcome_at: Option<DateTime<Utc>>,
+
+    /// Canonical skill this one was merged into, if deduplicated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged_into: Option<Uuid>,
+
     /// Space (project/user scope) this skill belongs to.
     pub space_id: Uuid,
 
@@ -175,6 +195,10 @@ impl Skill {
             tool_sops,
             complexity_score: 0.5,
             success_count: 1,
+            failure_count: 0,
+            version: 1,
+            last_outcome_at: None,
+            merged_into: None,
             space_id,
             embedding: None,
             created_at: now,
@@ -207,6 +231,47 @@ impl Skill {
         self.updated_at = Utc::now();
     }
 
+    /// Count the outcome of a session that used this skill.
+    pub fn record_outcome(&mut self, success: bool, at: DateTime<Utc>) {
+        if success {
+            self.success_count += 1;
+        } else {
+            self.failure_count += 1;
+        }
+        self.last_outcome_at = Some(at);
+        self.updated_at = at;
+    }
+
+    /// Start a new version after changing the SOP.
+    pub fn bump_version(&mut self) {
+        self.version += 1;
+        self.updated_at = Utc::now();
+    }
+
+    /// Whether this skill has been merged into another.
+    #[must_use]
+    pub fn is_merged(&self) -> bool {
+        self.merged_into.is_some()
+    }
+
+    /// Confidence that this skill still helps, in `0.0..=1.0`.
+    ///
+    /// The success rate with a uniform prior, halved for every `half_life`
+    /// since the skill was last confirmed by an outcome (or changed), so
+    /// stale and failing SOPs sink in rankings.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Counts and ages are far below f32 precision limits
+    pub fn confidence(&self, now: DateTime<Utc>, half_life: chrono::Duration) -> f32 {
+        let successes = self.success_count as f32;
+        let failures = self.failure_count as f32;
+        let rate = (successes + 1.0) / (successes + failures + 2.0);
+
+        let last_confirmed = self.last_outcome_at.unwrap_or(self.updated_at);
+        let age = (now - last_confirmed).num_seconds().max(0) as f32;
+        let half_life = half_life.num_seconds().max(1) as f32;
+        rate * 0.5_f32.powf(age / half_life)
+    }
+
     /// Generate a summary of this skill for inclusion in prompts.
     #[must_use]
     pub fn to_prompt_summary(&self) -> String {
@@ -225,12 +290,101 @@ impl Skill {
                 "{}. `{}`: {}",
                 step.order, step.tool_name, step.action
             );
+            if let Some(hint) = &step.parameters_hint {
+                let _ = writeln!(summary, "   - Parameters: {hint}");
+            }
         }
 
         summary
     }
 }
 
+/// Snapshot of a skill's SOP at one version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillVersion {
+    /// Skill this version belongs to.
+    pub skill_id: Uuid,
+
+    /// Version number.
+    pub version: u32,
+
+    /// Condition describing when the skill should be used.
+    pub use_when: String,
+
+    /// Preferences at this version.
+    pub preferences: Vec<String>,
+
+    /// Steps at this version.
+    pub tool_sops: Vec<ToolStep>,
+
+    /// When this version was created.
+    pub created_at: DateTime<Utc>,
+}
+
+impl SkillVersion {
+    /// Snapshot the skill's current version.
+    #[must_use]
+    pub fn of(skill: &Skill) -> Self {
+        Self {
+            skill_id: skill.id,
+            version: skill.version,
+            use_when: skill.use_when.clone(),
+            preferences: skill.preferences.clone(),
+            tool_sops: skill.tool_sops.clone(),
+            created_at: skill.updated_at,
+        }
+    }
+}
+
+/// Outcome of a session that used a skill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillOutcome {
+    /// Unique identifier.
+    pub id: Uuid,
+
+    /// Skill that was used.
+    pub skill_id: Uuid,
+
+    /// Version of the skill that was used.
+    pub skill_version: u32,
+
+    /// Session that used the skill.
+    pub session_id: Uuid,
+
+    /// Whether the session succeeded.
+    pub success: bool,
+
+    /// Optional context, e.g. the failure reason.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+
+    /// When the outcome was recorded.
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl SkillOutcome {
+    /// Create an outcome for the skill's current version.
+    #[must_use]
+    pub fn new(skill: &Skill, session_id: Uuid, success: bool) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            skill_id: skill.id,
+            skill_version: skill.version,
+            session_id,
+            success,
+            notes: None,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    /// Attach notes.
+    #[must_use]
+    pub fn with_notes(mut self, notes: impl Into<String>) -> Self {
+        self.notes = Some(notes.into());
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,7 +420,7 @@ mod tests {
         let mut skill = Skill::new(
             "setting up authentication",
             AgentType::Blaze,
-            vec![ToolStep::new(1, "search_files", "Find auth config")],
+            vec![ToolStep::new(1, "search_files", "Find auth config").with_hint("glob: **/auth*")],
             space_id,
         );
         skill.add_preference("Use Better Auth");
@@ -275,5 +429,24 @@ mod tests {
         assert!(summary.contains("setting up authentication"));
         assert!(summary.contains("Better Auth"));
         assert!(summary.contains("search_files"));
+        assert!(summary.contains("Parameters: glob: **/auth*"));
+    }
+
+    #[test]
+    fn test_confidence_drops_with_failures_and_age() {
+        let now = Utc::now();
+        let half_life = chrono::Duration::days(30);
+        let mut skill = Skill::new("deploy", AgentType::Bolt, Vec::new(), Uuid::new_v4());
+        skill.record_outcome(true, now);
+        let fresh = skill.confidence(now, half_life);
+        assert!((fresh - 0.75).abs() < 1e-6);
+
+        skill.record_outcome(false, now);
+        skill.record_outcome(false, now);
+        assert!(skill.confidence(now, half_life) < fresh);
+
+        let later = now + half_life;
+        let stale = skill.confidence(later, half_life);
+        assert!((stale - skill.confidence(now, half_life) / 2.0).abs() < 1e-6);
     }
 }
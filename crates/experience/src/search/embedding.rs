@@ -1,48 +1,281 @@
 //! Embedding-based skill search.
 
+use std::sync::Arc;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use uuid::Uuid;
 
+use crate::learning::LifecycleConfig;
 use crate::models::{SearchMode, Skill};
+use crate::storage::ExperienceStore;
 
 use super::SkillSearcher;
 
+/// Turns text into embedding vectors.
+///
+/// Skills and queries must be embedded by the same embedder for similarity
+/// scores to mean anything.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Embed a piece of text.
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+}
+
+/// Local embedder using signed feature hashing of words and character
+/// trigrams.
+///
+/// Needs no model or network access, so it works in development and CI.
+/// Vectors are L2-normalised and deterministic across builds and platforms,
+/// which makes them safe to persist.
+#[derive(Debug, Clone)]
+pub struct HashedEmbedder {
+    dimensions: usize,
+}
+
+impl HashedEmbedder {
+    /// Default vector size.
+    pub const DEFAULT_DIMENSIONS: usize = 384;
+
+    /// Weight of a whole-word feature relative to a character trigram.
+    const WORD_WEIGHT: f32 = 2.0;
+
+    /// Create an embedder producing vectors of `dimensions` entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dimensions` is zero.
+    #[must_use]
+    pub fn new(dimensions: usize) -> Self {
+        assert!(dimensions > 0, "embedding dimensions must be non-zero");
+        Self { dimensions }
+    }
+
+    /// Vector size produced by this embedder.
+    #[must_use]
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Embed text synchronously.
+    #[must_use]
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimensions];
+        let lowered = text.to_lowercase();
+        for word in lowered
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            self.add_feature(&mut vector, word.as_bytes(), Self::WORD_WEIGHT);
+
+            let padded: Vec<char> = format!("#{word}#").chars().collect();
+            for trigram in padded.windows(3) {
+                let trigram: String = trigram.iter().collect();
+                self.add_feature(&mut vector, trigram.as_bytes(), 1.0);
+            }
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for value in &mut vector {
+                *value /= norm;
+            }
+        }
+        vector
+    }
+
+    fn add_feature(&self, vector: &mut [f32], feature: &[u8], weight: f32) {
+        let hash = fnv1a(feature);
+        #[allow(clippy::cast_possible_truncation)] // Reduced modulo the dimension
+        let index = (hash % self.dimensions as u64) as usize;
+        // A sign bit independent of the index keeps collisions from only
+        // ever adding up.
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[index] += sign * weight;
+    }
+}
+
+impl Default for HashedEmbedder {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_DIMENSIONS)
+    }
+}
+
+#[async_trait]
+impl Embedder for HashedEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        Ok(self.embed_text(text))
+    }
+}
+
+/// 64-bit FNV-1a, used instead of `DefaultHasher` because its output is
+/// stable across Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Cosine similarity of two vectors, or 0.0 if either is all zeros.
+#[must_use]
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
 /// Embedding-based skill searcher.
+///
+/// Embeds the query and asks the store for the nearest skills in the space,
+/// then re-ranks them by similarity weighted with skill confidence so SOPs
+/// that keep failing or have gone stale sink (and eventually drop out).
 pub struct EmbeddingSearcher {
-    /// API key for embedding service.
-    #[allow(dead_code)]
-    api_key: String,
-
-    /// Model to use for embeddings.
-    #[allow(dead_code)]
-    model: String,
+    store: Arc<dyn ExperienceStore>,
+    embedder: Arc<dyn Embedder>,
+    lifecycle: LifecycleConfig,
 }
 
 impl EmbeddingSearcher {
+    /// Candidates fetched per requested result before re-ranking.
+    const OVERFETCH: usize = 3;
+
     /// Create a new embedding searcher.
     #[must_use]
-    pub fn new(api_key: impl Into<String>, model: impl Into<String>) -> Self {
+    pub fn new(store: Arc<dyn ExperienceStore>, embedder: Arc<dyn Embedder>) -> Self {
         Self {
-            api_key: api_key.into(),
-            model: model.into(),
+            store,
+            embedder,
+            lifecycle: LifecycleConfig::default(),
         }
     }
+
+    /// Use custom confidence settings for re-ranking.
+    #[must_use]
+    pub fn with_lifecycle(mut self, lifecycle: LifecycleConfig) -> Self {
+        self.lifecycle = lifecycle;
+        self
+    }
+
+    /// Create a searcher using the local [`HashedEmbedder`].
+    #[must_use]
+    pub fn local(store: Arc<dyn ExperienceStore>) -> Self {
+        Self::new(store, Arc::new(HashedEmbedder::default()))
+    }
 }
 
 #[async_trait]
 impl SkillSearcher for EmbeddingSearcher {
     async fn search(
         &self,
-        _query: &str,
-        _space_id: Uuid,
+        query: &str,
+        space_id: Uuid,
         _mode: SearchMode,
-        _limit: usize,
+        limit: usize,
     ) -> Result<Vec<Skill>> {
-        // TODO: Implement actual embedding search with vector DB
-        // 1. Generate embedding for query
-        // 2. Search vector DB for similar skills
-        // 3. Return top matches
-        Ok(Vec::new())
+        if query.trim().is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let embedding = self.embedder.embed(query).await?;
+        let candidates = self
+            .store
+            .search_skills_by_embedding(&embedding, space_id, limit.saturating_mul(Self::OVERFETCH))
+            .await?;
+
+        let now = chrono::Utc::now();
+        let mut scored: Vec<(f32, Skill)> = candidates
+            .into_iter()
+            .filter_map(|skill| {
+                let confidence = self.lifecycle.confidence(&skill, now);
+                if confidence < self.lifecycle.min_confidence {
+                    return None;
+                }
+                let similarity = skill
+                    .embedding
+                    .as_deref()
+                    .map_or(0.0, |stored| cosine_similarity(&embedding, stored));
+                Some((similarity * confidence, skill))
+            })
+            .collect();
+        scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        Ok(scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, skill)| skill)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashed_embedding_is_normalised_and_deterministic() {
+        let embedder = HashedEmbedder::new(64);
+        let a = embedder.embed_text("Implement the HTTP handler");
+        let b = embedder.embed_text("implement the http handler");
+
+        assert_eq!(a.len(), 64);
+        assert_eq!(a, b);
+        let norm = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert!(embedder.embed_text("  ").iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn test_related_text_scores_higher() {
+        let embedder = HashedEmbedder::default();
+        let query = embedder.embed_text("add authentication to the HTTP handler");
+        let related = embedder.embed_text("implement HTTP handler authentication middleware");
+        let unrelated = embedder.embed_text("deploy helm chart to staging cluster");
+
+        assert!(cosine_similarity(&query, &related) > cosine_similarity(&query, &unrelated));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_failing_skills_rank_below_reliable_ones() {
+        use crate::models::{AgentType, Space};
+        use crate::storage::SqliteStore;
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let space = store.create_space(&Space::new("test")).await.unwrap();
+        let embedder = HashedEmbedder::default();
+
+        let mut failing = Skill::new("fix flaky test", AgentType::Tess, Vec::new(), space.id)
+            .with_embedding(embedder.embed_text("fix flaky test"));
+        failing.failure_count = 3;
+        let mut reliable = Skill::new(
+            "fix flaky integration test",
+            AgentType::Tess,
+            Vec::new(),
+            space.id,
+        )
+        .with_embedding(embedder.embed_text("fix flaky integration test"));
+        reliable.success_count = 4;
+        let mut broken = failing.clone();
+        broken.id = Uuid::new_v4();
+        broken.failure_count = 20;
+        for skill in [&failing, &reliable, &broken] {
+            store.create_skill(skill).await.unwrap();
+        }
+
+        let results = EmbeddingSearcher::local(store)
+            .search("fix flaky test", space.id, SearchMode::Fast, 5)
+            .await
+            .unwrap();
+        let ids: Vec<Uuid> = results.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![reliable.id, failing.id]);
+    }
+
+    #[test]
+    fn test_cosine_similarity_handles_zero_vectors() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]).abs() < f32::EPSILON);
     }
 }
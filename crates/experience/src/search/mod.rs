@@ -6,7 +6,7 @@ mod agentic;
 mod embedding;
 
 pub use agentic::AgenticSearcher;
-pub use embedding::EmbeddingSearcher;
+pub use embedding::{cosine_similarity, Embedder, EmbeddingSearcher, HashedEmbedder};
 
 use crate::models::{SearchMode, Skill};
 use anyhow::Result;
@@ -1,10 +1,19 @@
-//! `PostgreSQL` storage layer for experience data.
+//! Storage layer for experience data.
+//!
+//! [`PostgresStore`] is the shared production backend; [`SqliteStore`] is an
+//! embedded single-file backend for development and CI.
 
+#[cfg(feature = "postgres")]
 mod postgres;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 
+#[cfg(feature = "postgres")]
 pub use postgres::PostgresStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
 
-use crate::models::{SessionRecord, Skill, Space, TaskRecord};
+use crate::models::{SessionRecord, Skill, SkillOutcome, SkillVersion, Space, TaskRecord};
 use anyhow::Result;
 use async_trait::async_trait;
 use uuid::Uuid;
@@ -29,6 +38,10 @@ pub trait ExperienceStore: Send + Sync {
     async fn get_tasks_for_session(&self, session_id: Uuid) -> Result<Vec<TaskRecord>>;
 
     // Skill operations
+    //
+    // Creating or updating a skill also snapshots its current version if
+    // that version has not been recorded yet. Merged skills are left out of
+    // searches and space listings.
     async fn create_skill(&self, skill: &Skill) -> Result<Skill>;
     async fn update_skill(&self, skill: &Skill) -> Result<()>;
     async fn get_skill(&self, id: Uuid) -> Result<Option<Skill>>;
@@ -39,4 +52,9 @@ pub trait ExperienceStore: Send + Sync {
         limit: usize,
     ) -> Result<Vec<Skill>>;
     async fn get_skills_for_space(&self, space_id: Uuid, limit: usize) -> Result<Vec<Skill>>;
+    async fn get_skill_versions(&self, skill_id: Uuid) -> Result<Vec<SkillVersion>>;
+
+    // Skill outcome operations
+    async fn record_skill_outcome(&self, outcome: &SkillOutcome) -> Result<()>;
+    async fn get_skill_outcomes(&self, skill_id: Uuid) -> Result<Vec<SkillOutcome>>;
 }
@@ -1,111 +1,551 @@
 //! `PostgreSQL` storage implementation.
+//!
+//! Schema lives in `crates/experience/migrations` and is applied with
+//! [`PostgresStore::migrate`]. Nested collections (messages, tool calls,
+//! SOP steps) are stored as JSONB; skill embeddings use a pgvector
+//! `vector` column searched by cosine distance.
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use async_trait::async_trait;
+use pgvector::Vector;
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::types::Json;
+use sqlx::Row;
 use uuid::Uuid;
 
 use super::ExperienceStore;
-use crate::models::{SessionRecord, Skill, Space, TaskRecord};
+use crate::models::{
+    MessageRecord, SessionRecord, Skill, SkillOutcome, SkillVersion, Space, TaskRecord,
+    ToolCallRecord, ToolStep,
+};
+
+/// Embedded schema migrations.
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
 
 /// `PostgreSQL`-based storage for experience data.
 pub struct PostgresStore {
-    #[allow(dead_code)]
-    pool: sqlx::PgPool,
+    pool: PgPool,
 }
 
 impl PostgresStore {
-    /// Create a new `PostgreSQL` store.
+    /// Connect to `PostgreSQL` and apply pending migrations.
     pub async fn new(database_url: &str) -> Result<Self> {
-        let pool = sqlx::PgPool::connect(database_url).await?;
-        Ok(Self { pool })
+        let pool = PgPool::connect(database_url)
+            .await
+            .context("failed to connect to experience database")?;
+        let store = Self { pool };
+        store.migrate().await?;
+        Ok(store)
     }
 
     /// Create from an existing pool.
+    ///
+    /// Migrations are not applied; call [`migrate`](Self::migrate) if the
+    /// schema may be missing.
     #[must_use]
-    pub fn from_pool(pool: sqlx::PgPool) -> Self {
+    pub fn from_pool(pool: PgPool) -> Self {
         Self { pool }
     }
+
+    /// Apply pending schema migrations.
+    pub async fn migrate(&self) -> Result<()> {
+        MIGRATOR
+            .run(&self.pool)
+            .await
+            .context("failed to migrate experience database")
+    }
+
+    /// The underlying connection pool.
+    #[must_use]
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    async fn insert_task(
+        executor: impl sqlx::PgExecutor<'_>,
+        task: &TaskRecord,
+        session_id: Uuid,
+    ) -> Result<TaskRecord> {
+        let row = sqlx::query(
+            r"INSERT INTO experience_tasks
+                (uuid, session_id, task_id, description, status, progresses,
+                 user_preferences, tool_calls, agent, started_at, completed_at)
+              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+              RETURNING *",
+        )
+        .bind(task.uuid)
+        .bind(session_id)
+        .bind(&task.id)
+        .bind(&task.description)
+        .bind(task.status.to_string())
+        .bind(Json(&task.progresses))
+        .bind(Json(&task.user_preferences))
+        .bind(Json(&task.tool_calls))
+        .bind(&task.agent)
+        .bind(task.started_at)
+        .bind(task.completed_at)
+        .fetch_one(executor)
+        .await
+        .with_context(|| format!("failed to insert task {}", task.uuid))?;
+        task_from_row(&row)
+    }
+
+    async fn snapshot_version(executor: impl sqlx::PgExecutor<'_>, skill: &Skill) -> Result<()> {
+        let version = SkillVersion::of(skill);
+        sqlx::query(
+            r"INSERT INTO experience_skill_versions
+                (skill_id, version, use_when, preferences, tool_sops, created_at)
+              VALUES ($1, $2, $3, $4, $5, $6)
+              ON CONFLICT (skill_id, version) DO NOTHING",
+        )
+        .bind(version.skill_id)
+        .bind(to_i32(version.version))
+        .bind(&version.use_when)
+        .bind(Json(&version.preferences))
+        .bind(Json(&version.tool_sops))
+        .bind(version.created_at)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
+    async fn load_session(&self, row: Option<PgRow>) -> Result<Option<SessionRecord>> {
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let mut session = session_from_row(&row)?;
+        session.tasks = self.get_tasks_for_session(session.id).await?;
+        Ok(Some(session))
+    }
 }
 
 #[async_trait]
 impl ExperienceStore for PostgresStore {
     async fn create_space(&self, space: &Space) -> Result<Space> {
-        // TODO: Implement SQL insert
-        Ok(space.clone())
+        let row = sqlx::query(
+            r"INSERT INTO experience_spaces (id, name, project_id, user_id, metadata, created_at)
+              VALUES ($1, $2, $3, $4, $5, $6)
+              RETURNING *",
+        )
+        .bind(space.id)
+        .bind(&space.name)
+        .bind(&space.project_id)
+        .bind(&space.user_id)
+        .bind(&space.metadata)
+        .bind(space.created_at)
+        .fetch_one(&self.pool)
+        .await
+        .with_context(|| format!("failed to insert space {}", space.id))?;
+        space_from_row(&row)
     }
 
-    async fn get_space(&self, _id: Uuid) -> Result<Option<Space>> {
-        // TODO: Implement SQL select
-        Ok(None)
+    async fn get_space(&self, id: Uuid) -> Result<Option<Space>> {
+        let row = sqlx::query("SELECT * FROM experience_spaces WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        row.as_ref().map(space_from_row).transpose()
     }
 
-    async fn get_space_by_project(&self, _project_id: &str) -> Result<Option<Space>> {
-        // TODO: Implement SQL select
-        Ok(None)
+    async fn get_space_by_project(&self, project_id: &str) -> Result<Option<Space>> {
+        let row = sqlx::query(
+            r"SELECT * FROM experience_spaces
+              WHERE project_id = $1
+              ORDER BY created_at
+              LIMIT 1",
+        )
+        .bind(project_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.as_ref().map(space_from_row).transpose()
     }
 
     async fn create_session(&self, session: &SessionRecord) -> Result<SessionRecord> {
-        // TODO: Implement SQL insert
-        Ok(session.clone())
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            r"INSERT INTO experience_sessions
+                (id, play_id, space_id, status, messages, repository, service,
+                 metadata, started_at, completed_at)
+              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+              RETURNING *",
+        )
+        .bind(session.id)
+        .bind(&session.play_id)
+        .bind(session.space_id)
+        .bind(session.status.to_string())
+        .bind(Json(&session.messages))
+        .bind(&session.repository)
+        .bind(&session.service)
+        .bind(&session.metadata)
+        .bind(session.started_at)
+        .bind(session.completed_at)
+        .fetch_one(&mut *tx)
+        .await
+        .with_context(|| format!("failed to insert session {}", session.id))?;
+
+        let mut created = session_from_row(&row)?;
+        for task in &session.tasks {
+            created
+                .tasks
+                .push(Self::insert_task(&mut *tx, task, session.id).await?);
+        }
+        tx.commit().await?;
+        Ok(created)
     }
 
-    async fn update_session(&self, _session: &SessionRecord) -> Result<()> {
-        // TODO: Implement SQL update
+    async fn update_session(&self, session: &SessionRecord) -> Result<()> {
+        let result = sqlx::query(
+            r"UPDATE experience_sessions
+              SET status = $2, messages = $3, repository = $4, service = $5,
+                  metadata = $6, completed_at = $7
+              WHERE id = $1",
+        )
+        .bind(session.id)
+        .bind(session.status.to_string())
+        .bind(Json(&session.messages))
+        .bind(&session.repository)
+        .bind(&session.service)
+        .bind(&session.metadata)
+        .bind(session.completed_at)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            bail!("session {} not found", session.id);
+        }
         Ok(())
     }
 
-    async fn get_session(&self, _id: Uuid) -> Result<Option<SessionRecord>> {
-        // TODO: Implement SQL select
-        Ok(None)
+    async fn get_session(&self, id: Uuid) -> Result<Option<SessionRecord>> {
+        let row = sqlx::query("SELECT * FROM experience_sessions WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        self.load_session(row).await
     }
 
-    async fn get_session_by_play_id(&self, _play_id: &str) -> Result<Option<SessionRecord>> {
-        // TODO: Implement SQL select
-        Ok(None)
+    async fn get_session_by_play_id(&self, play_id: &str) -> Result<Option<SessionRecord>> {
+        let row = sqlx::query(
+            r"SELECT * FROM experience_sessions
+              WHERE play_id = $1
+              ORDER BY started_at DESC
+              LIMIT 1",
+        )
+        .bind(play_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        self.load_session(row).await
     }
 
-    async fn create_task(&self, task: &TaskRecord, _session_id: Uuid) -> Result<TaskRecord> {
-        // TODO: Implement SQL insert
-        Ok(task.clone())
+    async fn create_task(&self, task: &TaskRecord, session_id: Uuid) -> Result<TaskRecord> {
+        Self::insert_task(&self.pool, task, session_id).await
     }
 
-    async fn update_task(&self, _task: &TaskRecord) -> Result<()> {
-        // TODO: Implement SQL update
+    async fn update_task(&self, task: &TaskRecord) -> Result<()> {
+        let result = sqlx::query(
+            r"UPDATE experience_tasks
+              SET description = $2, status = $3, progresses = $4, user_preferences = $5,
+                  tool_calls = $6, agent = $7, started_at = $8, completed_at = $9
+              WHERE uuid = $1",
+        )
+        .bind(task.uuid)
+        .bind(&task.description)
+        .bind(task.status.to_string())
+        .bind(Json(&task.progresses))
+        .bind(Json(&task.user_preferences))
+        .bind(Json(&task.tool_calls))
+        .bind(&task.agent)
+        .bind(task.started_at)
+        .bind(task.completed_at)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            bail!("task {} not found", task.uuid);
+        }
         Ok(())
     }
 
-    async fn get_tasks_for_session(&self, _session_id: Uuid) -> Result<Vec<TaskRecord>> {
-        // TODO: Implement SQL select
-        Ok(Vec::new())
+    async fn get_tasks_for_session(&self, session_id: Uuid) -> Result<Vec<TaskRecord>> {
+        let rows = sqlx::query(
+            r"SELECT * FROM experience_tasks
+              WHERE session_id = $1
+              ORDER BY started_at, task_id",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(task_from_row).collect()
     }
 
     async fn create_skill(&self, skill: &Skill) -> Result<Skill> {
-        // TODO: Implement SQL insert with pgvector
-        Ok(skill.clone())
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            r"INSERT INTO experience_skills
+                (id, space_id, use_when, agent, preferences, tool_sops, complexity_score,
+                 success_count, failure_count, version, last_outcome_at, merged_into,
+                 embedding, created_at, updated_at)
+              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+              RETURNING *",
+        )
+        .bind(skill.id)
+        .bind(skill.space_id)
+        .bind(&skill.use_when)
+        .bind(skill.agent.to_string())
+        .bind(Json(&skill.preferences))
+        .bind(Json(&skill.tool_sops))
+        .bind(skill.complexity_score)
+        .bind(to_i32(skill.success_count))
+        .bind(to_i32(skill.failure_count))
+        .bind(to_i32(skill.version))
+        .bind(skill.last_outcome_at)
+        .bind(skill.merged_into)
+        .bind(skill.embedding.clone().map(Vector::from))
+        .bind(skill.created_at)
+        .bind(skill.updated_at)
+        .fetch_one(&mut *tx)
+        .await
+        .with_context(|| format!("failed to insert skill {}", skill.id))?;
+        Self::snapshot_version(&mut *tx, skill).await?;
+        tx.commit().await?;
+        skill_from_row(&row)
     }
 
-    async fn update_skill(&self, _skill: &Skill) -> Result<()> {
-        // TODO: Implement SQL update
+    async fn update_skill(&self, skill: &Skill) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            r"UPDATE experience_skills
+              SET use_when = $2, agent = $3, preferences = $4, tool_sops = $5,
+                  complexity_score = $6, success_count = $7, failure_count = $8,
+                  version = $9, last_outcome_at = $10, merged_into = $11,
+                  embedding = $12, updated_at = $13
+              WHERE id = $1",
+        )
+        .bind(skill.id)
+        .bind(&skill.use_when)
+        .bind(skill.agent.to_string())
+        .bind(Json(&skill.preferences))
+        .bind(Json(&skill.tool_sops))
+        .bind(skill.complexity_score)
+        .bind(to_i32(skill.success_count))
+        .bind(to_i32(skill.failure_count))
+        .bind(to_i32(skill.version))
+        .bind(skill.last_outcome_at)
+        .bind(skill.merged_into)
+        .bind(skill.embedding.clone().map(Vector::from))
+        .bind(skill.updated_at)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            bail!("skill {} not found", skill.id);
+        }
+        Self::snapshot_version(&mut *tx, skill).await?;
+        tx.commit().await?;
         Ok(())
     }
 
-    async fn get_skill(&self, _id: Uuid) -> Result<Option<Skill>> {
-        // TODO: Implement SQL select
-        Ok(None)
+    async fn get_skill(&self, id: Uuid) -> Result<Option<Skill>> {
+        let row = sqlx::query("SELECT * FROM experience_skills WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        row.as_ref().map(skill_from_row).transpose()
     }
 
     async fn search_skills_by_embedding(
         &self,
-        _embedding: &[f32],
-        _space_id: Uuid,
-        _limit: usize,
+        embedding: &[f32],
+        space_id: Uuid,
+        limit: usize,
     ) -> Result<Vec<Skill>> {
-        // TODO: Implement vector similarity search
-        Ok(Vec::new())
+        if embedding.is_empty() {
+            return Ok(Vec::new());
+        }
+        // `<=>` is pgvector's cosine distance; skills embedded with a
+        // different dimension than the query are skipped rather than erroring.
+        let rows = sqlx::query(
+            r"SELECT * FROM experience_skills
+              WHERE space_id = $2
+                AND merged_into IS NULL
+                AND embedding IS NOT NULL
+                AND vector_dims(embedding) = vector_dims($1)
+              ORDER BY embedding <=> $1, success_count DESC
+              LIMIT $3",
+        )
+        .bind(Vector::from(embedding.to_vec()))
+        .bind(space_id)
+        .bind(to_limit(limit))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(skill_from_row).collect()
+    }
+
+    async fn get_skills_for_space(&self, space_id: Uuid, limit: usize) -> Result<Vec<Skill>> {
+        let rows = sqlx::query(
+            r"SELECT * FROM experience_skills
+              WHERE space_id = $1 AND merged_into IS NULL
+              ORDER BY success_count DESC, updated_at DESC
+              LIMIT $2",
+        )
+        .bind(space_id)
+        .bind(to_limit(limit))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(skill_from_row).collect()
     }
 
-    async fn get_skills_for_space(&self, _space_id: Uuid, _limit: usize) -> Result<Vec<Skill>> {
-        // TODO: Implement SQL select
-        Ok(Vec::new())
+    async fn get_skill_versions(&self, skill_id: Uuid) -> Result<Vec<SkillVersion>> {
+        let rows = sqlx::query(
+            r"SELECT * FROM experience_skill_versions
+              WHERE skill_id = $1
+              ORDER BY version",
+        )
+        .bind(skill_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(version_from_row).collect()
     }
+
+    async fn record_skill_outcome(&self, outcome: &SkillOutcome) -> Result<()> {
+        sqlx::query(
+            r"INSERT INTO experience_skill_outcomes
+                (id, skill_id, skill_version, session_id, success, notes, recorded_at)
+              VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(outcome.id)
+        .bind(outcome.skill_id)
+        .bind(to_i32(outcome.skill_version))
+        .bind(outcome.session_id)
+        .bind(outcome.success)
+        .bind(&outcome.notes)
+        .bind(outcome.recorded_at)
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("failed to record outcome for skill {}", outcome.skill_id))?;
+        Ok(())
+    }
+
+    async fn get_skill_outcomes(&self, skill_id: Uuid) -> Result<Vec<SkillOutcome>> {
+        let rows = sqlx::query(
+            r"SELECT * FROM experience_skill_outcomes
+              WHERE skill_id = $1
+              ORDER BY recorded_at",
+        )
+        .bind(skill_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(outcome_from_row).collect()
+    }
+}
+
+fn space_from_row(row: &PgRow) -> Result<Space> {
+    Ok(Space {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        project_id: row.try_get("project_id")?,
+        user_id: row.try_get("user_id")?,
+        metadata: row.try_get("metadata")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn session_from_row(row: &PgRow) -> Result<SessionRecord> {
+    let status: String = row.try_get("status")?;
+    let Json(messages): Json<Vec<MessageRecord>> = row.try_get("messages")?;
+    Ok(SessionRecord {
+        id: row.try_get("id")?,
+        play_id: row.try_get("play_id")?,
+        space_id: row.try_get("space_id")?,
+        status: status.parse().map_err(|e: String| anyhow!(e))?,
+        tasks: Vec::new(),
+        messages,
+        repository: row.try_get("repository")?,
+        service: row.try_get("service")?,
+        metadata: row.try_get("metadata")?,
+        started_at: row.try_get("started_at")?,
+        completed_at: row.try_get("completed_at")?,
+    })
+}
+
+fn task_from_row(row: &PgRow) -> Result<TaskRecord> {
+    let status: String = row.try_get("status")?;
+    let Json(progresses): Json<Vec<String>> = row.try_get("progresses")?;
+    let Json(user_preferences): Json<Vec<String>> = row.try_get("user_preferences")?;
+    let Json(tool_calls): Json<Vec<ToolCallRecord>> = row.try_get("tool_calls")?;
+    Ok(TaskRecord {
+        uuid: row.try_get("uuid")?,
+        id: row.try_get("task_id")?,
+        description: row.try_get("description")?,
+        status: status.parse().map_err(|e: String| anyhow!(e))?,
+        progresses,
+        user_preferences,
+        tool_calls,
+        agent: row.try_get("agent")?,
+        started_at: row.try_get("started_at")?,
+        completed_at: row.try_get("completed_at")?,
+    })
+}
+
+fn skill_from_row(row: &PgRow) -> Result<Skill> {
+    let agent: String = row.try_get("agent")?;
+    let Json(preferences): Json<Vec<String>> = row.try_get("preferences")?;
+    let Json(tool_sops): Json<Vec<ToolStep>> = row.try_get("tool_sops")?;
+    let success_count: i32 = row.try_get("success_count")?;
+    let failure_count: i32 = row.try_get("failure_count")?;
+    let version: i32 = row.try_get("version")?;
+    let embedding: Option<Vector> = row.try_get("embedding")?;
+    Ok(Skill {
+        id: row.try_get("id")?,
+        use_when: row.try_get("use_when")?,
+        agent: agent.parse().map_err(|e: String| anyhow!(e))?,
+        preferences,
+        tool_sops,
+        complexity_score: row.try_get("complexity_score")?,
+        success_count: to_u32(success_count),
+        failure_count: to_u32(failure_count),
+        version: to_u32(version),
+        last_outcome_at: row.try_get("last_outcome_at")?,
+        merged_into: row.try_get("merged_into")?,
+        space_id: row.try_get("space_id")?,
+        embedding: embedding.map(|vector| vector.to_vec()),
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+fn version_from_row(row: &PgRow) -> Result<SkillVersion> {
+    let version: i32 = row.try_get("version")?;
+    let Json(preferences): Json<Vec<String>> = row.try_get("preferences")?;
+    let Json(tool_sops): Json<Vec<ToolStep>> = row.try_get("tool_sops")?;
+    Ok(SkillVersion {
+        skill_id: row.try_get("skill_id")?,
+        version: to_u32(version),
+        use_when: row.try_get("use_when")?,
+        preferences,
+        tool_sops,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+fn outcome_from_row(row: &PgRow) -> Result<SkillOutcome> {
+    let skill_version: i32 = row.try_get("skill_version")?;
+    Ok(SkillOutcome {
+        id: row.try_get("id")?,
+        skill_id: row.try_get("skill_id")?,
+        skill_version: to_u32(skill_version),
+        session_id: row.try_get("session_id")?,
+        success: row.try_get("success")?,
+        notes: row.try_get("notes")?,
+        recorded_at: row.try_get("recorded_at")?,
+    })
+}
+
+fn to_u32(value: i32) -> u32 {
+    u32::try_from(value).unwrap_or(0)
+}
+
+fn to_i32(value: u32) -> i32 {
+    i32::try_from(value).unwrap_or(i32::MAX)
+}
+
+fn to_limit(limit: usize) -> i64 {
+    i64::try_from(limit).unwrap_or(i64::MAX)
 }
@@ -0,0 +1,823 @@
+//! Embedded `SQLite` storage implementation.
+//!
+//! Keeps everything in a single file (or in memory) so the learn → search
+//! loop runs without Postgres. Nested collections are stored as JSON text,
+//! timestamps as microseconds since the epoch and embeddings as
+//! little-endian `f32` blobs. Embedding search is an exact cosine scan over
+//! the space's skills, which is fine at the scale of a single project.
+
+use std::cmp::Ordering;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::ExperienceStore;
+use crate::models::{SessionRecord, Skill, SkillOutcome, SkillVersion, Space, TaskRecord};
+use crate::search::cosine_similarity;
+
+/// Schema migrations, applied in order and tracked with `user_version`.
+const MIGRATIONS: &[&str] = &[
+    // 1: spaces, sessions, tasks and skills
+    "CREATE TABLE experience_spaces (
+        id         TEXT PRIMARY KEY,
+        name       TEXT NOT NULL,
+        project_id TEXT,
+        user_id    TEXT,
+        metadata   TEXT NOT NULL,
+        created_us INTEGER NOT NULL
+    );
+    CREATE INDEX experience_spaces_project ON experience_spaces (project_id);
+    CREATE TABLE experience_sessions (
+        id           TEXT PRIMARY KEY,
+        play_id      TEXT NOT NULL,
+        space_id     TEXT NOT NULL REFERENCES experience_spaces (id) ON DELETE CASCADE,
+        status       TEXT NOT NULL,
+        messages     TEXT NOT NULL,
+        repository   TEXT,
+        service      TEXT,
+        metadata     TEXT NOT NULL,
+        started_us   INTEGER NOT NULL,
+        completed_us INTEGER
+    );
+    CREATE INDEX experience_sessions_play ON experience_sessions (play_id, started_us);
+    CREATE TABLE experience_tasks (
+        uuid             TEXT PRIMARY KEY,
+        session_id       TEXT NOT NULL REFERENCES experience_sessions (id) ON DELETE CASCADE,
+        task_id          TEXT NOT NULL,
+        description      TEXT NOT NULL,
+        status           TEXT NOT NULL,
+        progresses       TEXT NOT NULL,
+        user_preferences TEXT NOT NULL,
+        tool_calls       TEXT NOT NULL,
+        agent            TEXT,
+        started_us       INTEGER NOT NULL,
+        completed_us     INTEGER
+    );
+    CREATE INDEX experience_tasks_session ON experience_tasks (session_id, started_us);
+    CREATE TABLE experience_skills (
+        id               TEXT PRIMARY KEY,
+        space_id         TEXT NOT NULL REFERENCES experience_spaces (id) ON DELETE CASCADE,
+        use_when         TEXT NOT NULL,
+        agent            TEXT NOT NULL,
+        preferences      TEXT NOT NULL,
+        tool_sops        TEXT NOT NULL,
+        complexity_score REAL NOT NULL,
+        success_count    INTEGER NOT NULL,
+        embedding        BLOB,
+        created_us       INTEGER NOT NULL,
+        updated_us       INTEGER NOT NULL
+    );
+    CREATE INDEX experience_skills_space
+        ON experience_skills (space_id, success_count DESC, updated_us DESC);",
+    // 2: skill versions, outcomes and deduplication
+    "ALTER TABLE experience_skills ADD COLUMN failure_count INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE experience_skills ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+    ALTER TABLE experience_skills ADD COLUMN last_outcome_us INTEGER;
+    ALTER TABLE experience_skills ADD COLUMN merged_into TEXT
+        REFERENCES experience_skills (id) ON DELETE SET NULL;
+    CREATE TABLE experience_skill_versions (
+        skill_id    TEXT NOT NULL REFERENCES experience_skills (id) ON DELETE CASCADE,
+        version     INTEGER NOT NULL,
+        use_when    TEXT NOT NULL,
+        preferences TEXT NOT NULL,
+        tool_sops   TEXT NOT NULL,
+        created_us  INTEGER NOT NULL,
+        PRIMARY KEY (skill_id, version)
+    );
+    CREATE TABLE experience_skill_outcomes (
+        id            TEXT PRIMARY KEY,
+        skill_id      TEXT NOT NULL REFERENCES experience_skills (id) ON DELETE CASCADE,
+        skill_version INTEGER NOT NULL,
+        session_id    TEXT NOT NULL,
+        success       INTEGER NOT NULL,
+        notes         TEXT,
+        recorded_us   INTEGER NOT NULL
+    );
+    CREATE INDEX experience_skill_outcomes_skill
+        ON experience_skill_outcomes (skill_id, recorded_us);
+    INSERT OR IGNORE INTO experience_skill_versions
+        SELECT id, version, use_when, preferences, tool_sops, created_us FROM experience_skills;",
+];
+
+/// Embedded `SQLite` storage for experience data.
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open (or create) a store file and bring its schema up to date.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open experience store {}", path.display()))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        Self::init(conn)
+    }
+
+    /// Create a throwaway in-memory store.
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self> {
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)] // A handful of migrations
+const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let current: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if current > SCHEMA_VERSION {
+        bail!(
+            "experience store schema version {current} is newer than supported version {SCHEMA_VERSION}"
+        );
+    }
+    for (version, sql) in (1..).zip(MIGRATIONS).skip_while(|(v, _)| *v <= current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn insert_task(conn: &Connection, task: &TaskRecord, session_id: Uuid) -> Result<()> {
+    conn.execute(
+        "INSERT INTO experience_tasks
+            (uuid, session_id, task_id, description, status, progresses,
+             user_preferences, tool_calls, agent, started_us, completed_us)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            task.uuid.to_string(),
+            session_id.to_string(),
+            task.id,
+            task.description,
+            task.status.to_string(),
+            to_json(&task.progresses)?,
+            to_json(&task.user_preferences)?,
+            to_json(&task.tool_calls)?,
+            task.agent,
+            task.started_at.timestamp_micros(),
+            task.completed_at.map(|t| t.timestamp_micros()),
+        ],
+    )
+    .with_context(|| format!("failed to insert task {}", task.uuid))?;
+    Ok(())
+}
+
+fn tasks_for_session(conn: &Connection, session_id: Uuid) -> Result<Vec<TaskRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT * FROM experience_tasks WHERE session_id = ? ORDER BY started_us, task_id",
+    )?;
+    let tasks = stmt
+        .query_map([session_id.to_string()], task_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tasks)
+}
+
+fn load_session(
+    conn: &Connection,
+    session: Option<SessionRecord>,
+) -> Result<Option<SessionRecord>> {
+    let Some(mut session) = session else {
+        return Ok(None);
+    };
+    session.tasks = tasks_for_session(conn, session.id)?;
+    Ok(Some(session))
+}
+
+fn snapshot_version(conn: &Connection, skill: &Skill) -> Result<()> {
+    let version = SkillVersion::of(skill);
+    conn.execute(
+        "INSERT OR IGNORE INTO experience_skill_versions
+            (skill_id, version, use_when, preferences, tool_sops, created_us)
+         VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            version.skill_id.to_string(),
+            version.version,
+            version.use_when,
+            to_json(&version.preferences)?,
+            to_json(&version.tool_sops)?,
+            version.created_at.timestamp_micros(),
+        ],
+    )?;
+    Ok(())
+}
+
+#[async_trait]
+impl ExperienceStore for SqliteStore {
+    async fn create_space(&self, space: &Space) -> Result<Space> {
+        self.lock()
+            .execute(
+                "INSERT INTO experience_spaces (id, name, project_id, user_id, metadata, created_us)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                params![
+                    space.id.to_string(),
+                    space.name,
+                    space.project_id,
+                    space.user_id,
+                    to_json(&space.metadata)?,
+                    space.created_at.timestamp_micros(),
+                ],
+            )
+            .with_context(|| format!("failed to insert space {}", space.id))?;
+        Ok(space.clone())
+    }
+
+    async fn get_space(&self, id: Uuid) -> Result<Option<Space>> {
+        Ok(self
+            .lock()
+            .query_row(
+                "SELECT * FROM experience_spaces WHERE id = ?",
+                [id.to_string()],
+                space_from_row,
+            )
+            .optional()?)
+    }
+
+    async fn get_space_by_project(&self, project_id: &str) -> Result<Option<Space>> {
+        Ok(self
+            .lock()
+            .query_row(
+                "SELECT * FROM experience_spaces WHERE project_id = ?
+                 ORDER BY created_us LIMIT 1",
+                [project_id],
+                space_from_row,
+            )
+            .optional()?)
+    }
+
+    async fn create_session(&self, session: &SessionRecord) -> Result<SessionRecord> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO experience_sessions
+                (id, play_id, space_id, status, messages, repository, service,
+                 metadata, started_us, completed_us)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                session.id.to_string(),
+                session.play_id,
+                session.space_id.to_string(),
+                session.status.to_string(),
+                to_json(&session.messages)?,
+                session.repository,
+                session.service,
+                to_json(&session.metadata)?,
+                session.started_at.timestamp_micros(),
+                session.completed_at.map(|t| t.timestamp_micros()),
+            ],
+        )
+        .with_context(|| format!("failed to insert session {}", session.id))?;
+        for task in &session.tasks {
+            insert_task(&tx, task, session.id)?;
+        }
+        tx.commit()?;
+        Ok(session.clone())
+    }
+
+    async fn update_session(&self, session: &SessionRecord) -> Result<()> {
+        let updated = self.lock().execute(
+            "UPDATE experience_sessions
+             SET status = ?2, messages = ?3, repository = ?4, service = ?5,
+                 metadata = ?6, completed_us = ?7
+             WHERE id = ?1",
+            params![
+                session.id.to_string(),
+                session.status.to_string(),
+                to_json(&session.messages)?,
+                session.repository,
+                session.service,
+                to_json(&session.metadata)?,
+                session.completed_at.map(|t| t.timestamp_micros()),
+            ],
+        )?;
+        if updated == 0 {
+            bail!("session {} not found", session.id);
+        }
+        Ok(())
+    }
+
+    async fn get_session(&self, id: Uuid) -> Result<Option<SessionRecord>> {
+        let conn = self.lock();
+        let session = conn
+            .query_row(
+                "SELECT * FROM experience_sessions WHERE id = ?",
+                [id.to_string()],
+                session_from_row,
+            )
+            .optional()?;
+        load_session(&conn, session)
+    }
+
+    async fn get_session_by_play_id(&self, play_id: &str) -> Result<Option<SessionRecord>> {
+        let conn = self.lock();
+        let session = conn
+            .query_row(
+                "SELECT * FROM experience_sessions WHERE play_id = ?
+                 ORDER BY started_us DESC LIMIT 1",
+                [play_id],
+                session_from_row,
+            )
+            .optional()?;
+        load_session(&conn, session)
+    }
+
+    async fn create_task(&self, task: &TaskRecord, session_id: Uuid) -> Result<TaskRecord> {
+        insert_task(&self.lock(), task, session_id)?;
+        Ok(task.clone())
+    }
+
+    async fn update_task(&self, task: &TaskRecord) -> Result<()> {
+        let updated = self.lock().execute(
+            "UPDATE experience_tasks
+             SET description = ?2, status = ?3, progresses = ?4, user_preferences = ?5,
+                 tool_calls = ?6, agent = ?7, started_us = ?8, completed_us = ?9
+             WHERE uuid = ?1",
+            params![
+                task.uuid.to_string(),
+                task.description,
+                task.status.to_string(),
+                to_json(&task.progresses)?,
+                to_json(&task.user_preferences)?,
+                to_json(&task.tool_calls)?,
+                task.agent,
+                task.started_at.timestamp_micros(),
+                task.completed_at.map(|t| t.timestamp_micros()),
+            ],
+        )?;
+        if updated == 0 {
+            bail!("task {} not found", task.uuid);
+        }
+        Ok(())
+    }
+
+    async fn get_tasks_for_session(&self, session_id: Uuid) -> Result<Vec<TaskRecord>> {
+        tasks_for_session(&self.lock(), session_id)
+    }
+
+    async fn create_skill(&self, skill: &Skill) -> Result<Skill> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO experience_skills
+                (id, space_id, use_when, agent, preferences, tool_sops, complexity_score,
+                 success_count, failure_count, version, last_outcome_us, merged_into,
+                 embedding, created_us, updated_us)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                skill.id.to_string(),
+                skill.space_id.to_string(),
+                skill.use_when,
+                skill.agent.to_string(),
+                to_json(&skill.preferences)?,
+                to_json(&skill.tool_sops)?,
+                f64::from(skill.complexity_score),
+                skill.success_count,
+                skill.failure_count,
+                skill.version,
+                skill.last_outcome_at.map(|t| t.timestamp_micros()),
+                skill.merged_into.map(|id| id.to_string()),
+                skill.embedding.as_deref().map(embedding_to_blob),
+                skill.created_at.timestamp_micros(),
+                skill.updated_at.timestamp_micros(),
+            ],
+        )
+        .with_context(|| format!("failed to insert skill {}", skill.id))?;
+        snapshot_version(&tx, skill)?;
+        tx.commit()?;
+        Ok(skill.clone())
+    }
+
+    async fn update_skill(&self, skill: &Skill) -> Result<()> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        let updated = tx.execute(
+            "UPDATE experience_skills
+             SET use_when = ?2, agent = ?3, preferences = ?4, tool_sops = ?5,
+                 complexity_score = ?6, success_count = ?7, failure_count = ?8,
+                 version = ?9, last_outcome_us = ?10, merged_into = ?11,
+                 embedding = ?12, updated_us = ?13
+             WHERE id = ?1",
+            params![
+                skill.id.to_string(),
+                skill.use_when,
+                skill.agent.to_string(),
+                to_json(&skill.preferences)?,
+                to_json(&skill.tool_sops)?,
+                f64::from(skill.complexity_score),
+                skill.success_count,
+                skill.failure_count,
+                skill.version,
+                skill.last_outcome_at.map(|t| t.timestamp_micros()),
+                skill.merged_into.map(|id| id.to_string()),
+                skill.embedding.as_deref().map(embedding_to_blob),
+                skill.updated_at.timestamp_micros(),
+            ],
+        )?;
+        if updated == 0 {
+            bail!("skill {} not found", skill.id);
+        }
+        snapshot_version(&tx, skill)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn get_skill(&self, id: Uuid) -> Result<Option<Skill>> {
+        Ok(self
+            .lock()
+            .query_row(
+                "SELECT * FROM experience_skills WHERE id = ?",
+                [id.to_string()],
+                skill_from_row,
+            )
+            .optional()?)
+    }
+
+    async fn search_skills_by_embedding(
+        &self,
+        embedding: &[f32],
+        space_id: Uuid,
+        limit: usize,
+    ) -> Result<Vec<Skill>> {
+        if embedding.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = self.lock();
+        let mut stmt = conn.prepare(
+            "SELECT * FROM experience_skills
+             WHERE space_id = ? AND merged_into IS NULL AND embedding IS NOT NULL",
+        )?;
+        let skills = stmt
+            .query_map([space_id.to_string()], skill_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Skills embedded with a different dimension than the query are
+        // skipped rather than erroring, matching the Postgres store.
+        let mut scored: Vec<(f32, Skill)> = skills
+            .into_iter()
+            .filter_map(|skill| {
+                let stored = skill.embedding.as_deref()?;
+                let score = (stored.len() == embedding.len())
+                    .then(|| cosine_similarity(embedding, stored))?;
+                Some((score, skill))
+            })
+            .collect();
+        scored.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .partial_cmp(a_score)
+                .unwrap_or(Ordering::Equal)
+                .then(b.success_count.cmp(&a.success_count))
+        });
+        Ok(scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, skill)| skill)
+            .collect())
+    }
+
+    async fn get_skills_for_space(&self, space_id: Uuid, limit: usize) -> Result<Vec<Skill>> {
+        let conn = self.lock();
+        let mut stmt = conn.prepare(
+            "SELECT * FROM experience_skills WHERE space_id = ? AND merged_into IS NULL
+             ORDER BY success_count DESC, updated_us DESC LIMIT ?",
+        )?;
+        let skills = stmt
+            .query_map(
+                params![
+                    space_id.to_string(),
+                    i64::try_from(limit).unwrap_or(i64::MAX)
+                ],
+                skill_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(skills)
+    }
+
+    async fn get_skill_versions(&self, skill_id: Uuid) -> Result<Vec<SkillVersion>> {
+        let conn = self.lock();
+        let mut stmt = conn.prepare(
+            "SELECT * FROM experience_skill_versions WHERE skill_id = ? ORDER BY version",
+        )?;
+        let versions = stmt
+            .query_map([skill_id.to_string()], version_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(versions)
+    }
+
+    async fn record_skill_outcome(&self, outcome: &SkillOutcome) -> Result<()> {
+        self.lock()
+            .execute(
+                "INSERT INTO experience_skill_outcomes
+                    (id, skill_id, skill_version, session_id, success, notes, recorded_us)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    outcome.id.to_string(),
+                    outcome.skill_id.to_string(),
+                    outcome.skill_version,
+                    outcome.session_id.to_string(),
+                    outcome.success,
+                    outcome.notes,
+                    outcome.recorded_at.timestamp_micros(),
+                ],
+            )
+            .with_context(|| format!("failed to record outcome for skill {}", outcome.skill_id))?;
+        Ok(())
+    }
+
+    async fn get_skill_outcomes(&self, skill_id: Uuid) -> Result<Vec<SkillOutcome>> {
+        let conn = self.lock();
+        let mut stmt = conn.prepare(
+            "SELECT * FROM experience_skill_outcomes WHERE skill_id = ? ORDER BY recorded_us",
+        )?;
+        let outcomes = stmt
+            .query_map([skill_id.to_string()], outcome_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(outcomes)
+    }
+}
+
+fn to_json(value: &impl Serialize) -> Result<String> {
+    Ok(serde_json::to_string(value)?)
+}
+
+fn conversion_error(
+    column: &str,
+    err: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        0,
+        Type::Text,
+        format!("{column}: {}", err.into()).into(),
+    )
+}
+
+fn get_uuid(row: &Row<'_>, column: &str) -> rusqlite::Result<Uuid> {
+    let value: String = row.get(column)?;
+    Uuid::parse_str(&value).map_err(|e| conversion_error(column, e))
+}
+
+fn get_optional_uuid(row: &Row<'_>, column: &str) -> rusqlite::Result<Option<Uuid>> {
+    match row.get::<_, Option<String>>(column)? {
+        Some(_) => get_uuid(row, column).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn get_json<T: DeserializeOwned>(row: &Row<'_>, column: &str) -> rusqlite::Result<T> {
+    let value: String = row.get(column)?;
+    serde_json::from_str(&value).map_err(|e| conversion_error(column, e))
+}
+
+fn get_parsed<T: std::str::FromStr<Err = String>>(
+    row: &Row<'_>,
+    column: &str,
+) -> rusqlite::Result<T> {
+    let value: String = row.get(column)?;
+    value
+        .parse()
+        .map_err(|e: String| conversion_error(column, e))
+}
+
+fn get_time(row: &Row<'_>, column: &str) -> rusqlite::Result<DateTime<Utc>> {
+    let micros: i64 = row.get(column)?;
+    DateTime::from_timestamp_micros(micros)
+        .ok_or_else(|| conversion_error(column, "timestamp out of range"))
+}
+
+fn get_optional_time(row: &Row<'_>, column: &str) -> rusqlite::Result<Option<DateTime<Utc>>> {
+    match row.get::<_, Option<i64>>(column)? {
+        Some(_) => get_time(row, column).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn embedding_from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+fn space_from_row(row: &Row<'_>) -> rusqlite::Result<Space> {
+    Ok(Space {
+        id: get_uuid(row, "id")?,
+        name: row.get("name")?,
+        project_id: row.get("project_id")?,
+        user_id: row.get("user_id")?,
+        metadata: get_json(row, "metadata")?,
+        created_at: get_time(row, "created_us")?,
+    })
+}
+
+fn session_from_row(row: &Row<'_>) -> rusqlite::Result<SessionRecord> {
+    Ok(SessionRecord {
+        id: get_uuid(row, "id")?,
+        play_id: row.get("play_id")?,
+        space_id: get_uuid(row, "space_id")?,
+        status: get_parsed(row, "status")?,
+        tasks: Vec::new(),
+        messages: get_json(row, "messages")?,
+        repository: row.get("repository")?,
+        service: row.get("service")?,
+        metadata: get_json(row, "metadata")?,
+        started_at: get_time(row, "started_us")?,
+        completed_at: get_optional_time(row, "completed_us")?,
+    })
+}
+
+fn task_from_row(row: &Row<'_>) -> rusqlite::Result<TaskRecord> {
+    Ok(TaskRecord {
+        uuid: get_uuid(row, "uuid")?,
+        id: row.get("task_id")?,
+        description: row.get("description")?,
+        status: get_parsed(row, "status")?,
+        progresses: get_json(row, "progresses")?,
+        user_preferences: get_json(row, "user_preferences")?,
+        tool_calls: get_json(row, "tool_calls")?,
+        agent: row.get("agent")?,
+        started_at: get_time(row, "started_us")?,
+        completed_at: get_optional_time(row, "completed_us")?,
+    })
+}
+
+fn skill_from_row(row: &Row<'_>) -> rusqlite::Result<Skill> {
+    let complexity: f64 = row.get("complexity_score")?;
+    let embedding: Option<Vec<u8>> = row.get("embedding")?;
+    Ok(Skill {
+        id: get_uuid(row, "id")?,
+        use_when: row.get("use_when")?,
+        agent: get_parsed(row, "agent")?,
+        preferences: get_json(row, "preferences")?,
+        tool_sops: get_json(row, "tool_sops")?,
+        #[allow(clippy::cast_possible_truncation)] // Stored from an f32
+        complexity_score: complexity as f32,
+        success_count: row.get("success_count")?,
+        failure_count: row.get("failure_count")?,
+        version: row.get("version")?,
+        last_outcome_at: get_optional_time(row, "last_outcome_us")?,
+        merged_into: get_optional_uuid(row, "merged_into")?,
+        space_id: get_uuid(row, "space_id")?,
+        embedding: embedding.as_deref().map(embedding_from_blob),
+        created_at: get_time(row, "created_us")?,
+        updated_at: get_time(row, "updated_us")?,
+    })
+}
+
+fn version_from_row(row: &Row<'_>) -> rusqlite::Result<SkillVersion> {
+    Ok(SkillVersion {
+        skill_id: get_uuid(row, "skill_id")?,
+        version: row.get("version")?,
+        use_when: row.get("use_when")?,
+        preferences: get_json(row, "preferences")?,
+        tool_sops: get_json(row, "tool_sops")?,
+        created_at: get_time(row, "created_us")?,
+    })
+}
+
+fn outcome_from_row(row: &Row<'_>) -> rusqlite::Result<SkillOutcome> {
+    Ok(SkillOutcome {
+        id: get_uuid(row, "id")?,
+        skill_id: get_uuid(row, "skill_id")?,
+        skill_version: row.get("skill_version")?,
+        session_id: get_uuid(row, "session_id")?,
+        success: row.get("success")?,
+        notes: row.get("notes")?,
+        recorded_at: get_time(row, "recorded_us")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AgentType, MessageRecord, SessionStatus, TaskStatus, ToolStep};
+
+    async fn store_with_space() -> (SqliteStore, Space) {
+        let store = SqliteStore::in_memory().unwrap();
+        let space = store
+            .create_space(&Space::for_project("cto", "cto"))
+            .await
+            .unwrap();
+        (store, space)
+    }
+
+    fn skill(space_id: Uuid, embedding: Vec<f32>) -> Skill {
+        Skill::new(
+            "fix clippy warnings",
+            AgentType::Rex,
+            vec![ToolStep::new(1, "shell", "cargo clippy")],
+            space_id,
+        )
+        .with_embedding(embedding)
+    }
+
+    #[tokio::test]
+    async fn test_session_round_trip() {
+        let (store, space) = store_with_space().await;
+        let mut session = SessionRecord::new("play-1", space.id);
+        session.add_message(MessageRecord::user("go").with_tokens(1));
+        session.add_task(TaskRecord::new("1", "Add endpoint").with_agent("rex"));
+        store.create_session(&session).await.unwrap();
+
+        let mut fetched = store
+            .get_session_by_play_id("play-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.tasks.len(), 1);
+        assert_eq!(fetched.messages[0].content, "go");
+        assert_eq!(
+            fetched.started_at.timestamp_micros(),
+            session.started_at.timestamp_micros()
+        );
+
+        fetched.tasks[0].complete(true);
+        store.update_task(&fetched.tasks[0]).await.unwrap();
+        fetched.complete(true);
+        store.update_session(&fetched).await.unwrap();
+
+        let reloaded = store.get_session(session.id).await.unwrap().unwrap();
+        assert_eq!(reloaded.status, SessionStatus::Completed);
+        assert_eq!(reloaded.tasks[0].status, TaskStatus::Success);
+        assert!(store.get_space_by_project("cto").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_search_orders_by_similarity_and_skips_other_dimensions() {
+        let (store, space) = store_with_space().await;
+        let near = skill(space.id, vec![1.0, 0.0]);
+        let far = skill(space.id, vec![0.0, 1.0]);
+        let other_dims = skill(space.id, vec![1.0, 0.0, 0.0]);
+        for s in [&far, &near, &other_dims] {
+            store.create_skill(s).await.unwrap();
+        }
+
+        let results = store
+            .search_skills_by_embedding(&[0.9, 0.1], space.id, 5)
+            .await
+            .unwrap();
+        let ids: Vec<Uuid> = results.iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![near.id, far.id]);
+        assert_eq!(results[0].embedding.as_deref(), Some(&[1.0, 0.0][..]));
+    }
+
+    #[tokio::test]
+    async fn test_update_skill_and_missing_rows() {
+        let (store, space) = store_with_space().await;
+        let mut s = skill(space.id, vec![1.0]);
+        store.create_skill(&s).await.unwrap();
+
+        s.increment_success();
+        store.update_skill(&s).await.unwrap();
+        assert_eq!(
+            store.get_skill(s.id).await.unwrap().unwrap().success_count,
+            2
+        );
+
+        let missing = skill(space.id, vec![1.0]);
+        assert!(store.update_skill(&missing).await.is_err());
+        let missing = SessionRecord::new("missing", space.id);
+        assert!(store.update_session(&missing).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reopen_file_keeps_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("experience.db");
+        let skill_id = {
+            let store = SqliteStore::open(&path).unwrap();
+            let space = store.create_space(&Space::new("local")).await.unwrap();
+            store
+                .create_skill(&skill(space.id, vec![0.5]))
+                .await
+                .unwrap()
+                .id
+        };
+
+        let store = SqliteStore::open(&path).unwrap();
+        assert!(store.get_skill(skill_id).await.unwrap().is_some());
+    }
+}
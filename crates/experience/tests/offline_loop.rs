@@ -0,0 +1,84 @@
+//! End-to-end learn → search loop on the embedded store.
+//!
+//! Runs entirely offline: skills are stored in `SQLite` and embedded with the
+//! local hashed embedder.
+
+#![cfg(feature = "sqlite")]
+
+use std::sync::Arc;
+
+use experience::storage::{ExperienceStore, SqliteStore};
+use experience::{
+    ComplexityFilter, EmbeddingSearcher, HashedEmbedder, SearchMode, SkillLearner, SkillSearcher,
+    Space, TaskRecord, ToolCallRecord,
+};
+
+fn completed_task(id: &str, description: &str, tools: &[&str]) -> TaskRecord {
+    let mut task = TaskRecord::new(id, description).with_agent("rex");
+    task.start();
+    for (i, tool) in tools.iter().enumerate() {
+        task.add_tool_call(ToolCallRecord::new(i.to_string(), *tool, "{}"));
+    }
+    task.complete(true);
+    task
+}
+
+#[tokio::test]
+async fn test_learned_skills_are_found_by_related_queries() {
+    let store = Arc::new(SqliteStore::in_memory().unwrap());
+    let space = store
+        .create_space(&Space::for_project("cto", "cto"))
+        .await
+        .unwrap();
+
+    let embedder = Arc::new(HashedEmbedder::default());
+    let learner = SkillLearner::new(ComplexityFilter {
+        min_duration_secs: 0,
+        complexity_threshold: 0.0,
+        ..Default::default()
+    })
+    .with_embedder(embedder.clone());
+
+    let tasks = [
+        completed_task(
+            "1",
+            "Implement HTTP handler with token authentication",
+            &["read_file", "search_files", "write_file", "git_diff"],
+        ),
+        completed_task(
+            "2",
+            "Deploy Helm chart to the staging cluster",
+            &["list_directory", "read_file", "write_file", "git_status"],
+        ),
+    ];
+    let mut learned = Vec::new();
+    for task in &tasks {
+        let skill = learner
+            .extract_skill(task, space.id, None)
+            .await
+            .unwrap()
+            .expect("task should be learnable");
+        assert!(skill.embedding.is_some());
+        learned.push(store.create_skill(&skill).await.unwrap());
+    }
+
+    let searcher = EmbeddingSearcher::new(store.clone(), embedder);
+    let results = searcher
+        .search(
+            "add authentication to an HTTP handler",
+            space.id,
+            SearchMode::Fast,
+            1,
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, learned[0].id);
+
+    let results = searcher
+        .search("helm deploy to staging", space.id, SearchMode::Fast, 5)
+        .await
+        .unwrap();
+    assert_eq!(results[0].id, learned[1].id);
+    assert_eq!(results.len(), 2);
+}
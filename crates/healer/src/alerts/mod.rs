@@ -2,6 +2,8 @@
 //!
 //! This module implements reactive alerts that detect anomalies during workflow execution.
 //! Each alert handler evaluates the current state and optionally triggers Factory for analysis.
+//! Alerts that only look at a pod or `CodeRun` are declarative [`rules`] loaded
+//! from YAML; the rest need GitHub or workflow state and are Rust handlers.
 //!
 //! # Alert Types
 //! - A1: Agent comment order mismatch (GitHub vs K8s state)
@@ -9,62 +11,79 @@
 //! - A3: Stale progress (no commits for threshold duration)
 //! - A4: Repeated approval loop (same agent approving multiple times)
 //! - A5: Post-Tess CI/Merge failure (CI failing or merge conflict after Tess approval)
-//! - A7: Pod failure (any CTO pod in Failed/Error state)
+//! - A7: Pod failure (any CTO pod in Failed/Error state) - built-in rule
 //! - A8: Workflow step timeout (step running longer than threshold)
-//! - A9: Stuck `CodeRun` (`CodeRun` in non-terminal state beyond threshold)
+//! - A9: Stuck `CodeRun` (`CodeRun` in non-terminal state beyond threshold) - built-in rule
 
 pub mod a1_comment_order;
 pub mod a2_silent_failure;
 pub mod a3_stale_progress;
 pub mod a4_approval_loop;
 pub mod a5_post_tess_ci;
-pub mod a7_pod_failure;
 pub mod a8_step_timeout;
 pub mod a9_stuck_coderun;
+pub mod rules;
 pub mod silent_failure_poc;
 pub mod types;
 
 // Public API re-exports
 pub use a9_stuck_coderun::CodeRunTracker;
+pub use rules::RuleSet;
 #[allow(unused_imports)] // Re-exported for external use
 pub use types::{Alert, AlertConfig, AlertContext, AlertHandler, AlertId};
 
 use crate::github::GitHubState;
 use crate::k8s::K8sEvent;
 
-/// Registry of all alert handlers
+/// Registry of all alert rules and handlers
 pub struct AlertRegistry {
+    rules: RuleSet,
     handlers: Vec<Box<dyn AlertHandler>>,
 }
 
 impl AlertRegistry {
-    /// Create a new alert registry with all handlers enabled
+    /// Create a new alert registry with the built-in rules and all handlers enabled
     pub fn new() -> Self {
+        Self::with_rules(RuleSet::builtin())
+    }
+
+    /// Create a registry using `rules` in place of the built-in rules
+    pub fn with_rules(rules: RuleSet) -> Self {
         Self {
+            rules,
             handlers: vec![
                 Box::new(a1_comment_order::Handler::new()),
                 Box::new(a2_silent_failure::Handler::new()),
                 Box::new(a3_stale_progress::Handler::new()),
                 Box::new(a4_approval_loop::Handler::new()),
                 Box::new(a5_post_tess_ci::Handler::new()),
-                Box::new(a7_pod_failure::Handler::new()),
                 Box::new(a8_step_timeout::Handler::new()),
-                Box::new(a9_stuck_coderun::Handler::new()),
             ],
         }
     }
 
-    /// Evaluate all handlers against current state
+    /// Whether evaluating `event` needs the resource's logs (see [`RuleSet::needs_logs`])
+    pub fn needs_logs(&self, event: &K8sEvent) -> bool {
+        self.rules.needs_logs(event)
+    }
+
+    /// Evaluate all rules and handlers against current state
+    ///
+    /// Rules with log patterns only fire when `logs` is provided.
     pub fn evaluate(
         &self,
         event: &K8sEvent,
         github: &GitHubState,
         ctx: &AlertContext,
+        logs: Option<&str>,
     ) -> Vec<Alert> {
-        self.handlers
-            .iter()
-            .filter_map(|h| h.evaluate(event, github, ctx))
-            .collect()
+        let mut alerts = self.rules.evaluate(event, ctx, logs);
+        alerts.extend(
+            self.handlers
+                .iter()
+                .filter_map(|h| h.evaluate(event, github, ctx)),
+        );
+        alerts
     }
 }
 
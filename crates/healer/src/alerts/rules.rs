@@ -0,0 +1,732 @@
+//! Declarative alert rules loaded from YAML.
+//!
+//! A rule selects pods or `CodeRun`s, checks conditions on their phase,
+//! restart count, age and logs, and raises an alert with a severity and a
+//! remediation template. The built-in rules in `builtin_rules.yaml` ship with
+//! the binary; rule files passed with `--rules` are layered on top, and a
+//! rule reusing a built-in `id` replaces it, so detection can be tuned per
+//! cluster without a release.
+//!
+//! ```yaml
+//! rules:
+//!   - id: oom-killed-agent
+//!     selector:
+//!       kind: pod
+//!       labels: { app: agent }
+//!     conditions:
+//!       phases: [Failed]
+//!       log_patterns: ["(?i)out of memory"]
+//!     severity: critical
+//!     message: "Agent pod {{pod_name}} ran out of memory"
+//!     remediation:
+//!       template: oom-killed
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::types::{Alert, AlertContext, AlertId, Severity};
+use crate::k8s::{is_excluded_pod, CodeRun, K8sEvent, Pod, WatchEvent};
+
+const BUILTIN_RULES: &str = include_str!("builtin_rules.yaml");
+
+/// Kind of resource a rule applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceKind {
+    Pod,
+    #[serde(alias = "code_run")]
+    CodeRun,
+}
+
+/// Which resources a rule looks at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Selector {
+    pub kind: ResourceKind,
+    /// Only resources whose name starts with one of these (all if empty)
+    #[serde(default)]
+    pub name_prefixes: Vec<String>,
+    #[serde(default)]
+    pub exclude_name_prefixes: Vec<String>,
+    /// Labels that must all be present with these values
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// Resources with any of these label values are skipped
+    #[serde(default)]
+    pub exclude_labels: HashMap<String, String>,
+    /// Skip platform infrastructure pods (see `k8s::is_excluded_pod`)
+    #[serde(default = "default_true")]
+    pub exclude_infrastructure: bool,
+}
+
+/// Conditions that must all hold for a rule to fire.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Conditions {
+    /// Phase must be one of these (any if empty)
+    #[serde(default)]
+    pub phases: Vec<String>,
+    #[serde(default)]
+    pub exclude_phases: Vec<String>,
+    /// Bounds on the total container restart count (pods only)
+    pub min_restarts: Option<i32>,
+    pub max_restarts: Option<i32>,
+    /// Minimum minutes since the pod started or the `CodeRun` was created
+    pub min_age_mins: Option<i64>,
+    /// Regexes matched against the resource's logs; any match satisfies
+    #[serde(default)]
+    pub log_patterns: Vec<String>,
+}
+
+/// How a detected alert is remediated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Remediation {
+    /// Prompt template under `alerts/` (without extension). Defaults to the
+    /// template for the rule's alert type, or the rule ID for custom rules.
+    pub template: Option<String>,
+}
+
+/// A single alert rule as written in YAML.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRule {
+    pub id: String,
+    /// Built-in alert type raised by this rule (custom if omitted)
+    #[serde(default = "default_alert")]
+    pub alert: AlertId,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub selector: Selector,
+    #[serde(default)]
+    pub conditions: Conditions,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// Alert message; `{{key}}` placeholders are filled from the alert context
+    pub message: String,
+    #[serde(default)]
+    pub remediation: Remediation,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_alert() -> AlertId {
+    AlertId::Custom
+}
+
+fn default_severity() -> Severity {
+    Severity::Warning
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    #[serde(default)]
+    rules: Vec<AlertRule>,
+}
+
+/// A rule with its log patterns compiled.
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    rule: AlertRule,
+    log_patterns: Vec<Regex>,
+}
+
+impl CompiledRule {
+    fn compile(rule: AlertRule) -> Result<Self> {
+        let log_patterns = rule
+            .conditions
+            .log_patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .with_context(|| format!("rule '{}': invalid log pattern '{pattern}'", rule.id))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rule, log_patterns })
+    }
+
+    /// Selector and every condition except the log patterns.
+    fn matches_resource(&self, resource: &Resource, now: DateTime<Utc>) -> bool {
+        let selector = &self.rule.selector;
+        let conditions = &self.rule.conditions;
+        let name = resource.name();
+        let phase = resource.phase();
+
+        if !self.rule.enabled || resource.kind() != selector.kind {
+            return false;
+        }
+        if selector.exclude_infrastructure
+            && resource.kind() == ResourceKind::Pod
+            && is_excluded_pod(name)
+        {
+            return false;
+        }
+        if !selector.name_prefixes.is_empty()
+            && !selector.name_prefixes.iter().any(|p| name.starts_with(p))
+        {
+            return false;
+        }
+        if selector
+            .exclude_name_prefixes
+            .iter()
+            .any(|p| name.starts_with(p))
+        {
+            return false;
+        }
+        let labels = resource.labels();
+        if !selector
+            .labels
+            .iter()
+            .all(|(k, v)| labels.get(k) == Some(v))
+        {
+            return false;
+        }
+        if selector
+            .exclude_labels
+            .iter()
+            .any(|(k, v)| labels.get(k) == Some(v))
+        {
+            return false;
+        }
+
+        if !conditions.phases.is_empty() && !conditions.phases.iter().any(|p| p == phase) {
+            return false;
+        }
+        if conditions.exclude_phases.iter().any(|p| p == phase) {
+            return false;
+        }
+        let restarts = resource.restart_count();
+        if conditions.min_restarts.is_some_and(|min| restarts < min)
+            || conditions.max_restarts.is_some_and(|max| restarts > max)
+        {
+            return false;
+        }
+        if let Some(min_age) = conditions.min_age_mins {
+            match resource.age_mins(now) {
+                Some(age) if age >= min_age => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// First log line matching any pattern, `Some("")` if the rule has none.
+    fn match_logs<'a>(&self, logs: Option<&'a str>) -> Option<&'a str> {
+        if self.log_patterns.is_empty() {
+            return Some("");
+        }
+        logs?
+            .lines()
+            .find(|line| self.log_patterns.iter().any(|re| re.is_match(line)))
+    }
+
+    fn build_alert(&self, mut context: HashMap<String, String>, matched_log: &str) -> Alert {
+        let rule = &self.rule;
+        context.insert("rule_id".to_string(), rule.id.clone());
+        if !matched_log.is_empty() {
+            context.insert(
+                "matched_log_line".to_string(),
+                matched_log.trim().to_string(),
+            );
+        }
+        if let Some(template) = &rule.remediation.template {
+            context.insert("remediation_template".to_string(), template.clone());
+        }
+
+        let message = render_message(&rule.message, &context);
+        let mut alert = Alert::new(rule.alert, message)
+            .with_severity(rule.severity)
+            .with_rule(rule.id.clone());
+        alert.context = context;
+        alert
+    }
+}
+
+/// Fill `{{key}}` placeholders from `context`.
+fn render_message(template: &str, context: &HashMap<String, String>) -> String {
+    context
+        .iter()
+        .fold(template.to_string(), |message, (key, value)| {
+            message.replace(&format!("{{{{{key}}}}}"), value)
+        })
+}
+
+/// The resource an event is about.
+enum Resource<'a> {
+    Pod(&'a Pod),
+    CodeRun(&'a CodeRun),
+}
+
+impl<'a> Resource<'a> {
+    fn from_event(event: &'a K8sEvent) -> Option<Self> {
+        match event {
+            K8sEvent::PodRunning(pod)
+            | K8sEvent::PodModified(pod)
+            | K8sEvent::PodSucceeded(pod)
+            | K8sEvent::PodFailed(pod) => Some(Self::Pod(pod)),
+            K8sEvent::CodeRunChanged(coderun) => Some(Self::CodeRun(coderun)),
+            K8sEvent::WorkflowPhaseChanged(_) | K8sEvent::GitHubUpdate => None,
+        }
+    }
+
+    fn kind(&self) -> ResourceKind {
+        match self {
+            Self::Pod(_) => ResourceKind::Pod,
+            Self::CodeRun(_) => ResourceKind::CodeRun,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::Pod(pod) => &pod.name,
+            Self::CodeRun(coderun) => &coderun.name,
+        }
+    }
+
+    fn phase(&self) -> &str {
+        match self {
+            Self::Pod(pod) => &pod.phase,
+            Self::CodeRun(coderun) => &coderun.phase,
+        }
+    }
+
+    fn labels(&self) -> &HashMap<String, String> {
+        match self {
+            Self::Pod(pod) => &pod.labels,
+            Self::CodeRun(coderun) => &coderun.labels,
+        }
+    }
+
+    fn restart_count(&self) -> i32 {
+        match self {
+            Self::Pod(pod) => pod.container_statuses.iter().map(|c| c.restart_count).sum(),
+            Self::CodeRun(_) => 0,
+        }
+    }
+
+    fn age_mins(&self, now: DateTime<Utc>) -> Option<i64> {
+        let since = match self {
+            Self::Pod(pod) => pod.started_at,
+            Self::CodeRun(coderun) => coderun.created_at,
+        }?;
+        Some((now - since).num_minutes())
+    }
+
+    /// Context keys match those the original Rust handlers produced, so
+    /// existing prompt templates keep working.
+    fn context(&self, ctx: &AlertContext, now: DateTime<Utc>) -> HashMap<String, String> {
+        let mut context = HashMap::new();
+        match self {
+            Self::Pod(pod) => {
+                context.insert("pod_name".to_string(), pod.name.clone());
+                context.insert("phase".to_string(), pod.phase.clone());
+                context.insert(
+                    "restart_count".to_string(),
+                    self.restart_count().to_string(),
+                );
+                context.insert(
+                    "agent".to_string(),
+                    pod.labels.get("agent").cloned().unwrap_or_default(),
+                );
+                context.insert(
+                    "task_id".to_string(),
+                    pod.labels.get("task-id").cloned().unwrap_or_default(),
+                );
+            }
+            Self::CodeRun(coderun) => {
+                context.insert("coderun_name".to_string(), coderun.name.clone());
+                context.insert("coderun_phase".to_string(), coderun.phase.clone());
+                context.insert("agent".to_string(), coderun.agent.clone());
+                context.insert("task_id".to_string(), coderun.task_id.clone());
+                context.insert(
+                    "threshold_minutes".to_string(),
+                    ctx.config.stuck_coderun_threshold_mins.to_string(),
+                );
+            }
+        }
+        if let Some(age) = self.age_mins(now) {
+            context.insert("age_minutes".to_string(), age.to_string());
+        }
+        context
+    }
+}
+
+/// An ordered set of alert rules.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleSet {
+    /// Rules embedded in the binary.
+    pub fn builtin() -> Self {
+        Self::from_yaml(BUILTIN_RULES).expect("built-in alert rules are valid")
+    }
+
+    /// Parse rules from a YAML document with a top-level `rules:` list.
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let file: RuleFile = serde_yaml::from_str(yaml).context("invalid alert rules YAML")?;
+        let mut set = Self::default();
+        for rule in file.rules {
+            if set.rules.iter().any(|r| r.rule.id == rule.id) {
+                bail!("duplicate alert rule id '{}'", rule.id);
+            }
+            set.rules.push(CompiledRule::compile(rule)?);
+        }
+        Ok(set)
+    }
+
+    /// Load rules from a YAML file, or from every `.yaml`/`.yml` file in a
+    /// directory in name order.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_dir() {
+            let yaml = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read rules file {}", path.display()))?;
+            return Self::from_yaml(&yaml).with_context(|| format!("in {}", path.display()));
+        }
+
+        let mut files: Vec<_> = std::fs::read_dir(path)
+            .with_context(|| format!("failed to read rules directory {}", path.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.extension()
+                    .is_some_and(|ext| ext == "yaml" || ext == "yml")
+            })
+            .collect();
+        files.sort();
+
+        let mut set = Self::default();
+        for file in files {
+            set = set.with_overrides(Self::load(&file)?);
+        }
+        Ok(set)
+    }
+
+    /// Layer `overrides` on top: rules with a matching ID are replaced in
+    /// place, new rules are appended.
+    #[must_use]
+    pub fn with_overrides(mut self, overrides: Self) -> Self {
+        for rule in overrides.rules {
+            match self.rules.iter_mut().find(|r| r.rule.id == rule.rule.id) {
+                Some(existing) => *existing = rule,
+                None => self.rules.push(rule),
+            }
+        }
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether any rule would need the resource's logs to decide on `event`.
+    ///
+    /// Lets callers skip fetching logs for the common case.
+    pub fn needs_logs(&self, event: &K8sEvent) -> bool {
+        let Some(resource) = Resource::from_event(event) else {
+            return false;
+        };
+        let now = Utc::now();
+        self.rules
+            .iter()
+            .any(|r| !r.log_patterns.is_empty() && r.matches_resource(&resource, now))
+    }
+
+    /// Evaluate every rule against `event`.
+    ///
+    /// Rules with log patterns only fire when `logs` is provided.
+    pub fn evaluate(&self, event: &K8sEvent, ctx: &AlertContext, logs: Option<&str>) -> Vec<Alert> {
+        self.evaluate_at(event, ctx, logs, Utc::now())
+    }
+
+    fn evaluate_at(
+        &self,
+        event: &K8sEvent,
+        ctx: &AlertContext,
+        logs: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Vec<Alert> {
+        let Some(resource) = Resource::from_event(event) else {
+            return Vec::new();
+        };
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches_resource(&resource, now))
+            .filter_map(|rule| {
+                let matched = rule.match_logs(logs)?;
+                Some(rule.build_alert(resource.context(ctx, now), matched))
+            })
+            .collect()
+    }
+}
+
+/// A recorded watch event with optional logs and expected rule IDs.
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    #[serde(flatten)]
+    event: WatchEvent,
+    #[serde(default)]
+    logs: Option<String>,
+    #[serde(default)]
+    expect: Option<Vec<String>>,
+}
+
+/// Outcome of evaluating one fixture.
+#[derive(Debug, Clone, Serialize)]
+pub struct FixtureResult {
+    /// 1-based line in the fixtures file
+    pub line: usize,
+    pub resource: String,
+    /// IDs of the rules that fired, in rule order
+    pub fired: Vec<String>,
+    pub expected: Option<Vec<String>>,
+}
+
+impl FixtureResult {
+    /// Whether the fired rules match expectations (vacuously true without any).
+    pub fn passed(&self) -> bool {
+        self.expected.as_ref().is_none_or(|expected| {
+            let mut expected = expected.clone();
+            let mut fired = self.fired.clone();
+            expected.sort();
+            fired.sort();
+            expected == fired
+        })
+    }
+}
+
+/// Evaluate `rules` against recorded watch events.
+///
+/// `fixtures` holds one `kubectl get -w -o json --output-watch-events` line
+/// per event (blank lines and `#` comments are skipped). Each line may add
+/// `logs` to feed log patterns and `expect` with the rule IDs that should
+/// fire.
+pub fn test_fixtures(
+    rules: &RuleSet,
+    fixtures: &str,
+    ctx: &AlertContext,
+) -> Result<Vec<FixtureResult>> {
+    let mut results = Vec::new();
+    for (index, line) in fixtures.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fixture: Fixture = serde_json::from_str(line)
+            .with_context(|| format!("line {line_no}: invalid watch event"))?;
+        let resource = format!(
+            "{}/{}",
+            fixture.event.object["kind"].as_str().unwrap_or("Unknown"),
+            fixture.event.object["metadata"]["name"]
+                .as_str()
+                .unwrap_or("unknown")
+        );
+        let fired = fixture
+            .event
+            .to_k8s_event(&ctx.namespace)
+            .map(|event| rules.evaluate(&event, ctx, fixture.logs.as_deref()))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|alert| alert.rule_id)
+            .collect();
+        results.push(FixtureResult {
+            line: line_no,
+            resource,
+            fired,
+            expected: fixture.expect,
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::types::AlertConfig;
+    use crate::k8s::ContainerStatus;
+
+    fn ctx() -> AlertContext {
+        AlertContext {
+            task_id: String::new(),
+            repository: String::new(),
+            namespace: "cto".to_string(),
+            pr_number: None,
+            workflow_name: None,
+            config: AlertConfig::default(),
+        }
+    }
+
+    fn failed_pod(name: &str, restarts: i32) -> Pod {
+        Pod {
+            name: name.to_string(),
+            namespace: "cto".to_string(),
+            phase: "Failed".to_string(),
+            labels: HashMap::from([("agent".to_string(), "rex".to_string())]),
+            container_statuses: vec![ContainerStatus {
+                name: "main".to_string(),
+                restart_count: restarts,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_builtin_pod_failure_rules() {
+        let rules = RuleSet::builtin();
+
+        let alerts = rules.evaluate(&K8sEvent::PodFailed(failed_pod("rex-1", 1)), &ctx(), None);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].id, AlertId::A7);
+        assert_eq!(alerts[0].key(), "A7");
+        assert_eq!(alerts[0].severity, Severity::Warning);
+        assert_eq!(alerts[0].message, "Pod rex-1 failed with phase: Failed");
+        assert_eq!(alerts[0].context["agent"], "rex");
+        assert_eq!(alerts[0].context["remediation_template"], "a7-pod-failure");
+
+        let alerts = rules.evaluate(&K8sEvent::PodFailed(failed_pod("rex-2", 5)), &ctx(), None);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].severity, Severity::Critical);
+        assert_eq!(
+            alerts[0].message,
+            "Pod rex-2 in CrashLoopBackOff (5 restarts)"
+        );
+
+        let infra = K8sEvent::PodFailed(failed_pod("cto-controller-abc", 5));
+        assert!(rules.evaluate(&infra, &ctx(), None).is_empty());
+    }
+
+    #[test]
+    fn test_builtin_stuck_coderun_rule() {
+        let rules = RuleSet::builtin();
+        let mut coderun = CodeRun {
+            name: "coderun-42".to_string(),
+            phase: "Running".to_string(),
+            agent: "rex".to_string(),
+            task_id: "42".to_string(),
+            ..Default::default()
+        };
+
+        let alerts = rules.evaluate(&K8sEvent::CodeRunChanged(coderun.clone()), &ctx(), None);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].id, AlertId::A9);
+        assert_eq!(
+            alerts[0].message,
+            "CodeRun coderun-42 has been in 'Running' state for over 10 minutes without completing"
+        );
+
+        coderun
+            .labels
+            .insert("remediation".to_string(), "true".to_string());
+        assert!(rules
+            .evaluate(&K8sEvent::CodeRunChanged(coderun), &ctx(), None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_overrides_replace_and_disable_rules() {
+        let overrides = RuleSet::from_yaml(
+            r"
+rules:
+  - id: a7-pod-crashloop
+    alert: A7
+    selector: { kind: pod }
+    conditions: { phases: [Failed], min_restarts: 10 }
+    severity: critical
+    message: tuned
+  - id: a7-pod-failure
+    enabled: false
+    selector: { kind: pod }
+    message: disabled
+",
+        )
+        .unwrap();
+        let rules = RuleSet::builtin().with_overrides(overrides);
+        assert_eq!(rules.len(), RuleSet::builtin().len());
+
+        let event = K8sEvent::PodFailed(failed_pod("rex-1", 5));
+        assert!(rules.evaluate(&event, &ctx(), None).is_empty());
+        let event = K8sEvent::PodFailed(failed_pod("rex-1", 12));
+        assert_eq!(rules.evaluate(&event, &ctx(), None)[0].message, "tuned");
+    }
+
+    #[test]
+    fn test_custom_rule_with_logs_and_age() {
+        let rules = RuleSet::from_yaml(
+            r#"
+rules:
+  - id: oom-killed
+    selector:
+      kind: pod
+      labels: { agent: rex }
+    conditions:
+      phases: [Failed]
+      min_age_mins: 5
+      log_patterns: ["(?i)out of memory"]
+    severity: critical
+    message: "{{pod_name}} OOM: {{matched_log_line}}"
+"#,
+        )
+        .unwrap();
+        let now = Utc::now();
+        let mut pod = failed_pod("rex-1", 0);
+        pod.started_at = Some(now - chrono::Duration::minutes(10));
+        let event = K8sEvent::PodFailed(pod.clone());
+        let logs = "starting\nfatal: Out of memory allocating 4GB\n";
+
+        assert!(rules.needs_logs(&event));
+        assert!(rules.evaluate_at(&event, &ctx(), None, now).is_empty());
+        let alerts = rules.evaluate_at(&event, &ctx(), Some(logs), now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].key(), "oom-killed");
+        assert_eq!(
+            alerts[0].message,
+            "rex-1 OOM: fatal: Out of memory allocating 4GB"
+        );
+
+        pod.started_at = Some(now - chrono::Duration::minutes(1));
+        let young = K8sEvent::PodFailed(pod);
+        assert!(!rules.needs_logs(&young));
+        assert!(rules
+            .evaluate_at(&young, &ctx(), Some(logs), now)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        let bad_regex = "rules:\n  - id: x\n    selector: { kind: pod }\n    conditions: { log_patterns: ['('] }\n    message: x\n";
+        assert!(RuleSet::from_yaml(bad_regex).is_err());
+
+        let duplicate = "rules:\n  - id: x\n    selector: { kind: pod }\n    message: x\n  - id: x\n    selector: { kind: pod }\n    message: y\n";
+        assert!(RuleSet::from_yaml(duplicate).is_err());
+
+        let unknown_field =
+            "rules:\n  - id: x\n    selector: { kind: pod }\n    conditons: {}\n    message: x\n";
+        assert!(RuleSet::from_yaml(unknown_field).is_err());
+    }
+
+    #[test]
+    fn test_recorded_watch_events() {
+        let fixtures = include_str!("../../tests/fixtures/watch-events.jsonl");
+        let results = test_fixtures(&RuleSet::builtin(), fixtures, &ctx()).unwrap();
+
+        assert_eq!(results.len(), 5);
+        for result in &results {
+            assert!(result.passed(), "fixture failed: {result:?}");
+        }
+    }
+}
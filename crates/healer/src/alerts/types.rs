@@ -18,6 +18,7 @@ pub enum AlertId {
     A8,         // Step timeout
     A9,         // Stuck CodeRun (no phase transition)
     Completion, // Success completion check
+    Custom,     // Raised by a user-defined rule
 }
 
 impl AlertId {
@@ -32,6 +33,7 @@ impl AlertId {
             Self::A8 => "A8",
             Self::A9 => "A9",
             Self::Completion => "completion",
+            Self::Custom => "custom",
         }
     }
 
@@ -46,6 +48,7 @@ impl AlertId {
             Self::A8 => "Workflow Step Timeout",
             Self::A9 => "Stuck CodeRun",
             Self::Completion => "Success Completion Check",
+            Self::Custom => "Custom Rule",
         }
     }
 }
@@ -58,6 +61,9 @@ pub struct Alert {
     pub severity: Severity,
     pub context: HashMap<String, String>,
     pub detected_at: DateTime<Utc>,
+    /// Rule that raised this alert, if it came from a declarative rule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
 }
 
 impl Alert {
@@ -68,6 +74,23 @@ impl Alert {
             severity: Severity::Warning,
             context: HashMap::new(),
             detected_at: Utc::now(),
+            rule_id: None,
+        }
+    }
+
+    pub fn with_rule(mut self, rule_id: impl Into<String>) -> Self {
+        self.rule_id = Some(rule_id.into());
+        self
+    }
+
+    /// Key used for deduplication and prompt selection.
+    ///
+    /// Alerts of a built-in type share their alert ID (e.g. `A7`) whichever
+    /// rule raised them; custom rules are keyed by rule ID.
+    pub fn key(&self) -> &str {
+        match (&self.id, &self.rule_id) {
+            (AlertId::Custom, Some(rule_id)) => rule_id,
+            _ => self.id.as_str(),
         }
     }
 
@@ -85,8 +108,11 @@ impl Alert {
 /// Alert severity levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Severity {
+    #[serde(alias = "info")]
     Info,
+    #[serde(alias = "warning")]
     Warning,
+    #[serde(alias = "critical")]
     Critical,
 }
 
@@ -0,0 +1,331 @@
+//! Adaptive agent routing.
+//!
+//! Picks an agent per failure type by Thompson sampling over the outcomes
+//! in [`OutcomeStore`]. Each candidate agent's fix rate is modelled as a
+//! Beta distribution; the static [`CiRouter`](super::router::CiRouter)
+//! choice starts with `prior_strength` pseudo-successes and every other
+//! candidate with as many pseudo-failures, so routing matches the static
+//! mapping until real outcomes say otherwise. Sampling (rather than always
+//! taking the best mean) keeps a small amount of exploration going.
+
+use anyhow::Result;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::outcomes::{AgentStats, OutcomeStore};
+use super::types::{Agent, CiFailureType};
+
+/// Agents that can take any non-security failure.
+const CANDIDATES: &[Agent] = &[Agent::Rex, Agent::Blaze, Agent::Bolt, Agent::Atlas];
+
+/// Adaptive routing configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptiveRoutingConfig {
+    /// `SQLite` file for remediation outcomes (adaptive routing is off
+    /// without one)
+    #[serde(default)]
+    pub store_path: Option<String>,
+    /// Pseudo-observations backing the static routing choice
+    #[serde(default = "default_prior_strength")]
+    pub prior_strength: f64,
+}
+
+fn default_prior_strength() -> f64 {
+    4.0
+}
+
+impl Default for AdaptiveRoutingConfig {
+    fn default() -> Self {
+        Self {
+            store_path: None,
+            prior_strength: default_prior_strength(),
+        }
+    }
+}
+
+/// Beta posterior for one candidate agent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AgentPosterior {
+    /// Candidate agent
+    pub agent: Agent,
+    /// Successes plus prior
+    pub alpha: f64,
+    /// Failures plus prior
+    pub beta: f64,
+}
+
+impl AgentPosterior {
+    /// Expected fix rate.
+    #[must_use]
+    pub fn mean(&self) -> f64 {
+        self.alpha / (self.alpha + self.beta)
+    }
+}
+
+/// Thompson-sampling router over recorded outcomes.
+pub struct AdaptiveRouter {
+    store: Arc<OutcomeStore>,
+    prior_strength: f64,
+}
+
+impl AdaptiveRouter {
+    /// Create a router learning from `store`.
+    #[must_use]
+    pub fn new(store: Arc<OutcomeStore>, config: &AdaptiveRoutingConfig) -> Self {
+        Self {
+            store,
+            prior_strength: config.prior_strength.max(0.0),
+        }
+    }
+
+    /// Open the configured store, or `None` if adaptive routing is off or
+    /// the store cannot be opened.
+    #[must_use]
+    pub fn from_config(config: &AdaptiveRoutingConfig) -> Option<Self> {
+        let path = config.store_path.as_deref()?;
+        match OutcomeStore::open(path) {
+            Ok(store) => Some(Self::new(Arc::new(store), config)),
+            Err(e) => {
+                warn!("Adaptive routing disabled, cannot open outcome store: {e:#}");
+                None
+            }
+        }
+    }
+
+    /// The outcome store.
+    #[must_use]
+    pub fn store(&self) -> &Arc<OutcomeStore> {
+        &self.store
+    }
+
+    /// Posteriors for every candidate not in `exclude`.
+    pub fn posteriors(
+        &self,
+        failure_type: &CiFailureType,
+        prior: Agent,
+        exclude: &[Agent],
+    ) -> Result<Vec<AgentPosterior>> {
+        let stats = self.store.agent_stats(failure_type)?;
+        let candidates: &[Agent] = if failure_type.is_security() {
+            &[Agent::Cipher]
+        } else {
+            CANDIDATES
+        };
+
+        Ok(candidates
+            .iter()
+            .copied()
+            .filter(|agent| !exclude.contains(agent))
+            .map(|agent| {
+                let observed = stats.get(&agent).copied().unwrap_or_default();
+                self.posterior(agent, observed, agent == prior)
+            })
+            .collect())
+    }
+
+    fn posterior(&self, agent: Agent, stats: AgentStats, is_prior: bool) -> AgentPosterior {
+        let (prior_alpha, prior_beta) = if is_prior {
+            (self.prior_strength, 0.0)
+        } else {
+            (0.0, self.prior_strength)
+        };
+        AgentPosterior {
+            agent,
+            alpha: 1.0 + prior_alpha + f64::from(stats.successes),
+            beta: 1.0 + prior_beta + f64::from(stats.failures),
+        }
+    }
+
+    /// Choose an agent for a failure type. `prior` is the static routing
+    /// choice; agents in `exclude` (e.g. ones that already failed this run)
+    /// are never picked. Falls back to `prior` if the store fails or every
+    /// candidate is excluded.
+    #[must_use]
+    pub fn select(&self, failure_type: &CiFailureType, prior: Agent, exclude: &[Agent]) -> Agent {
+        self.select_with_rng(failure_type, prior, exclude, &mut rand::rng())
+    }
+
+    /// [`select`](Self::select) with a caller-provided RNG.
+    pub fn select_with_rng(
+        &self,
+        failure_type: &CiFailureType,
+        prior: Agent,
+        exclude: &[Agent],
+        rng: &mut impl Rng,
+    ) -> Agent {
+        let posteriors = match self.posteriors(failure_type, prior, exclude) {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("Adaptive routing failed, using static route: {e:#}");
+                return prior;
+            }
+        };
+
+        posteriors
+            .iter()
+            .map(|p| (p.agent, sample_beta(rng, p.alpha, p.beta)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(prior, |(agent, sample)| {
+                debug!(
+                    "Adaptive routing {} -> {} (sample {sample:.3}, static {})",
+                    failure_type.short_name(),
+                    agent.name(),
+                    prior.name()
+                );
+                agent
+            })
+    }
+}
+
+/// Draw from Beta(alpha, beta) as a ratio of Gamma draws.
+fn sample_beta(rng: &mut impl Rng, alpha: f64, beta: f64) -> f64 {
+    let x = sample_gamma(rng, alpha);
+    let y = sample_gamma(rng, beta);
+    x / (x + y)
+}
+
+/// Marsaglia-Tsang Gamma(shape, 1) sampler. Shapes here are always >= 1.
+fn sample_gamma(rng: &mut impl Rng, shape: f64) -> f64 {
+    let offset = shape - 1.0 / 3.0;
+    let scale = 1.0 / (9.0 * offset).sqrt();
+    loop {
+        let normal = sample_normal(rng);
+        let cube = (1.0 + scale * normal).powi(3);
+        if cube <= 0.0 {
+            continue;
+        }
+        let uniform: f64 = rng.random();
+        if uniform.ln() < 0.5 * normal * normal + offset - offset * cube + offset * cube.ln() {
+            return offset * cube;
+        }
+    }
+}
+
+/// Standard normal via Box-Muller.
+fn sample_normal(rng: &mut impl Rng) -> f64 {
+    let u1: f64 = 1.0 - rng.random::<f64>(); // (0, 1], keeps ln finite
+    let u2: f64 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ci::outcomes::OutcomeRecord;
+    use crate::ci::types::AttemptOutcome;
+    use chrono::Utc;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashMap;
+
+    fn router() -> AdaptiveRouter {
+        AdaptiveRouter::new(
+            Arc::new(OutcomeStore::in_memory().unwrap()),
+            &AdaptiveRoutingConfig::default(),
+        )
+    }
+
+    fn record(router: &AdaptiveRouter, run: u64, agent: Agent, outcome: AttemptOutcome) {
+        router
+            .store()
+            .record(&OutcomeRecord {
+                recorded_at: Utc::now(),
+                repository: "5dlabs/cto".to_string(),
+                workflow_run_id: run,
+                signature: "CI/build".to_string(),
+                failure_type: CiFailureType::RustBuild,
+                agent,
+                attempt: 1,
+                outcome,
+            })
+            .unwrap();
+    }
+
+    /// How often each agent is picked over `n` draws.
+    fn picks(router: &AdaptiveRouter, prior: Agent, n: usize) -> HashMap<Agent, usize> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut counts = HashMap::new();
+        for _ in 0..n {
+            let agent = router.select_with_rng(&CiFailureType::RustBuild, prior, &[], &mut rng);
+            *counts.entry(agent).or_default() += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_static_route_wins_without_history() {
+        let router = router();
+        let counts = picks(&router, Agent::Rex, 500);
+        assert!(counts[&Agent::Rex] > 400, "{counts:?}");
+    }
+
+    #[test]
+    fn test_learns_to_prefer_the_agent_that_fixes() {
+        let router = router();
+        for run in 0..20 {
+            record(&router, run, Agent::Rex, AttemptOutcome::CiStillFailing);
+            record(&router, 100 + run, Agent::Atlas, AttemptOutcome::Success);
+        }
+        let counts = picks(&router, Agent::Rex, 500);
+        assert!(counts[&Agent::Atlas] > 450, "{counts:?}");
+        assert!(counts.get(&Agent::Rex).copied().unwrap_or_default() < 25);
+    }
+
+    #[test]
+    fn test_excluded_and_security_candidates() {
+        let router = router();
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..50 {
+            let agent = router.select_with_rng(
+                &CiFailureType::RustBuild,
+                Agent::Rex,
+                &[Agent::Rex],
+                &mut rng,
+            );
+            assert_ne!(agent, Agent::Rex);
+        }
+        assert_eq!(
+            router.select_with_rng(
+                &CiFailureType::SecurityDependabot,
+                Agent::Cipher,
+                &[],
+                &mut rng
+            ),
+            Agent::Cipher
+        );
+        // Nothing left to choose from
+        assert_eq!(
+            router.select_with_rng(
+                &CiFailureType::SecuritySecret,
+                Agent::Cipher,
+                &[Agent::Cipher],
+                &mut rng
+            ),
+            Agent::Cipher
+        );
+    }
+
+    #[test]
+    fn test_posterior_means() {
+        let router = router();
+        record(&router, 1, Agent::Rex, AttemptOutcome::Success);
+        let posteriors = router
+            .posteriors(&CiFailureType::RustBuild, Agent::Rex, &[])
+            .unwrap();
+        let rex = posteriors.iter().find(|p| p.agent == Agent::Rex).unwrap();
+        assert!((rex.alpha - 6.0).abs() < f64::EPSILON);
+        assert!((rex.beta - 1.0).abs() < f64::EPSILON);
+        let bolt = posteriors.iter().find(|p| p.agent == Agent::Bolt).unwrap();
+        assert!((bolt.mean() - 1.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_beta_sampler_mean() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let n = 5000;
+        let mean = (0..n).map(|_| sample_beta(&mut rng, 2.0, 6.0)).sum::<f64>() / f64::from(n);
+        assert!((mean - 0.25).abs() < 0.02, "{mean}");
+    }
+}
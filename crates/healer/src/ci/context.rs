@@ -7,12 +7,17 @@
 //! - Kubernetes: pod state, events
 
 use anyhow::{Context as _, Result};
-use std::process::Command;
+use std::sync::Arc;
 use tracing::{debug, warn};
 
+use super::parsers;
 use super::types::{
-    ArgoCdStatus, ChangedFile, CiFailure, PodState, PullRequest, RemediationContext,
+    ArgoCdStatus, ChangedFile, CiFailure, CommitInfo, PodState, PullRequest, RemediationContext,
 };
+use crate::clients::{ClusterClient, GitHubClient};
+
+/// Namespace Argo CD Applications live in.
+const ARGOCD_NAMESPACE: &str = "argocd";
 
 /// Context gatherer for CI remediation.
 pub struct ContextGatherer {
@@ -20,6 +25,10 @@ pub struct ContextGatherer {
     repository: String,
     /// Namespace for Kubernetes operations
     namespace: String,
+    /// GitHub API client (logs, PRs and commits are skipped without one)
+    github: Option<Arc<dyn GitHubClient>>,
+    /// Kubernetes API client (cluster context is skipped without one)
+    cluster: Option<Arc<dyn ClusterClient>>,
     /// `ArgoCD` server URL (optional)
     argocd_url: Option<String>,
     /// Loki URL (optional)
@@ -33,11 +42,27 @@ impl ContextGatherer {
         Self {
             repository: repository.to_string(),
             namespace: namespace.to_string(),
+            github: None,
+            cluster: None,
             argocd_url: None,
             loki_url: None,
         }
     }
 
+    /// Set the GitHub client.
+    #[must_use]
+    pub fn with_github(mut self, github: Arc<dyn GitHubClient>) -> Self {
+        self.github = Some(github);
+        self
+    }
+
+    /// Set the Kubernetes client.
+    #[must_use]
+    pub fn with_cluster(mut self, cluster: Arc<dyn ClusterClient>) -> Self {
+        self.cluster = Some(cluster);
+        self
+    }
+
     /// Set `ArgoCD` URL.
     #[must_use]
     pub fn with_argocd(mut self, url: &str) -> Self {
@@ -52,38 +77,55 @@ impl ContextGatherer {
         self
     }
 
+    fn github(&self) -> Result<&dyn GitHubClient> {
+        self.github
+            .as_deref()
+            .context("GitHub client not configured")
+    }
+
+    fn cluster(&self) -> Result<&dyn ClusterClient> {
+        self.cluster
+            .as_deref()
+            .context("Kubernetes client not configured")
+    }
+
     /// Gather full context for a CI failure.
     ///
     /// # Errors
     ///
     /// Returns an error if critical context gathering fails. Most individual
     /// context sources are fault-tolerant and will log warnings on failure.
-    pub fn gather(&self, failure: &CiFailure) -> Result<RemediationContext> {
+    pub async fn gather(&self, failure: &CiFailure) -> Result<RemediationContext> {
         let mut ctx = RemediationContext {
             failure: Some(failure.clone()),
             ..Default::default()
         };
 
         // Gather workflow logs (most important for diagnosis)
-        match self.fetch_workflow_logs(failure.workflow_run_id) {
-            Ok(logs) => ctx.workflow_logs = logs,
+        match self.fetch_workflow_logs(failure.workflow_run_id).await {
+            Ok(logs) => {
+                ctx.failures = parsers::parse_ci_output(&logs);
+                debug!("Parsed {} structured failures", ctx.failures.len());
+                ctx.workflow_logs = logs;
+            }
             Err(e) => warn!("Failed to fetch workflow logs: {e}"),
         }
 
         // Gather PR information if this is a PR-triggered build
-        if let Ok(Some(pr)) = self.fetch_pr_for_branch(&failure.branch) {
-            ctx.pr = Some(pr);
+        match self.fetch_pr_for_branch(&failure.branch).await {
+            Ok(pr) => ctx.pr = pr,
+            Err(e) => debug!("Failed to fetch PR for {}: {e}", failure.branch),
         }
 
         // Gather changed files
-        match self.fetch_changed_files(&failure.head_sha) {
+        match self.fetch_changed_files(&failure.head_sha).await {
             Ok(files) => ctx.changed_files = files,
             Err(e) => warn!("Failed to fetch changed files: {e}"),
         }
 
         // Gather ArgoCD status if URL is configured
         if self.argocd_url.is_some() {
-            match self.fetch_argocd_status("cto-controller") {
+            match self.fetch_argocd_status("cto-controller").await {
                 Ok(status) => ctx.argocd_status = Some(status),
                 Err(e) => debug!("Failed to fetch ArgoCD status: {e}"),
             }
@@ -91,14 +133,14 @@ impl ContextGatherer {
 
         // Gather recent Loki logs if URL is configured
         if self.loki_url.is_some() {
-            match self.fetch_loki_errors(&failure.branch) {
+            match self.fetch_loki_errors(&failure.branch).await {
                 Ok(logs) => ctx.recent_logs = logs,
                 Err(e) => debug!("Failed to fetch Loki logs: {e}"),
             }
         }
 
         // Gather pod state from Kubernetes
-        match self.fetch_pod_state(&failure.workflow_name) {
+        match self.fetch_pod_state(&failure.workflow_name).await {
             Ok(state) => ctx.pod_state = Some(state),
             Err(e) => debug!("Failed to fetch pod state: {e}"),
         }
@@ -106,176 +148,88 @@ impl ContextGatherer {
         Ok(ctx)
     }
 
-    /// Fetch workflow logs using gh CLI.
+    /// Fetch the logs of a workflow run's failed jobs (or of every job if
+    /// none failed).
     ///
     /// # Errors
     ///
-    /// Returns an error if the `gh` CLI command fails.
-    pub fn fetch_workflow_logs(&self, run_id: u64) -> Result<String> {
-        let output = Command::new("gh")
-            .args([
-                "run",
-                "view",
-                &run_id.to_string(),
-                "--repo",
-                &self.repository,
-                "--log-failed",
-            ])
-            .output()
-            .context("Failed to execute gh run view")?;
-
-        if !output.status.success() {
-            // Try getting any logs if --log-failed doesn't work
-            let output = Command::new("gh")
-                .args([
-                    "run",
-                    "view",
-                    &run_id.to_string(),
-                    "--repo",
-                    &self.repository,
-                    "--log",
-                ])
-                .output()
-                .context("Failed to execute gh run view --log")?;
-
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                anyhow::bail!("gh run view failed: {stderr}");
-            }
-
-            return Ok(String::from_utf8_lossy(&output.stdout).to_string());
-        }
-
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    /// Returns an error if no GitHub client is configured or the logs cannot
+    /// be fetched.
+    pub async fn fetch_workflow_logs(&self, run_id: u64) -> Result<String> {
+        self.github()?
+            .workflow_run_logs(&self.repository, run_id)
+            .await
     }
 
-    /// Fetch PR for a branch using gh CLI.
+    /// Fetch the open PR for a branch.
     ///
     /// # Errors
     ///
-    /// Returns an error if the `gh` CLI command fails to execute.
-    #[allow(clippy::cast_possible_truncation)] // PR numbers are small positive integers
-    pub fn fetch_pr_for_branch(&self, branch: &str) -> Result<Option<PullRequest>> {
-        let output = Command::new("gh")
-            .args([
-                "pr",
-                "list",
-                "--repo",
-                &self.repository,
-                "--head",
-                branch,
-                "--json",
-                "number,title,state,headRefName,baseRefName,mergeable,statusCheckRollup,url",
-                "--limit",
-                "1",
-            ])
-            .output()
-            .context("Failed to execute gh pr list")?;
-
-        if !output.status.success() {
+    /// Returns an error if no GitHub client is configured or the PR or its
+    /// checks cannot be fetched.
+    pub async fn fetch_pr_for_branch(&self, branch: &str) -> Result<Option<PullRequest>> {
+        let github = self.github()?;
+        let Some(pr) = github.find_pull_request(&self.repository, branch).await? else {
             return Ok(None);
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let prs: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap_or_default();
-
-        if prs.is_empty() {
-            return Ok(None);
-        }
-
-        let pr = &prs[0];
+        };
+        let checks = github.check_summary(&self.repository, &pr.head_sha).await?;
+        let state = if pr.merged {
+            "MERGED"
+        } else if pr.open {
+            "OPEN"
+        } else {
+            "CLOSED"
+        };
         Ok(Some(PullRequest {
-            number: pr["number"].as_u64().unwrap_or(0) as u32,
-            title: pr["title"].as_str().unwrap_or("").to_string(),
-            state: pr["state"].as_str().unwrap_or("").to_string(),
-            head_ref: pr["headRefName"].as_str().unwrap_or("").to_string(),
-            base_ref: pr["baseRefName"].as_str().unwrap_or("").to_string(),
-            mergeable: pr["mergeable"].as_str().map(|s| s == "MERGEABLE"),
-            checks_status: pr["statusCheckRollup"].as_array().map_or_else(
-                || "unknown".to_string(),
-                |checks| {
-                    let failed = checks
-                        .iter()
-                        .filter(|c| c["conclusion"].as_str() == Some("FAILURE"))
-                        .count();
-                    if failed > 0 {
-                        format!("{failed} checks failing")
-                    } else {
-                        "all passing".to_string()
-                    }
-                },
-            ),
-            html_url: pr["url"].as_str().unwrap_or("").to_string(),
+            number: u32::try_from(pr.number).unwrap_or(u32::MAX),
+            title: pr.title,
+            state: state.to_string(),
+            head_ref: pr.head_branch,
+            base_ref: pr.base_branch,
+            mergeable: pr.mergeable,
+            checks_status: if checks.failed > 0 {
+                format!("{} checks failing", checks.failed)
+            } else {
+                "all passing".to_string()
+            },
+            html_url: pr.html_url,
         }))
     }
 
-    /// Fetch changed files for a commit using gh CLI.
+    /// Fetch changed files for a commit.
     ///
     /// # Errors
     ///
-    /// Returns an error if the `gh` CLI command fails.
-    pub fn fetch_changed_files(&self, sha: &str) -> Result<Vec<ChangedFile>> {
-        let output = Command::new("gh")
-            .args([
-                "api",
-                &format!("/repos/{}/commits/{}", self.repository, sha),
-                "--jq",
-                ".files[] | {filename: .filename, status: .status, additions: .additions, deletions: .deletions}",
-            ])
-            .output()
-            .context("Failed to execute gh api")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh api failed: {stderr}");
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut files = Vec::new();
-
-        // Parse JSONL output (one JSON object per line)
-        for line in stdout.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
-            if let Ok(file) = serde_json::from_str::<ChangedFile>(line) {
-                files.push(file);
-            }
-        }
-
-        Ok(files)
+    /// Returns an error if no GitHub client is configured or the commit
+    /// cannot be fetched.
+    pub async fn fetch_changed_files(&self, sha: &str) -> Result<Vec<ChangedFile>> {
+        let files = self.github()?.commit_files(&self.repository, sha).await?;
+        Ok(files
+            .into_iter()
+            .map(|file| ChangedFile {
+                filename: file.filename,
+                status: file.status,
+                additions: u32::try_from(file.additions).unwrap_or(u32::MAX),
+                deletions: u32::try_from(file.deletions).unwrap_or(u32::MAX),
+            })
+            .collect())
     }
 
-    /// Fetch `ArgoCD` application status using argocd CLI or API.
+    /// Fetch `ArgoCD` application status.
     ///
     /// # Errors
     ///
-    /// Returns an error if the kubectl command fails.
-    pub fn fetch_argocd_status(&self, app_name: &str) -> Result<ArgoCdStatus> {
-        // Try using kubectl to get ArgoCD app status
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "application",
-                app_name,
-                "-n",
-                "argocd",
-                "-o",
-                "jsonpath={.status.health.status},{.status.sync.status}",
-            ])
-            .output()
-            .context("Failed to execute kubectl get application")?;
-
-        if !output.status.success() {
-            anyhow::bail!("kubectl get application failed");
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let parts: Vec<&str> = stdout.split(',').collect();
-
+    /// Returns an error if no Kubernetes client is configured or the
+    /// application does not exist.
+    pub async fn fetch_argocd_status(&self, app_name: &str) -> Result<ArgoCdStatus> {
+        let status = self
+            .cluster()?
+            .get_application(ARGOCD_NAMESPACE, app_name)
+            .await?
+            .with_context(|| format!("Application {app_name} not found"))?;
         Ok(ArgoCdStatus {
-            health: (*parts.first().unwrap_or(&"Unknown")).to_string(),
-            sync: (*parts.get(1).unwrap_or(&"Unknown")).to_string(),
+            health: status.health,
+            sync: status.sync,
             unhealthy_resources: Vec::new(), // Would need additional query
         })
     }
@@ -284,75 +238,32 @@ impl ContextGatherer {
     ///
     /// # Errors
     ///
-    /// Returns an error if log fetching fails completely.
-    pub fn fetch_loki_errors(&self, branch: &str) -> Result<String> {
+    /// Returns an error if no Kubernetes client is configured.
+    pub async fn fetch_loki_errors(&self, branch: &str) -> Result<String> {
         // This would typically use HTTP to query Loki
-        // For now, use kubectl logs as a fallback
-        let output = Command::new("kubectl")
-            .args([
-                "logs",
-                "-n",
-                &self.namespace,
-                "-l",
-                &format!("branch={branch}"),
-                "--tail=100",
-                "--since=30m",
-            ])
-            .output();
-
-        match output {
-            Ok(out) if out.status.success() => Ok(String::from_utf8_lossy(&out.stdout).to_string()),
-            _ => Ok(String::new()), // Empty string if we can't get logs
-        }
+        // For now, read the branch's pod logs as a fallback
+        let selector = format!("branch={branch}");
+        Ok(self.logs_by_label(&selector, 100).await.unwrap_or_default()) // Empty string if we can't get logs
     }
 
     /// Fetch pod state from Kubernetes.
     ///
     /// # Errors
     ///
-    /// Returns an error if kubectl commands fail.
-    pub fn fetch_pod_state(&self, workflow_name: &str) -> Result<PodState> {
-        // Get pod names
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "pods",
-                "-n",
-                &self.namespace,
-                "-l",
-                &format!("workflows.argoproj.io/workflow={workflow_name}"),
-                "-o",
-                "jsonpath={.items[*].metadata.name}",
-            ])
-            .output()
-            .context("Failed to get pods")?;
-
-        let names: Vec<String> = String::from_utf8_lossy(&output.stdout)
-            .split_whitespace()
-            .map(String::from)
+    /// Returns an error if no Kubernetes client is configured or pods or
+    /// events cannot be listed.
+    pub async fn fetch_pod_state(&self, workflow_name: &str) -> Result<PodState> {
+        let cluster = self.cluster()?;
+        let selector = format!("workflows.argoproj.io/workflow={workflow_name}");
+        let names = cluster
+            .list_pods(&self.namespace, Some(&selector))
+            .await?
+            .into_iter()
+            .map(|pod| pod.name)
             .collect();
-
-        // Get recent events
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "events",
-                "-n",
-                &self.namespace,
-                "--field-selector",
-                &format!("involvedObject.name={workflow_name}"),
-                "-o",
-                "jsonpath={.items[*].message}",
-            ])
-            .output()
-            .context("Failed to get events")?;
-
-        let events: Vec<String> = String::from_utf8_lossy(&output.stdout)
-            .split('\n')
-            .filter(|s| !s.is_empty())
-            .map(String::from)
-            .collect();
-
+        let events = cluster
+            .event_messages(&self.namespace, workflow_name)
+            .await?;
         Ok(PodState { names, events })
     }
 
@@ -360,92 +271,72 @@ impl ContextGatherer {
     ///
     /// # Errors
     ///
-    /// Returns an error if the `gh` CLI command fails.
-    pub fn fetch_commits_since(
+    /// Returns an error if no GitHub client is configured.
+    pub async fn fetch_commits_since(
         &self,
         original_sha: &str,
         branch: &str,
-    ) -> Result<Vec<serde_json::Value>> {
-        let output = Command::new("gh")
-            .args([
-                "api",
-                &format!(
-                    "/repos/{}/compare/{}...{}",
-                    self.repository, original_sha, branch
-                ),
-                "--jq",
-                ".commits[] | {sha: .sha, message: .commit.message, author: .commit.author.name}",
-            ])
-            .output()
-            .context("Failed to fetch commits")?;
-
-        if !output.status.success() {
-            return Ok(Vec::new());
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut commits = Vec::new();
-
-        for line in stdout.lines() {
-            if let Ok(commit) = serde_json::from_str(line) {
-                commits.push(commit);
+    ) -> Result<Vec<CommitInfo>> {
+        let github = self.github()?;
+        let commits = match github
+            .compare_commits(&self.repository, original_sha, branch)
+            .await
+        {
+            Ok(commits) => commits,
+            Err(e) => {
+                debug!("Failed to compare {original_sha}...{branch}: {e}");
+                return Ok(Vec::new());
             }
-        }
-
-        Ok(commits)
+        };
+        Ok(commits
+            .into_iter()
+            .map(|commit| CommitInfo {
+                sha: commit.sha,
+                message: commit.message,
+                author: commit.author,
+            })
+            .collect())
     }
 
     /// Fetch `CodeRun` logs for retry context.
     ///
     /// # Errors
     ///
-    /// Returns an error if kubectl logs command fails.
-    pub fn fetch_coderun_logs(&self, coderun_name: &str) -> Result<String> {
-        let output = Command::new("kubectl")
-            .args([
-                "logs",
-                "-n",
-                &self.namespace,
-                "-l",
-                &format!("coderun.cto.5dlabs.io/name={coderun_name}"),
-                "--tail=200",
-            ])
-            .output()
-            .context("Failed to fetch CodeRun logs")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("kubectl logs failed: {stderr}");
-        }
-
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    /// Returns an error if no Kubernetes client is configured or the logs
+    /// cannot be fetched.
+    pub async fn fetch_coderun_logs(&self, coderun_name: &str) -> Result<String> {
+        let selector = format!("coderun.cto.5dlabs.io/name={coderun_name}");
+        self.logs_by_label(&selector, 200).await
     }
 
     /// Post a comment on a PR.
     ///
     /// # Errors
     ///
-    /// Returns an error if the `gh` CLI command fails.
-    pub fn post_pr_comment(&self, pr_number: u32, comment: &str) -> Result<()> {
-        let output = Command::new("gh")
-            .args([
-                "pr",
-                "comment",
-                &pr_number.to_string(),
-                "--repo",
-                &self.repository,
-                "--body",
-                comment,
-            ])
-            .output()
-            .context("Failed to post PR comment")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh pr comment failed: {stderr}");
-        }
+    /// Returns an error if no GitHub client is configured or the comment
+    /// cannot be posted.
+    pub async fn post_pr_comment(&self, pr_number: u32, comment: &str) -> Result<()> {
+        self.github()?
+            .comment(&self.repository, u64::from(pr_number), comment)
+            .await
+    }
 
-        Ok(())
+    /// Last `tail_lines` lines of every pod matching `selector`,
+    /// concatenated.
+    async fn logs_by_label(&self, selector: &str, tail_lines: i64) -> Result<String> {
+        let cluster = self.cluster()?;
+        let mut logs = String::new();
+        for pod in cluster.list_pods(&self.namespace, Some(selector)).await? {
+            let pod_logs = cluster
+                .pod_logs(&self.namespace, &pod.name, tail_lines)
+                .await
+                .with_context(|| format!("Failed to fetch logs of {}", pod.name))?;
+            logs.push_str(&pod_logs);
+            if !logs.is_empty() && !logs.ends_with('\n') {
+                logs.push('\n');
+            }
+        }
+        Ok(logs)
     }
 }
 
@@ -488,6 +379,156 @@ impl ChangedFilesAnalysis for Vec<ChangedFile> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clients::{
+        self, ApplicationStatus, CheckSummary, CommitFile, FakeCluster, FakeGitHub,
+    };
+    use crate::k8s::Pod;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn failure() -> CiFailure {
+        CiFailure {
+            workflow_run_id: 99,
+            workflow_name: "controller-ci".to_string(),
+            job_name: Some("lint-rust".to_string()),
+            conclusion: "failure".to_string(),
+            branch: "feat/x".to_string(),
+            head_sha: "abc123".to_string(),
+            commit_message: "feat: x".to_string(),
+            html_url: String::new(),
+            repository: "5dlabs/cto".to_string(),
+            sender: "dev".to_string(),
+            detected_at: Utc::now(),
+            raw_event: None,
+        }
+    }
+
+    fn workflow_pod(name: &str) -> Pod {
+        Pod {
+            name: name.to_string(),
+            namespace: "cto".to_string(),
+            labels: HashMap::from([(
+                "workflows.argoproj.io/workflow".to_string(),
+                "controller-ci".to_string(),
+            )]),
+            ..Pod::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gather_through_clients() {
+        let github = FakeGitHub::new()
+            .with_run_logs(
+                "5dlabs/cto",
+                99,
+                "=== lint-rust ===\nerror[E0425]: cannot find value `y`\n  --> src/main.rs:4:13\n",
+            )
+            .with_pull_request(
+                "5dlabs/cto",
+                clients::PullRequest {
+                    number: 12,
+                    title: "feat: x".to_string(),
+                    head_branch: "feat/x".to_string(),
+                    head_sha: "abc123".to_string(),
+                    base_branch: "main".to_string(),
+                    open: true,
+                    mergeable: Some(true),
+                    ..clients::PullRequest::default()
+                },
+            )
+            .with_checks(
+                "5dlabs/cto",
+                "abc123",
+                CheckSummary {
+                    total: 5,
+                    succeeded: 3,
+                    failed: 2,
+                },
+            )
+            .with_commit_files(
+                "5dlabs/cto",
+                "abc123",
+                vec![CommitFile {
+                    filename: "src/main.rs".to_string(),
+                    status: "modified".to_string(),
+                    additions: 3,
+                    deletions: 1,
+                }],
+            );
+        let cluster = FakeCluster::new()
+            .with_pod(workflow_pod("controller-ci-1"))
+            .with_event(
+                "cto",
+                "controller-ci",
+                "Back-off restarting failed container",
+            )
+            .with_application(
+                "argocd",
+                "cto-controller",
+                ApplicationStatus {
+                    health: "Degraded".to_string(),
+                    sync: "OutOfSync".to_string(),
+                    ..ApplicationStatus::default()
+                },
+            );
+        let gatherer = ContextGatherer::new("5dlabs/cto", "cto")
+            .with_github(Arc::new(github))
+            .with_cluster(Arc::new(cluster))
+            .with_argocd("https://argocd.example");
+
+        let ctx = gatherer.gather(&failure()).await.unwrap();
+
+        assert_eq!(ctx.failures.len(), 1);
+        assert_eq!(ctx.failures[0].rule.as_deref(), Some("E0425"));
+        let pr = ctx.pr.unwrap();
+        assert_eq!((pr.number, pr.state.as_str()), (12, "OPEN"));
+        assert_eq!(pr.checks_status, "2 checks failing");
+        assert_eq!(ctx.changed_files.len(), 1);
+        assert!(ctx.changed_files.mostly_rust());
+        let argocd = ctx.argocd_status.unwrap();
+        assert_eq!(
+            (argocd.health.as_str(), argocd.sync.as_str()),
+            ("Degraded", "OutOfSync")
+        );
+        let pods = ctx.pod_state.unwrap();
+        assert_eq!(pods.names, vec!["controller-ci-1".to_string()]);
+        assert_eq!(pods.events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gather_without_clients_keeps_the_failure() {
+        let ctx = ContextGatherer::new("5dlabs/cto", "cto")
+            .gather(&failure())
+            .await
+            .unwrap();
+        assert!(ctx.failure.is_some());
+        assert!(ctx.workflow_logs.is_empty());
+        assert!(ctx.pr.is_none());
+        assert!(ctx.pod_state.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_coderun_logs_across_pods() {
+        let labels = HashMap::from([(
+            "coderun.cto.5dlabs.io/name".to_string(),
+            "healer-ci-1".to_string(),
+        )]);
+        let pod = |name: &str| Pod {
+            name: name.to_string(),
+            namespace: "cto".to_string(),
+            labels: labels.clone(),
+            ..Pod::default()
+        };
+        let cluster = FakeCluster::new()
+            .with_pod(pod("healer-ci-1-a"))
+            .with_pod(pod("healer-ci-1-b"))
+            .with_logs("cto", "healer-ci-1-a", "first")
+            .with_logs("cto", "healer-ci-1-b", "second");
+        let gatherer = ContextGatherer::new("5dlabs/cto", "cto").with_cluster(Arc::new(cluster));
+
+        let logs = gatherer.fetch_coderun_logs("healer-ci-1").await.unwrap();
+        assert_eq!(logs, "first\nsecond\n");
+    }
 
     #[test]
     fn test_changed_files_analysis() {
@@ -5,11 +5,12 @@
 //! - Sends Discord notifications via the notify crate
 //! - Creates GitHub issues for tracking
 
-use anyhow::{Context as _, Result};
-use std::process::Command;
+use anyhow::Result;
+use std::sync::Arc;
 use tracing::{info, warn};
 
 use super::types::{CiFailure, RemediationAttempt};
+use crate::clients::GitHubClient;
 
 /// Escalation configuration.
 #[derive(Debug, Clone)]
@@ -36,15 +37,17 @@ impl Default for EscalationConfig {
 pub struct Escalator {
     config: EscalationConfig,
     notifier: notify::Notifier,
+    github: Arc<dyn GitHubClient>,
 }
 
 impl Escalator {
     /// Create a new escalator.
     #[must_use]
-    pub fn new(config: EscalationConfig) -> Self {
+    pub fn new(config: EscalationConfig, github: Arc<dyn GitHubClient>) -> Self {
         Self {
             config,
             notifier: notify::Notifier::from_env(),
+            github,
         }
     }
 
@@ -53,7 +56,7 @@ impl Escalator {
     /// # Errors
     ///
     /// Returns an error if all escalation methods fail.
-    pub fn escalate(
+    pub async fn escalate(
         &self,
         failure: &CiFailure,
         attempts: &[RemediationAttempt],
@@ -71,7 +74,10 @@ impl Escalator {
         // Post PR comment if we have a PR
         if self.config.pr_comment_enabled {
             if let Some(pr) = pr_number {
-                if let Err(e) = Self::post_pr_comment(&failure.repository, pr, &message) {
+                if let Err(e) = self
+                    .post_pr_comment(&failure.repository, pr, &message)
+                    .await
+                {
                     warn!("Failed to post PR comment: {e}");
                 }
             }
@@ -84,7 +90,10 @@ impl Escalator {
 
         // Create GitHub issue if enabled
         if self.config.github_issue_enabled {
-            if let Err(e) = Self::create_github_issue(&failure.repository, failure, attempts) {
+            if let Err(e) = self
+                .create_github_issue(&failure.repository, failure, attempts)
+                .await
+            {
                 warn!("Failed to create GitHub issue: {e}");
             }
         }
@@ -176,24 +185,10 @@ impl Escalator {
     }
 
     /// Post a comment to a GitHub PR.
-    fn post_pr_comment(repository: &str, pr_number: u32, message: &str) -> Result<()> {
-        let output = Command::new("gh")
-            .args([
-                "pr",
-                "comment",
-                &pr_number.to_string(),
-                "--repo",
-                repository,
-                "--body",
-                message,
-            ])
-            .output()
-            .context("Failed to execute gh pr comment")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh pr comment failed: {stderr}");
-        }
+    async fn post_pr_comment(&self, repository: &str, pr_number: u32, message: &str) -> Result<()> {
+        self.github
+            .comment(repository, u64::from(pr_number), message)
+            .await?;
 
         info!("Posted escalation comment to PR #{pr_number}");
 
@@ -239,7 +234,8 @@ impl Escalator {
     }
 
     /// Create a GitHub issue for tracking.
-    fn create_github_issue(
+    async fn create_github_issue(
+        &self,
         repository: &str,
         failure: &CiFailure,
         attempts: &[RemediationAttempt],
@@ -248,23 +244,17 @@ impl Escalator {
 
         let body = Self::build_escalation_message(failure, attempts);
 
-        let labels = "healer,ci-failure,needs-attention";
+        let labels: Vec<String> = ["healer", "ci-failure", "needs-attention"]
+            .into_iter()
+            .map(String::from)
+            .collect();
 
-        let output = Command::new("gh")
-            .args([
-                "issue", "create", "--repo", repository, "--title", &title, "--body", &body,
-                "--label", labels,
-            ])
-            .output()
-            .context("Failed to execute gh issue create")?;
+        let number = self
+            .github
+            .create_issue(repository, &title, &body, &labels)
+            .await?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh issue create failed: {stderr}");
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        info!("Created GitHub issue: {}", stdout.trim());
+        info!("Created GitHub issue: {repository}#{number}");
 
         Ok(())
     }
@@ -274,6 +264,7 @@ impl Escalator {
 mod tests {
     use super::*;
     use crate::ci::types::{Agent, AttemptOutcome};
+    use crate::clients::{FakeGitHub, PullRequest};
     use chrono::Utc;
 
     fn create_test_failure() -> CiFailure {
@@ -320,7 +311,6 @@ mod tests {
 
     #[test]
     fn test_build_escalation_message() {
-        let _escalator = Escalator::new(EscalationConfig::default());
         let failure = create_test_failure();
         let attempts = create_test_attempts();
 
@@ -332,4 +322,40 @@ mod tests {
         assert!(message.contains("atlas"));
         assert!(message.contains("feat/test"));
     }
+
+    #[tokio::test]
+    async fn test_escalate_comments_on_pr_and_opens_issue() {
+        let github = Arc::new(FakeGitHub::new().with_pull_request(
+            "test/repo",
+            PullRequest {
+                number: 42,
+                title: "Fix the build".to_string(),
+                head_branch: "feat/test".to_string(),
+                open: true,
+                merged: false,
+                ..PullRequest::default()
+            },
+        ));
+        let config = EscalationConfig {
+            discord_enabled: false,
+            github_issue_enabled: true,
+            pr_comment_enabled: true,
+        };
+        let escalator = Escalator::new(config, github.clone());
+
+        escalator
+            .escalate(&create_test_failure(), &create_test_attempts(), Some(42))
+            .await
+            .unwrap();
+
+        let comments = github.comments();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].number, 42);
+        assert!(comments[0].body.contains("CI Remediation Escalation"));
+
+        let issues = github.issues("test/repo");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].title, "[Healer] CI Remediation Failed: CI");
+        assert!(issues[0].labels.contains(&"needs-attention".to_string()));
+    }
 }
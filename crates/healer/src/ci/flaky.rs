@@ -0,0 +1,613 @@
+//! Flaky test detection and quarantine.
+//!
+//! Records per-test pass/fail history from parsed CI results, keyed by
+//! repository, branch and test ID, and scores each test by how often its
+//! outcome flips between runs of the same commit. A test that fails and then
+//! passes on a retry without any code change is flaky, not broken.
+//!
+//! When every failing test in a run is known to be flaky, the failure is
+//! quarantined - tracked in a GitHub issue - instead of dispatching a fix
+//! agent.
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+use super::parsers::FailureItem;
+use super::types::CiFailure;
+use crate::clients::{GitHubClient, IssueQuery};
+
+/// Flaky test detection configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlakyTestConfig {
+    /// Whether flaky failures are quarantined instead of remediated
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Minimum flip rate (0.0-1.0) for a test to count as flaky
+    #[serde(default = "default_min_flip_rate")]
+    pub min_flip_rate: f64,
+    /// Minimum same-commit flips before a test can count as flaky
+    #[serde(default = "default_min_flips")]
+    pub min_flips: u32,
+    /// Runs of history kept per test
+    #[serde(default = "default_history_limit")]
+    pub history_limit: usize,
+    /// File the history is persisted to between restarts
+    #[serde(default)]
+    pub state_path: Option<String>,
+    /// Labels for quarantine issues
+    #[serde(default = "default_quarantine_labels")]
+    pub quarantine_labels: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_min_flip_rate() -> f64 {
+    0.3
+}
+
+fn default_min_flips() -> u32 {
+    2
+}
+
+fn default_history_limit() -> usize {
+    50
+}
+
+fn default_quarantine_labels() -> Vec<String> {
+    vec!["flaky-test".to_string(), "healer".to_string()]
+}
+
+impl Default for FlakyTestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            min_flip_rate: default_min_flip_rate(),
+            min_flips: default_min_flips(),
+            history_limit: default_history_limit(),
+            state_path: None,
+            quarantine_labels: default_quarantine_labels(),
+        }
+    }
+}
+
+/// One recorded outcome of a test.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestRun {
+    /// Commit the run tested
+    pub head_sha: String,
+    /// Workflow run ID
+    pub workflow_run_id: u64,
+    /// Whether the test passed
+    pub passed: bool,
+    /// When the outcome was recorded
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Pass/fail history for one test on one branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestHistory {
+    /// Repository full name
+    pub repository: String,
+    /// Branch
+    pub branch: String,
+    /// Test ID (file-qualified test name)
+    pub test_id: String,
+    /// Workflow (and job) that runs the test
+    pub workflow: String,
+    /// Recorded runs, oldest first
+    pub runs: VecDeque<TestRun>,
+}
+
+impl TestHistory {
+    /// Score the test from its same-commit retries.
+    #[must_use]
+    pub fn score(&self) -> FlakeScore {
+        let mut by_commit: HashMap<&str, Vec<bool>> = HashMap::new();
+        for run in &self.runs {
+            by_commit
+                .entry(run.head_sha.as_str())
+                .or_default()
+                .push(run.passed);
+        }
+
+        let mut flips = 0u32;
+        let mut transitions = 0u32;
+        let mut mixed_commits = 0u32;
+        for outcomes in by_commit.values() {
+            let commit_flips = outcomes.windows(2).filter(|w| w[0] != w[1]).count() as u32;
+            transitions += outcomes.len().saturating_sub(1) as u32;
+            flips += commit_flips;
+            if commit_flips > 0 {
+                mixed_commits += 1;
+            }
+        }
+
+        FlakeScore {
+            flip_rate: if transitions == 0 {
+                0.0
+            } else {
+                f64::from(flips) / f64::from(transitions)
+            },
+            flips,
+            runs: self.runs.len(),
+            failures: self.runs.iter().filter(|r| !r.passed).count(),
+            mixed_commits,
+        }
+    }
+}
+
+/// How flaky a test looks.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FlakeScore {
+    /// Share of same-commit retries whose outcome differed from the
+    /// previous run of that commit
+    pub flip_rate: f64,
+    /// Outcome changes between runs of the same commit
+    pub flips: u32,
+    /// Runs recorded
+    pub runs: usize,
+    /// Failed runs recorded
+    pub failures: usize,
+    /// Commits that both passed and failed
+    pub mixed_commits: u32,
+}
+
+/// A failing test that is known to be flaky.
+#[derive(Debug, Clone)]
+pub struct FlakyFailure {
+    /// Test ID
+    pub test_id: String,
+    /// The parsed failure
+    pub item: FailureItem,
+    /// The test's score
+    pub score: FlakeScore,
+}
+
+/// Persisted history for every tracked test.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FlakeState {
+    tests: HashMap<String, TestHistory>,
+}
+
+/// Records test outcomes and decides which failures are flaky.
+#[derive(Debug, Default)]
+pub struct FlakeTracker {
+    config: FlakyTestConfig,
+    state: FlakeState,
+}
+
+/// Stable ID for a parsed test failure, or `None` for non-test failures
+/// such as compiler errors.
+#[must_use]
+pub fn test_id(item: &FailureItem) -> Option<String> {
+    let name = item.test_name.as_deref()?;
+    Some(match &item.file {
+        Some(file) if !name.contains(file.as_str()) => format!("{file}::{name}"),
+        _ => name.to_string(),
+    })
+}
+
+/// Workflow label a run's tests are recorded under.
+fn workflow_label(failure: &CiFailure) -> String {
+    match &failure.job_name {
+        Some(job) => format!("{} / {job}", failure.workflow_name),
+        None => failure.workflow_name.clone(),
+    }
+}
+
+fn history_key(repository: &str, branch: &str, test_id: &str) -> String {
+    format!("{repository}|{branch}|{test_id}")
+}
+
+impl FlakeTracker {
+    /// Create an empty tracker.
+    #[must_use]
+    pub fn new(config: FlakyTestConfig) -> Self {
+        Self {
+            config,
+            state: FlakeState::default(),
+        }
+    }
+
+    /// Create a tracker, restoring history from `config.state_path` if it
+    /// exists.
+    pub fn load(config: FlakyTestConfig) -> Result<Self> {
+        let mut tracker = Self::new(config);
+        if let Some(path) = tracker.config.state_path.as_deref() {
+            if Path::new(path).exists() {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read flaky test state from {path}"))?;
+                tracker.state = serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse flaky test state in {path}"))?;
+                info!(
+                    "Loaded history for {} tests from {path}",
+                    tracker.state.tests.len()
+                );
+            }
+        }
+        Ok(tracker)
+    }
+
+    /// Persist history to `config.state_path`, if set.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = self.config.state_path.as_deref() else {
+            return Ok(());
+        };
+        let json = serde_json::to_string(&self.state)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write flaky test state to {path}"))
+    }
+
+    /// Configuration.
+    #[must_use]
+    pub fn config(&self) -> &FlakyTestConfig {
+        &self.config
+    }
+
+    /// History for a test, if any has been recorded.
+    #[must_use]
+    pub fn history(&self, repository: &str, branch: &str, test_id: &str) -> Option<&TestHistory> {
+        self.state
+            .tests
+            .get(&history_key(repository, branch, test_id))
+    }
+
+    /// Record the failing tests of a failed run.
+    pub fn record_failure(&mut self, failure: &CiFailure, items: &[FailureItem]) {
+        for id in items.iter().filter_map(test_id) {
+            self.push(failure, &id, false);
+        }
+    }
+
+    /// Record a passing run: every test this workflow has failed on the
+    /// branch before passed this time. A passing workflow run (no job name)
+    /// covers the tests of all its jobs.
+    pub fn record_success(&mut self, failure: &CiFailure) {
+        let workflow = workflow_label(failure);
+        let job_prefix = format!("{} / ", failure.workflow_name);
+        let ids: Vec<String> = self
+            .state
+            .tests
+            .values()
+            .filter(|h| {
+                h.repository == failure.repository
+                    && h.branch == failure.branch
+                    && (h.workflow == workflow
+                        || (failure.job_name.is_none() && h.workflow.starts_with(&job_prefix)))
+            })
+            .map(|h| h.test_id.clone())
+            .collect();
+        debug!("Recording {} passing tests for {workflow}", ids.len());
+        for id in ids {
+            self.push(failure, &id, true);
+        }
+    }
+
+    fn push(&mut self, failure: &CiFailure, test_id: &str, passed: bool) {
+        let history = self
+            .state
+            .tests
+            .entry(history_key(&failure.repository, &failure.branch, test_id))
+            .or_insert_with(|| TestHistory {
+                repository: failure.repository.clone(),
+                branch: failure.branch.clone(),
+                test_id: test_id.to_string(),
+                workflow: workflow_label(failure),
+                runs: VecDeque::new(),
+            });
+
+        // Re-delivered events must not count as retries
+        if history
+            .runs
+            .iter()
+            .any(|r| r.workflow_run_id == failure.workflow_run_id && r.passed == passed)
+        {
+            return;
+        }
+
+        history.runs.push_back(TestRun {
+            head_sha: failure.head_sha.clone(),
+            workflow_run_id: failure.workflow_run_id,
+            passed,
+            recorded_at: Utc::now(),
+        });
+        while history.runs.len() > self.config.history_limit {
+            history.runs.pop_front();
+        }
+    }
+
+    /// Whether a score crosses the flakiness thresholds.
+    #[must_use]
+    pub fn is_flaky(&self, score: &FlakeScore) -> bool {
+        score.flips >= self.config.min_flips && score.flip_rate >= self.config.min_flip_rate
+    }
+
+    /// The flaky failures of a run, if the run should be quarantined.
+    ///
+    /// Returns `None` unless quarantine is enabled, the run has at least one
+    /// failing test, and every parsed failure is a known-flaky test. A run
+    /// that also has compiler errors, lint failures or a genuinely new test
+    /// failure still goes to a fix agent.
+    #[must_use]
+    pub fn quarantine_candidates(
+        &self,
+        failure: &CiFailure,
+        items: &[FailureItem],
+    ) -> Option<Vec<FlakyFailure>> {
+        if !self.config.enabled || items.is_empty() {
+            return None;
+        }
+
+        let mut flaky = Vec::new();
+        for item in items {
+            let id = test_id(item)?;
+            let score = self
+                .history(&failure.repository, &failure.branch, &id)?
+                .score();
+            if !self.is_flaky(&score) {
+                return None;
+            }
+            flaky.push(FlakyFailure {
+                test_id: id,
+                item: item.clone(),
+                score,
+            });
+        }
+        Some(flaky)
+    }
+}
+
+/// Tracks quarantined tests in GitHub issues, one per test.
+pub struct Quarantiner {
+    labels: Vec<String>,
+    github: Arc<dyn GitHubClient>,
+}
+
+impl Quarantiner {
+    /// Create a quarantiner that labels its issues with `labels`.
+    #[must_use]
+    pub fn new(labels: Vec<String>, github: Arc<dyn GitHubClient>) -> Self {
+        Self { labels, github }
+    }
+
+    fn issue_title(test_id: &str) -> String {
+        format!("[Healer] Flaky test: {test_id}")
+    }
+
+    /// Open an issue for each flaky test, or comment on the existing one.
+    /// Returns the issue numbers.
+    pub async fn quarantine(
+        &self,
+        failure: &CiFailure,
+        flaky: &[FlakyFailure],
+    ) -> Result<Vec<u64>> {
+        let labels: Vec<&str> = self.labels.iter().map(String::as_str).collect();
+        let existing = self
+            .github
+            .list_issues(
+                &failure.repository,
+                &IssueQuery::open_with_labels(&labels, 100),
+            )
+            .await?;
+
+        let mut numbers = Vec::new();
+        for test in flaky {
+            let title = Self::issue_title(&test.test_id);
+            let body = Self::build_body(failure, test);
+            let number = if let Some(issue) = existing.iter().find(|i| i.title == title) {
+                self.github
+                    .comment(&failure.repository, issue.number, &body)
+                    .await?;
+                issue.number
+            } else {
+                self.github
+                    .create_issue(&failure.repository, &title, &body, &self.labels)
+                    .await?
+            };
+            info!(
+                "Quarantined flaky test {} in {}#{number}",
+                test.test_id, failure.repository
+            );
+            numbers.push(number);
+        }
+        Ok(numbers)
+    }
+
+    fn build_body(failure: &CiFailure, test: &FlakyFailure) -> String {
+        let mut body = String::new();
+        let _ = writeln!(
+            body,
+            "`{}` failed again and is known to be flaky, so no fix agent was dispatched.\n",
+            test.test_id
+        );
+        let _ = writeln!(body, "| Field | Value |");
+        let _ = writeln!(body, "|-------|-------|");
+        let _ = writeln!(body, "| Workflow | {} |", workflow_label(failure));
+        let _ = writeln!(body, "| Branch | `{}` |", failure.branch);
+        let _ = writeln!(body, "| Commit | `{}` |", failure.head_sha);
+        let _ = writeln!(body, "| Run | {} |", failure.html_url);
+        let _ = writeln!(
+            body,
+            "| Flip rate | {:.0}% ({} flips over {} runs, {} failed) |",
+            test.score.flip_rate * 100.0,
+            test.score.flips,
+            test.score.runs,
+            test.score.failures
+        );
+        let _ = writeln!(
+            body,
+            "| Commits that both passed and failed | {} |",
+            test.score.mixed_commits
+        );
+        let _ = writeln!(body, "\n**Failure:** {}", test.item.message);
+        body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ci::parsers::parse_ci_output;
+    use crate::clients::FakeGitHub;
+
+    fn failure(run_id: u64, sha: &str, conclusion: &str) -> CiFailure {
+        CiFailure {
+            workflow_run_id: run_id,
+            workflow_name: "Controller CI".to_string(),
+            job_name: Some("test".to_string()),
+            conclusion: conclusion.to_string(),
+            branch: "main".to_string(),
+            head_sha: sha.to_string(),
+            commit_message: "test".to_string(),
+            html_url: format!("https://github.com/5dlabs/cto/actions/runs/{run_id}"),
+            repository: "5dlabs/cto".to_string(),
+            sender: "developer".to_string(),
+            detected_at: Utc::now(),
+            raw_event: None,
+        }
+    }
+
+    const FLAKY_LOGS: &str = "\
+---- tests::test_watch_reconnects stdout ----
+thread 'tests::test_watch_reconnects' panicked at src/watch.rs:88:9:
+timed out waiting for event
+failures:
+    tests::test_watch_reconnects
+";
+
+    const BUILD_ERROR_LOGS: &str = "\
+error[E0425]: cannot find value `x` in this scope
+  --> src/main.rs:4:13
+";
+
+    /// Fail then pass on each of `commits`, as retries would.
+    fn tracker_with_retries(commits: &[&str]) -> FlakeTracker {
+        let mut tracker = FlakeTracker::new(FlakyTestConfig::default());
+        let items = parse_ci_output(FLAKY_LOGS);
+        for (i, sha) in commits.iter().enumerate() {
+            let run_id = i as u64 * 10;
+            tracker.record_failure(&failure(run_id, sha, "failure"), &items);
+            tracker.record_success(&failure(run_id + 1, sha, "success"));
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_flip_rate_counts_same_commit_retries_only() {
+        let tracker = tracker_with_retries(&["aaa", "bbb"]);
+        let id = test_id(&parse_ci_output(FLAKY_LOGS)[0]).unwrap();
+        let score = tracker.history("5dlabs/cto", "main", &id).unwrap().score();
+        assert_eq!(score.runs, 4);
+        assert_eq!(score.flips, 2);
+        assert_eq!(score.mixed_commits, 2);
+        assert!((score.flip_rate - 1.0).abs() < f64::EPSILON);
+        assert!(tracker.is_flaky(&score));
+
+        // Failing on every new commit is a real regression, not a flake
+        let mut broken = FlakeTracker::new(FlakyTestConfig::default());
+        let items = parse_ci_output(FLAKY_LOGS);
+        for (run_id, sha) in [(1, "aaa"), (2, "bbb"), (3, "ccc")] {
+            broken.record_failure(&failure(run_id, sha, "failure"), &items);
+        }
+        let score = broken.history("5dlabs/cto", "main", &id).unwrap().score();
+        assert_eq!(score.flips, 0);
+        assert!(!broken.is_flaky(&score));
+    }
+
+    #[test]
+    fn test_redelivered_events_are_ignored() {
+        let mut tracker = FlakeTracker::new(FlakyTestConfig::default());
+        let items = parse_ci_output(FLAKY_LOGS);
+        let run = failure(1, "aaa", "failure");
+        tracker.record_failure(&run, &items);
+        tracker.record_failure(&run, &items);
+        let id = test_id(&items[0]).unwrap();
+        assert_eq!(
+            tracker
+                .history("5dlabs/cto", "main", &id)
+                .unwrap()
+                .runs
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_quarantine_only_when_every_failure_is_flaky() {
+        let tracker = tracker_with_retries(&["aaa", "bbb"]);
+        let run = failure(100, "ccc", "failure");
+
+        let flaky_items = parse_ci_output(FLAKY_LOGS);
+        let candidates = tracker.quarantine_candidates(&run, &flaky_items).unwrap();
+        assert_eq!(candidates.len(), 1);
+
+        let mut mixed = flaky_items.clone();
+        mixed.extend(parse_ci_output(BUILD_ERROR_LOGS));
+        assert!(tracker.quarantine_candidates(&run, &mixed).is_none());
+
+        // Unknown tests and runs without parsed failures go to an agent
+        assert!(tracker.quarantine_candidates(&run, &[]).is_none());
+        let fresh = FlakeTracker::new(FlakyTestConfig::default());
+        assert!(fresh.quarantine_candidates(&run, &flaky_items).is_none());
+
+        let disabled = FlakeTracker {
+            config: FlakyTestConfig {
+                enabled: false,
+                ..FlakyTestConfig::default()
+            },
+            state: tracker.state.clone(),
+        };
+        assert!(disabled.quarantine_candidates(&run, &flaky_items).is_none());
+    }
+
+    #[test]
+    fn test_state_persists_between_restarts() {
+        let dir = std::env::temp_dir().join(format!("healer-flaky-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = FlakyTestConfig {
+            state_path: Some(dir.join("flaky.json").to_string_lossy().into_owned()),
+            ..FlakyTestConfig::default()
+        };
+
+        let mut tracker = FlakeTracker::load(config.clone()).unwrap();
+        tracker.state = tracker_with_retries(&["aaa", "bbb"]).state;
+        tracker.save().unwrap();
+
+        let restored = FlakeTracker::load(config).unwrap();
+        let run = failure(100, "ccc", "failure");
+        assert!(restored
+            .quarantine_candidates(&run, &parse_ci_output(FLAKY_LOGS))
+            .is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_quarantine_opens_then_reuses_issue() {
+        let github = Arc::new(FakeGitHub::new());
+        let quarantiner = Quarantiner::new(default_quarantine_labels(), github.clone());
+        let tracker = tracker_with_retries(&["aaa", "bbb"]);
+        let run = failure(100, "ccc", "failure");
+        let flaky = tracker
+            .quarantine_candidates(&run, &parse_ci_output(FLAKY_LOGS))
+            .unwrap();
+
+        let first = quarantiner.quarantine(&run, &flaky).await.unwrap();
+        let second = quarantiner.quarantine(&run, &flaky).await.unwrap();
+        assert_eq!(first, second);
+
+        let issues = github.issues("5dlabs/cto");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].title.contains("tests::test_watch_reconnects"));
+        assert!(issues[0].body.contains("Flip rate | 100%"));
+        assert!(issues[0].labels.contains(&"flaky-test".to_string()));
+        assert_eq!(github.comments().len(), 1);
+    }
+}
@@ -0,0 +1,798 @@
+//! Blast-radius limits and a read-only circuit breaker for remediation.
+//!
+//! On top of the cluster-wide `max_concurrent_coderuns`, remediation is
+//! bounded per repository and per alert (failure) type, and auto-merges are
+//! capped per rolling hour. The breaker watches remediation outcomes and
+//! reverts of PRs healer auto-merged; once either rate passes its threshold,
+//! healer goes read-only (no spawns, no merges) and sends a notification
+//! until the breaker is reset through the API or `reset_after_mins` passes.
+//!
+//! Breaker state is persisted in the shared state ConfigMap when a store is
+//! configured. Every change is applied to the stored state, so server
+//! replicas and the alert watch count each other's outcomes and merges and
+//! honour each other's trips.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use tracing::{error, info, warn};
+
+use crate::clients::GitHubClient;
+use crate::ha::StateStore;
+
+/// State store key for breaker state.
+const STATE_KEY: &str = "guardrails";
+
+/// Default-branch commits checked per repository and revert scan.
+const MAX_SCANNED_COMMITS: usize = 100;
+
+/// Guardrail configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardrailConfig {
+    /// Concurrent remediations per repository (0 = unlimited)
+    #[serde(default = "default_max_concurrent_per_repo")]
+    pub max_concurrent_per_repo: usize,
+    /// Concurrent remediations per alert/failure type (0 = unlimited)
+    #[serde(default = "default_max_concurrent_per_alert_type")]
+    pub max_concurrent_per_alert_type: usize,
+    /// Auto-merges per rolling hour across all repositories (0 = unlimited)
+    #[serde(default = "default_max_auto_merges_per_hour")]
+    pub max_auto_merges_per_hour: usize,
+    /// Trip when this fraction (0.0-1.0) of completed remediations failed
+    #[serde(default = "default_failure_rate_threshold")]
+    pub failure_rate_threshold: f64,
+    /// Trip when this fraction (0.0-1.0) of auto-merged PRs were reverted
+    #[serde(default = "default_revert_rate_threshold")]
+    pub revert_rate_threshold: f64,
+    /// Outcomes (or merges) needed in the window before a rate can trip
+    #[serde(default = "default_min_samples")]
+    pub min_samples: usize,
+    /// Window rates are computed over (hours)
+    #[serde(default = "default_rate_window_hours")]
+    pub rate_window_hours: u32,
+    /// Leave read-only mode automatically after this long (0 = only via
+    /// the API)
+    #[serde(default)]
+    pub reset_after_mins: u32,
+}
+
+fn default_max_concurrent_per_repo() -> usize {
+    2
+}
+
+fn default_max_concurrent_per_alert_type() -> usize {
+    3
+}
+
+fn default_max_auto_merges_per_hour() -> usize {
+    3
+}
+
+fn default_failure_rate_threshold() -> f64 {
+    0.6
+}
+
+fn default_revert_rate_threshold() -> f64 {
+    0.2
+}
+
+fn default_min_samples() -> usize {
+    5
+}
+
+fn default_rate_window_hours() -> u32 {
+    24
+}
+
+impl Default for GuardrailConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_per_repo: default_max_concurrent_per_repo(),
+            max_concurrent_per_alert_type: default_max_concurrent_per_alert_type(),
+            max_auto_merges_per_hour: default_max_auto_merges_per_hour(),
+            failure_rate_threshold: default_failure_rate_threshold(),
+            revert_rate_threshold: default_revert_rate_threshold(),
+            min_samples: default_min_samples(),
+            rate_window_hours: default_rate_window_hours(),
+            reset_after_mins: 0,
+        }
+    }
+}
+
+/// A remediation currently running, as counted against the limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveRemediation {
+    pub repository: String,
+    pub alert_type: String,
+}
+
+/// Why a guardrail refused an action.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Blocked {
+    /// The circuit breaker tripped
+    ReadOnly { reason: String },
+    /// Too many remediations running for the repository
+    RepoLimit { repository: String, limit: usize },
+    /// Too many remediations running for the alert type
+    AlertTypeLimit { alert_type: String, limit: usize },
+    /// Too many auto-merges in the last hour
+    MergeLimit { limit: usize },
+}
+
+impl std::fmt::Display for Blocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReadOnly { reason } => write!(f, "Healer is read-only: {reason}"),
+            Self::RepoLimit { repository, limit } => write!(
+                f,
+                "Repository limit reached: {limit} concurrent remediations for {repository}"
+            ),
+            Self::AlertTypeLimit { alert_type, limit } => write!(
+                f,
+                "Alert type limit reached: {limit} concurrent remediations for {alert_type}"
+            ),
+            Self::MergeLimit { limit } => {
+                write!(
+                    f,
+                    "Auto-merge limit reached: {limit} merges in the last hour"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Blocked {}
+
+/// When and why the breaker tripped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trip {
+    pub at: DateTime<Utc>,
+    pub reason: String,
+    /// When read-only mode ends on its own (`None` = reset through the API)
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// A PR healer auto-merged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MergeRecord {
+    at: DateTime<Utc>,
+    repository: String,
+    pr_number: u32,
+    #[serde(default)]
+    reverted: bool,
+    /// Reverted before a reset: stays reverted, so the revert is not
+    /// counted again, but no longer weighs on the revert rate
+    #[serde(default)]
+    forgiven: bool,
+}
+
+/// A finished remediation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OutcomeRecord {
+    at: DateTime<Utc>,
+    success: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct GuardrailState {
+    #[serde(default)]
+    outcomes: Vec<OutcomeRecord>,
+    #[serde(default)]
+    merges: Vec<MergeRecord>,
+    #[serde(default)]
+    trip: Option<Trip>,
+}
+
+/// Current guardrail state, for the status API.
+#[derive(Debug, Clone, Serialize)]
+pub struct GuardrailStatus {
+    pub read_only: bool,
+    pub trip: Option<Trip>,
+    pub failure_rate: Option<f64>,
+    pub revert_rate: Option<f64>,
+    pub outcomes_in_window: usize,
+    pub merges_in_window: usize,
+    pub merges_last_hour: usize,
+    pub config: GuardrailConfig,
+}
+
+/// Blast-radius limits and circuit breaker.
+pub struct Guardrails {
+    config: GuardrailConfig,
+    state: Mutex<GuardrailState>,
+    store: Option<StateStore>,
+    notifier: Option<notify::Notifier>,
+}
+
+impl Guardrails {
+    #[must_use]
+    pub fn new(config: GuardrailConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(GuardrailState::default()),
+            store: None,
+            notifier: None,
+        }
+    }
+
+    /// Persist breaker state in `store`.
+    #[must_use]
+    pub fn with_state_store(mut self, store: StateStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Notify through `notifier` when the breaker trips.
+    #[must_use]
+    pub fn with_notifier(mut self, notifier: notify::Notifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Reload breaker state from the state store, e.g. a trip recorded by
+    /// another process.
+    pub async fn refresh(&self) -> anyhow::Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        if let Some(state) = store.load::<GuardrailState>(STATE_KEY).await? {
+            *self.lock() = state;
+        }
+        Ok(())
+    }
+
+    /// The active trip, if healer is read-only.
+    pub fn read_only(&self) -> Option<Trip> {
+        self.read_only_at(Utc::now())
+    }
+
+    fn read_only_at(&self, now: DateTime<Utc>) -> Option<Trip> {
+        let trip = self.lock().trip.clone()?;
+        if trip.until.is_some_and(|until| until <= now) {
+            return None;
+        }
+        Some(trip)
+    }
+
+    /// Whether a new remediation for `repository` / `alert_type` may start
+    /// while `active` are running.
+    pub fn admit_spawn(
+        &self,
+        repository: &str,
+        alert_type: &str,
+        active: &[ActiveRemediation],
+    ) -> Result<(), Blocked> {
+        if let Some(trip) = self.read_only() {
+            return Err(Blocked::ReadOnly {
+                reason: trip.reason,
+            });
+        }
+        let limit = self.config.max_concurrent_per_repo;
+        if limit > 0 && active.iter().filter(|a| a.repository == repository).count() >= limit {
+            return Err(Blocked::RepoLimit {
+                repository: repository.to_string(),
+                limit,
+            });
+        }
+        let limit = self.config.max_concurrent_per_alert_type;
+        if limit > 0 && active.iter().filter(|a| a.alert_type == alert_type).count() >= limit {
+            return Err(Blocked::AlertTypeLimit {
+                alert_type: alert_type.to_string(),
+                limit,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether an auto-merge may happen now.
+    pub fn admit_merge(&self) -> Result<(), Blocked> {
+        self.admit_merge_at(Utc::now())
+    }
+
+    fn admit_merge_at(&self, now: DateTime<Utc>) -> Result<(), Blocked> {
+        if let Some(trip) = self.read_only_at(now) {
+            return Err(Blocked::ReadOnly {
+                reason: trip.reason,
+            });
+        }
+        let limit = self.config.max_auto_merges_per_hour;
+        if limit > 0 && self.merges_since(now - Duration::hours(1)) >= limit {
+            return Err(Blocked::MergeLimit { limit });
+        }
+        Ok(())
+    }
+
+    /// Record a finished remediation; may trip the breaker.
+    pub async fn record_outcome(&self, success: bool) {
+        self.update(Utc::now(), |state, at| {
+            state.outcomes.push(OutcomeRecord { at, success });
+        })
+        .await;
+    }
+
+    /// Record an auto-merge.
+    pub async fn record_merge(&self, repository: &str, pr_number: u32) {
+        self.update(Utc::now(), |state, at| {
+            state.merges.push(MergeRecord {
+                at,
+                repository: repository.to_string(),
+                pr_number,
+                reverted: false,
+                forgiven: false,
+            });
+        })
+        .await;
+    }
+
+    /// Record that PR `pr_number` was reverted; may trip the breaker.
+    /// Returns whether it was a PR healer auto-merged (and not already
+    /// counted).
+    pub async fn record_revert(&self, repository: &str, pr_number: u32) -> bool {
+        let mut counted = false;
+        self.update(Utc::now(), |state, _| {
+            counted = false;
+            if let Some(merge) = state
+                .merges
+                .iter_mut()
+                .find(|m| m.repository == repository && m.pr_number == pr_number && !m.reverted)
+            {
+                merge.reverted = true;
+                counted = true;
+            }
+        })
+        .await;
+        if counted {
+            warn!("Auto-merged PR {repository}#{pr_number} was reverted");
+        }
+        counted
+    }
+
+    /// Repositories with auto-merged PRs in the window not yet seen
+    /// reverted, with the oldest such merge: reverts landing after it still
+    /// count against the breaker.
+    pub fn unreverted_merges(&self) -> BTreeMap<String, DateTime<Utc>> {
+        let mut oldest = BTreeMap::new();
+        for merge in self.lock().merges.iter().filter(|m| !m.reverted) {
+            oldest
+                .entry(merge.repository.clone())
+                .and_modify(|at: &mut DateTime<Utc>| *at = (*at).min(merge.at))
+                .or_insert(merge.at);
+        }
+        oldest
+    }
+
+    /// Record reverts of auto-merged PRs landed on the default branch of
+    /// every repository healer merged into since its oldest unreverted
+    /// merge. Returns how many were counted.
+    pub async fn scan_reverts(&self, github: &dyn GitHubClient) -> usize {
+        let mut counted = 0;
+        for (repository, since) in self.unreverted_merges() {
+            let commits = match github
+                .list_commits_since(&repository, since, MAX_SCANNED_COMMITS)
+                .await
+            {
+                Ok(commits) => commits,
+                Err(e) => {
+                    warn!("Failed to check {repository} for reverts: {e:#}");
+                    continue;
+                }
+            };
+            for pr_number in commits.iter().flat_map(|c| reverted_pr_numbers(&c.message)) {
+                if self.record_revert(&repository, pr_number).await {
+                    counted += 1;
+                }
+            }
+        }
+        counted
+    }
+
+    /// Leave read-only mode and forget the outcomes and reverts that
+    /// tripped it. Reverted merges stay marked so the revert scan does not
+    /// count the same reverts again.
+    pub async fn reset(&self) {
+        self.update(Utc::now(), |state, _| {
+            state.trip = None;
+            state.outcomes.clear();
+            state
+                .merges
+                .iter_mut()
+                .filter(|m| m.reverted)
+                .for_each(|m| m.forgiven = true);
+        })
+        .await;
+        info!("Guardrail circuit breaker reset; remediation resumed");
+    }
+
+    pub fn status(&self) -> GuardrailStatus {
+        let now = Utc::now();
+        let trip = self.read_only_at(now);
+        let mut state = self.lock().clone();
+        self.prune(&mut state, now);
+        GuardrailStatus {
+            read_only: trip.is_some(),
+            trip,
+            failure_rate: failure_rate(&state),
+            revert_rate: revert_rate(&state),
+            outcomes_in_window: state.outcomes.len(),
+            merges_in_window: state.merges.len(),
+            merges_last_hour: self.merges_since(now - Duration::hours(1)),
+            config: self.config.clone(),
+        }
+    }
+
+    fn merges_since(&self, since: DateTime<Utc>) -> usize {
+        self.lock().merges.iter().filter(|m| m.at > since).count()
+    }
+
+    /// Apply `change`, prune, re-evaluate the breaker and persist. With a
+    /// state store, `change` is applied to the stored state inside its
+    /// read-modify-write, so other replicas' records are kept.
+    async fn update(
+        &self,
+        now: DateTime<Utc>,
+        mut change: impl FnMut(&mut GuardrailState, DateTime<Utc>),
+    ) {
+        let mut tripped = None;
+        let mut apply = |state: &mut GuardrailState| {
+            change(state, now);
+            tripped = self.settle(state, now);
+        };
+        let stored = match &self.store {
+            Some(store) => match store.modify(STATE_KEY, &mut apply).await {
+                Ok(state) => Some(state),
+                Err(e) => {
+                    warn!("Failed to persist guardrail state: {e:#}");
+                    None
+                }
+            },
+            None => None,
+        };
+        let state = stored.unwrap_or_else(|| {
+            let mut state = self.lock().clone();
+            apply(&mut state);
+            state
+        });
+        *self.lock() = state;
+        if let Some(trip) = tripped {
+            self.notify_trip(&trip);
+        }
+    }
+
+    /// Prune `state` and re-evaluate the breaker; returns a new trip.
+    fn settle(&self, state: &mut GuardrailState, now: DateTime<Utc>) -> Option<Trip> {
+        self.prune(state, now);
+        // An expired trip is cleared so the breaker can trip again
+        if state
+            .trip
+            .as_ref()
+            .is_some_and(|t| t.until.is_some_and(|u| u <= now))
+        {
+            state.trip = None;
+        }
+        if state.trip.is_some() {
+            return None;
+        }
+        let trip = self.evaluate(state).map(|reason| Trip {
+            at: now,
+            reason,
+            until: (self.config.reset_after_mins > 0)
+                .then(|| now + Duration::minutes(i64::from(self.config.reset_after_mins))),
+        })?;
+        state.trip = Some(trip.clone());
+        Some(trip)
+    }
+
+    /// Trip reason if a rate is over its threshold.
+    fn evaluate(&self, state: &GuardrailState) -> Option<String> {
+        let min_samples = self.config.min_samples.max(1);
+        if state.outcomes.len() >= min_samples {
+            let rate = failure_rate(state)?;
+            if rate >= self.config.failure_rate_threshold {
+                return Some(format!(
+                    "{:.0}% of the last {} remediations failed (threshold {:.0}%)",
+                    rate * 100.0,
+                    state.outcomes.len(),
+                    self.config.failure_rate_threshold * 100.0
+                ));
+            }
+        }
+        let rated = rated_merges(state).count();
+        if rated >= min_samples {
+            let rate = revert_rate(state)?;
+            if rate >= self.config.revert_rate_threshold {
+                return Some(format!(
+                    "{:.0}% of the last {} auto-merged PRs were reverted (threshold {:.0}%)",
+                    rate * 100.0,
+                    rated,
+                    self.config.revert_rate_threshold * 100.0
+                ));
+            }
+        }
+        None
+    }
+
+    fn prune(&self, state: &mut GuardrailState, now: DateTime<Utc>) {
+        let cutoff = now - Duration::hours(i64::from(self.config.rate_window_hours));
+        state.outcomes.retain(|o| o.at > cutoff);
+        state.merges.retain(|m| m.at > cutoff);
+    }
+
+    fn notify_trip(&self, trip: &Trip) {
+        error!(
+            "Guardrail circuit breaker tripped, healer is read-only: {}",
+            trip.reason
+        );
+        if let Some(notifier) = &self.notifier {
+            notifier.notify(notify::NotifyEvent::HealAlert {
+                alert_id: "circuit-breaker".to_string(),
+                severity: notify::Severity::Critical,
+                message: format!(
+                    "Healer remediation circuit breaker tripped; no CodeRuns will be spawned \
+                     or PRs merged until it is reset. {}",
+                    trip.reason
+                ),
+                context: HashMap::new(),
+                timestamp: trip.at,
+            });
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, GuardrailState> {
+        self.state.lock().expect("guardrail lock poisoned")
+    }
+}
+
+#[allow(clippy::cast_precision_loss)] // Window counts are small
+fn failure_rate(state: &GuardrailState) -> Option<f64> {
+    if state.outcomes.is_empty() {
+        return None;
+    }
+    let failures = state.outcomes.iter().filter(|o| !o.success).count();
+    Some(failures as f64 / state.outcomes.len() as f64)
+}
+
+#[allow(clippy::cast_precision_loss)] // Window counts are small
+fn revert_rate(state: &GuardrailState) -> Option<f64> {
+    let rated = rated_merges(state).count();
+    if rated == 0 {
+        return None;
+    }
+    let reverted = rated_merges(state).filter(|m| m.reverted).count();
+    Some(reverted as f64 / rated as f64)
+}
+
+/// Merges the revert rate is computed over (all but those forgiven by a reset).
+fn rated_merges(state: &GuardrailState) -> impl Iterator<Item = &MergeRecord> {
+    state.merges.iter().filter(|m| !m.forgiven)
+}
+
+/// PR numbers a GitHub revert commit undoes, e.g. `4242` for
+/// `Revert "Fix clippy lints (#4242)" (#4250)`. Empty for other commits.
+pub fn reverted_pr_numbers(commit_message: &str) -> Vec<u32> {
+    let Some(rest) = commit_message.trim_start().strip_prefix("Revert \"") else {
+        return Vec::new();
+    };
+    let subject = rest.lines().next().unwrap_or_default();
+    let reverted = subject.rsplit_once('"').map_or(subject, |(inner, _)| inner);
+    reverted
+        .split("(#")
+        .skip(1)
+        .filter_map(|part| part.split_once(')')?.0.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn active(repository: &str, alert_type: &str) -> ActiveRemediation {
+        ActiveRemediation {
+            repository: repository.to_string(),
+            alert_type: alert_type.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_concurrency_limits() {
+        let guardrails = Guardrails::new(GuardrailConfig::default());
+        let running = [active("5dlabs/cto", "clippy"), active("5dlabs/cto", "helm")];
+        assert_eq!(
+            guardrails.admit_spawn("5dlabs/cto", "rust-test", &running),
+            Err(Blocked::RepoLimit {
+                repository: "5dlabs/cto".to_string(),
+                limit: 2
+            })
+        );
+        assert!(guardrails
+            .admit_spawn("5dlabs/web", "rust-test", &running)
+            .is_ok());
+
+        let running = [
+            active("a/one", "clippy"),
+            active("a/two", "clippy"),
+            active("a/three", "clippy"),
+        ];
+        assert!(matches!(
+            guardrails.admit_spawn("a/four", "clippy", &running),
+            Err(Blocked::AlertTypeLimit { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_merge_rate_limit() {
+        let guardrails = Guardrails::new(GuardrailConfig::default());
+        for pr in 1..=3 {
+            assert!(guardrails.admit_merge().is_ok());
+            guardrails.record_merge("5dlabs/cto", pr).await;
+        }
+        assert_eq!(
+            guardrails.admit_merge(),
+            Err(Blocked::MergeLimit { limit: 3 })
+        );
+        let later = Utc::now() + Duration::minutes(61);
+        assert!(guardrails.admit_merge_at(later).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_failure_rate_trips_read_only_until_reset() {
+        let guardrails = Guardrails::new(GuardrailConfig::default());
+        guardrails.record_outcome(true).await;
+        for _ in 0..3 {
+            guardrails.record_outcome(false).await;
+        }
+        // Below min_samples: no verdict yet
+        assert!(guardrails.read_only().is_none());
+
+        guardrails.record_outcome(false).await;
+        let trip = guardrails.read_only().expect("breaker should trip");
+        assert!(trip.reason.contains("80%"));
+        assert!(matches!(
+            guardrails.admit_spawn("5dlabs/cto", "clippy", &[]),
+            Err(Blocked::ReadOnly { .. })
+        ));
+        assert!(guardrails.admit_merge().is_err());
+
+        guardrails.reset().await;
+        assert!(guardrails.admit_spawn("5dlabs/cto", "clippy", &[]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reverts_trip_and_auto_reset() {
+        let config = GuardrailConfig {
+            max_auto_merges_per_hour: 0,
+            reset_after_mins: 30,
+            ..GuardrailConfig::default()
+        };
+        let guardrails = Guardrails::new(config);
+        for pr in 1..=5 {
+            guardrails.record_merge("5dlabs/cto", pr).await;
+        }
+        assert!(!guardrails.record_revert("5dlabs/cto", 99).await);
+        assert!(guardrails.record_revert("5dlabs/cto", 2).await);
+        assert!(!guardrails.record_revert("5dlabs/cto", 2).await);
+
+        let trip = guardrails.read_only().expect("breaker should trip");
+        assert!(trip.reason.contains("reverted"));
+        let later = trip.at + Duration::minutes(31);
+        assert!(guardrails.read_only_at(later).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reset_does_not_recount_reverts() {
+        let config = GuardrailConfig {
+            max_auto_merges_per_hour: 0,
+            ..GuardrailConfig::default()
+        };
+        let guardrails = Guardrails::new(config);
+        for pr in 1..=5 {
+            guardrails.record_merge("5dlabs/cto", pr).await;
+        }
+        guardrails.record_revert("5dlabs/cto", 2).await;
+        assert!(guardrails.read_only().is_some());
+
+        guardrails.reset().await;
+        // The revert scan finds the same revert commit again
+        assert!(!guardrails.record_revert("5dlabs/cto", 2).await);
+        assert!(guardrails.read_only().is_none());
+        assert!(guardrails.admit_merge().is_ok());
+        assert_eq!(guardrails.status().revert_rate, Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_scan_reverts_from_default_branch_commits() {
+        use crate::clients::{Commit, FakeGitHub};
+
+        let config = GuardrailConfig {
+            max_auto_merges_per_hour: 0,
+            ..GuardrailConfig::default()
+        };
+        let guardrails = Guardrails::new(config);
+        for pr in 1..=5 {
+            guardrails.record_merge("5dlabs/cto", pr).await;
+        }
+        let commit = |sha: &str, message: &str| Commit {
+            sha: sha.to_string(),
+            message: message.to_string(),
+            committed_at: Some(Utc::now()),
+            ..Commit::default()
+        };
+        let github = FakeGitHub::new()
+            .with_commit("5dlabs/cto", commit("a1", "Fix clippy lints (#3)"))
+            .with_commit(
+                "5dlabs/cto",
+                commit("b2", "Revert \"Fix clippy lints (#3)\" (#9)"),
+            )
+            .with_commit("5dlabs/cto", commit("c3", "Revert \"Manual change (#77)\""))
+            .with_commit("5dlabs/web", commit("d4", "Revert \"Fix lints (#4)\""));
+
+        assert_eq!(guardrails.scan_reverts(&github).await, 1);
+        assert!(guardrails.read_only().is_some());
+        // The same commits are seen again on the next scan
+        assert_eq!(guardrails.scan_reverts(&github).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_unreverted_merges_per_repository() {
+        let guardrails = Guardrails::new(GuardrailConfig::default());
+        guardrails.record_merge("5dlabs/cto", 1).await;
+        guardrails.record_merge("5dlabs/cto", 2).await;
+        guardrails.record_merge("5dlabs/web", 3).await;
+        let first = guardrails.unreverted_merges()["5dlabs/cto"];
+
+        guardrails.record_revert("5dlabs/web", 3).await;
+        let pending = guardrails.unreverted_merges();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending["5dlabs/cto"], first);
+    }
+
+    #[tokio::test]
+    async fn test_trip_shared_through_state_store() {
+        let cluster = std::sync::Arc::new(crate::clients::FakeCluster::new());
+        let store = StateStore::new(cluster, "cto", "healer-state");
+        let config = GuardrailConfig {
+            min_samples: 1,
+            ..GuardrailConfig::default()
+        };
+        let server = Guardrails::new(config.clone()).with_state_store(store.clone());
+        server.record_outcome(false).await;
+        assert!(server.read_only().is_some());
+
+        let watch = Guardrails::new(config).with_state_store(store);
+        watch.refresh().await.unwrap();
+        assert!(watch.read_only().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_replicas_keep_each_others_records() {
+        let cluster = std::sync::Arc::new(crate::clients::FakeCluster::new());
+        let store = StateStore::new(cluster, "cto", "healer-state");
+        let config = GuardrailConfig {
+            min_samples: 2,
+            ..GuardrailConfig::default()
+        };
+        let first = Guardrails::new(config.clone()).with_state_store(store.clone());
+        let second = Guardrails::new(config).with_state_store(store);
+
+        // Neither replica refreshes; the second still sees the first's failure
+        first.record_outcome(false).await;
+        assert!(first.read_only().is_none());
+        second.record_outcome(false).await;
+        assert!(second.read_only().is_some());
+        assert_eq!(second.status().outcomes_in_window, 2);
+    }
+
+    #[test]
+    fn test_reverted_pr_numbers() {
+        assert_eq!(
+            reverted_pr_numbers("Revert \"Fix clippy lints (#4242)\" (#4250)"),
+            vec![4242]
+        );
+        assert_eq!(
+            reverted_pr_numbers("Revert \"Fix clippy lints (#4242)\"\n\nThis reverts commit abc."),
+            vec![4242]
+        );
+        assert!(reverted_pr_numbers("Fix clippy lints (#4242)").is_empty());
+    }
+}
@@ -4,7 +4,9 @@
 //! - Monitoring PR status after successful remediation
 //! - Auto-merging PRs when checks pass
 //! - Detecting and routing merge conflicts
+//! - Respecting the hourly auto-merge limit and read-only mode
 
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
@@ -12,6 +14,7 @@ use tracing::{debug, error, info, warn};
 
 use crate::github::{GitHubClient, MergeMethod, MergeStatus};
 
+use super::guardrails::Guardrails;
 use super::tracker::{CompletionAction, TrackedRemediation};
 use super::types::RemediationConfig;
 
@@ -24,13 +27,26 @@ pub struct AutoMergeHandler {
     github: GitHubClient,
     /// Configuration
     config: RemediationConfig,
+    /// Auto-merge rate limit and read-only breaker (if configured)
+    guardrails: Option<Arc<Guardrails>>,
 }
 
 impl AutoMergeHandler {
     /// Create a new auto-merge handler.
     #[must_use]
     pub fn new(github: GitHubClient, config: RemediationConfig) -> Self {
-        Self { github, config }
+        Self {
+            github,
+            config,
+            guardrails: None,
+        }
+    }
+
+    /// Check `guardrails` before merging.
+    #[must_use]
+    pub fn with_guardrails(mut self, guardrails: Arc<Guardrails>) -> Self {
+        self.guardrails = Some(guardrails);
+        self
     }
 
     /// Handle a successful remediation by attempting to merge the PR.
@@ -111,6 +127,14 @@ impl AutoMergeHandler {
     fn attempt_merge(&self, pr_number: u32) -> Result<CompletionAction> {
         let method = parse_merge_method(&self.config.merge_method);
 
+        // Leave the PR for a human rather than exceed the blast radius
+        if let Some(guardrails) = &self.guardrails {
+            if let Err(blocked) = guardrails.admit_merge() {
+                warn!(pr = pr_number, "Not auto-merging: {blocked}");
+                return Ok(CompletionAction::ReadyToMerge { pr_number });
+            }
+        }
+
         info!(
             pr = pr_number,
             method = ?method,
@@ -2,30 +2,46 @@
 //!
 //! This module provides the central CI remediation hub functionality:
 //! - Receives CI failure events from webhooks/sensors
-//! - Classifies failure types using workflow logs and changed files
+//! - Parses CI output into structured failures and classifies them
+//! - Quarantines known-flaky test failures instead of dispatching agents
 //! - Routes to specialist agents (Rex, Blaze, Bolt, Cipher, Atlas)
+//! - Learns which agents fix which failures from a local outcome store
 //! - Tracks remediation attempts and implements retry logic
 //! - Escalates to humans after max attempts
 //! - Stores outcomes to `OpenMemory` for learning
 //! - Auto-merges PRs after successful remediation
+//! - Limits blast radius and goes read-only when remediation misbehaves
+//! - Measures detection latency, MTTR and fix success rates
 
+pub mod adaptive;
 pub mod context;
 pub mod escalate;
+pub mod flaky;
+pub mod guardrails;
 pub mod memory;
 pub mod merge;
+pub mod outcomes;
+pub mod parsers;
 pub mod router;
 pub mod server;
+pub mod slo;
 pub mod spawner;
 pub mod tracker;
 pub mod types;
 
 // Re-export primary types
+pub use adaptive::{AdaptiveRouter, AdaptiveRoutingConfig};
 pub use context::ContextGatherer;
 pub use escalate::Escalator;
+pub use flaky::{FlakeTracker, FlakyTestConfig, Quarantiner};
+pub use guardrails::{GuardrailConfig, Guardrails};
 pub use memory::{MemoryClient, MemoryConfig};
 pub use merge::AutoMergeHandler;
+pub use outcomes::{OutcomeRecord, OutcomeStore};
+pub use parsers::{FailureClassification, FailureItem, FailureSource};
 pub use router::CiRouter;
 pub use server::{build_router, run_server, ServerState};
+pub use slo::SloMetrics;
 pub use spawner::CodeRunSpawner;
 pub use tracker::{CompletionAction, RemediationTracker, TrackedRemediation};
 pub use types::{
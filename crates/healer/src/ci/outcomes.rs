@@ -0,0 +1,389 @@
+//! Local remediation outcome store.
+//!
+//! Every finished remediation attempt is recorded in an embedded SQLite
+//! database with its failure signature, failure type, agent, attempt number
+//! and result. Unlike the `OpenMemory` integration in [`super::memory`] this
+//! needs no external service, and it is what [`super::adaptive`] learns
+//! agent routing from.
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, Row};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use super::parsers::FailureItem;
+use super::types::{Agent, AttemptOutcome, CiFailure, CiFailureType};
+
+/// Rules or tests included in a failure signature.
+const SIGNATURE_ITEMS: usize = 5;
+
+/// Schema migrations, applied in order; `PRAGMA user_version` records how
+/// many have run.
+const MIGRATIONS: &[&str] = &["CREATE TABLE outcomes (
+        id              INTEGER PRIMARY KEY AUTOINCREMENT,
+        recorded_at     TEXT NOT NULL,
+        repository      TEXT NOT NULL,
+        workflow_run_id INTEGER NOT NULL,
+        signature       TEXT NOT NULL,
+        failure_type    TEXT NOT NULL,
+        agent           TEXT NOT NULL,
+        attempt         INTEGER NOT NULL,
+        outcome         TEXT NOT NULL,
+        UNIQUE (workflow_run_id, attempt)
+    );
+    CREATE INDEX outcomes_failure_type ON outcomes (failure_type);
+    CREATE INDEX outcomes_signature ON outcomes (signature);"];
+
+/// One finished remediation attempt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutcomeRecord {
+    /// When the attempt finished
+    pub recorded_at: DateTime<Utc>,
+    /// Repository full name
+    pub repository: String,
+    /// Workflow run being remediated
+    pub workflow_run_id: u64,
+    /// Failure signature (see [`failure_signature`])
+    pub signature: String,
+    /// Classified failure type
+    pub failure_type: CiFailureType,
+    /// Agent that made the attempt
+    pub agent: Agent,
+    /// Attempt number (1-indexed)
+    pub attempt: u32,
+    /// Result of the attempt
+    pub outcome: AttemptOutcome,
+}
+
+/// Success and failure counts for one agent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AgentStats {
+    /// Attempts that fixed CI
+    pub successes: u32,
+    /// Attempts that did not
+    pub failures: u32,
+}
+
+impl AgentStats {
+    /// Total attempts with a known result.
+    #[must_use]
+    pub fn attempts(&self) -> u32 {
+        self.successes + self.failures
+    }
+}
+
+/// Signature identifying a class of failure: the workflow and job, plus the
+/// most common rules (or test IDs) among its parsed failures.
+///
+/// Two runs failing on the same lint in the same job share a signature even
+/// if the files and line numbers differ.
+#[must_use]
+pub fn failure_signature(failure: &CiFailure, items: &[FailureItem]) -> String {
+    let mut signature = failure.workflow_name.clone();
+    if let Some(job) = &failure.job_name {
+        signature.push('/');
+        signature.push_str(job);
+    }
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for key in items
+        .iter()
+        .filter_map(|i| i.rule.as_deref().or(i.test_name.as_deref()))
+    {
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut keys: Vec<(&str, usize)> = counts.into_iter().collect();
+    keys.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let keys: BTreeSet<&str> = keys
+        .into_iter()
+        .take(SIGNATURE_ITEMS)
+        .map(|(k, _)| k)
+        .collect();
+
+    if !keys.is_empty() {
+        signature.push(':');
+        signature.push_str(&keys.into_iter().collect::<Vec<_>>().join(","));
+    }
+    signature
+}
+
+/// Remediation outcomes kept in a SQLite database.
+#[derive(Debug)]
+pub struct OutcomeStore {
+    conn: Mutex<Connection>,
+}
+
+impl OutcomeStore {
+    /// Open (or create) a store file and bring its schema up to date.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open outcome store {}", path.display()))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        Self::init(conn)
+    }
+
+    /// Create a throwaway in-memory store.
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self> {
+        conn.busy_timeout(Duration::from_secs(5))?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record an attempt. Recording the same attempt of a run twice keeps
+    /// the latest result.
+    pub fn record(&self, record: &OutcomeRecord) -> Result<()> {
+        self.lock().execute(
+            "INSERT INTO outcomes (recorded_at, repository, workflow_run_id, signature,
+                 failure_type, agent, attempt, outcome)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT (workflow_run_id, attempt) DO UPDATE SET
+                 recorded_at = excluded.recorded_at,
+                 agent = excluded.agent,
+                 outcome = excluded.outcome",
+            params![
+                record.recorded_at.to_rfc3339(),
+                record.repository,
+                record.workflow_run_id as i64,
+                record.signature,
+                to_sql(&record.failure_type)?,
+                to_sql(&record.agent)?,
+                record.attempt,
+                to_sql(&record.outcome)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Per-agent results for a failure type. Escalations are not counted:
+    /// they say nothing about whether the agent could have fixed it.
+    pub fn agent_stats(&self, failure_type: &CiFailureType) -> Result<HashMap<Agent, AgentStats>> {
+        let conn = self.lock();
+        let mut stmt = conn.prepare(
+            "SELECT agent, outcome, COUNT(*) FROM outcomes
+             WHERE failure_type = ?1 GROUP BY agent, outcome",
+        )?;
+        let rows = stmt.query_map(params![to_sql(failure_type)?], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, u32>(2)?,
+            ))
+        })?;
+
+        let mut stats: HashMap<Agent, AgentStats> = HashMap::new();
+        for row in rows {
+            let (agent, outcome, count) = row?;
+            let agent: Agent = from_sql(&agent)?;
+            let entry = stats.entry(agent).or_default();
+            match from_sql::<AttemptOutcome>(&outcome)? {
+                AttemptOutcome::Success => entry.successes += count,
+                AttemptOutcome::AgentFailed
+                | AttemptOutcome::CiStillFailing
+                | AttemptOutcome::Timeout => entry.failures += count,
+                AttemptOutcome::Escalated => {}
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Most recent attempts for a signature, newest first.
+    pub fn by_signature(&self, signature: &str, limit: usize) -> Result<Vec<OutcomeRecord>> {
+        let conn = self.lock();
+        let mut stmt = conn.prepare(
+            "SELECT recorded_at, repository, workflow_run_id, signature, failure_type, agent,
+                 attempt, outcome
+             FROM outcomes WHERE signature = ?1 ORDER BY recorded_at DESC, id DESC LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![signature, limit as i64], |row| Ok(read_record(row)))?;
+        rows.map(|row| row?).collect()
+    }
+}
+
+fn read_record(row: &Row<'_>) -> Result<OutcomeRecord> {
+    let recorded_at: String = row.get(0)?;
+    let workflow_run_id: i64 = row.get(2)?;
+    Ok(OutcomeRecord {
+        recorded_at: DateTime::parse_from_rfc3339(&recorded_at)?.with_timezone(&Utc),
+        repository: row.get(1)?,
+        workflow_run_id: workflow_run_id as u64,
+        signature: row.get(3)?,
+        failure_type: from_sql(&row.get::<_, String>(4)?)?,
+        agent: from_sql(&row.get::<_, String>(5)?)?,
+        attempt: row.get(6)?,
+        outcome: from_sql(&row.get::<_, String>(7)?)?,
+    })
+}
+
+/// Store an enum as its serde name.
+fn to_sql<T: Serialize>(value: &T) -> Result<String> {
+    match serde_json::to_value(value)? {
+        serde_json::Value::String(s) => Ok(s),
+        other => anyhow::bail!("Expected a string, got {other}"),
+    }
+}
+
+fn from_sql<T: DeserializeOwned>(value: &str) -> Result<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .with_context(|| format!("Unknown value in outcome store: {value}"))
+}
+
+/// Latest schema version this build knows.
+#[allow(clippy::cast_possible_wrap)] // A handful of migrations
+const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let current: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if current > SCHEMA_VERSION {
+        anyhow::bail!(
+            "Outcome store schema version {current} is newer than supported version {SCHEMA_VERSION}"
+        );
+    }
+    for (version, sql) in (1..).zip(MIGRATIONS).skip_while(|(v, _)| *v <= current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+        tracing::debug!("Migrated outcome store to schema version {version}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ci::parsers::parse_ci_output;
+
+    fn failure(job: &str) -> CiFailure {
+        CiFailure {
+            workflow_run_id: 1,
+            workflow_name: "Controller CI".to_string(),
+            job_name: Some(job.to_string()),
+            conclusion: "failure".to_string(),
+            branch: "main".to_string(),
+            head_sha: "abc".to_string(),
+            commit_message: "test".to_string(),
+            html_url: "https://github.com".to_string(),
+            repository: "5dlabs/cto".to_string(),
+            sender: "developer".to_string(),
+            detected_at: Utc::now(),
+            raw_event: None,
+        }
+    }
+
+    fn record(run: u64, attempt: u32, agent: Agent, outcome: AttemptOutcome) -> OutcomeRecord {
+        OutcomeRecord {
+            recorded_at: Utc::now(),
+            repository: "5dlabs/cto".to_string(),
+            workflow_run_id: run,
+            signature: "Controller CI/clippy:E0425".to_string(),
+            failure_type: CiFailureType::RustBuild,
+            agent,
+            attempt,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn test_signature_ignores_locations() {
+        let a = parse_ci_output(
+            "error[E0425]: cannot find value `x` in this scope\n  --> src/main.rs:4:13\n",
+        );
+        let b = parse_ci_output(
+            "error[E0425]: cannot find value `y` in this scope\n  --> src/lib.rs:90:1\n",
+        );
+        assert_eq!(
+            failure_signature(&failure("build"), &a),
+            failure_signature(&failure("build"), &b)
+        );
+        assert_eq!(
+            failure_signature(&failure("build"), &a),
+            "Controller CI/build:E0425"
+        );
+        assert_eq!(
+            failure_signature(&failure("lint"), &[]),
+            "Controller CI/lint"
+        );
+    }
+
+    #[test]
+    fn test_agent_stats_by_failure_type() {
+        let store = OutcomeStore::in_memory().unwrap();
+        store
+            .record(&record(1, 1, Agent::Rex, AttemptOutcome::CiStillFailing))
+            .unwrap();
+        store
+            .record(&record(1, 2, Agent::Atlas, AttemptOutcome::Success))
+            .unwrap();
+        store
+            .record(&record(2, 1, Agent::Rex, AttemptOutcome::Success))
+            .unwrap();
+        store
+            .record(&record(3, 1, Agent::Rex, AttemptOutcome::Escalated))
+            .unwrap();
+
+        let stats = store.agent_stats(&CiFailureType::RustBuild).unwrap();
+        assert_eq!(
+            stats[&Agent::Rex],
+            AgentStats {
+                successes: 1,
+                failures: 1
+            }
+        );
+        assert_eq!(stats[&Agent::Atlas].successes, 1);
+        assert!(store
+            .agent_stats(&CiFailureType::FrontendLint)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rerecording_an_attempt_keeps_latest_result() {
+        let store = OutcomeStore::in_memory().unwrap();
+        store
+            .record(&record(1, 1, Agent::Rex, AttemptOutcome::AgentFailed))
+            .unwrap();
+        store
+            .record(&record(1, 1, Agent::Rex, AttemptOutcome::Success))
+            .unwrap();
+
+        let records = store
+            .by_signature("Controller CI/clippy:E0425", 10)
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].outcome, AttemptOutcome::Success);
+        assert_eq!(records[0].failure_type, CiFailureType::RustBuild);
+    }
+
+    #[test]
+    fn test_store_persists_to_disk() {
+        let dir = std::env::temp_dir().join(format!("healer-outcomes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("outcomes.db");
+
+        OutcomeStore::open(&path)
+            .unwrap()
+            .record(&record(1, 1, Agent::Bolt, AttemptOutcome::Success))
+            .unwrap();
+        let stats = OutcomeStore::open(&path)
+            .unwrap()
+            .agent_stats(&CiFailureType::RustBuild)
+            .unwrap();
+        assert_eq!(stats[&Agent::Bolt].successes, 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,75 @@
+//! `ESLint` JSON output (`eslint -f json`).
+
+use serde::Deserialize;
+
+use super::{FailureItem, FailureSource};
+
+/// `ESLint` severity for errors (1 is a warning).
+const SEVERITY_ERROR: u8 = 2;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileResult {
+    file_path: String,
+    #[serde(default)]
+    messages: Vec<Message>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Message {
+    rule_id: Option<String>,
+    severity: u8,
+    message: String,
+    line: Option<u32>,
+    column: Option<u32>,
+}
+
+/// Parse error-severity messages from every JSON result array in the logs.
+pub(super) fn parse(logs: &str) -> Vec<FailureItem> {
+    logs.lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("[{") && line.contains("\"filePath\""))
+        .filter_map(|line| serde_json::from_str::<Vec<FileResult>>(line).ok())
+        .flatten()
+        .flat_map(|file| {
+            let path = relative_path(&file.file_path);
+            file.messages
+                .into_iter()
+                .filter(|m| m.severity >= SEVERITY_ERROR)
+                .map(move |m| {
+                    let mut item = FailureItem::new(FailureSource::Eslint, m.message);
+                    item.file = Some(path.clone());
+                    item.line = m.line;
+                    item.column = m.column;
+                    item.rule = m.rule_id;
+                    item
+                })
+        })
+        .collect()
+}
+
+/// `ESLint` reports absolute paths; strip the runner's checkout prefix
+/// (`/home/runner/work/<repo>/<repo>/`) so paths match the repository.
+fn relative_path(path: &str) -> String {
+    path.strip_prefix("/home/runner/work/")
+        .and_then(|p| p.splitn(3, '/').nth(2))
+        .unwrap_or(path)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_eslint_json() {
+        let logs = r#"> web@1.0.0 lint
+[{"filePath":"/home/runner/work/cto/cto/web/src/App.tsx","messages":[{"ruleId":"no-unused-vars","severity":2,"message":"'x' is defined but never used.","line":3,"column":7},{"ruleId":"no-console","severity":1,"message":"Unexpected console statement.","line":9,"column":3}],"errorCount":1,"warningCount":1},{"filePath":"/repo/web/src/ok.ts","messages":[],"errorCount":0}]"#;
+        let items = parse(logs);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].rule.as_deref(), Some("no-unused-vars"));
+        assert_eq!(items[0].location().as_deref(), Some("web/src/App.tsx:3"));
+        assert_eq!(items[0].column, Some(7));
+    }
+}
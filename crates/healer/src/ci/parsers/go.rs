@@ -0,0 +1,92 @@
+//! `go test -json` output.
+
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use super::{parse_num, FailureItem, FailureSource};
+
+/// `    foo_test.go:12: expected 1, got 2`
+static GO_LOCATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s+([\w./-]+\.go):(\d+): (.+)$").unwrap());
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TestEvent {
+    action: String,
+    package: Option<String>,
+    test: Option<String>,
+    output: Option<String>,
+}
+
+/// Parse failed tests, taking the first `file.go:line:` output as the cause.
+pub(super) fn parse(logs: &str) -> Vec<FailureItem> {
+    let mut outputs: HashMap<(String, String), Vec<String>> = HashMap::new();
+    let mut items = Vec::new();
+
+    let events = logs
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('{') && line.contains("\"Action\""))
+        .filter_map(|line| serde_json::from_str::<TestEvent>(line).ok());
+
+    for event in events {
+        let (Some(package), Some(test)) = (event.package, event.test) else {
+            continue;
+        };
+        let key = (package, test);
+        match event.action.as_str() {
+            "output" => {
+                if let Some(output) = event.output {
+                    outputs.entry(key).or_default().push(output);
+                }
+            }
+            "fail" => {
+                let cause = outputs
+                    .get(&key)
+                    .into_iter()
+                    .flatten()
+                    .find_map(|line| GO_LOCATION.captures(line.trim_end()));
+                let (package, test) = key;
+                let mut item = FailureItem::new(FailureSource::GoTest, "test failed");
+                if let Some(caps) = cause {
+                    item.file = Some(format!("{package}/{}", &caps[1]));
+                    item.line = parse_num(caps.get(2));
+                    item.message = caps[3].to_string();
+                }
+                item.test_name = Some(test);
+                items.push(item);
+            }
+            _ => {}
+        }
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_go_test_json() {
+        let logs = r#"{"Action":"run","Package":"example.com/api","Test":"TestLogin"}
+{"Action":"output","Package":"example.com/api","Test":"TestLogin","Output":"=== RUN   TestLogin\n"}
+{"Action":"output","Package":"example.com/api","Test":"TestLogin","Output":"    login_test.go:21: expected status 200, got 401\n"}
+{"Action":"fail","Package":"example.com/api","Test":"TestLogin","Elapsed":0.01}
+{"Action":"pass","Package":"example.com/api","Test":"TestHealth","Elapsed":0}
+{"Action":"fail","Package":"example.com/api","Test":"TestPanics","Elapsed":0}
+{"Action":"fail","Package":"example.com/api","Elapsed":0.02}"#;
+        let items = parse(logs);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].test_name.as_deref(), Some("TestLogin"));
+        assert_eq!(
+            items[0].location().as_deref(),
+            Some("example.com/api/login_test.go:21")
+        );
+        assert_eq!(items[0].message, "expected status 200, got 401");
+        assert_eq!(items[1].test_name.as_deref(), Some("TestPanics"));
+        assert_eq!(items[1].message, "test failed");
+    }
+}
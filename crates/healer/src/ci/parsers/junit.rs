@@ -0,0 +1,184 @@
+//! JUnit XML reports (jest-junit, nextest, pytest `--junitxml`, surefire, ...).
+
+use quick_xml::escape::resolve_xml_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{FailureItem, FailureSource};
+
+/// Extract every `<testsuites>`/`<testsuite>` document from the logs and
+/// parse failing test cases.
+pub(super) fn parse(logs: &str) -> Vec<FailureItem> {
+    let mut items = Vec::new();
+    let mut rest = logs;
+
+    while let Some(start) = rest.find("<testsuite") {
+        let report = &rest[start..];
+        let (close, end) = if report.starts_with("<testsuites") {
+            ("</testsuites>", report.find("</testsuites>"))
+        } else {
+            ("</testsuite>", report.find("</testsuite>"))
+        };
+        let Some(end) = end else { break };
+        items.extend(parse_report(&report[..end + close.len()]));
+        rest = &report[end + close.len()..];
+    }
+
+    items
+}
+
+/// Fields of the `<testcase>` currently being read.
+#[derive(Default)]
+struct TestCase {
+    name: String,
+    classname: String,
+    file: Option<String>,
+    line: Option<u32>,
+}
+
+fn attribute(element: &BytesStart<'_>, key: &str) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|a| a.key.as_ref() == key.as_bytes())
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+fn test_case(element: &BytesStart<'_>) -> TestCase {
+    TestCase {
+        name: attribute(element, "name").unwrap_or_default(),
+        classname: attribute(element, "classname").unwrap_or_default(),
+        file: attribute(element, "file"),
+        line: attribute(element, "line").and_then(|l| l.parse().ok()),
+    }
+}
+
+fn failure_item(case: &TestCase, message: Option<String>, body: &str) -> FailureItem {
+    let message = message
+        .filter(|m| !m.trim().is_empty())
+        .or_else(|| {
+            body.lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .map(String::from)
+        })
+        .unwrap_or_else(|| "test failed".to_string());
+    let mut item = FailureItem::new(FailureSource::Junit, message);
+    item.test_name = Some(if case.classname.is_empty() {
+        case.name.clone()
+    } else {
+        format!("{}.{}", case.classname, case.name)
+    });
+    item.file.clone_from(&case.file);
+    item.line = case.line;
+    item
+}
+
+fn parse_report(xml: &str) -> Vec<FailureItem> {
+    let mut reader = Reader::from_str(xml);
+    let mut items = Vec::new();
+    let mut case: Option<TestCase> = None;
+    // Message attribute and body text of an open <failure>/<error>
+    let mut failure: Option<(Option<String>, String)> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"testcase" => case = Some(test_case(&e)),
+                b"failure" | b"error" => failure = Some((attribute(&e, "message"), String::new())),
+                _ => {}
+            },
+            Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"failure" | b"error" => {
+                    if let Some(case) = &case {
+                        items.push(failure_item(case, attribute(&e, "message"), ""));
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Text(t)) => {
+                if let (Some((_, body)), Ok(text)) = (&mut failure, t.decode()) {
+                    body.push_str(&text);
+                }
+            }
+            Ok(Event::CData(t)) => {
+                if let (Some((_, body)), Ok(text)) = (&mut failure, t.decode()) {
+                    body.push_str(&text);
+                }
+            }
+            Ok(Event::GeneralRef(r)) => {
+                if let Some((_, body)) = &mut failure {
+                    if let Ok(Some(c)) = r.resolve_char_ref() {
+                        body.push(c);
+                    } else if let Some(s) = r.decode().ok().and_then(|n| resolve_xml_entity(&n)) {
+                        body.push_str(s);
+                    }
+                }
+            }
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"failure" | b"error" => {
+                    if let (Some(case), Some((message, body))) = (&case, failure.take()) {
+                        items.push(failure_item(case, message, &body));
+                    }
+                }
+                b"testcase" => case = None,
+                _ => {}
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_junit_report() {
+        let logs = r#"Uploading report...
+<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="jest tests" tests="3" failures="2">
+  <testsuite name="App" tests="3">
+    <testcase classname="App renders" name="shows the header" file="src/App.test.tsx" line="12" time="0.01">
+      <failure message="expect(received).toBe(expected)">Error: expect(received).toBe(expected)
+    at Object.&lt;anonymous&gt; (src/App.test.tsx:14:5)</failure>
+    </testcase>
+    <testcase classname="App" name="passes" time="0.01"/>
+    <testcase name="loads config" time="0.02">
+      <error><![CDATA[TypeError: cannot read properties of undefined]]></error>
+    </testcase>
+  </testsuite>
+</testsuites>
+Done"#;
+        let items = parse(logs);
+        assert_eq!(items.len(), 2);
+
+        assert_eq!(
+            items[0].test_name.as_deref(),
+            Some("App renders.shows the header")
+        );
+        assert_eq!(items[0].location().as_deref(), Some("src/App.test.tsx:12"));
+        assert_eq!(items[0].message, "expect(received).toBe(expected)");
+
+        assert_eq!(items[1].test_name.as_deref(), Some("loads config"));
+        assert_eq!(
+            items[1].message,
+            "TypeError: cannot read properties of undefined"
+        );
+        assert_eq!(items[1].file, None);
+    }
+
+    #[test]
+    fn test_parse_multiple_suites_and_empty_failures() {
+        let logs = r#"<testsuite name="a"><testcase classname="pkg::tests" name="one"><failure/></testcase></testsuite>
+noise
+<testsuite name="b"><testcase classname="b" name="two"><failure message="boom"/></testcase></testsuite>"#;
+        let items = parse(logs);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].message, "test failed");
+        assert_eq!(items[1].message, "boom");
+    }
+}
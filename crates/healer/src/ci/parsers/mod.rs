@@ -0,0 +1,353 @@
+//! Structured CI failure parsers.
+//!
+//! Turns raw CI output into a list of [`FailureItem`]s - one per failing
+//! diagnostic or test, with its file, line, rule or test name and message -
+//! so classification and remediation prompts work from the actual failures
+//! rather than a blob of log text.
+//!
+//! Supported formats:
+//! - cargo/rustc JSON diagnostics and human-readable rustc/clippy output
+//! - `cargo test` and nextest output
+//! - JUnit XML
+//! - `ESLint` JSON (`eslint -f json`)
+//! - `tsc` output (plain and `--pretty`)
+//! - `go test -json`
+//! - pytest summaries
+
+mod eslint;
+mod go;
+mod junit;
+mod pytest;
+mod rust;
+mod typescript;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+use super::types::CiFailureType;
+
+/// Tool output a failure was parsed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureSource {
+    /// rustc compiler diagnostic
+    Rustc,
+    /// Clippy lint
+    Clippy,
+    /// `cargo test` failure
+    CargoTest,
+    /// nextest failure
+    Nextest,
+    /// JUnit XML test case
+    Junit,
+    /// `ESLint` rule violation
+    Eslint,
+    /// TypeScript compiler error
+    TypeScript,
+    /// `go test` failure
+    GoTest,
+    /// pytest failure
+    Pytest,
+}
+
+impl FailureSource {
+    /// How much a match from this source should be trusted. Machine-readable
+    /// formats score higher than text scraped from human output.
+    #[must_use]
+    pub fn reliability(self) -> f32 {
+        match self {
+            Self::Eslint | Self::GoTest | Self::Junit => 1.0,
+            Self::Rustc | Self::Clippy | Self::TypeScript | Self::Nextest => 0.95,
+            Self::CargoTest | Self::Pytest => 0.9,
+        }
+    }
+}
+
+/// A single failing diagnostic or test.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureItem {
+    /// Tool output this was parsed from
+    pub source: FailureSource,
+    /// File the failure points at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// 1-based line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// 1-based column
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+    /// Error code or lint rule (`E0425`, `clippy::unwrap_used`, `no-unused-vars`, `TS2304`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Failing test name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_name: Option<String>,
+    /// Failure message
+    pub message: String,
+}
+
+impl FailureItem {
+    fn new(source: FailureSource, message: impl Into<String>) -> Self {
+        Self {
+            source,
+            file: None,
+            line: None,
+            column: None,
+            rule: None,
+            test_name: None,
+            message: message.into(),
+        }
+    }
+
+    /// `file:line` (or just the file), if known.
+    #[must_use]
+    pub fn location(&self) -> Option<String> {
+        self.file.as_ref().map(|file| match self.line {
+            Some(line) => format!("{file}:{line}"),
+            None => file.clone(),
+        })
+    }
+
+    /// The failure type this item points to.
+    #[must_use]
+    pub fn failure_type(&self) -> CiFailureType {
+        match self.source {
+            FailureSource::Rustc => CiFailureType::RustBuild,
+            FailureSource::Clippy => CiFailureType::RustClippy,
+            FailureSource::CargoTest | FailureSource::Nextest => CiFailureType::RustTest,
+            FailureSource::Eslint => CiFailureType::FrontendLint,
+            FailureSource::TypeScript => CiFailureType::FrontendTypeScript,
+            FailureSource::Junit => self.junit_failure_type(),
+            FailureSource::GoTest | FailureSource::Pytest => CiFailureType::General,
+        }
+    }
+
+    /// JUnit reports come from many runners; infer the language from the file.
+    fn junit_failure_type(&self) -> CiFailureType {
+        let extension = self
+            .file
+            .as_deref()
+            .and_then(|f| std::path::Path::new(f).extension())
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        let is_frontend = ["ts", "tsx", "js", "jsx", "mjs", "vue"].contains(&extension.as_str());
+        let is_rust =
+            extension == "rs" || self.test_name.as_deref().is_some_and(|n| n.contains("::"));
+        if is_frontend {
+            CiFailureType::FrontendTest
+        } else if is_rust {
+            CiFailureType::RustTest
+        } else {
+            CiFailureType::General
+        }
+    }
+}
+
+/// Failure type inferred from parsed items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureClassification {
+    /// Most likely failure type
+    pub failure_type: CiFailureType,
+    /// 0.0-1.0 confidence in the classification
+    pub confidence: f32,
+}
+
+/// GitHub Actions log prefixes: `job<TAB>step<TAB>timestamp ` or just the timestamp.
+static LOG_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:[^\t]*\t[^\t]*\t)?(?:\x{feff})?\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?Z ?",
+    )
+    .unwrap()
+});
+
+/// ANSI color escape sequences.
+static ANSI_ESCAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap());
+
+/// Strip GitHub Actions prefixes and ANSI colors from every line.
+#[must_use]
+pub fn normalize_logs(logs: &str) -> String {
+    logs.lines()
+        .map(|line| {
+            let line = LOG_PREFIX.replace(line, "");
+            ANSI_ESCAPE.replace_all(&line, "").into_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parse every supported format out of CI output.
+///
+/// Each parser only picks up lines in its own format, so mixed logs (e.g.
+/// a workflow running both clippy and `tsc`) yield items from each tool.
+/// Duplicates, such as nextest repeating failures in its summary, are
+/// dropped.
+#[must_use]
+pub fn parse_ci_output(logs: &str) -> Vec<FailureItem> {
+    let logs = normalize_logs(logs);
+
+    let mut items = Vec::new();
+    items.extend(rust::parse_json_diagnostics(&logs));
+    items.extend(rust::parse_human_diagnostics(&logs));
+    items.extend(rust::parse_cargo_test(&logs));
+    items.extend(rust::parse_nextest(&logs));
+    items.extend(junit::parse(&logs));
+    items.extend(eslint::parse(&logs));
+    items.extend(typescript::parse(&logs));
+    items.extend(go::parse(&logs));
+    items.extend(pytest::parse(&logs));
+
+    let mut seen = HashSet::new();
+    items.retain(|item| {
+        seen.insert((
+            item.file.clone(),
+            item.line,
+            item.rule.clone(),
+            item.test_name.clone(),
+            item.message.clone(),
+        ))
+    });
+    items
+}
+
+/// Classify a failure from parsed items.
+///
+/// Each item votes for its failure type, weighted by its source's
+/// reliability. Confidence is the winning share of the vote scaled by the
+/// average reliability of the winning items, so a clean sweep of structured
+/// diagnostics scores close to 1.0 while mixed or scraped output scores
+/// lower. Returns `None` when there are no items.
+#[must_use]
+#[allow(clippy::cast_precision_loss)] // Item counts are small
+pub fn classify(items: &[FailureItem]) -> Option<FailureClassification> {
+    let mut votes: HashMap<CiFailureType, (f32, usize)> = HashMap::new();
+    for item in items {
+        let vote = votes.entry(item.failure_type()).or_default();
+        vote.0 += item.source.reliability();
+        vote.1 += 1;
+    }
+
+    let total: f32 = votes.values().map(|(weight, _)| weight).sum();
+    let (failure_type, (weight, count)) = votes
+        .into_iter()
+        // Prefer specific types over General, then by weight
+        .max_by(|(a_type, a), (b_type, b)| {
+            (*a_type != CiFailureType::General)
+                .cmp(&(*b_type != CiFailureType::General))
+                .then(a.0.total_cmp(&b.0))
+                .then_with(|| b_type.short_name().cmp(a_type.short_name()))
+        })?;
+
+    let share = weight / total;
+    let reliability = weight / count as f32;
+    let mut confidence = share * reliability;
+    if failure_type == CiFailureType::General {
+        // Knowing tests failed says little about who should fix them
+        confidence *= 0.5;
+    }
+
+    Some(FailureClassification {
+        failure_type,
+        confidence,
+    })
+}
+
+/// Render items as a compact bullet list for prompts.
+#[must_use]
+pub fn format_failures(items: &[FailureItem], limit: usize) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    for item in items.iter().take(limit) {
+        out.push_str("- ");
+        if let Some(location) = item.location() {
+            let _ = write!(out, "`{location}` ");
+        }
+        if let Some(test) = &item.test_name {
+            let _ = write!(out, "**{test}** ");
+        }
+        if let Some(rule) = &item.rule {
+            let _ = write!(out, "[{rule}] ");
+        }
+        out.push_str(&item.message);
+        out.push('\n');
+    }
+    if items.len() > limit {
+        let _ = writeln!(out, "- ... and {} more", items.len() - limit);
+    }
+    out
+}
+
+/// Parse a number captured by a regex.
+fn parse_num(value: Option<regex::Match<'_>>) -> Option<u32> {
+    value.and_then(|m| m.as_str().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_logs_strips_actions_prefixes() {
+        let logs = "lint\tRun cargo clippy\t2026-01-04T10:15:00.1234567Z error[E0425]: oops\n\
+                    2026-01-04T10:15:01Z \x1b[31merror\x1b[0m: plain";
+        assert_eq!(normalize_logs(logs), "error[E0425]: oops\nerror: plain");
+    }
+
+    #[test]
+    fn test_mixed_logs_classify_by_majority() {
+        let logs = "\
+error: used `unwrap()` on a `Result` value
+  --> src/lib.rs:3:5
+   |
+   = help: for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#unwrap_used
+error: this `if` has identical blocks
+  --> src/main.rs:10:5
+   |
+   = note: `#[deny(clippy::if_same_then_else)]` on by default
+src/app.ts(4,1): error TS2304: Cannot find name 'foo'.
+";
+        let items = parse_ci_output(logs);
+        assert_eq!(items.len(), 3);
+
+        let classification = classify(&items).unwrap();
+        assert_eq!(classification.failure_type, CiFailureType::RustClippy);
+        assert!(classification.confidence > 0.5 && classification.confidence < 0.7);
+    }
+
+    #[test]
+    fn test_single_tool_classifies_with_high_confidence() {
+        let items = parse_ci_output(
+            r#"[{"filePath":"/repo/src/App.tsx","messages":[{"ruleId":"no-unused-vars","severity":2,"message":"'x' is defined but never used.","line":3,"column":7}],"errorCount":1}]"#,
+        );
+        let classification = classify(&items).unwrap();
+        assert_eq!(classification.failure_type, CiFailureType::FrontendLint);
+        assert!((classification.confidence - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_general_only_results_have_low_confidence() {
+        let items = parse_ci_output("FAILED tests/test_api.py::test_login - AssertionError");
+        let classification = classify(&items).unwrap();
+        assert_eq!(classification.failure_type, CiFailureType::General);
+        assert!(classification.confidence < 0.5);
+        assert!(classify(&[]).is_none());
+    }
+
+    #[test]
+    fn test_format_failures() {
+        let mut item = FailureItem::new(FailureSource::Rustc, "cannot find value `x`");
+        item.file = Some("src/main.rs".to_string());
+        item.line = Some(10);
+        item.rule = Some("E0425".to_string());
+        let out = format_failures(&[item.clone(), item], 1);
+        assert_eq!(
+            out,
+            "- `src/main.rs:10` [E0425] cannot find value `x`\n- ... and 1 more\n"
+        );
+    }
+}
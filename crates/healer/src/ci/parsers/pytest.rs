@@ -0,0 +1,64 @@
+//! pytest output.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+use super::{FailureItem, FailureSource};
+
+/// `FAILED tests/test_api.py::TestAuth::test_login - AssertionError: ...`
+static PYTEST_SUMMARY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:FAILED|ERROR) ([^\s:]+\.py)::(\S+)(?: - (.+))?$").unwrap());
+
+/// Parse the short test summary, taking line numbers from the tracebacks
+/// (`tests/test_api.py:42: AssertionError`).
+pub(super) fn parse(logs: &str) -> Vec<FailureItem> {
+    logs.lines()
+        .filter_map(|line| PYTEST_SUMMARY.captures(line))
+        .map(|caps| {
+            let file = &caps[1];
+            let message = caps
+                .get(3)
+                .map_or("test failed", |m| m.as_str())
+                .trim()
+                .to_string();
+            let mut item = FailureItem::new(FailureSource::Pytest, message);
+            item.file = Some(file.to_string());
+            item.test_name = Some(caps[2].to_string());
+            let prefix = format!("{file}:");
+            item.line = logs.lines().find_map(|l| {
+                l.strip_prefix(&prefix)
+                    .and_then(|rest| rest.split_once(':'))
+                    .and_then(|(line, _)| line.parse().ok())
+            });
+            item
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pytest_summary() {
+        let logs = "\
+    def test_login(client):
+>       assert client.post('/login').status_code == 200
+E       assert 401 == 200
+
+tests/test_api.py:42: AssertionError
+=========================== short test summary info ============================
+FAILED tests/test_api.py::TestAuth::test_login - assert 401 == 200
+ERROR tests/test_db.py::test_connect
+========================= 1 failed, 1 error in 0.12s ==========================
+";
+        let items = parse(logs);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].test_name.as_deref(), Some("TestAuth::test_login"));
+        assert_eq!(items[0].location().as_deref(), Some("tests/test_api.py:42"));
+        assert_eq!(items[0].message, "assert 401 == 200");
+        assert_eq!(items[1].file.as_deref(), Some("tests/test_db.py"));
+        assert_eq!(items[1].line, None);
+        assert_eq!(items[1].message, "test failed");
+    }
+}
@@ -0,0 +1,352 @@
+//! cargo, rustc, clippy, `cargo test` and nextest output.
+
+use regex::Regex;
+use serde_json::Value;
+use std::sync::LazyLock;
+
+use super::{parse_num, FailureItem, FailureSource};
+
+/// `error[E0425]: message` / `error: message`
+static DIAGNOSTIC_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^error(?:\[(E\d{4})\])?: (.+)$").unwrap());
+
+/// `  --> src/main.rs:10:5`
+static DIAGNOSTIC_LOCATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*--> (.+?):(\d+):(\d+)").unwrap());
+
+/// Lint name from `#[deny(clippy::x)]`, `-D clippy::x` or the clippy docs link.
+static CLIPPY_LINT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:deny\(|-D )(clippy::[a-z0-9_-]+)|rust-clippy/[^#\s]*#([a-z0-9_]+)").unwrap()
+});
+
+/// `---- tests::foo stdout ----`
+static CARGO_TEST_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^---- (\S+) stdout ----$").unwrap());
+
+/// `test tests::foo ... FAILED`
+static CARGO_TEST_FAILED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^test (\S+) \.\.\. FAILED$").unwrap());
+
+/// `thread 'x' panicked at src/lib.rs:10:9:` (1.73+) or
+/// `thread 'x' panicked at 'message', src/lib.rs:10:9`
+static PANIC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"panicked at (?:'(.*)', )?([^\s:]+):(\d+):(\d+):?$").unwrap());
+
+/// `        FAIL [   0.004s] my-crate tests::foo`
+static NEXTEST_FAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:FAIL|TIMEOUT|SIGSEGV|SIGABRT) \[\s*[\d.]+s\] \S+ (\S+)").unwrap()
+});
+
+/// `--- STDERR:              my-crate tests::foo ---`
+static NEXTEST_OUTPUT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^--- STD(?:OUT|ERR):\s+(\S+) (\S+)").unwrap());
+
+/// Summary lines that are not diagnostics in their own right.
+const DIAGNOSTIC_NOISE: &[&str] = &[
+    "aborting due to",
+    "could not compile",
+    "process didn't exit successfully",
+    "test failed, to rerun pass",
+    "Recipe `",
+];
+
+/// Parse `--message-format=json` (cargo) and `--error-format=json` (rustc) lines.
+pub(super) fn parse_json_diagnostics(logs: &str) -> Vec<FailureItem> {
+    logs.lines()
+        .filter(|line| line.trim_start().starts_with('{') && line.contains("\"level\""))
+        .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+        .filter_map(|value| {
+            if value["reason"] == "compiler-message" {
+                Some(value["message"].clone())
+            } else if value["$message_type"] == "diagnostic" {
+                Some(value)
+            } else {
+                None
+            }
+        })
+        .filter(|diag| diag["level"] == "error")
+        .filter_map(|diag| {
+            let message = diag["message"].as_str()?;
+            if DIAGNOSTIC_NOISE.iter().any(|n| message.contains(n)) {
+                return None;
+            }
+            let code = diag["code"]["code"].as_str().map(String::from);
+            let source = if code.as_deref().is_some_and(|c| c.starts_with("clippy::")) {
+                FailureSource::Clippy
+            } else {
+                FailureSource::Rustc
+            };
+
+            let mut item = FailureItem::new(source, message);
+            item.rule = code;
+            let span = diag["spans"]
+                .as_array()
+                .and_then(|spans| spans.iter().find(|s| s["is_primary"] == true));
+            if let Some(span) = span {
+                item.file = span["file_name"].as_str().map(String::from);
+                item.line = span["line_start"]
+                    .as_u64()
+                    .and_then(|l| u32::try_from(l).ok());
+                item.column = span["column_start"]
+                    .as_u64()
+                    .and_then(|c| u32::try_from(c).ok());
+            }
+            Some(item)
+        })
+        .collect()
+}
+
+/// Parse human-readable rustc and clippy errors that carry a `-->` location.
+pub(super) fn parse_human_diagnostics(logs: &str) -> Vec<FailureItem> {
+    let lines: Vec<&str> = logs.lines().collect();
+    let mut items = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some(header) = DIAGNOSTIC_HEADER.captures(line) else {
+            continue;
+        };
+        let message = &header[2];
+        if DIAGNOSTIC_NOISE.iter().any(|n| message.contains(n)) {
+            continue;
+        }
+        let Some(location) = lines
+            .iter()
+            .skip(i + 1)
+            .take(3)
+            .find_map(|l| DIAGNOSTIC_LOCATION.captures(l))
+        else {
+            continue;
+        };
+
+        // The diagnostic body runs until the next blank line or header
+        let lint = lines
+            .iter()
+            .skip(i + 1)
+            .take_while(|l| !l.trim().is_empty() && !DIAGNOSTIC_HEADER.is_match(l))
+            .find_map(|l| CLIPPY_LINT.captures(l))
+            .and_then(|c| {
+                c.get(1)
+                    .map(|m| m.as_str().replace('-', "_"))
+                    .or_else(|| c.get(2).map(|m| format!("clippy::{}", m.as_str())))
+            });
+
+        let source = if lint.is_some() {
+            FailureSource::Clippy
+        } else {
+            FailureSource::Rustc
+        };
+        let mut item = FailureItem::new(source, message);
+        item.rule = lint.or_else(|| header.get(1).map(|m| m.as_str().to_string()));
+        item.file = Some(location[1].to_string());
+        item.line = parse_num(location.get(2));
+        item.column = parse_num(location.get(3));
+        items.push(item);
+    }
+
+    items
+}
+
+/// Find the first panic in `lines`, returning `(file, line, message)`.
+fn find_panic<'a>(lines: impl Iterator<Item = &'a str>) -> Option<(String, Option<u32>, String)> {
+    let mut lines = lines.peekable();
+    while let Some(line) = lines.next() {
+        if let Some(caps) = PANIC.captures(line) {
+            let message = match caps.get(1) {
+                Some(m) => m.as_str().to_string(),
+                None => lines
+                    .peek()
+                    .map(|l| l.trim().to_string())
+                    .unwrap_or_default(),
+            };
+            return Some((caps[2].to_string(), parse_num(caps.get(3)), message));
+        }
+    }
+    None
+}
+
+/// Parse `cargo test` (libtest) failures.
+pub(super) fn parse_cargo_test(logs: &str) -> Vec<FailureItem> {
+    let lines: Vec<&str> = logs.lines().collect();
+    let mut items: Vec<FailureItem> = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some(header) = CARGO_TEST_HEADER.captures(line) else {
+            continue;
+        };
+        let body = lines[i + 1..]
+            .iter()
+            .take_while(|l| !CARGO_TEST_HEADER.is_match(l) && **l != "failures:")
+            .copied();
+        let mut item = FailureItem::new(FailureSource::CargoTest, "test failed");
+        item.test_name = Some(header[1].to_string());
+        if let Some((file, line, message)) = find_panic(body) {
+            item.file = Some(file);
+            item.line = line;
+            item.message = message;
+        }
+        items.push(item);
+    }
+
+    // Failures without captured output (e.g. `--nocapture` runs)
+    for caps in lines.iter().filter_map(|l| CARGO_TEST_FAILED.captures(l)) {
+        let name = &caps[1];
+        if !items.iter().any(|i| i.test_name.as_deref() == Some(name)) {
+            let mut item = FailureItem::new(FailureSource::CargoTest, "test failed");
+            item.test_name = Some(name.to_string());
+            items.push(item);
+        }
+    }
+
+    items
+}
+
+/// Parse nextest failures, attaching panics from their captured output.
+pub(super) fn parse_nextest(logs: &str) -> Vec<FailureItem> {
+    let lines: Vec<&str> = logs.lines().collect();
+    let mut items: Vec<FailureItem> = Vec::new();
+
+    for caps in lines.iter().filter_map(|l| NEXTEST_FAIL.captures(l)) {
+        let name = &caps[1];
+        if items.iter().any(|i| i.test_name.as_deref() == Some(name)) {
+            continue;
+        }
+        let mut item = FailureItem::new(FailureSource::Nextest, "test failed");
+        item.test_name = Some(name.to_string());
+
+        let output = lines.iter().enumerate().find_map(|(i, l)| {
+            NEXTEST_OUTPUT
+                .captures(l)
+                .filter(|c| &c[2] == name)
+                .and_then(|_| {
+                    find_panic(
+                        lines[i + 1..]
+                            .iter()
+                            .take_while(|l| !l.starts_with("--- ") && !NEXTEST_FAIL.is_match(l))
+                            .copied(),
+                    )
+                })
+        });
+        if let Some((file, line, message)) = output {
+            item.file = Some(file);
+            item.line = line;
+            item.message = message;
+        }
+        items.push(item);
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cargo_json_diagnostics() {
+        let logs = r#"{"reason":"compiler-artifact","package_id":"x"}
+{"reason":"compiler-message","package_id":"x","message":{"$message_type":"diagnostic","message":"cannot find value `y` in this scope","code":{"code":"E0425","explanation":null},"level":"error","spans":[{"file_name":"src/main.rs","line_start":4,"column_start":13,"is_primary":true}],"children":[],"rendered":"..."}}
+{"reason":"compiler-message","package_id":"x","message":{"$message_type":"diagnostic","message":"used `unwrap()` on an `Option` value","code":{"code":"clippy::unwrap_used","explanation":null},"level":"error","spans":[{"file_name":"src/lib.rs","line_start":9,"column_start":5,"is_primary":true}],"children":[],"rendered":"..."}}
+{"reason":"compiler-message","package_id":"x","message":{"$message_type":"diagnostic","message":"unused variable: `z`","code":{"code":"unused_variables","explanation":null},"level":"warning","spans":[],"children":[],"rendered":"..."}}
+{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"..."}"#;
+
+        let items = parse_json_diagnostics(logs);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].source, FailureSource::Rustc);
+        assert_eq!(items[0].rule.as_deref(), Some("E0425"));
+        assert_eq!(items[0].location().as_deref(), Some("src/main.rs:4"));
+        assert_eq!(items[0].column, Some(13));
+        assert_eq!(items[1].source, FailureSource::Clippy);
+        assert_eq!(items[1].rule.as_deref(), Some("clippy::unwrap_used"));
+    }
+
+    #[test]
+    fn test_parse_human_diagnostics() {
+        let logs = "\
+   Compiling healer v0.1.0
+error[E0308]: mismatched types
+  --> crates/healer/src/main.rs:12:20
+   |
+12 |     let x: u32 = \"a\";
+   |            ---   ^^^ expected `u32`, found `&str`
+
+error: redundant clone
+ --> src/lib.rs:7:14
+  |
+  = note: `-D clippy::redundant-clone` implied by `-D clippy::pedantic`
+
+error: could not compile `healer` (bin \"healer\") due to 2 previous errors
+";
+        let items = parse_human_diagnostics(logs);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].source, FailureSource::Rustc);
+        assert_eq!(items[0].rule.as_deref(), Some("E0308"));
+        assert_eq!(items[0].file.as_deref(), Some("crates/healer/src/main.rs"));
+        assert_eq!(items[0].line, Some(12));
+        assert_eq!(items[1].source, FailureSource::Clippy);
+        assert_eq!(items[1].rule.as_deref(), Some("clippy::redundant_clone"));
+        assert_eq!(items[1].message, "redundant clone");
+    }
+
+    #[test]
+    fn test_parse_cargo_test() {
+        let logs = "\
+running 3 tests
+test tests::passes ... ok
+test tests::adds ... FAILED
+test tests::legacy ... FAILED
+test tests::silent ... FAILED
+
+failures:
+
+---- tests::adds stdout ----
+thread 'tests::adds' panicked at src/lib.rs:10:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+
+---- tests::legacy stdout ----
+thread 'tests::legacy' panicked at 'boom', src/old.rs:3:5
+
+failures:
+    tests::adds
+    tests::legacy
+    tests::silent
+
+test result: FAILED. 1 passed; 3 failed
+";
+        let items = parse_cargo_test(logs);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].test_name.as_deref(), Some("tests::adds"));
+        assert_eq!(items[0].location().as_deref(), Some("src/lib.rs:10"));
+        assert_eq!(items[0].message, "assertion `left == right` failed");
+        assert_eq!(items[1].message, "boom");
+        assert_eq!(items[1].location().as_deref(), Some("src/old.rs:3"));
+        assert_eq!(items[2].test_name.as_deref(), Some("tests::silent"));
+        assert_eq!(items[2].file, None);
+    }
+
+    #[test]
+    fn test_parse_nextest() {
+        let logs = "\
+        PASS [   0.003s] healer scanner::tests::ok
+        FAIL [   0.004s] healer dedup::tests::family
+--- STDOUT:              healer dedup::tests::family ---
+
+running 1 test
+--- STDERR:              healer dedup::tests::family ---
+thread 'dedup::tests::family' panicked at crates/healer/src/dedup.rs:240:9:
+expected play-task-4
+------------
+     Summary [   0.010s] 2 tests run: 1 passed, 1 failed
+        FAIL [   0.004s] healer dedup::tests::family
+";
+        let items = parse_nextest(logs);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].test_name.as_deref(), Some("dedup::tests::family"));
+        assert_eq!(
+            items[0].location().as_deref(),
+            Some("crates/healer/src/dedup.rs:240")
+        );
+        assert_eq!(items[0].message, "expected play-task-4");
+    }
+}
@@ -0,0 +1,58 @@
+//! `tsc` output.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+use super::{parse_num, FailureItem, FailureSource};
+
+/// `src/app.ts(10,5): error TS2304: message` (default) or
+/// `src/app.ts:10:5 - error TS2304: message` (`--pretty`)
+static TSC_ERROR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*(?P<file>[^\s(:]+\.(?:ts|tsx|mts|cts|js|jsx|vue))(?:\((?P<l1>\d+),(?P<c1>\d+)\):|:(?P<l2>\d+):(?P<c2>\d+) -) error (?P<code>TS\d+): (?P<msg>.+)$",
+    )
+    .unwrap()
+});
+
+/// Parse `tsc` errors.
+pub(super) fn parse(logs: &str) -> Vec<FailureItem> {
+    logs.lines()
+        .filter_map(|line| TSC_ERROR.captures(line))
+        .map(|caps| {
+            let mut item = FailureItem::new(FailureSource::TypeScript, caps["msg"].trim());
+            item.file = Some(caps["file"].to_string());
+            item.line = parse_num(caps.name("l1").or_else(|| caps.name("l2")));
+            item.column = parse_num(caps.name("c1").or_else(|| caps.name("c2")));
+            item.rule = Some(caps["code"].to_string());
+            item
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tsc_output() {
+        let logs = "\
+src/app.ts(10,5): error TS2304: Cannot find name 'foo'.
+src/components/Nav.tsx:4:18 - error TS2322: Type 'string' is not assignable to type 'number'.
+
+4 const n: number = 'a';
+                   ~~~
+Found 2 errors in 2 files.
+";
+        let items = parse(logs);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].rule.as_deref(), Some("TS2304"));
+        assert_eq!(items[0].location().as_deref(), Some("src/app.ts:10"));
+        assert_eq!(items[0].column, Some(5));
+        assert_eq!(items[1].file.as_deref(), Some("src/components/Nav.tsx"));
+        assert_eq!(items[1].line, Some(4));
+        assert_eq!(
+            items[1].message,
+            "Type 'string' is not assignable to type 'number'."
+        );
+    }
+}
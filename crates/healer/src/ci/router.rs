@@ -9,10 +9,17 @@
 use regex::Regex;
 use std::sync::LazyLock;
 
+use super::parsers::{self, FailureClassification, FailureItem};
 use super::types::{
     Agent, ChangedFile, CiFailure, CiFailureType, RemediationContext, SecurityAlert,
 };
 
+/// Confidence assigned when only raw log patterns matched.
+const LOG_PATTERN_CONFIDENCE: f32 = 0.4;
+
+/// Confidence assigned when only the workflow or job name matched.
+const WORKFLOW_NAME_CONFIDENCE: f32 = 0.3;
+
 /// Regex patterns for failure classification.
 static RUST_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
     vec![
@@ -338,21 +345,54 @@ impl CiRouter {
         }
     }
 
-    /// Classify a CI failure type from available context.
+    /// Classify a CI failure type from raw logs.
     #[must_use]
     pub fn classify_failure(&self, failure: &CiFailure, logs: &str) -> CiFailureType {
-        // Check logs first (most specific)
+        let failures = parsers::parse_ci_output(logs);
+        self.classify(failure, &failures, logs).failure_type
+    }
+
+    /// Classify a CI failure with a confidence score.
+    ///
+    /// Structured failures parsed from the logs are the strongest signal.
+    /// When they are absent or only say "tests failed" without a language,
+    /// fall back to log patterns, then the workflow/job name.
+    #[must_use]
+    pub fn classify(
+        &self,
+        failure: &CiFailure,
+        failures: &[FailureItem],
+        logs: &str,
+    ) -> FailureClassification {
+        let structured = parsers::classify(failures);
+        if let Some(classification) = structured
+            .as_ref()
+            .filter(|c| c.failure_type != CiFailureType::General)
+        {
+            return classification.clone();
+        }
+
+        // Check logs next (more specific than names)
         if let Some(failure_type) = Self::classify_from_logs(logs) {
-            return failure_type;
+            return FailureClassification {
+                failure_type,
+                confidence: LOG_PATTERN_CONFIDENCE,
+            };
         }
 
         // Check workflow/job name
         if let Some(failure_type) = Self::classify_from_workflow_name(failure) {
-            return failure_type;
+            return FailureClassification {
+                failure_type,
+                confidence: WORKFLOW_NAME_CONFIDENCE,
+            };
         }
 
         // Default to general
-        CiFailureType::General
+        structured.unwrap_or(FailureClassification {
+            failure_type: CiFailureType::General,
+            confidence: 0.0,
+        })
     }
 
     /// Classify from log content.
@@ -647,4 +687,39 @@ mod tests {
         let ft = router.classify_failure(&failure, "TS2304: Cannot find name");
         assert_eq!(ft, CiFailureType::FrontendTypeScript);
     }
+
+    #[test]
+    fn test_classify_prefers_structured_failures() {
+        let router = CiRouter::new();
+        let failure = CiFailure {
+            workflow_run_id: 123,
+            workflow_name: "Frontend CI".to_string(),
+            job_name: Some("docker build".to_string()),
+            conclusion: "failure".to_string(),
+            branch: "main".to_string(),
+            head_sha: "abc".to_string(),
+            commit_message: "test".to_string(),
+            html_url: "https://github.com".to_string(),
+            repository: "test/repo".to_string(),
+            sender: "user".to_string(),
+            detected_at: chrono::Utc::now(),
+            raw_event: None,
+        };
+
+        // Mentions eslint, but the parsed failures are rustc errors
+        let logs = "npx eslint skipped\nerror[E0425]: cannot find value `x` in this scope\n  --> src/main.rs:4:13\n";
+        let failures = parsers::parse_ci_output(logs);
+        let classification = router.classify(&failure, &failures, logs);
+        assert_eq!(classification.failure_type, CiFailureType::RustBuild);
+        assert!(classification.confidence > 0.9);
+
+        // No structured failures: log patterns, then the job name
+        let classification = router.classify(&failure, &[], "npm ERR! peer dep");
+        assert_eq!(classification.failure_type, CiFailureType::FrontendDeps);
+        assert!((classification.confidence - LOG_PATTERN_CONFIDENCE).abs() < f32::EPSILON);
+
+        let classification = router.classify(&failure, &[], "exit code 1");
+        assert_eq!(classification.failure_type, CiFailureType::DockerBuild);
+        assert!((classification.confidence - WORKFLOW_NAME_CONFIDENCE).abs() < f32::EPSILON);
+    }
 }
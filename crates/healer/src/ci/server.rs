@@ -3,14 +3,21 @@
 //! Provides REST API endpoints for:
 //! - Health checks
 //! - Receiving CI failure events from sensors
+//! - Recording test outcomes and quarantining flaky tests
+//! - Tracking in-flight remediations (optionally persisted for failover)
+//! - Managing alert silences and maintenance windows
+//! - Querying incident timelines and exporting postmortem drafts
+//! - Inspecting and resetting the remediation guardrails, and watching
+//!   default branches for reverts of auto-merged PRs
+//! - Prometheus metrics and a rolling summary of remediation SLOs
 //! - Querying remediation status
 
 use anyhow::Result;
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
     response::IntoResponse,
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
@@ -19,21 +26,60 @@ use tokio::sync::RwLock;
 use tower_http::trace::TraceLayer;
 use tracing::{error, info, warn};
 
+use crate::clients::{ClusterClient, GitHubClient, OctocrabGitHubClient};
+use crate::ha::StateStore;
+use crate::incident::{
+    ci_incident_key, render_postmortem, Incident, IncidentEvent, IncidentEventKind, IncidentStore,
+};
+use crate::platform::{build_platform_router, PlatformServerState};
+use crate::silence::{AlertTarget, NewSilence, Silences};
+
 use super::{
+    adaptive::AdaptiveRouter,
     context::ContextGatherer,
+    flaky::{FlakeTracker, Quarantiner},
+    guardrails::{Blocked, Guardrails},
     router::CiRouter,
-    spawner::CodeRunSpawner,
-    types::{CiFailure, RemediationConfig, RemediationContext},
+    slo::{SloMetrics, SloSummary},
+    spawner::{remediation_prs, CodeRunSpawner},
+    tracker::RemediationTracker,
+    types::{CiFailure, RemediationConfig, RemediationContext, RemediationStatus},
 };
 
+/// How often default branches are checked for reverts of auto-merged PRs.
+const REVERT_SCAN_INTERVAL: std::time::Duration = std::time::Duration::from_mins(5);
+
+/// How often healer `CodeRuns` are checked for opened PRs.
+const PR_SCAN_INTERVAL: std::time::Duration = std::time::Duration::from_mins(1);
+
 /// Server state shared across handlers.
 pub struct ServerState {
     /// CI router for failure classification
     pub router: CiRouter,
+    /// Learned routing over recorded outcomes (if an outcome store is configured)
+    pub adaptive: Option<AdaptiveRouter>,
     /// Context gatherer
     pub gatherer: ContextGatherer,
     /// `CodeRun` spawner
     pub spawner: RwLock<CodeRunSpawner>,
+    /// Per-test pass/fail history
+    pub flakes: RwLock<FlakeTracker>,
+    /// Opens issues for flaky tests (requires a GitHub token)
+    pub quarantiner: Option<Quarantiner>,
+    /// GitHub API client (requires a GitHub token)
+    pub github: Option<Arc<dyn GitHubClient>>,
+    /// Kubernetes API client (for watching `CodeRuns`)
+    pub cluster: Option<Arc<dyn ClusterClient>>,
+    /// In-flight remediations by workflow run
+    pub tracker: RemediationTracker,
+    /// Alert silences and maintenance windows
+    pub silences: Arc<Silences>,
+    /// Incident timelines
+    pub incidents: Arc<IncidentStore>,
+    /// Blast-radius limits and read-only circuit breaker
+    pub guardrails: Arc<Guardrails>,
+    /// Detection latency, MTTR and fix success metrics
+    pub slo: Arc<SloMetrics>,
     /// Configuration
     pub config: RemediationConfig,
     /// Repository
@@ -50,8 +96,11 @@ impl ServerState {
     /// Returns an error if the `CodeRunSpawner` cannot be created or templates cannot be loaded.
     pub fn new(config: RemediationConfig, repository: &str, namespace: &str) -> Result<Self> {
         let router = CiRouter::new();
-        let gatherer = ContextGatherer::new(repository, namespace);
-        let mut spawner = CodeRunSpawner::new(config.clone(), namespace, repository)?;
+        let guardrails = Arc::new(
+            Guardrails::new(config.guardrails.clone()).with_notifier(notify::Notifier::from_env()),
+        );
+        let mut spawner = CodeRunSpawner::new(config.clone(), namespace, repository)?
+            .with_guardrails(guardrails.clone());
 
         // Load CI prompt templates from standard locations
         // Use HEALER_TEMPLATES_DIR env var, falling back to standard paths
@@ -79,15 +128,92 @@ impl ServerState {
             warn!("No CI templates found, using generic prompts");
         }
 
+        let flakes = FlakeTracker::load(config.flaky_tests.clone()).unwrap_or_else(|e| {
+            warn!("Failed to load flaky test history, starting empty: {e}");
+            FlakeTracker::new(config.flaky_tests.clone())
+        });
+        let github: Option<Arc<dyn GitHubClient>> = match OctocrabGitHubClient::from_env() {
+            Ok(github) => Some(Arc::new(github)),
+            Err(e) => {
+                warn!("Workflow logs, flaky test quarantine and revert detection disabled: {e}");
+                None
+            }
+        };
+        let mut gatherer = ContextGatherer::new(repository, namespace);
+        if let Some(github) = &github {
+            gatherer = gatherer.with_github(github.clone());
+        }
+        let quarantiner = github
+            .clone()
+            .map(|github| Quarantiner::new(config.flaky_tests.quarantine_labels.clone(), github));
+
+        let adaptive = AdaptiveRouter::from_config(&config.adaptive_routing);
+        let incidents = Arc::new(IncidentStore::new());
+        let slo = Arc::new(SloMetrics::new());
+        let tracker = RemediationTracker::new(config.clone(), None)?
+            .with_incidents(incidents.clone())
+            .with_guardrails(guardrails.clone())
+            .with_slo(slo.clone());
+
         Ok(Self {
             router,
+            adaptive,
             gatherer,
             spawner: RwLock::new(spawner),
+            flakes: RwLock::new(flakes),
+            quarantiner,
+            github,
+            cluster: None,
+            tracker,
+            silences: Arc::new(Silences::new()),
+            incidents,
+            guardrails,
+            slo,
             config,
             repository: repository.to_string(),
             namespace: namespace.to_string(),
         })
     }
+
+    /// Persist in-flight remediations and incidents in `store` so a
+    /// restarted or replacement server resumes them (see
+    /// [`RemediationTracker::restore`]).
+    #[must_use]
+    pub fn with_state_store(mut self, store: StateStore) -> Self {
+        self.incidents = Arc::new(IncidentStore::new().with_state_store(&store));
+        self.guardrails = Arc::new(
+            Guardrails::new(self.config.guardrails.clone())
+                .with_notifier(notify::Notifier::from_env())
+                .with_state_store(store.clone()),
+        );
+        self.spawner = RwLock::new(
+            self.spawner
+                .into_inner()
+                .with_guardrails(self.guardrails.clone()),
+        );
+        self.tracker = self
+            .tracker
+            .with_state_store(store)
+            .with_incidents(self.incidents.clone())
+            .with_guardrails(self.guardrails.clone());
+        self
+    }
+
+    /// Use `silences` (e.g. loaded from a file or backed by the state store).
+    #[must_use]
+    pub fn with_silences(mut self, silences: Silences) -> Self {
+        self.silences = Arc::new(silences);
+        self
+    }
+
+    /// Watch healer `CodeRuns` through `cluster` for the PRs they open and
+    /// gather cluster context for failures.
+    #[must_use]
+    pub fn with_cluster(mut self, cluster: Arc<dyn ClusterClient>) -> Self {
+        self.gatherer = self.gatherer.with_cluster(cluster.clone());
+        self.cluster = Some(cluster);
+        self
+    }
 }
 
 /// Build the HTTP router.
@@ -101,6 +227,18 @@ pub fn build_router(state: Arc<ServerState>) -> Router {
         )
         .route("/api/status", get(status_handler))
         .route("/api/status/{task_id}", get(task_status_handler))
+        .route(
+            "/api/silences",
+            get(list_silences_handler).post(create_silence_handler),
+        )
+        .route("/api/silences/{id}", delete(expire_silence_handler))
+        .route("/api/incidents", get(list_incidents_handler))
+        .route("/api/incidents/{id}", get(get_incident_handler))
+        .route("/api/incidents/{id}/postmortem", get(postmortem_handler))
+        .route("/api/guardrails", get(guardrails_handler))
+        .route("/api/guardrails/reset", post(reset_guardrails_handler))
+        .route("/api/slo", get(slo_handler))
+        .route("/metrics", get(metrics_handler))
         .layer(TraceLayer::new_for_http())
         .with_state(state)
 }
@@ -111,7 +249,34 @@ pub fn build_router(state: Arc<ServerState>) -> Router {
 ///
 /// Returns an error if the server fails to start or bind to the address.
 pub async fn run_server(state: Arc<ServerState>, addr: &str) -> Result<()> {
-    let app = build_router(state);
+    // Platform alerts share the CI server's silences
+    let platform = Arc::new(PlatformServerState::new(
+        &state.namespace,
+        &state.repository,
+        state.silences.clone(),
+    ));
+    // Reverts land on the default branch, not in CI events, so they are
+    // looked for there
+    let reverts = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REVERT_SCAN_INTERVAL);
+        loop {
+            interval.tick().await;
+            scan_reverts(&reverts).await;
+        }
+    });
+
+    // CodeRuns report the PRs they open in their status
+    let prs = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PR_SCAN_INTERVAL);
+        loop {
+            interval.tick().await;
+            scan_remediation_prs(&prs).await;
+        }
+    });
+
+    let app = build_router(state).merge(build_platform_router(platform));
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
     info!("Healer CI remediation server listening on {addr}");
@@ -121,6 +286,34 @@ pub async fn run_server(state: Arc<ServerState>, addr: &str) -> Result<()> {
     Ok(())
 }
 
+/// Record reverts of auto-merged PRs found on the default branch of every
+/// repository healer merged into, feeding the circuit breaker.
+async fn scan_reverts(state: &ServerState) {
+    let Some(github) = &state.github else {
+        return;
+    };
+    if let Err(e) = state.guardrails.refresh().await {
+        warn!("Failed to refresh guardrail state: {e:#}");
+    }
+    state.guardrails.scan_reverts(github.as_ref()).await;
+}
+
+/// Record PRs opened by healer `CodeRuns` on their remediation lifecycles.
+async fn scan_remediation_prs(state: &ServerState) {
+    let (Some(cluster), Some(github)) = (&state.cluster, &state.github) else {
+        return;
+    };
+    if !state.slo.awaiting_prs() {
+        return;
+    }
+    match remediation_prs(cluster.as_ref(), &state.namespace).await {
+        Ok(prs) => {
+            state.slo.record_opened_prs(&prs, github.as_ref()).await;
+        }
+        Err(e) => warn!("Failed to check CodeRuns for opened PRs: {e:#}"),
+    }
+}
+
 // ============================================================================
 // Request/Response types
 // ============================================================================
@@ -163,6 +356,12 @@ pub enum ResponseStatus {
     Accepted,
     /// Request skipped (duplicate, excluded, etc.)
     Skipped,
+    /// Known-flaky tests quarantined instead of remediated
+    Quarantined,
+    /// Suppressed by an active silence or maintenance window
+    Silenced,
+    /// Refused by blast-radius limits or read-only mode
+    Blocked,
     /// Request failed
     Failed,
 }
@@ -186,6 +385,48 @@ struct ConfigStatus {
     memory_enabled: bool,
 }
 
+/// A silence with its current state.
+#[derive(Debug, Serialize)]
+struct SilenceStatus {
+    #[serde(flatten)]
+    silence: crate::silence::Silence,
+    active: bool,
+    expired: bool,
+}
+
+/// An incident without its timeline.
+#[derive(Debug, Serialize)]
+struct IncidentSummary {
+    id: String,
+    key: String,
+    title: String,
+    status: crate::incident::IncidentStatus,
+    opened_at: chrono::DateTime<chrono::Utc>,
+    closed_at: Option<chrono::DateTime<chrono::Utc>>,
+    events: usize,
+}
+
+impl From<&Incident> for IncidentSummary {
+    fn from(incident: &Incident) -> Self {
+        Self {
+            id: incident.id.clone(),
+            key: incident.key.clone(),
+            title: incident.title.clone(),
+            status: incident.status,
+            opened_at: incident.opened_at,
+            closed_at: incident.closed_at,
+            events: incident.events.len(),
+        }
+    }
+}
+
+/// Query for the SLO summary.
+#[derive(Debug, Deserialize)]
+struct SloQuery {
+    /// Window in hours (default 24)
+    window_hours: Option<u32>,
+}
+
 /// Task status response.
 #[derive(Debug, Serialize)]
 struct TaskStatus {
@@ -230,6 +471,56 @@ async fn ci_failure_handler(
         );
     };
 
+    let incident_key =
+        ci_incident_key(&failure.repository, &failure.workflow_name, &failure.branch);
+    let incident_title = format!("{} failing on {}", failure.workflow_name, failure.branch);
+    let run_event = |kind, summary: String| {
+        IncidentEvent::new(kind, summary)
+            .with_detail("workflow_run_id", failure.workflow_run_id.to_string())
+    };
+
+    // Passing runs only feed the flaky test history and close incidents
+    if failure.conclusion == "success" {
+        let mut flakes = state.flakes.write().await;
+        flakes.record_success(&failure);
+        if let Err(e) = flakes.save() {
+            warn!("Failed to save flaky test history: {e}");
+        }
+        drop(flakes);
+        state
+            .slo
+            .record_verified(&failure.repository, &failure.workflow_name, &failure.branch);
+        let passed = run_event(
+            IncidentEventKind::CiRun,
+            format!(
+                "{} run {} passed",
+                failure.workflow_name, failure.workflow_run_id
+            ),
+        );
+        if state
+            .incidents
+            .append(&incident_key, passed)
+            .await
+            .is_some()
+        {
+            let resolved = IncidentEvent::new(
+                IncidentEventKind::Resolved,
+                format!("{} is green on {}", failure.workflow_name, failure.branch),
+            );
+            state.incidents.append(&incident_key, resolved).await;
+        }
+        return (
+            StatusCode::OK,
+            Json(CiFailureResponse {
+                status: ResponseStatus::Skipped,
+                coderun_name: None,
+                agent: None,
+                failure_type: None,
+                reason: Some("Recorded passing run".to_string()),
+            }),
+        );
+    }
+
     // Validate the event
     if !should_process(&failure, &state.config) {
         info!("Skipping CI failure (filtered out)");
@@ -245,8 +536,95 @@ async fn ci_failure_handler(
         );
     }
 
+    // Planned maintenance: count the failure but do not remediate it
+    if let Err(e) = state.silences.refresh().await {
+        warn!("Failed to refresh silences: {e:#}");
+    }
+    let target = AlertTarget {
+        alert_id: "ci-failure".to_string(),
+        namespace: Some(state.namespace.clone()),
+        repository: Some(failure.repository.clone()),
+        service: None,
+        labels: [
+            ("workflow".to_string(), failure.workflow_name.clone()),
+            ("branch".to_string(), failure.branch.clone()),
+        ]
+        .into(),
+    };
+    if let Some(silence) = state.silences.check(&target).await {
+        info!(
+            "Skipping (silenced by {}): {} on {}",
+            silence.id, failure.workflow_name, failure.branch
+        );
+        let silenced = run_event(
+            IncidentEventKind::Silenced,
+            format!(
+                "Run {} silenced: {}",
+                failure.workflow_run_id, silence.reason
+            ),
+        )
+        .with_detail("silence", &silence.id);
+        state.incidents.append(&incident_key, silenced).await;
+        return (
+            StatusCode::OK,
+            Json(CiFailureResponse {
+                status: ResponseStatus::Silenced,
+                coderun_name: None,
+                agent: None,
+                failure_type: None,
+                reason: Some(format!("Silenced: {}", silence.reason)),
+            }),
+        );
+    }
+
+    // Another request (possibly to a previous replica) already owns this run
+    state
+        .tracker
+        .forget_stale(chrono::Duration::minutes(i64::from(
+            state.config.time_window_mins,
+        )))
+        .await;
+    if let Some(tracked) = state.tracker.get(failure.workflow_run_id).await {
+        if matches!(
+            tracked.state.status,
+            RemediationStatus::Pending | RemediationStatus::InProgress
+        ) {
+            info!(
+                "Skipping (in flight): workflow run {} already has remediation {:?}",
+                failure.workflow_run_id, tracked.active_coderun
+            );
+            let deduplicated = run_event(
+                IncidentEventKind::Deduplicated,
+                format!(
+                    "Run {} already has remediation {}",
+                    failure.workflow_run_id,
+                    tracked.active_coderun.as_deref().unwrap_or("pending")
+                ),
+            );
+            state
+                .incidents
+                .record(
+                    &incident_key,
+                    &incident_title,
+                    Some(&failure.repository),
+                    deduplicated,
+                )
+                .await;
+            return (
+                StatusCode::OK,
+                Json(CiFailureResponse {
+                    status: ResponseStatus::Skipped,
+                    coderun_name: tracked.active_coderun,
+                    agent: None,
+                    failure_type: Some(tracked.failure_type.short_name().to_string()),
+                    reason: Some("Remediation already in progress".to_string()),
+                }),
+            );
+        }
+    }
+
     // Gather context
-    let mut ctx = match state.gatherer.gather(&failure) {
+    let mut ctx = match state.gatherer.gather(&failure).await {
         Ok(c) => c,
         Err(e) => {
             error!("Failed to gather context: {e}");
@@ -258,25 +636,142 @@ async fn ci_failure_handler(
         }
     };
 
+    // Record test outcomes and quarantine runs that only failed on flaky tests
+    let flaky = {
+        let mut flakes = state.flakes.write().await;
+        flakes.record_failure(&failure, &ctx.failures);
+        if let Err(e) = flakes.save() {
+            warn!("Failed to save flaky test history: {e}");
+        }
+        flakes.quarantine_candidates(&failure, &ctx.failures)
+    };
+    if let (Some(flaky), Some(quarantiner)) = (flaky, &state.quarantiner) {
+        match quarantiner.quarantine(&failure, &flaky).await {
+            Ok(issues) => {
+                info!(
+                    "Quarantined {} flaky tests for {} (issues {issues:?})",
+                    flaky.len(),
+                    failure.workflow_name
+                );
+                let tests: Vec<&str> = flaky.iter().map(|f| f.test_id.as_str()).collect();
+                for event in [
+                    run_event(
+                        IncidentEventKind::AlertDetected,
+                        format!(
+                            "{} run {} failed",
+                            failure.workflow_name, failure.workflow_run_id
+                        ),
+                    ),
+                    run_event(IncidentEventKind::Quarantined, tests.join(", ")),
+                ] {
+                    state
+                        .incidents
+                        .record(
+                            &incident_key,
+                            &incident_title,
+                            Some(&failure.repository),
+                            event,
+                        )
+                        .await;
+                }
+                return (
+                    StatusCode::OK,
+                    Json(CiFailureResponse {
+                        status: ResponseStatus::Quarantined,
+                        coderun_name: None,
+                        agent: None,
+                        failure_type: None,
+                        reason: Some(format!(
+                            "Known-flaky tests: {}",
+                            flaky
+                                .iter()
+                                .map(|f| f.test_id.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )),
+                    }),
+                );
+            }
+            // Fall through to remediation rather than dropping the failure
+            Err(e) => warn!("Failed to quarantine flaky tests: {e}"),
+        }
+    }
+
     // Classify the failure
-    let failure_type = state.router.classify_failure(&failure, &ctx.workflow_logs);
+    let classification = state
+        .router
+        .classify(&failure, &ctx.failures, &ctx.workflow_logs);
+    let failure_type = classification.failure_type;
     ctx.failure_type = Some(failure_type.clone());
+    ctx.classification_confidence = Some(classification.confidence);
 
-    // Route to agent
-    let agent = state.router.route(&ctx);
+    // Route to agent, letting recorded outcomes override the static mapping
+    let mut agent = state.router.route(&ctx);
+    if let Some(adaptive) = &state.adaptive {
+        if !ctx.is_security_event() {
+            agent = adaptive.select(&failure_type, agent, &[]);
+        }
+    }
 
     info!(
-        "Routing {} ({}) to {:?}",
+        "Routing {} ({}, confidence {:.2}, {} parsed failures) to {:?}",
         failure.workflow_name,
         failure_type.short_name(),
+        classification.confidence,
+        ctx.failures.len(),
         agent
     );
+    let detected = run_event(
+        IncidentEventKind::AlertDetected,
+        format!(
+            "{} run {} failed ({})",
+            failure.workflow_name,
+            failure.workflow_run_id,
+            failure_type.short_name()
+        ),
+    )
+    .with_detail("failure_type", failure_type.short_name())
+    .with_detail("confidence", format!("{:.2}", classification.confidence));
+    state
+        .slo
+        .record_detection(&failure, failure_type.short_name());
+    state
+        .incidents
+        .record(
+            &incident_key,
+            &incident_title,
+            Some(&failure.repository),
+            detected,
+        )
+        .await;
 
     // Spawn CodeRun
     let spawner = state.spawner.read().await;
     match spawner.spawn(agent, &ctx) {
         Ok(coderun_name) => {
             info!("Spawned CodeRun: {coderun_name}");
+            drop(spawner);
+            let run_id = state
+                .tracker
+                .track(
+                    failure.clone(),
+                    failure_type.clone(),
+                    &ctx.failures,
+                    ctx.pr.as_ref().map(|pr| pr.number),
+                )
+                .await;
+            state
+                .tracker
+                .record_spawn(run_id, &coderun_name, agent)
+                .await;
+            state.slo.record_spawn(run_id, agent.name());
+            let spawned = run_event(
+                IncidentEventKind::RemediationSpawned,
+                format!("Spawned {coderun_name} with {}", agent.name()),
+            )
+            .with_detail("agent", agent.name())
+            .with_detail("coderun", &coderun_name);
+            state.incidents.append(&incident_key, spawned).await;
             (
                 StatusCode::ACCEPTED,
                 Json(CiFailureResponse {
@@ -293,6 +788,8 @@ async fn ci_failure_handler(
             let msg = e.to_string();
             if msg.contains("already exists") || msg.contains("Recent remediation") {
                 info!("Skipping (dedup): {msg}");
+                let deduplicated = run_event(IncidentEventKind::Deduplicated, msg.clone());
+                state.incidents.append(&incident_key, deduplicated).await;
                 (
                     StatusCode::OK,
                     Json(CiFailureResponse {
@@ -303,6 +800,18 @@ async fn ci_failure_handler(
                         reason: Some(msg),
                     }),
                 )
+            } else if let Some(blocked) = e.downcast_ref::<Blocked>() {
+                warn!("Skipping (guardrail): {blocked}");
+                (
+                    StatusCode::OK,
+                    Json(CiFailureResponse {
+                        status: ResponseStatus::Blocked,
+                        coderun_name: None,
+                        agent: Some(agent.name().to_string()),
+                        failure_type: Some(failure_type.short_name().to_string()),
+                        reason: Some(blocked.to_string()),
+                    }),
+                )
             } else {
                 error!("Failed to spawn CodeRun: {e}");
                 (
@@ -381,8 +890,8 @@ async fn security_alert_handler(
 async fn status_handler(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
     Json(ServerStatus {
         status: "running",
-        active_remediations: 0, // TODO: Track active remediations
-        total_processed: 0,     // TODO: Track total processed
+        active_remediations: state.tracker.get_active().await.len(),
+        total_processed: 0, // TODO: Track total processed
         config: ConfigStatus {
             cli: state.config.cli.clone(),
             model: state.config.model.clone(),
@@ -408,10 +917,156 @@ async fn task_status_handler(
     })
 }
 
+/// List silences handler.
+async fn list_silences_handler(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    if let Err(e) = state.silences.refresh().await {
+        warn!("Failed to refresh silences: {e:#}");
+    }
+    let now = chrono::Utc::now();
+    let silences: Vec<SilenceStatus> = state
+        .silences
+        .list()
+        .await
+        .into_iter()
+        .map(|silence| SilenceStatus {
+            active: silence.is_active(now),
+            expired: silence.is_expired(now),
+            silence,
+        })
+        .collect();
+    Json(silences)
+}
+
+/// Create silence handler.
+async fn create_silence_handler(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<NewSilence>,
+) -> impl IntoResponse {
+    match state.silences.create(request).await {
+        Ok(silence) => (StatusCode::CREATED, Json(serde_json::json!(silence))),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": format!("{e:#}") })),
+        ),
+    }
+}
+
+/// Expire silence handler.
+async fn expire_silence_handler(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = state.silences.refresh().await {
+        warn!("Failed to refresh silences: {e:#}");
+    }
+    match state.silences.expire(&id).await {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(e) => {
+            error!("Failed to expire silence {id}: {e:#}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// List incidents handler.
+async fn list_incidents_handler(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    if let Err(e) = state.incidents.refresh().await {
+        warn!("Failed to refresh incidents: {e:#}");
+    }
+    let incidents: Vec<IncidentSummary> = state
+        .incidents
+        .list()
+        .await
+        .iter()
+        .map(IncidentSummary::from)
+        .collect();
+    Json(incidents)
+}
+
+/// Incident timeline handler.
+async fn get_incident_handler(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match find_incident(&state, &id).await {
+        Some(incident) => Json(incident).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Postmortem export handler (Markdown).
+async fn postmortem_handler(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match find_incident(&state, &id).await {
+        Some(incident) => (
+            [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+            render_postmortem(&incident, chrono::Utc::now()),
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Guardrail status handler.
+async fn guardrails_handler(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    if let Err(e) = state.guardrails.refresh().await {
+        warn!("Failed to refresh guardrail state: {e:#}");
+    }
+    Json(state.guardrails.status())
+}
+
+/// Reset the circuit breaker, leaving read-only mode.
+async fn reset_guardrails_handler(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    state.guardrails.reset().await;
+    Json(state.guardrails.status())
+}
+
+/// Rolling remediation SLO summary.
+async fn slo_handler(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<SloQuery>,
+) -> Json<SloSummary> {
+    follow_alert_lifecycles(&state).await;
+    Json(state.slo.summary(query.window_hours.unwrap_or(24)))
+}
+
+/// Prometheus metrics.
+async fn metrics_handler(State(state): State<Arc<ServerState>>) -> impl IntoResponse {
+    follow_alert_lifecycles(&state).await;
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.slo.render(),
+    )
+}
+
 // ============================================================================
 // Helper functions
 // ============================================================================
 
+/// Bring alert-driven remediations the alert watch recorded on incidents
+/// into the SLO metrics.
+async fn follow_alert_lifecycles(state: &ServerState) {
+    if let Err(e) = state.incidents.refresh().await {
+        warn!("Failed to refresh incidents: {e:#}");
+    }
+    state.slo.record_incidents(&state.incidents.list().await);
+}
+
+/// Look an incident up, reloading from the state store on a miss (it may
+/// have been opened by the alert watch or another replica).
+async fn find_incident(state: &ServerState, id: &str) -> Option<Incident> {
+    if let Some(incident) = state.incidents.get(id).await {
+        return Some(incident);
+    }
+    if let Err(e) = state.incidents.refresh().await {
+        warn!("Failed to refresh incidents: {e:#}");
+    }
+    state.incidents.get(id).await
+}
+
 /// Parse a CI failure from the webhook event.
 fn parse_ci_failure(event: &serde_json::Value) -> Option<CiFailure> {
     // Try workflow_job format first
@@ -419,6 +1074,11 @@ fn parse_ci_failure(event: &serde_json::Value) -> Option<CiFailure> {
         return CiFailure::from_workflow_job(event);
     }
 
+    // Try workflow_run format
+    if event.get("workflow_run").is_some() {
+        return CiFailure::from_workflow_run(event);
+    }
+
     // Try check_run format
     if event.get("check_run").is_some() {
         return CiFailure::from_check_run(event);
@@ -430,6 +1090,9 @@ fn parse_ci_failure(event: &serde_json::Value) -> Option<CiFailure> {
         if body.get("workflow_job").is_some() {
             return CiFailure::from_workflow_job(body);
         }
+        if body.get("workflow_run").is_some() {
+            return CiFailure::from_workflow_run(body);
+        }
         if body.get("check_run").is_some() {
             return CiFailure::from_check_run(body);
         }
@@ -586,6 +1249,71 @@ mod tests {
         assert_eq!(failure.conclusion, "failure");
     }
 
+    #[test]
+    fn test_sensor_passing_run_reaches_flake_history() {
+        use super::super::flaky::{test_id, FlakyTestConfig};
+        use super::super::parsers::parse_ci_output;
+        use crate::sensors::WorkflowFailure;
+
+        let failed_job = CiFailure {
+            workflow_run_id: 100,
+            workflow_name: "Controller CI".to_string(),
+            job_name: Some("test".to_string()),
+            conclusion: "failure".to_string(),
+            branch: "main".to_string(),
+            head_sha: "abc".to_string(),
+            commit_message: String::new(),
+            html_url: "https://github.com/5dlabs/cto/actions/runs/100".to_string(),
+            repository: "5dlabs/cto".to_string(),
+            sender: "developer".to_string(),
+            detected_at: chrono::Utc::now(),
+            raw_event: None,
+        };
+        let items = parse_ci_output(
+            "---- tests::test_watch_reconnects stdout ----\n\
+             thread 'tests::test_watch_reconnects' panicked at src/watch.rs:88:9:\n\
+             failures:\n    tests::test_watch_reconnects\n",
+        );
+        let id = test_id(&items[0]).expect("test failure");
+        let mut flakes = FlakeTracker::new(FlakyTestConfig::default());
+        flakes.record_failure(&failed_job, &items);
+
+        let run = WorkflowFailure {
+            run_id: 101,
+            workflow_name: "Controller CI".to_string(),
+            job_name: None,
+            job_id: None,
+            branch: "main".to_string(),
+            head_sha: "abc".to_string(),
+            commit_message: "Retry".to_string(),
+            repository: "5dlabs/cto".to_string(),
+            html_url: "https://github.com/5dlabs/cto/actions/runs/101".to_string(),
+            job_url: None,
+            actor: "developer".to_string(),
+            run_started_at: chrono::Utc::now(),
+            detected_at: chrono::Utc::now(),
+            conclusion: "success".to_string(),
+        };
+        let event = run.to_workflow_run_event();
+
+        // Delivered directly by the sensor and wrapped by Argo Events
+        for event in [event.clone(), serde_json::json!({ "body": event })] {
+            let passing = parse_ci_failure(&event).expect("Should parse");
+            assert_eq!(passing.workflow_run_id, 101);
+            assert_eq!(passing.conclusion, "success");
+            assert_eq!(passing.job_name, None);
+            assert_eq!(passing.commit_message, "Retry");
+            assert_eq!(passing.sender, "developer");
+            flakes.record_success(&passing);
+        }
+
+        let history = flakes.history("5dlabs/cto", "main", &id).unwrap();
+        assert_eq!(
+            history.runs.iter().map(|r| r.passed).collect::<Vec<_>>(),
+            vec![false, true]
+        );
+    }
+
     #[test]
     fn test_should_process() {
         let config = RemediationConfig::default();
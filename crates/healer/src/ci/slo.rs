@@ -0,0 +1,999 @@
+//! Remediation SLO metrics.
+//!
+//! Each remediation is followed through its lifecycle (detection, spawn,
+//! PR opened, fix, merge, verification) to derive:
+//! - Detection latency: from the CI job failing to healer receiving it
+//! - Time from detection to each later stage; detection to merge is MTTR
+//! - First-attempt and overall fix success rates per alert type and agent
+//!
+//! [`SloMetrics::render`] exposes counters and histograms in the Prometheus
+//! text format for `/metrics`, and [`SloMetrics::summary`] rolls recent
+//! lifecycles up for `/api/slo`. Metrics are in-memory and reset on restart.
+//! Alert-driven remediations are followed from the incidents the alert watch
+//! records ([`SloMetrics::record_incidents`]).
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use tracing::warn;
+
+use super::types::CiFailure;
+use crate::clients::GitHubClient;
+use crate::incident::{Incident, IncidentEventKind};
+
+/// Detection latency buckets (seconds).
+const DETECTION_BUCKETS: &[f64] = &[5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0];
+
+/// Detection-to-stage buckets (seconds), up to a day.
+const STAGE_BUCKETS: &[f64] = &[
+    60.0, 300.0, 600.0, 1800.0, 3600.0, 7200.0, 14400.0, 43200.0, 86400.0,
+];
+
+/// How long lifecycles are kept for the rolling summary.
+const RETENTION_DAYS: i64 = 7;
+
+/// Upper bound on retained lifecycles.
+const MAX_LIFECYCLES: usize = 5000;
+
+/// A lifecycle stage measured from detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    /// First `CodeRun` spawned
+    Spawned,
+    /// A `CodeRun` opened the fix PR
+    PrOpened,
+    /// An agent attempt succeeded
+    Fixed,
+    /// The fix PR was auto-merged
+    Merged,
+    /// CI passed again on the branch after remediation started
+    Verified,
+}
+
+impl Stage {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Spawned => "spawned",
+            Self::PrOpened => "pr_opened",
+            Self::Fixed => "fixed",
+            Self::Merged => "merged",
+            Self::Verified => "verified",
+        }
+    }
+}
+
+/// One remediation from detection onwards.
+#[derive(Debug, Clone, Serialize)]
+pub struct Lifecycle {
+    /// `run-<id>` for CI failures, the incident id for alerts
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_run_id: Option<u64>,
+    pub repository: String,
+    pub workflow: String,
+    pub branch: String,
+    pub alert_type: String,
+    /// When the CI job finished, if the event said
+    pub failed_at: Option<DateTime<Utc>>,
+    pub detected_at: DateTime<Utc>,
+    pub spawned_at: Option<DateTime<Utc>>,
+    pub pr_opened_at: Option<DateTime<Utc>>,
+    pub fixed_at: Option<DateTime<Utc>>,
+    pub merged_at: Option<DateTime<Utc>>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub escalated_at: Option<DateTime<Utc>>,
+    /// Agent of the first attempt
+    pub first_agent: Option<String>,
+    /// Whether the first attempt fixed it (`None` until it completes)
+    pub first_attempt_succeeded: Option<bool>,
+    pub attempts: u32,
+}
+
+impl Lifecycle {
+    fn stage_at(&self, stage: Stage) -> Option<DateTime<Utc>> {
+        match stage {
+            Stage::Spawned => self.spawned_at,
+            Stage::PrOpened => self.pr_opened_at,
+            Stage::Fixed => self.fixed_at,
+            Stage::Merged => self.merged_at,
+            Stage::Verified => self.verified_at,
+        }
+    }
+
+    /// Time from the CI job failing to detection.
+    pub fn detection_latency(&self) -> Option<Duration> {
+        self.failed_at
+            .map(|failed| (self.detected_at - failed).max(Duration::zero()))
+    }
+
+    /// Time from detection to `stage`, if reached.
+    pub fn time_to(&self, stage: Stage) -> Option<Duration> {
+        self.stage_at(stage)
+            .map(|at| (at - self.detected_at).max(Duration::zero()))
+    }
+}
+
+/// A Prometheus histogram with cumulative bucket counts.
+#[derive(Debug, Clone)]
+struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: Duration) {
+        #[allow(clippy::cast_precision_loss)] // Millisecond precision is plenty
+        let secs = value.num_milliseconds() as f64 / 1000.0;
+        for (bound, count) in self.bounds.iter().zip(&mut self.counts) {
+            if secs <= *bound {
+                *count += 1;
+            }
+        }
+        self.sum += secs;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {count}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{{{labels}}} {}", self.count);
+    }
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    lifecycles: HashMap<String, Lifecycle>,
+    /// By alert type
+    detection: BTreeMap<String, Histogram>,
+    /// By alert type and stage
+    stages: BTreeMap<(String, Stage), Histogram>,
+    /// By alert type, agent, first attempt, success
+    attempts: BTreeMap<(String, String, bool, bool), u64>,
+    /// By alert type and result
+    resolved: BTreeMap<(String, &'static str), u64>,
+}
+
+impl MetricsState {
+    /// Set `stage` on a lifecycle (first time only) and observe its duration.
+    fn reach(&mut self, id: &str, stage: Stage, now: DateTime<Utc>) {
+        let Some(lifecycle) = self.lifecycles.get_mut(id) else {
+            return;
+        };
+        let slot = match stage {
+            Stage::Spawned => &mut lifecycle.spawned_at,
+            Stage::PrOpened => &mut lifecycle.pr_opened_at,
+            Stage::Fixed => &mut lifecycle.fixed_at,
+            Stage::Merged => &mut lifecycle.merged_at,
+            Stage::Verified => &mut lifecycle.verified_at,
+        };
+        if slot.is_some() {
+            return;
+        }
+        *slot = Some(now);
+        let elapsed = (now - lifecycle.detected_at).max(Duration::zero());
+        self.stages
+            .entry((lifecycle.alert_type.clone(), stage))
+            .or_insert_with(|| Histogram::new(STAGE_BUCKETS))
+            .observe(elapsed);
+    }
+
+    /// Count a completed agent attempt; the first success fixes it.
+    fn attempt(&mut self, id: &str, agent: &str, success: bool, now: DateTime<Utc>) {
+        let Some(lifecycle) = self.lifecycles.get_mut(id) else {
+            return;
+        };
+        let first = lifecycle.attempts == 0;
+        lifecycle.attempts += 1;
+        if first {
+            lifecycle.first_attempt_succeeded = Some(success);
+        }
+        let alert_type = lifecycle.alert_type.clone();
+        let already_fixed = lifecycle.fixed_at.is_some();
+        *self
+            .attempts
+            .entry((alert_type.clone(), agent.to_string(), first, success))
+            .or_default() += 1;
+        if success && !already_fixed {
+            self.reach(id, Stage::Fixed, now);
+            self.resolve(&alert_type, "fixed");
+        }
+    }
+
+    fn escalate(&mut self, id: &str, now: DateTime<Utc>) {
+        let Some(lifecycle) = self.lifecycles.get_mut(id) else {
+            return;
+        };
+        if lifecycle.escalated_at.is_some() {
+            return;
+        }
+        lifecycle.escalated_at = Some(now);
+        let alert_type = lifecycle.alert_type.clone();
+        self.resolve(&alert_type, "escalated");
+    }
+
+    fn resolve(&mut self, alert_type: &str, result: &'static str) {
+        *self
+            .resolved
+            .entry((alert_type.to_string(), result))
+            .or_default() += 1;
+    }
+
+    fn prune(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - Duration::days(RETENTION_DAYS);
+        self.lifecycles.retain(|_, l| l.detected_at >= cutoff);
+        if self.lifecycles.len() > MAX_LIFECYCLES {
+            let mut by_age: Vec<(String, DateTime<Utc>)> = self
+                .lifecycles
+                .values()
+                .map(|l| (l.id.clone(), l.detected_at))
+                .collect();
+            by_age.sort_by_key(|(_, at)| *at);
+            let excess = self.lifecycles.len() - MAX_LIFECYCLES;
+            for (id, _) in by_age.into_iter().take(excess) {
+                self.lifecycles.remove(&id);
+            }
+        }
+    }
+}
+
+/// Count, mean and percentiles of a set of durations (seconds).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DurationStats {
+    pub count: usize,
+    pub mean_secs: Option<f64>,
+    pub p50_secs: Option<f64>,
+    pub p95_secs: Option<f64>,
+}
+
+impl DurationStats {
+    #[allow(clippy::cast_precision_loss)] // Sample counts are small
+    fn from_durations(durations: impl Iterator<Item = Duration>) -> Self {
+        let mut secs: Vec<f64> = durations.map(|d| d.num_seconds() as f64).collect();
+        if secs.is_empty() {
+            return Self::default();
+        }
+        secs.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let rank = ((p * secs.len() as f64).ceil() as usize).clamp(1, secs.len());
+            secs[rank - 1]
+        };
+        Self {
+            count: secs.len(),
+            mean_secs: Some(secs.iter().sum::<f64>() / secs.len() as f64),
+            p50_secs: Some(percentile(0.5)),
+            p95_secs: Some(percentile(0.95)),
+        }
+    }
+}
+
+/// First-attempt success for one alert type and agent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FirstAttemptRate {
+    pub alert_type: String,
+    pub agent: String,
+    pub attempts: usize,
+    pub successes: usize,
+    pub rate: f64,
+}
+
+/// Rolling SLO summary over remediations detected in a window.
+#[derive(Debug, Clone, Serialize)]
+pub struct SloSummary {
+    pub window_hours: u32,
+    pub detected: usize,
+    pub fixed: usize,
+    pub escalated: usize,
+    pub in_progress: usize,
+    /// Fixed / (fixed + escalated)
+    pub fix_success_rate: Option<f64>,
+    pub detection_latency: DurationStats,
+    pub time_to_spawn: DurationStats,
+    pub time_to_pr: DurationStats,
+    pub time_to_fix: DurationStats,
+    /// Detection to merged fix
+    pub mttr: DurationStats,
+    pub time_to_verify: DurationStats,
+    pub first_attempt_success: Vec<FirstAttemptRate>,
+}
+
+/// Remediation lifecycle metrics.
+#[derive(Debug, Default)]
+pub struct SloMetrics {
+    state: Mutex<MetricsState>,
+}
+
+impl SloMetrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MetricsState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Start a lifecycle for a classified failure. Repeat deliveries of the
+    /// same run are ignored.
+    pub fn record_detection(&self, failure: &CiFailure, alert_type: &str) {
+        let mut state = self.lock();
+        let id = run_key(failure.workflow_run_id);
+        if state.lifecycles.contains_key(&id) {
+            return;
+        }
+        let lifecycle = Lifecycle {
+            id: id.clone(),
+            workflow_run_id: Some(failure.workflow_run_id),
+            repository: failure.repository.clone(),
+            workflow: failure.workflow_name.clone(),
+            branch: failure.branch.clone(),
+            alert_type: alert_type.to_string(),
+            failed_at: failed_at(failure),
+            detected_at: failure.detected_at,
+            spawned_at: None,
+            pr_opened_at: None,
+            fixed_at: None,
+            merged_at: None,
+            verified_at: None,
+            escalated_at: None,
+            first_agent: None,
+            first_attempt_succeeded: None,
+            attempts: 0,
+        };
+        if let Some(latency) = lifecycle.detection_latency() {
+            state
+                .detection
+                .entry(alert_type.to_string())
+                .or_insert_with(|| Histogram::new(DETECTION_BUCKETS))
+                .observe(latency);
+        }
+        state.lifecycles.insert(id, lifecycle);
+        state.prune(failure.detected_at);
+    }
+
+    /// Record the first `CodeRun` spawned for a run.
+    pub fn record_spawn(&self, workflow_run_id: u64, agent: &str) {
+        self.record_spawn_at(workflow_run_id, agent, Utc::now());
+    }
+
+    fn record_spawn_at(&self, workflow_run_id: u64, agent: &str, now: DateTime<Utc>) {
+        let mut state = self.lock();
+        let id = run_key(workflow_run_id);
+        if let Some(lifecycle) = state.lifecycles.get_mut(&id) {
+            lifecycle
+                .first_agent
+                .get_or_insert_with(|| agent.to_string());
+        }
+        state.reach(&id, Stage::Spawned, now);
+    }
+
+    /// Record a run's fix PR as opened at `at`. Repeat sightings are ignored.
+    fn record_pr_opened(&self, workflow_run_id: u64, at: DateTime<Utc>) {
+        self.lock()
+            .reach(&run_key(workflow_run_id), Stage::PrOpened, at);
+    }
+
+    /// Whether a spawned remediation is still waiting for its fix PR.
+    #[must_use]
+    pub fn awaiting_prs(&self) -> bool {
+        self.lock()
+            .lifecycles
+            .values()
+            .any(|l| l.workflow_run_id.is_some() && awaits_pr(l))
+    }
+
+    /// Record the PRs opened by `CodeRuns` (workflow run id and PR URL) at
+    /// the time GitHub created them. Returns how many were recorded.
+    pub async fn record_opened_prs(
+        &self,
+        prs: &[(u64, String)],
+        github: &dyn GitHubClient,
+    ) -> usize {
+        let mut recorded = 0;
+        for (workflow_run_id, url) in prs {
+            let waiting = self
+                .lock()
+                .lifecycles
+                .get(&run_key(*workflow_run_id))
+                .is_some_and(awaits_pr);
+            if !waiting {
+                continue;
+            }
+            let Some((repo, number)) = parse_pr_url(url) else {
+                warn!("Run {workflow_run_id} reported an unrecognized PR URL: {url}");
+                continue;
+            };
+            match github.get_pull_request(&repo, number).await {
+                Ok(Some(pr)) => {
+                    if let Some(created_at) = pr.created_at {
+                        self.record_pr_opened(*workflow_run_id, created_at);
+                        recorded += 1;
+                    }
+                }
+                Ok(None) => warn!("PR {url} of run {workflow_run_id} not found"),
+                Err(e) => warn!("Failed to get PR {url} of run {workflow_run_id}: {e:#}"),
+            }
+        }
+        recorded
+    }
+
+    /// Record a completed agent attempt.
+    pub fn record_attempt(&self, workflow_run_id: u64, agent: &str, success: bool) {
+        self.record_attempt_at(workflow_run_id, agent, success, Utc::now());
+    }
+
+    fn record_attempt_at(
+        &self,
+        workflow_run_id: u64,
+        agent: &str,
+        success: bool,
+        now: DateTime<Utc>,
+    ) {
+        self.lock()
+            .attempt(&run_key(workflow_run_id), agent, success, now);
+    }
+
+    /// Record the fix PR being auto-merged.
+    pub fn record_merge(&self, workflow_run_id: u64) {
+        self.lock()
+            .reach(&run_key(workflow_run_id), Stage::Merged, Utc::now());
+    }
+
+    /// Record a remediation handed to a human.
+    pub fn record_escalation(&self, workflow_run_id: u64) {
+        self.lock().escalate(&run_key(workflow_run_id), Utc::now());
+    }
+
+    /// Follow alert-driven remediations from their incidents, e.g. ones the
+    /// alert watch recorded through the shared state store. Only incidents
+    /// whose detection names an alert are followed, and stages and attempts
+    /// already counted are not counted again.
+    pub fn record_incidents(&self, incidents: &[Incident]) {
+        let now = Utc::now();
+        let cutoff = now - Duration::days(RETENTION_DAYS);
+        let mut state = self.lock();
+        for incident in incidents {
+            let Some((detected, alert_type)) = incident
+                .events_of(IncidentEventKind::AlertDetected)
+                .find_map(|e| Some((e, e.details.get("alert")?)))
+            else {
+                continue;
+            };
+            if detected.at < cutoff {
+                continue;
+            }
+            let id = incident.id.clone();
+            state
+                .lifecycles
+                .entry(id.clone())
+                .or_insert_with(|| Lifecycle {
+                    id: id.clone(),
+                    workflow_run_id: None,
+                    repository: incident.repository.clone().unwrap_or_default(),
+                    workflow: String::new(),
+                    branch: String::new(),
+                    alert_type: alert_type.clone(),
+                    failed_at: None,
+                    detected_at: detected.at,
+                    spawned_at: None,
+                    pr_opened_at: None,
+                    fixed_at: None,
+                    merged_at: None,
+                    verified_at: None,
+                    escalated_at: None,
+                    first_agent: None,
+                    first_attempt_succeeded: None,
+                    attempts: 0,
+                });
+
+            let Some(spawned) = incident
+                .events_of(IncidentEventKind::RemediationSpawned)
+                .next()
+            else {
+                continue;
+            };
+            if let (Some(lifecycle), Some(agent)) =
+                (state.lifecycles.get_mut(&id), spawned.details.get("agent"))
+            {
+                lifecycle.first_agent.get_or_insert_with(|| agent.clone());
+            }
+            state.reach(&id, Stage::Spawned, spawned.at);
+
+            let counted = state.lifecycles.get(&id).map_or(0, |l| l.attempts as usize);
+            for completed in incident
+                .events_of(IncidentEventKind::RemediationCompleted)
+                .skip(counted)
+            {
+                let agent = completed
+                    .details
+                    .get("agent")
+                    .map_or("unknown", String::as_str);
+                let success = completed
+                    .details
+                    .get("result")
+                    .is_some_and(|result| result == "success");
+                state.attempt(&id, agent, success, completed.at);
+            }
+            if let Some(escalated) = incident.events_of(IncidentEventKind::Escalated).next() {
+                state.escalate(&id, escalated.at);
+            }
+            // An incident closed for inactivity says nothing about the fix
+            if let Some(resolved) = incident
+                .events_of(IncidentEventKind::Resolved)
+                .find(|e| !e.details.contains_key("idle"))
+            {
+                state.reach(&id, Stage::Verified, resolved.at);
+            }
+        }
+        state.prune(now);
+    }
+
+    /// Record CI passing on a branch: verifies every started, unverified
+    /// remediation of that workflow there. Returns how many were verified.
+    pub fn record_verified(&self, repository: &str, workflow: &str, branch: &str) -> usize {
+        self.record_verified_at(repository, workflow, branch, Utc::now())
+    }
+
+    fn record_verified_at(
+        &self,
+        repository: &str,
+        workflow: &str,
+        branch: &str,
+        now: DateTime<Utc>,
+    ) -> usize {
+        let mut state = self.lock();
+        let pending: Vec<String> = state
+            .lifecycles
+            .values()
+            .filter(|l| {
+                l.spawned_at.is_some()
+                    && l.verified_at.is_none()
+                    && l.repository == repository
+                    && l.workflow == workflow
+                    && l.branch == branch
+            })
+            .map(|l| l.id.clone())
+            .collect();
+        for id in &pending {
+            state.reach(id, Stage::Verified, now);
+        }
+        pending.len()
+    }
+
+    /// Roll up lifecycles detected within `window_hours` of now.
+    pub fn summary(&self, window_hours: u32) -> SloSummary {
+        self.summary_at(window_hours, Utc::now())
+    }
+
+    #[allow(clippy::cast_precision_loss)] // Counts are small
+    fn summary_at(&self, window_hours: u32, now: DateTime<Utc>) -> SloSummary {
+        let state = self.lock();
+        let cutoff = now - Duration::hours(i64::from(window_hours));
+        let recent: Vec<&Lifecycle> = state
+            .lifecycles
+            .values()
+            .filter(|l| l.detected_at >= cutoff)
+            .collect();
+
+        let fixed = recent.iter().filter(|l| l.fixed_at.is_some()).count();
+        let escalated = recent
+            .iter()
+            .filter(|l| l.fixed_at.is_none() && l.escalated_at.is_some())
+            .count();
+        let stage =
+            |stage| DurationStats::from_durations(recent.iter().filter_map(|l| l.time_to(stage)));
+
+        let mut rates: BTreeMap<(String, String), (usize, usize)> = BTreeMap::new();
+        for lifecycle in &recent {
+            if let (Some(agent), Some(succeeded)) =
+                (&lifecycle.first_agent, lifecycle.first_attempt_succeeded)
+            {
+                let entry = rates
+                    .entry((lifecycle.alert_type.clone(), agent.clone()))
+                    .or_default();
+                entry.0 += 1;
+                entry.1 += usize::from(succeeded);
+            }
+        }
+
+        SloSummary {
+            window_hours,
+            detected: recent.len(),
+            fixed,
+            escalated,
+            in_progress: recent.len() - fixed - escalated,
+            fix_success_rate: (fixed + escalated > 0)
+                .then(|| fixed as f64 / (fixed + escalated) as f64),
+            detection_latency: DurationStats::from_durations(
+                recent.iter().filter_map(|l| l.detection_latency()),
+            ),
+            time_to_spawn: stage(Stage::Spawned),
+            time_to_pr: stage(Stage::PrOpened),
+            time_to_fix: stage(Stage::Fixed),
+            mttr: stage(Stage::Merged),
+            time_to_verify: stage(Stage::Verified),
+            first_attempt_success: rates
+                .into_iter()
+                .map(
+                    |((alert_type, agent), (attempts, successes))| FirstAttemptRate {
+                        alert_type,
+                        agent,
+                        attempts,
+                        successes,
+                        rate: successes as f64 / attempts as f64,
+                    },
+                )
+                .collect(),
+        }
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let state = self.lock();
+        let mut out = String::with_capacity(4096);
+
+        let _ = writeln!(
+            out,
+            "# HELP healer_detection_latency_seconds Time from a CI job failing to healer detecting it."
+        );
+        let _ = writeln!(out, "# TYPE healer_detection_latency_seconds histogram");
+        for (alert_type, histogram) in &state.detection {
+            histogram.render(
+                &mut out,
+                "healer_detection_latency_seconds",
+                &format!("alert_type=\"{}\"", escape_label(alert_type)),
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP healer_remediation_stage_seconds Time from detection to each remediation stage (merged = MTTR)."
+        );
+        let _ = writeln!(out, "# TYPE healer_remediation_stage_seconds histogram");
+        for ((alert_type, stage), histogram) in &state.stages {
+            histogram.render(
+                &mut out,
+                "healer_remediation_stage_seconds",
+                &format!(
+                    "alert_type=\"{}\",stage=\"{}\"",
+                    escape_label(alert_type),
+                    stage.name()
+                ),
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP healer_remediation_attempts_total Completed agent attempts by alert type, agent, attempt and result."
+        );
+        let _ = writeln!(out, "# TYPE healer_remediation_attempts_total counter");
+        for ((alert_type, agent, first, success), count) in &state.attempts {
+            let _ = writeln!(
+                out,
+                "healer_remediation_attempts_total{{alert_type=\"{}\",agent=\"{}\",attempt=\"{}\",result=\"{}\"}} {count}",
+                escape_label(alert_type),
+                escape_label(agent),
+                if *first { "first" } else { "retry" },
+                if *success { "success" } else { "failure" }
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP healer_remediations_total Remediations by alert type and final result."
+        );
+        let _ = writeln!(out, "# TYPE healer_remediations_total counter");
+        for ((alert_type, result), count) in &state.resolved {
+            let _ = writeln!(
+                out,
+                "healer_remediations_total{{alert_type=\"{}\",result=\"{result}\"}} {count}",
+                escape_label(alert_type)
+            );
+        }
+
+        out
+    }
+}
+
+/// Lifecycle key for a CI workflow run.
+/// Whether `lifecycle` was spawned and has neither a PR nor an outcome yet.
+fn awaits_pr(lifecycle: &Lifecycle) -> bool {
+    lifecycle.spawned_at.is_some()
+        && lifecycle.pr_opened_at.is_none()
+        && lifecycle.merged_at.is_none()
+        && lifecycle.escalated_at.is_none()
+}
+
+/// Split `https://github.com/<owner>/<repo>/pull/<number>` into repository
+/// and PR number.
+fn parse_pr_url(url: &str) -> Option<(String, u64)> {
+    let path = url.trim_end_matches('/').split("github.com/").nth(1)?;
+    let mut parts = path.split('/');
+    let (owner, repo) = (parts.next()?, parts.next()?);
+    if parts.next()? != "pull" {
+        return None;
+    }
+    Some((format!("{owner}/{repo}"), parts.next()?.parse().ok()?))
+}
+
+fn run_key(workflow_run_id: u64) -> String {
+    format!("run-{workflow_run_id}")
+}
+
+/// When the failing job finished, from the raw webhook event.
+fn failed_at(failure: &CiFailure) -> Option<DateTime<Utc>> {
+    let event = failure.raw_event.as_ref()?;
+    ["workflow_job", "check_run"]
+        .iter()
+        .find_map(|key| event.get(key)?.get("completed_at")?.as_str()?.parse().ok())
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn failure(run_id: u64, detected_at: DateTime<Utc>) -> CiFailure {
+        CiFailure {
+            workflow_run_id: run_id,
+            workflow_name: "Controller CI".to_string(),
+            job_name: Some("lint-rust".to_string()),
+            conclusion: "failure".to_string(),
+            branch: "main".to_string(),
+            head_sha: "abc123".to_string(),
+            commit_message: "test".to_string(),
+            html_url: String::new(),
+            repository: "5dlabs/cto".to_string(),
+            sender: "developer".to_string(),
+            detected_at,
+            raw_event: Some(serde_json::json!({
+                "workflow_job": {
+                    "completed_at": (detected_at - Duration::seconds(40)).to_rfc3339()
+                }
+            })),
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_summary() {
+        let t0 = Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap();
+        let slo = SloMetrics::new();
+
+        // Fixed on the first attempt, merged and verified
+        slo.record_detection(&failure(1, t0), "clippy");
+        slo.record_spawn_at(1, "rex", t0 + Duration::minutes(1));
+        slo.lock()
+            .reach("run-1", Stage::PrOpened, t0 + Duration::minutes(15));
+        slo.record_attempt_at(1, "rex", true, t0 + Duration::minutes(20));
+        slo.lock()
+            .reach("run-1", Stage::Merged, t0 + Duration::minutes(30));
+        assert_eq!(
+            slo.record_verified_at(
+                "5dlabs/cto",
+                "Controller CI",
+                "main",
+                t0 + Duration::hours(1)
+            ),
+            1
+        );
+
+        // Failed once, then escalated
+        slo.record_detection(&failure(2, t0), "clippy");
+        slo.record_spawn_at(2, "rex", t0 + Duration::minutes(1));
+        slo.record_attempt_at(2, "rex", false, t0 + Duration::minutes(10));
+        slo.record_escalation(2);
+
+        // Duplicate delivery does not restart the lifecycle
+        slo.record_detection(&failure(1, t0 + Duration::minutes(5)), "clippy");
+
+        let summary = slo.summary_at(24, t0 + Duration::hours(2));
+        assert_eq!(summary.detected, 2);
+        assert_eq!(summary.fixed, 1);
+        assert_eq!(summary.escalated, 1);
+        assert_eq!(summary.fix_success_rate, Some(0.5));
+        assert_eq!(summary.detection_latency.p50_secs, Some(40.0));
+        assert_eq!(summary.time_to_pr.mean_secs, Some(900.0));
+        assert_eq!(summary.mttr.count, 1);
+        assert_eq!(summary.mttr.mean_secs, Some(1800.0));
+        assert_eq!(summary.time_to_verify.p95_secs, Some(3600.0));
+        assert_eq!(
+            summary.first_attempt_success,
+            vec![FirstAttemptRate {
+                alert_type: "clippy".to_string(),
+                agent: "rex".to_string(),
+                attempts: 2,
+                successes: 1,
+                rate: 0.5,
+            }]
+        );
+
+        // Outside the window
+        assert_eq!(slo.summary_at(1, t0 + Duration::hours(2)).detected, 0);
+    }
+
+    #[test]
+    fn test_green_run_verifies_started_remediation() {
+        let t0 = Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap();
+        let slo = SloMetrics::new();
+        slo.record_detection(&failure(1, t0), "clippy");
+        slo.record_detection(&failure(2, t0), "clippy");
+        slo.record_spawn_at(1, "rex", t0 + Duration::minutes(1));
+
+        let later = t0 + Duration::minutes(40);
+        assert_eq!(
+            slo.record_verified_at("5dlabs/cto", "Controller CI", "develop", later),
+            0
+        );
+        // Only the run healer acted on is verified, and only once
+        assert_eq!(
+            slo.record_verified_at("5dlabs/cto", "Controller CI", "main", later),
+            1
+        );
+        assert_eq!(
+            slo.record_verified_at("5dlabs/cto", "Controller CI", "main", later),
+            0
+        );
+        let summary = slo.summary_at(24, later);
+        assert_eq!(summary.time_to_verify.mean_secs, Some(2400.0));
+    }
+
+    #[tokio::test]
+    async fn test_opened_prs_use_their_creation_time() {
+        use crate::clients::{FakeGitHub, PullRequest};
+
+        let t0 = Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap();
+        let github = FakeGitHub::new().with_pull_request(
+            "5dlabs/cto",
+            PullRequest {
+                number: 7,
+                title: "fix: clippy".to_string(),
+                head_branch: "healer/fix-1".to_string(),
+                open: true,
+                merged: false,
+                created_at: Some(t0 + Duration::minutes(12)),
+                ..PullRequest::default()
+            },
+        );
+        let slo = SloMetrics::new();
+        assert!(!slo.awaiting_prs());
+        slo.record_detection(&failure(1, t0), "clippy");
+        slo.record_spawn_at(1, "rex", t0 + Duration::minutes(1));
+        assert!(slo.awaiting_prs());
+
+        let prs = vec![
+            (1, "https://github.com/5dlabs/cto/pull/7".to_string()),
+            // Not tracked here
+            (2, "https://github.com/5dlabs/cto/pull/8".to_string()),
+        ];
+        assert_eq!(slo.record_opened_prs(&prs, &github).await, 1);
+        assert!(!slo.awaiting_prs());
+        assert_eq!(
+            slo.lock().lifecycles["run-1"].pr_opened_at,
+            Some(t0 + Duration::minutes(12))
+        );
+
+        // Later scans leave it alone
+        assert_eq!(slo.record_opened_prs(&prs, &github).await, 0);
+    }
+
+    #[test]
+    fn test_parse_pr_url() {
+        assert_eq!(
+            parse_pr_url("https://github.com/5dlabs/cto/pull/7"),
+            Some(("5dlabs/cto".to_string(), 7))
+        );
+        assert_eq!(parse_pr_url("https://github.com/5dlabs/cto/issues/7"), None);
+        assert_eq!(parse_pr_url("not a url"), None);
+    }
+
+    #[tokio::test]
+    async fn test_alert_watch_lifecycles_from_incidents() {
+        use crate::incident::{workload_incident_key, IncidentEvent, IncidentStore};
+
+        let incidents = IncidentStore::new();
+        let key = workload_incident_key("cto", "coderun-1");
+        let event = |kind, offset: i64| {
+            let mut event = IncidentEvent::new(kind, "x");
+            event.at = Utc::now() - Duration::minutes(60 - offset);
+            event
+        };
+        incidents
+            .record(
+                &key,
+                "A9 on CodeRun coderun-1",
+                None,
+                event(IncidentEventKind::AlertDetected, 0).with_detail("alert", "a9"),
+            )
+            .await;
+        for event in [
+            event(IncidentEventKind::RemediationSpawned, 2).with_detail("agent", "factory"),
+            event(IncidentEventKind::RemediationCompleted, 12)
+                .with_detail("agent", "factory")
+                .with_detail("result", "success"),
+            event(IncidentEventKind::Resolved, 30),
+        ] {
+            incidents.append(&key, event).await;
+        }
+        // CI incidents are followed from the server's own events instead
+        incidents
+            .record(
+                "ci:5dlabs/cto:CI:main",
+                "CI failing on main",
+                None,
+                event(IncidentEventKind::AlertDetected, 0),
+            )
+            .await;
+
+        let slo = SloMetrics::new();
+        // Repeated refreshes count each stage once
+        slo.record_incidents(&incidents.list().await);
+        slo.record_incidents(&incidents.list().await);
+
+        let summary = slo.summary(24);
+        assert_eq!(summary.detected, 1);
+        assert_eq!(summary.fixed, 1);
+        assert_eq!(summary.time_to_spawn.mean_secs, Some(120.0));
+        assert_eq!(summary.time_to_verify.mean_secs, Some(1800.0));
+        assert_eq!(summary.first_attempt_success[0].alert_type, "a9");
+        assert_eq!(summary.first_attempt_success[0].attempts, 1);
+        assert!(slo
+            .render()
+            .contains("healer_remediations_total{alert_type=\"a9\",result=\"fixed\"} 1"));
+    }
+
+    #[test]
+    fn test_prometheus_rendering() {
+        let t0 = Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap();
+        let slo = SloMetrics::new();
+        slo.record_detection(&failure(1, t0), "clippy");
+        slo.record_spawn_at(1, "rex", t0 + Duration::minutes(2));
+        slo.record_attempt_at(1, "rex", false, t0 + Duration::minutes(10));
+        slo.record_attempt_at(1, "blaze", true, t0 + Duration::minutes(25));
+
+        let text = slo.render();
+        assert!(text.contains("# TYPE healer_detection_latency_seconds histogram"));
+        assert!(text.contains(
+            "healer_detection_latency_seconds_bucket{alert_type=\"clippy\",le=\"30\"} 0"
+        ));
+        assert!(text.contains(
+            "healer_detection_latency_seconds_bucket{alert_type=\"clippy\",le=\"60\"} 1"
+        ));
+        assert!(text.contains("healer_detection_latency_seconds_sum{alert_type=\"clippy\"} 40"));
+        assert!(text.contains(
+            "healer_remediation_stage_seconds_count{alert_type=\"clippy\",stage=\"fixed\"} 1"
+        ));
+        assert!(text.contains(
+            "healer_remediation_attempts_total{alert_type=\"clippy\",agent=\"rex\",attempt=\"first\",result=\"failure\"} 1"
+        ));
+        assert!(text.contains(
+            "healer_remediation_attempts_total{alert_type=\"clippy\",agent=\"blaze\",attempt=\"retry\",result=\"success\"} 1"
+        ));
+        assert!(
+            text.contains("healer_remediations_total{alert_type=\"clippy\",result=\"fixed\"} 1")
+        );
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Creates Kubernetes `CodeRun` resources with:
 //! - Deduplication to prevent duplicate remediation attempts
+//! - Blast-radius limits and the read-only circuit breaker
 //! - Enriched prompts from templates
 //! - Proper labels for tracking
 
@@ -11,9 +12,16 @@ use handlebars::{Context as HbsContext, Handlebars, Helper, HelperResult, Output
 use serde_json::json;
 use std::collections::BTreeMap;
 use std::process::Command;
+use std::sync::Arc;
 use tracing::{debug, info, warn};
 
+use super::guardrails::{ActiveRemediation, Guardrails};
+use super::parsers::format_failures;
 use super::types::{Agent, CiFailureType, RemediationConfig, RemediationContext};
+use crate::clients::ClusterClient;
+
+/// Maximum structured failures listed in a prompt.
+const MAX_PROMPT_FAILURES: usize = 30;
 
 // =============================================================================
 // Handlebars Helpers
@@ -82,6 +90,8 @@ pub struct CodeRunSpawner {
     namespace: String,
     /// Repository
     repository: String,
+    /// Per-repo/per-type limits and read-only breaker (if configured)
+    guardrails: Option<Arc<Guardrails>>,
 }
 
 impl CodeRunSpawner {
@@ -107,9 +117,17 @@ impl CodeRunSpawner {
             templates,
             namespace: namespace.to_string(),
             repository: repository.to_string(),
+            guardrails: None,
         })
     }
 
+    /// Check `guardrails` before every spawn, retries included.
+    #[must_use]
+    pub fn with_guardrails(mut self, guardrails: Arc<Guardrails>) -> Self {
+        self.guardrails = Some(guardrails);
+        self
+    }
+
     /// Load templates from a directory.
     ///
     /// Templates are registered with a "ci/" prefix to match the lookup pattern
@@ -427,6 +445,38 @@ impl CodeRunSpawner {
         Ok(true)
     }
 
+    /// List active (Pending or Running) healer `CodeRuns` by repository and
+    /// failure type, as counted by the guardrails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if kubectl command fails.
+    pub fn list_active_remediations(&self) -> Result<Vec<ActiveRemediation>> {
+        let output = Command::new("kubectl")
+            .args([
+                "get",
+                "coderuns",
+                "-n",
+                &self.namespace,
+                "-l",
+                "app.kubernetes.io/name=healer",
+                "-o",
+                "jsonpath={range .items[*]}{.status.phase}\t{.metadata.labels.healer/repository}\t{.metadata.labels.healer/failure-type}{\"\\n\"}{end}",
+            ])
+            .output()
+            .context("Failed to list active CodeRuns")?;
+
+        if !output.status.success() {
+            // Same policy as the global limit: unknown counts do not block
+            warn!("Could not query CodeRuns for guardrails, assuming none active");
+            return Ok(Vec::new());
+        }
+
+        Ok(parse_active_remediations(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
     /// Check for recent failed remediation for a branch and calculate backoff.
     ///
     /// Returns the number of recent failures for this branch (used for backoff calculation).
@@ -531,6 +581,16 @@ impl CodeRunSpawner {
             );
         }
 
+        // Per-repo/per-type limits and read-only mode
+        if let Some(guardrails) = &self.guardrails {
+            let failure_type = ctx
+                .failure_type
+                .as_ref()
+                .map_or("general", CiFailureType::short_name);
+            let active = self.list_active_remediations()?;
+            guardrails.admit_spawn(&sanitize_label(&self.repository), failure_type, &active)?;
+        }
+
         // Skip deduplication checks for retries - the previous CodeRun will still exist
         // but we intentionally want to spawn a new one for the retry attempt
         let is_retry = !ctx.previous_attempts.is_empty();
@@ -657,6 +717,24 @@ impl CodeRunSpawner {
         data.insert("workflow_logs".into(), json!(&sanitized_logs));
         data.insert("recent_error_logs".into(), json!(&sanitized_recent));
 
+        // Structured failures parsed from the logs
+        data.insert("failures".into(), json!(&ctx.failures));
+        if !ctx.failures.is_empty() {
+            data.insert(
+                "failure_summary".into(),
+                json!(strip_control_chars(&format_failures(
+                    &ctx.failures,
+                    MAX_PROMPT_FAILURES
+                ))),
+            );
+        }
+        if let Some(confidence) = ctx.classification_confidence {
+            data.insert(
+                "classification_confidence".into(),
+                json!(format!("{confidence:.2}")),
+            );
+        }
+
         // PR context
         if let Some(pr) = &ctx.pr {
             data.insert("pr_number".into(), json!(pr.number));
@@ -775,6 +853,14 @@ impl CodeRunSpawner {
             );
         }
 
+        if !ctx.failures.is_empty() {
+            prompt.push_str("\n## Failing Items\n");
+            prompt.push_str(&strip_control_chars(&format_failures(
+                &ctx.failures,
+                MAX_PROMPT_FAILURES,
+            )));
+        }
+
         prompt.push_str("\n## Failure Logs\n```\n");
         // Strip control characters and truncate logs if too long (UTF-8 safe)
         let sanitized_logs = strip_control_chars(&ctx.workflow_logs);
@@ -821,6 +907,7 @@ impl CodeRunSpawner {
         let mut labels: BTreeMap<&str, String> = BTreeMap::new();
         labels.insert("app.kubernetes.io/name", "healer".to_string());
         labels.insert("healer/agent", agent.name().to_string());
+        labels.insert("healer/repository", sanitize_label(&self.repository));
         labels.insert("healer/failure-type", failure_type.to_string());
         labels.insert("healer/workflow-run-id", workflow_run_id.to_string());
         labels.insert("healer/branch", sanitize_label(branch));
@@ -958,6 +1045,109 @@ fn truncate_utf8_safe(s: &str, max_bytes: usize) -> &str {
     &s[..end]
 }
 
+/// Active (Pending or Running) healer `CodeRuns` in `namespace` by
+/// repository and failure type, as counted by the guardrails.
+///
+/// # Errors
+///
+/// Returns an error if the `CodeRuns` cannot be listed.
+pub async fn active_remediations(
+    cluster: &dyn ClusterClient,
+    namespace: &str,
+) -> Result<Vec<ActiveRemediation>> {
+    let coderuns = cluster
+        .list_coderuns(namespace, Some("app.kubernetes.io/name=healer"))
+        .await?;
+    Ok(coderuns
+        .into_iter()
+        .filter(|c| is_active_phase(&c.phase))
+        .map(|c| ActiveRemediation {
+            repository: c
+                .labels
+                .get("healer/repository")
+                .cloned()
+                .unwrap_or_default(),
+            alert_type: c
+                .labels
+                .get("healer/failure-type")
+                .cloned()
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Healer `CodeRuns` in `namespace` that opened a PR, as workflow run id
+/// and PR URL.
+///
+/// # Errors
+///
+/// Returns an error if the `CodeRuns` cannot be listed.
+pub async fn remediation_prs(
+    cluster: &dyn ClusterClient,
+    namespace: &str,
+) -> Result<Vec<(u64, String)>> {
+    let coderuns = cluster
+        .list_coderuns(namespace, Some("app.kubernetes.io/name=healer"))
+        .await?;
+    Ok(coderuns
+        .into_iter()
+        .filter_map(|c| {
+            let run_id = c.labels.get("healer/workflow-run-id")?.parse().ok()?;
+            Some((run_id, c.pull_request_url?))
+        })
+        .collect())
+}
+
+/// Admit a remediation for `repository` / `alert_type` spawned outside
+/// [`CodeRunSpawner`] (e.g. by the alert watch) against the guardrails and
+/// the healer `CodeRuns` active in `namespace`.
+///
+/// # Errors
+///
+/// Returns [`Blocked`](super::guardrails::Blocked) if a guardrail refuses.
+pub async fn admit_remediation(
+    guardrails: &Guardrails,
+    cluster: &dyn ClusterClient,
+    namespace: &str,
+    repository: &str,
+    alert_type: &str,
+) -> Result<()> {
+    let active = match active_remediations(cluster, namespace).await {
+        Ok(active) => active,
+        Err(e) => {
+            // Same policy as the global limit: unknown counts do not block
+            warn!("Could not list CodeRuns for guardrails, assuming none active: {e:#}");
+            Vec::new()
+        }
+    };
+    guardrails.admit_spawn(&sanitize_label(repository), alert_type, &active)?;
+    Ok(())
+}
+
+/// Whether a `CodeRun` in `phase` still counts as running. One without a
+/// phase has not been picked up yet.
+fn is_active_phase(phase: &str) -> bool {
+    matches!(phase, "" | "Unknown" | "Pending" | "Running")
+}
+
+/// Parse `phase\trepository\tfailure-type` lines into running remediations.
+fn parse_active_remediations(output: &str) -> Vec<ActiveRemediation> {
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            if !is_active_phase(fields.next()?.trim()) {
+                return None;
+            }
+            Some(ActiveRemediation {
+                repository: fields.next().unwrap_or_default().to_string(),
+                alert_type: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
 /// Sanitize a string for use as a Kubernetes label value.
 fn sanitize_label(value: &str) -> String {
     // Labels must be <= 63 characters, alphanumeric, dashes, underscores, dots
@@ -1108,4 +1298,98 @@ mod tests {
         let spawner = CodeRunSpawner::new(config, "cto", "5dlabs/cto").unwrap();
         assert_eq!(spawner.config.max_concurrent_coderuns, 0);
     }
+
+    #[test]
+    fn test_parse_active_remediations() {
+        let output =
+            "Running\t5dlabs-cto\tclippy\nSucceeded\t5dlabs-cto\thelm\n\t5dlabs-web\tfe-test\n";
+        let active = parse_active_remediations(output);
+        assert_eq!(active.len(), 2);
+        assert_eq!(active[0].repository, "5dlabs-cto");
+        assert_eq!(active[0].alert_type, "clippy");
+        assert_eq!(active[1].alert_type, "fe-test");
+    }
+
+    #[tokio::test]
+    async fn test_admit_remediation_counts_active_coderuns() {
+        fn coderun(name: &str, phase: &str, repository: &str) -> crate::k8s::CodeRun {
+            crate::k8s::CodeRun {
+                name: name.to_string(),
+                namespace: "cto".to_string(),
+                phase: phase.to_string(),
+                agent: String::new(),
+                task_id: String::new(),
+                labels: [
+                    ("app.kubernetes.io/name", "healer"),
+                    ("healer/repository", repository),
+                    ("healer/failure-type", "a2"),
+                ]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+                created_at: None,
+                pull_request_url: None,
+                ..crate::k8s::CodeRun::default()
+            }
+        }
+        let cluster = crate::clients::FakeCluster::new()
+            .with_coderun(coderun("healer-a2-1", "Running", "5dlabs-cto"))
+            .with_coderun(coderun("healer-a2-2", "Succeeded", "5dlabs-cto"))
+            .with_coderun(coderun("healer-a2-3", "Unknown", "5dlabs-web"));
+
+        let active = active_remediations(&cluster, "cto").await.unwrap();
+        assert_eq!(active.len(), 2);
+
+        let guardrails = Guardrails::new(super::super::guardrails::GuardrailConfig {
+            max_concurrent_per_repo: 1,
+            max_concurrent_per_alert_type: 0,
+            ..Default::default()
+        });
+        assert!(
+            admit_remediation(&guardrails, &cluster, "cto", "5dlabs/cto", "a2")
+                .await
+                .is_err()
+        );
+        assert!(
+            admit_remediation(&guardrails, &cluster, "cto", "5dlabs/api", "a2")
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remediation_prs_from_coderun_status() {
+        fn coderun(name: &str, run_id: &str, pr: Option<&str>) -> crate::k8s::CodeRun {
+            crate::k8s::CodeRun {
+                name: name.to_string(),
+                namespace: "cto".to_string(),
+                labels: [
+                    ("app.kubernetes.io/name", "healer"),
+                    ("healer/workflow-run-id", run_id),
+                ]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+                pull_request_url: pr.map(String::from),
+                ..Default::default()
+            }
+        }
+        let cluster = crate::clients::FakeCluster::new()
+            .with_coderun(coderun(
+                "healer-a2-1",
+                "4242",
+                Some("https://github.com/5dlabs/cto/pull/7"),
+            ))
+            .with_coderun(coderun("healer-a2-2", "4243", None))
+            .with_coderun(coderun(
+                "healer-a2-3",
+                "",
+                Some("https://github.com/5dlabs/cto/pull/8"),
+            ));
+
+        assert_eq!(
+            remediation_prs(&cluster, "cto").await.unwrap(),
+            vec![(4242, "https://github.com/5dlabs/cto/pull/7".to_string())]
+        );
+    }
 }
@@ -4,7 +4,11 @@
 //! - Monitors `CodeRun` completion status
 //! - Manages retry logic with different agents
 //! - Coordinates with `OpenMemory` for learning
+//! - Records attempt outcomes for adaptive agent routing
 //! - Triggers escalation after max attempts
+//! - Persists in-flight remediations so another replica can resume them
+//! - Records completions, retries, merges and escalations on incidents
+//! - Feeds attempts, merges and escalations to the SLO metrics
 
 use anyhow::{Context as _, Result};
 use chrono::{DateTime, Utc};
@@ -14,15 +18,26 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use super::adaptive::AdaptiveRouter;
+use super::guardrails::Guardrails;
 use super::memory::{MemoryClient, MemoryConfig};
 use super::merge::AutoMergeHandler;
+use super::outcomes::{failure_signature, OutcomeRecord};
+use super::parsers::FailureItem;
 use super::router::CiRouter;
+use super::slo::SloMetrics;
 use super::spawner::CodeRunSpawner;
 use super::types::{
     Agent, AttemptOutcome, CiFailure, CiFailureType, RemediationConfig, RemediationContext,
     RemediationState, RemediationStatus,
 };
 use crate::github::GitHubClient;
+use crate::ha::StateStore;
+use crate::incident::{ci_incident_key, IncidentEvent, IncidentEventKind, IncidentStore};
+use crate::play::insights::{AgentObservation, ObservationType};
+
+/// State store key prefix for tracked remediations.
+const STATE_KEY_PREFIX: &str = "remediation-";
 
 /// Remediation tracker state.
 pub struct RemediationTracker {
@@ -32,12 +47,22 @@ pub struct RemediationTracker {
     memory: Option<MemoryClient>,
     /// Router for agent selection
     router: CiRouter,
+    /// Learned routing over recorded outcomes (if an outcome store is configured)
+    adaptive: Option<AdaptiveRouter>,
+    /// Cluster persistence for tracked remediations (if configured)
+    store: Option<StateStore>,
+    /// Incident timelines to record completions on (if configured)
+    incidents: Option<Arc<IncidentStore>>,
+    /// Outcome/merge accounting for the read-only breaker (if configured)
+    guardrails: Option<Arc<Guardrails>>,
+    /// Remediation lifecycle metrics (if configured)
+    slo: Option<Arc<SloMetrics>>,
     /// Configuration
     config: RemediationConfig,
 }
 
 /// Tracked remediation with full context.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackedRemediation {
     /// Workflow run ID (primary key)
     pub workflow_run_id: u64,
@@ -45,6 +70,8 @@ pub struct TrackedRemediation {
     pub failure: CiFailure,
     /// Classified failure type
     pub failure_type: CiFailureType,
+    /// Failure signature for outcome learning
+    pub signature: String,
     /// Current remediation state
     pub state: RemediationState,
     /// Active `CodeRun` name (if any)
@@ -106,15 +133,84 @@ impl RemediationTracker {
             active: Arc::new(RwLock::new(HashMap::new())),
             memory,
             router: CiRouter::new(),
+            adaptive: AdaptiveRouter::from_config(&config.adaptive_routing),
+            store: None,
+            incidents: None,
+            guardrails: None,
+            slo: None,
             config,
         })
     }
 
+    /// Record attempts, merges and escalations on `slo`.
+    #[must_use]
+    pub fn with_slo(mut self, slo: Arc<SloMetrics>) -> Self {
+        self.slo = Some(slo);
+        self
+    }
+
+    /// Feed outcomes and merges to `guardrails` and respect its merge limit.
+    #[must_use]
+    pub fn with_guardrails(mut self, guardrails: Arc<Guardrails>) -> Self {
+        self.guardrails = Some(guardrails);
+        self
+    }
+
+    /// Record completions, retries, merges and escalations on `incidents`.
+    #[must_use]
+    pub fn with_incidents(mut self, incidents: Arc<IncidentStore>) -> Self {
+        self.incidents = Some(incidents);
+        self
+    }
+
+    /// Persist tracked remediations in `store`.
+    #[must_use]
+    pub fn with_state_store(mut self, store: StateStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Load remediations persisted by a previous leader. Returns how many
+    /// were restored.
+    pub async fn restore(&self) -> Result<usize> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        let restored: Vec<TrackedRemediation> = store.load_all(STATE_KEY_PREFIX).await?;
+        let count = restored.len();
+        let mut active = self.active.write().await;
+        for tracked in restored {
+            active.insert(tracked.workflow_run_id, tracked);
+        }
+        if count > 0 {
+            info!(
+                "Restored {count} tracked remediations from {}",
+                store.name()
+            );
+        }
+        Ok(count)
+    }
+
+    /// Write a remediation through to the state store. Failures are logged,
+    /// not returned: losing persistence must not stop a remediation.
+    async fn persist(&self, tracked: &TrackedRemediation) {
+        if let Some(store) = &self.store {
+            let key = format!("{STATE_KEY_PREFIX}{}", tracked.workflow_run_id);
+            if let Err(e) = store.save(&key, tracked).await {
+                warn!(
+                    "Failed to persist remediation {}: {e:#}",
+                    tracked.workflow_run_id
+                );
+            }
+        }
+    }
+
     /// Track a new remediation.
     pub async fn track(
         &self,
         failure: CiFailure,
         failure_type: CiFailureType,
+        failures: &[FailureItem],
         pr_number: Option<u32>,
     ) -> u64 {
         let workflow_run_id = failure.workflow_run_id;
@@ -127,6 +223,7 @@ impl RemediationTracker {
             workflow_run_id,
             repository: failure.repository.clone(),
             branch: failure.branch.clone(),
+            signature: failure_signature(&failure, failures),
             failure,
             failure_type,
             state,
@@ -136,8 +233,11 @@ impl RemediationTracker {
             updated_at: now,
         };
 
-        let mut active = self.active.write().await;
-        active.insert(workflow_run_id, tracked);
+        self.active
+            .write()
+            .await
+            .insert(workflow_run_id, tracked.clone());
+        self.persist(&tracked).await;
 
         info!("Tracking remediation for workflow run {workflow_run_id}");
 
@@ -158,6 +258,10 @@ impl RemediationTracker {
                 agent.name(),
                 workflow_run_id
             );
+
+            let snapshot = tracked.clone();
+            drop(active);
+            self.persist(&snapshot).await;
         }
     }
 
@@ -168,11 +272,90 @@ impl RemediationTracker {
     /// # Errors
     ///
     /// Returns an error if spawning a retry `CodeRun` fails.
-    #[allow(clippy::too_many_lines)] // Complex function not easily split
     pub async fn handle_completion(
         &self,
         completion: CodeRunCompletion,
         spawner: &CodeRunSpawner,
+    ) -> Result<CompletionAction> {
+        let tracked = self.get(completion.workflow_run_id).await;
+        let summary = match &completion.error_message {
+            Some(error) => format!("{} {:?}: {error}", completion.name, completion.status),
+            None => format!("{} {:?}", completion.name, completion.status),
+        };
+        let observation = AgentObservation {
+            agent: completion.agent.clone(),
+            task_id: completion.workflow_run_id.to_string(),
+            timestamp: Utc::now(),
+            observation_type: if completion.status == CodeRunStatus::Success {
+                ObservationType::SuccessPattern
+            } else {
+                ObservationType::RepeatedMistake
+            },
+            details: completion
+                .error_message
+                .clone()
+                .unwrap_or_else(|| format!("{:?}", completion.status)),
+            stage: None,
+        };
+        let completed = IncidentEvent::new(IncidentEventKind::RemediationCompleted, summary)
+            .with_detail("agent", &completion.agent)
+            .with_detail("coderun", &completion.name);
+
+        let success = match completion.status {
+            CodeRunStatus::Success => Some(true),
+            CodeRunStatus::Failed | CodeRunStatus::Timeout => Some(false),
+            CodeRunStatus::Cancelled => None,
+        };
+
+        let workflow_run_id = completion.workflow_run_id;
+        let agent = completion.agent.clone();
+        let action = self.complete(completion, spawner).await;
+
+        if let Some(slo) = &self.slo {
+            if let Some(success) = success {
+                slo.record_attempt(workflow_run_id, &agent, success);
+            }
+            match &action {
+                Ok(CompletionAction::Merged { .. }) => slo.record_merge(workflow_run_id),
+                Ok(CompletionAction::Escalate { .. }) => slo.record_escalation(workflow_run_id),
+                _ => {}
+            }
+        }
+
+        if let Some(guardrails) = &self.guardrails {
+            if let Some(success) = success {
+                guardrails.record_outcome(success).await;
+            }
+            if let (Ok(CompletionAction::Merged { pr_number }), Some(tracked)) = (&action, &tracked)
+            {
+                guardrails
+                    .record_merge(&tracked.repository, *pr_number)
+                    .await;
+            }
+        }
+
+        if let (Some(incidents), Some(tracked)) = (&self.incidents, tracked) {
+            let key = ci_incident_key(
+                &tracked.repository,
+                &tracked.failure.workflow_name,
+                &tracked.branch,
+            );
+            incidents.append(&key, completed).await;
+            incidents.observe(&key, observation).await;
+            if let Ok(action) = &action {
+                if let Some(event) = action_event(action) {
+                    incidents.append(&key, event).await;
+                }
+            }
+        }
+        action
+    }
+
+    #[allow(clippy::too_many_lines)] // Complex function not easily split
+    async fn complete(
+        &self,
+        completion: CodeRunCompletion,
+        spawner: &CodeRunSpawner,
     ) -> Result<CompletionAction> {
         let workflow_run_id = completion.workflow_run_id;
 
@@ -210,6 +393,24 @@ impl RemediationTracker {
             .state
             .record_attempt(outcome, &completion.name, agent);
 
+        // Record the outcome for adaptive routing
+        if let Some(adaptive) = &self.adaptive {
+            #[allow(clippy::cast_possible_truncation)] // Attempt count is always small
+            let record = OutcomeRecord {
+                recorded_at: Utc::now(),
+                repository: tracked.repository.clone(),
+                workflow_run_id,
+                signature: tracked.signature.clone(),
+                failure_type: tracked.failure_type.clone(),
+                agent,
+                attempt: tracked.state.attempts.len() as u32,
+                outcome,
+            };
+            if let Err(e) = adaptive.store().record(&record) {
+                warn!("Failed to record remediation outcome: {e:#}");
+            }
+        }
+
         // Store outcome in memory
         if let Some(memory) = &self.memory {
             let description = completion.error_message.as_deref().unwrap_or("No details");
@@ -247,6 +448,7 @@ impl RemediationTracker {
 
             // Release lock BEFORE async operation to prevent deadlock
             drop(active);
+            self.persist(&tracked_clone).await;
 
             // Attempt auto-merge if enabled and PR exists
             if config_clone.auto_merge_enabled && tracked_clone.pr_number.is_some() {
@@ -254,7 +456,10 @@ impl RemediationTracker {
                 let parts: Vec<&str> = tracked_clone.repository.split('/').collect();
                 if parts.len() == 2 {
                     let github = GitHubClient::new(parts[0], parts[1]);
-                    let merge_handler = AutoMergeHandler::new(github, config_clone);
+                    let mut merge_handler = AutoMergeHandler::new(github, config_clone);
+                    if let Some(guardrails) = &self.guardrails {
+                        merge_handler = merge_handler.with_guardrails(guardrails.clone());
+                    }
 
                     info!(
                         pr = tracked_clone.pr_number,
@@ -292,6 +497,7 @@ impl RemediationTracker {
 
                 // Release lock BEFORE async operation to prevent deadlock
                 drop(active);
+                self.persist(&tracked_clone).await;
 
                 // Now safe to call async function without holding the lock
                 let ctx = self.build_retry_context(&tracked_clone).await?;
@@ -303,6 +509,9 @@ impl RemediationTracker {
                         let mut active = self.active.write().await;
                         if let Some(tracked) = active.get_mut(&workflow_run_id) {
                             tracked.active_coderun = Some(coderun_name.clone());
+                            let snapshot = tracked.clone();
+                            drop(active);
+                            self.persist(&snapshot).await;
                         }
 
                         Ok(CompletionAction::Retry {
@@ -341,6 +550,9 @@ impl RemediationTracker {
                 let failure = tracked.failure.clone();
                 let attempts = tracked.state.attempts.clone();
                 let pr_number = tracked.pr_number;
+                let snapshot = tracked.clone();
+                drop(active);
+                self.persist(&snapshot).await;
 
                 Ok(CompletionAction::Escalate {
                     failure,
@@ -362,7 +574,14 @@ impl RemediationTracker {
             ..Default::default()
         };
 
-        self.router.try_different_agent(failed_agent, &ctx)
+        let next = self.router.try_different_agent(failed_agent, &ctx);
+        let Some(adaptive) = &self.adaptive else {
+            return next;
+        };
+
+        // Never hand the run back to an agent that already failed it
+        let tried: Vec<Agent> = tracked.state.attempts.iter().map(|a| a.agent).collect();
+        adaptive.select(&tracked.failure_type, next, &tried)
     }
 
     /// Build context for a retry attempt.
@@ -413,21 +632,51 @@ impl RemediationTracker {
 
     /// Remove completed/escalated remediations.
     pub async fn cleanup(&self) {
-        let mut active = self.active.write().await;
-        let before = active.len();
-
-        active.retain(|_, v| {
-            matches!(
-                v.state.status,
-                RemediationStatus::Pending | RemediationStatus::InProgress
-            )
-        });
-
-        let removed = before - active.len();
+        let removed = self
+            .remove_where(|v| {
+                !matches!(
+                    v.state.status,
+                    RemediationStatus::Pending | RemediationStatus::InProgress
+                )
+            })
+            .await;
         if removed > 0 {
             info!("Cleaned up {removed} completed remediations");
         }
     }
+
+    /// Remove remediations not updated within `max_age`, whatever their
+    /// status, e.g. when no completion event will ever arrive for them.
+    pub async fn forget_stale(&self, max_age: chrono::Duration) {
+        let cutoff = Utc::now() - max_age;
+        let removed = self.remove_where(|v| v.updated_at < cutoff).await;
+        if removed > 0 {
+            debug!("Forgot {removed} stale remediations");
+        }
+    }
+
+    /// Remove matching remediations from memory and the state store.
+    async fn remove_where(&self, matches: impl Fn(&TrackedRemediation) -> bool) -> usize {
+        let mut active = self.active.write().await;
+        let removed: Vec<u64> = active
+            .values()
+            .filter(|v| matches(v))
+            .map(|v| v.workflow_run_id)
+            .collect();
+        for id in &removed {
+            active.remove(id);
+        }
+        drop(active);
+
+        if let Some(store) = &self.store {
+            for id in &removed {
+                if let Err(e) = store.remove(&format!("{STATE_KEY_PREFIX}{id}")).await {
+                    warn!("Failed to remove persisted remediation {id}: {e:#}");
+                }
+            }
+        }
+        removed.len()
+    }
 }
 
 /// Action to take after a `CodeRun` completes.
@@ -462,6 +711,28 @@ pub enum CompletionAction {
     Merged { pr_number: u32 },
 }
 
+/// The incident event for what happened after a completion, if any.
+fn action_event(action: &CompletionAction) -> Option<IncidentEvent> {
+    let event = match action {
+        CompletionAction::Retry { agent, coderun } => IncidentEvent::new(
+            IncidentEventKind::RemediationSpawned,
+            format!("Retrying with {} as {coderun}", agent.name()),
+        )
+        .with_detail("agent", agent.name())
+        .with_detail("coderun", coderun),
+        CompletionAction::Escalate { attempts, .. } => IncidentEvent::new(
+            IncidentEventKind::Escalated,
+            format!("Escalated to humans after {} attempts", attempts.len()),
+        ),
+        CompletionAction::Merged { pr_number } => {
+            IncidentEvent::new(IncidentEventKind::Merged, format!("Merged PR #{pr_number}"))
+                .with_detail("pr", pr_number.to_string())
+        }
+        _ => return None,
+    };
+    Some(event)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -474,4 +745,58 @@ mod tests {
         let failed: CodeRunStatus = serde_json::from_str("\"failed\"").unwrap();
         assert_eq!(failed, CodeRunStatus::Failed);
     }
+
+    #[tokio::test]
+    async fn test_new_leader_resumes_persisted_remediations() {
+        let cluster = Arc::new(crate::clients::FakeCluster::new());
+        let store = || StateStore::new(cluster.clone(), "cto", "healer-ci-state");
+        let failure = CiFailure {
+            workflow_run_id: 42,
+            workflow_name: "Controller CI".to_string(),
+            job_name: Some("build".to_string()),
+            conclusion: "failure".to_string(),
+            branch: "main".to_string(),
+            head_sha: "abc123".to_string(),
+            commit_message: "test".to_string(),
+            html_url: "https://github.com/5dlabs/cto/actions/runs/42".to_string(),
+            repository: "5dlabs/cto".to_string(),
+            sender: "developer".to_string(),
+            detected_at: Utc::now(),
+            raw_event: None,
+        };
+
+        let old_leader = RemediationTracker::new(RemediationConfig::default(), None)
+            .unwrap()
+            .with_state_store(store());
+        old_leader
+            .track(failure, CiFailureType::RustBuild, &[], Some(7))
+            .await;
+        old_leader
+            .record_spawn(42, "healer-ci-rex-42", Agent::Rex)
+            .await;
+
+        let new_leader = RemediationTracker::new(RemediationConfig::default(), None)
+            .unwrap()
+            .with_state_store(store());
+        assert_eq!(new_leader.restore().await.unwrap(), 1);
+        let resumed = new_leader.get(42).await.unwrap();
+        assert_eq!(resumed.active_coderun.as_deref(), Some("healer-ci-rex-42"));
+        assert_eq!(resumed.state.status, RemediationStatus::InProgress);
+        assert_eq!(resumed.pr_number, Some(7));
+
+        // Finished remediations are dropped from the store too
+        new_leader
+            .active
+            .write()
+            .await
+            .get_mut(&42)
+            .unwrap()
+            .state
+            .status = RemediationStatus::Succeeded;
+        new_leader.cleanup().await;
+        let fresh = RemediationTracker::new(RemediationConfig::default(), None)
+            .unwrap()
+            .with_state_store(store());
+        assert_eq!(fresh.restore().await.unwrap(), 0);
+    }
 }
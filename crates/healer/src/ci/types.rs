@@ -11,6 +11,11 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 
+use super::adaptive::AdaptiveRoutingConfig;
+use super::flaky::FlakyTestConfig;
+use super::guardrails::GuardrailConfig;
+use super::parsers::FailureItem;
+
 /// Specialist agent identifiers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -328,6 +333,46 @@ impl CiFailure {
         })
     }
 
+    /// Create a new CI failure from a workflow run event.
+    ///
+    /// The run covers every job in the workflow, so `job_name` is `None`.
+    #[must_use]
+    pub fn from_workflow_run(event: &serde_json::Value) -> Option<Self> {
+        let run = event.get("workflow_run")?;
+        let repo = event.get("repository")?;
+        let str_field = |value: Option<&serde_json::Value>| {
+            value.and_then(|v| v.as_str()).unwrap_or("").to_string()
+        };
+
+        Some(Self {
+            workflow_run_id: run.get("id")?.as_u64()?,
+            workflow_name: run
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            job_name: None,
+            conclusion: run
+                .get("conclusion")
+                .and_then(|v| v.as_str())
+                .unwrap_or("failure")
+                .to_string(),
+            branch: str_field(run.get("head_branch")),
+            head_sha: str_field(run.get("head_sha")),
+            commit_message: str_field(run.get("head_commit").and_then(|c| c.get("message"))),
+            html_url: str_field(run.get("html_url")),
+            repository: str_field(repo.get("full_name")),
+            sender: str_field(
+                event
+                    .get("sender")
+                    .or_else(|| run.get("actor"))
+                    .and_then(|s| s.get("login")),
+            ),
+            detected_at: Utc::now(),
+            raw_event: Some(event.clone()),
+        })
+    }
+
     /// Create a new CI failure from a check run event.
     #[must_use]
     pub fn from_check_run(event: &serde_json::Value) -> Option<Self> {
@@ -426,6 +471,10 @@ pub struct RemediationContext {
     pub failure_type: Option<CiFailureType>,
     /// Workflow logs
     pub workflow_logs: String,
+    /// Structured failures parsed from the workflow logs
+    pub failures: Vec<FailureItem>,
+    /// Confidence (0.0-1.0) in `failure_type`
+    pub classification_confidence: Option<f32>,
     /// PR associated with this failure (if any)
     pub pr: Option<PullRequest>,
     /// Changed files in the failing commit
@@ -835,6 +884,15 @@ pub struct RemediationConfig {
     /// Maximum time to wait for checks before giving up (minutes)
     #[serde(default = "default_check_timeout")]
     pub check_timeout_mins: u32,
+    /// Flaky test detection and quarantine
+    #[serde(default)]
+    pub flaky_tests: FlakyTestConfig,
+    /// Outcome store and learned agent routing
+    #[serde(default)]
+    pub adaptive_routing: AdaptiveRoutingConfig,
+    /// Per-repo/per-type limits, auto-merge rate and read-only breaker
+    #[serde(default)]
+    pub guardrails: GuardrailConfig,
 }
 
 fn default_max_concurrent() -> usize {
@@ -863,6 +921,9 @@ impl Default for RemediationConfig {
             auto_merge_enabled: false, // Disabled by default for safety
             merge_method: default_merge_method(),
             check_timeout_mins: default_check_timeout(),
+            flaky_tests: FlakyTestConfig::default(),
+            adaptive_routing: AdaptiveRoutingConfig::default(),
+            guardrails: GuardrailConfig::default(),
         }
     }
 }
@@ -0,0 +1,879 @@
+//! In-memory [`ClusterClient`] and [`GitHubClient`] fakes for tests.
+//!
+//! Both fakes are seeded with builder methods and record every write, so
+//! tests can assert on what a flow did without a cluster or GitHub token.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{
+    matches_label_selector, ApplicationStatus, CheckRun, CheckSummary, ClusterClient, Commit,
+    CommitFile, ConfigMapData, GitHubClient, Issue, IssueQuery, LeaseRecord, PullRequest, Review,
+    WorkflowRun,
+};
+use crate::k8s::{parse_coderun_from_json, CodeRun, Pod};
+
+#[derive(Default)]
+struct ClusterState {
+    pods: Vec<Pod>,
+    coderuns: Vec<CodeRun>,
+    logs: HashMap<(String, String), String>,
+    workflows: HashSet<(String, String)>,
+    applications: HashMap<(String, String), ApplicationStatus>,
+    events: HashMap<(String, String), Vec<String>>,
+    exec_output: HashMap<(String, String, String), String>,
+    created_coderuns: Vec<serde_json::Value>,
+    leases: HashMap<(String, String), LeaseRecord>,
+    config_maps: HashMap<(String, String), ConfigMapData>,
+    config_map_labels: HashMap<(String, String), HashMap<String, String>>,
+    persistent_volume_claims: HashSet<(String, String)>,
+    leases_unavailable: bool,
+    resource_version: u64,
+}
+
+impl ClusterState {
+    /// Apply an optimistic-concurrency write: the stored object's version
+    /// must match `expected` (`None` meaning "does not exist yet").
+    fn write<T: Clone>(
+        &mut self,
+        objects: fn(&mut Self) -> &mut HashMap<(String, String), T>,
+        version: fn(&mut T) -> &mut Option<String>,
+        key: (String, String),
+        object: &T,
+    ) -> bool {
+        let mut object = object.clone();
+        let expected = version(&mut object).clone();
+        let current = objects(self)
+            .get_mut(&key)
+            .and_then(|stored| version(stored).clone());
+        if current != expected {
+            return false;
+        }
+        self.resource_version += 1;
+        *version(&mut object) = Some(self.resource_version.to_string());
+        objects(self).insert(key, object);
+        true
+    }
+}
+
+/// In-memory cluster.
+#[derive(Default)]
+pub struct FakeCluster {
+    state: Mutex<ClusterState>,
+}
+
+impl FakeCluster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, ClusterState> {
+        self.state.lock().expect("fake cluster lock poisoned")
+    }
+
+    #[must_use]
+    pub fn with_pod(self, pod: Pod) -> Self {
+        self.state().pods.push(pod);
+        self
+    }
+
+    #[must_use]
+    pub fn with_persistent_volume_claim(self, namespace: &str, name: &str) -> Self {
+        self.state()
+            .persistent_volume_claims
+            .insert((namespace.to_string(), name.to_string()));
+        self
+    }
+
+    #[must_use]
+    pub fn with_coderun(self, coderun: CodeRun) -> Self {
+        self.state().coderuns.push(coderun);
+        self
+    }
+
+    #[must_use]
+    pub fn with_logs(self, namespace: &str, pod: &str, logs: &str) -> Self {
+        self.state()
+            .logs
+            .insert((namespace.to_string(), pod.to_string()), logs.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn with_container_logs(
+        self,
+        namespace: &str,
+        pod: &str,
+        container: &str,
+        logs: &str,
+    ) -> Self {
+        self.state().logs.insert(
+            (namespace.to_string(), format!("{pod}/{container}")),
+            logs.to_string(),
+        );
+        self
+    }
+
+    /// Seed the logs of a container's previous (restarted) instance.
+    #[must_use]
+    pub fn with_previous_container_logs(
+        self,
+        namespace: &str,
+        pod: &str,
+        container: &str,
+        logs: &str,
+    ) -> Self {
+        self.state().logs.insert(
+            (namespace.to_string(), format!("{pod}/{container}/previous")),
+            logs.to_string(),
+        );
+        self
+    }
+
+    /// Seed the stdout of running `command` (space-joined) in a pod.
+    /// Unseeded commands fail.
+    #[must_use]
+    pub fn with_exec(self, namespace: &str, pod: &str, command: &str, stdout: &str) -> Self {
+        self.state().exec_output.insert(
+            (namespace.to_string(), pod.to_string(), command.to_string()),
+            stdout.to_string(),
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn with_config_map(
+        self,
+        namespace: &str,
+        name: &str,
+        labels: &[(&str, &str)],
+        data: &[(&str, &str)],
+    ) -> Self {
+        {
+            let mut state = self.state();
+            let key = (namespace.to_string(), name.to_string());
+            state.resource_version += 1;
+            let config_map = ConfigMapData {
+                data: data
+                    .iter()
+                    .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                    .collect(),
+                resource_version: Some(state.resource_version.to_string()),
+            };
+            state.config_maps.insert(key.clone(), config_map);
+            state.config_map_labels.insert(
+                key,
+                labels
+                    .iter()
+                    .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                    .collect(),
+            );
+        }
+        self
+    }
+
+    /// Make Lease reads and writes fail, as during an API server outage.
+    pub fn set_leases_unavailable(&self, unavailable: bool) {
+        self.state().leases_unavailable = unavailable;
+    }
+
+    /// Manifests passed to `create_coderun`, in order.
+    pub fn created_coderuns(&self) -> Vec<serde_json::Value> {
+        self.state().created_coderuns.clone()
+    }
+
+    /// Names of the `CodeRun`s in `namespace`.
+    pub fn coderun_names(&self, namespace: &str) -> Vec<String> {
+        self.state()
+            .coderuns
+            .iter()
+            .filter(|c| c.namespace == namespace)
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
+    /// Names of the workflows in `namespace`, sorted.
+    pub fn workflow_names(&self, namespace: &str) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .state()
+            .workflows
+            .iter()
+            .filter(|(ns, _)| ns == namespace)
+            .map(|(_, name)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[must_use]
+    pub fn with_workflow(self, namespace: &str, name: &str) -> Self {
+        self.state()
+            .workflows
+            .insert((namespace.to_string(), name.to_string()));
+        self
+    }
+
+    #[must_use]
+    pub fn with_application(self, namespace: &str, name: &str, status: ApplicationStatus) -> Self {
+        self.state()
+            .applications
+            .insert((namespace.to_string(), name.to_string()), status);
+        self
+    }
+
+    #[must_use]
+    pub fn with_event(self, namespace: &str, object: &str, message: &str) -> Self {
+        self.state()
+            .events
+            .entry((namespace.to_string(), object.to_string()))
+            .or_default()
+            .push(message.to_string());
+        self
+    }
+}
+
+#[async_trait]
+impl ClusterClient for FakeCluster {
+    async fn get_pod(&self, namespace: &str, name: &str) -> Result<Option<Pod>> {
+        Ok(self
+            .state()
+            .pods
+            .iter()
+            .find(|p| p.namespace == namespace && p.name == name)
+            .cloned())
+    }
+
+    async fn list_pods(&self, namespace: &str, label_selector: Option<&str>) -> Result<Vec<Pod>> {
+        Ok(self
+            .state()
+            .pods
+            .iter()
+            .filter(|p| {
+                p.namespace == namespace && matches_label_selector(&p.labels, label_selector)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_pod(&self, namespace: &str, name: &str) -> Result<bool> {
+        let mut state = self.state();
+        let before = state.pods.len();
+        state
+            .pods
+            .retain(|p| !(p.namespace == namespace && p.name == name));
+        Ok(state.pods.len() < before)
+    }
+
+    async fn pod_logs(&self, namespace: &str, name: &str, tail_lines: i64) -> Result<String> {
+        let state = self.state();
+        let logs = state
+            .logs
+            .get(&(namespace.to_string(), name.to_string()))
+            .ok_or_else(|| anyhow::anyhow!("pods \"{name}\" not found"))?;
+        let lines: Vec<&str> = logs.lines().collect();
+        let skip = lines
+            .len()
+            .saturating_sub(usize::try_from(tail_lines).unwrap_or(0));
+        Ok(lines[skip..].join("\n"))
+    }
+
+    async fn container_logs(
+        &self,
+        namespace: &str,
+        name: &str,
+        container: &str,
+        tail_lines: i64,
+    ) -> Result<String> {
+        self.pod_logs(namespace, &format!("{name}/{container}"), tail_lines)
+            .await
+    }
+
+    async fn previous_container_logs(
+        &self,
+        namespace: &str,
+        name: &str,
+        container: &str,
+        tail_lines: i64,
+    ) -> Result<String> {
+        self.pod_logs(
+            namespace,
+            &format!("{name}/{container}/previous"),
+            tail_lines,
+        )
+        .await
+    }
+
+    async fn exec(&self, namespace: &str, pod: &str, command: &[&str]) -> Result<String> {
+        self.state()
+            .exec_output
+            .get(&(namespace.to_string(), pod.to_string(), command.join(" ")))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("command terminated with exit code 1"))
+    }
+
+    async fn get_coderun(&self, namespace: &str, name: &str) -> Result<Option<CodeRun>> {
+        Ok(self
+            .state()
+            .coderuns
+            .iter()
+            .find(|c| c.namespace == namespace && c.name == name)
+            .cloned())
+    }
+
+    async fn list_coderuns(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+    ) -> Result<Vec<CodeRun>> {
+        Ok(self
+            .state()
+            .coderuns
+            .iter()
+            .filter(|c| {
+                c.namespace == namespace && matches_label_selector(&c.labels, label_selector)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn create_coderun(
+        &self,
+        namespace: &str,
+        manifest: &serde_json::Value,
+    ) -> Result<String> {
+        let mut state = self.state();
+        let name = match manifest["metadata"]["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => {
+                let prefix = manifest["metadata"]["generateName"]
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("CodeRun needs a name or generateName"))?;
+                state.resource_version += 1;
+                format!("{prefix}{}", state.resource_version)
+            }
+        };
+        if state
+            .coderuns
+            .iter()
+            .any(|c| c.namespace == namespace && c.name == name)
+        {
+            anyhow::bail!("coderuns \"{name}\" already exists");
+        }
+        let mut coderun = parse_coderun_from_json(manifest, namespace);
+        coderun.name.clone_from(&name);
+        state.coderuns.push(coderun);
+        state.created_coderuns.push(manifest.clone());
+        Ok(name)
+    }
+
+    async fn delete_coderun(&self, namespace: &str, name: &str) -> Result<bool> {
+        let mut state = self.state();
+        let before = state.coderuns.len();
+        state
+            .coderuns
+            .retain(|c| !(c.namespace == namespace && c.name == name));
+        Ok(state.coderuns.len() < before)
+    }
+
+    async fn workflow_exists(&self, namespace: &str, name: &str) -> Result<bool> {
+        Ok(self
+            .state()
+            .workflows
+            .contains(&(namespace.to_string(), name.to_string())))
+    }
+
+    async fn list_workflows(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+    ) -> Result<Vec<String>> {
+        // Seeded workflows carry no labels
+        if !matches_label_selector(&HashMap::new(), label_selector) {
+            return Ok(Vec::new());
+        }
+        Ok(self.workflow_names(namespace))
+    }
+
+    async fn delete_workflow(&self, namespace: &str, name: &str) -> Result<bool> {
+        Ok(self
+            .state()
+            .workflows
+            .remove(&(namespace.to_string(), name.to_string())))
+    }
+
+    async fn get_application(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<Option<ApplicationStatus>> {
+        Ok(self
+            .state()
+            .applications
+            .get(&(namespace.to_string(), name.to_string()))
+            .cloned())
+    }
+
+    async fn event_messages(&self, namespace: &str, object: &str) -> Result<Vec<String>> {
+        Ok(self
+            .state()
+            .events
+            .get(&(namespace.to_string(), object.to_string()))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_lease(&self, namespace: &str, name: &str) -> Result<Option<LeaseRecord>> {
+        let state = self.state();
+        if state.leases_unavailable {
+            anyhow::bail!("leases.coordination.k8s.io \"{name}\" unavailable");
+        }
+        Ok(state
+            .leases
+            .get(&(namespace.to_string(), name.to_string()))
+            .cloned())
+    }
+
+    async fn write_lease(&self, namespace: &str, name: &str, lease: &LeaseRecord) -> Result<bool> {
+        let mut state = self.state();
+        if state.leases_unavailable {
+            anyhow::bail!("leases.coordination.k8s.io \"{name}\" unavailable");
+        }
+        Ok(state.write(
+            |s| &mut s.leases,
+            |l| &mut l.resource_version,
+            (namespace.to_string(), name.to_string()),
+            lease,
+        ))
+    }
+
+    async fn get_config_map(&self, namespace: &str, name: &str) -> Result<Option<ConfigMapData>> {
+        Ok(self
+            .state()
+            .config_maps
+            .get(&(namespace.to_string(), name.to_string()))
+            .cloned())
+    }
+
+    async fn write_config_map(
+        &self,
+        namespace: &str,
+        name: &str,
+        config_map: &ConfigMapData,
+    ) -> Result<bool> {
+        Ok(self.state().write(
+            |s| &mut s.config_maps,
+            |c| &mut c.resource_version,
+            (namespace.to_string(), name.to_string()),
+            config_map,
+        ))
+    }
+
+    async fn list_config_maps(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+    ) -> Result<BTreeMap<String, ConfigMapData>> {
+        let state = self.state();
+        let no_labels = HashMap::new();
+        Ok(state
+            .config_maps
+            .iter()
+            .filter(|((ns, name), _)| {
+                let labels = state
+                    .config_map_labels
+                    .get(&(ns.clone(), name.clone()))
+                    .unwrap_or(&no_labels);
+                ns == namespace && matches_label_selector(labels, label_selector)
+            })
+            .map(|((_, name), cm)| (name.clone(), cm.clone()))
+            .collect())
+    }
+
+    async fn delete_config_map(&self, namespace: &str, name: &str) -> Result<bool> {
+        let mut state = self.state();
+        let key = (namespace.to_string(), name.to_string());
+        state.config_map_labels.remove(&key);
+        Ok(state.config_maps.remove(&key).is_some())
+    }
+
+    async fn list_persistent_volume_claims(&self, namespace: &str) -> Result<Vec<String>> {
+        let mut names: Vec<String> = self
+            .state()
+            .persistent_volume_claims
+            .iter()
+            .filter(|(ns, _)| ns == namespace)
+            .map(|(_, name)| name.clone())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    async fn delete_persistent_volume_claim(&self, namespace: &str, name: &str) -> Result<bool> {
+        Ok(self
+            .state()
+            .persistent_volume_claims
+            .remove(&(namespace.to_string(), name.to_string())))
+    }
+}
+
+/// A comment recorded by [`FakeGitHub`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedComment {
+    pub repo: String,
+    pub number: u64,
+    pub body: String,
+}
+
+#[derive(Default)]
+struct GitHubState {
+    issues: Vec<(String, Issue)>,
+    pull_requests: Vec<(String, PullRequest)>,
+    workflow_runs: Vec<(String, String, WorkflowRun)>,
+    commits: Vec<(String, Commit)>,
+    commit_files: HashMap<(String, String), Vec<CommitFile>>,
+    checks: HashMap<(String, String), CheckSummary>,
+    check_runs: HashMap<(String, String), Vec<CheckRun>>,
+    reviews: HashMap<(String, u64), Vec<Review>>,
+    run_logs: HashMap<(String, u64), String>,
+    comments: Vec<RecordedComment>,
+    next_number: u64,
+}
+
+impl GitHubState {
+    fn issue_mut(&mut self, repo: &str, number: u64) -> Result<&mut Issue> {
+        self.issues
+            .iter_mut()
+            .find(|(r, i)| r == repo && i.number == number)
+            .map(|(_, i)| i)
+            .ok_or_else(|| anyhow::anyhow!("issue {repo}#{number} not found"))
+    }
+
+    fn allocate_number(&mut self) -> u64 {
+        self.next_number += 1;
+        self.next_number
+    }
+}
+
+/// In-memory GitHub.
+#[derive(Default)]
+pub struct FakeGitHub {
+    state: Mutex<GitHubState>,
+}
+
+impl FakeGitHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, GitHubState> {
+        self.state.lock().expect("fake GitHub lock poisoned")
+    }
+
+    /// Seed an issue. Issue numbers created later continue after the
+    /// highest seeded number.
+    #[must_use]
+    pub fn with_issue(self, repo: &str, issue: Issue) -> Self {
+        {
+            let mut state = self.state();
+            state.next_number = state.next_number.max(issue.number);
+            state.issues.push((repo.to_string(), issue));
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn with_pull_request(self, repo: &str, pr: PullRequest) -> Self {
+        {
+            let mut state = self.state();
+            state.next_number = state.next_number.max(pr.number);
+            state.pull_requests.push((repo.to_string(), pr));
+        }
+        self
+    }
+
+    #[must_use]
+    pub fn with_workflow_run(self, repo: &str, workflow: &str, run: WorkflowRun) -> Self {
+        self.state()
+            .workflow_runs
+            .push((repo.to_string(), workflow.to_string(), run));
+        self
+    }
+
+    #[must_use]
+    pub fn with_commit(self, repo: &str, commit: Commit) -> Self {
+        self.state().commits.push((repo.to_string(), commit));
+        self
+    }
+
+    #[must_use]
+    pub fn with_commit_files(self, repo: &str, sha: &str, files: Vec<CommitFile>) -> Self {
+        self.state()
+            .commit_files
+            .insert((repo.to_string(), sha.to_string()), files);
+        self
+    }
+
+    /// Seed the check runs on a ref (default none).
+    #[must_use]
+    pub fn with_checks(self, repo: &str, git_ref: &str, checks: CheckSummary) -> Self {
+        self.state()
+            .checks
+            .insert((repo.to_string(), git_ref.to_string()), checks);
+        self
+    }
+
+    #[must_use]
+    pub fn with_check_runs(self, repo: &str, git_ref: &str, runs: Vec<CheckRun>) -> Self {
+        self.state()
+            .check_runs
+            .insert((repo.to_string(), git_ref.to_string()), runs);
+        self
+    }
+
+    #[must_use]
+    pub fn with_reviews(self, repo: &str, number: u64, reviews: Vec<Review>) -> Self {
+        self.state()
+            .reviews
+            .insert((repo.to_string(), number), reviews);
+        self
+    }
+
+    #[must_use]
+    pub fn with_run_logs(self, repo: &str, run_id: u64, logs: &str) -> Self {
+        self.state()
+            .run_logs
+            .insert((repo.to_string(), run_id), logs.to_string());
+        self
+    }
+
+    /// All issues in `repo`, open and closed, in creation order.
+    pub fn issues(&self, repo: &str) -> Vec<Issue> {
+        self.state()
+            .issues
+            .iter()
+            .filter(|(r, _)| r == repo)
+            .map(|(_, i)| i.clone())
+            .collect()
+    }
+
+    /// Every comment posted, in order.
+    pub fn comments(&self) -> Vec<RecordedComment> {
+        self.state().comments.clone()
+    }
+}
+
+#[async_trait]
+impl GitHubClient for FakeGitHub {
+    async fn list_issues(&self, repo: &str, query: &IssueQuery) -> Result<Vec<Issue>> {
+        let mut issues: Vec<Issue> = self
+            .issues(repo)
+            .into_iter()
+            .filter(|i| query.include_closed || i.open)
+            .filter(|i| query.labels.iter().all(|l| i.labels.contains(l)))
+            .collect();
+        issues.sort_by_key(|i| std::cmp::Reverse(i.created_at));
+        issues.truncate(query.limit);
+        Ok(issues)
+    }
+
+    async fn create_issue(
+        &self,
+        repo: &str,
+        title: &str,
+        body: &str,
+        labels: &[String],
+    ) -> Result<u64> {
+        let mut state = self.state();
+        let number = state.allocate_number();
+        state.issues.push((
+            repo.to_string(),
+            Issue {
+                number,
+                title: title.to_string(),
+                body: body.to_string(),
+                labels: labels.to_vec(),
+                open: true,
+                created_at: Utc::now(),
+            },
+        ));
+        Ok(number)
+    }
+
+    async fn comment(&self, repo: &str, number: u64, body: &str) -> Result<()> {
+        let mut state = self.state();
+        let exists = state
+            .issues
+            .iter()
+            .any(|(r, i)| r == repo && i.number == number)
+            || state
+                .pull_requests
+                .iter()
+                .any(|(r, p)| r == repo && p.number == number);
+        if !exists {
+            anyhow::bail!("{repo}#{number} not found");
+        }
+        state.comments.push(RecordedComment {
+            repo: repo.to_string(),
+            number,
+            body: body.to_string(),
+        });
+        Ok(())
+    }
+
+    async fn close_issue(&self, repo: &str, number: u64) -> Result<()> {
+        self.state().issue_mut(repo, number)?.open = false;
+        Ok(())
+    }
+
+    async fn add_labels(&self, repo: &str, number: u64, labels: &[String]) -> Result<()> {
+        let mut state = self.state();
+        let issue = state.issue_mut(repo, number)?;
+        for label in labels {
+            if !issue.labels.contains(label) {
+                issue.labels.push(label.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_pull_request(&self, repo: &str, number: u64) -> Result<Option<PullRequest>> {
+        Ok(self
+            .state()
+            .pull_requests
+            .iter()
+            .find(|(r, p)| r == repo && p.number == number)
+            .map(|(_, p)| p.clone()))
+    }
+
+    async fn find_pull_request(
+        &self,
+        repo: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequest>> {
+        Ok(self
+            .state()
+            .pull_requests
+            .iter()
+            .filter(|(r, p)| r == repo && p.open && p.head_branch == head_branch)
+            .map(|(_, p)| p.clone())
+            .max_by_key(|p| p.number))
+    }
+
+    async fn check_summary(&self, repo: &str, git_ref: &str) -> Result<CheckSummary> {
+        Ok(self
+            .state()
+            .checks
+            .get(&(repo.to_string(), git_ref.to_string()))
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn list_pull_requests(&self, repo: &str, label: &str) -> Result<Vec<PullRequest>> {
+        let mut pull_requests: Vec<PullRequest> = self
+            .state()
+            .pull_requests
+            .iter()
+            .filter(|(r, p)| r == repo && p.open && p.labels.iter().any(|l| l == label))
+            .map(|(_, p)| p.clone())
+            .collect();
+        pull_requests.sort_by_key(|p| std::cmp::Reverse(p.number));
+        Ok(pull_requests)
+    }
+
+    async fn pull_request_reviews(&self, repo: &str, number: u64) -> Result<Vec<Review>> {
+        Ok(self
+            .state()
+            .reviews
+            .get(&(repo.to_string(), number))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn check_runs(&self, repo: &str, git_ref: &str) -> Result<Vec<CheckRun>> {
+        Ok(self
+            .state()
+            .check_runs
+            .get(&(repo.to_string(), git_ref.to_string()))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn commit_files(&self, repo: &str, sha: &str) -> Result<Vec<CommitFile>> {
+        self.state()
+            .commit_files
+            .get(&(repo.to_string(), sha.to_string()))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("commit {sha} not found in {repo}"))
+    }
+
+    async fn compare_commits(&self, repo: &str, base: &str, head: &str) -> Result<Vec<Commit>> {
+        // Seeded commits are a linear history in insertion order
+        let commits: Vec<Commit> = self
+            .state()
+            .commits
+            .iter()
+            .filter(|(r, _)| r == repo)
+            .map(|(_, c)| c.clone())
+            .collect();
+        let position = |sha: &str| commits.iter().position(|c| c.sha == sha);
+        let (Some(base), Some(head)) = (position(base), position(head)) else {
+            anyhow::bail!("cannot compare {base}...{head} in {repo}");
+        };
+        Ok(commits
+            .get(base + 1..=head)
+            .map(<[Commit]>::to_vec)
+            .unwrap_or_default())
+    }
+
+    async fn workflow_run_logs(&self, repo: &str, run_id: u64) -> Result<String> {
+        self.state()
+            .run_logs
+            .get(&(repo.to_string(), run_id))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("run {run_id} not found in {repo}"))
+    }
+
+    async fn list_workflow_runs(
+        &self,
+        repo: &str,
+        workflow: &str,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<WorkflowRun>> {
+        let mut runs: Vec<WorkflowRun> = self
+            .state()
+            .workflow_runs
+            .iter()
+            .filter(|(r, w, _)| r == repo && w == workflow)
+            .filter(|(_, _, run)| {
+                status.is_none_or(|s| run.status == s || run.conclusion.as_deref() == Some(s))
+            })
+            .map(|(_, _, run)| run.clone())
+            .collect();
+        runs.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+        runs.truncate(limit);
+        Ok(runs)
+    }
+
+    async fn list_commits_since(
+        &self,
+        repo: &str,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Commit>> {
+        let mut commits: Vec<Commit> = self
+            .state()
+            .commits
+            .iter()
+            .filter(|(r, c)| r == repo && c.committed_at.is_some_and(|at| at >= since))
+            .map(|(_, c)| c.clone())
+            .collect();
+        commits.sort_by_key(|c| std::cmp::Reverse(c.committed_at));
+        commits.truncate(limit);
+        Ok(commits)
+    }
+}
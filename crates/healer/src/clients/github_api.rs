@@ -0,0 +1,473 @@
+//! octocrab implementation of [`GitHubClient`].
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use octocrab::models::issues::IssueStateReason;
+use octocrab::models::workflows::Conclusion;
+use octocrab::models::{pulls, IssueState, RunId};
+use octocrab::{params, Octocrab};
+
+use chrono::{DateTime, Utc};
+
+use super::{
+    split_repo, CheckRun, CheckSummary, Commit, CommitFile, GitHubClient, Issue, IssueQuery,
+    PullRequest, Review, WorkflowRun,
+};
+
+/// GitHub's maximum page size.
+const MAX_PER_PAGE: usize = 100;
+
+/// GitHub client backed by octocrab.
+#[derive(Clone)]
+pub struct OctocrabGitHubClient {
+    octocrab: Octocrab,
+}
+
+impl OctocrabGitHubClient {
+    /// Create a client authenticated with `GITHUB_TOKEN` (or `GH_TOKEN`, as
+    /// used by the `gh` CLI).
+    pub fn from_env() -> Result<Self> {
+        let token = std::env::var("GITHUB_TOKEN")
+            .or_else(|_| std::env::var("GH_TOKEN"))
+            .context("GITHUB_TOKEN or GH_TOKEN must be set")?;
+        let octocrab = Octocrab::builder()
+            .personal_token(token)
+            .build()
+            .context("Failed to create GitHub client")?;
+        Ok(Self::new(octocrab))
+    }
+
+    /// Wrap an existing octocrab instance.
+    pub fn new(octocrab: Octocrab) -> Self {
+        Self { octocrab }
+    }
+}
+
+fn per_page(limit: usize) -> u8 {
+    limit.clamp(1, MAX_PER_PAGE) as u8
+}
+
+fn is_not_found(err: &octocrab::Error) -> bool {
+    matches!(err, octocrab::Error::GitHub { source, .. } if source.status_code.as_u16() == 404)
+}
+
+fn to_pull_request(pr: pulls::PullRequest) -> PullRequest {
+    PullRequest {
+        number: pr.number,
+        title: pr.title.unwrap_or_default(),
+        head_branch: pr.head.ref_field,
+        head_sha: pr.head.sha,
+        base_branch: pr.base.ref_field,
+        html_url: pr.html_url.map(|u| u.to_string()).unwrap_or_default(),
+        open: pr.state == Some(IssueState::Open),
+        merged: pr.merged_at.is_some(),
+        mergeable: pr.mergeable,
+        draft: pr.draft.unwrap_or(false),
+        labels: pr
+            .labels
+            .unwrap_or_default()
+            .into_iter()
+            .map(|l| l.name)
+            .collect(),
+        created_at: pr.created_at,
+    }
+}
+
+/// Check run conclusions that count as failing.
+const FAILING_CONCLUSIONS: [&str; 4] = ["failure", "timed_out", "cancelled", "action_required"];
+
+#[async_trait]
+impl GitHubClient for OctocrabGitHubClient {
+    async fn list_issues(&self, repo: &str, query: &IssueQuery) -> Result<Vec<Issue>> {
+        let (owner, name) = split_repo(repo)?;
+        let state = if query.include_closed {
+            params::State::All
+        } else {
+            params::State::Open
+        };
+        let page = self
+            .octocrab
+            .issues(owner, name)
+            .list()
+            .state(state)
+            .labels(&query.labels)
+            .sort(params::issues::Sort::Created)
+            .direction(params::Direction::Descending)
+            .per_page(per_page(query.limit))
+            .send()
+            .await
+            .with_context(|| format!("Failed to list issues in {repo}"))?;
+
+        Ok(page
+            .items
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .take(query.limit)
+            .map(|issue| Issue {
+                number: issue.number,
+                title: issue.title,
+                body: issue.body.unwrap_or_default(),
+                labels: issue.labels.into_iter().map(|l| l.name).collect(),
+                open: issue.state == IssueState::Open,
+                created_at: issue.created_at,
+            })
+            .collect())
+    }
+
+    async fn create_issue(
+        &self,
+        repo: &str,
+        title: &str,
+        body: &str,
+        labels: &[String],
+    ) -> Result<u64> {
+        let (owner, name) = split_repo(repo)?;
+        let issue = self
+            .octocrab
+            .issues(owner, name)
+            .create(title)
+            .body(body)
+            .labels(labels.to_vec())
+            .send()
+            .await
+            .with_context(|| format!("Failed to create issue in {repo}"))?;
+        Ok(issue.number)
+    }
+
+    async fn comment(&self, repo: &str, number: u64, body: &str) -> Result<()> {
+        let (owner, name) = split_repo(repo)?;
+        self.octocrab
+            .issues(owner, name)
+            .create_comment(number, body)
+            .await
+            .with_context(|| format!("Failed to comment on {repo}#{number}"))?;
+        Ok(())
+    }
+
+    async fn close_issue(&self, repo: &str, number: u64) -> Result<()> {
+        let (owner, name) = split_repo(repo)?;
+        self.octocrab
+            .issues(owner, name)
+            .update(number)
+            .state(IssueState::Closed)
+            .state_reason(IssueStateReason::Completed)
+            .send()
+            .await
+            .with_context(|| format!("Failed to close {repo}#{number}"))?;
+        Ok(())
+    }
+
+    async fn add_labels(&self, repo: &str, number: u64, labels: &[String]) -> Result<()> {
+        let (owner, name) = split_repo(repo)?;
+        self.octocrab
+            .issues(owner, name)
+            .add_labels(number, labels)
+            .await
+            .with_context(|| format!("Failed to label {repo}#{number}"))?;
+        Ok(())
+    }
+
+    async fn get_pull_request(&self, repo: &str, number: u64) -> Result<Option<PullRequest>> {
+        let (owner, name) = split_repo(repo)?;
+        match self.octocrab.pulls(owner, name).get(number).await {
+            Ok(pr) => Ok(Some(to_pull_request(pr))),
+            Err(e) if is_not_found(&e) => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to get PR {repo}#{number}")),
+        }
+    }
+
+    async fn find_pull_request(
+        &self,
+        repo: &str,
+        head_branch: &str,
+    ) -> Result<Option<PullRequest>> {
+        let (owner, name) = split_repo(repo)?;
+        let page = self
+            .octocrab
+            .pulls(owner, name)
+            .list()
+            .state(params::State::Open)
+            .head(format!("{owner}:{head_branch}"))
+            .per_page(1)
+            .send()
+            .await
+            .with_context(|| format!("Failed to list PRs for {head_branch} in {repo}"))?;
+        let Some(listed) = page.items.into_iter().next() else {
+            return Ok(None);
+        };
+        // `mergeable` is only computed on the single-PR endpoint
+        self.get_pull_request(repo, listed.number).await
+    }
+
+    async fn check_summary(&self, repo: &str, git_ref: &str) -> Result<CheckSummary> {
+        let (owner, name) = split_repo(repo)?;
+        let checks = self
+            .octocrab
+            .checks(owner, name)
+            .list_check_runs_for_git_ref(params::repos::Commitish(git_ref.to_string()))
+            .per_page(per_page(MAX_PER_PAGE))
+            .send()
+            .await
+            .with_context(|| format!("Failed to list check runs for {git_ref} in {repo}"))?;
+        let conclusions: Vec<Option<&str>> = checks
+            .check_runs
+            .iter()
+            .map(|run| run.conclusion.as_deref())
+            .collect();
+        Ok(CheckSummary {
+            total: conclusions.len(),
+            succeeded: conclusions
+                .iter()
+                .filter(|c| **c == Some("success"))
+                .count(),
+            failed: conclusions
+                .iter()
+                .filter(|c| c.is_some_and(|c| FAILING_CONCLUSIONS.contains(&c)))
+                .count(),
+        })
+    }
+
+    async fn list_pull_requests(&self, repo: &str, label: &str) -> Result<Vec<PullRequest>> {
+        let (owner, name) = split_repo(repo)?;
+        // The pulls endpoint cannot filter by label; the issues endpoint can
+        // and lists pull requests too
+        let page = self
+            .octocrab
+            .issues(owner, name)
+            .list()
+            .state(params::State::Open)
+            .labels(&[label.to_string()])
+            .sort(params::issues::Sort::Created)
+            .direction(params::Direction::Descending)
+            .per_page(per_page(MAX_PER_PAGE))
+            .send()
+            .await
+            .with_context(|| format!("Failed to list PRs labelled {label} in {repo}"))?;
+
+        let mut pull_requests = Vec::new();
+        for issue in page.items.into_iter().filter(|i| i.pull_request.is_some()) {
+            if let Some(pr) = self.get_pull_request(repo, issue.number).await? {
+                pull_requests.push(pr);
+            }
+        }
+        Ok(pull_requests)
+    }
+
+    async fn pull_request_reviews(&self, repo: &str, number: u64) -> Result<Vec<Review>> {
+        let (owner, name) = split_repo(repo)?;
+        let page = self
+            .octocrab
+            .pulls(owner, name)
+            .list_reviews(number)
+            .per_page(per_page(MAX_PER_PAGE))
+            .send()
+            .await
+            .with_context(|| format!("Failed to list reviews on {repo}#{number}"))?;
+        Ok(page
+            .items
+            .into_iter()
+            .filter_map(|review| {
+                let state = serde_json::to_value(review.state?).ok()?;
+                Some(Review {
+                    author: review.user?.login,
+                    state: state.as_str()?.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    async fn check_runs(&self, repo: &str, git_ref: &str) -> Result<Vec<CheckRun>> {
+        let (owner, name) = split_repo(repo)?;
+        let checks = self
+            .octocrab
+            .checks(owner, name)
+            .list_check_runs_for_git_ref(params::repos::Commitish(git_ref.to_string()))
+            .per_page(per_page(MAX_PER_PAGE))
+            .send()
+            .await
+            .with_context(|| format!("Failed to list check runs for {git_ref} in {repo}"))?;
+        Ok(checks
+            .check_runs
+            .into_iter()
+            .map(|run| {
+                // octocrab does not model `status`; infer it from the timestamps
+                let status = if run.completed_at.is_some() || run.conclusion.is_some() {
+                    "completed"
+                } else if run.started_at.is_some() {
+                    "in_progress"
+                } else {
+                    "queued"
+                };
+                CheckRun {
+                    name: run.name,
+                    status: status.to_string(),
+                    conclusion: run.conclusion,
+                }
+            })
+            .collect())
+    }
+
+    async fn commit_files(&self, repo: &str, sha: &str) -> Result<Vec<CommitFile>> {
+        let (owner, name) = split_repo(repo)?;
+        let commit = self
+            .octocrab
+            .commits(owner, name)
+            .get(sha)
+            .await
+            .with_context(|| format!("Failed to get commit {sha} in {repo}"))?;
+        Ok(commit
+            .files
+            .unwrap_or_default()
+            .into_iter()
+            .map(|file| CommitFile {
+                filename: file.filename,
+                status: serde_json::to_value(&file.status)
+                    .ok()
+                    .and_then(|v| v.as_str().map(String::from))
+                    .unwrap_or_default(),
+                additions: file.additions,
+                deletions: file.deletions,
+            })
+            .collect())
+    }
+
+    async fn compare_commits(&self, repo: &str, base: &str, head: &str) -> Result<Vec<Commit>> {
+        let (owner, name) = split_repo(repo)?;
+        let comparison = self
+            .octocrab
+            .commits(owner, name)
+            .compare(base, head)
+            .send()
+            .await
+            .with_context(|| format!("Failed to compare {base}...{head} in {repo}"))?;
+        Ok(comparison
+            .commits
+            .into_iter()
+            .map(|commit| {
+                let author = commit.commit.author;
+                Commit {
+                    sha: commit.sha,
+                    message: commit.commit.message,
+                    committed_at: author
+                        .as_ref()
+                        .and_then(|a| a.date.as_deref())
+                        .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                        .map(|d| d.with_timezone(&Utc)),
+                    author: author.and_then(|a| a.name).unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+
+    async fn workflow_run_logs(&self, repo: &str, run_id: u64) -> Result<String> {
+        let (owner, name) = split_repo(repo)?;
+        let jobs = self
+            .octocrab
+            .workflows(owner, name)
+            .list_jobs(RunId(run_id))
+            .per_page(per_page(MAX_PER_PAGE))
+            .send()
+            .await
+            .with_context(|| format!("Failed to list jobs of run {run_id} in {repo}"))?
+            .items;
+        let failed: Vec<_> = jobs
+            .iter()
+            .filter(|job| {
+                matches!(
+                    job.conclusion,
+                    Some(Conclusion::Failure | Conclusion::TimedOut)
+                )
+            })
+            .collect();
+        let selected = if failed.is_empty() {
+            jobs.iter().collect()
+        } else {
+            failed
+        };
+
+        let mut logs = String::new();
+        for job in selected {
+            let route = format!("/repos/{owner}/{name}/actions/jobs/{}/logs", job.id);
+            let response = self
+                .octocrab
+                ._get(route)
+                .await
+                .with_context(|| format!("Failed to fetch logs of job {}", job.name))?;
+            let response = self.octocrab.follow_location_to_data(response).await?;
+            let body = self.octocrab.body_to_string(response).await?;
+            logs.push_str("=== ");
+            logs.push_str(&job.name);
+            logs.push_str(" ===\n");
+            logs.push_str(&body);
+            if !body.ends_with('\n') {
+                logs.push('\n');
+            }
+        }
+        Ok(logs)
+    }
+
+    async fn list_workflow_runs(
+        &self,
+        repo: &str,
+        workflow: &str,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<WorkflowRun>> {
+        let (owner, name) = split_repo(repo)?;
+        let workflows = self.octocrab.workflows(owner, name);
+        let mut request = workflows.list_runs(workflow).per_page(per_page(limit));
+        if let Some(status) = status {
+            request = request.status(status);
+        }
+        let page = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to list runs of {workflow} in {repo}"))?;
+
+        Ok(page
+            .items
+            .into_iter()
+            .take(limit)
+            .map(|run| WorkflowRun {
+                id: run.id.into_inner(),
+                workflow_name: run.name,
+                head_branch: run.head_branch,
+                head_sha: run.head_sha,
+                status: run.status,
+                conclusion: run.conclusion,
+                html_url: run.html_url.to_string(),
+                created_at: run.created_at,
+            })
+            .collect())
+    }
+
+    async fn list_commits_since(
+        &self,
+        repo: &str,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Commit>> {
+        let (owner, name) = split_repo(repo)?;
+        let page = self
+            .octocrab
+            .repos(owner, name)
+            .list_commits()
+            .since(since)
+            .per_page(per_page(limit))
+            .send()
+            .await
+            .with_context(|| format!("Failed to list commits in {repo}"))?;
+
+        Ok(page
+            .items
+            .into_iter()
+            .take(limit)
+            .map(|commit| Commit {
+                sha: commit.sha,
+                committed_at: commit.commit.committer.and_then(|c| c.date),
+                author: commit.commit.author.map(|a| a.name).unwrap_or_default(),
+                message: commit.commit.message,
+            })
+            .collect())
+    }
+}
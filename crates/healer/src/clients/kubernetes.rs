@@ -0,0 +1,496 @@
+//! kube-rs implementation of [`ClusterClient`].
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use k8s_openapi::api::coordination::v1::{Lease, LeaseSpec};
+use k8s_openapi::api::core::v1::{ConfigMap, Event, PersistentVolumeClaim, Pod as K8sPod};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta};
+use kube::api::{
+    ApiResource, AttachParams, DeleteParams, DynamicObject, GroupVersionKind, ListParams,
+    LogParams, PostParams,
+};
+use kube::{Api, Client};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::io::AsyncReadExt;
+
+use std::collections::BTreeMap;
+
+use super::{ApplicationStatus, ClusterClient, ConfigMapData, LeaseRecord};
+use crate::k8s::{parse_coderun_from_json, parse_pod_from_json, CodeRun, Pod};
+
+/// Cluster client backed by kube-rs.
+#[derive(Clone)]
+pub struct KubeClusterClient {
+    client: Client,
+    coderun: ApiResource,
+    workflow: ApiResource,
+    application: ApiResource,
+}
+
+impl KubeClusterClient {
+    /// Create a client from in-cluster config or the local kubeconfig.
+    pub async fn try_default() -> Result<Self> {
+        let client = Client::try_default()
+            .await
+            .context("Failed to create Kubernetes client")?;
+        Ok(Self::new(client))
+    }
+
+    /// Wrap an existing kube client.
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            coderun: ApiResource::from_gvk(&GroupVersionKind::gvk(
+                "agents.platform",
+                "v1",
+                "CodeRun",
+            )),
+            workflow: ApiResource::from_gvk(&GroupVersionKind::gvk(
+                "argoproj.io",
+                "v1alpha1",
+                "Workflow",
+            )),
+            application: ApiResource::from_gvk(&GroupVersionKind::gvk(
+                "argoproj.io",
+                "v1alpha1",
+                "Application",
+            )),
+        }
+    }
+
+    fn pods(&self, namespace: &str) -> Api<K8sPod> {
+        Api::namespaced(self.client.clone(), namespace)
+    }
+
+    fn coderuns(&self, namespace: &str) -> Api<DynamicObject> {
+        Api::namespaced_with(self.client.clone(), namespace, &self.coderun)
+    }
+
+    fn workflows(&self, namespace: &str) -> Api<DynamicObject> {
+        Api::namespaced_with(self.client.clone(), namespace, &self.workflow)
+    }
+}
+
+fn list_params(label_selector: Option<&str>) -> ListParams {
+    match label_selector {
+        Some(selector) => ListParams::default().labels(selector),
+        None => ListParams::default(),
+    }
+}
+
+/// Create `object` (no resource version) or replace it, mapping a 409
+/// conflict to `false`.
+async fn create_or_replace<K>(api: &Api<K>, name: &str, object: &K) -> kube::Result<bool>
+where
+    K: kube::Resource + Clone + DeserializeOwned + Serialize + std::fmt::Debug,
+{
+    let params = PostParams::default();
+    let result = if object.meta().resource_version.is_some() {
+        api.replace(name, &params, object).await
+    } else {
+        api.create(&params, object).await
+    };
+    match result {
+        Ok(_) => Ok(true),
+        Err(kube::Error::Api(e)) if e.code == 409 => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Delete `name`, mapping a 404 to `false`.
+async fn delete_if_exists<K>(api: &Api<K>, name: &str) -> kube::Result<bool>
+where
+    K: kube::Resource + Clone + DeserializeOwned + std::fmt::Debug,
+{
+    match api.delete(name, &DeleteParams::default()).await {
+        Ok(_) => Ok(true),
+        Err(kube::Error::Api(e)) if e.code == 404 => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Convert a typed object to JSON so the shared `kubectl -o json` parsers
+/// can be reused.
+fn to_json<T: Serialize>(object: &T) -> Result<serde_json::Value> {
+    serde_json::to_value(object).context("Failed to serialize Kubernetes object")
+}
+
+#[async_trait]
+impl ClusterClient for KubeClusterClient {
+    async fn get_pod(&self, namespace: &str, name: &str) -> Result<Option<Pod>> {
+        let pod = self
+            .pods(namespace)
+            .get_opt(name)
+            .await
+            .with_context(|| format!("Failed to get pod {namespace}/{name}"))?;
+        pod.map(|p| Ok(parse_pod_from_json(&to_json(&p)?, namespace)))
+            .transpose()
+    }
+
+    async fn list_pods(&self, namespace: &str, label_selector: Option<&str>) -> Result<Vec<Pod>> {
+        let pods = self
+            .pods(namespace)
+            .list(&list_params(label_selector))
+            .await
+            .with_context(|| format!("Failed to list pods in {namespace}"))?;
+        pods.items
+            .iter()
+            .map(|p| Ok(parse_pod_from_json(&to_json(p)?, namespace)))
+            .collect()
+    }
+
+    async fn delete_pod(&self, namespace: &str, name: &str) -> Result<bool> {
+        match self
+            .pods(namespace)
+            .delete(name, &DeleteParams::default().grace_period(0))
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(kube::Error::Api(e)) if e.code == 404 => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete pod {namespace}/{name}")),
+        }
+    }
+
+    async fn pod_logs(&self, namespace: &str, name: &str, tail_lines: i64) -> Result<String> {
+        let params = LogParams {
+            tail_lines: Some(tail_lines),
+            ..LogParams::default()
+        };
+        self.pods(namespace)
+            .logs(name, &params)
+            .await
+            .with_context(|| format!("Failed to get logs for pod {namespace}/{name}"))
+    }
+
+    async fn container_logs(
+        &self,
+        namespace: &str,
+        name: &str,
+        container: &str,
+        tail_lines: i64,
+    ) -> Result<String> {
+        let params = LogParams {
+            container: Some(container.to_string()),
+            tail_lines: Some(tail_lines),
+            ..LogParams::default()
+        };
+        self.pods(namespace)
+            .logs(name, &params)
+            .await
+            .with_context(|| format!("Failed to get logs for {namespace}/{name}/{container}"))
+    }
+
+    async fn previous_container_logs(
+        &self,
+        namespace: &str,
+        name: &str,
+        container: &str,
+        tail_lines: i64,
+    ) -> Result<String> {
+        let params = LogParams {
+            container: Some(container.to_string()),
+            tail_lines: Some(tail_lines),
+            previous: true,
+            ..LogParams::default()
+        };
+        self.pods(namespace)
+            .logs(name, &params)
+            .await
+            .with_context(|| {
+                format!("Failed to get previous logs for {namespace}/{name}/{container}")
+            })
+    }
+
+    async fn exec(&self, namespace: &str, pod: &str, command: &[&str]) -> Result<String> {
+        let mut process = self
+            .pods(namespace)
+            .exec(
+                pod,
+                command.iter().copied(),
+                &AttachParams::default().stderr(false),
+            )
+            .await
+            .with_context(|| format!("Failed to exec in pod {namespace}/{pod}"))?;
+        let mut stdout = String::new();
+        if let Some(mut reader) = process.stdout() {
+            reader
+                .read_to_string(&mut stdout)
+                .await
+                .with_context(|| format!("Failed to read exec output from {namespace}/{pod}"))?;
+        }
+        let status = match process.take_status() {
+            Some(status) => status.await,
+            None => None,
+        };
+        process.join().await.ok();
+        if let Some(status) = status.filter(|s| s.status.as_deref() == Some("Failure")) {
+            anyhow::bail!(
+                "{} in pod {namespace}/{pod} failed: {}",
+                command.join(" "),
+                status.message.unwrap_or_default()
+            );
+        }
+        Ok(stdout)
+    }
+
+    async fn get_coderun(&self, namespace: &str, name: &str) -> Result<Option<CodeRun>> {
+        let coderun = self
+            .coderuns(namespace)
+            .get_opt(name)
+            .await
+            .with_context(|| format!("Failed to get CodeRun {namespace}/{name}"))?;
+        coderun
+            .map(|c| Ok(parse_coderun_from_json(&to_json(&c)?, namespace)))
+            .transpose()
+    }
+
+    async fn list_coderuns(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+    ) -> Result<Vec<CodeRun>> {
+        let coderuns = self
+            .coderuns(namespace)
+            .list(&list_params(label_selector))
+            .await
+            .with_context(|| format!("Failed to list CodeRuns in {namespace}"))?;
+        coderuns
+            .items
+            .iter()
+            .map(|c| Ok(parse_coderun_from_json(&to_json(c)?, namespace)))
+            .collect()
+    }
+
+    async fn create_coderun(
+        &self,
+        namespace: &str,
+        manifest: &serde_json::Value,
+    ) -> Result<String> {
+        let object: DynamicObject =
+            serde_json::from_value(manifest.clone()).context("Invalid CodeRun manifest")?;
+        let created = self
+            .coderuns(namespace)
+            .create(&PostParams::default(), &object)
+            .await
+            .with_context(|| format!("Failed to create CodeRun in {namespace}"))?;
+        created.metadata.name.context("Created CodeRun has no name")
+    }
+
+    async fn delete_coderun(&self, namespace: &str, name: &str) -> Result<bool> {
+        delete_if_exists(&self.coderuns(namespace), name)
+            .await
+            .with_context(|| format!("Failed to delete CodeRun {namespace}/{name}"))
+    }
+
+    async fn workflow_exists(&self, namespace: &str, name: &str) -> Result<bool> {
+        let workflow = self
+            .workflows(namespace)
+            .get_opt(name)
+            .await
+            .with_context(|| format!("Failed to get workflow {namespace}/{name}"))?;
+        Ok(workflow.is_some())
+    }
+
+    async fn list_workflows(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let workflows = self
+            .workflows(namespace)
+            .list_metadata(&list_params(label_selector))
+            .await
+            .with_context(|| format!("Failed to list workflows in {namespace}"))?;
+        Ok(workflows
+            .items
+            .into_iter()
+            .filter_map(|w| w.metadata.name)
+            .collect())
+    }
+
+    async fn delete_workflow(&self, namespace: &str, name: &str) -> Result<bool> {
+        delete_if_exists(&self.workflows(namespace), name)
+            .await
+            .with_context(|| format!("Failed to delete workflow {namespace}/{name}"))
+    }
+
+    async fn get_application(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<Option<ApplicationStatus>> {
+        let applications: Api<DynamicObject> =
+            Api::namespaced_with(self.client.clone(), namespace, &self.application);
+        let application = applications
+            .get_opt(name)
+            .await
+            .with_context(|| format!("Failed to get application {namespace}/{name}"))?;
+        Ok(application.map(|app| {
+            let status = &app.data["status"];
+            let field = |pointer: &str| {
+                status
+                    .pointer(pointer)
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or("Unknown")
+                    .to_string()
+            };
+            ApplicationStatus {
+                health: field("/health/status"),
+                sync: field("/sync/status"),
+                revision: field("/sync/revision"),
+            }
+        }))
+    }
+
+    async fn event_messages(&self, namespace: &str, object: &str) -> Result<Vec<String>> {
+        let events: Api<Event> = Api::namespaced(self.client.clone(), namespace);
+        let params = ListParams::default().fields(&format!("involvedObject.name={object}"));
+        let mut events = events
+            .list(&params)
+            .await
+            .with_context(|| format!("Failed to list events for {namespace}/{object}"))?
+            .items;
+        events.sort_by_key(|e| {
+            e.last_timestamp
+                .as_ref()
+                .map(|t| t.0)
+                .or_else(|| e.metadata.creation_timestamp.as_ref().map(|t| t.0))
+        });
+        Ok(events.into_iter().filter_map(|e| e.message).collect())
+    }
+
+    async fn get_lease(&self, namespace: &str, name: &str) -> Result<Option<LeaseRecord>> {
+        let leases: Api<Lease> = Api::namespaced(self.client.clone(), namespace);
+        let lease = leases
+            .get_opt(name)
+            .await
+            .with_context(|| format!("Failed to get lease {namespace}/{name}"))?;
+        Ok(lease.map(|lease| {
+            let spec = lease.spec.unwrap_or_default();
+            LeaseRecord {
+                holder: spec.holder_identity,
+                acquired_at: spec.acquire_time.map(|t| t.0),
+                renewed_at: spec.renew_time.map(|t| t.0),
+                duration_secs: spec.lease_duration_seconds.unwrap_or_default(),
+                transitions: spec.lease_transitions.unwrap_or_default(),
+                resource_version: lease.metadata.resource_version,
+            }
+        }))
+    }
+
+    async fn write_lease(&self, namespace: &str, name: &str, lease: &LeaseRecord) -> Result<bool> {
+        let leases: Api<Lease> = Api::namespaced(self.client.clone(), namespace);
+        let object = Lease {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                resource_version: lease.resource_version.clone(),
+                ..ObjectMeta::default()
+            },
+            spec: Some(LeaseSpec {
+                holder_identity: lease.holder.clone(),
+                acquire_time: lease.acquired_at.map(MicroTime),
+                renew_time: lease.renewed_at.map(MicroTime),
+                lease_duration_seconds: Some(lease.duration_secs),
+                lease_transitions: Some(lease.transitions),
+                ..LeaseSpec::default()
+            }),
+        };
+        create_or_replace(&leases, name, &object)
+            .await
+            .with_context(|| format!("Failed to write lease {namespace}/{name}"))
+    }
+
+    async fn get_config_map(&self, namespace: &str, name: &str) -> Result<Option<ConfigMapData>> {
+        let config_maps: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+        let config_map = config_maps
+            .get_opt(name)
+            .await
+            .with_context(|| format!("Failed to get ConfigMap {namespace}/{name}"))?;
+        Ok(config_map.map(|cm| ConfigMapData {
+            data: cm.data.unwrap_or_default(),
+            resource_version: cm.metadata.resource_version,
+        }))
+    }
+
+    async fn write_config_map(
+        &self,
+        namespace: &str,
+        name: &str,
+        config_map: &ConfigMapData,
+    ) -> Result<bool> {
+        let config_maps: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+        let object = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                resource_version: config_map.resource_version.clone(),
+                labels: Some(
+                    [(
+                        "app.kubernetes.io/managed-by".to_string(),
+                        "healer".to_string(),
+                    )]
+                    .into(),
+                ),
+                ..ObjectMeta::default()
+            },
+            data: Some(config_map.data.clone()),
+            ..ConfigMap::default()
+        };
+        create_or_replace(&config_maps, name, &object)
+            .await
+            .with_context(|| format!("Failed to write ConfigMap {namespace}/{name}"))
+    }
+
+    async fn list_config_maps(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+    ) -> Result<BTreeMap<String, ConfigMapData>> {
+        let config_maps: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+        let config_maps = config_maps
+            .list(&list_params(label_selector))
+            .await
+            .with_context(|| format!("Failed to list ConfigMaps in {namespace}"))?;
+        Ok(config_maps
+            .items
+            .into_iter()
+            .filter_map(|cm| {
+                let name = cm.metadata.name?;
+                Some((
+                    name,
+                    ConfigMapData {
+                        data: cm.data.unwrap_or_default(),
+                        resource_version: cm.metadata.resource_version,
+                    },
+                ))
+            })
+            .collect())
+    }
+
+    async fn delete_config_map(&self, namespace: &str, name: &str) -> Result<bool> {
+        let config_maps: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+        delete_if_exists(&config_maps, name)
+            .await
+            .with_context(|| format!("Failed to delete ConfigMap {namespace}/{name}"))
+    }
+
+    async fn list_persistent_volume_claims(&self, namespace: &str) -> Result<Vec<String>> {
+        let claims: Api<PersistentVolumeClaim> = Api::namespaced(self.client.clone(), namespace);
+        let claims = claims
+            .list_metadata(&ListParams::default())
+            .await
+            .with_context(|| format!("Failed to list PVCs in {namespace}"))?;
+        Ok(claims
+            .items
+            .into_iter()
+            .filter_map(|c| c.metadata.name)
+            .collect())
+    }
+
+    async fn delete_persistent_volume_claim(&self, namespace: &str, name: &str) -> Result<bool> {
+        let claims: Api<PersistentVolumeClaim> = Api::namespaced(self.client.clone(), namespace);
+        delete_if_exists(&claims, name)
+            .await
+            .with_context(|| format!("Failed to delete PVC {namespace}/{name}"))
+    }
+}
@@ -0,0 +1,431 @@
+//! Typed Kubernetes and GitHub clients.
+//!
+//! Healer's cluster and GitHub operations go through [`ClusterClient`] and
+//! [`GitHubClient`] instead of shelling out to `kubectl` and `gh`, so flows
+//! like deduplication, issue reconciliation and escalation can be unit tested
+//! against the in-memory fakes in [`fake`]. Leases and ConfigMaps back
+//! leader election and persisted state (see [`crate::ha`]).
+//!
+//! - [`KubeClusterClient`] - kube-rs, using in-cluster or kubeconfig credentials
+//! - [`OctocrabGitHubClient`] - octocrab, authenticated with `GITHUB_TOKEN`/`GH_TOKEN`
+
+#![allow(dead_code)] // Public API - not every operation is used by every binary
+
+pub mod fake;
+mod github_api;
+mod kubernetes;
+
+#[allow(unused_imports)] // Re-exported for tests
+pub use fake::{FakeCluster, FakeGitHub};
+pub use github_api::OctocrabGitHubClient;
+pub use kubernetes::KubeClusterClient;
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::k8s::{CodeRun, Pod};
+
+/// Kubernetes operations used by healer.
+#[async_trait]
+pub trait ClusterClient: Send + Sync {
+    /// Get a pod, or `None` if it does not exist.
+    async fn get_pod(&self, namespace: &str, name: &str) -> Result<Option<Pod>>;
+
+    /// List pods, optionally filtered by an equality label selector
+    /// (`key=value,key2=value2`).
+    async fn list_pods(&self, namespace: &str, label_selector: Option<&str>) -> Result<Vec<Pod>>;
+
+    /// Delete a pod without a grace period. Returns `false` if it did not
+    /// exist.
+    async fn delete_pod(&self, namespace: &str, name: &str) -> Result<bool>;
+
+    /// Last `tail_lines` lines of a pod's logs (all containers' default).
+    async fn pod_logs(&self, namespace: &str, name: &str, tail_lines: i64) -> Result<String>;
+
+    /// Last `tail_lines` lines of one container's logs.
+    async fn container_logs(
+        &self,
+        namespace: &str,
+        name: &str,
+        container: &str,
+        tail_lines: i64,
+    ) -> Result<String>;
+
+    /// Last `tail_lines` lines of the previous (restarted) instance of a
+    /// container.
+    async fn previous_container_logs(
+        &self,
+        namespace: &str,
+        name: &str,
+        container: &str,
+        tail_lines: i64,
+    ) -> Result<String>;
+
+    /// Run `command` in a pod's default container and return its stdout.
+    /// Fails if the command exits non-zero.
+    async fn exec(&self, namespace: &str, pod: &str, command: &[&str]) -> Result<String>;
+
+    /// Get a `CodeRun`, or `None` if it does not exist.
+    async fn get_coderun(&self, namespace: &str, name: &str) -> Result<Option<CodeRun>>;
+
+    /// List `CodeRun`s, optionally filtered by an equality label selector.
+    async fn list_coderuns(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+    ) -> Result<Vec<CodeRun>>;
+
+    /// Create a `CodeRun` from a manifest (which may use `generateName`)
+    /// and return its name.
+    async fn create_coderun(&self, namespace: &str, manifest: &serde_json::Value)
+        -> Result<String>;
+
+    /// Delete a `CodeRun`. Returns `false` if it did not exist.
+    async fn delete_coderun(&self, namespace: &str, name: &str) -> Result<bool>;
+
+    /// Whether an Argo workflow exists.
+    async fn workflow_exists(&self, namespace: &str, name: &str) -> Result<bool>;
+
+    /// Names of Argo workflows, optionally filtered by an equality label
+    /// selector.
+    async fn list_workflows(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+    ) -> Result<Vec<String>>;
+
+    /// Delete an Argo workflow. Returns `false` if it did not exist.
+    async fn delete_workflow(&self, namespace: &str, name: &str) -> Result<bool>;
+
+    /// Health and sync status of an Argo CD Application, or `None` if it
+    /// does not exist.
+    async fn get_application(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<Option<ApplicationStatus>>;
+
+    /// Messages of the events about `object` (by name), oldest first.
+    async fn event_messages(&self, namespace: &str, object: &str) -> Result<Vec<String>>;
+
+    /// Get a `coordination.k8s.io` Lease, or `None` if it does not exist.
+    async fn get_lease(&self, namespace: &str, name: &str) -> Result<Option<LeaseRecord>>;
+
+    /// Create a Lease (no `resource_version`) or replace it (matching
+    /// `resource_version`). Returns `false` if another writer got there first.
+    async fn write_lease(&self, namespace: &str, name: &str, lease: &LeaseRecord) -> Result<bool>;
+
+    /// Get a ConfigMap's data, or `None` if it does not exist.
+    async fn get_config_map(&self, namespace: &str, name: &str) -> Result<Option<ConfigMapData>>;
+
+    /// Create or replace a ConfigMap with the same optimistic concurrency as
+    /// [`write_lease`](Self::write_lease).
+    async fn write_config_map(
+        &self,
+        namespace: &str,
+        name: &str,
+        config_map: &ConfigMapData,
+    ) -> Result<bool>;
+
+    /// List ConfigMaps by name, optionally filtered by an equality label
+    /// selector.
+    async fn list_config_maps(
+        &self,
+        namespace: &str,
+        label_selector: Option<&str>,
+    ) -> Result<BTreeMap<String, ConfigMapData>>;
+
+    /// Delete a ConfigMap. Returns `false` if it did not exist.
+    async fn delete_config_map(&self, namespace: &str, name: &str) -> Result<bool>;
+
+    /// Names of the PersistentVolumeClaims in a namespace.
+    async fn list_persistent_volume_claims(&self, namespace: &str) -> Result<Vec<String>>;
+
+    /// Delete a PersistentVolumeClaim. Returns `false` if it did not exist.
+    async fn delete_persistent_volume_claim(&self, namespace: &str, name: &str) -> Result<bool>;
+}
+
+/// Holder record of a Lease.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LeaseRecord {
+    /// Identity of the current holder
+    pub holder: Option<String>,
+    pub acquired_at: Option<DateTime<Utc>>,
+    pub renewed_at: Option<DateTime<Utc>>,
+    /// How long the holder may go without renewing
+    pub duration_secs: i32,
+    /// Number of times the lease changed hands
+    pub transitions: i32,
+    /// `metadata.resourceVersion`, `None` until the lease is created
+    pub resource_version: Option<String>,
+}
+
+impl LeaseRecord {
+    /// Whether the holder's last renewal has lapsed at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.renewed_at.is_none_or(|renewed| {
+            renewed + chrono::Duration::seconds(i64::from(self.duration_secs)) <= now
+        })
+    }
+}
+
+/// Status of an Argo CD Application.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApplicationStatus {
+    /// `Healthy`, `Progressing`, `Degraded`, ...
+    pub health: String,
+    /// `Synced` or `OutOfSync`
+    pub sync: String,
+    /// Revision the application last synced to
+    pub revision: String,
+}
+
+/// ConfigMap data.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigMapData {
+    pub data: BTreeMap<String, String>,
+    /// `metadata.resourceVersion`, `None` until the ConfigMap is created
+    pub resource_version: Option<String>,
+}
+
+/// A GitHub issue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+    pub open: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filter for listing issues.
+#[derive(Debug, Clone, Default)]
+pub struct IssueQuery {
+    /// Issues must carry all of these labels
+    pub labels: Vec<String>,
+    /// Include closed issues
+    pub include_closed: bool,
+    /// Maximum number of issues to return (newest first)
+    pub limit: usize,
+}
+
+impl IssueQuery {
+    /// Open issues carrying all of `labels`.
+    pub fn open_with_labels(labels: &[&str], limit: usize) -> Self {
+        Self {
+            labels: labels.iter().map(|l| (*l).to_string()).collect(),
+            include_closed: false,
+            limit,
+        }
+    }
+}
+
+/// A GitHub pull request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub head_branch: String,
+    pub head_sha: String,
+    pub base_branch: String,
+    pub html_url: String,
+    pub open: bool,
+    pub merged: bool,
+    /// `None` while GitHub is still computing it
+    pub mergeable: Option<bool>,
+    pub draft: bool,
+    pub labels: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A review on a pull request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    pub author: String,
+    /// `APPROVED`, `CHANGES_REQUESTED`, `COMMENTED`, ...
+    pub state: String,
+}
+
+/// A check run on a ref.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckRun {
+    pub name: String,
+    /// `queued`, `in_progress` or `completed`
+    pub status: String,
+    /// `success`, `failure`, ... once completed
+    pub conclusion: Option<String>,
+}
+
+/// A GitHub Actions workflow run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    pub id: u64,
+    pub workflow_name: String,
+    pub head_branch: String,
+    pub head_sha: String,
+    /// `queued`, `in_progress` or `completed`
+    pub status: String,
+    /// `success`, `failure`, ... once completed
+    pub conclusion: Option<String>,
+    pub html_url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A commit on a repository's default branch.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commit {
+    pub sha: String,
+    pub message: String,
+    pub author: String,
+    pub committed_at: Option<DateTime<Utc>>,
+}
+
+/// Outcome counts of the check runs on a ref.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    /// Failed, timed out, cancelled or needing action
+    pub failed: usize,
+}
+
+/// A file changed by a commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitFile {
+    pub filename: String,
+    /// `added`, `modified`, `removed`, `renamed`, ...
+    pub status: String,
+    pub additions: u64,
+    pub deletions: u64,
+}
+
+/// GitHub operations used by healer. `repo` is always `owner/name`.
+#[async_trait]
+pub trait GitHubClient: Send + Sync {
+    /// List issues (pull requests excluded), newest first.
+    async fn list_issues(&self, repo: &str, query: &IssueQuery) -> Result<Vec<Issue>>;
+
+    /// Create an issue and return its number.
+    async fn create_issue(
+        &self,
+        repo: &str,
+        title: &str,
+        body: &str,
+        labels: &[String],
+    ) -> Result<u64>;
+
+    /// Comment on an issue or pull request.
+    async fn comment(&self, repo: &str, number: u64, body: &str) -> Result<()>;
+
+    /// Close an issue as completed.
+    async fn close_issue(&self, repo: &str, number: u64) -> Result<()>;
+
+    /// Add labels to an issue or pull request.
+    async fn add_labels(&self, repo: &str, number: u64, labels: &[String]) -> Result<()>;
+
+    /// Get a pull request, or `None` if it does not exist.
+    async fn get_pull_request(&self, repo: &str, number: u64) -> Result<Option<PullRequest>>;
+
+    /// The newest open pull request from `head_branch`, if any.
+    async fn find_pull_request(&self, repo: &str, head_branch: &str)
+        -> Result<Option<PullRequest>>;
+
+    /// Outcome counts of the check runs on `git_ref` (a SHA or branch).
+    async fn check_summary(&self, repo: &str, git_ref: &str) -> Result<CheckSummary>;
+
+    /// Open pull requests carrying `label`, newest first.
+    async fn list_pull_requests(&self, repo: &str, label: &str) -> Result<Vec<PullRequest>>;
+
+    /// Reviews on a pull request, oldest first.
+    async fn pull_request_reviews(&self, repo: &str, number: u64) -> Result<Vec<Review>>;
+
+    /// Check runs on a ref.
+    async fn check_runs(&self, repo: &str, git_ref: &str) -> Result<Vec<CheckRun>>;
+
+    /// Files changed by a commit.
+    async fn commit_files(&self, repo: &str, sha: &str) -> Result<Vec<CommitFile>>;
+
+    /// Commits reachable from `head` but not from `base`, oldest first.
+    async fn compare_commits(&self, repo: &str, base: &str, head: &str) -> Result<Vec<Commit>>;
+
+    /// Logs of the failed jobs of a workflow run, or of every job if none
+    /// failed, each headed by the job name.
+    async fn workflow_run_logs(&self, repo: &str, run_id: u64) -> Result<String>;
+
+    /// List runs of a workflow (by name or file), newest first, optionally
+    /// only those with the given status or conclusion (e.g. `success`).
+    async fn list_workflow_runs(
+        &self,
+        repo: &str,
+        workflow: &str,
+        status: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<WorkflowRun>>;
+
+    /// List commits on the default branch since `since`, newest first.
+    async fn list_commits_since(
+        &self,
+        repo: &str,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Commit>>;
+}
+
+/// Split `owner/name` into its parts.
+pub(crate) fn split_repo(repo: &str) -> Result<(&str, &str)> {
+    repo.split_once('/')
+        .filter(|(owner, name)| !owner.is_empty() && !name.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("invalid repository '{repo}', expected owner/name"))
+}
+
+/// Whether `labels` satisfy an equality selector like `a=b,c=d`.
+pub(crate) fn matches_label_selector(
+    labels: &HashMap<String, String>,
+    selector: Option<&str>,
+) -> bool {
+    selector.is_none_or(|selector| {
+        selector
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .all(|term| match term.split_once('=') {
+                Some((key, value)) => {
+                    labels.get(key.trim()).map(String::as_str) == Some(value.trim())
+                }
+                None => labels.contains_key(term),
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_label_selector() {
+        let labels = HashMap::from([
+            ("alert-type".to_string(), "a7".to_string()),
+            ("remediation".to_string(), "true".to_string()),
+        ]);
+        assert!(matches_label_selector(&labels, None));
+        assert!(matches_label_selector(
+            &labels,
+            Some("alert-type=a7,remediation=true")
+        ));
+        assert!(matches_label_selector(&labels, Some("remediation")));
+        assert!(!matches_label_selector(&labels, Some("alert-type=a2")));
+        assert!(!matches_label_selector(&labels, Some("target-pod=x")));
+    }
+
+    #[test]
+    fn test_split_repo() {
+        assert_eq!(split_repo("5dlabs/cto").unwrap(), ("5dlabs", "cto"));
+        assert!(split_repo("cto").is_err());
+        assert!(split_repo("/cto").is_err());
+    }
+}
@@ -8,8 +8,9 @@
 //! `play-task-4-*`) are grouped together, but pods from different workflows (e.g.,
 //! `atlas-*` vs `play-*`) are treated separately.
 
-use anyhow::{Context, Result};
-use std::process::Command;
+use anyhow::Result;
+
+use crate::clients::{ClusterClient, GitHubClient, IssueQuery};
 
 /// Label used to exclude pods from healer monitoring
 pub const EXCLUDE_LABEL: &str = "healer.platform/exclude";
@@ -62,10 +63,16 @@ pub fn should_exclude_pod(labels: &std::collections::HashMap<String, String>) ->
     labels.get(EXCLUDE_LABEL).is_some_and(|v| v == "true")
 }
 
+/// `CodeRun` phases that still count as an active remediation.
+fn is_active_phase(phase: &str) -> bool {
+    matches!(phase, "Pending" | "Running" | "" | "Unknown")
+}
+
 /// Check if there's already an active remediation `CodeRun` for this alert+pod combination.
 ///
 /// Returns `Some(coderun_name)` if a running/pending remediation exists, `None` otherwise.
-pub fn check_existing_remediation(
+pub async fn check_existing_remediation(
+    cluster: &dyn ClusterClient,
     alert_type: &str,
     pod_name: &str,
     namespace: &str,
@@ -73,113 +80,48 @@ pub fn check_existing_remediation(
     // Query for CodeRuns with matching labels that are still active
     let label_selector = format!("alert-type={alert_type},target-pod={pod_name},remediation=true");
 
-    let output = Command::new("kubectl")
-        .args([
-            "get",
-            "coderuns",
-            "-n",
-            namespace,
-            "-l",
-            &label_selector,
-            "-o",
-            "jsonpath={.items[*].metadata.name}",
-        ])
-        .output()
-        .context("Failed to query existing CodeRuns")?;
-
-    if !output.status.success() {
-        // If kubectl fails (e.g., CRD not installed), allow proceeding
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("NotFound") || stderr.contains("the server doesn't have") {
+    let coderuns = match cluster
+        .list_coderuns(namespace, Some(&label_selector))
+        .await
+    {
+        Ok(coderuns) => coderuns,
+        Err(e) => {
+            // If the query fails (e.g., CRD not installed), allow remediation to proceed
+            tracing::warn!("Failed to check existing CodeRuns: {e:#}");
             return Ok(None);
         }
-        // Log but don't fail - allow remediation to proceed
-        tracing::warn!("Failed to check existing CodeRuns: {stderr}");
-        return Ok(None);
-    }
-
-    let names = String::from_utf8_lossy(&output.stdout);
-    let names = names.trim();
-
-    if names.is_empty() {
-        return Ok(None);
-    }
-
-    // Found existing CodeRun(s) - return the first one
-    let first_name = names.split_whitespace().next().unwrap_or("");
-    if first_name.is_empty() {
-        return Ok(None);
-    }
+    };
 
-    // Check if it's still running (not completed/failed)
-    let phase_output = Command::new("kubectl")
-        .args([
-            "get",
-            "coderun",
-            first_name,
-            "-n",
-            namespace,
-            "-o",
-            "jsonpath={.status.phase}",
-        ])
-        .output()
-        .context("Failed to get CodeRun phase")?;
-
-    let phase = String::from_utf8_lossy(&phase_output.stdout);
-    let phase = phase.trim();
-
-    // Only block if the CodeRun is still active
-    match phase {
-        "Pending" | "Running" | "" => Ok(Some(first_name.to_string())),
-        _ => Ok(None), // Completed, Failed, etc. - allow new remediation
-    }
+    // Only block if a matching CodeRun is still active
+    Ok(coderuns
+        .into_iter()
+        .find(|c| is_active_phase(&c.phase))
+        .map(|c| c.name))
 }
 
 /// Check if there's an open GitHub issue for this alert+pod combination.
 ///
 /// Returns `Some(issue_number)` if an open issue exists, `None` otherwise.
 #[allow(dead_code)] // Will be used in Phase 2 of deduplication
-pub fn check_existing_github_issue(
+pub async fn check_existing_github_issue(
+    github: &dyn GitHubClient,
     alert_type: &str,
     pod_name: &str,
     repo: &str,
 ) -> Result<Option<u64>> {
-    // Query GitHub for open issues with matching labels
-    let search_query = format!("[HEAL-{}] {} in:title", alert_type.to_uppercase(), pod_name);
-
-    let output = Command::new("gh")
-        .args([
-            "issue",
-            "list",
-            "--repo",
-            repo,
-            "--state",
-            "open",
-            "--label",
-            &format!("heal,{alert_type}"),
-            "--search",
-            &search_query,
-            "--json",
-            "number",
-            "--jq",
-            ".[0].number",
-        ])
-        .output()
-        .context("Failed to query GitHub issues")?;
-
-    if !output.status.success() {
-        // gh CLI not available or auth issue - allow proceeding
-        return Ok(None);
-    }
-
-    let number_str = String::from_utf8_lossy(&output.stdout);
-    let number_str = number_str.trim();
+    let title_prefix = format!("[HEAL-{}]", alert_type.to_uppercase());
+    let query = IssueQuery::open_with_labels(&["heal", alert_type], 50);
 
-    if number_str.is_empty() || number_str == "null" {
-        return Ok(None);
-    }
+    let issues = match github.list_issues(repo, &query).await {
+        Ok(issues) => issues,
+        // GitHub unavailable or auth issue - allow proceeding
+        Err(_) => return Ok(None),
+    };
 
-    Ok(number_str.parse::<u64>().ok())
+    Ok(issues
+        .into_iter()
+        .find(|i| i.title.starts_with(&title_prefix) && i.title.contains(pod_name))
+        .map(|i| i.number))
 }
 
 /// Check if there's a recent open GitHub issue for this alert TYPE within the same workflow family.
@@ -192,7 +134,8 @@ pub fn check_existing_github_issue(
 /// - An `atlas-*` pod and a `play-*` pod failing with A2 will get separate issues
 ///
 /// Returns `Some((issue_number, title))` if a recent open issue exists for the same family.
-pub fn check_recent_alert_type_issue(
+pub async fn check_recent_alert_type_issue(
+    github: &dyn GitHubClient,
     alert_type: &str,
     pod_name: &str,
     repo: &str,
@@ -201,65 +144,24 @@ pub fn check_recent_alert_type_issue(
     let current_family = extract_workflow_family(pod_name);
 
     // Query for any open issues with this alert type created recently
-    let output = Command::new("gh")
-        .args([
-            "issue",
-            "list",
-            "--repo",
-            repo,
-            "--state",
-            "open",
-            "--label",
-            &format!("heal,{alert_type}"),
-            "--json",
-            "number,title,createdAt",
-            "--limit",
-            "10",
-        ])
-        .output()
-        .context("Failed to query GitHub issues")?;
-
-    if !output.status.success() {
-        return Ok(None);
-    }
-
-    let json_str = String::from_utf8_lossy(&output.stdout);
-    let json_str = json_str.trim();
-
-    if json_str.is_empty() || json_str == "[]" {
-        return Ok(None);
-    }
-
-    // Parse the JSON response
-    let issues: Vec<serde_json::Value> = match serde_json::from_str(json_str) {
-        Ok(v) => v,
+    let query = IssueQuery::open_with_labels(&["heal", alert_type], 10);
+    let issues = match github.list_issues(repo, &query).await {
+        Ok(issues) => issues,
         Err(_) => return Ok(None),
     };
 
     // Find the most recent issue created within the dedup window for the same workflow family
     let now = chrono::Utc::now();
     for issue in issues {
-        let number = issue["number"].as_u64();
-        let title = issue["title"].as_str();
-        let created_at = issue["createdAt"].as_str();
-
-        if let (Some(num), Some(t), Some(created)) = (number, title, created_at) {
-            // Parse the ISO timestamp
-            if let Ok(created_time) = chrono::DateTime::parse_from_rfc3339(created) {
-                let age_mins = (now - created_time.with_timezone(&chrono::Utc))
-                    .num_minutes()
-                    .unsigned_abs();
-
-                // Only dedup if within time window AND same workflow family
-                if age_mins <= DEDUP_WINDOW_MINS {
-                    // Extract pod name from title: "[HEAL-A2] Silent Failure: pod-name-here"
-                    // and check if it's from the same workflow family
-                    if let Some(issue_pod) = extract_pod_from_title(t) {
-                        let issue_family = extract_workflow_family(&issue_pod);
-                        if issue_family == current_family {
-                            return Ok(Some((num, t.to_string())));
-                        }
-                    }
+        let age_mins = (now - issue.created_at).num_minutes().unsigned_abs();
+
+        // Only dedup if within time window AND same workflow family
+        if age_mins <= DEDUP_WINDOW_MINS {
+            // Extract pod name from title: "[HEAL-A2] Silent Failure: pod-name-here"
+            // and check if it's from the same workflow family
+            if let Some(issue_pod) = extract_pod_from_title(&issue.title) {
+                if extract_workflow_family(&issue_pod) == current_family {
+                    return Ok(Some((issue.number, issue.title)));
                 }
             }
         }
@@ -290,43 +192,25 @@ pub fn extract_pod_from_title(title: &str) -> Option<String> {
 /// This prevents spawning multiple remediations for the same systemic failure.
 /// Returns `Some(coderun_name)` if an active remediation exists.
 #[allow(dead_code)] // Will be used for CodeRun-level deduplication
-pub fn check_alert_type_remediation(alert_type: &str, namespace: &str) -> Result<Option<String>> {
+pub async fn check_alert_type_remediation(
+    cluster: &dyn ClusterClient,
+    alert_type: &str,
+    namespace: &str,
+) -> Result<Option<String>> {
     // Query for any active CodeRuns with this alert type
     let label_selector = format!("alert-type={alert_type},remediation=true");
 
-    let output = Command::new("kubectl")
-        .args([
-            "get",
-            "coderuns",
-            "-n",
-            namespace,
-            "-l",
-            &label_selector,
-            "-o",
-            "jsonpath={range .items[*]}{.metadata.name},{.status.phase}{\"\\n\"}{end}",
-        ])
-        .output()
-        .context("Failed to query CodeRuns")?;
-
-    if !output.status.success() {
+    let Ok(coderuns) = cluster
+        .list_coderuns(namespace, Some(&label_selector))
+        .await
+    else {
         return Ok(None);
-    }
-
-    let output_str = String::from_utf8_lossy(&output.stdout);
-
-    // Check if any are still active
-    for line in output_str.lines() {
-        let parts: Vec<&str> = line.split(',').collect();
-        if parts.len() >= 2 {
-            let name = parts[0];
-            let phase = parts[1];
-            if matches!(phase, "Pending" | "Running" | "") && !name.is_empty() {
-                return Ok(Some(name.to_string()));
-            }
-        }
-    }
+    };
 
-    Ok(None)
+    Ok(coderuns
+        .into_iter()
+        .find(|c| is_active_phase(&c.phase) && !c.name.is_empty())
+        .map(|c| c.name))
 }
 
 /// Sanitize a pod name for use as a Kubernetes label value.
@@ -345,6 +229,95 @@ pub fn sanitize_label_value(value: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clients::{FakeCluster, FakeGitHub, Issue};
+    use crate::k8s::CodeRun;
+
+    const REPO: &str = "5dlabs/cto";
+
+    fn remediation(name: &str, phase: &str, pod: &str) -> CodeRun {
+        CodeRun {
+            name: name.to_string(),
+            namespace: "cto".to_string(),
+            phase: phase.to_string(),
+            labels: [
+                ("alert-type", "a7"),
+                ("target-pod", pod),
+                ("remediation", "true"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+            ..CodeRun::default()
+        }
+    }
+
+    fn heal_issue(number: u64, title: &str, age_mins: i64) -> Issue {
+        Issue {
+            number,
+            title: title.to_string(),
+            body: String::new(),
+            labels: vec!["heal".to_string(), "a2".to_string()],
+            open: true,
+            created_at: chrono::Utc::now() - chrono::Duration::minutes(age_mins),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_existing_remediation_only_blocks_active() {
+        let cluster = FakeCluster::new()
+            .with_coderun(remediation("heal-done", "Succeeded", "pod-a"))
+            .with_coderun(remediation("heal-running", "Running", "pod-b"));
+
+        let found = check_existing_remediation(&cluster, "a7", "pod-a", "cto")
+            .await
+            .unwrap();
+        assert_eq!(found, None);
+
+        let found = check_existing_remediation(&cluster, "a7", "pod-b", "cto")
+            .await
+            .unwrap();
+        assert_eq!(found.as_deref(), Some("heal-running"));
+
+        let found = check_alert_type_remediation(&cluster, "a7", "cto")
+            .await
+            .unwrap();
+        assert_eq!(found.as_deref(), Some("heal-running"));
+    }
+
+    #[tokio::test]
+    async fn test_check_recent_alert_type_issue_scoped_to_family_and_window() {
+        let github = FakeGitHub::new()
+            .with_issue(
+                REPO,
+                heal_issue(1, "[HEAL-A2] Silent Failure: play-task-4-abc-step-1", 5),
+            )
+            .with_issue(
+                REPO,
+                heal_issue(2, "[HEAL-A2] Silent Failure: atlas-guardian-old", 90),
+            );
+
+        let found = check_recent_alert_type_issue(&github, "a2", "play-task-4-xyz-step-2", REPO)
+            .await
+            .unwrap();
+        assert_eq!(found.map(|(n, _)| n), Some(1));
+
+        // Different family
+        let found = check_recent_alert_type_issue(&github, "a2", "play-task-6-xyz", REPO)
+            .await
+            .unwrap();
+        assert_eq!(found, None);
+
+        // Same family, but outside the dedup window
+        let found = check_recent_alert_type_issue(&github, "a2", "atlas-guardian-new", REPO)
+            .await
+            .unwrap();
+        assert_eq!(found, None);
+
+        let found = check_existing_github_issue(&github, "a2", "atlas-guardian-old", REPO)
+            .await
+            .unwrap();
+        assert_eq!(found, Some(2));
+    }
 
     #[test]
     fn test_should_exclude_pod() {
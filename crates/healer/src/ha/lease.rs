@@ -0,0 +1,370 @@
+//! Lease-based leader election.
+//!
+//! Follows the client-go protocol: the holder renews `renewTime` every
+//! `renew_interval_secs`; any other replica may take the lease over once
+//! `renewTime + leaseDurationSeconds` has passed. A holder that has not
+//! renewed within `renew_deadline_secs` (shorter than the lease duration)
+//! steps down first, so it stops leading before anyone else can start. All
+//! writes go through the Lease's `resourceVersion`, so two replicas racing
+//! for an expired lease cannot both win.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::clients::{ClusterClient, LeaseRecord};
+
+/// Leader election configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderElectionConfig {
+    /// Lease name; replicas sharing a name elect one leader between them
+    pub lease_name: String,
+    /// Namespace of the Lease
+    pub namespace: String,
+    /// This replica's identity (defaults to the pod name)
+    #[serde(default = "default_identity")]
+    pub identity: String,
+    /// How long a leader may go without renewing before others take over
+    #[serde(default = "default_lease_duration_secs")]
+    pub lease_duration_secs: i32,
+    /// How long the leader keeps leading without a successful renewal;
+    /// capped below `lease_duration_secs`
+    #[serde(default = "default_renew_deadline_secs")]
+    pub renew_deadline_secs: i32,
+    /// How often the leader renews (and followers retry)
+    #[serde(default = "default_renew_interval_secs")]
+    pub renew_interval_secs: u64,
+}
+
+/// `POD_NAME`, then `HOSTNAME` (the pod name in-cluster), then a random id.
+pub fn default_identity() -> String {
+    std::env::var("POD_NAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| format!("healer-{}", uuid::Uuid::new_v4()))
+}
+
+fn default_lease_duration_secs() -> i32 {
+    15
+}
+
+fn default_renew_deadline_secs() -> i32 {
+    10
+}
+
+fn default_renew_interval_secs() -> u64 {
+    5
+}
+
+impl LeaderElectionConfig {
+    /// Config for `lease_name` in `namespace` with default timings.
+    pub fn new(lease_name: &str, namespace: &str) -> Self {
+        Self {
+            lease_name: lease_name.to_string(),
+            namespace: namespace.to_string(),
+            identity: default_identity(),
+            lease_duration_secs: default_lease_duration_secs(),
+            renew_deadline_secs: default_renew_deadline_secs(),
+            renew_interval_secs: default_renew_interval_secs(),
+        }
+    }
+}
+
+/// Elects one leader among replicas sharing a Lease.
+pub struct LeaderElector {
+    client: Arc<dyn ClusterClient>,
+    config: LeaderElectionConfig,
+    leader: watch::Sender<bool>,
+    /// When we last successfully renewed, so a failing API server only costs
+    /// leadership once the renew deadline has passed
+    last_renewed: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl LeaderElector {
+    pub fn new(client: Arc<dyn ClusterClient>, config: LeaderElectionConfig) -> Self {
+        Self {
+            client,
+            config,
+            leader: watch::Sender::new(false),
+            last_renewed: Mutex::new(None),
+        }
+    }
+
+    /// This replica's identity.
+    pub fn identity(&self) -> &str {
+        &self.config.identity
+    }
+
+    /// Whether this replica currently holds the lease.
+    pub fn is_leader(&self) -> bool {
+        *self.leader.borrow()
+    }
+
+    /// Leadership changes.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.leader.subscribe()
+    }
+
+    /// Wait until this replica holds the lease.
+    pub async fn wait_for_leadership(&self) {
+        let mut rx = self.subscribe();
+        // Only fails if the sender is dropped, which `self` prevents
+        let _ = rx.wait_for(|leader| *leader).await;
+    }
+
+    /// Run one election round: create, renew or take over the lease.
+    /// Returns whether this replica is now the leader.
+    pub async fn try_acquire_or_renew(&self) -> Result<bool> {
+        self.try_acquire_or_renew_at(Utc::now()).await
+    }
+
+    async fn try_acquire_or_renew_at(&self, now: DateTime<Utc>) -> Result<bool> {
+        let result = self.acquire_or_renew(now).await;
+        let leader = match &result {
+            Ok(leader) => *leader,
+            // Keep leading through API errors until the renew deadline
+            Err(_) => self
+                .last_renewed()
+                .is_some_and(|renewed| now < renewed + self.renew_deadline()),
+        };
+        if leader && result.is_ok() {
+            *self.last_renewed.lock().expect("lease lock poisoned") = Some(now);
+        }
+        self.set_leader(leader);
+        result
+    }
+
+    async fn acquire_or_renew(&self, now: DateTime<Utc>) -> Result<bool> {
+        let namespace = &self.config.namespace;
+        let name = &self.config.lease_name;
+        let identity = &self.config.identity;
+
+        let Some(current) = self.client.get_lease(namespace, name).await? else {
+            let lease = LeaseRecord {
+                holder: Some(identity.clone()),
+                acquired_at: Some(now),
+                renewed_at: Some(now),
+                duration_secs: self.config.lease_duration_secs,
+                transitions: 0,
+                resource_version: None,
+            };
+            return self.client.write_lease(namespace, name, &lease).await;
+        };
+
+        let held_by_us = current.holder.as_deref() == Some(identity.as_str());
+        if !held_by_us && current.holder.is_some() && !current.is_expired(now) {
+            return Ok(false);
+        }
+
+        let lease = if held_by_us {
+            LeaseRecord {
+                renewed_at: Some(now),
+                duration_secs: self.config.lease_duration_secs,
+                ..current
+            }
+        } else {
+            LeaseRecord {
+                holder: Some(identity.clone()),
+                acquired_at: Some(now),
+                renewed_at: Some(now),
+                duration_secs: self.config.lease_duration_secs,
+                transitions: current.transitions + 1,
+                resource_version: current.resource_version,
+            }
+        };
+        self.client.write_lease(namespace, name, &lease).await
+    }
+
+    /// Give the lease up so another replica can take over without waiting
+    /// for it to expire. No-op unless this replica holds it.
+    pub async fn release(&self) -> Result<()> {
+        let namespace = &self.config.namespace;
+        let name = &self.config.lease_name;
+        if let Some(current) = self.client.get_lease(namespace, name).await? {
+            if current.holder.as_deref() == Some(self.identity()) {
+                let lease = LeaseRecord {
+                    holder: None,
+                    renewed_at: None,
+                    ..current
+                };
+                self.client.write_lease(namespace, name, &lease).await?;
+            }
+        }
+        self.set_leader(false);
+        Ok(())
+    }
+
+    /// Run election rounds every `renew_interval_secs` in the background.
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let interval = std::time::Duration::from_secs(self.config.renew_interval_secs.max(1));
+            loop {
+                if let Err(e) = self.try_acquire_or_renew().await {
+                    warn!(lease = %self.config.lease_name, "Leader election round failed: {e:#}");
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    fn last_renewed(&self) -> Option<DateTime<Utc>> {
+        *self.last_renewed.lock().expect("lease lock poisoned")
+    }
+
+    /// `renew_deadline_secs`, kept at least a second short of the lease
+    /// duration so we step down before another replica can take over.
+    fn renew_deadline(&self) -> chrono::Duration {
+        let deadline = self
+            .config
+            .renew_deadline_secs
+            .min(self.config.lease_duration_secs - 1)
+            .max(0);
+        chrono::Duration::seconds(i64::from(deadline))
+    }
+
+    fn set_leader(&self, leader: bool) {
+        let changed = self.leader.send_if_modified(|current| {
+            let changed = *current != leader;
+            *current = leader;
+            changed
+        });
+        if changed {
+            let lease = &self.config.lease_name;
+            let identity = self.identity();
+            if leader {
+                info!(lease = %lease, identity = %identity, "Acquired leadership");
+            } else {
+                warn!(lease = %lease, identity = %identity, "Lost leadership");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::FakeCluster;
+
+    fn elector(cluster: &Arc<FakeCluster>, identity: &str) -> LeaderElector {
+        let config = LeaderElectionConfig {
+            identity: identity.to_string(),
+            ..LeaderElectionConfig::new("healer-test", "cto")
+        };
+        LeaderElector::new(cluster.clone(), config)
+    }
+
+    #[tokio::test]
+    async fn test_single_leader_and_renewal() {
+        let cluster = Arc::new(FakeCluster::new());
+        let a = elector(&cluster, "a");
+        let b = elector(&cluster, "b");
+        let now = Utc::now();
+
+        assert!(a.try_acquire_or_renew_at(now).await.unwrap());
+        assert!(!b.try_acquire_or_renew_at(now).await.unwrap());
+        assert!(a.is_leader());
+        assert!(!b.is_leader());
+
+        // Renewals keep b out past the original expiry
+        let later = now + chrono::Duration::seconds(10);
+        assert!(a.try_acquire_or_renew_at(later).await.unwrap());
+        let after_first_expiry = now + chrono::Duration::seconds(20);
+        assert!(!b.try_acquire_or_renew_at(after_first_expiry).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_takeover_after_expiry() {
+        let cluster = Arc::new(FakeCluster::new());
+        let a = elector(&cluster, "a");
+        let b = elector(&cluster, "b");
+        let now = Utc::now();
+
+        assert!(a.try_acquire_or_renew_at(now).await.unwrap());
+        let expired = now + chrono::Duration::seconds(16);
+        assert!(b.try_acquire_or_renew_at(expired).await.unwrap());
+
+        let lease = cluster
+            .get_lease("cto", "healer-test")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lease.holder.as_deref(), Some("b"));
+        assert_eq!(lease.transitions, 1);
+
+        // The old leader notices on its next round
+        assert!(!a.try_acquire_or_renew_at(expired).await.unwrap());
+        assert!(!a.is_leader());
+    }
+
+    #[tokio::test]
+    async fn test_api_errors_cost_leadership_at_renew_deadline() {
+        let cluster = Arc::new(FakeCluster::new());
+        let a = elector(&cluster, "a");
+        let b = elector(&cluster, "b");
+        let now = Utc::now();
+
+        assert!(a.try_acquire_or_renew_at(now).await.unwrap());
+        cluster.set_leases_unavailable(true);
+
+        let within_deadline = now + chrono::Duration::seconds(9);
+        assert!(a.try_acquire_or_renew_at(within_deadline).await.is_err());
+        assert!(a.is_leader());
+
+        // Steps down at the renew deadline, before the lease expires
+        let at_deadline = now + chrono::Duration::seconds(10);
+        assert!(a.try_acquire_or_renew_at(at_deadline).await.is_err());
+        assert!(!a.is_leader());
+
+        cluster.set_leases_unavailable(false);
+        let before_expiry = now + chrono::Duration::seconds(14);
+        assert!(!b.try_acquire_or_renew_at(before_expiry).await.unwrap());
+        let expired = now + chrono::Duration::seconds(15);
+        assert!(b.try_acquire_or_renew_at(expired).await.unwrap());
+    }
+
+    #[test]
+    fn test_renew_deadline_stays_below_lease_duration() {
+        let cluster = Arc::new(FakeCluster::new());
+        let config = LeaderElectionConfig {
+            renew_deadline_secs: 30,
+            ..LeaderElectionConfig::new("healer-test", "cto")
+        };
+        let elector = LeaderElector::new(cluster, config);
+        assert_eq!(elector.renew_deadline(), chrono::Duration::seconds(14));
+    }
+
+    #[tokio::test]
+    async fn test_release_hands_over_immediately() {
+        let cluster = Arc::new(FakeCluster::new());
+        let a = elector(&cluster, "a");
+        let b = elector(&cluster, "b");
+        let now = Utc::now();
+
+        assert!(a.try_acquire_or_renew_at(now).await.unwrap());
+        a.release().await.unwrap();
+        assert!(!a.is_leader());
+        assert!(b.try_acquire_or_renew_at(now).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_stale_write_loses_race() {
+        let cluster = Arc::new(FakeCluster::new());
+        let lease = LeaseRecord {
+            holder: Some("a".to_string()),
+            ..LeaseRecord::default()
+        };
+        assert!(cluster.write_lease("cto", "l", &lease).await.unwrap());
+        // A second create (no resource version) conflicts
+        assert!(!cluster.write_lease("cto", "l", &lease).await.unwrap());
+
+        let current = cluster.get_lease("cto", "l").await.unwrap().unwrap();
+        assert!(cluster.write_lease("cto", "l", &current).await.unwrap());
+        // Replacing with the now-stale version conflicts
+        assert!(!cluster.write_lease("cto", "l", &current).await.unwrap());
+    }
+}
@@ -0,0 +1,15 @@
+//! High availability for healer.
+//!
+//! Watchers and scanners run on one elected replica at a time
+//! ([`LeaderElector`], backed by a `coordination.k8s.io` Lease). The state a
+//! new leader needs to resume where the old one stopped - remediation
+//! attempts, Play sessions and alert dedup - is persisted in ConfigMaps
+//! ([`StateStore`]) rather than process memory.
+
+#![allow(dead_code)] // Public API - not every operation is used by every binary
+
+pub mod lease;
+pub mod state;
+
+pub use lease::{LeaderElectionConfig, LeaderElector};
+pub use state::StateStore;
@@ -0,0 +1,258 @@
+//! ConfigMap-backed state persistence.
+//!
+//! Each entry is stored as JSON under its own ConfigMap key, so replicas
+//! updating different entries only conflict at the API level, never on
+//! content. Writes are read-modify-write on the ConfigMap's
+//! `resourceVersion` and retried on conflict. ConfigMaps are capped at
+//! 1 MiB, so callers should remove entries once they reach a terminal state.
+
+use anyhow::{bail, Context as _, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::clients::{ClusterClient, ConfigMapData};
+
+/// Attempts per write before giving up on a contended ConfigMap.
+const MAX_WRITE_ATTEMPTS: usize = 5;
+
+/// JSON entries persisted in one ConfigMap.
+#[derive(Clone)]
+pub struct StateStore {
+    client: Arc<dyn ClusterClient>,
+    namespace: String,
+    name: String,
+}
+
+impl std::fmt::Debug for StateStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StateStore")
+            .field("namespace", &self.namespace)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StateStore {
+    /// Store entries in ConfigMap `namespace/name` (created on first write).
+    pub fn new(client: Arc<dyn ClusterClient>, namespace: &str, name: &str) -> Self {
+        Self {
+            client,
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    /// The backing ConfigMap's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Store in ConfigMap `<name>-<suffix>` in the same namespace, for
+    /// state large enough to crowd other entries out of this one.
+    #[must_use]
+    pub fn sibling(&self, suffix: &str) -> Self {
+        Self::new(
+            self.client.clone(),
+            &self.namespace,
+            &format!("{}-{suffix}", self.name),
+        )
+    }
+
+    /// Load one entry.
+    pub async fn load<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let key = sanitize_key(key);
+        let Some(config_map) = self.get().await? else {
+            return Ok(None);
+        };
+        config_map
+            .data
+            .get(&key)
+            .map(|json| {
+                serde_json::from_str(json)
+                    .with_context(|| format!("Failed to parse state entry {}/{key}", self.name))
+            })
+            .transpose()
+    }
+
+    /// Load every entry whose key starts with `prefix`. Entries that no
+    /// longer parse (e.g. after a schema change) are skipped with a warning.
+    pub async fn load_all<T: DeserializeOwned>(&self, prefix: &str) -> Result<Vec<T>> {
+        let prefix = sanitize_key(prefix);
+        let Some(config_map) = self.get().await? else {
+            return Ok(Vec::new());
+        };
+        Ok(config_map
+            .data
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .filter_map(|(key, json)| match serde_json::from_str(json) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    warn!("Skipping unreadable state entry {}/{key}: {e}", self.name);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Insert or replace one entry.
+    pub async fn save<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        let key = sanitize_key(key);
+        let json = serde_json::to_string(value)
+            .with_context(|| format!("Failed to serialize state entry {key}"))?;
+        self.update(|data| {
+            data.insert(key.clone(), json.clone());
+        })
+        .await
+    }
+
+    /// Change one entry in place, starting from `T::default()` if absent.
+    /// `change` runs inside the conflict-retried write against the stored
+    /// value, so replicas changing the same entry never drop each other's
+    /// updates. Returns the value as written.
+    pub async fn modify<T>(&self, key: &str, mut change: impl FnMut(&mut T)) -> Result<T>
+    where
+        T: Default + Serialize + DeserializeOwned,
+    {
+        let key = sanitize_key(key);
+        let mut written = None;
+        let mut failure = None;
+        self.update(|data| {
+            let mut value = match data.get(&key).map(|json| serde_json::from_str(json)) {
+                Some(Ok(value)) => value,
+                Some(Err(e)) => {
+                    warn!("Replacing unreadable state entry {}/{key}: {e}", self.name);
+                    T::default()
+                }
+                None => T::default(),
+            };
+            change(&mut value);
+            match serde_json::to_string(&value) {
+                Ok(json) => {
+                    data.insert(key.clone(), json);
+                    written = Some(value);
+                    failure = None;
+                }
+                Err(e) => failure = Some(e),
+            }
+        })
+        .await?;
+        if let Some(e) = failure {
+            return Err(e).with_context(|| format!("Failed to serialize state entry {key}"));
+        }
+        written.with_context(|| format!("State entry {key} was not written"))
+    }
+
+    /// Remove one entry (no-op if absent).
+    pub async fn remove(&self, key: &str) -> Result<()> {
+        let key = sanitize_key(key);
+        self.update(|data| {
+            data.remove(&key);
+        })
+        .await
+    }
+
+    async fn get(&self) -> Result<Option<ConfigMapData>> {
+        self.client
+            .get_config_map(&self.namespace, &self.name)
+            .await
+    }
+
+    async fn update(&self, mut apply: impl FnMut(&mut BTreeMap<String, String>)) -> Result<()> {
+        for _ in 0..MAX_WRITE_ATTEMPTS {
+            let mut config_map = self.get().await?.unwrap_or_default();
+            let before = config_map.data.clone();
+            apply(&mut config_map.data);
+            if config_map.resource_version.is_some() && config_map.data == before {
+                return Ok(());
+            }
+            if self
+                .client
+                .write_config_map(&self.namespace, &self.name, &config_map)
+                .await?
+            {
+                return Ok(());
+            }
+        }
+        bail!(
+            "Gave up writing ConfigMap {}/{} after {MAX_WRITE_ATTEMPTS} conflicting attempts",
+            self.namespace,
+            self.name
+        )
+    }
+}
+
+/// Map `key` onto the characters ConfigMap keys allow (`[-._a-zA-Z0-9]`).
+pub fn sanitize_key(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::FakeCluster;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entry {
+        id: u64,
+    }
+
+    fn store(cluster: &Arc<FakeCluster>) -> StateStore {
+        StateStore::new(cluster.clone(), "cto", "healer-state")
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_and_remove() {
+        let cluster = Arc::new(FakeCluster::new());
+        let store = store(&cluster);
+        assert_eq!(store.load::<Entry>("run-1").await.unwrap(), None);
+
+        store.save("run-1", &Entry { id: 1 }).await.unwrap();
+        store.save("run-2", &Entry { id: 2 }).await.unwrap();
+        store.save("other", &Entry { id: 3 }).await.unwrap();
+        assert_eq!(
+            store.load::<Entry>("run-1").await.unwrap(),
+            Some(Entry { id: 1 })
+        );
+
+        let mut runs: Vec<Entry> = store.load_all("run-").await.unwrap();
+        runs.sort_by_key(|e| e.id);
+        assert_eq!(runs, vec![Entry { id: 1 }, Entry { id: 2 }]);
+
+        store.remove("run-1").await.unwrap();
+        store.remove("missing").await.unwrap();
+        assert_eq!(store.load_all::<Entry>("run-").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replicas_share_state() {
+        let cluster = Arc::new(FakeCluster::new());
+        store(&cluster)
+            .save("play/abc 1", &Entry { id: 7 })
+            .await
+            .unwrap();
+        // A second replica (new leader) sees the first one's writes
+        assert_eq!(
+            store(&cluster).load::<Entry>("play/abc 1").await.unwrap(),
+            Some(Entry { id: 7 })
+        );
+    }
+
+    #[test]
+    fn test_sanitize_key() {
+        assert_eq!(sanitize_key("play/abc 1:x"), "play_abc_1_x");
+        assert_eq!(sanitize_key("run-12.a_b"), "run-12.a_b");
+    }
+}
@@ -0,0 +1,560 @@
+//! Incidents: one timeline joining everything healer did about a problem.
+//!
+//! Alert detection, dedup and silence decisions, spawned remediation
+//! `CodeRuns`, CI runs, merges and escalations are recorded as
+//! [`IncidentEvent`]s on the open [`Incident`] for their correlation key
+//! (e.g. one workflow on one branch). Agent observations recorded alongside
+//! feed the root-cause hypothesis of the [`postmortem`] export.
+//!
+//! Incidents are persisted in their own ConfigMap next to the shared state
+//! ConfigMap when a store is configured, so the server API also lists
+//! incidents the alert watch opened. Open incidents close on a resolving
+//! event (e.g. a forwarded green CI run) or after [`IDLE_CLOSE_DAYS`] without
+//! activity, and once the stored incidents outgrow [`MAX_STORED_BYTES`] the
+//! least recently active are evicted, open ones included.
+
+#![allow(dead_code)]
+
+pub mod postmortem;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::ha::StateStore;
+use crate::play::insights::AgentObservation;
+
+pub use postmortem::render_postmortem;
+
+/// State store key prefix for incidents.
+const STATE_KEY_PREFIX: &str = "incident-";
+
+/// Suffix of the incident ConfigMap, kept apart from the shared state
+/// ConfigMap so timelines cannot crowd out dedup and session state.
+const CONFIGMAP_SUFFIX: &str = "incidents";
+
+/// Open incidents without events for this long are closed, e.g. CI on a
+/// branch that was deleted rather than fixed.
+const IDLE_CLOSE_DAYS: i64 = 7;
+
+/// Serialized incidents one writer keeps. Beyond this the least recently
+/// active are evicted, closed ones first, so the server and the alert watch
+/// together stay well under the 1 MiB ConfigMap cap.
+const MAX_STORED_BYTES: usize = 256 * 1024;
+
+/// How long closed incidents stay queryable before they are pruned.
+const CLOSED_RETENTION_DAYS: i64 = 7;
+
+/// Events kept per incident; the oldest are dropped beyond this so a
+/// flapping alert cannot outgrow the state ConfigMap.
+const MAX_EVENTS: usize = 200;
+
+/// What happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentEventKind {
+    /// An alert or CI failure was detected
+    AlertDetected,
+    /// A repeat of an alert already being handled was skipped
+    Deduplicated,
+    /// An alert was suppressed by a silence or maintenance window
+    Silenced,
+    /// Known-flaky tests were quarantined instead of remediated
+    Quarantined,
+    /// A remediation `CodeRun` was spawned
+    RemediationSpawned,
+    /// A remediation `CodeRun` finished
+    RemediationCompleted,
+    /// A CI run for the affected workflow finished
+    CiRun,
+    /// The remediation PR was merged
+    Merged,
+    /// The incident was handed to humans
+    Escalated,
+    /// The problem is gone
+    Resolved,
+}
+
+impl IncidentEventKind {
+    /// Human-readable label for timelines.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::AlertDetected => "🚨 Alert detected",
+            Self::Deduplicated => "⏭️ Deduplicated",
+            Self::Silenced => "🔇 Silenced",
+            Self::Quarantined => "🧪 Quarantined",
+            Self::RemediationSpawned => "🔧 Remediation spawned",
+            Self::RemediationCompleted => "🏁 Remediation completed",
+            Self::CiRun => "🔁 CI run",
+            Self::Merged => "🔀 Merged",
+            Self::Escalated => "📣 Escalated",
+            Self::Resolved => "✅ Resolved",
+        }
+    }
+
+    /// Whether this event is an action healer took (vs. an observation).
+    pub fn is_action(self) -> bool {
+        matches!(
+            self,
+            Self::Quarantined
+                | Self::RemediationSpawned
+                | Self::RemediationCompleted
+                | Self::Merged
+                | Self::Escalated
+        )
+    }
+}
+
+/// One timestamped entry on an incident's timeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncidentEvent {
+    pub at: DateTime<Utc>,
+    pub kind: IncidentEventKind,
+    pub summary: String,
+    /// Structured details (e.g. `failure_type`, `agent`, `coderun`, `pr`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
+}
+
+impl IncidentEvent {
+    /// Event happening now.
+    pub fn new(kind: IncidentEventKind, summary: impl Into<String>) -> Self {
+        Self {
+            at: Utc::now(),
+            kind,
+            summary: summary.into(),
+            details: BTreeMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_detail(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.details.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+/// Incident lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentStatus {
+    /// Being handled by healer
+    Open,
+    /// Handed to humans, not yet resolved
+    Escalated,
+    /// Closed
+    Resolved,
+}
+
+/// Everything recorded about one problem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Incident {
+    pub id: String,
+    /// Correlation key; at most one incident per key is open at a time
+    pub key: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    pub status: IncidentStatus,
+    pub opened_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+    pub events: Vec<IncidentEvent>,
+    /// Agent observations for the root-cause hypothesis
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub observations: Vec<AgentObservation>,
+}
+
+impl Incident {
+    fn open(key: &str, title: &str, repository: Option<&str>, at: DateTime<Utc>) -> Self {
+        Self {
+            id: format!(
+                "inc-{}-{}",
+                at.format("%Y%m%d"),
+                &uuid::Uuid::new_v4().simple().to_string()[..8]
+            ),
+            key: key.to_string(),
+            title: title.to_string(),
+            repository: repository.map(str::to_string),
+            status: IncidentStatus::Open,
+            opened_at: at,
+            closed_at: None,
+            events: Vec::new(),
+            observations: Vec::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+
+    /// Time from opening to closing (or to `now` while open).
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration {
+        self.closed_at.unwrap_or(now) - self.opened_at
+    }
+
+    /// When the last event happened (or the incident opened, if none has).
+    pub fn last_activity(&self) -> DateTime<Utc> {
+        self.events.last().map_or(self.opened_at, |e| e.at)
+    }
+
+    /// Events of `kind`, oldest first.
+    pub fn events_of(&self, kind: IncidentEventKind) -> impl Iterator<Item = &IncidentEvent> {
+        self.events.iter().filter(move |e| e.kind == kind)
+    }
+
+    fn push(&mut self, event: IncidentEvent) {
+        match event.kind {
+            IncidentEventKind::Escalated => self.status = IncidentStatus::Escalated,
+            IncidentEventKind::Resolved => {
+                self.status = IncidentStatus::Resolved;
+                self.closed_at = Some(event.at);
+            }
+            _ => {}
+        }
+        self.events.push(event);
+        self.events.sort_by_key(|e| e.at);
+        if self.events.len() > MAX_EVENTS {
+            let excess = self.events.len() - MAX_EVENTS;
+            self.events.drain(..excess);
+        }
+    }
+}
+
+/// Registry of incidents.
+#[derive(Debug, Default)]
+pub struct IncidentStore {
+    incidents: RwLock<Vec<Incident>>,
+    store: Option<StateStore>,
+}
+
+impl IncidentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist incidents in a ConfigMap next to `store`'s.
+    #[must_use]
+    pub fn with_state_store(mut self, store: &StateStore) -> Self {
+        self.store = Some(store.sibling(CONFIGMAP_SUFFIX));
+        self
+    }
+
+    /// Reload incidents from the state store, e.g. ones recorded by the
+    /// alert watch or another replica.
+    pub async fn refresh(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        let incidents: Vec<Incident> = store.load_all(STATE_KEY_PREFIX).await?;
+        *self.incidents.write().await = incidents;
+        Ok(())
+    }
+
+    /// All incidents, most recently opened first.
+    pub async fn list(&self) -> Vec<Incident> {
+        let mut incidents = self.incidents.read().await.clone();
+        incidents.sort_by_key(|i| std::cmp::Reverse(i.opened_at));
+        incidents
+    }
+
+    pub async fn get(&self, id: &str) -> Option<Incident> {
+        self.incidents
+            .read()
+            .await
+            .iter()
+            .find(|i| i.id == id)
+            .cloned()
+    }
+
+    /// Add `event` to the open incident for `key`, opening one titled
+    /// `title` if there is none. Returns the incident id.
+    pub async fn record(
+        &self,
+        key: &str,
+        title: &str,
+        repository: Option<&str>,
+        event: IncidentEvent,
+    ) -> String {
+        let mut incidents = self.incidents.write().await;
+        let index = if let Some(index) = incidents.iter().position(|i| i.is_open() && i.key == key)
+        {
+            index
+        } else {
+            let incident = Incident::open(key, title, repository, event.at);
+            info!("Opened incident {} ({key}): {title}", incident.id);
+            incidents.push(incident);
+            incidents.len() - 1
+        };
+        incidents[index].push(event);
+        let snapshot = incidents[index].clone();
+        let tidied = tidy(&mut incidents, Utc::now());
+        drop(incidents);
+        self.persist_tidied(&snapshot, tidied).await;
+        snapshot.id
+    }
+
+    /// Add `event` to the open incident for `key`, if there is one. Use for
+    /// events that only matter as part of an incident (e.g. a passing CI
+    /// run) so they never open one.
+    pub async fn append(&self, key: &str, event: IncidentEvent) -> Option<String> {
+        let mut incidents = self.incidents.write().await;
+        let incident = incidents.iter_mut().find(|i| i.is_open() && i.key == key)?;
+        incident.push(event);
+        let snapshot = incident.clone();
+        let tidied = tidy(&mut incidents, Utc::now());
+        drop(incidents);
+        self.persist_tidied(&snapshot, tidied).await;
+        Some(snapshot.id)
+    }
+
+    /// Attach an agent observation to the open incident for `key`.
+    pub async fn observe(&self, key: &str, observation: AgentObservation) {
+        let mut incidents = self.incidents.write().await;
+        let Some(incident) = incidents.iter_mut().find(|i| i.is_open() && i.key == key) else {
+            return;
+        };
+        incident.observations.push(observation);
+        let snapshot = incident.clone();
+        let tidied = tidy(&mut incidents, Utc::now());
+        drop(incidents);
+        self.persist_tidied(&snapshot, tidied).await;
+    }
+
+    /// Persist `incident` along with what tidying the registry changed.
+    async fn persist_tidied(&self, incident: &Incident, tidied: Tidied) {
+        if !tidied.removed.contains(&incident.id) {
+            self.persist(incident).await;
+        }
+        for closed in &tidied.closed {
+            self.persist(closed).await;
+        }
+        for id in tidied.removed {
+            self.remove_persisted(&id).await;
+        }
+    }
+
+    async fn persist(&self, incident: &Incident) {
+        if let Some(store) = &self.store {
+            let key = format!("{STATE_KEY_PREFIX}{}", incident.id);
+            if let Err(e) = store.save(&key, incident).await {
+                warn!("Failed to persist incident {}: {e:#}", incident.id);
+            }
+        }
+    }
+
+    async fn remove_persisted(&self, id: &str) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.remove(&format!("{STATE_KEY_PREFIX}{id}")).await {
+                warn!("Failed to remove incident {id}: {e:#}");
+            }
+        }
+    }
+}
+
+/// What [`tidy`] changed: incidents it closed for inactivity and ids of
+/// incidents it dropped.
+#[derive(Debug, Default)]
+struct Tidied {
+    closed: Vec<Incident>,
+    removed: Vec<String>,
+}
+
+/// Close idle incidents, prune expired ones and evict down to the byte
+/// budget.
+fn tidy(incidents: &mut Vec<Incident>, now: DateTime<Utc>) -> Tidied {
+    let idle = Duration::days(IDLE_CLOSE_DAYS);
+    let mut closed = Vec::new();
+    for incident in incidents
+        .iter_mut()
+        .filter(|i| i.is_open() && i.last_activity() + idle <= now)
+    {
+        info!("Closing idle incident {} ({})", incident.id, incident.key);
+        incident.push(IncidentEvent {
+            at: now,
+            kind: IncidentEventKind::Resolved,
+            summary: format!("Closed after {IDLE_CLOSE_DAYS} days without activity"),
+            details: BTreeMap::from([("idle".to_string(), IDLE_CLOSE_DAYS.to_string())]),
+        });
+        closed.push(incident.clone());
+    }
+    let mut removed = prune(incidents, now);
+    removed.extend(evict(incidents, MAX_STORED_BYTES));
+    closed.retain(|i| !removed.contains(&i.id));
+    Tidied { closed, removed }
+}
+
+/// Evict incidents until their serialized size fits `max_bytes`: closed
+/// ones before open ones, least recently active first. Returns their ids.
+fn evict(incidents: &mut Vec<Incident>, max_bytes: usize) -> Vec<String> {
+    let size = |incident: &Incident| serde_json::to_vec(incident).map_or(0, |json| json.len());
+    let mut total: usize = incidents.iter().map(size).sum();
+    let mut order: Vec<&Incident> = incidents.iter().collect();
+    order.sort_by_key(|i| (i.is_open(), i.last_activity()));
+    let mut evicted = Vec::new();
+    for incident in order {
+        if total <= max_bytes {
+            break;
+        }
+        total -= size(incident);
+        evicted.push(incident.id.clone());
+    }
+    if !evicted.is_empty() {
+        warn!(
+            "Evicting {} incident(s) to stay under {max_bytes} stored bytes",
+            evicted.len()
+        );
+        incidents.retain(|i| !evicted.contains(&i.id));
+    }
+    evicted
+}
+
+/// Drop incidents closed longer ago than the retention; returns their ids.
+fn prune(incidents: &mut Vec<Incident>, now: DateTime<Utc>) -> Vec<String> {
+    let retention = Duration::days(CLOSED_RETENTION_DAYS);
+    let pruned: Vec<String> = incidents
+        .iter()
+        .filter(|i| i.closed_at.is_some_and(|closed| closed + retention <= now))
+        .map(|i| i.id.clone())
+        .collect();
+    incidents.retain(|i| !pruned.contains(&i.id));
+    pruned
+}
+
+/// Correlation key for CI failures: one incident per workflow and branch.
+pub fn ci_incident_key(repository: &str, workflow: &str, branch: &str) -> String {
+    format!("ci:{repository}:{workflow}:{branch}")
+}
+
+/// Correlation key for alerts on a pod or `CodeRun`: every alert on the
+/// same workload joins one incident.
+pub fn workload_incident_key(namespace: &str, name: &str) -> String {
+    format!("workload:{namespace}/{name}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::FakeCluster;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_events_join_open_incident_until_resolved() {
+        let incidents = IncidentStore::new();
+        let key = ci_incident_key("5dlabs/cto", "CI", "main");
+        let first = incidents
+            .record(
+                &key,
+                "CI failing on main",
+                Some("5dlabs/cto"),
+                IncidentEvent::new(IncidentEventKind::AlertDetected, "Run 1 failed"),
+            )
+            .await;
+        let second = incidents
+            .record(
+                &key,
+                "CI failing on main",
+                Some("5dlabs/cto"),
+                IncidentEvent::new(IncidentEventKind::Deduplicated, "Run 2 failed"),
+            )
+            .await;
+        assert_eq!(first, second);
+
+        let resolved = incidents
+            .append(
+                &key,
+                IncidentEvent::new(IncidentEventKind::Resolved, "Green"),
+            )
+            .await;
+        assert_eq!(resolved.as_deref(), Some(first.as_str()));
+        let incident = incidents.get(&first).await.unwrap();
+        assert_eq!(incident.status, IncidentStatus::Resolved);
+        assert_eq!(incident.events.len(), 3);
+
+        // A passing run alone never opens an incident
+        assert!(incidents
+            .append(&key, IncidentEvent::new(IncidentEventKind::CiRun, "Green"))
+            .await
+            .is_none());
+        // A new failure opens a new one
+        let third = incidents
+            .record(
+                &key,
+                "CI failing on main",
+                None,
+                IncidentEvent::new(IncidentEventKind::AlertDetected, "Run 3 failed"),
+            )
+            .await;
+        assert_ne!(first, third);
+        assert_eq!(incidents.list().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_incidents_shared_through_state_store() {
+        let cluster = Arc::new(FakeCluster::new());
+        let store = StateStore::new(cluster, "cto", "healer-state");
+        let watch = IncidentStore::new().with_state_store(&store);
+        let id = watch
+            .record(
+                &workload_incident_key("cto", "coderun-1"),
+                "CodeRun stuck",
+                None,
+                IncidentEvent::new(IncidentEventKind::AlertDetected, "Stuck for 45m"),
+            )
+            .await;
+
+        let server = IncidentStore::new().with_state_store(&store);
+        server.refresh().await.unwrap();
+        assert_eq!(server.get(&id).await.unwrap().title, "CodeRun stuck");
+        // Timelines stay out of the shared state ConfigMap
+        let shared: Vec<Incident> = store.load_all(STATE_KEY_PREFIX).await.unwrap();
+        assert!(shared.is_empty());
+    }
+
+    #[test]
+    fn test_prune_closed_incidents() {
+        let now = Utc::now();
+        let mut open = Incident::open("a", "a", None, now - Duration::days(30));
+        open.push(IncidentEvent::new(IncidentEventKind::AlertDetected, "a"));
+        let mut closed = Incident::open("b", "b", None, now - Duration::days(30));
+        closed.closed_at = Some(now - Duration::days(8));
+        let closed_id = closed.id.clone();
+        let mut incidents = vec![open, closed];
+        assert_eq!(prune(&mut incidents, now), vec![closed_id]);
+        assert_eq!(incidents.len(), 1);
+    }
+
+    #[test]
+    fn test_idle_incidents_close_and_oldest_are_evicted() {
+        let now = Utc::now();
+        let incident = |key: &str, days_ago: i64| {
+            let at = now - Duration::days(days_ago);
+            let mut incident = Incident::open(key, key, None, at);
+            let mut event = IncidentEvent::new(IncidentEventKind::AlertDetected, "x".repeat(1024));
+            event.at = at;
+            incident.push(event);
+            incident
+        };
+
+        // CI on a deleted branch never sees a green run
+        let mut incidents = vec![incident("idle", 8), incident("active", 1)];
+        let tidied = tidy(&mut incidents, now);
+        assert_eq!(tidied.closed.len(), 1);
+        assert_eq!(tidied.closed[0].key, "idle");
+        assert_eq!(tidied.closed[0].status, IncidentStatus::Resolved);
+        assert!(incidents.iter().any(|i| i.key == "active" && i.is_open()));
+
+        // Closed incidents go first, then the least recently active open ones
+        let mut closed = incident("closed", 1);
+        closed.push(IncidentEvent::new(IncidentEventKind::Resolved, "Green"));
+        let mut incidents = vec![incident("old", 3), incident("new", 2), closed];
+        let one = serde_json::to_vec(&incidents[1]).unwrap().len();
+        let evicted = evict(&mut incidents, one + one / 2);
+        assert_eq!(evicted.len(), 2);
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].key, "new");
+    }
+}
@@ -0,0 +1,260 @@
+//! Markdown postmortem drafts for incidents.
+//!
+//! The draft covers the timeline, a root-cause hypothesis (failure
+//! classification plus recurring patterns from the [`InsightCollector`]),
+//! the actions healer took and follow-ups for humans. Output depends only on
+//! the recorded incident and `now`, so it can be snapshot-tested.
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Write as _;
+
+use super::{Incident, IncidentEventKind, IncidentStatus};
+use crate::play::insights::InsightCollector;
+
+/// Render a postmortem draft for `incident`. `now` stands in for the end
+/// of incidents that are still open.
+pub fn render_postmortem(incident: &Incident, now: DateTime<Utc>) -> String {
+    let mut insights = InsightCollector::new();
+    for observation in &incident.observations {
+        insights.record(observation.clone());
+    }
+
+    let mut md = String::new();
+    let _ = writeln!(md, "# Postmortem: {}\n", incident.title);
+    let _ = writeln!(
+        md,
+        "> Draft generated by healer from {} recorded events. Review before sharing.\n",
+        incident.events.len()
+    );
+
+    write_summary(&mut md, incident, now);
+    write_timeline(&mut md, incident);
+    write_hypothesis(&mut md, incident, &insights);
+    write_actions(&mut md, incident);
+    write_follow_ups(&mut md, incident, &insights);
+    md
+}
+
+fn write_summary(md: &mut String, incident: &Incident, now: DateTime<Utc>) {
+    let count = |kind| incident.events_of(kind).count();
+    let status = match incident.status {
+        IncidentStatus::Open => "Open",
+        IncidentStatus::Escalated => "Escalated",
+        IncidentStatus::Resolved => "Resolved",
+    };
+
+    md.push_str("## Summary\n\n| | |\n|---|---|\n");
+    let _ = writeln!(md, "| Incident | `{}` |", incident.id);
+    let _ = writeln!(md, "| Status | {status} |");
+    if let Some(repository) = &incident.repository {
+        let _ = writeln!(md, "| Repository | {repository} |");
+    }
+    let _ = writeln!(md, "| Opened | {} |", timestamp(incident.opened_at));
+    let closed = incident
+        .closed_at
+        .map_or_else(|| "Still open".to_string(), timestamp);
+    let _ = writeln!(md, "| Closed | {closed} |");
+    let _ = writeln!(
+        md,
+        "| Duration | {} |",
+        human_duration(incident.duration(now))
+    );
+    let _ = writeln!(
+        md,
+        "| Alerts | {} ({} deduplicated, {} silenced) |",
+        count(IncidentEventKind::AlertDetected),
+        count(IncidentEventKind::Deduplicated),
+        count(IncidentEventKind::Silenced)
+    );
+    let _ = writeln!(
+        md,
+        "| Remediation attempts | {} |\n",
+        count(IncidentEventKind::RemediationSpawned)
+    );
+}
+
+fn write_timeline(md: &mut String, incident: &Incident) {
+    md.push_str("## Timeline\n\n");
+    if incident.events.is_empty() {
+        md.push_str("No events recorded.\n\n");
+        return;
+    }
+    md.push_str("| Time (UTC) | Event | Details |\n|---|---|---|\n");
+    for event in &incident.events {
+        let mut details = event.summary.clone();
+        if !event.details.is_empty() {
+            let extra: Vec<String> = event
+                .details
+                .iter()
+                .map(|(k, v)| format!("{k}: `{v}`"))
+                .collect();
+            let _ = write!(details, " ({})", extra.join(", "));
+        }
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} |",
+            timestamp(event.at),
+            event.kind.label(),
+            escape_cell(&details)
+        );
+    }
+    md.push('\n');
+}
+
+fn write_hypothesis(md: &mut String, incident: &Incident, insights: &InsightCollector) {
+    md.push_str("## Root-cause hypothesis\n\n");
+    let mut any = false;
+
+    // The latest classification reflects the most context
+    if let Some(event) = incident
+        .events_of(IncidentEventKind::AlertDetected)
+        .filter(|e| e.details.contains_key("failure_type"))
+        .last()
+    {
+        let failure_type = &event.details["failure_type"];
+        match event.details.get("confidence") {
+            Some(confidence) => {
+                let _ = writeln!(
+                    md,
+                    "- Classified as **{failure_type}** (confidence {confidence})."
+                );
+            }
+            None => {
+                let _ = writeln!(md, "- Classified as **{failure_type}**.");
+            }
+        }
+        any = true;
+    }
+
+    for pattern in insights.failure_patterns() {
+        let _ = writeln!(
+            md,
+            "- {} repeatedly hit: {} ({} times between {} and {}).",
+            pattern.agent,
+            pattern.description,
+            pattern.occurrences,
+            timestamp(pattern.first_seen),
+            timestamp(pattern.last_seen)
+        );
+        any = true;
+    }
+
+    if !any {
+        md.push_str("- No classification or recurring agent pattern was recorded.\n");
+    }
+    md.push('\n');
+}
+
+fn write_actions(md: &mut String, incident: &Incident) {
+    md.push_str("## Actions taken\n\n");
+    let actions: Vec<_> = incident
+        .events
+        .iter()
+        .filter(|e| e.kind.is_action())
+        .collect();
+    if actions.is_empty() {
+        md.push_str("- None; healer only observed this incident.\n\n");
+        return;
+    }
+    for event in actions {
+        let _ = writeln!(
+            md,
+            "- {} {}: {}",
+            timestamp(event.at),
+            event.kind.label(),
+            event.summary
+        );
+    }
+    md.push('\n');
+}
+
+fn write_follow_ups(md: &mut String, incident: &Incident, insights: &InsightCollector) {
+    md.push_str("## Follow-ups\n\n");
+    let mut follow_ups = Vec::new();
+    match incident.status {
+        IncidentStatus::Escalated => {
+            follow_ups.push("Land a fix for the escalated failure and confirm CI is green".into());
+        }
+        IncidentStatus::Open => {
+            follow_ups.push("Confirm the incident is resolved and close it".into());
+        }
+        IncidentStatus::Resolved => {}
+    }
+    let silenced = incident.events_of(IncidentEventKind::Silenced).count();
+    if silenced > 0 {
+        follow_ups.push(format!(
+            "Review the {silenced} alerts suppressed by silences during the incident"
+        ));
+    }
+    for event in incident.events_of(IncidentEventKind::Quarantined) {
+        follow_ups.push(format!(
+            "Fix and un-quarantine flaky tests: {}",
+            event.summary
+        ));
+    }
+    for suggestion in insights.suggest_optimizations() {
+        follow_ups.push(format!(
+            "Prompt change for {} ({} confidence): {}",
+            suggestion.agent, suggestion.confidence, suggestion.suggested_change
+        ));
+    }
+
+    if follow_ups.is_empty() {
+        md.push_str("- None identified.\n");
+    }
+    for follow_up in follow_ups {
+        let _ = writeln!(md, "- [ ] {follow_up}");
+    }
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn human_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m}m"),
+        (h, m) => format!("{h}h {m}m"),
+    }
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/incidents/ci-failure.json");
+    const SNAPSHOT: &str = include_str!("../../tests/fixtures/incidents/ci-failure.md");
+
+    /// Set `UPDATE_SNAPSHOTS=1` to rewrite the expected Markdown.
+    #[test]
+    fn test_postmortem_snapshot() {
+        let incident: Incident = serde_json::from_str(FIXTURE).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let rendered = render_postmortem(&incident, now);
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            let path = concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/incidents/ci-failure.md"
+            );
+            std::fs::write(path, &rendered).unwrap();
+            return;
+        }
+        assert_eq!(rendered, SNAPSHOT);
+    }
+
+    #[test]
+    fn test_open_incident_without_events() {
+        let opened = Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap();
+        let incident = Incident::open("k", "Pod crashlooping", None, opened);
+        let md = render_postmortem(&incident, opened + Duration::minutes(90));
+        assert!(md.contains("| Duration | 1h 30m |"));
+        assert!(md.contains("No events recorded."));
+        assert!(md.contains("- [ ] Confirm the incident is resolved"));
+    }
+}
@@ -134,4 +134,202 @@ pub struct CodeRun {
     pub agent: String,
     pub task_id: String,
     pub labels: HashMap<String, String>,
+    pub created_at: Option<DateTime<Utc>>,
+    /// PR opened by the run, once it has one
+    pub pull_request_url: Option<String>,
+    /// Pod running the agent, once scheduled
+    pub pod_name: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Status message, usually set when the run fails
+    pub message: Option<String>,
+}
+
+/// A single line of `kubectl get -w -o json --output-watch-events` output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchEvent {
+    /// `ADDED`, `MODIFIED` or `DELETED`.
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// The watched resource.
+    pub object: serde_json::Value,
+}
+
+impl WatchEvent {
+    /// Convert to a [`K8sEvent`] for alert evaluation.
+    ///
+    /// Returns `None` for deletions and for resource kinds or pod phase
+    /// transitions the alert system does not look at.
+    pub fn to_k8s_event(&self, namespace: &str) -> Option<K8sEvent> {
+        if self.event_type == "DELETED" {
+            return None;
+        }
+        match self.object["kind"].as_str()? {
+            "Pod" => pod_event(
+                parse_pod_from_json(&self.object, namespace),
+                &self.event_type,
+            ),
+            "CodeRun" => Some(K8sEvent::CodeRunChanged(parse_coderun_from_json(
+                &self.object,
+                namespace,
+            ))),
+            _ => None,
+        }
+    }
+}
+
+/// Classify a pod watch event by phase and event type.
+pub fn pod_event(pod: Pod, event_type: &str) -> Option<K8sEvent> {
+    match (pod.phase.as_str(), event_type) {
+        ("Failed" | "Error", _) => Some(K8sEvent::PodFailed(pod)),
+        ("Succeeded", _) => Some(K8sEvent::PodSucceeded(pod)),
+        ("Running", "ADDED") => Some(K8sEvent::PodRunning(pod)),
+        (_, "MODIFIED") => Some(K8sEvent::PodModified(pod)),
+        _ => None,
+    }
+}
+
+/// Parse kubectl JSON output into our Pod type
+pub fn parse_pod_from_json(json: &serde_json::Value, namespace: &str) -> Pod {
+    let mut labels = HashMap::new();
+    if let Some(obj) = json["metadata"]["labels"].as_object() {
+        for (k, v) in obj {
+            if let Some(s) = v.as_str() {
+                labels.insert(k.clone(), s.to_string());
+            }
+        }
+    }
+
+    let mut container_statuses = Vec::new();
+    if let Some(statuses) = json["status"]["containerStatuses"].as_array() {
+        for status in statuses {
+            #[allow(clippy::cast_possible_truncation)] // exit codes are small i32 values
+            let state = if status["state"]["terminated"].is_object() {
+                let terminated = &status["state"]["terminated"];
+                ContainerState::Terminated {
+                    exit_code: terminated["exitCode"].as_i64().unwrap_or(0) as i32,
+                    reason: terminated["reason"]
+                        .as_str()
+                        .map(std::string::ToString::to_string),
+                    finished_at: terminated["finishedAt"]
+                        .as_str()
+                        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                        .map(|dt| dt.with_timezone(&Utc)),
+                }
+            } else if status["state"]["running"].is_object() {
+                let running = &status["state"]["running"];
+                ContainerState::Running {
+                    started_at: running["startedAt"]
+                        .as_str()
+                        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                        .map(|dt| dt.with_timezone(&Utc)),
+                }
+            } else {
+                let waiting = &status["state"]["waiting"];
+                ContainerState::Waiting {
+                    reason: waiting["reason"]
+                        .as_str()
+                        .map(std::string::ToString::to_string),
+                }
+            };
+
+            #[allow(clippy::cast_possible_truncation)] // restart counts are small i32 values
+            container_statuses.push(ContainerStatus {
+                name: status["name"].as_str().unwrap_or("").to_string(),
+                ready: status["ready"].as_bool().unwrap_or(false),
+                state,
+                restart_count: status["restartCount"].as_i64().unwrap_or(0) as i32,
+            });
+        }
+    }
+
+    // Parse pod conditions
+    let mut conditions = Vec::new();
+    if let Some(conds) = json["status"]["conditions"].as_array() {
+        for cond in conds {
+            conditions.push(PodCondition {
+                condition_type: cond["type"].as_str().unwrap_or("").to_string(),
+                status: cond["status"].as_str().unwrap_or("Unknown").to_string(),
+                reason: cond["reason"]
+                    .as_str()
+                    .map(std::string::ToString::to_string),
+                message: cond["message"]
+                    .as_str()
+                    .map(std::string::ToString::to_string),
+            });
+        }
+    }
+
+    // Parse pod start time
+    let started_at = json["status"]["startTime"]
+        .as_str()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&Utc));
+
+    Pod {
+        name: json["metadata"]["name"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string(),
+        namespace: namespace.to_string(),
+        phase: json["status"]["phase"]
+            .as_str()
+            .unwrap_or("Unknown")
+            .to_string(),
+        labels,
+        conditions,
+        container_statuses,
+        started_at,
+    }
+}
+
+/// Parse kubectl JSON output into our `CodeRun` type.
+pub fn parse_coderun_from_json(json: &serde_json::Value, namespace: &str) -> CodeRun {
+    let mut labels = HashMap::new();
+    if let Some(obj) = json["metadata"]["labels"].as_object() {
+        for (k, v) in obj {
+            if let Some(s) = v.as_str() {
+                labels.insert(k.clone(), s.to_string());
+            }
+        }
+    }
+
+    CodeRun {
+        name: json["metadata"]["name"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string(),
+        namespace: namespace.to_string(),
+        phase: json["status"]["phase"]
+            .as_str()
+            .unwrap_or("Unknown")
+            .to_string(),
+        agent: json["spec"]["githubApp"].as_str().unwrap_or("").to_string(),
+        task_id: json["spec"]["taskId"]
+            .as_str()
+            .map(String::from)
+            .or_else(|| json["spec"]["taskId"].as_i64().map(|n| n.to_string()))
+            .unwrap_or_default(),
+        labels,
+        created_at: json["metadata"]["creationTimestamp"]
+            .as_str()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc)),
+        pull_request_url: json["status"]["pullRequestUrl"]
+            .as_str()
+            .or_else(|| json["status"]["outputs"]["pr-url"].as_str())
+            .filter(|url| !url.is_empty())
+            .map(String::from),
+        pod_name: json["status"]["podName"]
+            .as_str()
+            .filter(|pod| !pod.is_empty())
+            .map(String::from),
+        started_at: json["status"]["startedAt"]
+            .as_str()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc)),
+        message: json["status"]["message"]
+            .as_str()
+            .filter(|message| !message.is_empty())
+            .map(String::from),
+    }
 }
@@ -5,6 +5,9 @@
 //! - CI remediation
 //! - Platform health monitoring
 //! - Loki log scanning
+//! - Leader election and persisted state for running multiple replicas
+//! - Alert silences and maintenance windows
+//! - Incident timelines and postmortem drafts
 
 // Internal modules needed by other modules
 pub mod acp;
@@ -13,10 +16,17 @@ mod templates;
 
 // Re-export modules for integration tests and library usage
 pub mod ci;
+pub mod clients;
+pub mod ha;
+pub mod incident;
+pub mod k8s;
 pub mod loki;
+pub mod platform;
 pub mod play;
+mod prometheus;
 pub mod scanner;
 pub mod sensors;
+pub mod silence;
 
 // Re-export common types
 pub use acp::{
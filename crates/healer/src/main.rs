@@ -4,21 +4,29 @@
 //! Uses kubectl --watch for real-time streaming of workflows, CRDs, pods, and sensors.
 //! Emits unified JSON events for Cursor agent E2E feedback loop automation.
 
+pub mod acp;
 mod alerts;
 pub mod ci;
+mod clients;
 mod dedup;
 mod github;
+mod ha;
+mod incident;
 mod k8s;
 pub mod loki;
+pub mod platform;
 pub mod play;
+mod prometheus;
 mod reconcile;
 pub mod scanner;
 pub mod sensors;
+mod silence;
 mod templates;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
+use clients::ClusterClient as _;
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -304,9 +312,33 @@ enum Commands {
             default_value = "/app/templates/healer"
         )]
         prompts_dir: String,
+        /// Alert rules YAML file or directory, layered over the built-in rules
+        #[arg(long, env = "HEALER_RULES_PATH")]
+        rules: Option<PathBuf>,
         /// Dry run - detect but don't spawn Factory
         #[arg(long)]
         dry_run: bool,
+        /// Only act on events while holding the `healer-alert-watch` Lease,
+        /// so several replicas can run with one active
+        #[arg(long, env = "HEALER_LEADER_ELECT")]
+        leader_elect: bool,
+        /// ConfigMap to persist alert dedup state in, so a new leader does not
+        /// re-alert on pods and `CodeRuns` the old one already handled; silences
+        /// created through the server API are read from it too
+        #[arg(long, env = "HEALER_STATE_CONFIGMAP")]
+        state_configmap: Option<String>,
+        /// YAML/JSON file of silences and maintenance windows
+        #[arg(long, env = "HEALER_SILENCES_PATH")]
+        silences: Option<PathBuf>,
+        /// Remediation config file; its guardrails bound alert-driven
+        /// remediation the same way they bound CI remediation
+        #[arg(long, env = "HEALER_CONFIG_PATH")]
+        config: Option<String>,
+    },
+    /// [ALERTS] Inspect and test declarative alert rules
+    Rules {
+        #[command(subcommand)]
+        action: RulesCommands,
     },
     /// [ALERTS] Test an alert flow manually
     TestAlert {
@@ -394,8 +426,17 @@ enum Commands {
         namespace: String,
 
         /// Path to remediation config file
-        #[arg(long)]
+        #[arg(long, env = "HEALER_CONFIG_PATH")]
         config: Option<String>,
+
+        /// ConfigMap to persist in-flight remediations in, so restarts and
+        /// other replicas resume them
+        #[arg(long, env = "HEALER_STATE_CONFIGMAP")]
+        state_configmap: Option<String>,
+
+        /// YAML/JSON file of silences and maintenance windows
+        #[arg(long, env = "HEALER_SILENCES_PATH")]
+        silences: Option<PathBuf>,
     },
     /// [SERVER] Run Play API server for MCP integration
     PlayApi {
@@ -406,6 +447,11 @@ enum Commands {
         /// Kubernetes namespace
         #[arg(long, default_value = "cto")]
         namespace: String,
+
+        /// ConfigMap to persist Play sessions in, so restarts and other
+        /// replicas resume them
+        #[arg(long, env = "HEALER_STATE_CONFIGMAP")]
+        state_configmap: Option<String>,
     },
     /// [SCANNER] Scan logs for errors and warnings across platform namespaces
     ScanLogs {
@@ -432,6 +478,16 @@ enum Commands {
         /// Show remediation candidates
         #[arg(long)]
         show_candidates: bool,
+
+        /// Namespace of the state ConfigMap
+        #[arg(long, default_value = "cto")]
+        namespace: String,
+
+        /// ConfigMap holding mined log templates and their history; loaded
+        /// before the scan and saved after it so new and spiking error
+        /// templates are detected across runs
+        #[arg(long, env = "HEALER_STATE_CONFIGMAP")]
+        state_configmap: Option<String>,
     },
     /// [SCANNER] Trigger remediation from scan results (reads JSON from stdin)
     RemediateFromScan {
@@ -565,6 +621,38 @@ enum SensorCommands {
         /// Path to remediation config file (optional)
         #[arg(long)]
         config: Option<String>,
+
+        /// Only poll while holding the `healer-github-actions-sensor` Lease,
+        /// so several replicas can run with one active
+        #[arg(long, env = "HEALER_LEADER_ELECT")]
+        leader_elect: bool,
+
+        /// ConfigMap to persist processed run IDs in, so a new leader does
+        /// not re-process runs the old one already handled
+        #[arg(long, env = "HEALER_STATE_CONFIGMAP")]
+        state_configmap: Option<String>,
+
+        /// CI remediation server endpoint to forward passing runs to, so
+        /// flaky-test history and incidents see green runs
+        #[arg(long, env = "HEALER_CI_SERVER_URL")]
+        ci_server_url: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum RulesCommands {
+    /// Evaluate rules against recorded watch events
+    Test {
+        /// JSONL file of `kubectl get -w -o json --output-watch-events` lines,
+        /// optionally with `logs` and `expect` (rule IDs that should fire)
+        #[arg(long)]
+        fixtures: PathBuf,
+        /// Alert rules YAML file or directory, layered over the built-in rules
+        #[arg(long, env = "HEALER_RULES_PATH")]
+        rules: Option<PathBuf>,
+        /// Test only the given rules, without the built-in ones
+        #[arg(long, requires = "rules")]
+        no_builtin: bool,
     },
 }
 
@@ -647,6 +735,24 @@ enum PlayCommands {
         #[arg(long)]
         force: bool,
     },
+    /// Replay the labelled evaluator dataset and report verdict precision/recall
+    EvalRegression {
+        /// Directory of regression case JSON files
+        #[arg(long, default_value = "crates/healer/tests/fixtures/evaluator")]
+        dataset: PathBuf,
+        /// Fail if any verdict's precision is below this (0.0-1.0)
+        #[arg(long, default_value = "1.0")]
+        min_precision: f64,
+        /// Fail if any verdict's recall is below this (0.0-1.0)
+        #[arg(long, default_value = "1.0")]
+        min_recall: f64,
+        /// Re-record answers from the LLM endpoint and rewrite the cases
+        #[arg(long)]
+        record: bool,
+        /// LLM endpoint to record from (defaults to the evaluator's)
+        #[arg(long, env = "HEALER_EVALUATOR_ENDPOINT")]
+        endpoint: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -1329,7 +1435,8 @@ fn generate_remediation_acceptance_criteria(failure: &FailureContext) -> String
 /// This ensures the remediation pod shares the `healer-workspace` PVC with the
 /// healer monitor deployment, allowing access to prompts and logs.
 #[allow(clippy::too_many_lines)] // 102 lines - complex YAML template generation
-fn trigger_remediation(
+async fn trigger_remediation(
+    cluster: &dyn clients::ClusterClient,
     config: &RemediationConfig,
     failure: &FailureContext,
     task_id: &str,
@@ -1432,32 +1539,12 @@ spec:
         max_iterations = config.max_iterations,
     );
 
-    // Apply via kubectl
-    let mut child = Command::new("kubectl")
-        .args(["apply", "-f", "-"])
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .context("Failed to spawn kubectl apply")?;
-
-    if let Some(mut stdin) = child.stdin.take() {
-        use std::io::Write;
-        stdin
-            .write_all(coderun_yaml.as_bytes())
-            .context("Failed to write YAML to kubectl stdin")?;
-    }
-
-    let output = child
-        .wait_with_output()
-        .context("Failed to wait for kubectl")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow::anyhow!(
-            "Failed to create remediation CodeRun: {stderr}"
-        ));
-    }
+    let manifest: serde_json::Value =
+        serde_yaml::from_str(&coderun_yaml).context("Failed to parse CodeRun manifest")?;
+    cluster
+        .create_coderun(namespace, &manifest)
+        .await
+        .context("Failed to create remediation CodeRun")?;
 
     println!(
         "{}",
@@ -1470,7 +1557,8 @@ spec:
 /// Create a Monitor `CodeRun` to start/continue the E2E watch loop
 ///
 /// Returns the name of the created `CodeRun`
-fn create_monitor_coderun(
+async fn create_monitor_coderun(
+    cluster: &dyn clients::ClusterClient,
     config: &MonitorConfig,
     play_config: &PlayConfig,
     iteration: u32,
@@ -1548,32 +1636,12 @@ spec:
         max_iterations = config.max_iterations,
     );
 
-    // Apply via kubectl
-    let mut child = Command::new("kubectl")
-        .args(["apply", "-f", "-"])
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .context("Failed to spawn kubectl apply")?;
-
-    if let Some(mut stdin) = child.stdin.take() {
-        use std::io::Write;
-        stdin
-            .write_all(coderun_yaml.as_bytes())
-            .context("Failed to write YAML to kubectl stdin")?;
-    }
-
-    let output = child
-        .wait_with_output()
-        .context("Failed to wait for kubectl")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow::anyhow!(
-            "Failed to create monitor CodeRun: {stderr}"
-        ));
-    }
+    let manifest: serde_json::Value =
+        serde_yaml::from_str(&coderun_yaml).context("Failed to parse CodeRun manifest")?;
+    cluster
+        .create_coderun(namespace, &manifest)
+        .await
+        .context("Failed to create monitor CodeRun")?;
 
     println!(
         "{}",
@@ -1584,19 +1652,9 @@ spec:
 }
 
 fn ensure_kube_context(expected: &str) -> Result<()> {
-    let output = Command::new("kubectl")
-        .args(["config", "current-context"])
-        .output()
-        .context("Failed to read current kubectl context. Is kubectl installed?")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow::anyhow!(
-            "Unable to determine kubectl context: {stderr}"
-        ));
-    }
-
-    let current = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let kubeconfig = kube::config::Kubeconfig::read()
+        .context("Failed to read kubeconfig to determine the current context")?;
+    let current = kubeconfig.current_context.unwrap_or_default();
 
     if current != expected {
         return Err(anyhow::anyhow!(
@@ -1610,6 +1668,7 @@ fn ensure_kube_context(expected: &str) -> Result<()> {
 
 /// Wait for a `CodeRun` to complete (Succeeded or Failed)
 async fn wait_for_coderun(
+    cluster: &dyn clients::ClusterClient,
     coderun_name: &str,
     namespace: &str,
     timeout_secs: u64,
@@ -1622,42 +1681,14 @@ async fn wait_for_coderun(
             return Ok((false, Some("Timeout waiting for CodeRun".to_string())));
         }
 
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "coderun",
-                coderun_name,
-                "-n",
-                namespace,
-                "-o",
-                "jsonpath={.status.phase}",
-            ])
-            .output()
+        let coderun = cluster
+            .get_coderun(namespace, coderun_name)
+            .await
             .context("Failed to get CodeRun status")?;
 
-        let phase = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-        match phase.as_str() {
-            "Succeeded" => return Ok((true, None)),
-            "Failed" => {
-                // Get failure message
-                let msg_output = Command::new("kubectl")
-                    .args([
-                        "get",
-                        "coderun",
-                        coderun_name,
-                        "-n",
-                        namespace,
-                        "-o",
-                        "jsonpath={.status.message}",
-                    ])
-                    .output()
-                    .ok();
-                let message = msg_output
-                    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-                    .filter(|s| !s.is_empty());
-                return Ok((false, message));
-            }
+        match coderun {
+            Some(run) if run.phase == "Succeeded" => return Ok((true, None)),
+            Some(run) if run.phase == "Failed" => return Ok((false, run.message)),
             _ => {
                 // Still running, wait and retry
                 tokio::time::sleep(std::time::Duration::from_secs(10)).await;
@@ -1670,6 +1701,7 @@ async fn wait_for_coderun(
 ///
 /// This polls the `ArgoCD` application status to detect when changes are deployed
 async fn wait_for_argocd_sync(
+    cluster: &dyn clients::ClusterClient,
     app_name: &str,
     expected_commit: Option<&str>,
     timeout_secs: u64,
@@ -1689,50 +1721,35 @@ async fn wait_for_argocd_sync(
         }
 
         // Get ArgoCD app status
-        let output = Command::new("kubectl")
-            .args(["get", "application", app_name, "-n", "argocd", "-o", "json"])
-            .output();
-
-        if let Ok(output) = output {
-            if output.status.success() {
-                if let Ok(app) = serde_json::from_slice::<serde_json::Value>(&output.stdout) {
-                    let sync_status = app["status"]["sync"]["status"]
-                        .as_str()
-                        .unwrap_or("Unknown");
-                    let health_status = app["status"]["health"]["status"]
-                        .as_str()
-                        .unwrap_or("Unknown");
-                    let revision = app["status"]["sync"]["revision"]
-                        .as_str()
-                        .unwrap_or("unknown");
-
-                    debug!(
-                        "ArgoCD app status: sync={}, health={}, revision={}",
-                        sync_status, health_status, revision
-                    );
+        match cluster.get_application("argocd", app_name).await {
+            Ok(Some(app)) => {
+                debug!(
+                    "ArgoCD app status: sync={}, health={}, revision={}",
+                    app.sync, app.health, app.revision
+                );
 
-                    // Check if synced and healthy
-                    if sync_status == "Synced" && health_status == "Healthy" {
-                        // If we have an expected commit, verify it matches
-                        if let Some(expected) = expected_commit {
-                            if revision.starts_with(expected) || expected.starts_with(revision) {
-                                println!(
-                                    "{}",
-                                    format!("ArgoCD synced to commit: {revision}").green()
-                                );
-                                return Ok(true);
-                            }
-                        } else {
-                            // No specific commit expected, just need synced + healthy
-                            println!(
-                                "{}",
-                                format!("ArgoCD synced and healthy (revision: {revision})").green()
-                            );
+                // Check if synced and healthy
+                if app.sync == "Synced" && app.health == "Healthy" {
+                    let revision = &app.revision;
+                    // If we have an expected commit, verify it matches
+                    if let Some(expected) = expected_commit {
+                        if revision.starts_with(expected) || expected.starts_with(revision.as_str())
+                        {
+                            println!("{}", format!("ArgoCD synced to commit: {revision}").green());
                             return Ok(true);
                         }
+                    } else {
+                        // No specific commit expected, just need synced + healthy
+                        println!(
+                            "{}",
+                            format!("ArgoCD synced and healthy (revision: {revision})").green()
+                        );
+                        return Ok(true);
                     }
                 }
             }
+            Ok(None) => debug!("ArgoCD application {app_name} not found"),
+            Err(e) => debug!("Failed to get ArgoCD application {app_name}: {e}"),
         }
 
         // Wait before next poll
@@ -1740,29 +1757,6 @@ async fn wait_for_argocd_sync(
     }
 }
 
-/// Get the PR URL from a `CodeRun` after remediation completes
-fn get_coderun_pr_url(coderun_name: &str, namespace: &str) -> Option<String> {
-    let output = Command::new("kubectl")
-        .args([
-            "get",
-            "coderun",
-            coderun_name,
-            "-n",
-            namespace,
-            "-o",
-            "jsonpath={.status.outputs.pr-url}",
-        ])
-        .output()
-        .ok()?;
-
-    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if url.is_empty() {
-        None
-    } else {
-        Some(url)
-    }
-}
-
 // =============================================================================
 // Argo Workflow Types - parsed from `argo get -o json`
 // =============================================================================
@@ -2248,7 +2242,8 @@ async fn main() -> Result<()> {
             tail,
             errors_only,
         } => {
-            let result = get_logs(&play_id, step.as_deref(), &cli.namespace, tail, errors_only)?;
+            let result =
+                get_logs(&play_id, step.as_deref(), &cli.namespace, tail, errors_only).await?;
             output_result(&result, cli.format)?;
         }
         Commands::Reset {
@@ -2259,7 +2254,8 @@ async fn main() -> Result<()> {
             force,
         } => {
             let result =
-                reset_environment(&cli.namespace, &org, &repo, skip_k8s, skip_github, force)?;
+                reset_environment(&cli.namespace, &org, &repo, skip_k8s, skip_github, force)
+                    .await?;
             output_result(&result, cli.format)?;
         }
         Commands::Run {
@@ -2325,12 +2321,15 @@ async fn main() -> Result<()> {
             })?;
 
             // Create Monitor CodeRun and exit
+            let cluster = clients::KubeClusterClient::try_default().await?;
             let coderun_name = create_monitor_coderun(
+                &cluster,
                 &monitor_config,
                 &cto_config.defaults.play,
                 1, // Always start at iteration 1
                 &cli.namespace,
-            )?;
+            )
+            .await?;
 
             println!(
                 "{}",
@@ -2407,10 +2406,29 @@ async fn main() -> Result<()> {
         Commands::AlertWatch {
             namespace,
             prompts_dir,
+            rules,
             dry_run,
+            leader_elect,
+            state_configmap,
+            silences,
+            config,
         } => {
             // Default enable_docker to true (matches CRD default)
-            run_alert_watch(&namespace, &prompts_dir, dry_run, true).await?;
+            run_alert_watch(
+                &namespace,
+                &prompts_dir,
+                rules.as_deref(),
+                dry_run,
+                true,
+                leader_elect,
+                state_configmap.as_deref(),
+                silences.as_deref(),
+                config.as_deref(),
+            )
+            .await?;
+        }
+        Commands::Rules { action } => {
+            handle_rules_command(action)?;
         }
         Commands::TestAlert {
             alert,
@@ -2447,7 +2465,8 @@ async fn main() -> Result<()> {
                 issue_number,
                 issue_file.as_deref(),
                 &config,
-            )?;
+            )
+            .await?;
         }
         Commands::FetchLogs {
             pod_name,
@@ -2458,7 +2477,7 @@ async fn main() -> Result<()> {
             fetch_pod_logs(&pod_name, &namespace, &output_dir, tail)?;
         }
         Commands::Play { action } => {
-            handle_play_command(action, &cli.namespace)?;
+            handle_play_command(action, &cli.namespace).await?;
         }
         Commands::Insights { action } => {
             handle_insights_command(action)?;
@@ -2468,11 +2487,25 @@ async fn main() -> Result<()> {
             repository,
             namespace,
             config: config_path,
+            state_configmap,
+            silences,
         } => {
-            run_server_command(&addr, &repository, &namespace, config_path.as_deref()).await?;
+            run_server_command(
+                &addr,
+                &repository,
+                &namespace,
+                config_path.as_deref(),
+                state_configmap.as_deref(),
+                silences.as_deref(),
+            )
+            .await?;
         }
-        Commands::PlayApi { addr, namespace } => {
-            run_play_api_command(&addr, &namespace).await?;
+        Commands::PlayApi {
+            addr,
+            namespace,
+            state_configmap,
+        } => {
+            run_play_api_command(&addr, &namespace, state_configmap.as_deref()).await?;
         }
         Commands::ScanLogs {
             window,
@@ -2481,6 +2514,8 @@ async fn main() -> Result<()> {
             warn_threshold,
             output,
             show_candidates,
+            namespace,
+            state_configmap,
         } => {
             run_scan_logs_command(
                 &window,
@@ -2489,6 +2524,8 @@ async fn main() -> Result<()> {
                 warn_threshold,
                 &output,
                 show_candidates,
+                &namespace,
+                state_configmap.as_deref(),
             )
             .await?;
         }
@@ -2497,7 +2534,7 @@ async fn main() -> Result<()> {
             dry_run,
             max_coderuns,
         } => {
-            run_remediate_from_scan(&config, dry_run, max_coderuns)?;
+            run_remediate_from_scan(&config, dry_run, max_coderuns).await?;
         }
         Commands::ReconcileIssues {
             repository,
@@ -2514,7 +2551,8 @@ async fn main() -> Result<()> {
                 dry_run,
                 max_issues,
                 &output,
-            )?;
+            )
+            .await?;
         }
         Commands::Sensor { action } => match action {
             SensorCommands::GithubActions {
@@ -2529,6 +2567,9 @@ async fn main() -> Result<()> {
                 max_per_poll,
                 once,
                 config,
+                leader_elect,
+                state_configmap,
+                ci_server_url,
             } => {
                 run_github_actions_sensor(
                     &repositories,
@@ -2542,6 +2583,9 @@ async fn main() -> Result<()> {
                     max_per_poll,
                     once,
                     config.as_deref(),
+                    leader_elect,
+                    state_configmap.as_deref(),
+                    ci_server_url,
                 )
                 .await?;
             }
@@ -2575,10 +2619,75 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+// =============================================================================
+// High Availability
+// =============================================================================
+
+/// ConfigMap state store, if `--state-configmap` was given.
+async fn open_state_store(
+    namespace: &str,
+    configmap: Option<&str>,
+) -> Result<Option<ha::StateStore>> {
+    let Some(name) = configmap else {
+        return Ok(None);
+    };
+    let cluster = clients::KubeClusterClient::try_default().await?;
+    info!("Persisting state in ConfigMap {namespace}/{name}");
+    Ok(Some(ha::StateStore::new(
+        std::sync::Arc::new(cluster),
+        namespace,
+        name,
+    )))
+}
+
+/// Start electing a leader on Lease `lease_name` when `--leader-elect` is
+/// set. The returned elector keeps renewing in the background.
+async fn start_leader_election(
+    enabled: bool,
+    lease_name: &str,
+    namespace: &str,
+) -> Result<Option<std::sync::Arc<ha::LeaderElector>>> {
+    if !enabled {
+        return Ok(None);
+    }
+    let cluster = clients::KubeClusterClient::try_default().await?;
+    let config = ha::LeaderElectionConfig::new(lease_name, namespace);
+    let elector = std::sync::Arc::new(ha::LeaderElector::new(std::sync::Arc::new(cluster), config));
+    info!(
+        "Leader election on Lease {namespace}/{lease_name} as {}",
+        elector.identity()
+    );
+    elector.clone().spawn();
+    Ok(Some(elector))
+}
+
+/// Silences from `--silences`, plus the API-created ones in the state store.
+async fn load_silences(
+    path: Option<&std::path::Path>,
+    store: Option<&ha::StateStore>,
+) -> Result<silence::Silences> {
+    let mut silences = silence::Silences::new();
+    if let Some(path) = path {
+        silences = silences.with_file(path)?;
+    }
+    if let Some(store) = store {
+        silences = silences.with_state_store(store.clone());
+        silences.refresh().await?;
+    }
+    Ok(silences)
+}
+
 // =============================================================================
 // GitHub Actions Sensor
 // =============================================================================
 
+/// State store key for the sensor's processed run IDs.
+const SENSOR_STATE_KEY: &str = "github-actions-sensor-processed-runs";
+
+/// Processed run IDs kept in the state store. Run IDs only grow, so the
+/// newest are the ones a lookback window can still return.
+const SENSOR_STATE_MAX_RUNS: usize = 2000;
+
 /// Run the GitHub Actions sensor to monitor for workflow failures.
 #[allow(clippy::too_many_arguments)] // CLI entry point with distinct configuration options
 async fn run_github_actions_sensor(
@@ -2593,6 +2702,9 @@ async fn run_github_actions_sensor(
     max_per_poll: usize,
     once: bool,
     config_path: Option<&str>,
+    leader_elect: bool,
+    state_configmap: Option<&str>,
+    ci_server_url: Option<String>,
 ) -> Result<()> {
     use sensors::{GitHubActionsSensor, SensorConfig};
 
@@ -2641,6 +2753,7 @@ async fn run_github_actions_sensor(
         excluded_workflows: excluded,
         max_per_poll,
         namespace: namespace.to_string(),
+        ci_server_url,
     };
 
     // Load remediation config if provided
@@ -2664,6 +2777,7 @@ async fn run_github_actions_sensor(
         // Run once and exit
         info!("Running single poll cycle...");
         let failures = sensor.poll_once()?;
+        sensor.forward_passing_runs().await;
         info!("Processed {} failure(s)", failures.len());
         for failure in &failures {
             println!(
@@ -2671,6 +2785,50 @@ async fn run_github_actions_sensor(
                 failure.workflow_name, failure.run_id, failure.branch, failure.html_url
             );
         }
+    } else if leader_elect || state_configmap.is_some() {
+        let elector =
+            start_leader_election(leader_elect, "healer-github-actions-sensor", namespace).await?;
+        let store = open_state_store(namespace, state_configmap).await?;
+        let interval = std::time::Duration::from_secs(poll_interval);
+        let mut leading = false;
+
+        info!("Starting continuous monitoring loop (Ctrl+C to stop)");
+        loop {
+            if let Some(elector) = &elector {
+                if !elector.is_leader() {
+                    leading = false;
+                    elector.wait_for_leadership().await;
+                    continue;
+                }
+            }
+
+            // Resume from the runs the previous leader already handled
+            if !leading {
+                leading = true;
+                if let Some(store) = &store {
+                    match store.load::<Vec<u64>>(SENSOR_STATE_KEY).await {
+                        Ok(runs) => sensor.mark_processed(runs.unwrap_or_default()),
+                        Err(e) => warn!("Failed to load processed runs: {e:#}"),
+                    }
+                }
+            }
+
+            if let Err(e) = sensor.poll_once() {
+                error!("Sensor poll failed: {e}");
+            }
+            sensor.forward_passing_runs().await;
+
+            if let Some(store) = &store {
+                let mut runs: Vec<u64> = sensor.processed_runs().iter().copied().collect();
+                runs.sort_unstable_by(|a, b| b.cmp(a));
+                runs.truncate(SENSOR_STATE_MAX_RUNS);
+                if let Err(e) = store.save(SENSOR_STATE_KEY, &runs).await {
+                    warn!("Failed to persist processed runs: {e:#}");
+                }
+            }
+
+            tokio::time::sleep(interval).await;
+        }
     } else {
         // Run continuous loop
         info!("Starting continuous monitoring loop (Ctrl+C to stop)");
@@ -2723,7 +2881,14 @@ async fn run_play_monitor(
         ..Default::default()
     };
 
-    let mut monitor = PlayMonitor::new(config);
+    let cluster = clients::KubeClusterClient::try_default().await?;
+    let mut monitor = PlayMonitor::new(config, std::sync::Arc::new(cluster));
+    if repository.is_some() {
+        match clients::OctocrabGitHubClient::from_env() {
+            Ok(github) => monitor = monitor.with_github(std::sync::Arc::new(github)),
+            Err(e) => warn!("GitHub client unavailable ({e}); issue creation disabled"),
+        }
+    }
 
     // Set up event channel for logging
     let (tx, mut rx) = tokio::sync::mpsc::channel(100);
@@ -3102,7 +3267,7 @@ async fn run_loop(
             let logs = if fetch_logs {
                 if let Some(ref step) = failed_step {
                     if let Some(ref pod_name) = step.pod_name {
-                        get_step_logs(pod_name, namespace, log_tail).ok()
+                        get_step_logs(pod_name, namespace, log_tail).await.ok()
                     } else {
                         None
                     }
@@ -3145,7 +3310,7 @@ async fn run_loop(
             let logs = if fetch_logs {
                 if let Some(ref step) = failed_step {
                     if let Some(ref pod_name) = step.pod_name {
-                        get_step_logs(pod_name, namespace, log_tail).ok()
+                        get_step_logs(pod_name, namespace, log_tail).await.ok()
                     } else {
                         None
                     }
@@ -3556,6 +3721,7 @@ async fn run_self_healing_loop(
     let play = &config.defaults.play;
     let org_name = &config.org_name;
     let remediation_config = config.defaults.remediation.clone();
+    let cluster = clients::KubeClusterClient::try_default().await?;
 
     // Resolve agent names with org_name defaults
     let impl_agent = play
@@ -3733,12 +3899,14 @@ async fn run_self_healing_loop(
 
                     // Trigger remediation
                     let coderun_name = trigger_remediation(
+                        &cluster,
                         &remediation,
                         &failure_context,
                         task_id,
                         iteration,
                         agent_namespace,
-                    )?;
+                    )
+                    .await?;
 
                     // Wait for remediation CodeRun to complete
                     println!(
@@ -3747,7 +3915,7 @@ async fn run_self_healing_loop(
                     );
 
                     let (success, message) =
-                        wait_for_coderun(&coderun_name, agent_namespace, 3600).await?;
+                        wait_for_coderun(&cluster, &coderun_name, agent_namespace, 3600).await?;
 
                     if !success {
                         println!(
@@ -3762,7 +3930,11 @@ async fn run_self_healing_loop(
                     }
 
                     // Get PR URL from CodeRun
-                    if let Some(pr_url) = get_coderun_pr_url(&coderun_name, agent_namespace) {
+                    let pr_url = cluster
+                        .get_coderun(agent_namespace, &coderun_name)
+                        .await?
+                        .and_then(|run| run.pull_request_url);
+                    if let Some(pr_url) = pr_url {
                         println!("{}", format!("Remediation PR created: {pr_url}").green());
 
                         // Wait for PR to be merged and ArgoCD to sync
@@ -3771,6 +3943,7 @@ async fn run_self_healing_loop(
                         // Note: In production, we'd monitor the PR status and wait for merge
                         // For now, we wait for ArgoCD to sync (which happens after merge)
                         let synced = wait_for_argocd_sync(
+                            &cluster,
                             "cto-controller", // ArgoCD app name
                             None,             // No specific commit
                             remediation.sync_timeout_secs,
@@ -3799,6 +3972,7 @@ async fn run_self_healing_loop(
                         println!("{}", "Waiting for ArgoCD to sync inline fix...".cyan());
 
                         let synced = wait_for_argocd_sync(
+                            &cluster,
                             "cto-controller", // ArgoCD app name
                             None,             // No specific commit
                             remediation.sync_timeout_secs,
@@ -3965,7 +4139,7 @@ async fn run_multi_watch(
 
                                 // Fetch logs on failure
                                 let logs = if fetch_logs {
-                                    get_step_logs(&name, &namespace, log_tail).ok()
+                                    get_step_logs(&name, &namespace, log_tail).await.ok()
                                 } else {
                                     None
                                 };
@@ -4020,7 +4194,7 @@ async fn run_multi_watch(
                     if let Some(ref p) = phase {
                         if p == "Failed" {
                             let logs = if fetch_logs {
-                                get_step_logs(&name, &namespace, log_tail).ok()
+                                get_step_logs(&name, &namespace, log_tail).await.ok()
                             } else {
                                 None
                             };
@@ -4102,10 +4276,17 @@ async fn poll_github_state(
     let Some(repo) = repository else {
         return;
     };
+    let github = match clients::OctocrabGitHubClient::from_env() {
+        Ok(github) => github,
+        Err(e) => {
+            warn!("GitHub client unavailable ({e}); PR state polling disabled");
+            return;
+        }
+    };
 
     loop {
-        // Use gh CLI to get PR state (poll immediately, then sleep)
-        let pr_state = get_github_pr_state(repo, task_id).await;
+        // Poll immediately, then sleep
+        let pr_state = get_github_pr_state(&github, repo, task_id).await;
 
         match pr_state {
             Ok(state) => {
@@ -4139,81 +4320,58 @@ async fn poll_github_state(
     }
 }
 
-/// Get GitHub PR state using gh CLI
-async fn get_github_pr_state(repository: &str, task_id: &str) -> Result<Option<PullRequestState>> {
-    // List PRs with the task label
-    let output = AsyncCommand::new("gh")
-        .args([
-            "pr",
-            "list",
-            "-R",
-            repository,
-            "-l",
-            &format!("task-{task_id}"),
-            "--json",
-            "number,state,title,mergeable,isDraft,labels,reviews,statusCheckRollup",
-        ])
-        .output()
+/// Get the state of the task's PR, in the shape `gh pr list --json` reports it
+async fn get_github_pr_state(
+    github: &dyn clients::GitHubClient,
+    repository: &str,
+    task_id: &str,
+) -> Result<Option<PullRequestState>> {
+    let prs = github
+        .list_pull_requests(repository, &format!("task-{task_id}"))
         .await?;
-
-    if !output.status.success() {
+    let Some(pr) = prs.into_iter().next() else {
         return Ok(None);
-    }
+    };
 
-    let prs: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+    let reviews = github
+        .pull_request_reviews(repository, pr.number)
+        .await?
+        .into_iter()
+        .map(|review| ReviewState {
+            author: review.author,
+            state: review.state,
+        })
+        .collect();
 
-    if let Some(pr) = prs.first() {
-        let labels: Vec<String> = pr["labels"]
-            .as_array()
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|l| l["name"].as_str().map(ToString::to_string))
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        let reviews: Vec<ReviewState> = pr["reviews"]
-            .as_array()
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|r| {
-                        Some(ReviewState {
-                            author: r["author"]["login"].as_str()?.to_string(),
-                            state: r["state"].as_str()?.to_string(),
-                        })
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        let checks: Vec<CheckState> = pr["statusCheckRollup"]
-            .as_array()
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|c| {
-                        Some(CheckState {
-                            name: c["name"].as_str()?.to_string(),
-                            status: c["status"].as_str()?.to_string(),
-                            conclusion: c["conclusion"].as_str().map(ToString::to_string),
-                        })
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        return Ok(Some(PullRequestState {
-            number: pr["number"].as_u64().unwrap_or(0),
-            state: pr["state"].as_str().unwrap_or("unknown").to_string(),
-            title: pr["title"].as_str().unwrap_or("").to_string(),
-            mergeable: pr["mergeable"].as_str().map(|s| s == "MERGEABLE"),
-            draft: pr["isDraft"].as_bool().unwrap_or(false),
-            labels,
-            reviews,
-            checks,
-        }));
-    }
+    let checks = github
+        .check_runs(repository, &pr.head_sha)
+        .await?
+        .into_iter()
+        .map(|run| CheckState {
+            name: run.name,
+            status: run.status.to_uppercase(),
+            conclusion: run.conclusion.map(|c| c.to_uppercase()),
+        })
+        .collect();
+
+    let state = if pr.merged {
+        "MERGED"
+    } else if pr.open {
+        "OPEN"
+    } else {
+        "CLOSED"
+    };
 
-    Ok(None)
+    Ok(Some(PullRequestState {
+        number: pr.number,
+        state: state.to_string(),
+        title: pr.title,
+        mergeable: pr.mergeable,
+        draft: pr.draft,
+        labels: pr.labels,
+        reviews,
+        checks,
+    }))
 }
 
 // =============================================================================
@@ -4221,7 +4379,7 @@ async fn get_github_pr_state(repository: &str, task_id: &str) -> Result<Option<P
 // =============================================================================
 
 /// Get logs for a workflow, optionally for a specific step
-fn get_logs(
+async fn get_logs(
     play_id: &str,
     step: Option<&str>,
     namespace: &str,
@@ -4230,7 +4388,7 @@ fn get_logs(
 ) -> Result<LogsResponse> {
     let logs = if let Some(step_name) = step {
         // Get logs for specific step/pod
-        get_step_logs(step_name, namespace, tail)?
+        get_step_logs(step_name, namespace, tail).await?
     } else {
         // Get logs from failed step(s) in the workflow
         let status = get_workflow_status(play_id, namespace)?;
@@ -4241,7 +4399,7 @@ fn get_logs(
             if let Some(recent) = status.steps.last() {
                 if let Some(ref pod_name) = recent.pod_name {
                     let _ = writeln!(all_logs, "=== {} ({}) ===", recent.name, recent.phase);
-                    all_logs.push_str(&get_step_logs(pod_name, namespace, tail)?);
+                    all_logs.push_str(&get_step_logs(pod_name, namespace, tail).await?);
                 }
             }
         } else {
@@ -4252,7 +4410,7 @@ fn get_logs(
                     if let Some(ref msg) = failed.message {
                         let _ = writeln!(all_logs, "Message: {msg}");
                     }
-                    all_logs.push_str(&get_step_logs(pod_name, namespace, tail)?);
+                    all_logs.push_str(&get_step_logs(pod_name, namespace, tail).await?);
                     all_logs.push('\n');
                 }
             }
@@ -4281,7 +4439,7 @@ fn get_logs(
 }
 
 /// Get logs for a specific step/pod
-fn get_step_logs(pod_name: &str, namespace: &str, tail: u32) -> Result<String> {
+async fn get_step_logs(pod_name: &str, namespace: &str, tail: u32) -> Result<String> {
     debug!("Getting logs for pod {} in {}", pod_name, namespace);
 
     // First try argo logs (works even for completed pods)
@@ -4305,40 +4463,44 @@ fn get_step_logs(pod_name: &str, namespace: &str, tail: u32) -> Result<String> {
         }
     }
 
-    // Fallback to kubectl logs
-    let output = Command::new("kubectl")
-        .args([
-            "logs",
-            pod_name,
-            "-n",
-            namespace,
-            "--tail",
-            &tail.to_string(),
-            "--all-containers=true",
-        ])
-        .output()
-        .context("Failed to execute kubectl logs")?;
-
-    if output.status.success() {
-        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    // Fall back to the pod's containers, then their previous instances
+    let cluster = clients::KubeClusterClient::try_default().await?;
+    match all_container_logs(&cluster, namespace, pod_name, tail, false).await {
+        Ok(logs) => Ok(logs),
+        Err(e) => {
+            debug!("Falling back to previous logs for {pod_name}: {e:#}");
+            all_container_logs(&cluster, namespace, pod_name, tail, true).await
+        }
     }
+}
 
-    // Try previous logs
-    let output = Command::new("kubectl")
-        .args([
-            "logs",
-            pod_name,
-            "-n",
-            namespace,
-            "--tail",
-            &tail.to_string(),
-            "--all-containers=true",
-            "--previous",
-        ])
-        .output()
-        .context("Failed to execute kubectl logs --previous")?;
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+/// Logs of every container in a pod, like `kubectl logs --all-containers`.
+async fn all_container_logs(
+    cluster: &dyn clients::ClusterClient,
+    namespace: &str,
+    pod_name: &str,
+    tail: u32,
+    previous: bool,
+) -> Result<String> {
+    let pod = cluster
+        .get_pod(namespace, pod_name)
+        .await?
+        .with_context(|| format!("Pod {namespace}/{pod_name} not found"))?;
+    let tail = i64::from(tail);
+    let mut logs = String::new();
+    for container in &pod.container_statuses {
+        let container_logs = if previous {
+            cluster
+                .previous_container_logs(namespace, pod_name, &container.name, tail)
+                .await?
+        } else {
+            cluster
+                .container_logs(namespace, pod_name, &container.name, tail)
+                .await?
+        };
+        logs.push_str(&container_logs);
+    }
+    Ok(logs)
 }
 
 /// Filter logs to only include error-related lines
@@ -4369,7 +4531,8 @@ fn filter_error_logs(logs: &str) -> String {
         .join("\n")
 }
 
-fn capture_terminated_agent_logs(
+async fn capture_terminated_agent_logs(
+    cluster: &dyn clients::ClusterClient,
     status: &WorkflowStatus,
     namespace: &str,
     logs_dir: &str,
@@ -4389,7 +4552,7 @@ fn capture_terminated_agent_logs(
             continue;
         }
 
-        if let Some(exit) = check_agent_container_exit(pod_name, namespace) {
+        if let Some(exit) = check_agent_container_exit(cluster, pod_name, namespace).await {
             println!(
                 "{}",
                 format!(
@@ -4399,8 +4562,9 @@ fn capture_terminated_agent_logs(
                 .yellow()
             );
 
-            let logs =
-                get_step_logs(pod_name, namespace, 10_000).context("Failed to read pod logs")?;
+            let logs = get_step_logs(pod_name, namespace, 10_000)
+                .await
+                .context("Failed to read pod logs")?;
             let safe_name = step.name.replace(' ', "_");
             let file_path = format!("{logs_dir}/{}_{}.log", safe_name, exit.container_name);
             std::fs::write(&file_path, logs)
@@ -4413,47 +4577,32 @@ fn capture_terminated_agent_logs(
     Ok(findings)
 }
 
-fn check_agent_container_exit(pod_name: &str, namespace: &str) -> Option<ContainerExitInfo> {
-    let output = Command::new("kubectl")
-        .args(["get", "pod", pod_name, "-n", namespace, "-o", "json"])
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let pod: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
-    let statuses = pod["status"]["containerStatuses"].as_array()?;
+async fn check_agent_container_exit(
+    cluster: &dyn clients::ClusterClient,
+    pod_name: &str,
+    namespace: &str,
+) -> Option<ContainerExitInfo> {
+    let pod = cluster.get_pod(namespace, pod_name).await.ok()??;
 
     let mut agent_exit: Option<ContainerExitInfo> = None;
     let mut sidecar_running = false;
 
-    for status in statuses {
-        let name = status["name"].as_str().unwrap_or("");
-        let state = &status["state"];
-
-        if let Some(term) = state["terminated"].as_object() {
-            #[allow(clippy::cast_possible_truncation)] // exit codes are always small i32 values
-            let exit_code = term
-                .get("exitCode")
-                .and_then(serde_json::Value::as_i64)
-                .map(|v| v as i32);
-            let reason = term
-                .get("reason")
-                .and_then(|v| v.as_str())
-                .map(ToString::to_string);
-            if !name.contains("docker") {
+    for status in &pod.container_statuses {
+        let name = status.name.as_str();
+        match &status.state {
+            k8s::ContainerState::Terminated {
+                exit_code, reason, ..
+            } if !name.contains("docker") => {
                 agent_exit = Some(ContainerExitInfo {
                     container_name: name.to_string(),
-                    exit_code,
-                    reason,
+                    exit_code: Some(*exit_code),
+                    reason: reason.clone(),
                 });
             }
-        } else if state["running"].is_object()
-            && (name.contains("docker") || name == "docker-daemon")
-        {
-            sidecar_running = true;
+            k8s::ContainerState::Running { .. } if name.contains("docker") => {
+                sidecar_running = true;
+            }
+            _ => {}
         }
     }
 
@@ -4553,7 +4702,7 @@ fn output_result<T: Serialize>(result: &T, format: OutputFormat) -> Result<()> {
 
 /// Reset the E2E environment - clean cluster and reset test repo
 #[allow(clippy::too_many_lines)] // Complex function not easily split
-fn reset_environment(
+async fn reset_environment(
     namespace: &str,
     org: &str,
     repo: &str,
@@ -4577,113 +4726,19 @@ fn reset_environment(
         }
     }
 
-    let mut k8s_cleanup = CleanupResult {
-        workflows_deleted: 0,
-        pods_deleted: 0,
-        configmaps_deleted: 0,
-        pvcs_deleted: 0,
-        skipped: skip_k8s,
-    };
-
-    // Kubernetes cleanup
-    if !skip_k8s {
+    let k8s_cleanup = if skip_k8s {
+        CleanupResult {
+            workflows_deleted: 0,
+            pods_deleted: 0,
+            configmaps_deleted: 0,
+            pvcs_deleted: 0,
+            skipped: true,
+        }
+    } else {
         println!("{}", "Cleaning up Kubernetes resources...".cyan());
-
-        // Delete workflows
-        let output = Command::new("kubectl")
-            .args([
-                "delete",
-                "workflows",
-                "--all",
-                "-n",
-                namespace,
-                "--force",
-                "--grace-period=0",
-            ])
-            .output()
-            .context("Failed to delete workflows")?;
-        if output.status.success() {
-            k8s_cleanup.workflows_deleted = count_deleted(&output.stdout);
-            println!("  {} Deleted workflows", "✓".green());
-        }
-
-        // Delete pods
-        let output = Command::new("kubectl")
-            .args([
-                "delete",
-                "pods",
-                "--all",
-                "-n",
-                namespace,
-                "--force",
-                "--grace-period=0",
-            ])
-            .output()
-            .context("Failed to delete pods")?;
-        if output.status.success() {
-            k8s_cleanup.pods_deleted = count_deleted(&output.stdout);
-            println!("  {} Deleted pods", "✓".green());
-        }
-
-        // Delete test ConfigMaps (play-*, test-*, coderun-*, remediation-*)
-        for pattern in &["play-", "test-", "coderun-", "remediation-"] {
-            let list_output = Command::new("kubectl")
-                .args(["get", "configmaps", "-n", namespace, "-o", "name"])
-                .output()?;
-
-            if list_output.status.success() {
-                let output_str = String::from_utf8_lossy(&list_output.stdout);
-                let cms: Vec<&str> = output_str.lines().filter(|l| l.contains(pattern)).collect();
-
-                for cm in &cms {
-                    let delete_result = Command::new("kubectl")
-                        .args(["delete", cm, "-n", namespace, "--force", "--grace-period=0"])
-                        .output();
-                    if delete_result.is_ok_and(|o| o.status.success()) {
-                        k8s_cleanup.configmaps_deleted += 1;
-                    }
-                }
-            }
-        }
-        println!(
-            "  {} Deleted {} ConfigMaps",
-            "✓".green(),
-            k8s_cleanup.configmaps_deleted
-        );
-
-        // Delete test PVCs (workspace-play-*, workspace-test-*)
-        for pattern in &["workspace-play-", "workspace-test-"] {
-            let list_output = Command::new("kubectl")
-                .args(["get", "pvc", "-n", namespace, "-o", "name"])
-                .output()?;
-
-            if list_output.status.success() {
-                let output_str = String::from_utf8_lossy(&list_output.stdout);
-                let pvcs: Vec<&str> = output_str.lines().filter(|l| l.contains(pattern)).collect();
-
-                for pvc in &pvcs {
-                    let delete_result = Command::new("kubectl")
-                        .args([
-                            "delete",
-                            pvc,
-                            "-n",
-                            namespace,
-                            "--force",
-                            "--grace-period=0",
-                        ])
-                        .output();
-                    if delete_result.is_ok_and(|o| o.status.success()) {
-                        k8s_cleanup.pvcs_deleted += 1;
-                    }
-                }
-            }
-        }
-        println!(
-            "  {} Deleted {} PVCs",
-            "✓".green(),
-            k8s_cleanup.pvcs_deleted
-        );
-    }
+        let cluster = clients::KubeClusterClient::try_default().await?;
+        cleanup_k8s_resources(&cluster, namespace).await?
+    };
 
     // GitHub repository reset
     let github_reset = if skip_github {
@@ -4845,15 +4900,80 @@ fn reset_github_repo(org: &str, repo: &str, _force: bool) -> Result<GithubResetR
     Ok(result)
 }
 
-/// Count deleted resources from kubectl output
-fn count_deleted(output: &[u8]) -> i32 {
-    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-    // Deleted line count is always small
-    let count = String::from_utf8_lossy(output)
-        .lines()
-        .filter(|l| l.contains("deleted"))
-        .count() as i32;
-    count
+/// Delete all workflows and pods in `namespace`, plus the ConfigMaps and
+/// PVCs left behind by test plays.
+async fn cleanup_k8s_resources(
+    cluster: &dyn clients::ClusterClient,
+    namespace: &str,
+) -> Result<CleanupResult> {
+    let mut cleanup = CleanupResult {
+        workflows_deleted: 0,
+        pods_deleted: 0,
+        configmaps_deleted: 0,
+        pvcs_deleted: 0,
+        skipped: false,
+    };
+
+    // Delete workflows
+    let workflows = cluster
+        .list_workflows(namespace, None)
+        .await
+        .context("Failed to delete workflows")?;
+    for workflow in &workflows {
+        if matches!(cluster.delete_workflow(namespace, workflow).await, Ok(true)) {
+            cleanup.workflows_deleted += 1;
+        }
+    }
+    println!("  {} Deleted workflows", "✓".green());
+
+    // Delete pods
+    let pods = cluster
+        .list_pods(namespace, None)
+        .await
+        .context("Failed to delete pods")?;
+    for pod in &pods {
+        if matches!(cluster.delete_pod(namespace, &pod.name).await, Ok(true)) {
+            cleanup.pods_deleted += 1;
+        }
+    }
+    println!("  {} Deleted pods", "✓".green());
+
+    // Delete test ConfigMaps (play-*, test-*, coderun-*, remediation-*)
+    let config_maps = cluster.list_config_maps(namespace, None).await?;
+    for name in config_maps.keys().filter(|name| {
+        ["play-", "test-", "coderun-", "remediation-"]
+            .iter()
+            .any(|pattern| name.contains(pattern))
+    }) {
+        if matches!(cluster.delete_config_map(namespace, name).await, Ok(true)) {
+            cleanup.configmaps_deleted += 1;
+        }
+    }
+    println!(
+        "  {} Deleted {} ConfigMaps",
+        "✓".green(),
+        cleanup.configmaps_deleted
+    );
+
+    // Delete test PVCs (workspace-play-*, workspace-test-*)
+    let claims = cluster.list_persistent_volume_claims(namespace).await?;
+    for name in claims.iter().filter(|name| {
+        ["workspace-play-", "workspace-test-"]
+            .iter()
+            .any(|pattern| name.contains(pattern))
+    }) {
+        if matches!(
+            cluster
+                .delete_persistent_volume_claim(namespace, name)
+                .await,
+            Ok(true)
+        ) {
+            cleanup.pvcs_deleted += 1;
+        }
+    }
+    println!("  {} Deleted {} PVCs", "✓".green(), cleanup.pvcs_deleted);
+
+    Ok(cleanup)
 }
 
 /// Run/submit a play workflow via Argo CLI (reads all parameters from config)
@@ -5215,6 +5335,7 @@ async fn run_monitor_loop(params: &MonitorParams) -> Result<()> {
 
     let logs_dir = "/workspace/watch/logs";
     std::fs::create_dir_all(logs_dir).ok();
+    let cluster = clients::KubeClusterClient::try_default().await?;
     let mut archived_pods: HashSet<String> = HashSet::new();
     let mut detected_failures: Vec<String> = Vec::new();
 
@@ -5227,7 +5348,7 @@ async fn run_monitor_loop(params: &MonitorParams) -> Result<()> {
         // Extract org/repo from repository string
         let parts: Vec<&str> = params.repository.split('/').collect();
         if parts.len() == 2 {
-            reset_environment(&params.namespace, parts[0], parts[1], false, false, true)?;
+            reset_environment(&params.namespace, parts[0], parts[1], false, false, true).await?;
         }
     }
 
@@ -5269,11 +5390,13 @@ async fn run_monitor_loop(params: &MonitorParams) -> Result<()> {
     loop {
         let status = get_workflow_status(&workflow_name, &params.namespace)?;
         let new_failures = capture_terminated_agent_logs(
+            &cluster,
             &status,
             &params.namespace,
             logs_dir,
             &mut archived_pods,
-        )?;
+        )
+        .await?;
         if !new_failures.is_empty() {
             for (step_name, exit_info) in new_failures {
                 detected_failures.push(format!(
@@ -5452,13 +5575,16 @@ Analyze the errors above and fix the underlying issues in the CTO platform.
         timestamp: Utc::now(),
     };
 
+    let cluster = clients::KubeClusterClient::try_default().await?;
     let coderun_name = trigger_remediation(
+        &cluster,
         &remediation_config,
         &failure,
         &params.task_id,
         params.iteration,
         &params.namespace,
-    )?;
+    )
+    .await?;
 
     println!(
         "{}",
@@ -5545,7 +5671,11 @@ fn run_remediation_loop(
 /// Create the next Monitor `CodeRun` after successful remediation
 /// Called by the remediation agent after PR is merged and synced
 #[allow(dead_code)]
-fn create_next_monitor_iteration(config_path: &str, iteration: u32, namespace: &str) -> Result<()> {
+async fn create_next_monitor_iteration(
+    config_path: &str,
+    iteration: u32,
+    namespace: &str,
+) -> Result<()> {
     let config_content = std::fs::read_to_string(config_path)?;
     let config: CtoConfig = serde_json::from_str(&config_content)?;
 
@@ -5554,12 +5684,15 @@ fn create_next_monitor_iteration(config_path: &str, iteration: u32, namespace: &
         .monitor
         .ok_or_else(|| anyhow::anyhow!("Missing monitor config"))?;
 
+    let cluster = clients::KubeClusterClient::try_default().await?;
     let coderun_name = create_monitor_coderun(
+        &cluster,
         &monitor_config,
         &config.defaults.play,
         iteration + 1,
         namespace,
-    )?;
+    )
+    .await?;
 
     println!(
         "{}",
@@ -5580,6 +5713,42 @@ enum AlertWatchEvent {
     CodeRunEvent(serde_json::Value),
 }
 
+/// State store key for [`AlertWatchState`].
+const ALERT_WATCH_STATE_KEY: &str = "alert-watch";
+
+/// Alert watch dedup state, persisted so a new leader neither re-alerts on
+/// what the old one handled nor restarts the stuck `CodeRun` clock.
+#[derive(Default, Serialize, Deserialize)]
+struct AlertWatchState {
+    /// `CodeRun` first-seen timestamps for A9 alerts
+    coderuns: alerts::CodeRunTracker,
+    /// `CodeRuns` we've already alerted on (to avoid spam)
+    alerted_coderuns: HashSet<String>,
+    /// Pods we've already alerted on (key: "alert_id:pod_name")
+    alerted_pods: HashSet<String>,
+}
+
+/// Save `state` if it changed since `last_saved`.
+async fn persist_alert_watch_state(
+    store: Option<&ha::StateStore>,
+    state: &AlertWatchState,
+    last_saved: &mut String,
+) {
+    let Some(store) = store else {
+        return;
+    };
+    let Ok(json) = serde_json::to_string(state) else {
+        return;
+    };
+    if json == *last_saved {
+        return;
+    }
+    match store.save(ALERT_WATCH_STATE_KEY, state).await {
+        Ok(()) => *last_saved = json,
+        Err(e) => warn!("Failed to persist alert watch state: {e:#}"),
+    }
+}
+
 /// Verify that the healer templates directory exists and contains expected structure.
 ///
 /// Returns an error if the templates directory is missing or incomplete.
@@ -5655,8 +5824,13 @@ fn verify_templates_directory(templates_dir: &str) -> Result<()> {
 async fn run_alert_watch(
     namespace: &str,
     prompts_dir: &str,
+    rules_path: Option<&std::path::Path>,
     dry_run: bool,
     enable_docker: bool,
+    leader_elect: bool,
+    state_configmap: Option<&str>,
+    silences_path: Option<&std::path::Path>,
+    config_path: Option<&str>,
 ) -> Result<()> {
     use tokio::io::{AsyncBufReadExt, BufReader};
     use tokio::process::Command as AsyncCommand;
@@ -5692,16 +5866,60 @@ async fn run_alert_watch(
         );
     }
 
-    // Initialize alert registry and default context
-    let registry = alerts::AlertRegistry::new();
+    // Initialize alert registry (built-in rules plus any from --rules) and default context
+    let mut rules = alerts::RuleSet::builtin();
+    if let Some(path) = rules_path {
+        let loaded = alerts::RuleSet::load(path)?;
+        if loaded.is_empty() {
+            warn!("No alert rules found in {}", path.display());
+        }
+        println!(
+            "{}",
+            format!(
+                "Loaded {} alert rules from {}",
+                loaded.len(),
+                path.display()
+            )
+            .green()
+        );
+        rules = rules.with_overrides(loaded);
+    }
+    let registry = alerts::AlertRegistry::with_rules(rules);
     let github_state = github::GitHubState::default();
 
-    // Track CodeRun timestamps for A9 alerts
-    let mut coderun_tracker = alerts::CodeRunTracker::new();
-    // Track which CodeRuns we've already alerted on (to avoid spam)
-    let mut alerted_coderuns: std::collections::HashSet<String> = std::collections::HashSet::new();
-    // Track which pods we've already alerted on (key: "alert_id:pod_name")
-    let mut alerted_pods: std::collections::HashSet<String> = std::collections::HashSet::new();
+    // CodeRun timestamps and alert dedup, resumed from the state store when
+    // this replica becomes leader
+    let mut watch_state = AlertWatchState::default();
+    let mut last_saved = String::new();
+    let elector = start_leader_election(leader_elect, "healer-alert-watch", namespace).await?;
+    let store = open_state_store(namespace, state_configmap).await?;
+    let mut leading = false;
+
+    // Silences suppress detection and notification; API-created ones are
+    // picked up from the state store every minute
+    let silences = load_silences(silences_path, store.as_ref()).await?;
+    let mut silences_refreshed = std::time::Instant::now();
+
+    // Alerts and resolutions are recorded on incidents the server API exports
+    let incidents = match &store {
+        Some(store) => incident::IncidentStore::new().with_state_store(store),
+        None => incident::IncidentStore::new(),
+    };
+
+    // The server's guardrails bound alert-driven remediation too, and a
+    // circuit breaker tripped by the server also stops it here
+    let guardrail_config = load_remediation_config(config_path)?.guardrails;
+    let guardrails = match &store {
+        Some(store) => ci::Guardrails::new(guardrail_config).with_state_store(store.clone()),
+        None => ci::Guardrails::new(guardrail_config),
+    };
+    guardrails.refresh().await?;
+    let cluster = clients::KubeClusterClient::try_default().await?;
+    let admission = AlertAdmission {
+        guardrails: &guardrails,
+        cluster: &cluster,
+        namespace,
+    };
 
     // Create a channel for events from both watches
     // Increased buffer to reduce chance of dropped events
@@ -5895,10 +6113,50 @@ async fn run_alert_watch(
 
     // Process events from both watches
     while let Some(event) = rx.recv().await {
+        // Followers drain events without acting on them
+        if let Some(elector) = &elector {
+            if !elector.is_leader() {
+                leading = false;
+                continue;
+            }
+        }
+        if !leading {
+            leading = true;
+            if let Some(store) = &store {
+                match store.load::<AlertWatchState>(ALERT_WATCH_STATE_KEY).await {
+                    Ok(Some(state)) => {
+                        println!(
+                            "{}",
+                            format!(
+                                "Resumed alert watch state: {} pod and {} CodeRun alerts",
+                                state.alerted_pods.len(),
+                                state.alerted_coderuns.len()
+                            )
+                            .green()
+                        );
+                        watch_state = state;
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("Failed to load alert watch state: {e:#}"),
+                }
+            }
+        }
+        persist_alert_watch_state(store.as_ref(), &watch_state, &mut last_saved).await;
+        if silences_refreshed.elapsed() >= std::time::Duration::from_mins(1) {
+            silences_refreshed = std::time::Instant::now();
+            if let Err(e) = silences.refresh().await {
+                warn!("Failed to refresh silences: {e:#}");
+            }
+            if let Err(e) = guardrails.refresh().await {
+                warn!("Failed to refresh guardrail state: {e:#}");
+            }
+        }
+        let read_only = guardrails.read_only();
+
         match event {
             AlertWatchEvent::PodEvent(event_json) => {
                 // Convert JSON to our Pod type
-                let pod = parse_pod_from_json(&event_json["object"], namespace);
+                let pod = k8s::parse_pod_from_json(&event_json["object"], namespace);
                 let event_type = event_json["type"].as_str().unwrap_or("");
 
                 // Debug: Log all pod events with container status info
@@ -5933,7 +6191,9 @@ async fn run_alert_watch(
                 // for pods that were alerted on before getting the exclusion label
                 if event_type == "DELETED" {
                     let suffix = format!(":{}", pod.name);
-                    alerted_pods.retain(|key| !key.ends_with(&suffix));
+                    watch_state
+                        .alerted_pods
+                        .retain(|key| !key.ends_with(&suffix));
                     continue; // Nothing more to do for deleted pods
                 }
 
@@ -5944,12 +6204,8 @@ async fn run_alert_watch(
                 }
 
                 // Determine the K8sEvent type based on phase and event type
-                let k8s_event = match (pod.phase.as_str(), event_type) {
-                    ("Failed" | "Error", _) => k8s::K8sEvent::PodFailed(pod.clone()),
-                    ("Succeeded", _) => k8s::K8sEvent::PodSucceeded(pod.clone()),
-                    ("Running", "ADDED") => k8s::K8sEvent::PodRunning(pod.clone()),
-                    ("Running" | _, "MODIFIED") => k8s::K8sEvent::PodModified(pod.clone()),
-                    _ => continue, // Skip other events
+                let Some(k8s_event) = k8s::pod_event(pod.clone(), event_type) else {
+                    continue; // Skip other events
                 };
 
                 // Debug: Log any terminated containers in Running pods (potential A2 alerts)
@@ -5981,20 +6237,28 @@ async fn run_alert_watch(
                 };
 
                 // Evaluate all alert handlers
-                let detected_alerts = registry.evaluate(&k8s_event, &github_state, &alert_ctx);
+                // Only fetch logs when a rule with log patterns selects this pod
+                let logs = if registry.needs_logs(&k8s_event) {
+                    Some(get_pod_logs_with_loki_fallback(&pod.name, namespace, 500).await)
+                } else {
+                    None
+                };
+
+                let detected_alerts =
+                    registry.evaluate(&k8s_event, &github_state, &alert_ctx, logs.as_deref());
 
                 // Process each detected alert (with deduplication)
                 for alert in detected_alerts {
                     // Build dedup key: "alert_id:pod_name"
-                    let dedup_key = format!("{}:{}", alert.id.as_str(), pod.name);
+                    let dedup_key = format!("{}:{}", alert.key(), pod.name);
 
                     // Skip if we've already alerted on this combination
-                    if alerted_pods.contains(&dedup_key) {
+                    if watch_state.alerted_pods.contains(&dedup_key) {
                         println!(
                             "{}",
                             format!(
                                 "⏭️  Skipping duplicate alert {}: {} (already alerted)",
-                                alert.id.as_str(),
+                                alert.key(),
                                 pod.name
                             )
                             .dimmed()
@@ -6002,11 +6266,36 @@ async fn run_alert_watch(
                         continue;
                     }
 
+                    // Suppressed alerts are counted on the silence, not handled
+                    let target =
+                        silence::AlertTarget::for_workload(alert.key(), namespace, &pod.labels);
+                    let incident_key = incident::workload_incident_key(namespace, &pod.name);
+                    if let Some(silence) = silences.check(&target).await {
+                        let silenced = incident::IncidentEvent::new(
+                            incident::IncidentEventKind::Silenced,
+                            format!("{} silenced: {}", alert.key(), silence.reason),
+                        )
+                        .with_detail("silence", silence.id.clone());
+                        incidents.append(&incident_key, silenced).await;
+                        println!(
+                            "{}",
+                            format!(
+                                "🔇 Silenced alert {}: {} ({}, {} suppressed)",
+                                alert.key(),
+                                pod.name,
+                                silence.reason,
+                                silence.suppressed
+                            )
+                            .dimmed()
+                        );
+                        continue;
+                    }
+
                     println!(
                         "{}",
                         format!(
                             "🚨 ALERT {}: {} [severity: {:?}]",
-                            alert.id.as_str(),
+                            alert.key(),
                             alert.message,
                             alert.severity
                         )
@@ -6015,7 +6304,7 @@ async fn run_alert_watch(
 
                     // Emit notification for this alert
                     notifier.notify(notify::NotifyEvent::HealAlert {
-                        alert_id: alert.id.as_str().to_string(),
+                        alert_id: alert.key().to_string(),
                         severity: match alert.severity {
                             alerts::types::Severity::Info => notify::Severity::Info,
                             alerts::types::Severity::Warning => notify::Severity::Warning,
@@ -6026,25 +6315,45 @@ async fn run_alert_watch(
                         timestamp: chrono::Utc::now(),
                     });
 
+                    incidents
+                        .record(
+                            &incident_key,
+                            &format!("{} on pod {}", alert.key(), pod.name),
+                            None,
+                            alert_incident_event(&alert),
+                        )
+                        .await;
+
                     // Mark as alerted BEFORE handling to prevent races
-                    alerted_pods.insert(dedup_key);
+                    watch_state.alerted_pods.insert(dedup_key);
+                    persist_alert_watch_state(store.as_ref(), &watch_state, &mut last_saved).await;
+
+                    if let Some(trip) = &read_only {
+                        print_read_only_skip(alert.key(), &pod.name, trip);
+                        continue;
+                    }
 
                     // Handle the alert (load prompt, fetch logs, spawn Factory)
-                    handle_detected_alert(
+                    if let Some(remediation) = handle_detected_alert(
                         &alert,
                         &pod,
                         namespace,
                         prompts_dir,
                         dry_run,
                         enable_docker,
+                        &admission,
                     )
-                    .await?;
+                    .await?
+                    {
+                        record_alert_remediation(&incidents, &incident_key, &remediation).await;
+                    }
                 }
 
                 // Also check for completion (pod succeeded) - this is a proactive check, not an alert
                 // Skip infrastructure pods (cronjobs, platform services)
                 if matches!(k8s_event, k8s::K8sEvent::PodSucceeded(_))
                     && !k8s::is_excluded_pod(&pod.name)
+                    && read_only.is_none()
                 {
                     println!(
                         "{}",
@@ -6057,7 +6366,7 @@ async fn run_alert_watch(
             }
             AlertWatchEvent::CodeRunEvent(event_json) => {
                 // Parse CodeRun from JSON
-                let coderun = parse_coderun_from_json(&event_json["object"], namespace);
+                let coderun = k8s::parse_coderun_from_json(&event_json["object"], namespace);
                 let event_type = event_json["type"].as_str().unwrap_or("");
 
                 // Log CodeRun events for visibility
@@ -6072,19 +6381,29 @@ async fn run_alert_watch(
 
                 // Track CodeRun for A9 stuck detection
                 let phase = coderun.phase.as_str();
+                let incident_key = incident::workload_incident_key(namespace, &coderun.name);
                 if phase == "Succeeded" || phase == "Failed" || event_type == "DELETED" {
                     // Terminal state or deleted - remove from tracking
-                    coderun_tracker.remove(&coderun.name);
-                    alerted_coderuns.remove(&coderun.name);
+                    watch_state.coderuns.remove(&coderun.name);
+                    watch_state.alerted_coderuns.remove(&coderun.name);
+                    let finished = incident::IncidentEvent::new(
+                        incident::IncidentEventKind::Resolved,
+                        format!(
+                            "CodeRun {} finished (phase={phase}, event={event_type})",
+                            coderun.name
+                        ),
+                    );
+                    incidents.append(&incident_key, finished).await;
                 } else {
                     // Non-terminal state - track first seen time
-                    coderun_tracker.record_first_seen(&coderun.name);
+                    watch_state.coderuns.record_first_seen(&coderun.name);
 
                     // Check if we should alert (exceeded threshold and not yet alerted)
                     let config = alerts::types::AlertConfig::default();
-                    if coderun_tracker
+                    if watch_state
+                        .coderuns
                         .exceeds_threshold(&coderun.name, config.stuck_coderun_threshold_mins)
-                        && !alerted_coderuns.contains(&coderun.name)
+                        && !watch_state.alerted_coderuns.contains(&coderun.name)
                     {
                         // Create K8sEvent and evaluate
                         let k8s_event = k8s::K8sEvent::CodeRunChanged(coderun.clone());
@@ -6100,14 +6419,40 @@ async fn run_alert_watch(
                         };
 
                         let detected_alerts =
-                            registry.evaluate(&k8s_event, &github_state, &alert_ctx);
+                            registry.evaluate(&k8s_event, &github_state, &alert_ctx, None);
 
                         for alert in detected_alerts {
+                            let target = silence::AlertTarget::for_workload(
+                                alert.key(),
+                                namespace,
+                                &coderun.labels,
+                            );
+                            if let Some(silence) = silences.check(&target).await {
+                                let silenced = incident::IncidentEvent::new(
+                                    incident::IncidentEventKind::Silenced,
+                                    format!("{} silenced: {}", alert.key(), silence.reason),
+                                )
+                                .with_detail("silence", silence.id.clone());
+                                incidents.append(&incident_key, silenced).await;
+                                println!(
+                                    "{}",
+                                    format!(
+                                        "🔇 Silenced alert {}: {} ({}, {} suppressed)",
+                                        alert.key(),
+                                        coderun.name,
+                                        silence.reason,
+                                        silence.suppressed
+                                    )
+                                    .dimmed()
+                                );
+                                continue;
+                            }
+
                             println!(
                                 "{}",
                                 format!(
                                     "🚨 ALERT {}: {} [severity: {:?}]",
-                                    alert.id.as_str(),
+                                    alert.key(),
                                     alert.message,
                                     alert.severity
                                 )
@@ -6116,7 +6461,7 @@ async fn run_alert_watch(
 
                             // Emit notification for this CodeRun alert
                             notifier.notify(notify::NotifyEvent::HealAlert {
-                                alert_id: alert.id.as_str().to_string(),
+                                alert_id: alert.key().to_string(),
                                 severity: match alert.severity {
                                     alerts::types::Severity::Info => notify::Severity::Info,
                                     alerts::types::Severity::Warning => notify::Severity::Warning,
@@ -6127,19 +6472,44 @@ async fn run_alert_watch(
                                 timestamp: chrono::Utc::now(),
                             });
 
+                            incidents
+                                .record(
+                                    &incident_key,
+                                    &format!("{} on CodeRun {}", alert.key(), coderun.name),
+                                    None,
+                                    alert_incident_event(&alert),
+                                )
+                                .await;
+
                             // Mark as alerted to avoid spam
-                            alerted_coderuns.insert(coderun.name.clone());
+                            watch_state.alerted_coderuns.insert(coderun.name.clone());
+                            persist_alert_watch_state(
+                                store.as_ref(),
+                                &watch_state,
+                                &mut last_saved,
+                            )
+                            .await;
+
+                            if let Some(trip) = &read_only {
+                                print_read_only_skip(alert.key(), &coderun.name, trip);
+                                continue;
+                            }
 
                             // Handle the alert for CodeRun
-                            handle_coderun_alert(
+                            if let Some(remediation) = handle_coderun_alert(
                                 &alert,
                                 &coderun,
                                 namespace,
                                 prompts_dir,
                                 dry_run,
                                 enable_docker,
+                                &admission,
                             )
-                            .await?;
+                            .await?
+                            {
+                                record_alert_remediation(&incidents, &incident_key, &remediation)
+                                    .await;
+                            }
                         }
                     }
                 }
@@ -6155,35 +6525,119 @@ async fn run_alert_watch(
     Ok(())
 }
 
-/// Parse kubectl JSON output into our `CodeRun` type.
-fn parse_coderun_from_json(json: &serde_json::Value, namespace: &str) -> k8s::CodeRun {
-    let mut labels = std::collections::HashMap::new();
-    if let Some(obj) = json["metadata"]["labels"].as_object() {
-        for (k, v) in obj {
-            if let Some(s) = v.as_str() {
-                labels.insert(k.clone(), s.to_string());
+/// Repository alert-driven remediation works on.
+const ALERT_REPOSITORY: &str = "5dlabs/cto";
+
+/// Load the remediation config from `config_path`, or the defaults.
+fn load_remediation_config(config_path: Option<&str>) -> Result<ci::RemediationConfig> {
+    let Some(path) = config_path else {
+        return Ok(ci::RemediationConfig::default());
+    };
+    let content = std::fs::read_to_string(path).context("Failed to read config file")?;
+    serde_json::from_str(&content).context("Failed to parse config file")
+}
+
+/// The guardrails alert-driven remediations are admitted against, with the
+/// cluster the running ones are listed from.
+struct AlertAdmission<'a> {
+    guardrails: &'a ci::Guardrails,
+    cluster: &'a dyn clients::ClusterClient,
+    namespace: &'a str,
+}
+
+impl AlertAdmission<'_> {
+    /// Whether the guardrails admit an alert-driven remediation; reports why
+    /// not. Dry runs spawn nothing, so they are always admitted.
+    async fn admit(&self, alert_id: &str, name: &str, dry_run: bool) -> bool {
+        if dry_run {
+            return true;
+        }
+        let admitted = ci::spawner::admit_remediation(
+            self.guardrails,
+            self.cluster,
+            self.namespace,
+            ALERT_REPOSITORY,
+            alert_id,
+        )
+        .await;
+        match admitted {
+            Ok(()) => true,
+            Err(e) => {
+                println!(
+                    "{}",
+                    format!(
+                        "🛑 Not remediating {} for {name}: {e:#}",
+                        alert_id.to_uppercase()
+                    )
+                    .yellow()
+                );
+                false
             }
         }
     }
+}
 
-    k8s::CodeRun {
-        name: json["metadata"]["name"]
-            .as_str()
-            .unwrap_or("unknown")
-            .to_string(),
-        namespace: namespace.to_string(),
-        phase: json["status"]["phase"]
-            .as_str()
-            .unwrap_or("Unknown")
-            .to_string(),
-        agent: json["spec"]["githubApp"].as_str().unwrap_or("").to_string(),
-        task_id: json["spec"]["taskId"]
-            .as_str()
-            .map(String::from)
-            .or_else(|| json["spec"]["taskId"].as_i64().map(|n| n.to_string()))
-            .unwrap_or_default(),
-        labels,
+/// Report an alert notified but not remediated because healer is read-only.
+fn print_read_only_skip(alert_id: &str, name: &str, trip: &ci::guardrails::Trip) {
+    println!(
+        "{}",
+        format!(
+            "🛑 Not remediating {alert_id} for {name}: healer is read-only ({})",
+            trip.reason
+        )
+        .yellow()
+    );
+}
+
+/// A Factory run remediating an alert.
+struct AlertRemediation {
+    started_at: chrono::DateTime<chrono::Utc>,
+    finished_at: chrono::DateTime<chrono::Utc>,
+    succeeded: bool,
+}
+
+/// Record an alert-driven remediation on its incident, for the timeline and
+/// the server's SLO metrics.
+async fn record_alert_remediation(
+    incidents: &incident::IncidentStore,
+    incident_key: &str,
+    remediation: &AlertRemediation,
+) {
+    let result = if remediation.succeeded {
+        "success"
+    } else {
+        "failure"
+    };
+    let mut spawned = incident::IncidentEvent::new(
+        incident::IncidentEventKind::RemediationSpawned,
+        "Spawned Factory",
+    )
+    .with_detail("agent", "factory");
+    spawned.at = remediation.started_at;
+    let mut completed = incident::IncidentEvent::new(
+        incident::IncidentEventKind::RemediationCompleted,
+        format!("Factory finished ({result})"),
+    )
+    .with_detail("agent", "factory")
+    .with_detail("result", result);
+    completed.at = remediation.finished_at;
+    incidents.append(incident_key, spawned).await;
+    incidents.append(incident_key, completed).await;
+}
+
+/// Incident timeline entry for a detected alert.
+fn alert_incident_event(alert: &alerts::Alert) -> incident::IncidentEvent {
+    let mut event = incident::IncidentEvent::new(
+        incident::IncidentEventKind::AlertDetected,
+        format!("{}: {}", alert.key(), alert.message),
+    )
+    .with_detail("severity", format!("{:?}", alert.severity).to_lowercase())
+    .with_detail("alert", alert.key().to_lowercase());
+    event.at = alert.detected_at;
+    if let Some(rule_id) = &alert.rule_id {
+        event = event.with_detail("rule", rule_id.clone());
     }
+    event
 }
 
 /// Handle a detected alert for a `CodeRun`.
@@ -6194,11 +6648,16 @@ async fn handle_coderun_alert(
     prompts_dir: &str,
     dry_run: bool,
     enable_docker: bool,
-) -> Result<()> {
-    let alert_id = alert.id.as_str().to_lowercase();
+    admission: &AlertAdmission<'_>,
+) -> Result<Option<AlertRemediation>> {
+    let alert_id = alert.key().to_lowercase();
     let task_id = &coderun.task_id;
     let agent = &coderun.agent;
 
+    if !admission.admit(&alert_id, &coderun.name, dry_run).await {
+        return Ok(None);
+    }
+
     // For CodeRun alerts, we use the coderun name as the "pod" name for consistency
     handle_alert(
         &alert_id,
@@ -6219,100 +6678,6 @@ async fn handle_coderun_alert(
     .await
 }
 
-/// Parse kubectl JSON output into our Pod type
-fn parse_pod_from_json(json: &serde_json::Value, namespace: &str) -> k8s::Pod {
-    let mut labels = std::collections::HashMap::new();
-    if let Some(obj) = json["metadata"]["labels"].as_object() {
-        for (k, v) in obj {
-            if let Some(s) = v.as_str() {
-                labels.insert(k.clone(), s.to_string());
-            }
-        }
-    }
-
-    let mut container_statuses = Vec::new();
-    if let Some(statuses) = json["status"]["containerStatuses"].as_array() {
-        for status in statuses {
-            #[allow(clippy::cast_possible_truncation)] // exit codes are small i32 values
-            let state = if status["state"]["terminated"].is_object() {
-                let terminated = &status["state"]["terminated"];
-                k8s::ContainerState::Terminated {
-                    exit_code: terminated["exitCode"].as_i64().unwrap_or(0) as i32,
-                    reason: terminated["reason"]
-                        .as_str()
-                        .map(std::string::ToString::to_string),
-                    finished_at: terminated["finishedAt"]
-                        .as_str()
-                        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-                        .map(|dt| dt.with_timezone(&Utc)),
-                }
-            } else if status["state"]["running"].is_object() {
-                let running = &status["state"]["running"];
-                k8s::ContainerState::Running {
-                    started_at: running["startedAt"]
-                        .as_str()
-                        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-                        .map(|dt| dt.with_timezone(&Utc)),
-                }
-            } else {
-                let waiting = &status["state"]["waiting"];
-                k8s::ContainerState::Waiting {
-                    reason: waiting["reason"]
-                        .as_str()
-                        .map(std::string::ToString::to_string),
-                }
-            };
-
-            #[allow(clippy::cast_possible_truncation)] // restart counts are small i32 values
-            container_statuses.push(k8s::ContainerStatus {
-                name: status["name"].as_str().unwrap_or("").to_string(),
-                ready: status["ready"].as_bool().unwrap_or(false),
-                state,
-                restart_count: status["restartCount"].as_i64().unwrap_or(0) as i32,
-            });
-        }
-    }
-
-    // Parse pod conditions
-    let mut conditions = Vec::new();
-    if let Some(conds) = json["status"]["conditions"].as_array() {
-        for cond in conds {
-            conditions.push(k8s::PodCondition {
-                condition_type: cond["type"].as_str().unwrap_or("").to_string(),
-                status: cond["status"].as_str().unwrap_or("Unknown").to_string(),
-                reason: cond["reason"]
-                    .as_str()
-                    .map(std::string::ToString::to_string),
-                message: cond["message"]
-                    .as_str()
-                    .map(std::string::ToString::to_string),
-            });
-        }
-    }
-
-    // Parse pod start time
-    let started_at = json["status"]["startTime"]
-        .as_str()
-        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-        .map(|dt| dt.with_timezone(&Utc));
-
-    k8s::Pod {
-        name: json["metadata"]["name"]
-            .as_str()
-            .unwrap_or("unknown")
-            .to_string(),
-        namespace: namespace.to_string(),
-        phase: json["status"]["phase"]
-            .as_str()
-            .unwrap_or("Unknown")
-            .to_string(),
-        labels,
-        conditions,
-        container_statuses,
-        started_at,
-    }
-}
-
 /// Handle a detected alert from the registry
 async fn handle_detected_alert(
     alert: &alerts::Alert,
@@ -6321,8 +6686,9 @@ async fn handle_detected_alert(
     prompts_dir: &str,
     dry_run: bool,
     enable_docker: bool,
-) -> Result<()> {
-    let alert_id = alert.id.as_str().to_lowercase();
+    admission: &AlertAdmission<'_>,
+) -> Result<Option<AlertRemediation>> {
+    let alert_id = alert.key().to_lowercase();
     let task_id = alert
         .context
         .get("task_id")
@@ -6332,7 +6698,19 @@ async fn handle_detected_alert(
     // Check for recent GitHub issue with same alert type AND workflow family (prevents issue spam)
     // Different workflows (e.g., atlas-* vs play-*) get separate issues
     if !dry_run {
-        match dedup::check_recent_alert_type_issue(&alert_id, &pod.name, "5dlabs/cto") {
+        let recent_issue = match clients::OctocrabGitHubClient::from_env() {
+            Ok(github) => {
+                dedup::check_recent_alert_type_issue(
+                    &github,
+                    &alert_id,
+                    &pod.name,
+                    ALERT_REPOSITORY,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        match recent_issue {
             Ok(Some((issue_num, title))) => {
                 println!(
                     "{}",
@@ -6346,7 +6724,7 @@ async fn handle_detected_alert(
                     .yellow()
                 );
                 // TODO: Consider adding a comment to the existing issue instead
-                return Ok(());
+                return Ok(None);
             }
             Ok(None) => {
                 println!(
@@ -6368,6 +6746,10 @@ async fn handle_detected_alert(
         }
     }
 
+    if !admission.admit(&alert_id, &pod.name, dry_run).await {
+        return Ok(None);
+    }
+
     handle_alert(
         &alert_id,
         &pod.name,
@@ -6406,7 +6788,8 @@ async fn handle_completion_check(
         None, // No additional context for completion checks
         enable_docker,
     )
-    .await
+    .await?;
+    Ok(())
 }
 
 /// Handle a detected alert by loading prompt and spawning Factory
@@ -6422,7 +6805,7 @@ async fn handle_alert(
     dry_run: bool,
     alert_context: Option<&std::collections::HashMap<String, String>>,
     enable_docker: bool,
-) -> Result<()> {
+) -> Result<Option<AlertRemediation>> {
     // Fetch pod logs (with Loki fallback for GC'd pods)
     let logs = get_pod_logs_with_loki_fallback(pod_name, namespace, 500).await;
 
@@ -6435,8 +6818,11 @@ async fn handle_alert(
         String::new()
     };
 
-    // Build template context
-    let template_filename = templates::TemplateEngine::alert_to_filename(alert_id);
+    // Build template context (rules can name their own remediation template)
+    let template_filename = alert_context
+        .and_then(|ctx| ctx.get("remediation_template"))
+        .cloned()
+        .unwrap_or_else(|| templates::TemplateEngine::alert_to_filename(alert_id));
     let context = templates::AlertContext {
         alert_id: alert_id.to_string(),
         pod_name: pod_name.to_string(),
@@ -6506,16 +6892,21 @@ async fn handle_alert(
                 format!("⚠️  Unreplaced variables: {}", unreplaced.join(", ")).yellow()
             );
         }
-        return Ok(());
+        return Ok(None);
     }
 
     // Write prompt to temp file and spawn Factory
     let prompt_path = format!("/tmp/alert-{alert_id}-{pod_name}.md");
     std::fs::write(&prompt_path, &rendered)?;
 
-    spawn_factory_with_prompt(&prompt_path, pod_name, alert_id).await?;
+    let started_at = chrono::Utc::now();
+    let succeeded = spawn_factory_with_prompt(&prompt_path, pod_name, alert_id).await?;
 
-    Ok(())
+    Ok(Some(AlertRemediation {
+        started_at,
+        finished_at: chrono::Utc::now(),
+        succeeded,
+    }))
 }
 
 /// Legacy template rendering for backward compatibility with .md files
@@ -6558,29 +6949,14 @@ fn render_legacy_template(
     Ok(rendered)
 }
 
-/// Fetch recent logs for a pod (sync version, kubectl only)
-fn get_pod_logs_for_alert(pod_name: &str, namespace: &str, tail: u32) -> String {
-    let output = std::process::Command::new("kubectl")
-        .args([
-            "logs",
-            pod_name,
-            "-n",
-            namespace,
-            "--tail",
-            &tail.to_string(),
-            "--all-containers",
-        ])
-        .output();
-
-    let logs = match output {
-        Ok(out) if out.status.success() => String::from_utf8_lossy(&out.stdout).to_string(),
-        Ok(out) => {
-            format!(
-                "[Failed to fetch logs: {}]",
-                String::from_utf8_lossy(&out.stderr)
-            )
-        }
-        Err(e) => format!("[Error fetching logs: {e}]"),
+/// Fetch recent logs for every container of a live pod
+async fn get_pod_logs_for_alert(pod_name: &str, namespace: &str, tail: u32) -> String {
+    let logs = match clients::KubeClusterClient::try_default().await {
+        Ok(cluster) => match all_container_logs(&cluster, namespace, pod_name, tail, false).await {
+            Ok(logs) => logs,
+            Err(e) => format!("[Failed to fetch logs: {e:#}]"),
+        },
+        Err(e) => format!("[Error fetching logs: {e:#}]"),
     };
 
     // Redact secrets before returning
@@ -6589,11 +6965,11 @@ fn get_pod_logs_for_alert(pod_name: &str, namespace: &str, tail: u32) -> String
 
 /// Fetch logs for a pod with Loki fallback (async version)
 ///
-/// Tries kubectl logs first (for live pods), then falls back to Loki
+/// Tries the pod's container logs first (for live pods), then falls back to Loki
 /// for historical logs if the pod has been garbage collected.
 async fn get_pod_logs_with_loki_fallback(pod_name: &str, namespace: &str, tail: u32) -> String {
-    // First, try kubectl logs (works for live pods)
-    let kubectl_logs = get_pod_logs_for_alert(pod_name, namespace, tail);
+    // First, try the live container logs
+    let kubectl_logs = get_pod_logs_for_alert(pod_name, namespace, tail).await;
 
     // If kubectl succeeded (doesn't contain error markers), return those logs
     if !kubectl_logs.contains("[Failed to fetch logs:")
@@ -6760,7 +7136,7 @@ async fn spawn_factory_with_prompt(
     prompt_path: &str,
     pod_name: &str,
     alert_id: &str,
-) -> Result<()> {
+) -> Result<bool> {
     use std::io::Write;
     use tokio::process::Command as AsyncCommand;
 
@@ -6899,7 +7275,8 @@ async fn spawn_factory_with_prompt(
                 "═══════════════════════════════════════════════════════════════"
             )?;
 
-            if out.status.success() {
+            let succeeded = out.status.success();
+            if succeeded {
                 println!("{}", format!("✅ Factory completed → {log_file}").green());
             } else {
                 println!(
@@ -6923,6 +7300,7 @@ async fn spawn_factory_with_prompt(
                 }
                 println!("{}", "────────────────────────".cyan());
             }
+            Ok(succeeded)
         }
         Err(e) => {
             writeln!(file, "ERROR: Failed to spawn: {e}")?;
@@ -6930,10 +7308,9 @@ async fn spawn_factory_with_prompt(
                 "{}",
                 format!("❌ Failed to spawn Factory: {e}. Is 'droid' in PATH?").red()
             );
+            Ok(false)
         }
     }
-
-    Ok(())
 }
 
 /// Test an alert flow manually
@@ -6970,7 +7347,7 @@ async fn test_alert_flow(
 
 /// Spawn a remediation agent for a detected issue by creating a `CodeRun` CRD.
 #[allow(clippy::too_many_lines)] // Dedup check + file validation + YAML generation in one flow
-fn spawn_remediation_agent(
+async fn spawn_remediation_agent(
     alert: &str,
     task_id: &str,
     target_pod: Option<&str>,
@@ -6996,7 +7373,19 @@ fn spawn_remediation_agent(
             "{}",
             format!("🔍 Checking for existing remediation: alert={alert} pod={pod_name}").dimmed()
         );
-        match dedup::check_existing_remediation(alert, pod_name, &config.coderun.namespace) {
+        let existing = match clients::KubeClusterClient::try_default().await {
+            Ok(cluster) => {
+                dedup::check_existing_remediation(
+                    &cluster,
+                    alert,
+                    pod_name,
+                    &config.coderun.namespace,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        match existing {
             Ok(Some(existing)) => {
                 println!(
                     "{}",
@@ -7114,7 +7503,8 @@ fn spawn_remediation_agent(
         acceptance_file.as_deref(),
         &config,
     );
-    apply_coderun(&coderun_yaml, &coderun_name)
+    let cluster = clients::KubeClusterClient::try_default().await?;
+    apply_coderun(&cluster, &coderun_yaml, &coderun_name).await
 }
 
 /// Load heal configuration from file, falling back to defaults if not found.
@@ -7275,55 +7665,41 @@ metadata:
     )
 }
 
-/// Apply the `CodeRun` YAML via kubectl.
-fn apply_coderun(coderun_yaml: &str, coderun_name: &str) -> Result<()> {
-    use std::io::Write as _;
-
+/// Create the `CodeRun` described by `coderun_yaml` in its manifest's namespace.
+async fn apply_coderun(
+    cluster: &dyn clients::ClusterClient,
+    coderun_yaml: &str,
+    coderun_name: &str,
+) -> Result<()> {
     println!("{}", "📝 Generated CodeRun YAML:".dimmed());
     for line in coderun_yaml.lines().take(10) {
         println!("   {}", line.dimmed());
     }
     println!("   {}", "...".dimmed());
-    println!("{}", "🚀 Applying CodeRun via kubectl...".yellow());
+    println!("{}", "🚀 Creating CodeRun...".yellow());
 
-    let mut child = Command::new("kubectl")
-        .args(["apply", "-f", "-"])
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .context("Failed to spawn kubectl apply")?;
-
-    if let Some(mut stdin) = child.stdin.take() {
-        stdin
-            .write_all(coderun_yaml.as_bytes())
-            .context("Failed to write YAML")?;
-    }
-
-    let output = child
-        .wait_with_output()
-        .context("Failed to wait for kubectl")?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        println!("{}", format!("❌ kubectl apply failed: {stderr}").red());
-        return Err(anyhow::anyhow!(
-            "Failed to create remediation CodeRun: {stderr}"
-        ));
+    let manifest: serde_json::Value =
+        serde_yaml::from_str(coderun_yaml).context("Failed to parse CodeRun YAML")?;
+    let namespace = manifest["metadata"]["namespace"]
+        .as_str()
+        .unwrap_or("cto")
+        .to_string();
+    if let Err(e) = cluster.create_coderun(&namespace, &manifest).await {
+        println!("{}", format!("❌ CodeRun creation failed: {e:#}").red());
+        return Err(e.context("Failed to create remediation CodeRun"));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    println!("{}", format!("   kubectl: {}", stdout.trim()).green());
     println!("{}", "═".repeat(60).green());
     println!(
         "{}",
-        format!("✅ CREATED: {coderun_name} in namespace cto")
+        format!("✅ CREATED: {coderun_name} in namespace {namespace}")
             .green()
             .bold()
     );
     println!("{}", "═".repeat(60).green());
     println!(
         "{}",
-        format!("👀 Monitor: kubectl get coderun {coderun_name} -n cto -w").dimmed()
+        format!("👀 Monitor: kubectl get coderun {coderun_name} -n {namespace} -w").dimmed()
     );
     Ok(())
 }
@@ -7536,14 +7912,19 @@ fn fetch_kubectl_output(args: &[&str], output_file: &str) -> Result<usize> {
 
 /// Handle play orchestration commands.
 #[allow(clippy::too_many_lines)] // Complex function not easily split
-fn handle_play_command(action: PlayCommands, namespace: &str) -> Result<()> {
+async fn handle_play_command(action: PlayCommands, namespace: &str) -> Result<()> {
+    use std::sync::Arc;
+
     use play::cleanup::PlayCleanup;
     use play::{PlayBatch, PlayTracker};
 
+    async fn connect() -> Result<Arc<dyn clients::ClusterClient>> {
+        Ok(Arc::new(clients::KubeClusterClient::try_default().await?))
+    }
+
     match action {
         PlayCommands::Status { task_id, stuck } => {
-            let batch = PlayBatch::load_from_k8s(namespace)?;
-            let tracker = PlayTracker::new(batch);
+            let tracker = PlayTracker::load(connect().await?, namespace).await?;
 
             if let Some(tid) = task_id {
                 // Show specific task
@@ -7581,8 +7962,11 @@ fn handle_play_command(action: PlayCommands, namespace: &str) -> Result<()> {
             }
         }
         PlayCommands::Remediate { task_id } => {
-            let batch = PlayBatch::load_from_k8s(namespace)?;
-            let tracker = PlayTracker::new(batch);
+            let mut tracker = PlayTracker::load(connect().await?, namespace).await?;
+            match clients::OctocrabGitHubClient::from_env() {
+                Ok(github) => tracker = tracker.with_github(Arc::new(github)),
+                Err(e) => warn!("GitHub client unavailable ({e}); PR context disabled"),
+            }
 
             // Find the task
             let task = tracker.get_task(&task_id);
@@ -7601,7 +7985,7 @@ fn handle_play_command(action: PlayCommands, namespace: &str) -> Result<()> {
                 );
 
                 // Run remediation
-                let result = tracker.remediate(issue)?;
+                let result = tracker.remediate(issue).await?;
 
                 println!("{}", "Remediation spawned:".green().bold());
                 println!("  CodeRun: {}", result.coderun_name.cyan());
@@ -7617,44 +8001,32 @@ fn handle_play_command(action: PlayCommands, namespace: &str) -> Result<()> {
             // Query CodeRuns directly since batch state doesn't track remediations
             println!("{}", "Active Remediations:".cyan().bold());
 
-            let output = Command::new("kubectl")
-                .args([
-                    "get", "coderuns",
-                    "-n", namespace,
-                    "-l", "app.kubernetes.io/name=healer,app.kubernetes.io/component=remediation",
-                    "-o", "jsonpath={range .items[*]}{.metadata.name}|{.metadata.labels.task-id}|{.metadata.creationTimestamp}\\n{end}",
-                ])
-                .output()
-                .context("Failed to query CodeRuns")?;
-
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
-
-                if lines.is_empty() {
+            let cluster = connect().await?;
+            let selector = "app.kubernetes.io/name=healer,app.kubernetes.io/component=remediation";
+            match cluster.list_coderuns(namespace, Some(selector)).await {
+                Ok(runs) if runs.is_empty() => {
                     println!("{}", "  No active remediations".dimmed());
-                } else {
-                    for line in lines {
-                        let parts: Vec<&str> = line.split('|').collect();
-                        if parts.len() >= 3 {
-                            let name = parts[0];
-                            let task_id = parts[1];
-                            let created = parts[2];
-                            println!(
-                                "  Task {}: {} (created {})",
-                                task_id.cyan(),
-                                name.yellow(),
-                                created
-                            );
-                        }
+                }
+                Ok(runs) => {
+                    for run in runs {
+                        let task_id = run.labels.get("task-id").map_or("", String::as_str);
+                        let created = run.created_at.map(|t| t.to_rfc3339()).unwrap_or_default();
+                        println!(
+                            "  Task {}: {} (created {})",
+                            task_id.cyan(),
+                            run.name.yellow(),
+                            created
+                        );
                     }
                 }
-            } else {
-                // CodeRun CRD might not exist
-                println!(
-                    "{}",
-                    "  No active remediations (or CodeRun CRD not installed)".dimmed()
-                );
+                Err(e) => {
+                    // CodeRun CRD might not exist
+                    debug!("Failed to list remediation CodeRuns: {e}");
+                    println!(
+                        "{}",
+                        "  No active remediations (or CodeRun CRD not installed)".dimmed()
+                    );
+                }
             }
         }
         PlayCommands::CancelRemediation { task_id } => {
@@ -7664,38 +8036,73 @@ fn handle_play_command(action: PlayCommands, namespace: &str) -> Result<()> {
                 format!("Cancelling remediation for task {task_id}...").yellow()
             );
 
-            let output = Command::new("kubectl")
-                .args([
-                    "delete",
-                    "coderun",
-                    "-n",
-                    namespace,
-                    "-l",
-                    &format!("task-id={task_id},app.kubernetes.io/name=healer"),
-                ])
-                .output()
-                .context("Failed to delete CodeRun")?;
+            let cluster = connect().await?;
+            let selector = format!("task-id={task_id},app.kubernetes.io/name=healer");
+            let result = async {
+                let mut deleted = 0;
+                for run in cluster.list_coderuns(namespace, Some(&selector)).await? {
+                    if cluster.delete_coderun(namespace, &run.name).await? {
+                        deleted += 1;
+                    }
+                }
+                anyhow::Ok(deleted)
+            }
+            .await;
 
-            if output.status.success() {
-                println!("{}", "Remediation cancelled".green());
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                println!("{}", format!("Failed to cancel: {stderr}").red());
+            match result {
+                Ok(0) => println!("{}", "No remediation found to cancel".yellow()),
+                Ok(_) => println!("{}", "Remediation cancelled".green()),
+                Err(e) => println!("{}", format!("Failed to cancel: {e}").red()),
             }
         }
         PlayCommands::Cleanup { force } => {
-            let batch = PlayBatch::load_from_k8s(namespace)?;
+            let cluster = connect().await?;
+            let batch = PlayBatch::load_from_k8s(cluster.as_ref(), namespace).await?;
             let cleanup = if force {
-                PlayCleanup::new(namespace).force()
+                PlayCleanup::new(cluster, namespace).force()
             } else {
-                PlayCleanup::new(namespace)
+                PlayCleanup::new(cluster, namespace)
             };
 
             println!("{}", "Cleaning up play state...".cyan());
-            let report = cleanup.cleanup(&batch)?;
+            let report = cleanup.cleanup(&batch).await?;
 
             println!("{}", format!("Cleanup complete: {report}").green());
         }
+        PlayCommands::EvalRegression {
+            dataset,
+            min_precision,
+            min_recall,
+            record,
+            endpoint,
+        } => {
+            use play::regression;
+
+            let mut config = play::EvaluatorConfig::default();
+            if let Some(endpoint) = endpoint {
+                config.llm_endpoint = endpoint;
+            }
+            let mut cases = regression::load_dataset(&dataset)?;
+
+            if record {
+                let live: Arc<dyn play::LlmClient> = Arc::new(play::HttpLlmClient::new(&config));
+                for (path, case) in &mut cases {
+                    regression::record_case(case, &config, live.clone()).await?;
+                    std::fs::write(&*path, serde_json::to_string_pretty(case)? + "\n")
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!("{} Recorded {}", "✓".green(), path.display());
+                }
+            }
+
+            let cases: Vec<_> = cases.into_iter().map(|(_, case)| case).collect();
+            let report = regression::run_dataset(&cases, &config).await?;
+            print!("{}", report.render());
+            report.check(min_precision, min_recall)?;
+            println!(
+                "{}",
+                format!("{} cases within precision/recall thresholds", cases.len()).green()
+            );
+        }
     }
 
     Ok(())
@@ -7895,6 +8302,77 @@ fn print_task_detail(task: &play::TaskState) {
 // =============================================================================
 
 /// Handle insights commands.
+/// Handle `rules` subcommands.
+fn handle_rules_command(action: RulesCommands) -> Result<()> {
+    match action {
+        RulesCommands::Test {
+            fixtures,
+            rules,
+            no_builtin,
+        } => {
+            let base = if no_builtin {
+                alerts::RuleSet::default()
+            } else {
+                alerts::RuleSet::builtin()
+            };
+            let rules = match rules {
+                Some(path) => base.with_overrides(alerts::RuleSet::load(&path)?),
+                None => base,
+            };
+            let content = std::fs::read_to_string(&fixtures)
+                .with_context(|| format!("Failed to read fixtures {}", fixtures.display()))?;
+            let ctx = alerts::AlertContext {
+                task_id: String::new(),
+                repository: String::new(),
+                namespace: "cto".to_string(),
+                pr_number: None,
+                workflow_name: None,
+                config: alerts::types::AlertConfig::default(),
+            };
+
+            let results = alerts::rules::test_fixtures(&rules, &content, &ctx)?;
+            let mut failures = 0;
+            for result in &results {
+                let fired = if result.fired.is_empty() {
+                    "-".to_string()
+                } else {
+                    result.fired.join(", ")
+                };
+                if result.passed() {
+                    println!(
+                        "{} line {} {} → {}",
+                        "✓".green(),
+                        result.line,
+                        result.resource,
+                        fired
+                    );
+                } else {
+                    failures += 1;
+                    println!(
+                        "{} line {} {} → {} (expected {})",
+                        "✗".red(),
+                        result.line,
+                        result.resource,
+                        fired,
+                        result.expected.as_deref().unwrap_or_default().join(", ")
+                    );
+                }
+            }
+
+            println!(
+                "{} events evaluated against {} rules, {} failed",
+                results.len(),
+                rules.len(),
+                failures
+            );
+            if failures > 0 {
+                anyhow::bail!("{failures} fixture(s) did not match expectations");
+            }
+        }
+    }
+    Ok(())
+}
+
 fn handle_insights_command(action: InsightsCommands) -> Result<()> {
     use play::insights::InsightCollector;
 
@@ -8019,6 +8497,8 @@ async fn run_server_command(
     repository: &str,
     namespace: &str,
     config_path: Option<&str>,
+    state_configmap: Option<&str>,
+    silences_path: Option<&std::path::Path>,
 ) -> Result<()> {
     use std::sync::Arc;
 
@@ -8032,12 +8512,7 @@ async fn run_server_command(
     println!();
 
     // Load configuration
-    let config = if let Some(path) = config_path {
-        let content = std::fs::read_to_string(path).context("Failed to read config file")?;
-        serde_json::from_str(&content).context("Failed to parse config file")?
-    } else {
-        ci::RemediationConfig::default()
-    };
+    let config = load_remediation_config(config_path)?;
 
     println!("  CLI:        {}", config.cli.cyan());
     println!("  Model:      {}", config.model.cyan());
@@ -8053,9 +8528,24 @@ async fn run_server_command(
     );
     println!();
 
-    // Create server state
-    let state = ci::ServerState::new(config, repository, namespace)
+    // Create server state, resuming in-flight remediations if persisted
+    let mut state = ci::ServerState::new(config, repository, namespace)
         .context("Failed to initialize server state")?;
+    let store = open_state_store(namespace, state_configmap).await?;
+    if let Some(store) = &store {
+        state = state.with_state_store(store.clone());
+        let restored = state.tracker.restore().await?;
+        println!("  Restored {restored} in-flight remediations");
+        state.guardrails.refresh().await?;
+        if let Some(trip) = state.guardrails.read_only() {
+            println!("  ⚠️  Read-only since {}: {}", trip.at, trip.reason);
+        }
+    }
+    state = state.with_silences(load_silences(silences_path, store.as_ref()).await?);
+    match clients::KubeClusterClient::try_default().await {
+        Ok(cluster) => state = state.with_cluster(Arc::new(cluster)),
+        Err(e) => warn!("Cluster context and PR tracking for remediation SLOs disabled: {e:#}"),
+    }
     let state = Arc::new(state);
 
     // Run the server
@@ -8066,8 +8556,12 @@ async fn run_server_command(
 }
 
 /// Run the Play API server for MCP integration.
-async fn run_play_api_command(addr: &str, namespace: &str) -> Result<()> {
-    use play::{run_play_api_server, PlayApiState};
+async fn run_play_api_command(
+    addr: &str,
+    namespace: &str,
+    state_configmap: Option<&str>,
+) -> Result<()> {
+    use play::{run_play_api_server, PlayApiState, SessionStore};
     use std::sync::Arc;
 
     println!(
@@ -8109,8 +8603,16 @@ async fn run_play_api_command(addr: &str, namespace: &str) -> Result<()> {
     );
     println!();
 
-    // Create server state
-    let state = Arc::new(PlayApiState::new(namespace));
+    // Create server state, resuming sessions if persisted
+    let state = match open_state_store(namespace, state_configmap).await? {
+        Some(store) => {
+            let sessions = SessionStore::new().with_state_store(store);
+            let restored = sessions.restore().await?;
+            println!("{}: {restored}", "Restored sessions".bright_white());
+            Arc::new(PlayApiState::with_store(Arc::new(sessions), namespace))
+        }
+        None => Arc::new(PlayApiState::new(namespace)),
+    };
 
     // Run the server
     println!(
@@ -8122,8 +8624,15 @@ async fn run_play_api_command(addr: &str, namespace: &str) -> Result<()> {
     Ok(())
 }
 
+/// State store key for the scanner's mined log templates.
+const TEMPLATE_STATE_KEY: &str = "log-templates";
+
+/// Suffix of the ConfigMap the mined templates are kept in, apart from the
+/// shared state they would otherwise crowd out.
+const TEMPLATE_CONFIGMAP_SUFFIX: &str = "templates";
+
 /// Run the log scanning command.
-#[allow(clippy::too_many_lines)] // Complex function not easily split
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)] // Complex function not easily split
 async fn run_scan_logs_command(
     window: &str,
     namespaces: &str,
@@ -8131,8 +8640,10 @@ async fn run_scan_logs_command(
     warn_threshold: u32,
     output_format: &str,
     show_candidates: bool,
+    namespace: &str,
+    state_configmap: Option<&str>,
 ) -> Result<()> {
-    use scanner::{format_report_text, LogScanner, ScannerConfig};
+    use scanner::{format_report_text, AnomalyKind, LogScanner, ScannerConfig, TemplateState};
 
     // Parse window duration
     let window_duration = parse_duration(window)?;
@@ -8175,15 +8686,37 @@ async fn run_scan_logs_command(
         error_threshold,
         warn_threshold,
         include_info: false,
+        ..ScannerConfig::default()
     };
 
-    let scanner = LogScanner::with_config(loki, config);
+    let mut scanner = LogScanner::with_config(loki, config);
+    let store = open_state_store(namespace, state_configmap).await?;
+    let templates = store
+        .as_ref()
+        .map(|store| store.sibling(TEMPLATE_CONFIGMAP_SUFFIX));
+    if let (Some(store), Some(templates)) = (&store, &templates) {
+        // Templates used to be kept in the shared ConfigMap
+        let state = match templates.load::<TemplateState>(TEMPLATE_STATE_KEY).await? {
+            Some(state) => Some(state),
+            None => store.load::<TemplateState>(TEMPLATE_STATE_KEY).await?,
+        };
+        if let Some(state) = state {
+            scanner = scanner.with_template_state(state);
+        }
+    }
 
     if !is_json_output {
         println!("{}", "Scanning logs...".cyan());
     }
     let report = scanner.scan(window_duration).await?;
 
+    if let (Some(store), Some(templates)) = (&store, &templates) {
+        templates
+            .save(TEMPLATE_STATE_KEY, &scanner.template_state())
+            .await?;
+        store.remove(TEMPLATE_STATE_KEY).await?;
+    }
+
     // Output based on format
     match output_format {
         "json" => {
@@ -8241,6 +8774,27 @@ async fn run_scan_logs_command(
                 println!();
             }
 
+            if !report.template_anomalies.is_empty() {
+                println!("{}", "Log Template Anomalies:".yellow());
+                for anomaly in &report.template_anomalies {
+                    let label = match anomaly.kind {
+                        AnomalyKind::New => "NEW".red(),
+                        AnomalyKind::Spike => "SPIKE".yellow(),
+                    };
+                    println!(
+                        "  {} {} ({} lines, {} pods)",
+                        label,
+                        anomaly.template,
+                        anomaly.count,
+                        anomaly.affected_pods.len()
+                    );
+                    if let Some(exemplar) = anomaly.exemplars.first() {
+                        println!("    e.g. {}", exemplar.dimmed());
+                    }
+                }
+                println!();
+            }
+
             if report.remediation_recommended {
                 println!("{}", "⚠️  REMEDIATION RECOMMENDED".red().bold());
                 if let Some(reason) = &report.recommendation_reason {
@@ -8283,7 +8837,11 @@ async fn run_scan_logs_command(
 /// Reads scan JSON from stdin, parses it, and spawns `CodeRuns` for services
 /// that need remediation.
 #[allow(clippy::too_many_lines)] // Complex function not easily split
-fn run_remediate_from_scan(config_path: &str, dry_run: bool, max_coderuns: usize) -> Result<()> {
+async fn run_remediate_from_scan(
+    config_path: &str,
+    dry_run: bool,
+    max_coderuns: usize,
+) -> Result<()> {
     use scanner::{determine_agent_for_service, ScanReport};
     use std::io::{self, BufRead};
 
@@ -8386,6 +8944,13 @@ fn run_remediate_from_scan(config_path: &str, dry_run: bool, max_coderuns: usize
         .yellow()
     );
 
+    // Dry runs never touch the cluster
+    let cluster = if dry_run {
+        None
+    } else {
+        Some(clients::KubeClusterClient::try_default().await?)
+    };
+
     for issue in &report.services_with_issues {
         if coderuns_created >= max {
             println!(
@@ -8516,7 +9081,8 @@ Investigate and fix the errors detected in the {service} service.
             &config,
         );
 
-        match apply_coderun(&coderun_yaml, &coderun_name) {
+        let cluster = cluster.as_ref().context("No cluster connection")?;
+        match apply_coderun(cluster, &coderun_yaml, &coderun_name).await {
             Ok(()) => {
                 coderuns_created += 1;
                 println!(
@@ -8553,7 +9119,7 @@ Investigate and fix the errors detected in the {service} service.
 }
 
 /// Run issue reconciliation - check and close resolved issues.
-fn run_reconcile_issues(
+async fn run_reconcile_issues(
     repository: &str,
     namespace: &str,
     labels: &str,
@@ -8562,6 +9128,7 @@ fn run_reconcile_issues(
     output_format: &str,
 ) -> Result<()> {
     use reconcile::{format_report_text, IssueReconciler, ReconcileConfig};
+    use std::sync::Arc;
 
     let is_json_output = output_format == "json";
 
@@ -8597,8 +9164,10 @@ fn run_reconcile_issues(
         println!();
     }
 
-    let reconciler = IssueReconciler::new(config);
-    let report = reconciler.reconcile()?;
+    let cluster = clients::KubeClusterClient::try_default().await?;
+    let github = clients::OctocrabGitHubClient::from_env()?;
+    let reconciler = IssueReconciler::new(config, Arc::new(cluster), Arc::new(github));
+    let report = reconciler.reconcile().await?;
 
     // Output based on format
     if output_format == "json" {
@@ -8855,4 +9424,178 @@ INFO: Continuing";
         assert_eq!(failed.len(), 1);
         assert_eq!(failed[0].name, "cleo-quality");
     }
+
+    #[tokio::test]
+    async fn test_cleanup_k8s_resources_keeps_unrelated_objects() {
+        let cluster = clients::FakeCluster::new()
+            .with_workflow("cto", "play-1")
+            .with_pod(k8s::Pod {
+                name: "play-1-rex".to_string(),
+                namespace: "cto".to_string(),
+                ..Default::default()
+            })
+            .with_config_map("cto", "play-state-1", &[], &[])
+            .with_config_map("cto", "controller-config", &[], &[])
+            .with_persistent_volume_claim("cto", "workspace-play-1")
+            .with_persistent_volume_claim("cto", "healer-workspace");
+
+        let cleanup = cleanup_k8s_resources(&cluster, "cto").await.unwrap();
+
+        assert_eq!(
+            (
+                cleanup.workflows_deleted,
+                cleanup.pods_deleted,
+                cleanup.configmaps_deleted,
+                cleanup.pvcs_deleted
+            ),
+            (1, 1, 1, 1)
+        );
+        let config_maps = cluster.list_config_maps("cto", None).await.unwrap();
+        assert_eq!(
+            config_maps.keys().collect::<Vec<_>>(),
+            vec!["controller-config"]
+        );
+        assert_eq!(
+            cluster.list_persistent_volume_claims("cto").await.unwrap(),
+            vec!["healer-workspace"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_agent_container_exit_waits_for_failure_or_sidecar() {
+        let container = |name: &str, state: k8s::ContainerState| k8s::ContainerStatus {
+            name: name.to_string(),
+            ready: false,
+            state,
+            restart_count: 0,
+        };
+        let terminated = |exit_code| k8s::ContainerState::Terminated {
+            exit_code,
+            reason: Some("Completed".to_string()),
+            finished_at: None,
+        };
+        let cluster = clients::FakeCluster::new()
+            .with_pod(k8s::Pod {
+                name: "clean-exit".to_string(),
+                namespace: "cto".to_string(),
+                container_statuses: vec![container("agent", terminated(0))],
+                ..Default::default()
+            })
+            .with_pod(k8s::Pod {
+                name: "stuck-sidecar".to_string(),
+                namespace: "cto".to_string(),
+                container_statuses: vec![
+                    container("agent", terminated(0)),
+                    container(
+                        "docker-daemon",
+                        k8s::ContainerState::Running { started_at: None },
+                    ),
+                ],
+                ..Default::default()
+            })
+            .with_pod(k8s::Pod {
+                name: "failed".to_string(),
+                namespace: "cto".to_string(),
+                container_statuses: vec![container("agent", terminated(2))],
+                ..Default::default()
+            });
+
+        assert!(check_agent_container_exit(&cluster, "clean-exit", "cto")
+            .await
+            .is_none());
+        assert!(check_agent_container_exit(&cluster, "missing", "cto")
+            .await
+            .is_none());
+        let stuck = check_agent_container_exit(&cluster, "stuck-sidecar", "cto")
+            .await
+            .unwrap();
+        assert_eq!(stuck.container_name, "agent");
+        let failed = check_agent_container_exit(&cluster, "failed", "cto")
+            .await
+            .unwrap();
+        assert_eq!(failed.exit_code, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_all_container_logs_reads_previous_instances() {
+        let cluster = clients::FakeCluster::new()
+            .with_pod(k8s::Pod {
+                name: "rex".to_string(),
+                namespace: "cto".to_string(),
+                container_statuses: vec![k8s::ContainerStatus {
+                    name: "agent".to_string(),
+                    ready: false,
+                    state: k8s::ContainerState::default(),
+                    restart_count: 1,
+                }],
+                ..Default::default()
+            })
+            .with_container_logs("cto", "rex", "agent", "current")
+            .with_previous_container_logs("cto", "rex", "agent", "crashed");
+
+        assert_eq!(
+            all_container_logs(&cluster, "cto", "rex", 100, false)
+                .await
+                .unwrap(),
+            "current"
+        );
+        assert_eq!(
+            all_container_logs(&cluster, "cto", "rex", 100, true)
+                .await
+                .unwrap(),
+            "crashed"
+        );
+        assert!(all_container_logs(&cluster, "cto", "gone", 100, false)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_github_pr_state_matches_gh_output() {
+        let github = clients::FakeGitHub::new()
+            .with_pull_request(
+                "5dlabs/cto",
+                clients::PullRequest {
+                    number: 7,
+                    title: "Task 3".to_string(),
+                    head_sha: "abc123".to_string(),
+                    open: true,
+                    mergeable: Some(true),
+                    labels: vec!["task-3".to_string()],
+                    ..Default::default()
+                },
+            )
+            .with_reviews(
+                "5dlabs/cto",
+                7,
+                vec![clients::Review {
+                    author: "cleo".to_string(),
+                    state: "APPROVED".to_string(),
+                }],
+            )
+            .with_check_runs(
+                "5dlabs/cto",
+                "abc123",
+                vec![clients::CheckRun {
+                    name: "lint".to_string(),
+                    status: "completed".to_string(),
+                    conclusion: Some("success".to_string()),
+                }],
+            );
+
+        let state = get_github_pr_state(&github, "5dlabs/cto", "3")
+            .await
+            .unwrap()
+            .expect("PR labelled task-3");
+
+        assert_eq!(state.number, 7);
+        assert_eq!(state.state, "OPEN");
+        assert_eq!(state.reviews[0].state, "APPROVED");
+        assert_eq!(state.checks[0].status, "COMPLETED");
+        assert_eq!(state.checks[0].conclusion.as_deref(), Some("SUCCESS"));
+        assert!(get_github_pr_state(&github, "5dlabs/cto", "4")
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
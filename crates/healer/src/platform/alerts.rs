@@ -9,6 +9,7 @@ use tracing::{debug, info, warn};
 
 use crate::loki::LokiClient;
 use crate::prometheus::PrometheusClient;
+use crate::silence::{AlertTarget, Silences};
 
 use super::types::{
     AlertmanagerAlert, PlatformAlert, PlatformIssue, PlatformIssueType, RemediationStatus,
@@ -32,6 +33,8 @@ pub struct PlatformAlertHandler {
     max_concurrent: usize,
     /// Deduplication window in minutes
     dedup_window_mins: u64,
+    /// Silences and maintenance windows suppressing remediation
+    silences: Arc<Silences>,
 }
 
 impl PlatformAlertHandler {
@@ -46,9 +49,17 @@ impl PlatformAlertHandler {
             active: Arc::new(RwLock::new(HashMap::new())),
             max_concurrent: 5,
             dedup_window_mins: 30,
+            silences: Arc::new(Silences::new()),
         }
     }
 
+    /// Share silences with the CI server (see `/api/silences`).
+    #[must_use]
+    pub fn with_silences(mut self, silences: Arc<Silences>) -> Self {
+        self.silences = silences;
+        self
+    }
+
     /// Process an alert from Alertmanager.
     ///
     /// # Errors
@@ -72,6 +83,32 @@ impl PlatformAlertHandler {
             alert.component()
         );
 
+        // Silenced alerts are counted on the silence, not remediated
+        if let Err(e) = self.silences.refresh().await {
+            warn!("Failed to refresh silences: {e:#}");
+        }
+        let target = AlertTarget {
+            alert_id: alert.name().to_string(),
+            namespace: alert.namespace().map(str::to_string),
+            repository: alert.labels.get("repository").cloned(),
+            service: alert
+                .labels
+                .get("service")
+                .map(String::as_str)
+                .or_else(|| alert.component())
+                .map(str::to_string),
+            labels: alert.labels.clone(),
+        };
+        if let Some(silence) = self.silences.check(&target).await {
+            info!(
+                "Silenced platform alert {} ({}, {} suppressed)",
+                alert.name(),
+                silence.reason,
+                silence.suppressed
+            );
+            return Ok(None);
+        }
+
         // Check for duplicate
         if self.is_duplicate(&alert.fingerprint).await {
             debug!("Skipping duplicate alert: {}", alert.fingerprint);
@@ -95,13 +132,10 @@ impl PlatformAlertHandler {
         let issue_type = PlatformIssueType::from_alert_name(&platform_alert.name);
 
         // Gather context (logs)
-        let logs = self
-            .gather_logs(&platform_alert)
-            .await
-            .unwrap_or_else(|e| {
-                warn!("Failed to gather logs: {e}");
-                String::new()
-            });
+        let logs = self.gather_logs(&platform_alert).await.unwrap_or_else(|e| {
+            warn!("Failed to gather logs: {e}");
+            String::new()
+        });
 
         // Create remediation target
         let agent = issue_type.remediation_agent();
@@ -235,7 +269,10 @@ impl PlatformAlertHandler {
         }
         prompt.push_str(&format!("- **Started**: {}\n", issue.alert.started_at));
         prompt.push_str(&format!("\n**Summary**: {}\n", issue.alert.summary));
-        prompt.push_str(&format!("\n**Description**:\n{}\n", issue.alert.description));
+        prompt.push_str(&format!(
+            "\n**Description**:\n{}\n",
+            issue.alert.description
+        ));
 
         prompt.push_str("\n## Recent Logs\n\n```\n");
         if issue.logs.is_empty() {
@@ -321,7 +358,8 @@ spec:
         if let Some(tracked) = active.get(fingerprint) {
             // Check if within dedup window
             let elapsed = Utc::now() - tracked.started_at;
-            #[allow(clippy::cast_possible_wrap)] // dedup_window_mins is a small config value, won't wrap
+            #[allow(clippy::cast_possible_wrap)]
+            // dedup_window_mins is a small config value, won't wrap
             if elapsed.num_minutes() < self.dedup_window_mins as i64 {
                 return true;
             }
@@ -358,7 +396,12 @@ spec:
         let active = self.active.read().await;
         active
             .values()
-            .filter(|t| matches!(t.status, RemediationStatus::Pending | RemediationStatus::InProgress))
+            .filter(|t| {
+                matches!(
+                    t.status,
+                    RemediationStatus::Pending | RemediationStatus::InProgress
+                )
+            })
             .count()
     }
 
@@ -12,6 +12,8 @@ use serde::Serialize;
 use std::sync::Arc;
 use tracing::{error, info};
 
+use crate::silence::Silences;
+
 use super::alerts::PlatformAlertHandler;
 use super::types::AlertmanagerPayload;
 use super::workflow::WorkflowRemediator;
@@ -27,11 +29,13 @@ pub struct PlatformServerState {
 }
 
 impl PlatformServerState {
-    /// Create new server state.
+    /// Create new server state. `silences` is shared with the CI server, so
+    /// silences created through `/api/silences` also suppress platform alerts.
     #[must_use]
-    pub fn new(namespace: &str, repository: &str) -> Self {
+    pub fn new(namespace: &str, repository: &str, silences: Arc<Silences>) -> Self {
         Self {
-            platform_handler: PlatformAlertHandler::new(namespace, repository),
+            platform_handler: PlatformAlertHandler::new(namespace, repository)
+                .with_silences(silences),
             workflow_handler: WorkflowRemediator::new(namespace, repository),
             monitor_events: MonitorEventStore::default(),
         }
@@ -43,7 +47,10 @@ pub fn build_platform_router(state: Arc<PlatformServerState>) -> Router {
     Router::new()
         .route("/api/alerts/platform", post(platform_alert_handler))
         .route("/api/alerts/workflows", post(workflow_alert_handler))
-        .route("/api/v1/monitor-events/stakpak", post(stakpak_monitor_event_handler))
+        .route(
+            "/api/v1/monitor-events/stakpak",
+            post(stakpak_monitor_event_handler),
+        )
         .route("/api/platform/status", get(platform_status_handler))
         .route("/api/platform/remediations", get(remediations_handler))
         .with_state(state)
@@ -240,9 +247,80 @@ async fn platform_status_handler(
 }
 
 /// Remediations list handler.
-async fn remediations_handler(
-    State(state): State<Arc<PlatformServerState>>,
-) -> impl IntoResponse {
+async fn remediations_handler(State(state): State<Arc<PlatformServerState>>) -> impl IntoResponse {
     let remediations = state.platform_handler.get_remediations().await;
     Json(remediations)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::silence::{NewSilence, SilenceMatcher};
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_silence_suppresses_alertmanager_payload() {
+        let silences = Arc::new(Silences::new());
+        silences
+            .create(NewSilence {
+                matcher: SilenceMatcher {
+                    alert_id: Some("ControllerPodCrashLooping".to_string()),
+                    namespace: Some("cto".to_string()),
+                    ..SilenceMatcher::default()
+                },
+                reason: "Planned controller upgrade".to_string(),
+                created_by: None,
+                starts_at: None,
+                ends_at: None,
+                duration_mins: Some(60),
+                schedule: None,
+            })
+            .await
+            .unwrap();
+        let state = Arc::new(PlatformServerState::new(
+            "cto",
+            "5dlabs/cto",
+            silences.clone(),
+        ));
+
+        let payload = serde_json::json!({
+            "version": "4",
+            "groupKey": "{}:{alertname=\"ControllerPodCrashLooping\"}",
+            "status": "firing",
+            "receiver": "healer",
+            "alerts": [{
+                "status": "firing",
+                "labels": {
+                    "alertname": "ControllerPodCrashLooping",
+                    "namespace": "cto",
+                    "severity": "critical",
+                },
+                "annotations": {},
+                "startsAt": "2026-10-18T10:00:00Z",
+                "fingerprint": "c0ffee",
+            }],
+        });
+        let response = build_platform_router(state.clone())
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/alerts/platform")
+                    .header("content-type", "application/json")
+                    .body(Body::from(payload.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["coderuns_spawned"], serde_json::json!([]));
+        assert_eq!(body["errors"], serde_json::json!([]));
+        assert_eq!(silences.list().await[0].suppressed, 1);
+    }
+}
@@ -64,13 +64,17 @@ impl AlertmanagerAlert {
     /// Get the alert name.
     #[must_use]
     pub fn name(&self) -> &str {
-        self.labels.get("alertname").map_or("unknown", String::as_str)
+        self.labels
+            .get("alertname")
+            .map_or("unknown", String::as_str)
     }
 
     /// Get the severity.
     #[must_use]
     pub fn severity(&self) -> &str {
-        self.labels.get("severity").map_or("unknown", String::as_str)
+        self.labels
+            .get("severity")
+            .map_or("unknown", String::as_str)
     }
 
     /// Get the component label.
@@ -357,14 +361,8 @@ mod tests {
 
     #[test]
     fn test_remediation_agent() {
-        assert_eq!(
-            PlatformIssueType::ComponentDown.remediation_agent(),
-            "bolt"
-        );
-        assert_eq!(
-            PlatformIssueType::CodeRunStuck.remediation_agent(),
-            "rex"
-        );
+        assert_eq!(PlatformIssueType::ComponentDown.remediation_agent(), "bolt");
+        assert_eq!(PlatformIssueType::CodeRunStuck.remediation_agent(), "rex");
     }
 
     #[test]
@@ -144,7 +144,8 @@ impl WorkflowRemediator {
         } else {
             // Try to find workflow pods from the alert
             let workflow_pattern = Self::extract_workflow_pattern(alert);
-            self.fetch_workflow_logs(namespace, &workflow_pattern).await?
+            self.fetch_workflow_logs(namespace, &workflow_pattern)
+                .await?
         };
 
         // Analyze logs to determine diagnosis
@@ -227,10 +228,13 @@ impl WorkflowRemediator {
         }
 
         if logs_lower.contains("test result: failed") || logs_lower.contains("test failed") {
-            return "Test failures in agent code. Check test output for specific failures.".to_string();
+            return "Test failures in agent code. Check test output for specific failures."
+                .to_string();
         }
 
-        if logs_lower.contains("git") && (logs_lower.contains("conflict") || logs_lower.contains("merge")) {
+        if logs_lower.contains("git")
+            && (logs_lower.contains("conflict") || logs_lower.contains("merge"))
+        {
             return "Git merge conflict. Agent needs to resolve conflicting changes.".to_string();
         }
 
@@ -239,11 +243,13 @@ impl WorkflowRemediator {
         }
 
         if logs_lower.contains("oom") || logs_lower.contains("out of memory") {
-            return "Out of memory error. Consider increasing resource limits for the workflow.".to_string();
+            return "Out of memory error. Consider increasing resource limits for the workflow."
+                .to_string();
         }
 
         if logs_lower.contains("permission denied") || logs_lower.contains("unauthorized") {
-            return "Permission/authentication error. Check GitHub App credentials and RBAC.".to_string();
+            return "Permission/authentication error. Check GitHub App credentials and RBAC."
+                .to_string();
         }
 
         if logs_lower.contains("docker") && logs_lower.contains("error") {
@@ -430,7 +436,8 @@ spec:
         let active = self.active.read().await;
         if let Some(tracked) = active.get(fingerprint) {
             let elapsed = Utc::now() - tracked.started_at;
-            #[allow(clippy::cast_possible_wrap)] // dedup_window_mins is a small config value, won't wrap
+            #[allow(clippy::cast_possible_wrap)]
+            // dedup_window_mins is a small config value, won't wrap
             if elapsed.num_minutes() < self.dedup_window_mins as i64 {
                 return true;
             }
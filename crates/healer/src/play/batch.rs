@@ -3,11 +3,12 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::collections::BTreeMap;
 
 use super::stage::Stage;
 use super::task::TaskState;
 use super::types::{BatchStatus, TaskStatus};
+use crate::clients::{ClusterClient, ConfigMapData};
 
 /// A batch of parallel tasks being executed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,71 +55,42 @@ impl PlayBatch {
     ///
     /// # Errors
     ///
-    /// Returns an error if kubectl fails or JSON parsing fails.
-    pub fn load_from_k8s(namespace: &str) -> Result<Self> {
+    /// Returns an error if the `ConfigMaps` cannot be listed.
+    pub async fn load_from_k8s(cluster: &dyn ClusterClient, namespace: &str) -> Result<Self> {
         // Get all play-task-* ConfigMaps
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "configmaps",
-                "-n",
-                namespace,
-                "-l",
-                "app.kubernetes.io/component=play-state",
-                "-o",
-                "json",
-            ])
-            .output()
-            .context("Failed to query ConfigMaps")?;
-
-        if !output.status.success() {
+        let config_maps = match cluster
+            .list_config_maps(namespace, Some("app.kubernetes.io/component=play-state"))
+            .await
+        {
+            Ok(config_maps) => config_maps,
             // Try alternative: get by name pattern
-            let output = Command::new("kubectl")
-                .args(["get", "configmaps", "-n", namespace, "-o", "json"])
-                .output()
-                .context("Failed to query ConfigMaps")?;
-
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                anyhow::bail!("kubectl get configmaps failed: {stderr}");
-            }
-
-            return Self::parse_configmaps(&output.stdout, namespace);
-        }
+            Err(_) => cluster
+                .list_config_maps(namespace, None)
+                .await
+                .context("Failed to query ConfigMaps")?,
+        };
 
-        Self::parse_configmaps(&output.stdout, namespace)
+        Ok(Self::from_configmaps(&config_maps, namespace))
     }
 
-    /// Parse `ConfigMap` JSON output into a batch.
-    fn parse_configmaps(json_output: &[u8], namespace: &str) -> Result<Self> {
-        let json_str = String::from_utf8_lossy(json_output);
-        let value: serde_json::Value =
-            serde_json::from_str(&json_str).context("Failed to parse ConfigMap JSON")?;
-
-        let empty_vec = Vec::new();
-        let items = value["items"].as_array().unwrap_or(&empty_vec);
-
+    /// Build a batch from `ConfigMaps` by name.
+    fn from_configmaps(config_maps: &BTreeMap<String, ConfigMapData>, namespace: &str) -> Self {
         let mut batch = Self::new("play", "unknown", namespace);
         let mut repository = String::new();
 
-        for item in items {
-            let name = item["metadata"]["name"].as_str().unwrap_or("");
-
+        for (name, config_map) in config_maps {
             // Filter to play-task-* ConfigMaps
-            if !name.starts_with("play-task-") {
+            let Some(task_id) = name.strip_prefix("play-task-") else {
                 continue;
-            }
-
-            // Extract task ID from name (play-task-{id})
-            let task_id = name.strip_prefix("play-task-").unwrap_or(name).to_string();
+            };
 
             // Parse data fields
-            let data = &item["data"];
-            let stage_str = data["stage"].as_str().unwrap_or("pending");
-            let status_str = data["status"].as_str().unwrap_or("in-progress");
+            let data = |key: &str| config_map.data.get(key).map(String::as_str);
+            let stage_str = data("stage").unwrap_or("pending");
+            let status_str = data("status").unwrap_or("in-progress");
 
             // Get repository from ConfigMap if available
-            if let Some(repo) = data["repository"].as_str() {
+            if let Some(repo) = data("repository") {
                 if repository.is_empty() {
                     repository = repo.to_string();
                 }
@@ -128,17 +100,14 @@ impl PlayBatch {
             let stage = Stage::from_configmap_value(stage_str).unwrap_or(Stage::Pending);
 
             // Create task state
-            let mut task = TaskState::new(&task_id);
+            let mut task = TaskState::new(task_id);
 
             match status_str {
                 "completed" | "done" => {
                     task.status = TaskStatus::Completed;
                 }
                 "failed" | "error" => {
-                    let reason = data["error"]
-                        .as_str()
-                        .unwrap_or("Unknown error")
-                        .to_string();
+                    let reason = data("error").unwrap_or("Unknown error").to_string();
                     task.status = TaskStatus::Failed {
                         stage,
                         reason,
@@ -147,8 +116,7 @@ impl PlayBatch {
                 }
                 _ => {
                     // Parse start time if available
-                    let started = data["last-updated"]
-                        .as_str()
+                    let started = data("last-updated")
                         .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
                         .map_or_else(Utc::now, |dt| dt.with_timezone(&Utc));
 
@@ -160,17 +128,17 @@ impl PlayBatch {
             }
 
             // Get PR number if available
-            if let Some(pr) = data["pr-number"].as_str() {
+            if let Some(pr) = data("pr-number") {
                 task.pr_number = pr.parse().ok();
             }
 
             // Get workflow name
-            if let Some(wf) = data["workflow-name"].as_str() {
+            if let Some(wf) = data("workflow-name") {
                 task.workflow_name = Some(wf.to_string());
             }
 
             // Get active CodeRun
-            if let Some(cr) = data["coderun-name"].as_str() {
+            if let Some(cr) = data("coderun-name") {
                 task.active_coderun = Some(cr.to_string());
             }
 
@@ -188,7 +156,7 @@ impl PlayBatch {
         // Update batch status
         batch.update_status();
 
-        Ok(batch)
+        batch
     }
 
     /// Update the batch status based on task states.
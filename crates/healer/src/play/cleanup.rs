@@ -1,12 +1,16 @@
 //! State cleanup for ephemeral play tracking.
 
-use anyhow::{Context, Result};
-use std::process::Command;
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::warn;
 
 use super::batch::PlayBatch;
+use crate::clients::ClusterClient;
 
 /// Clean up play state after a batch completes.
 pub struct PlayCleanup {
+    /// Kubernetes API client
+    cluster: Arc<dyn ClusterClient>,
     /// Namespace for cleanup
     namespace: String,
     /// Whether to force cleanup (even if tasks still running)
@@ -16,8 +20,9 @@ pub struct PlayCleanup {
 impl PlayCleanup {
     /// Create a new cleanup handler.
     #[must_use]
-    pub fn new(namespace: impl Into<String>) -> Self {
+    pub fn new(cluster: Arc<dyn ClusterClient>, namespace: impl Into<String>) -> Self {
         Self {
+            cluster,
             namespace: namespace.into(),
             force: false,
         }
@@ -52,8 +57,9 @@ impl PlayCleanup {
     ///
     /// # Errors
     ///
-    /// Returns an error if tasks are still running (without force) or kubectl fails.
-    pub fn cleanup(&self, batch: &PlayBatch) -> Result<CleanupReport> {
+    /// Returns an error if tasks are still running (without force) or the
+    /// `ConfigMaps` cannot be listed.
+    pub async fn cleanup(&self, batch: &PlayBatch) -> Result<CleanupReport> {
         let mut report = CleanupReport::default();
 
         if !self.can_cleanup(batch)? {
@@ -64,46 +70,27 @@ impl PlayCleanup {
         }
 
         // Delete play-task-* `ConfigMaps`
-        report.configmaps_deleted = self.delete_task_configmaps()?;
+        report.configmaps_deleted = self.delete_task_configmaps().await?;
 
         // Delete any remediation `CodeRuns`
-        report.coderuns_deleted = self.delete_remediation_coderuns();
+        report.coderuns_deleted = self.delete_remediation_coderuns().await;
 
         // Delete any play-related workflows
-        report.workflows_deleted = self.delete_play_workflows();
+        report.workflows_deleted = self.delete_play_workflows().await;
 
         Ok(report)
     }
 
     /// Delete play-task-* `ConfigMaps`.
-    fn delete_task_configmaps(&self) -> Result<usize> {
-        // First, list the ConfigMaps
-        let output = Command::new("kubectl")
-            .args(["get", "configmaps", "-n", &self.namespace, "-o", "name"])
-            .output()
-            .context("Failed to list ConfigMaps")?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let configmaps: Vec<&str> = stdout
-            .lines()
-            .filter(|l| l.contains("play-task-"))
-            .collect();
-
-        if configmaps.is_empty() {
-            return Ok(0);
-        }
+    async fn delete_task_configmaps(&self) -> Result<usize> {
+        let config_maps = self.cluster.list_config_maps(&self.namespace, None).await?;
 
-        // Delete each ConfigMap
         let mut deleted = 0;
-        for cm in &configmaps {
-            let name = cm.trim_start_matches("configmap/");
-            let status = Command::new("kubectl")
-                .args(["delete", "configmap", name, "-n", &self.namespace])
-                .status()
-                .context("Failed to delete ConfigMap")?;
-
-            if status.success() {
-                deleted += 1;
+        for name in config_maps.keys().filter(|n| n.contains("play-task-")) {
+            match self.cluster.delete_config_map(&self.namespace, name).await {
+                Ok(true) => deleted += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to delete ConfigMap {name}: {e:#}"),
             }
         }
 
@@ -111,43 +98,24 @@ impl PlayCleanup {
     }
 
     /// Delete healer remediation `CodeRuns`.
-    fn delete_remediation_coderuns(&self) -> usize {
-        // List healer-fix-* CodeRuns
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "coderuns",
-                "-n",
-                &self.namespace,
-                "-l",
-                "app.kubernetes.io/name=healer",
-                "-o",
-                "name",
-            ])
-            .output();
-
+    async fn delete_remediation_coderuns(&self) -> usize {
         // CodeRun CRD might not exist, that's okay
-        let output = match output {
-            Ok(o) if o.status.success() => o,
-            _ => return 0,
-        };
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let coderuns: Vec<&str> = stdout.lines().collect();
-
-        if coderuns.is_empty() {
+        let Ok(coderuns) = self
+            .cluster
+            .list_coderuns(&self.namespace, Some("app.kubernetes.io/name=healer"))
+            .await
+        else {
             return 0;
-        }
+        };
 
-        // Delete each CodeRun
         let mut deleted = 0;
-        for cr in &coderuns {
-            let name = cr.trim_start_matches("coderun.cto.5dlabs.io/");
-            let status = Command::new("kubectl")
-                .args(["delete", "coderun", name, "-n", &self.namespace])
-                .status();
-
-            if matches!(status, Ok(s) if s.success()) {
+        for coderun in &coderuns {
+            if matches!(
+                self.cluster
+                    .delete_coderun(&self.namespace, &coderun.name)
+                    .await,
+                Ok(true)
+            ) {
                 deleted += 1;
             }
         }
@@ -156,33 +124,17 @@ impl PlayCleanup {
     }
 
     /// Delete play-related workflows.
-    fn delete_play_workflows(&self) -> usize {
-        // List play-* workflows
-        let output = Command::new("kubectl")
-            .args(["get", "workflows", "-n", &self.namespace, "-o", "name"])
-            .output();
-
-        let output = match output {
-            Ok(o) if o.status.success() => o,
-            _ => return 0,
-        };
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let workflows: Vec<&str> = stdout.lines().filter(|l| l.contains("play-")).collect();
-
-        if workflows.is_empty() {
+    async fn delete_play_workflows(&self) -> usize {
+        let Ok(workflows) = self.cluster.list_workflows(&self.namespace, None).await else {
             return 0;
-        }
+        };
 
-        // Delete each workflow
         let mut deleted = 0;
-        for wf in &workflows {
-            let name = wf.trim_start_matches("workflow.argoproj.io/");
-            let status = Command::new("kubectl")
-                .args(["delete", "workflow", name, "-n", &self.namespace])
-                .status();
-
-            if matches!(status, Ok(s) if s.success()) {
+        for name in workflows.iter().filter(|n| n.contains("play-")) {
+            if matches!(
+                self.cluster.delete_workflow(&self.namespace, name).await,
+                Ok(true)
+            ) {
                 deleted += 1;
             }
         }
@@ -194,26 +146,12 @@ impl PlayCleanup {
     ///
     /// # Errors
     ///
-    /// Returns an error if kubectl fails.
-    pub fn cleanup_task(&self, task_id: &str) -> Result<()> {
+    /// Returns an error if the `ConfigMap` cannot be deleted.
+    pub async fn cleanup_task(&self, task_id: &str) -> Result<()> {
         let configmap_name = format!("play-task-{task_id}");
-
-        let status = Command::new("kubectl")
-            .args([
-                "delete",
-                "configmap",
-                &configmap_name,
-                "-n",
-                &self.namespace,
-                "--ignore-not-found",
-            ])
-            .status()
-            .context("Failed to delete task ConfigMap")?;
-
-        if !status.success() {
-            tracing::warn!("Failed to delete ConfigMap {}", configmap_name);
-        }
-
+        self.cluster
+            .delete_config_map(&self.namespace, &configmap_name)
+            .await?;
         Ok(())
     }
 }
@@ -252,3 +190,54 @@ impl std::fmt::Display for CleanupReport {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::FakeCluster;
+    use crate::k8s::CodeRun;
+
+    #[tokio::test]
+    async fn test_cleanup_deletes_play_state() {
+        let healer_run = |name: &str| CodeRun {
+            name: name.to_string(),
+            namespace: "cto".to_string(),
+            labels: [("app.kubernetes.io/name".to_string(), "healer".to_string())].into(),
+            ..CodeRun::default()
+        };
+        let cluster = Arc::new(
+            FakeCluster::new()
+                .with_config_map("cto", "play-task-1", &[], &[("stage", "done")])
+                .with_config_map("cto", "play-task-2", &[], &[("stage", "done")])
+                .with_config_map("cto", "controller-config", &[], &[])
+                .with_coderun(healer_run("healer-fix-a"))
+                .with_coderun(CodeRun {
+                    name: "rex-task-1".to_string(),
+                    namespace: "cto".to_string(),
+                    ..CodeRun::default()
+                })
+                .with_workflow("cto", "play-project-x")
+                .with_workflow("cto", "nightly-backup"),
+        );
+        let batch = PlayBatch::new("play", "5dlabs/cto", "cto");
+
+        let report = PlayCleanup::new(cluster.clone(), "cto")
+            .cleanup(&batch)
+            .await
+            .unwrap();
+
+        assert_eq!(report.configmaps_deleted, 2);
+        assert_eq!(report.coderuns_deleted, 1);
+        assert_eq!(report.workflows_deleted, 1);
+        assert_eq!(cluster.coderun_names("cto"), vec!["rex-task-1".to_string()]);
+        assert_eq!(
+            cluster.workflow_names("cto"),
+            vec!["nightly-backup".to_string()]
+        );
+        let remaining = cluster.list_config_maps("cto", None).await.unwrap();
+        assert_eq!(
+            remaining.keys().collect::<Vec<_>>(),
+            vec!["controller-config"]
+        );
+    }
+}
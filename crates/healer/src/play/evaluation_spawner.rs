@@ -7,13 +7,15 @@
 //!
 //! This implements the "Model 1: Evaluation Agent" from the dual-model Healer architecture.
 
-use anyhow::{Context as _, Result};
+use std::sync::Arc;
+
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::process::Command;
 use tracing::{debug, info, warn};
 
 use super::session::PlaySession;
+use crate::clients::ClusterClient;
 
 /// Configuration for the Evaluation Agent spawner.
 #[derive(Debug, Clone)]
@@ -60,29 +62,29 @@ pub struct EvaluationSpawnResult {
 /// Creates `CodeRuns` that evaluate Play sessions for issues.
 pub struct EvaluationSpawner {
     config: EvaluationSpawnerConfig,
+    cluster: Arc<dyn ClusterClient>,
 }
 
 impl EvaluationSpawner {
     /// Create a new Evaluation Agent spawner.
     #[must_use]
-    pub fn new(config: EvaluationSpawnerConfig) -> Self {
-        Self { config }
+    pub fn new(config: EvaluationSpawnerConfig, cluster: Arc<dyn ClusterClient>) -> Self {
+        Self { config, cluster }
     }
 
     /// Create spawner with default configuration.
     #[must_use]
-    pub fn with_defaults() -> Self {
-        Self::new(EvaluationSpawnerConfig::default())
+    pub fn with_defaults(cluster: Arc<dyn ClusterClient>) -> Self {
+        Self::new(EvaluationSpawnerConfig::default(), cluster)
     }
 
     /// Spawn an Evaluation Agent `CodeRun` for a Play session.
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - kubectl cannot be executed
-    /// - The `CodeRun` creation fails
-    pub fn spawn_evaluation(&self, session: &PlaySession) -> Result<EvaluationSpawnResult> {
+    /// Never fails today: a rejected `CodeRun` is reported through
+    /// [`EvaluationSpawnResult::error`].
+    pub async fn spawn_evaluation(&self, session: &PlaySession) -> Result<EvaluationSpawnResult> {
         let play_id = &session.play_id;
         let timestamp = chrono::Utc::now().timestamp();
         let coderun_name = format!("eval-{}-{}", sanitize_name(play_id), timestamp);
@@ -99,61 +101,43 @@ impl EvaluationSpawner {
         // Build `CodeRun` spec
         let coderun_spec = build_coderun_spec(&coderun_name, &self.config, &prompt, play_id);
 
-        // Apply the `CodeRun` using kubectl (use JSON format)
-        let coderun_json = serde_json::to_string_pretty(&coderun_spec)
-            .context("Failed to serialize CodeRun spec")?;
-
         debug!(
             coderun_name = %coderun_name,
-            json_length = %coderun_json.len(),
-            "Applying Evaluation CodeRun"
+            prompt_length = %prompt.len(),
+            "Creating Evaluation CodeRun"
         );
 
-        // Pipe the JSON spec to kubectl apply
-        let mut child = Command::new("kubectl")
-            .args(["apply", "-f", "-", "-n", &self.config.namespace])
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()
-            .context("Failed to spawn kubectl")?;
-
-        if let Some(stdin) = child.stdin.as_mut() {
-            use std::io::Write;
-            stdin
-                .write_all(coderun_json.as_bytes())
-                .context("Failed to write to kubectl stdin")?;
-        }
-
-        let output = child
-            .wait_with_output()
-            .context("kubectl execution failed")?;
-
-        if output.status.success() {
-            info!(
-                play_id = %play_id,
-                coderun_name = %coderun_name,
-                "Evaluation Agent CodeRun created successfully"
-            );
-            Ok(EvaluationSpawnResult {
-                coderun_name,
-                play_id: play_id.clone(),
-                success: true,
-                error: None,
-            })
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            warn!(
-                play_id = %play_id,
-                error = %stderr,
-                "Failed to create Evaluation Agent CodeRun"
-            );
-            Ok(EvaluationSpawnResult {
-                coderun_name,
-                play_id: play_id.clone(),
-                success: false,
-                error: Some(stderr.to_string()),
-            })
+        match self
+            .cluster
+            .create_coderun(&self.config.namespace, &coderun_spec)
+            .await
+        {
+            Ok(coderun_name) => {
+                info!(
+                    play_id = %play_id,
+                    coderun_name = %coderun_name,
+                    "Evaluation Agent CodeRun created successfully"
+                );
+                Ok(EvaluationSpawnResult {
+                    coderun_name,
+                    play_id: play_id.clone(),
+                    success: true,
+                    error: None,
+                })
+            }
+            Err(e) => {
+                warn!(
+                    play_id = %play_id,
+                    error = %e,
+                    "Failed to create Evaluation Agent CodeRun"
+                );
+                Ok(EvaluationSpawnResult {
+                    coderun_name,
+                    play_id: play_id.clone(),
+                    success: false,
+                    error: Some(format!("{e:#}")),
+                })
+            }
         }
     }
 }
@@ -304,6 +288,7 @@ fn sanitize_name(name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clients::FakeCluster;
     use crate::play::session::{AgentConfig, AgentTools, CtoConfig, TaskInfo};
     use chrono::Utc;
     use std::collections::HashMap;
@@ -377,4 +362,16 @@ mod tests {
         );
         assert_eq!(spec["spec"]["model"], "claude-sonnet-4-20250514");
     }
+
+    #[tokio::test]
+    async fn test_spawn_evaluation_creates_coderun() {
+        let cluster = Arc::new(FakeCluster::new());
+        let spawner = EvaluationSpawner::with_defaults(cluster.clone());
+
+        let result = spawner.spawn_evaluation(&sample_session()).await.unwrap();
+
+        assert!(result.success);
+        assert!(result.coderun_name.starts_with("eval-test-play-1-"));
+        assert_eq!(cluster.coderun_names("cto"), vec![result.coderun_name]);
+    }
 }
@@ -5,10 +5,17 @@
 //! finding that traditional metrics (ROUGE, embedding similarity) fail to
 //! capture functional compression quality.
 //!
+//! Answers come from an [`LlmClient`]: [`HttpLlmClient`] in production, or
+//! [`ReplayLlmClient`] to re-run recorded answers (see [`super::regression`]).
+//!
 //! Reference: Agent-Skills-for-Context-Engineering/skills/evaluation
 
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
@@ -70,28 +77,230 @@ struct ChatMessageResponse {
     content: String,
 }
 
+/// Answers probe questions.
+#[async_trait]
+pub trait LlmClient: Send + Sync {
+    /// Answer `probe`, given the full evaluation `prompt`.
+    async fn complete(&self, probe: &EvaluationProbe, prompt: &str) -> Result<String>;
+}
+
+/// OpenAI-compatible chat completions endpoint.
+pub struct HttpLlmClient {
+    endpoint: String,
+    model: String,
+    http_client: reqwest::Client,
+}
+
+impl HttpLlmClient {
+    /// Create a client for the endpoint and model in `config`.
+    #[must_use]
+    pub fn new(config: &EvaluatorConfig) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            endpoint: config.llm_endpoint.clone(),
+            model: config.model.clone(),
+            http_client,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for HttpLlmClient {
+    async fn complete(&self, _probe: &EvaluationProbe, prompt: &str) -> Result<String> {
+        let request = ChatRequest {
+            model: self.model.clone(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            max_tokens: 500,
+            temperature: 0.0, // Deterministic for evaluation
+        };
+
+        let response = self
+            .http_client
+            .post(&self.endpoint)
+            .json(&request)
+            .send()
+            .await
+            .context("Failed to send request to LLM endpoint")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            warn!(
+                status = %status,
+                body = %body,
+                "LLM request failed"
+            );
+            anyhow::bail!("LLM request failed: {status}");
+        }
+
+        let chat_response: ChatResponse = response
+            .json()
+            .await
+            .context("Failed to parse LLM response")?;
+
+        Ok(chat_response
+            .choices
+            .first()
+            .map(|c| c.message.content.clone())
+            .unwrap_or_default())
+    }
+}
+
+/// Hex SHA-256 of a rendered probe prompt, stored next to a recorded answer
+/// so a replay can tell when the prompt has changed since recording.
+#[must_use]
+pub fn prompt_digest(prompt: &str) -> String {
+    format!("{:x}", Sha256::digest(prompt.as_bytes()))
+}
+
+/// Replays recorded answers, keyed by probe question.
+///
+/// With [`Self::with_prompt_digests`], an answer recorded for a different
+/// prompt is still returned but the question is reported as stale.
+#[derive(Debug, Default)]
+pub struct ReplayLlmClient {
+    responses: BTreeMap<String, String>,
+    prompt_digests: Option<BTreeMap<String, String>>,
+    missing: Mutex<Vec<String>>,
+    stale: Mutex<Vec<String>>,
+}
+
+impl ReplayLlmClient {
+    #[must_use]
+    pub fn new(responses: BTreeMap<String, String>) -> Self {
+        Self {
+            responses,
+            ..Self::default()
+        }
+    }
+
+    /// Check each prompt against the digest recorded with its answer.
+    #[must_use]
+    pub fn with_prompt_digests(mut self, prompt_digests: BTreeMap<String, String>) -> Self {
+        self.prompt_digests = Some(prompt_digests);
+        self
+    }
+
+    /// Questions asked that had no recorded answer.
+    pub fn missing(&self) -> Vec<String> {
+        self.missing
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Questions whose answer was recorded for a different (or unknown)
+    /// prompt.
+    pub fn stale(&self) -> Vec<String> {
+        self.stale
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[async_trait]
+impl LlmClient for ReplayLlmClient {
+    async fn complete(&self, probe: &EvaluationProbe, prompt: &str) -> Result<String> {
+        if let Some(answer) = self.responses.get(&probe.question) {
+            if let Some(digests) = &self.prompt_digests {
+                if digests.get(&probe.question) != Some(&prompt_digest(prompt)) {
+                    self.stale
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner)
+                        .push(probe.question.clone());
+                }
+            }
+            return Ok(answer.clone());
+        }
+        self.missing
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(probe.question.clone());
+        anyhow::bail!("No recorded answer for {:?}", probe.question)
+    }
+}
+
+/// Records the answers of another client so they can be replayed.
+pub struct RecordingLlmClient {
+    inner: Arc<dyn LlmClient>,
+    recorded: Mutex<BTreeMap<String, String>>,
+    prompt_digests: Mutex<BTreeMap<String, String>>,
+}
+
+impl RecordingLlmClient {
+    #[must_use]
+    pub fn new(inner: Arc<dyn LlmClient>) -> Self {
+        Self {
+            inner,
+            recorded: Mutex::new(BTreeMap::new()),
+            prompt_digests: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Answers recorded so far, keyed by probe question.
+    pub fn recorded(&self) -> BTreeMap<String, String> {
+        self.recorded
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// [`prompt_digest`] of the prompt each answer was recorded for, keyed
+    /// by probe question.
+    pub fn prompt_digests(&self) -> BTreeMap<String, String> {
+        self.prompt_digests
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[async_trait]
+impl LlmClient for RecordingLlmClient {
+    async fn complete(&self, probe: &EvaluationProbe, prompt: &str) -> Result<String> {
+        let answer = self.inner.complete(probe, prompt).await?;
+        self.recorded
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(probe.question.clone(), answer.clone());
+        self.prompt_digests
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(probe.question.clone(), prompt_digest(prompt));
+        Ok(answer)
+    }
+}
+
 /// LLM-powered probe evaluator for context engineering quality assessment.
 ///
 /// Sends probe questions to an LLM and scores the responses against expected
 /// keywords to measure how well an agent retained critical information.
 pub struct ProbeEvaluator {
     config: EvaluatorConfig,
-    http_client: reqwest::Client,
+    llm: Arc<dyn LlmClient>,
 }
 
 impl ProbeEvaluator {
     /// Create a new probe evaluator with the given configuration.
     #[must_use]
     pub fn new(config: EvaluatorConfig) -> Self {
-        let http_client = reqwest::Client::builder()
-            .timeout(config.timeout)
-            .build()
-            .unwrap_or_default();
+        let llm = Arc::new(HttpLlmClient::new(&config));
+        Self { config, llm }
+    }
 
-        Self {
-            config,
-            http_client,
-        }
+    /// Answer probes with `llm` instead of the configured endpoint.
+    #[must_use]
+    pub fn with_llm_client(mut self, llm: Arc<dyn LlmClient>) -> Self {
+        self.llm = llm;
+        self
     }
 
     /// Create a probe evaluator with default configuration.
@@ -166,10 +375,8 @@ Answer the question based ONLY on the context provided above. Be specific and in
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - The HTTP request to the LLM endpoint fails
-    /// - The LLM response cannot be parsed as JSON
-    /// - The response format is unexpected
+    /// Returns an error if the LLM client fails to answer (for HTTP: the
+    /// request fails, returns an error status or cannot be parsed).
     pub async fn evaluate_probe(
         &self,
         probe: &EvaluationProbe,
@@ -183,51 +390,7 @@ Answer the question based ONLY on the context provided above. Be specific and in
             "Evaluating probe"
         );
 
-        let request = ChatRequest {
-            model: self.config.model.clone(),
-            messages: vec![ChatMessage {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-            max_tokens: 500,
-            temperature: 0.0, // Deterministic for evaluation
-        };
-
-        let response = self
-            .http_client
-            .post(&self.config.llm_endpoint)
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send request to LLM endpoint")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            warn!(
-                status = %status,
-                body = %body,
-                "LLM request failed"
-            );
-            return Ok(ProbeResult {
-                probe: probe.clone(),
-                response: String::new(),
-                score: 0.0,
-                passed: false,
-                notes: Some(format!("LLM request failed: {status}")),
-            });
-        }
-
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .context("Failed to parse LLM response")?;
-
-        let answer = chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .unwrap_or_default();
+        let answer = self.llm.complete(probe, &prompt).await?;
 
         // Score the response against expected keywords
         let score = probe.score_response(&answer);
@@ -16,9 +16,10 @@ use anyhow::Result;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::{debug, info, warn};
 
-use crate::github::GitHubClient;
+use crate::clients::GitHubClient;
 
 use super::types::{EvaluationResults, ProbeResult, ProbeType};
 
@@ -124,7 +125,9 @@ pub struct FeedbackResult {
 /// Engine for generating feedback from evaluation results.
 pub struct FeedbackEngine {
     config: FeedbackConfig,
-    github: Option<GitHubClient>,
+    github: Option<Arc<dyn GitHubClient>>,
+    /// `owner/repo` that feedback issues are filed against
+    repository: Option<String>,
     /// Failure history by `play_id`
     history: HashMap<String, FailureHistory>,
 }
@@ -133,22 +136,29 @@ impl FeedbackEngine {
     /// Create a new feedback engine.
     #[must_use]
     pub fn new(config: FeedbackConfig) -> Self {
-        let github = config.repository.as_ref().map(|repo| {
-            let parts: Vec<&str> = repo.split('/').collect();
-            if parts.len() == 2 {
-                GitHubClient::new(parts[0], parts[1])
+        let repository = config.repository.as_ref().map(|repo| {
+            if repo.split('/').count() == 2 {
+                repo.clone()
             } else {
-                GitHubClient::new("5dlabs", repo)
+                format!("5dlabs/{repo}")
             }
         });
 
         Self {
             config,
-            github,
+            github: None,
+            repository,
             history: HashMap::new(),
         }
     }
 
+    /// File feedback issues through this client when a repository is configured.
+    #[must_use]
+    pub fn with_github(mut self, github: Arc<dyn GitHubClient>) -> Self {
+        self.github = Some(github);
+        self
+    }
+
     /// Create with default configuration.
     #[must_use]
     pub fn with_defaults() -> Self {
@@ -160,7 +170,7 @@ impl FeedbackEngine {
     /// # Errors
     ///
     /// Returns an error if serialization of the feedback result fails.
-    pub fn process_results(
+    pub async fn process_results(
         &mut self,
         play_id: &str,
         results: &EvaluationResults,
@@ -201,7 +211,8 @@ impl FeedbackEngine {
             && self.check_should_create_issue(&history_snapshot, &failed_probes);
 
         let issue_url = if should_create_issue {
-            self.create_feedback_issue(play_id, results, &suggestions)?
+            self.create_feedback_issue(play_id, results, &suggestions)
+                .await?
         } else {
             None
         };
@@ -383,13 +394,14 @@ Run verification: [specific command or check]"#
     }
 
     /// Create a GitHub issue for feedback.
-    fn create_feedback_issue(
+    async fn create_feedback_issue(
         &mut self,
         play_id: &str,
         results: &EvaluationResults,
         suggestions: &[PromptSuggestion],
     ) -> Result<Option<String>> {
-        let Some(github) = &self.github else {
+        let (Some(github), Some(repository)) = (self.github.clone(), self.repository.clone())
+        else {
             debug!("GitHub client not configured, skipping issue creation");
             return Ok(None);
         };
@@ -455,15 +467,12 @@ Run verification: [specific command or check]"#
 "#,
         );
 
-        let labels: Vec<&str> = self
-            .config
-            .issue_labels
-            .iter()
-            .map(String::as_str)
-            .collect();
-
-        match github.create_issue(&title, &body, &labels) {
-            Ok(url) => {
+        match github
+            .create_issue(&repository, &title, &body, &self.config.issue_labels)
+            .await
+        {
+            Ok(number) => {
+                let url = format!("https://github.com/{repository}/issues/{number}");
                 info!(issue = %url, play_id = %play_id, "Created feedback issue");
 
                 // Record that we created this issue
@@ -8,6 +8,7 @@
 //! - Monitor running plays with real-time log analysis
 //! - Detect anomalies based on expected agent behaviors
 //! - Probe-based evaluation for context engineering quality (LLM-powered)
+//! - Regression harness gating evaluator changes on verdict accuracy
 //! - HTTP API for MCP server integration (session start notification)
 
 pub mod api;
@@ -20,6 +21,7 @@ pub mod feedback;
 pub mod insights;
 pub mod monitor;
 pub mod orchestrator;
+pub mod regression;
 pub mod remediate;
 pub mod remediation_spawner;
 pub mod session;
@@ -33,13 +35,17 @@ pub use api::{build_play_api_router, run_play_api_server, PlayApiState};
 pub use batch::PlayBatch;
 pub use behavior::{AgentType, BehaviorAnalyzer, DetectionType, LogAnalysis};
 pub use evaluation_spawner::{EvaluationSpawnResult, EvaluationSpawner, EvaluationSpawnerConfig};
-pub use evaluator::{EvaluatorConfig, ProbeEvaluator};
+pub use evaluator::{
+    prompt_digest, EvaluatorConfig, HttpLlmClient, LlmClient, ProbeEvaluator, RecordingLlmClient,
+    ReplayLlmClient,
+};
 pub use feedback::{FeedbackConfig, FeedbackEngine, FeedbackResult, PromptSuggestion};
 pub use monitor::{MonitorConfig, MonitorEvent, MonitorStatus, PlayMonitor};
 pub use orchestrator::{
     verify_language_match, FeedbackLoopResult, HealerOrchestrator, ImplementationLanguage,
     LanguageMatchResult, OrchestratorConfig,
 };
+pub use regression::{RegressionCase, RegressionReport};
 pub use remediation_spawner::{
     RemediationSpawnResult, RemediationSpawner, RemediationSpawnerConfig, RemediationStrategy,
 };
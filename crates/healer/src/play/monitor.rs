@@ -11,11 +11,11 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::process::Command;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
-use crate::github::GitHubClient;
+use crate::clients::{ClusterClient, GitHubClient};
 use crate::loki::{LokiClient, LokiConfig};
 
 use super::behavior::{AgentType, BehaviorAnalyzer, DetectionType, LogAnalysis};
@@ -160,12 +160,27 @@ pub enum MonitorEventType {
     Error,
 }
 
+/// Whether `severity` is at least as severe as `min_severity`.
+pub(crate) fn meets_severity(severity: &str, min_severity: &str) -> bool {
+    let rank = |s: &str| match s {
+        "critical" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        _ => 4,
+    };
+    rank(severity) <= rank(min_severity)
+}
+
 /// Play monitor for real-time log analysis
 pub struct PlayMonitor {
     config: MonitorConfig,
     loki: LokiClient,
     analyzer: BehaviorAnalyzer,
-    github: Option<GitHubClient>,
+    cluster: Arc<dyn ClusterClient>,
+    github: Option<Arc<dyn GitHubClient>>,
+    /// `owner/repo` that anomaly issues are filed against
+    repository: Option<String>,
     /// Probe-based evaluator for context engineering quality assessment
     evaluator: ProbeEvaluator,
     /// Feedback engine for prompt improvement suggestions
@@ -181,14 +196,13 @@ pub struct PlayMonitor {
 impl PlayMonitor {
     /// Create a new play monitor
     #[must_use]
-    pub fn new(config: MonitorConfig) -> Self {
+    pub fn new(config: MonitorConfig, cluster: Arc<dyn ClusterClient>) -> Self {
         let loki = LokiClient::new(LokiConfig::default());
-        let github = config.repository.as_ref().map(|repo| {
-            let parts: Vec<&str> = repo.split('/').collect();
-            if parts.len() == 2 {
-                GitHubClient::new(parts[0], parts[1])
+        let repository = config.repository.as_ref().map(|repo| {
+            if repo.split('/').count() == 2 {
+                repo.clone()
             } else {
-                GitHubClient::new("5dlabs", repo)
+                format!("5dlabs/{repo}")
             }
         });
         let evaluator = ProbeEvaluator::new(config.evaluator_config.clone());
@@ -198,7 +212,9 @@ impl PlayMonitor {
             config,
             loki,
             analyzer: BehaviorAnalyzer::new(),
-            github,
+            cluster,
+            github: None,
+            repository,
             evaluator,
             feedback,
             plays: HashMap::new(),
@@ -207,6 +223,14 @@ impl PlayMonitor {
         }
     }
 
+    /// File anomaly issues through this client when a repository is configured.
+    #[must_use]
+    pub fn with_github(mut self, github: Arc<dyn GitHubClient>) -> Self {
+        self.feedback = self.feedback.with_github(github.clone());
+        self.github = Some(github);
+        self
+    }
+
     /// Set the event sender for emitting monitor events
     pub fn set_event_sender(&mut self, tx: mpsc::Sender<MonitorEvent>) {
         self.event_tx = Some(tx);
@@ -246,10 +270,10 @@ impl PlayMonitor {
     /// Poll once for active plays and check logs
     ///
     /// # Errors
-    /// Returns an error if kubectl or Loki queries fail.
+    /// Returns an error if the `CodeRun` listing or Loki queries fail.
     pub async fn poll_once(&mut self) -> Result<()> {
         // 1. Discover active CodeRuns
-        let active_coderuns = self.discover_active_coderuns()?;
+        let active_coderuns = self.discover_active_coderuns().await?;
 
         // 2. Group by play_id (task-id label)
         let mut plays_by_id: HashMap<String, Vec<ActiveCodeRun>> = HashMap::new();
@@ -314,61 +338,30 @@ impl PlayMonitor {
     }
 
     /// Discover active `CodeRuns` in the namespace
-    fn discover_active_coderuns(&self) -> Result<Vec<ActiveCodeRun>> {
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "coderuns",
-                "-n",
-                &self.config.namespace,
-                "-o",
-                "json",
-            ])
-            .output()
-            .context("Failed to run kubectl get coderuns")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("kubectl failed: {stderr}");
-        }
-
-        let json: serde_json::Value =
-            serde_json::from_slice(&output.stdout).context("Failed to parse kubectl output")?;
-
-        let mut coderuns = Vec::new();
-
-        if let Some(items) = json["items"].as_array() {
-            for item in items {
-                let name = item["metadata"]["name"].as_str().unwrap_or_default();
-                let phase = item["status"]["phase"].as_str().unwrap_or("Unknown");
-
-                // Only track Running or Pending CodeRuns
-                if phase != "Running" && phase != "Pending" {
-                    continue;
-                }
-
-                let labels = item["metadata"]["labels"].as_object();
-                let agent_str = labels
-                    .and_then(|l| l.get("agents.platform/agent").or(l.get("healer/agent")))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown");
-
-                let pod_name = item["status"]["podName"].as_str().map(String::from);
-
-                let started_at = item["status"]["startedAt"]
-                    .as_str()
-                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&Utc));
-
-                coderuns.push(ActiveCodeRun {
-                    name: name.to_string(),
+    async fn discover_active_coderuns(&self) -> Result<Vec<ActiveCodeRun>> {
+        let coderuns: Vec<ActiveCodeRun> = self
+            .cluster
+            .list_coderuns(&self.config.namespace, None)
+            .await
+            .context("Failed to list CodeRuns")?
+            .into_iter()
+            // Only track Running or Pending CodeRuns
+            .filter(|run| run.phase == "Running" || run.phase == "Pending")
+            .map(|run| {
+                let agent_str = run
+                    .labels
+                    .get("agents.platform/agent")
+                    .or_else(|| run.labels.get("healer/agent"))
+                    .map_or("unknown", String::as_str);
+                ActiveCodeRun {
                     agent: agent_str.parse().unwrap_or(AgentType::Unknown),
-                    pod_name,
-                    phase: phase.to_string(),
-                    started_at,
-                });
-            }
-        }
+                    name: run.name,
+                    pod_name: run.pod_name,
+                    phase: run.phase,
+                    started_at: run.started_at,
+                }
+            })
+            .collect();
 
         debug!(count = %coderuns.len(), "Discovered active CodeRuns");
         Ok(coderuns)
@@ -439,7 +432,7 @@ impl PlayMonitor {
 
         // If Loki didn't return logs, try kubectl
         let log_lines: Vec<String> = if entries.is_empty() {
-            self.get_kubectl_logs(pod_name, 100).unwrap_or_default()
+            self.get_pod_logs(pod_name, 100).await.unwrap_or_default()
         } else {
             entries.iter().map(|e| e.line.clone()).collect()
         };
@@ -476,27 +469,13 @@ impl PlayMonitor {
         Ok(())
     }
 
-    /// Get logs via kubectl as fallback
-    fn get_kubectl_logs(&self, pod_name: &str, tail: u32) -> Result<Vec<String>> {
-        let output = Command::new("kubectl")
-            .args([
-                "logs",
-                pod_name,
-                "-n",
-                &self.config.namespace,
-                "--tail",
-                &tail.to_string(),
-            ])
-            .output()
-            .context("Failed to run kubectl logs")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("kubectl logs failed: {stderr}");
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        Ok(stdout.lines().map(String::from).collect())
+    /// Get logs from the API server as fallback
+    async fn get_pod_logs(&self, pod_name: &str, tail: i64) -> Result<Vec<String>> {
+        let logs = self
+            .cluster
+            .pod_logs(&self.config.namespace, pod_name, tail)
+            .await?;
+        Ok(logs.lines().map(String::from).collect())
     }
 
     /// Handle a detected anomaly
@@ -507,15 +486,7 @@ impl PlayMonitor {
         analysis: LogAnalysis,
     ) -> Result<()> {
         // Check severity threshold
-        let severity_order = |s: &str| match s {
-            "critical" => 0,
-            "high" => 1,
-            "medium" => 2,
-            "low" => 3,
-            _ => 4,
-        };
-
-        if severity_order(&analysis.severity) > severity_order(&self.config.min_severity) {
+        if !meets_severity(&analysis.severity, &self.config.min_severity) {
             return Ok(());
         }
 
@@ -589,7 +560,7 @@ impl PlayMonitor {
         coderun_name: &str,
         analysis: &LogAnalysis,
     ) -> Result<()> {
-        let Some(github) = &self.github else {
+        let (Some(github), Some(repository)) = (&self.github, &self.repository) else {
             debug!("GitHub client not configured, skipping issue creation");
             return Ok(());
         };
@@ -641,8 +612,13 @@ impl PlayMonitor {
             detected_at = Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
         );
 
-        match github.create_issue(&title, &body, &["heal", "monitor", &analysis.severity]) {
-            Ok(issue_url) => {
+        let labels = ["heal", "monitor", analysis.severity.as_str()].map(String::from);
+        match github
+            .create_issue(repository, &title, &body, &labels)
+            .await
+        {
+            Ok(number) => {
+                let issue_url = format!("https://github.com/{repository}/issues/{number}");
                 info!(issue = %issue_url, "Created GitHub issue for anomaly");
 
                 if let Some(play) = self.plays.get_mut(play_id) {
@@ -744,26 +720,24 @@ impl PlayMonitor {
     /// Load artifact trail from a `CodeRun`'s workspace.
     ///
     /// The artifact trail is persisted by the sidecar to `/workspace/artifact-trail.json`.
-    fn load_artifact_trail(&self, pod_name: &str) -> Option<ArtifactTrail> {
-        let output = Command::new("kubectl")
-            .args([
-                "exec",
-                pod_name,
-                "-n",
+    async fn load_artifact_trail(&self, pod_name: &str) -> Option<ArtifactTrail> {
+        let output = match self
+            .cluster
+            .exec(
                 &self.config.namespace,
-                "--",
-                "cat",
-                "/workspace/artifact-trail.json",
-            ])
-            .output()
-            .ok()?;
-
-        if !output.status.success() {
-            debug!(pod = %pod_name, "No artifact trail found");
-            return None;
-        }
+                pod_name,
+                &["cat", "/workspace/artifact-trail.json"],
+            )
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                debug!(pod = %pod_name, error = %e, "No artifact trail found");
+                return None;
+            }
+        };
 
-        serde_json::from_slice(&output.stdout).ok()
+        serde_json::from_str(&output).ok()
     }
 
     /// Generate artifact probes from the artifact trail.
@@ -908,7 +882,7 @@ impl PlayMonitor {
     /// Uses the artifact trail and anomaly history to create targeted probes
     /// that test whether the agent retained critical information.
     #[must_use]
-    pub fn generate_probes(&self, play: &MonitoredPlay) -> Vec<EvaluationProbe> {
+    pub fn generate_probes(play: &MonitoredPlay) -> Vec<EvaluationProbe> {
         let mut probes = Vec::new();
 
         // Artifact and decision probes from trail
@@ -965,7 +939,7 @@ impl PlayMonitor {
             // Try to load from the first running pod
             let trail = if let Some(coderun) = play.active_coderuns.first() {
                 if let Some(pod) = &coderun.pod_name {
-                    self.load_artifact_trail(pod)
+                    self.load_artifact_trail(pod).await
                 } else {
                     None
                 }
@@ -989,7 +963,7 @@ impl PlayMonitor {
             artifact_trail: Some(trail.clone()),
             ..play.clone()
         };
-        let probes = self.generate_probes(&play_with_trail);
+        let probes = Self::generate_probes(&play_with_trail);
 
         info!(
             play_id = %play_id,
@@ -1008,7 +982,7 @@ impl PlayMonitor {
                         "LLM evaluation failed, falling back to offline mode"
                     );
                     // Regenerate probes since we consumed them
-                    let probes = self.generate_probes(&play_with_trail);
+                    let probes = Self::generate_probes(&play_with_trail);
                     self.evaluator.evaluate_offline(probes, &trail)
                 }
             }
@@ -1023,7 +997,7 @@ impl PlayMonitor {
 
         // Process feedback if enabled
         let feedback_result = if self.config.enable_feedback && !results.passed {
-            match self.feedback.process_results(play_id, &results) {
+            match self.feedback.process_results(play_id, &results).await {
                 Ok(Some(feedback)) => {
                     info!(
                         play_id = %play_id,
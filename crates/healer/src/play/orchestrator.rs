@@ -7,11 +7,14 @@
 //!
 //! This implements the "Feedback Loop" from the Healer architecture.
 
-use anyhow::Result;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
 use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, warn};
 
+use crate::clients::{ClusterClient, GitHubClient};
 use crate::loki::{LogEntry, LokiClient, LokiConfig};
 use crate::scanner::{is_actual_error, is_false_positive};
 
@@ -100,24 +103,29 @@ pub struct HealerOrchestrator {
     evaluation_spawner: EvaluationSpawner,
     remediation_spawner: RemediationSpawner,
     loki: LokiClient,
+    github: Option<Arc<dyn GitHubClient>>,
 }
 
 impl HealerOrchestrator {
     /// Create a new orchestrator.
     #[must_use]
-    pub fn new(config: OrchestratorConfig, session_store: SessionStoreHandle) -> Self {
+    pub fn new(
+        config: OrchestratorConfig,
+        session_store: SessionStoreHandle,
+        cluster: Arc<dyn ClusterClient>,
+    ) -> Self {
         let eval_config = EvaluationSpawnerConfig {
             namespace: config.namespace.clone(),
             ..EvaluationSpawnerConfig::default()
         };
-        let evaluation_spawner = EvaluationSpawner::new(eval_config);
+        let evaluation_spawner = EvaluationSpawner::new(eval_config, cluster.clone());
 
         let remediation_config = RemediationSpawnerConfig {
             namespace: config.namespace.clone(),
             max_retries: config.max_remediation_attempts,
             ..RemediationSpawnerConfig::default()
         };
-        let remediation_spawner = RemediationSpawner::new(remediation_config);
+        let remediation_spawner = RemediationSpawner::new(remediation_config, cluster);
 
         let loki = LokiClient::new(config.loki_config.clone());
 
@@ -127,9 +135,17 @@ impl HealerOrchestrator {
             evaluation_spawner,
             remediation_spawner,
             loki,
+            github: None,
         }
     }
 
+    /// Open GitHub issues for escalations through this client.
+    #[must_use]
+    pub fn with_github(mut self, github: Arc<dyn GitHubClient>) -> Self {
+        self.github = Some(github);
+        self
+    }
+
     /// Run the feedback loop for all active sessions.
     pub async fn run_feedback_loop(&self) -> Vec<FeedbackLoopResult> {
         let sessions = self.session_store.get_active_sessions().await;
@@ -216,13 +232,14 @@ impl HealerOrchestrator {
             if strategy == RemediationStrategy::Escalate {
                 // Don't spawn a CodeRun, escalate to humans
                 result.issues_escalated += 1;
-                Self::escalate_issue(session, issue);
+                self.escalate_issue(session, issue).await;
             } else {
                 // Spawn a remediation CodeRun
                 let attempt = Self::get_remediation_attempt_count(session, issue);
                 match self
                     .remediation_spawner
                     .spawn_remediation(session, issue, attempt)
+                    .await
                 {
                     Ok(spawn_result) => {
                         if spawn_result.success {
@@ -238,7 +255,7 @@ impl HealerOrchestrator {
                             .is_some_and(|e| e.contains("Max retries") || e.contains("exceeded"))
                         {
                             result.issues_escalated += 1;
-                            Self::escalate_issue(session, issue);
+                            self.escalate_issue(session, issue).await;
                         } else {
                             result.errors.push(
                                 spawn_result
@@ -473,7 +490,7 @@ impl HealerOrchestrator {
     /// This will:
     /// 1. Create a GitHub issue for tracking
     /// 2. Send Discord notification via the notify crate
-    fn escalate_issue(session: &PlaySession, issue: &SessionIssue) {
+    async fn escalate_issue(&self, session: &PlaySession, issue: &SessionIssue) {
         warn!(
             play_id = %session.play_id,
             issue_type = ?issue.issue_type,
@@ -482,7 +499,7 @@ impl HealerOrchestrator {
         );
 
         // Create GitHub issue for tracking
-        if let Err(e) = Self::create_github_issue_for_play(session, issue) {
+        if let Err(e) = self.create_github_issue_for_play(session, issue).await {
             warn!(
                 play_id = %session.play_id,
                 error = %e,
@@ -532,12 +549,16 @@ impl HealerOrchestrator {
     }
 
     /// Create a GitHub issue for a Play escalation.
-    fn create_github_issue_for_play(
+    async fn create_github_issue_for_play(
+        &self,
         session: &PlaySession,
         issue: &SessionIssue,
-    ) -> std::result::Result<(), String> {
+    ) -> Result<()> {
         use std::fmt::Write as _;
-        use std::process::Command;
+
+        let Some(github) = &self.github else {
+            anyhow::bail!("GitHub client not configured");
+        };
 
         let title = format!(
             "[Healer] Play {} - {:?} Escalation",
@@ -616,33 +637,15 @@ impl HealerOrchestrator {
             }
             _ => "healer,play-workflow,needs-attention",
         };
+        let labels: Vec<String> = labels.split(',').map(str::to_string).collect();
 
-        // Create the issue using gh CLI
-        let output = Command::new("gh")
-            .args([
-                "issue",
-                "create",
-                "--repo",
-                &session.repository,
-                "--title",
-                &title,
-                "--body",
-                &body,
-                "--label",
-                labels,
-            ])
-            .output()
-            .map_err(|e| format!("Failed to execute gh issue create: {e}"))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("gh issue create failed: {stderr}"));
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let number = github
+            .create_issue(&session.repository, &title, &body, &labels)
+            .await
+            .context("Failed to create escalation issue")?;
         info!(
-            "Created GitHub issue for Play escalation: {}",
-            stdout.trim()
+            "Created GitHub issue for Play escalation: https://github.com/{}/issues/{number}",
+            session.repository
         );
 
         Ok(())
@@ -661,13 +664,13 @@ impl HealerOrchestrator {
     /// # Errors
     ///
     /// Returns an error if the evaluation cannot be spawned.
-    pub fn run_evaluation(&self, session: &PlaySession) -> Result<EvaluationSpawnResult> {
+    pub async fn run_evaluation(&self, session: &PlaySession) -> Result<EvaluationSpawnResult> {
         info!(
             play_id = %session.play_id,
             "Running evaluation for session"
         );
 
-        self.evaluation_spawner.spawn_evaluation(session)
+        self.evaluation_spawner.spawn_evaluation(session).await
     }
 
     /// Check if a session should be re-evaluated after remediation.
@@ -0,0 +1,519 @@
+//! Regression harness for probe evaluation verdicts.
+//!
+//! A dataset is a directory of JSON cases. Each case holds a recorded play
+//! (agent and artifact trail), its logs, the LLM answer recorded for each
+//! probe question and the verdicts a human expects. [`run_dataset`] replays
+//! every case through the monitor's probe generation and the
+//! [`ProbeEvaluator`] with a [`ReplayLlmClient`], then reports precision and
+//! recall per probe type, so prompt, probe and scoring changes can be gated
+//! on verdict accuracy. Each answer is stored with a digest of the prompt it
+//! was recorded for; a replay whose prompt no longer matches is reported as
+//! stale and fails the check. [`record_case`] refreshes the recorded answers
+//! from a live endpoint after a prompt change.
+//!
+//! A failing verdict is the positive class: precision is the share of
+//! flagged probes a human also flagged, recall the share of real problems
+//! the evaluator caught.
+
+use anyhow::{Context as _, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::behavior::{AgentType, BehaviorAnalyzer, DetectionType};
+use super::evaluator::{
+    EvaluatorConfig, LlmClient, ProbeEvaluator, RecordingLlmClient, ReplayLlmClient,
+};
+use super::monitor::{meets_severity, DetectedAnomaly, MonitorConfig, MonitoredPlay, PlayMonitor};
+use super::types::{ArtifactTrail, EvaluationProbe, EvaluationResults, ProbeType};
+
+/// A labelled evaluation case.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionCase {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub play: RecordedPlay,
+    /// Agent log lines, analyzed for anomalies like the monitor does
+    #[serde(default)]
+    pub logs: Vec<String>,
+    /// Recorded LLM answers by probe question
+    #[serde(default)]
+    pub responses: BTreeMap<String, String>,
+    /// Digest of the prompt each answer was recorded for, by probe question
+    #[serde(default)]
+    pub prompt_digests: BTreeMap<String, String>,
+    pub expected: ExpectedVerdict,
+}
+
+/// The play state a case was recorded from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedPlay {
+    pub play_id: String,
+    #[serde(default = "default_agent")]
+    pub agent: AgentType,
+    #[serde(default)]
+    pub artifact_trail: ArtifactTrail,
+}
+
+fn default_agent() -> AgentType {
+    AgentType::Unknown
+}
+
+/// Verdicts a human expects for a case.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpectedVerdict {
+    /// Whether the stage is healthy overall
+    pub passed: bool,
+    /// Whether each probe type passes; unlisted types are not scored
+    #[serde(default)]
+    pub probes: BTreeMap<ProbeType, bool>,
+}
+
+impl RegressionCase {
+    /// The monitored play this case stands for, with anomalies detected
+    /// from its logs.
+    pub fn monitored_play(&self) -> MonitoredPlay {
+        let analyzer = BehaviorAnalyzer::new();
+        let min_severity = MonitorConfig::default().min_severity;
+        let anomalies = analyzer
+            .analyze_logs(&self.logs, self.play.agent)
+            .into_iter()
+            .filter(|a| {
+                matches!(
+                    a.detection_type,
+                    DetectionType::Failure | DetectionType::Anomaly
+                ) && meets_severity(&a.severity, &min_severity)
+            })
+            .map(|analysis| DetectedAnomaly {
+                detected_at: analysis.timestamp.unwrap_or_else(Utc::now),
+                fingerprint: format!("{}:{}", self.play.play_id, analysis.matched_pattern),
+                coderun_name: self.play.play_id.clone(),
+                issue_created: None,
+                analysis,
+            })
+            .collect();
+
+        MonitoredPlay {
+            play_id: self.play.play_id.clone(),
+            service: None,
+            started_at: Utc::now(),
+            active_coderuns: Vec::new(),
+            issues_created: Vec::new(),
+            last_log_check: None,
+            anomalies,
+            evaluation_results: None,
+            artifact_trail: Some(self.play.artifact_trail.clone()),
+        }
+    }
+
+    /// Probes the monitor would ask for this case.
+    pub fn probes(&self) -> Vec<EvaluationProbe> {
+        PlayMonitor::generate_probes(&self.monitored_play())
+    }
+}
+
+/// Load every `*.json` case in `dir`, sorted by file name.
+pub fn load_dataset(dir: &Path) -> Result<Vec<(PathBuf, RegressionCase)>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read dataset {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let case = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid regression case {}", path.display()))?;
+            Ok((path, case))
+        })
+        .collect()
+}
+
+/// Evaluate `case`, answering probes with `llm`.
+pub async fn evaluate_case(
+    case: &RegressionCase,
+    config: &EvaluatorConfig,
+    llm: Arc<dyn LlmClient>,
+) -> Result<EvaluationResults> {
+    ProbeEvaluator::new(config.clone())
+        .with_llm_client(llm)
+        .run_evaluation(case.probes(), &case.play.artifact_trail)
+        .await
+}
+
+/// Re-record the answers of `case` from `llm` (e.g. a live endpoint).
+pub async fn record_case(
+    case: &mut RegressionCase,
+    config: &EvaluatorConfig,
+    llm: Arc<dyn LlmClient>,
+) -> Result<()> {
+    let recorder = Arc::new(RecordingLlmClient::new(llm));
+    evaluate_case(case, config, recorder.clone()).await?;
+    case.responses = recorder.recorded();
+    case.prompt_digests = recorder.prompt_digests();
+    Ok(())
+}
+
+/// Replay every case in `cases` and score the verdicts.
+pub async fn run_dataset(
+    cases: &[RegressionCase],
+    config: &EvaluatorConfig,
+) -> Result<RegressionReport> {
+    let mut outcomes = Vec::with_capacity(cases.len());
+    for case in cases {
+        let replay = Arc::new(
+            ReplayLlmClient::new(case.responses.clone())
+                .with_prompt_digests(case.prompt_digests.clone()),
+        );
+        let results = evaluate_case(case, config, replay.clone()).await?;
+        outcomes.push(CaseOutcome::new(
+            case,
+            &results,
+            replay.missing(),
+            replay.stale(),
+        ));
+    }
+    Ok(RegressionReport::new(outcomes))
+}
+
+/// Verdicts for one case.
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseOutcome {
+    pub name: String,
+    pub passed: bool,
+    /// Verdict per probe type (a type passes when all its probes pass)
+    pub probes: BTreeMap<ProbeType, bool>,
+    pub expected: ExpectedVerdict,
+    /// Probe questions without a recorded answer
+    pub missing_responses: Vec<String>,
+    /// Probe questions answered from a recording of a different prompt
+    pub stale_responses: Vec<String>,
+}
+
+impl CaseOutcome {
+    fn new(
+        case: &RegressionCase,
+        results: &EvaluationResults,
+        missing: Vec<String>,
+        stale: Vec<String>,
+    ) -> Self {
+        let mut probes = BTreeMap::new();
+        for result in &results.probes {
+            let passed = probes.entry(result.probe.probe_type).or_insert(true);
+            *passed &= result.passed;
+        }
+        Self {
+            name: case.name.clone(),
+            passed: results.passed,
+            probes,
+            expected: case.expected.clone(),
+            missing_responses: missing,
+            stale_responses: stale,
+        }
+    }
+
+    /// Whether every labelled verdict matched. A labelled probe type the
+    /// evaluator did not ask counts as passing.
+    pub fn matches(&self) -> bool {
+        self.passed == self.expected.passed
+            && self
+                .expected
+                .probes
+                .iter()
+                .all(|(probe_type, expected)| self.verdict(*probe_type) == *expected)
+    }
+
+    fn verdict(&self, probe_type: ProbeType) -> bool {
+        self.probes.get(&probe_type).copied().unwrap_or(true)
+    }
+}
+
+/// Confusion counts with failing verdicts as the positive class.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Confusion {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub true_negatives: usize,
+    pub false_negatives: usize,
+}
+
+impl Confusion {
+    fn add(&mut self, passed: bool, expected_passed: bool) {
+        match (passed, expected_passed) {
+            (false, false) => self.true_positives += 1,
+            (false, true) => self.false_positives += 1,
+            (true, true) => self.true_negatives += 1,
+            (true, false) => self.false_negatives += 1,
+        }
+    }
+
+    /// Share of failing verdicts that were expected (`None` if nothing
+    /// was flagged).
+    #[allow(clippy::cast_precision_loss)] // Counts are small
+    pub fn precision(&self) -> Option<f64> {
+        let flagged = self.true_positives + self.false_positives;
+        (flagged > 0).then(|| self.true_positives as f64 / flagged as f64)
+    }
+
+    /// Share of expected failures that were flagged (`None` if none were
+    /// expected).
+    #[allow(clippy::cast_precision_loss)] // Counts are small
+    pub fn recall(&self) -> Option<f64> {
+        let expected = self.true_positives + self.false_negatives;
+        (expected > 0).then(|| self.true_positives as f64 / expected as f64)
+    }
+
+    pub fn total(&self) -> usize {
+        self.true_positives + self.false_positives + self.true_negatives + self.false_negatives
+    }
+}
+
+/// Verdict accuracy over a dataset.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegressionReport {
+    pub cases: Vec<CaseOutcome>,
+    /// Overall stage verdicts
+    pub overall: Confusion,
+    pub by_probe_type: BTreeMap<ProbeType, Confusion>,
+}
+
+impl RegressionReport {
+    fn new(cases: Vec<CaseOutcome>) -> Self {
+        let mut overall = Confusion::default();
+        let mut by_probe_type: BTreeMap<ProbeType, Confusion> = BTreeMap::new();
+        for case in &cases {
+            overall.add(case.passed, case.expected.passed);
+            for (probe_type, expected) in &case.expected.probes {
+                by_probe_type
+                    .entry(*probe_type)
+                    .or_default()
+                    .add(case.verdict(*probe_type), *expected);
+            }
+        }
+        Self {
+            cases,
+            overall,
+            by_probe_type,
+        }
+    }
+
+    /// Fail if any row is below `min_precision` or `min_recall`, or a case
+    /// is missing recorded answers or replayed answers recorded for a
+    /// different prompt.
+    pub fn check(&self, min_precision: f64, min_recall: f64) -> Result<()> {
+        let mut problems = Vec::new();
+        for case in &self.cases {
+            if !case.missing_responses.is_empty() {
+                problems.push(format!(
+                    "{}: {} probe(s) without a recorded answer",
+                    case.name,
+                    case.missing_responses.len()
+                ));
+            }
+            if !case.stale_responses.is_empty() {
+                problems.push(format!(
+                    "{}: {} stale recording(s), prompt changed since recording; re-run with --record",
+                    case.name,
+                    case.stale_responses.len()
+                ));
+            }
+        }
+        let rows = std::iter::once(("overall".to_string(), &self.overall)).chain(
+            self.by_probe_type
+                .iter()
+                .map(|(probe_type, confusion)| (format!("{probe_type:?}"), confusion)),
+        );
+        for (name, confusion) in rows {
+            if let Some(precision) = confusion.precision().filter(|p| *p < min_precision) {
+                problems.push(format!(
+                    "{name}: precision {precision:.2} < {min_precision:.2}"
+                ));
+            }
+            if let Some(recall) = confusion.recall().filter(|r| *r < min_recall) {
+                problems.push(format!("{name}: recall {recall:.2} < {min_recall:.2}"));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("Verdict regression:\n  {}", problems.join("\n  "))
+        }
+    }
+
+    /// Plain-text report: one line per case, then a precision/recall table.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for case in &self.cases {
+            let mark = if case.matches() { "✓" } else { "✗" };
+            let _ = write!(
+                out,
+                "{mark} {} (expected {}, got {})",
+                case.name,
+                verdict_label(case.expected.passed),
+                verdict_label(case.passed)
+            );
+            let wrong: Vec<String> = case
+                .expected
+                .probes
+                .iter()
+                .filter(|(probe_type, expected)| case.verdict(**probe_type) != **expected)
+                .map(|(probe_type, _)| format!("{probe_type:?}"))
+                .collect();
+            if !wrong.is_empty() {
+                let _ = write!(out, " mismatched: {}", wrong.join(", "));
+            }
+            out.push('\n');
+        }
+
+        let _ = writeln!(
+            out,
+            "\n{:<14} {:>5} {:>4} {:>4} {:>4} {:>4} {:>9} {:>7}",
+            "verdict", "n", "tp", "fp", "tn", "fn", "precision", "recall"
+        );
+        let rows = std::iter::once(("overall".to_string(), &self.overall)).chain(
+            self.by_probe_type
+                .iter()
+                .map(|(probe_type, confusion)| (format!("{probe_type:?}"), confusion)),
+        );
+        for (name, c) in rows {
+            let _ = writeln!(
+                out,
+                "{name:<14} {:>5} {:>4} {:>4} {:>4} {:>4} {:>9} {:>7}",
+                c.total(),
+                c.true_positives,
+                c.false_positives,
+                c.true_negatives,
+                c.false_negatives,
+                ratio(c.precision()),
+                ratio(c.recall())
+            );
+        }
+        out
+    }
+}
+
+fn verdict_label(passed: bool) -> &'static str {
+    if passed {
+        "pass"
+    } else {
+        "fail"
+    }
+}
+
+fn ratio(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{v:.2}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::evaluator::prompt_digest;
+    use super::*;
+
+    fn dataset_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/evaluator")
+    }
+
+    /// The checked-in dataset is the gate for evaluator changes.
+    #[tokio::test]
+    async fn test_dataset_verdicts() {
+        let cases: Vec<RegressionCase> = load_dataset(&dataset_dir())
+            .unwrap()
+            .into_iter()
+            .map(|(_, case)| case)
+            .collect();
+        assert!(cases.len() >= 3);
+
+        let report = run_dataset(&cases, &EvaluatorConfig::default())
+            .await
+            .unwrap();
+        let rendered = report.render();
+        assert!(report.cases.iter().all(CaseOutcome::matches), "{rendered}");
+        report.check(1.0, 1.0).unwrap();
+    }
+
+    #[test]
+    fn test_log_anomalies_become_recall_probes() {
+        let (_, case) = load_dataset(&dataset_dir())
+            .unwrap()
+            .into_iter()
+            .find(|(_, c)| !c.logs.is_empty())
+            .unwrap();
+        let probes = case.probes();
+        assert!(probes.iter().any(|p| p.probe_type == ProbeType::Recall));
+        assert!(probes.iter().any(|p| p.probe_type == ProbeType::Acceptance));
+    }
+
+    #[test]
+    fn test_confusion_rates() {
+        let mut confusion = Confusion::default();
+        confusion.add(false, false);
+        confusion.add(false, true);
+        confusion.add(true, false);
+        confusion.add(true, true);
+        assert_eq!(confusion.precision(), Some(0.5));
+        assert_eq!(confusion.recall(), Some(0.5));
+        assert_eq!(Confusion::default().precision(), None);
+    }
+
+    #[tokio::test]
+    async fn test_missing_answers_fail_the_check() {
+        let (_, mut case) = load_dataset(&dataset_dir())
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        case.responses.clear();
+        let report = run_dataset(&[case], &EvaluatorConfig::default())
+            .await
+            .unwrap();
+        assert!(!report.cases[0].missing_responses.is_empty());
+        assert!(report.check(0.0, 0.0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_changed_prompt_reports_stale_recording() {
+        let (_, mut case) = load_dataset(&dataset_dir())
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        let question = case.probes()[0].question.clone();
+        case.prompt_digests
+            .insert(question.clone(), prompt_digest("an older prompt"));
+
+        let report = run_dataset(&[case], &EvaluatorConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(report.cases[0].stale_responses, vec![question]);
+        let err = report.check(0.0, 0.0).unwrap_err();
+        assert!(err.to_string().contains("stale recording"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_record_case_stores_prompt_digests() {
+        let (_, mut case) = load_dataset(&dataset_dir())
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        case.prompt_digests.clear();
+        let config = EvaluatorConfig::default();
+        let replay = Arc::new(ReplayLlmClient::new(case.responses.clone()));
+        record_case(&mut case, &config, replay).await.unwrap();
+
+        assert_eq!(
+            case.prompt_digests.keys().collect::<Vec<_>>(),
+            case.responses.keys().collect::<Vec<_>>()
+        );
+        let report = run_dataset(&[case], &config).await.unwrap();
+        assert!(report.cases[0].stale_responses.is_empty());
+    }
+}
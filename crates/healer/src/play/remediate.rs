@@ -1,32 +1,42 @@
 //! Code-based remediation for play issues.
 
 use anyhow::{Context, Result};
-use std::process::Command;
+use serde_json::json;
+use std::sync::Arc;
 
 use super::batch::PlayBatch;
 use super::types::{Diagnosis, DiagnosisCategory, DiagnosisContext, Issue, PrContext};
+use crate::clients::{ClusterClient, GitHubClient};
+
+/// Lines of logs gathered per container for diagnosis.
+const LOG_TAIL_LINES: i64 = 100;
 
 /// Engine for gathering context and spawning fix `CodeRuns`.
 pub struct RemediationEngine {
+    /// Kubernetes API client
+    cluster: Arc<dyn ClusterClient>,
+    /// GitHub API client (PR context is skipped without one)
+    github: Option<Arc<dyn GitHubClient>>,
     /// Namespace for `CodeRuns`
     namespace: String,
 }
 
 impl RemediationEngine {
-    /// Create a new remediation engine.
+    /// Create a remediation engine for `namespace`.
     #[must_use]
-    pub fn new() -> Self {
+    pub fn new(cluster: Arc<dyn ClusterClient>, namespace: impl Into<String>) -> Self {
         Self {
-            namespace: "cto".to_string(),
+            cluster,
+            github: None,
+            namespace: namespace.into(),
         }
     }
 
-    /// Create with a custom namespace.
+    /// Set the GitHub client used for PR context.
     #[must_use]
-    pub fn with_namespace(namespace: impl Into<String>) -> Self {
-        Self {
-            namespace: namespace.into(),
-        }
+    pub fn with_github(mut self, github: Arc<dyn GitHubClient>) -> Self {
+        self.github = Some(github);
+        self
     }
 
     /// Gather context for diagnosing an issue.
@@ -34,7 +44,11 @@ impl RemediationEngine {
     /// # Errors
     ///
     /// Returns an error if gathering context fails.
-    pub fn gather_context(&self, issue: &Issue, batch: &PlayBatch) -> Result<DiagnosisContext> {
+    pub async fn gather_context(
+        &self,
+        issue: &Issue,
+        batch: &PlayBatch,
+    ) -> Result<DiagnosisContext> {
         let mut context = DiagnosisContext::default();
 
         let task_id = issue.task_id();
@@ -43,11 +57,11 @@ impl RemediationEngine {
         // Get logs from Loki if we have a workflow/coderun name
         if let Some(task) = task {
             if let Some(ref coderun) = task.active_coderun {
-                context.logs = self.fetch_pod_logs(coderun).unwrap_or_default();
+                context.logs = self.fetch_pod_logs(coderun).await.unwrap_or_default();
             }
             if let Some(ref workflow) = task.workflow_name {
                 if context.logs.is_empty() {
-                    context.logs = self.fetch_workflow_logs(workflow).unwrap_or_default();
+                    context.logs = self.fetch_workflow_logs(workflow).await.unwrap_or_default();
                 }
             }
         }
@@ -55,7 +69,10 @@ impl RemediationEngine {
         // Get PR context if we have a PR number
         if let Some(task) = task {
             if let Some(pr_number) = task.pr_number {
-                context.pr_state = Self::fetch_pr_context(&batch.repository, pr_number).ok();
+                context.pr_state = self
+                    .fetch_pr_context(&batch.repository, pr_number)
+                    .await
+                    .ok();
             }
         }
 
@@ -66,88 +83,85 @@ impl RemediationEngine {
     }
 
     /// Fetch pod logs from Loki.
-    fn fetch_pod_logs(&self, pod_name: &str) -> Result<String> {
-        // Use kubectl logs as fallback (Loki query would be better)
-        let output = Command::new("kubectl")
-            .args(["logs", pod_name, "-n", &self.namespace, "--tail=100"])
-            .output()
-            .context("Failed to fetch pod logs")?;
-
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
-        } else {
-            // Try with -l selector for jobs/workflows
-            let output = Command::new("kubectl")
-                .args([
-                    "logs",
-                    "-l",
-                    &format!("app.kubernetes.io/name={pod_name}"),
-                    "-n",
-                    &self.namespace,
-                    "--tail=100",
-                ])
-                .output()
-                .context("Failed to fetch pod logs by label")?;
-
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    async fn fetch_pod_logs(&self, pod_name: &str) -> Result<String> {
+        // Use pod logs as fallback (Loki query would be better)
+        match self
+            .cluster
+            .pod_logs(&self.namespace, pod_name, LOG_TAIL_LINES)
+            .await
+        {
+            Ok(logs) => Ok(logs),
+            // Try with a label selector for jobs/workflows
+            Err(_) => {
+                let selector = format!("app.kubernetes.io/name={pod_name}");
+                let mut logs = String::new();
+                for pod in self
+                    .cluster
+                    .list_pods(&self.namespace, Some(&selector))
+                    .await
+                    .context("Failed to fetch pod logs by label")?
+                {
+                    if let Ok(pod_logs) = self
+                        .cluster
+                        .pod_logs(&self.namespace, &pod.name, LOG_TAIL_LINES)
+                        .await
+                    {
+                        push_lines(&mut logs, &pod_logs);
+                    }
+                }
+                Ok(logs)
+            }
         }
     }
 
-    /// Fetch workflow logs.
-    fn fetch_workflow_logs(&self, workflow_name: &str) -> Result<String> {
-        let output = Command::new("kubectl")
-            .args([
-                "logs",
-                "-l",
-                &format!("workflows.argoproj.io/workflow={workflow_name}"),
-                "-n",
-                &self.namespace,
-                "--tail=100",
-                "--all-containers",
-            ])
-            .output()
+    /// Fetch workflow logs from every container of the workflow's pods.
+    async fn fetch_workflow_logs(&self, workflow_name: &str) -> Result<String> {
+        let selector = format!("workflows.argoproj.io/workflow={workflow_name}");
+        let pods = self
+            .cluster
+            .list_pods(&self.namespace, Some(&selector))
+            .await
             .context("Failed to fetch workflow logs")?;
 
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        let mut logs = String::new();
+        for pod in pods {
+            for container in &pod.container_statuses {
+                if let Ok(container_logs) = self
+                    .cluster
+                    .container_logs(&self.namespace, &pod.name, &container.name, LOG_TAIL_LINES)
+                    .await
+                {
+                    push_lines(&mut logs, &container_logs);
+                }
+            }
+        }
+        Ok(logs)
     }
 
     /// Fetch PR context from GitHub.
-    fn fetch_pr_context(repository: &str, pr_number: u32) -> Result<PrContext> {
-        let output = Command::new("gh")
-            .args([
-                "pr",
-                "view",
-                &pr_number.to_string(),
-                "--repo",
-                repository,
-                "--json",
-                "number,state,mergeable,statusCheckRollup",
-            ])
-            .output()
-            .context("Failed to fetch PR context")?;
-
-        if !output.status.success() {
-            anyhow::bail!(
-                "gh pr view failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            );
-        }
-
-        let json: serde_json::Value =
-            serde_json::from_slice(&output.stdout).context("Failed to parse PR JSON")?;
-
+    async fn fetch_pr_context(&self, repository: &str, pr_number: u32) -> Result<PrContext> {
+        let github = self
+            .github
+            .as_deref()
+            .context("GitHub client not configured")?;
+        let pr = github
+            .get_pull_request(repository, u64::from(pr_number))
+            .await?
+            .with_context(|| format!("PR {repository}#{pr_number} not found"))?;
+        let checks = github.check_summary(repository, &pr.head_sha).await?;
+
+        let state = if pr.merged {
+            "MERGED"
+        } else if pr.open {
+            "OPEN"
+        } else {
+            "CLOSED"
+        };
         Ok(PrContext {
             number: pr_number,
-            state: json["state"].as_str().unwrap_or("unknown").to_string(),
-            mergeable: json["mergeable"].as_str() == Some("MERGEABLE"),
-            checks_status: json["statusCheckRollup"].as_array().map_or_else(
-                || "unknown".to_string(),
-                |arr| {
-                    let passed = arr.iter().filter(|c| c["conclusion"] == "SUCCESS").count();
-                    let total = arr.len();
-                    format!("{passed}/{total} passed")
-                },
-            ),
+            state: state.to_string(),
+            mergeable: pr.mergeable == Some(true),
+            checks_status: format!("{}/{} passed", checks.succeeded, checks.total),
         })
     }
 
@@ -225,10 +239,10 @@ impl RemediationEngine {
     /// # Errors
     ///
     /// Returns an error if a remediation `CodeRun` already exists for this task,
-    /// or if kubectl fails to apply the `CodeRun` resource.
-    pub fn spawn_fix_coderun(&self, task_id: &str, diagnosis: &Diagnosis) -> Result<String> {
+    /// or if the `CodeRun` cannot be created.
+    pub async fn spawn_fix_coderun(&self, task_id: &str, diagnosis: &Diagnosis) -> Result<String> {
         // Check for existing CodeRun with same task-id to prevent duplicates
-        if let Some(existing) = self.check_existing_remediation(task_id)? {
+        if let Some(existing) = self.check_existing_remediation(task_id).await {
             anyhow::bail!(
                 "Remediation CodeRun already exists for task {task_id}: {existing}. \
                  Use 'healer play cancel-remediation --task-id {task_id}' to cancel it first."
@@ -264,99 +278,166 @@ Do NOT just restart or retry - fix the underlying issue in code.
             diagnosis.summary, diagnosis.category, diagnosis.suggested_fix
         );
 
-        // Create the CodeRun YAML
         // Labels include task-id for cancellation via CancelRemediation command
-        let coderun_yaml = format!(
-            r"apiVersion: agents.platform/v1
-kind: CodeRun
-metadata:
-  name: {}
-  namespace: {}
-  labels:
-    app.kubernetes.io/name: healer
-    app.kubernetes.io/component: remediation
-    task-id: '{}'
-spec:
-  cli: claude
-  model: sonnet
-  githubApp: cto-healer
-  repository: 5dlabs/cto
-  workingDir: /workspace
-  prompt: |
-{}
-",
-            coderun_name,
-            self.namespace,
-            task_id,
-            prompt
-                .lines()
-                .map(|l| format!("    {l}"))
-                .collect::<Vec<_>>()
-                .join("\n")
-        );
-
-        // Apply the CodeRun
-        let mut child = Command::new("kubectl")
-            .args(["apply", "-f", "-"])
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-            .context("Failed to spawn kubectl apply")?;
-
-        if let Some(mut stdin) = child.stdin.take() {
-            use std::io::Write;
-            stdin
-                .write_all(coderun_yaml.as_bytes())
-                .context("Failed to write CodeRun YAML")?;
-        }
-
-        let status = child.wait().context("Failed to wait for kubectl apply")?;
-
-        if !status.success() {
-            anyhow::bail!("kubectl apply failed for CodeRun");
-        }
+        let manifest = json!({
+            "apiVersion": "agents.platform/v1",
+            "kind": "CodeRun",
+            "metadata": {
+                "name": coderun_name,
+                "namespace": self.namespace,
+                "labels": {
+                    "app.kubernetes.io/name": "healer",
+                    "app.kubernetes.io/component": "remediation",
+                    "task-id": task_id,
+                }
+            },
+            "spec": {
+                "cli": "claude",
+                "model": "sonnet",
+                "githubApp": "cto-healer",
+                "repository": "5dlabs/cto",
+                "workingDir": "/workspace",
+                "prompt": prompt,
+            }
+        });
 
-        Ok(coderun_name)
+        self.cluster
+            .create_coderun(&self.namespace, &manifest)
+            .await
+            .context("Failed to create remediation CodeRun")
     }
 
     /// Check if an active remediation `CodeRun` already exists for this task.
     ///
     /// Returns the name of the existing `CodeRun` if found, `None` otherwise.
-    fn check_existing_remediation(&self, task_id: &str) -> Result<Option<String>> {
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "coderuns",
-                "-n",
-                &self.namespace,
-                "-l",
-                &format!("task-id={task_id},app.kubernetes.io/name=healer"),
-                "-o",
-                "jsonpath={.items[*].metadata.name}",
-            ])
-            .output()
-            .context("Failed to check for existing CodeRun")?;
-
-        if !output.status.success() {
-            // If kubectl fails, assume no existing CodeRun and proceed
-            return Ok(None);
-        }
+    async fn check_existing_remediation(&self, task_id: &str) -> Option<String> {
+        let selector = format!("task-id={task_id},app.kubernetes.io/name=healer");
+        // If the lookup fails, assume no existing CodeRun and proceed
+        let coderuns = self
+            .cluster
+            .list_coderuns(&self.namespace, Some(&selector))
+            .await
+            .ok()?;
+        coderuns.into_iter().next().map(|c| c.name)
+    }
+}
 
-        let names = String::from_utf8_lossy(&output.stdout);
-        let name = names.trim();
+/// Append `text` to `logs`, keeping one entry per line.
+fn push_lines(logs: &mut String, text: &str) {
+    logs.push_str(text);
+    if !logs.is_empty() && !logs.ends_with('\n') {
+        logs.push('\n');
+    }
+}
 
-        if name.is_empty() {
-            Ok(None)
-        } else {
-            // Return the first existing CodeRun name
-            Ok(Some(
-                name.split_whitespace().next().unwrap_or(name).to_string(),
-            ))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::{CheckSummary, FakeCluster, FakeGitHub, PullRequest};
+    use crate::k8s::{ContainerStatus, Pod};
+    use crate::play::stage::Stage;
+    use crate::play::task::TaskState;
+
+    fn batch_with_task(task: TaskState) -> PlayBatch {
+        let mut batch = PlayBatch::new("play", "5dlabs/cto", "cto");
+        batch.tasks.push(task);
+        batch
+    }
+
+    fn failed_issue() -> Issue {
+        Issue::NeedsRemediation {
+            task_id: "7".to_string(),
+            stage: Stage::ImplementationInProgress,
+            failure_reason: "tests failed".to_string(),
         }
     }
-}
 
-impl Default for RemediationEngine {
-    fn default() -> Self {
-        Self::new()
+    #[tokio::test]
+    async fn test_gather_context_reads_workflow_containers_and_pr() {
+        let pod = Pod {
+            name: "play-task-7-abc".to_string(),
+            namespace: "cto".to_string(),
+            labels: [(
+                "workflows.argoproj.io/workflow".to_string(),
+                "play-task-7".to_string(),
+            )]
+            .into(),
+            container_statuses: ["main", "wait"]
+                .iter()
+                .map(|name| ContainerStatus {
+                    name: (*name).to_string(),
+                    ..ContainerStatus::default()
+                })
+                .collect(),
+            ..Pod::default()
+        };
+        let cluster = FakeCluster::new()
+            .with_pod(pod)
+            .with_container_logs("cto", "play-task-7-abc", "main", "test fail: adds")
+            .with_container_logs("cto", "play-task-7-abc", "wait", "done");
+        let github = FakeGitHub::new()
+            .with_pull_request(
+                "5dlabs/cto",
+                PullRequest {
+                    number: 31,
+                    head_sha: "f00".to_string(),
+                    open: true,
+                    mergeable: Some(true),
+                    ..PullRequest::default()
+                },
+            )
+            .with_checks(
+                "5dlabs/cto",
+                "f00",
+                CheckSummary {
+                    total: 4,
+                    succeeded: 3,
+                    failed: 1,
+                },
+            );
+        let mut task = TaskState::new("7");
+        task.workflow_name = Some("play-task-7".to_string());
+        task.pr_number = Some(31);
+        let engine = RemediationEngine::new(Arc::new(cluster), "cto").with_github(Arc::new(github));
+
+        let context = engine
+            .gather_context(&failed_issue(), &batch_with_task(task))
+            .await
+            .unwrap();
+
+        assert_eq!(context.logs, "test fail: adds\ndone\n");
+        let pr = context.pr_state.unwrap();
+        assert_eq!(pr.state, "OPEN");
+        assert!(pr.mergeable);
+        assert_eq!(pr.checks_status, "3/4 passed");
+    }
+
+    #[tokio::test]
+    async fn test_spawn_fix_coderun_refuses_duplicates() {
+        let cluster = Arc::new(FakeCluster::new());
+        let engine = RemediationEngine::new(cluster.clone(), "cto");
+        let diagnosis = Diagnosis {
+            summary: "Test failure".to_string(),
+            category: DiagnosisCategory::CodeIssue,
+            suggested_fix: "Fix failing tests".to_string(),
+            relevant_files: vec![],
+        };
+
+        let name = engine.spawn_fix_coderun("7", &diagnosis).await.unwrap();
+        assert!(name.starts_with("healer-fix-"));
+        let manifest = &cluster.created_coderuns()[0];
+        assert_eq!(manifest["metadata"]["labels"]["task-id"], "7");
+        assert!(manifest["spec"]["prompt"]
+            .as_str()
+            .unwrap()
+            .contains("Summary: Test failure"));
+
+        let err = engine.spawn_fix_coderun("7", &diagnosis).await.unwrap_err();
+        assert!(err.to_string().contains(&name));
+        assert!(cluster
+            .list_coderuns("cto", Some("task-id=8"))
+            .await
+            .unwrap()
+            .is_empty());
     }
 }
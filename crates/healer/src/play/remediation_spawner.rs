@@ -7,13 +7,15 @@
 //!
 //! This implements the "Model 2: Remediation Agent" from the dual-model Healer architecture.
 
-use anyhow::{Context as _, Result};
+use std::sync::Arc;
+
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::process::Command;
 use tracing::{debug, info, warn};
 
 use super::session::{IssueSeverity, IssueType, PlaySession, SessionIssue};
+use crate::clients::ClusterClient;
 
 /// Configuration for the Remediation Agent spawner.
 #[derive(Debug, Clone)]
@@ -67,29 +69,29 @@ pub struct RemediationSpawnResult {
 /// Creates `CodeRuns` that fix issues detected during Play sessions.
 pub struct RemediationSpawner {
     config: RemediationSpawnerConfig,
+    cluster: Arc<dyn ClusterClient>,
 }
 
 impl RemediationSpawner {
     /// Create a new Remediation Agent spawner.
     #[must_use]
-    pub fn new(config: RemediationSpawnerConfig) -> Self {
-        Self { config }
+    pub fn new(config: RemediationSpawnerConfig, cluster: Arc<dyn ClusterClient>) -> Self {
+        Self { config, cluster }
     }
 
     /// Create spawner with default configuration.
     #[must_use]
-    pub fn with_defaults() -> Self {
-        Self::new(RemediationSpawnerConfig::default())
+    pub fn with_defaults(cluster: Arc<dyn ClusterClient>) -> Self {
+        Self::new(RemediationSpawnerConfig::default(), cluster)
     }
 
     /// Spawn a Remediation Agent `CodeRun` for an issue.
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - kubectl cannot be executed
-    /// - The `CodeRun` creation fails
-    pub fn spawn_remediation(
+    /// Never fails today: exhausted retries and rejected `CodeRun`s are
+    /// reported through [`RemediationSpawnResult::error`].
+    pub async fn spawn_remediation(
         &self,
         session: &PlaySession,
         issue: &SessionIssue,
@@ -141,66 +143,48 @@ impl RemediationSpawner {
         let coderun_spec =
             build_remediation_coderun_spec(&coderun_name, &self.config, &prompt, play_id, issue);
 
-        // Apply the `CodeRun` using kubectl (use JSON format)
-        let coderun_json = serde_json::to_string_pretty(&coderun_spec)
-            .context("Failed to serialize CodeRun spec")?;
-
         debug!(
             coderun_name = %coderun_name,
-            json_length = %coderun_json.len(),
-            "Applying Remediation CodeRun"
+            prompt_length = %prompt.len(),
+            "Creating Remediation CodeRun"
         );
 
-        // Pipe the JSON spec to kubectl apply
-        let mut child = Command::new("kubectl")
-            .args(["apply", "-f", "-", "-n", &self.config.namespace])
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()
-            .context("Failed to spawn kubectl")?;
-
-        if let Some(stdin) = child.stdin.as_mut() {
-            use std::io::Write;
-            stdin
-                .write_all(coderun_json.as_bytes())
-                .context("Failed to write to kubectl stdin")?;
-        }
-
-        let output = child
-            .wait_with_output()
-            .context("kubectl execution failed")?;
-
-        if output.status.success() {
-            info!(
-                play_id = %play_id,
-                coderun_name = %coderun_name,
-                issue_type = %issue_type,
-                "Remediation Agent CodeRun created successfully"
-            );
-            Ok(RemediationSpawnResult {
-                coderun_name,
-                play_id: play_id.clone(),
-                issue_type,
-                success: true,
-                error: None,
-                attempt,
-            })
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            warn!(
-                play_id = %play_id,
-                error = %stderr,
-                "Failed to create Remediation Agent CodeRun"
-            );
-            Ok(RemediationSpawnResult {
-                coderun_name,
-                play_id: play_id.clone(),
-                issue_type,
-                success: false,
-                error: Some(stderr.to_string()),
-                attempt,
-            })
+        match self
+            .cluster
+            .create_coderun(&self.config.namespace, &coderun_spec)
+            .await
+        {
+            Ok(coderun_name) => {
+                info!(
+                    play_id = %play_id,
+                    coderun_name = %coderun_name,
+                    issue_type = %issue_type,
+                    "Remediation Agent CodeRun created successfully"
+                );
+                Ok(RemediationSpawnResult {
+                    coderun_name,
+                    play_id: play_id.clone(),
+                    issue_type,
+                    success: true,
+                    error: None,
+                    attempt,
+                })
+            }
+            Err(e) => {
+                warn!(
+                    play_id = %play_id,
+                    error = %e,
+                    "Failed to create Remediation Agent CodeRun"
+                );
+                Ok(RemediationSpawnResult {
+                    coderun_name,
+                    play_id: play_id.clone(),
+                    issue_type,
+                    success: false,
+                    error: Some(format!("{e:#}")),
+                    attempt,
+                })
+            }
         }
     }
 
@@ -408,6 +392,7 @@ fn sanitize_name(name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clients::FakeCluster;
     use crate::play::session::{CtoConfig, SessionStatus};
     use chrono::Utc;
     use std::collections::HashMap;
@@ -498,4 +483,25 @@ mod tests {
         assert_eq!(sanitize_name("BuildFailure"), "buildfailure");
         assert_eq!(sanitize_name("pre_flight_failure"), "pre-flight-failure");
     }
+
+    #[tokio::test]
+    async fn test_spawn_remediation_stops_after_max_retries() {
+        let cluster = Arc::new(FakeCluster::new());
+        let spawner = RemediationSpawner::with_defaults(cluster.clone());
+        let issue = sample_issue(IssueType::BuildFailure, IssueSeverity::High);
+
+        let first = spawner
+            .spawn_remediation(&sample_session(), &issue, 1)
+            .await
+            .unwrap();
+        assert!(first.success);
+        assert_eq!(cluster.coderun_names("cto"), vec![first.coderun_name]);
+
+        let exhausted = spawner
+            .spawn_remediation(&sample_session(), &issue, 4)
+            .await
+            .unwrap();
+        assert!(!exhausted.success);
+        assert_eq!(cluster.coderun_names("cto").len(), 1);
+    }
 }
@@ -5,6 +5,9 @@
 //! - Task list and dependencies
 //! - Repository and service info
 //! - Expected agents and their tool requirements
+//!
+//! Sessions can be persisted in a ConfigMap ([`SessionStore::with_state_store`])
+//! so a restarted or replacement healer picks them up again.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -13,6 +16,11 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use crate::ha::StateStore;
+
+/// State store key prefix for Play sessions.
+const STATE_KEY_PREFIX: &str = "play-session-";
+
 /// Expected tools for an agent from CTO config.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AgentTools {
@@ -223,6 +231,8 @@ pub enum IssueSeverity {
 pub struct SessionStore {
     /// Active sessions by `play_id`
     sessions: RwLock<HashMap<String, PlaySession>>,
+    /// Cluster persistence for sessions (if configured)
+    store: Option<StateStore>,
 }
 
 impl SessionStore {
@@ -231,6 +241,47 @@ impl SessionStore {
     pub fn new() -> Self {
         Self {
             sessions: RwLock::new(HashMap::new()),
+            store: None,
+        }
+    }
+
+    /// Persist sessions in `store`.
+    #[must_use]
+    pub fn with_state_store(mut self, store: StateStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Load sessions persisted by a previous instance. Returns how many
+    /// were restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state store cannot be read.
+    pub async fn restore(&self) -> anyhow::Result<usize> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        let restored: Vec<PlaySession> = store.load_all(STATE_KEY_PREFIX).await?;
+        let count = restored.len();
+        let mut sessions = self.sessions.write().await;
+        for session in restored {
+            sessions.insert(session.play_id.clone(), session);
+        }
+        if count > 0 {
+            info!(count = %count, "Restored Play sessions from {}", store.name());
+        }
+        Ok(count)
+    }
+
+    /// Write a session through to the state store. Failures are logged:
+    /// losing persistence must not fail the API call.
+    async fn persist(&self, session: &PlaySession) {
+        if let Some(store) = &self.store {
+            let key = format!("{STATE_KEY_PREFIX}{}", session.play_id);
+            if let Err(e) = store.save(&key, session).await {
+                warn!(play_id = %session.play_id, "Failed to persist Play session: {e:#}");
+            }
         }
     }
 
@@ -262,6 +313,8 @@ impl SessionStore {
 
         let mut sessions = self.sessions.write().await;
         sessions.insert(session.play_id.clone(), session.clone());
+        drop(sessions);
+        self.persist(&session).await;
 
         session
     }
@@ -322,6 +375,8 @@ impl SessionStore {
         );
 
         sessions.insert(session.play_id.clone(), session.clone());
+        drop(sessions);
+        self.persist(&session).await;
         Ok(session)
     }
 
@@ -350,7 +405,9 @@ impl SessionStore {
     /// Update a session.
     pub async fn update_session(&self, session: PlaySession) {
         let mut sessions = self.sessions.write().await;
-        sessions.insert(session.play_id.clone(), session);
+        sessions.insert(session.play_id.clone(), session.clone());
+        drop(sessions);
+        self.persist(&session).await;
     }
 
     /// Add an issue to a session.
@@ -369,6 +426,9 @@ impl SessionStore {
             );
             session.issues.push(issue);
             session.last_updated = Utc::now();
+            let snapshot = session.clone();
+            drop(sessions);
+            self.persist(&snapshot).await;
             Ok(())
         } else {
             warn!(play_id = %play_id, "Session not found, cannot add issue");
@@ -392,6 +452,9 @@ impl SessionStore {
                 issues = %session.issues.len(),
                 "Completed Play session"
             );
+            let snapshot = session.clone();
+            drop(sessions);
+            self.persist(&snapshot).await;
         }
     }
 
@@ -400,14 +463,25 @@ impl SessionStore {
         let cutoff = Utc::now() - chrono::Duration::hours(max_age_hours);
         let mut sessions = self.sessions.write().await;
 
-        let old_count = sessions.len();
-        sessions.retain(|_, session| {
-            session.status == SessionStatus::Active || session.last_updated > cutoff
-        });
+        let removed: Vec<String> = sessions
+            .values()
+            .filter(|s| s.status != SessionStatus::Active && s.last_updated <= cutoff)
+            .map(|s| s.play_id.clone())
+            .collect();
+        for play_id in &removed {
+            sessions.remove(play_id);
+        }
+        drop(sessions);
 
-        let removed = old_count - sessions.len();
-        if removed > 0 {
-            debug!(removed = %removed, "Cleaned up old sessions");
+        if !removed.is_empty() {
+            debug!(removed = %removed.len(), "Cleaned up old sessions");
+        }
+        if let Some(store) = &self.store {
+            for play_id in &removed {
+                if let Err(e) = store.remove(&format!("{STATE_KEY_PREFIX}{play_id}")).await {
+                    warn!(play_id = %play_id, "Failed to remove persisted Play session: {e:#}");
+                }
+            }
         }
     }
 
@@ -481,6 +555,33 @@ mod tests {
         assert_eq!(completed.issues.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_sessions_survive_restart_with_state_store() {
+        let cluster = Arc::new(crate::clients::FakeCluster::new());
+        let state = || StateStore::new(cluster.clone(), "cto", "healer-play-state");
+
+        let store = SessionStore::new().with_state_store(state());
+        let request = StartSessionRequest {
+            play_id: "test-play-ha".to_string(),
+            repository: "5dlabs/test".to_string(),
+            service: None,
+            cto_config: CtoConfig::default(),
+            tasks: vec![],
+            namespace: "cto".to_string(),
+        };
+        assert!(store.try_start_session(request.clone()).await.is_ok());
+
+        // A replacement instance resumes the session and still rejects duplicates
+        let restarted = SessionStore::new().with_state_store(state());
+        assert_eq!(restarted.restore().await.unwrap(), 1);
+        assert!(restarted.try_start_session(request).await.is_err());
+
+        restarted.complete_session("test-play-ha", true).await;
+        restarted.cleanup_old_sessions(-1).await;
+        let fresh = SessionStore::new().with_state_store(state());
+        assert_eq!(fresh.restore().await.unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn test_try_start_session_rejects_duplicate() {
         let store = SessionStore::new();
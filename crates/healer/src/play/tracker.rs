@@ -1,5 +1,7 @@
 //! Play tracker for health monitoring and remediation triggering.
 
+use std::sync::Arc;
+
 use anyhow::Result;
 use chrono::Utc;
 
@@ -9,6 +11,7 @@ use super::remediate::RemediationEngine;
 use super::stage::STAGE_TIMEOUT;
 use super::task::TaskState;
 use super::types::{Issue, RemediationState};
+use crate::clients::{ClusterClient, GitHubClient};
 
 /// Watches a batch and triggers remediation when things go wrong.
 pub struct PlayTracker {
@@ -23,8 +26,8 @@ pub struct PlayTracker {
 impl PlayTracker {
     /// Create a new tracker for a batch.
     #[must_use]
-    pub fn new(batch: PlayBatch) -> Self {
-        let remediation = RemediationEngine::with_namespace(&batch.namespace);
+    pub fn new(cluster: Arc<dyn ClusterClient>, batch: PlayBatch) -> Self {
+        let remediation = RemediationEngine::new(cluster, batch.namespace.clone());
         Self {
             batch,
             insights: InsightCollector::new(),
@@ -32,14 +35,21 @@ impl PlayTracker {
         }
     }
 
+    /// Let remediation pull PR state through the GitHub client.
+    #[must_use]
+    pub fn with_github(mut self, github: Arc<dyn GitHubClient>) -> Self {
+        self.remediation = self.remediation.with_github(github);
+        self
+    }
+
     /// Load tracker from K8s state.
     ///
     /// # Errors
     ///
     /// Returns an error if loading from Kubernetes fails.
-    pub fn load(namespace: &str) -> Result<Self> {
-        let batch = PlayBatch::load_from_k8s(namespace)?;
-        Ok(Self::new(batch))
+    pub async fn load(cluster: Arc<dyn ClusterClient>, namespace: &str) -> Result<Self> {
+        let batch = PlayBatch::load_from_k8s(cluster.as_ref(), namespace).await?;
+        Ok(Self::new(cluster, batch))
     }
 
     /// Check all tasks and return any that need intervention.
@@ -79,9 +89,9 @@ impl PlayTracker {
     /// # Errors
     ///
     /// Returns an error if context gathering, diagnosis, or spawning fails.
-    pub fn remediate(&self, issue: &Issue) -> Result<RemediationState> {
+    pub async fn remediate(&self, issue: &Issue) -> Result<RemediationState> {
         // 1. Gather context (logs, code, agent output)
-        let context = self.remediation.gather_context(issue, &self.batch)?;
+        let context = self.remediation.gather_context(issue, &self.batch).await?;
 
         // 2. Diagnose root cause
         let diagnosis = self.remediation.diagnose(&context)?;
@@ -89,7 +99,8 @@ impl PlayTracker {
         // 3. Spawn Healer CodeRun to fix the code (passing task_id for cancellation)
         let coderun_name = self
             .remediation
-            .spawn_fix_coderun(issue.task_id(), &diagnosis)?;
+            .spawn_fix_coderun(issue.task_id(), &diagnosis)
+            .await?;
 
         Ok(RemediationState {
             coderun_name,
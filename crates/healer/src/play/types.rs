@@ -253,7 +253,7 @@ pub enum DiagnosisCategory {
 /// information by asking targeted questions.
 ///
 /// Reference: Agent-Skills-for-Context-Engineering/skills/context-compression
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ProbeType {
     /// Factual retention - "What was the original error message?"
     Recall,
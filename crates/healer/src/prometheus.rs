@@ -51,8 +51,8 @@ struct PrometheusData {
 #[derive(Debug, Deserialize)]
 struct PrometheusResult {
     metric: std::collections::HashMap<String, String>,
-    value: Option<(f64, String)>,          // For instant queries
-    values: Option<Vec<(f64, String)>>,    // For range queries
+    value: Option<(f64, String)>,       // For instant queries
+    values: Option<Vec<(f64, String)>>, // For range queries
 }
 
 /// A metric sample from Prometheus
@@ -212,18 +212,12 @@ impl PrometheusClient {
     /// # Errors
     /// Returns an error if the query fails.
     pub async fn get_pod_statuses(&self, namespace: &str) -> Result<Vec<PodStatus>> {
-        let phase_query = format!(
-            r#"kube_pod_status_phase{{namespace="{namespace}"}} == 1"#
-        );
-        let ready_query = format!(
-            r#"kube_pod_status_ready{{namespace="{namespace}", condition="true"}} == 1"#
-        );
-        let restart_query = format!(
-            r#"kube_pod_container_status_restarts_total{{namespace="{namespace}"}}"#
-        );
-        let created_query = format!(
-            r#"kube_pod_created{{namespace="{namespace}"}}"#
-        );
+        let phase_query = format!(r#"kube_pod_status_phase{{namespace="{namespace}"}} == 1"#);
+        let ready_query =
+            format!(r#"kube_pod_status_ready{{namespace="{namespace}", condition="true"}} == 1"#);
+        let restart_query =
+            format!(r#"kube_pod_container_status_restarts_total{{namespace="{namespace}"}}"#);
+        let created_query = format!(r#"kube_pod_created{{namespace="{namespace}"}}"#);
 
         // Execute queries in parallel
         let (phases, readies, restarts, created) = tokio::try_join!(
@@ -234,21 +228,24 @@ impl PrometheusClient {
         )?;
 
         // Build pod status map
-        let mut pods: std::collections::HashMap<String, PodStatus> = std::collections::HashMap::new();
+        let mut pods: std::collections::HashMap<String, PodStatus> =
+            std::collections::HashMap::new();
 
         // Process phases
         for sample in phases {
             let name = sample.labels.get("pod").cloned().unwrap_or_default();
             let phase = sample.labels.get("phase").cloned().unwrap_or_default();
-            
-            pods.entry(name.clone()).or_insert_with(|| PodStatus {
-                name: name.clone(),
-                namespace: namespace.to_string(),
-                phase: String::new(),
-                ready: false,
-                restart_count: 0,
-                age_seconds: 0.0,
-            }).phase = phase;
+
+            pods.entry(name.clone())
+                .or_insert_with(|| PodStatus {
+                    name: name.clone(),
+                    namespace: namespace.to_string(),
+                    phase: String::new(),
+                    ready: false,
+                    restart_count: 0,
+                    age_seconds: 0.0,
+                })
+                .phase = phase;
         }
 
         // Process readiness
@@ -263,7 +260,8 @@ impl PrometheusClient {
         for sample in restarts {
             let name = sample.labels.get("pod").cloned().unwrap_or_default();
             if let Some(pod) = pods.get_mut(&name) {
-                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Restart counts are small positive integers
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                // Restart counts are small positive integers
                 {
                     pod.restart_count = pod.restart_count.saturating_add(sample.value as u32);
                 }
@@ -271,7 +269,8 @@ impl PrometheusClient {
         }
 
         // Process creation time (calculate age)
-        #[allow(clippy::cast_precision_loss)] // Timestamp precision loss acceptable for age calculation
+        #[allow(clippy::cast_precision_loss)]
+        // Timestamp precision loss acceptable for age calculation
         let now = Utc::now().timestamp() as f64;
         for sample in created {
             let name = sample.labels.get("pod").cloned().unwrap_or_default();
@@ -370,8 +369,7 @@ impl PrometheusClient {
             if let Some((timestamp, value_str)) = &result.value {
                 let value: f64 = value_str.parse().unwrap_or(0.0);
                 #[allow(clippy::cast_possible_truncation)] // Prometheus timestamps fit in i64
-                let ts = DateTime::from_timestamp(*timestamp as i64, 0)
-                    .unwrap_or_else(Utc::now);
+                let ts = DateTime::from_timestamp(*timestamp as i64, 0).unwrap_or_else(Utc::now);
 
                 samples.push(MetricSample {
                     labels: result.metric.clone(),
@@ -393,8 +391,8 @@ impl PrometheusClient {
                 for (timestamp, value_str) in values {
                     let value: f64 = value_str.parse().unwrap_or(0.0);
                     #[allow(clippy::cast_possible_truncation)] // Prometheus timestamps fit in i64
-                    let ts = DateTime::from_timestamp(*timestamp as i64, 0)
-                        .unwrap_or_else(Utc::now);
+                    let ts =
+                        DateTime::from_timestamp(*timestamp as i64, 0).unwrap_or_else(Utc::now);
 
                     samples.push(MetricSample {
                         labels: result.metric.clone(),
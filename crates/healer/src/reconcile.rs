@@ -6,12 +6,13 @@
 //!
 //! This runs as a `CronJob` every 5 minutes to keep issues up-to-date.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::sync::Arc;
 use tracing::{debug, info, warn};
 
+use crate::clients::{ClusterClient, GitHubClient, Issue, IssueQuery};
 use crate::dedup::extract_pod_from_title;
 
 /// Configuration for the issue reconciler.
@@ -100,13 +101,23 @@ pub struct ClosedIssueInfo {
 /// Issue reconciler - checks and closes resolved issues.
 pub struct IssueReconciler {
     config: ReconcileConfig,
+    cluster: Arc<dyn ClusterClient>,
+    github: Arc<dyn GitHubClient>,
 }
 
 impl IssueReconciler {
-    /// Create a new reconciler with the given configuration.
+    /// Create a new reconciler with the given configuration and clients.
     #[must_use]
-    pub fn new(config: ReconcileConfig) -> Self {
-        Self { config }
+    pub fn new(
+        config: ReconcileConfig,
+        cluster: Arc<dyn ClusterClient>,
+        github: Arc<dyn GitHubClient>,
+    ) -> Self {
+        Self {
+            config,
+            cluster,
+            github,
+        }
     }
 
     /// Run the reconciliation process.
@@ -114,14 +125,14 @@ impl IssueReconciler {
     /// # Errors
     ///
     /// Returns an error if GitHub queries fail.
-    pub fn reconcile(&self) -> Result<ReconcileReport> {
+    pub async fn reconcile(&self) -> Result<ReconcileReport> {
         info!(
             "Starting issue reconciliation for {}",
             self.config.repository
         );
 
         // 1. Query open healer issues
-        let issues = self.query_open_issues()?;
+        let issues = self.query_open_issues().await?;
         info!("Found {} open healer issues", issues.len());
 
         let mut report = ReconcileReport {
@@ -135,7 +146,7 @@ impl IssueReconciler {
 
         // 2. Check each issue
         for issue in issues.iter().take(self.config.max_issues) {
-            match self.check_issue(issue) {
+            match self.check_issue(issue).await {
                 ReconcileResult::StillActive { reason } => {
                     debug!("Issue #{} still active: {}", issue.number, reason);
                     report.issues_still_active += 1;
@@ -143,7 +154,7 @@ impl IssueReconciler {
                 ReconcileResult::Resolved { reason } => {
                     info!("Issue #{} resolved: {}", issue.number, reason);
                     if !self.config.dry_run {
-                        if let Err(e) = self.close_issue(issue, &reason) {
+                        if let Err(e) = self.close_issue(issue, &reason).await {
                             warn!("Failed to close issue #{}: {}", issue.number, e);
                             report.issues_unknown += 1;
                             continue;
@@ -175,90 +186,22 @@ impl IssueReconciler {
     }
 
     /// Query open issues with healer labels.
-    fn query_open_issues(&self) -> Result<Vec<HealerIssue>> {
-        let label_args: Vec<String> = self
-            .config
-            .healer_labels
-            .iter()
-            .map(|l| format!("--label={l}"))
-            .collect();
-
-        let mut args = vec![
-            "issue".to_string(),
-            "list".to_string(),
-            "--repo".to_string(),
-            self.config.repository.clone(),
-            "--state".to_string(),
-            "open".to_string(),
-            "--json".to_string(),
-            "number,title,createdAt,labels".to_string(),
-            "--limit".to_string(),
-            self.config.max_issues.to_string(),
-        ];
-        args.extend(label_args);
-
-        let output = Command::new("gh")
-            .args(&args)
-            .output()
-            .context("Failed to query GitHub issues")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh issue list failed: {stderr}");
-        }
-
-        let json_str = String::from_utf8_lossy(&output.stdout);
-        if json_str.trim().is_empty() || json_str.trim() == "[]" {
-            return Ok(vec![]);
-        }
-
-        let raw_issues: Vec<serde_json::Value> =
-            serde_json::from_str(&json_str).context("Failed to parse GitHub issues JSON")?;
-
-        let issues = raw_issues
-            .into_iter()
-            .filter_map(|v| Self::parse_issue(&v))
-            .collect();
-
-        Ok(issues)
-    }
-
-    /// Parse a raw JSON issue into a `HealerIssue`.
-    fn parse_issue(value: &serde_json::Value) -> Option<HealerIssue> {
-        let number = value["number"].as_u64()?;
-        let title = value["title"].as_str()?.to_string();
-        let created_at_str = value["createdAt"].as_str()?;
-        let created_at = DateTime::parse_from_rfc3339(created_at_str)
-            .ok()?
-            .with_timezone(&Utc);
-
-        let labels: Vec<String> = value["labels"]
-            .as_array()
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|l| l["name"].as_str().map(String::from))
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        // Extract alert type from title: "[HEAL-A2]..." or labels
-        let alert_type = extract_alert_type(&title, &labels);
-
-        // Extract resource name from title
-        let resource_name = extract_pod_from_title(&title);
+    async fn query_open_issues(&self) -> Result<Vec<HealerIssue>> {
+        let query = IssueQuery {
+            labels: self.config.healer_labels.clone(),
+            include_closed: false,
+            limit: self.config.max_issues,
+        };
+        let issues = self
+            .github
+            .list_issues(&self.config.repository, &query)
+            .await?;
 
-        Some(HealerIssue {
-            number,
-            title,
-            created_at,
-            labels,
-            alert_type,
-            resource_name,
-        })
+        Ok(issues.into_iter().map(HealerIssue::from).collect())
     }
 
     /// Check if an issue should be closed.
-    fn check_issue(&self, issue: &HealerIssue) -> ReconcileResult {
+    async fn check_issue(&self, issue: &HealerIssue) -> ReconcileResult {
         // If we can't extract a resource name, we can't check status
         let Some(resource_name) = &issue.resource_name else {
             return ReconcileResult::Unknown {
@@ -270,67 +213,46 @@ impl IssueReconciler {
         match issue.alert_type.as_deref() {
             Some("A2" | "A7" | "a2" | "a7") => {
                 // Pod-based alerts - check if pod still exists and is still failing
-                self.check_pod_status(resource_name)
+                self.check_pod_status(resource_name).await
             }
             Some("A9" | "a9") => {
                 // CodeRun-based alerts - check if CodeRun still exists and is stuck
-                self.check_coderun_status(resource_name)
+                self.check_coderun_status(resource_name).await
             }
             Some(alert_type) if alert_type.starts_with("CI") || alert_type.contains("ci") => {
                 // CI failure alerts - check if there's been a successful run since
-                self.check_ci_status(resource_name)
+                self.check_ci_status(resource_name, issue.created_at).await
             }
             _ => {
                 // For unknown alert types, check if pod exists
                 // If no pod/workflow exists with this name, likely resolved
-                self.check_resource_exists(resource_name)
+                self.check_resource_exists(resource_name).await
             }
         }
     }
 
     /// Check pod status for A2/A7 alerts.
-    fn check_pod_status(&self, pod_name: &str) -> ReconcileResult {
-        // Check if pod exists
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "pod",
-                pod_name,
-                "-n",
-                &self.config.namespace,
-                "-o",
-                "jsonpath={.status.phase}",
-            ])
-            .output();
-
-        let output = match output {
-            Ok(o) => o,
+    async fn check_pod_status(&self, pod_name: &str) -> ReconcileResult {
+        let pod = match self.cluster.get_pod(&self.config.namespace, pod_name).await {
+            Ok(Some(pod)) => pod,
+            // Pod not found = resolved
+            Ok(None) => {
+                return ReconcileResult::Resolved {
+                    reason: format!("Pod '{pod_name}' no longer exists"),
+                }
+            }
             Err(e) => {
                 return ReconcileResult::Unknown {
-                    reason: format!("Failed to query pod status: {e}"),
+                    reason: format!("Failed to query pod status: {e:#}"),
                 }
             }
         };
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            // Pod not found = resolved
-            if stderr.contains("NotFound") || stderr.contains("not found") {
-                return ReconcileResult::Resolved {
-                    reason: format!("Pod '{pod_name}' no longer exists"),
-                };
-            }
-            return ReconcileResult::Unknown {
-                reason: format!("kubectl get pod failed: {stderr}"),
-            };
-        }
-
-        let phase = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-        match phase.as_str() {
+        let phase = pod.phase.as_str();
+        match phase {
             "Running" => {
-                // Pod is running - check if containers are healthy
-                if self.are_containers_healthy(pod_name) {
+                // Pod is running - check if all containers are healthy
+                if pod.container_statuses.iter().all(|c| c.ready) {
                     ReconcileResult::Resolved {
                         reason: format!(
                             "Pod '{pod_name}' is now healthy (Running with all containers ready)"
@@ -348,80 +270,34 @@ impl IssueReconciler {
             "Failed" | "Error" => ReconcileResult::StillActive {
                 reason: format!("Pod '{pod_name}' is in {phase} state"),
             },
-            "" => {
-                // Empty phase might mean pod was deleted
-                ReconcileResult::Resolved {
-                    reason: format!("Pod '{pod_name}' no longer exists"),
-                }
-            }
             _ => ReconcileResult::Unknown {
                 reason: format!("Pod '{pod_name}' in unexpected phase: {phase}"),
             },
         }
     }
 
-    /// Check if all containers in a pod are healthy.
-    fn are_containers_healthy(&self, pod_name: &str) -> bool {
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "pod",
-                pod_name,
-                "-n",
-                &self.config.namespace,
-                "-o",
-                "jsonpath={.status.containerStatuses[*].ready}",
-            ])
-            .output();
-
-        let output = match output {
-            Ok(o) if o.status.success() => o,
-            _ => return false,
-        };
-
-        let ready_statuses = String::from_utf8_lossy(&output.stdout);
-        // All containers should be "true"
-        ready_statuses.split_whitespace().all(|s| s == "true")
-    }
-
     /// Check `CodeRun` status for A9 alerts.
-    fn check_coderun_status(&self, coderun_name: &str) -> ReconcileResult {
-        let output = Command::new("kubectl")
-            .args([
-                "get",
-                "coderun",
-                coderun_name,
-                "-n",
-                &self.config.namespace,
-                "-o",
-                "jsonpath={.status.phase}",
-            ])
-            .output();
-
-        let output = match output {
-            Ok(o) => o,
+    async fn check_coderun_status(&self, coderun_name: &str) -> ReconcileResult {
+        let coderun = match self
+            .cluster
+            .get_coderun(&self.config.namespace, coderun_name)
+            .await
+        {
+            Ok(Some(coderun)) => coderun,
+            Ok(None) => {
+                return ReconcileResult::Resolved {
+                    reason: format!("CodeRun '{coderun_name}' no longer exists"),
+                }
+            }
             Err(e) => {
                 return ReconcileResult::Unknown {
-                    reason: format!("Failed to query CodeRun status: {e}"),
+                    reason: format!("Failed to query CodeRun status: {e:#}"),
                 }
             }
         };
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("NotFound") || stderr.contains("not found") {
-                return ReconcileResult::Resolved {
-                    reason: format!("CodeRun '{coderun_name}' no longer exists"),
-                };
-            }
-            return ReconcileResult::Unknown {
-                reason: format!("kubectl get coderun failed: {stderr}"),
-            };
-        }
-
-        let phase = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-        match phase.as_str() {
+        let phase = coderun.phase.as_str();
+        match phase {
             "Succeeded" | "Completed" => ReconcileResult::Resolved {
                 reason: format!("CodeRun '{coderun_name}' completed successfully"),
             },
@@ -431,9 +307,6 @@ impl IssueReconciler {
             "Running" | "Pending" => ReconcileResult::StillActive {
                 reason: format!("CodeRun '{coderun_name}' still in {phase} state"),
             },
-            "" => ReconcileResult::Resolved {
-                reason: format!("CodeRun '{coderun_name}' no longer exists"),
-            },
             _ => ReconcileResult::Unknown {
                 reason: format!("CodeRun '{coderun_name}' in unexpected phase: {phase}"),
             },
@@ -441,38 +314,25 @@ impl IssueReconciler {
     }
 
     /// Check CI status - look for successful workflow runs since issue creation.
-    fn check_ci_status(&self, workflow_name: &str) -> ReconcileResult {
-        // For CI failures, we'd need to check if there's been a successful run
-        // This is more complex - for now, just check if the workflow exists
-        let output = Command::new("gh")
-            .args([
-                "run",
-                "list",
-                "--repo",
-                &self.config.repository,
-                "--workflow",
-                workflow_name,
-                "--status",
-                "success",
-                "--limit",
-                "1",
-                "--json",
-                "createdAt",
-            ])
-            .output();
-
-        let output = match output {
-            Ok(o) if o.status.success() => o,
-            _ => {
+    async fn check_ci_status(
+        &self,
+        workflow_name: &str,
+        issue_created_at: DateTime<Utc>,
+    ) -> ReconcileResult {
+        let runs = match self
+            .github
+            .list_workflow_runs(&self.config.repository, workflow_name, Some("success"), 1)
+            .await
+        {
+            Ok(runs) => runs,
+            Err(_) => {
                 return ReconcileResult::Unknown {
                     reason: "Could not query workflow runs".to_string(),
                 }
             }
         };
 
-        let json_str = String::from_utf8_lossy(&output.stdout);
-        if json_str.contains("createdAt") {
-            // There's been a successful run
+        if runs.iter().any(|run| run.created_at >= issue_created_at) {
             ReconcileResult::Resolved {
                 reason: format!(
                     "Workflow '{workflow_name}' has had successful runs since issue creation"
@@ -486,55 +346,19 @@ impl IssueReconciler {
     }
 
     /// Generic check if a resource exists (fallback).
-    fn check_resource_exists(&self, resource_name: &str) -> ReconcileResult {
-        // Try pod first
-        let pod_output = Command::new("kubectl")
-            .args([
-                "get",
-                "pod",
-                resource_name,
-                "-n",
-                &self.config.namespace,
-                "--ignore-not-found",
-                "-o",
-                "name",
-            ])
-            .output();
-
-        if let Ok(output) = pod_output {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                if stdout.trim().is_empty() {
-                    return ReconcileResult::Resolved {
-                        reason: format!("Resource '{resource_name}' no longer exists"),
-                    };
-                }
-            }
-        }
+    async fn check_resource_exists(&self, resource_name: &str) -> ReconcileResult {
+        let namespace = &self.config.namespace;
 
-        // Try workflow
-        let wf_output = Command::new("kubectl")
-            .args([
-                "get",
-                "workflow",
-                resource_name,
-                "-n",
-                &self.config.namespace,
-                "--ignore-not-found",
-                "-o",
-                "name",
-            ])
-            .output();
-
-        if let Ok(output) = wf_output {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                if stdout.trim().is_empty() {
-                    return ReconcileResult::Resolved {
-                        reason: format!("Resource '{resource_name}' no longer exists"),
-                    };
-                }
-            }
+        // Try pod first, then workflow
+        if let Ok(None) = self.cluster.get_pod(namespace, resource_name).await {
+            return ReconcileResult::Resolved {
+                reason: format!("Resource '{resource_name}' no longer exists"),
+            };
+        }
+        if let Ok(false) = self.cluster.workflow_exists(namespace, resource_name).await {
+            return ReconcileResult::Resolved {
+                reason: format!("Resource '{resource_name}' no longer exists"),
+            };
         }
 
         ReconcileResult::Unknown {
@@ -543,7 +367,7 @@ impl IssueReconciler {
     }
 
     /// Close an issue with an auto-close comment.
-    fn close_issue(&self, issue: &HealerIssue, reason: &str) -> Result<()> {
+    async fn close_issue(&self, issue: &HealerIssue, reason: &str) -> Result<()> {
         // Add comment explaining why we're closing
         let comment = format!(
             "🤖 **Auto-closed by Healer Reconciler**\n\n\
@@ -552,51 +376,42 @@ impl IssueReconciler {
              If this issue was closed in error, please reopen it."
         );
 
-        let comment_output = Command::new("gh")
-            .args([
-                "issue",
-                "comment",
-                &issue.number.to_string(),
-                "--repo",
-                &self.config.repository,
-                "--body",
-                &comment,
-            ])
-            .output()
-            .context("Failed to add close comment")?;
-
-        if !comment_output.status.success() {
-            let stderr = String::from_utf8_lossy(&comment_output.stderr);
-            warn!(
-                "Failed to add comment to issue #{}: {}",
-                issue.number, stderr
-            );
+        if let Err(e) = self
+            .github
+            .comment(&self.config.repository, issue.number, &comment)
+            .await
+        {
+            warn!("Failed to add comment to issue #{}: {}", issue.number, e);
         }
 
-        // Close the issue
-        let close_output = Command::new("gh")
-            .args([
-                "issue",
-                "close",
-                &issue.number.to_string(),
-                "--repo",
-                &self.config.repository,
-                "--reason",
-                "completed",
-            ])
-            .output()
-            .context("Failed to close issue")?;
-
-        if !close_output.status.success() {
-            let stderr = String::from_utf8_lossy(&close_output.stderr);
-            anyhow::bail!("gh issue close failed: {stderr}");
-        }
+        self.github
+            .close_issue(&self.config.repository, issue.number)
+            .await?;
 
         info!("Closed issue #{}: {}", issue.number, issue.title);
         Ok(())
     }
 }
 
+impl From<Issue> for HealerIssue {
+    fn from(issue: Issue) -> Self {
+        // Extract alert type from title: "[HEAL-A2]..." or labels
+        let alert_type = extract_alert_type(&issue.title, &issue.labels);
+
+        // Extract resource name from title
+        let resource_name = extract_pod_from_title(&issue.title);
+
+        Self {
+            number: issue.number,
+            title: issue.title,
+            created_at: issue.created_at,
+            labels: issue.labels,
+            alert_type,
+            resource_name,
+        }
+    }
+}
+
 /// Extract alert type from issue title or labels.
 ///
 /// Titles follow patterns like:
@@ -662,6 +477,136 @@ pub fn format_report_text(report: &ReconcileReport) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clients::{FakeCluster, FakeGitHub, WorkflowRun};
+    use crate::k8s::{CodeRun, ContainerState, ContainerStatus, Pod};
+
+    const REPO: &str = "5dlabs/cto";
+
+    fn issue(number: u64, title: &str, created_at: DateTime<Utc>) -> Issue {
+        Issue {
+            number,
+            title: title.to_string(),
+            body: String::new(),
+            labels: vec!["heal".to_string()],
+            open: true,
+            created_at,
+        }
+    }
+
+    fn pod(name: &str, phase: &str, ready: bool) -> Pod {
+        Pod {
+            name: name.to_string(),
+            namespace: "cto".to_string(),
+            phase: phase.to_string(),
+            container_statuses: vec![ContainerStatus {
+                name: "main".to_string(),
+                ready,
+                state: ContainerState::Running { started_at: None },
+                restart_count: 0,
+            }],
+            ..Pod::default()
+        }
+    }
+
+    fn reconciler(
+        cluster: FakeCluster,
+        github: &Arc<FakeGitHub>,
+        dry_run: bool,
+    ) -> IssueReconciler {
+        IssueReconciler::new(
+            ReconcileConfig {
+                dry_run,
+                ..ReconcileConfig::default()
+            },
+            Arc::new(cluster),
+            github.clone(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_closes_resolved_issues() {
+        let now = Utc::now();
+        let github = Arc::new(
+            FakeGitHub::new()
+                .with_issue(REPO, issue(1, "[HEAL-A7] Pod Failure: gone-pod", now))
+                .with_issue(REPO, issue(2, "[HEAL-A7] Pod Failure: failing-pod", now))
+                .with_issue(REPO, issue(3, "[HEAL-A2] Silent Failure: healthy-pod", now))
+                .with_issue(REPO, issue(4, "[HEAL-A9] Stuck CodeRun: stuck-run", now))
+                .with_issue(
+                    REPO,
+                    issue(
+                        5,
+                        "[CI Failure] build: ci.yaml",
+                        now - chrono::Duration::hours(1),
+                    ),
+                )
+                .with_issue(REPO, issue(6, "No resource in this title", now))
+                .with_workflow_run(
+                    REPO,
+                    "ci.yaml",
+                    WorkflowRun {
+                        id: 10,
+                        workflow_name: "build".to_string(),
+                        head_branch: "main".to_string(),
+                        head_sha: "abc".to_string(),
+                        status: "completed".to_string(),
+                        conclusion: Some("success".to_string()),
+                        html_url: String::new(),
+                        created_at: now,
+                    },
+                ),
+        );
+        let cluster = FakeCluster::new()
+            .with_pod(pod("failing-pod", "Failed", false))
+            .with_pod(pod("healthy-pod", "Running", true))
+            .with_coderun(CodeRun {
+                name: "stuck-run".to_string(),
+                namespace: "cto".to_string(),
+                phase: "Running".to_string(),
+                ..CodeRun::default()
+            });
+
+        let report = reconciler(cluster, &github, false)
+            .reconcile()
+            .await
+            .unwrap();
+
+        assert_eq!(report.issues_checked, 6);
+        assert_eq!(report.issues_still_active, 2);
+        assert_eq!(report.issues_unknown, 1);
+        let mut closed: Vec<u64> = report.closed_issues.iter().map(|c| c.number).collect();
+        closed.sort_unstable();
+        assert_eq!(closed, vec![1, 3, 5]);
+
+        let open: Vec<u64> = github
+            .issues(REPO)
+            .iter()
+            .filter(|i| i.open)
+            .map(|i| i.number)
+            .collect();
+        assert_eq!(open, vec![2, 4, 6]);
+        assert_eq!(github.comments().len(), 3);
+        assert!(github.comments()[0]
+            .body
+            .contains("Auto-closed by Healer Reconciler"));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_dry_run_leaves_issues_open() {
+        let github = Arc::new(FakeGitHub::new().with_issue(
+            REPO,
+            issue(1, "[HEAL-A7] Pod Failure: gone-pod", Utc::now()),
+        ));
+
+        let report = reconciler(FakeCluster::new(), &github, true)
+            .reconcile()
+            .await
+            .unwrap();
+
+        assert_eq!(report.issues_closed, 1);
+        assert!(github.issues(REPO)[0].open);
+        assert!(github.comments().is_empty());
+    }
 
     #[test]
     fn test_extract_alert_type_from_title() {
@@ -0,0 +1,440 @@
+//! Template frequency tracking and anomaly detection.
+//!
+//! Each scan is one window. Error lines are mined into templates
+//! ([`super::drain`]), counted per window and compared against each
+//! template's history. A template is anomalous when it has never been seen
+//! before (after warm-up) or when its count jumps well above its average.
+//! Templates that stop appearing are forgotten after `idle_windows`, and the
+//! least recently seen go first past `max_templates`, so persisted state
+//! stays bounded.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use super::drain::{DrainConfig, TemplateMiner};
+use crate::loki::LogEntry;
+
+/// Loki labels that identify the `CodeRun` a log line belongs to.
+const CODERUN_LABELS: &[&str] = &["coderun", "cleanup_cto_dev_run"];
+
+/// Configuration for template anomaly detection.
+#[derive(Debug, Clone)]
+pub struct AnomalyConfig {
+    /// Template miner tuning
+    pub drain: DrainConfig,
+    /// Windows of per-template history kept for the baseline
+    pub history_windows: usize,
+    /// Windows without a line before a template is forgotten (at most
+    /// `history_windows`)
+    pub idle_windows: usize,
+    /// Templates kept; the least recently seen are forgotten first
+    pub max_templates: usize,
+    /// Windows observed before new templates raise alerts
+    pub warmup_windows: u64,
+    /// Count must exceed baseline by this factor to count as a spike
+    pub spike_factor: f64,
+    /// Minimum count in a window for a spike
+    pub spike_min_count: u64,
+    /// Exemplar lines kept per anomaly
+    pub max_exemplars: usize,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            drain: DrainConfig::default(),
+            history_windows: 24,
+            idle_windows: 24,
+            max_templates: 1000,
+            warmup_windows: 1,
+            spike_factor: 3.0,
+            spike_min_count: 10,
+            max_exemplars: 3,
+        }
+    }
+}
+
+/// Why a template was flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// First time this template has been seen
+    New,
+    /// Count jumped well above the template's baseline
+    Spike,
+}
+
+/// A new or suddenly frequent log template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateAnomaly {
+    /// Why the template was flagged
+    pub kind: AnomalyKind,
+    /// Stable template ID
+    pub template_id: u64,
+    /// Template with `<*>` for variable tokens
+    pub template: String,
+    /// Lines matching the template in this window
+    pub count: u64,
+    /// Average count per window over the template's history
+    pub baseline: f64,
+    /// Sample raw lines
+    pub exemplars: Vec<String>,
+    /// Namespaces the lines came from
+    pub namespaces: Vec<String>,
+    /// Pods that logged the template
+    pub affected_pods: Vec<String>,
+    /// `CodeRun`s that logged the template
+    pub affected_coderuns: Vec<String>,
+}
+
+/// Per-template history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TemplateStats {
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    total: u64,
+    /// Counts for previous windows, oldest first
+    history: VecDeque<u64>,
+}
+
+/// Persistable tracker state: the mined templates and their history.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TemplateState {
+    miner: TemplateMiner,
+    stats: HashMap<u64, TemplateStats>,
+    windows_observed: u64,
+}
+
+/// Lines seen for one template in the current window.
+#[derive(Default)]
+struct WindowCounts {
+    count: u64,
+    exemplars: Vec<String>,
+    namespaces: BTreeSet<String>,
+    pods: BTreeSet<String>,
+    coderuns: BTreeSet<String>,
+}
+
+/// Tracks template frequencies across scan windows.
+#[derive(Debug, Default)]
+pub struct TemplateTracker {
+    config: AnomalyConfig,
+    state: TemplateState,
+}
+
+impl TemplateTracker {
+    /// Create an empty tracker.
+    #[must_use]
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self::with_state(config, TemplateState::default())
+    }
+
+    /// Resume from persisted state.
+    #[must_use]
+    pub fn with_state(config: AnomalyConfig, mut state: TemplateState) -> Self {
+        state.miner.set_config(config.drain.clone());
+        Self { config, state }
+    }
+
+    /// Current state, for persisting between runs.
+    #[must_use]
+    pub fn state(&self) -> &TemplateState {
+        &self.state
+    }
+
+    /// Number of known templates.
+    #[must_use]
+    pub fn template_count(&self) -> usize {
+        self.state.miner.len()
+    }
+
+    /// Mine one window of error entries and return anomalous templates,
+    /// new templates first, then by count.
+    #[allow(clippy::cast_precision_loss)] // Window counts are small
+    pub fn observe_window(&mut self, entries: &[LogEntry]) -> Vec<TemplateAnomaly> {
+        let now = Utc::now();
+        let mut window: HashMap<u64, WindowCounts> = HashMap::new();
+
+        for entry in entries {
+            let Some(matched) = self.state.miner.add_line(&entry.line) else {
+                continue;
+            };
+            let counts = window.entry(matched.cluster_id).or_default();
+            counts.count += 1;
+            if counts.exemplars.len() < self.config.max_exemplars {
+                counts
+                    .exemplars
+                    .push(super::truncate_line(&entry.line, 200));
+            }
+            if let Some(ns) = label(entry, &["namespace", "service_namespace"]) {
+                counts.namespaces.insert(ns.to_string());
+            }
+            if let Some(pod) = label(entry, &["pod", "pod_name"]) {
+                counts.pods.insert(pod.to_string());
+            }
+            if let Some(coderun) = label(entry, CODERUN_LABELS) {
+                counts.coderuns.insert(coderun.to_string());
+            }
+        }
+
+        let warmed_up = self.state.windows_observed >= self.config.warmup_windows;
+        let mut anomalies = Vec::new();
+
+        for (&id, counts) in &window {
+            let kind = match self.state.stats.get(&id) {
+                None => warmed_up.then_some(AnomalyKind::New),
+                Some(stats) => {
+                    let baseline = mean(&stats.history);
+                    (counts.count >= self.config.spike_min_count
+                        && counts.count as f64 >= self.config.spike_factor * baseline.max(1.0))
+                    .then_some(AnomalyKind::Spike)
+                }
+            };
+            let Some(kind) = kind else { continue };
+
+            anomalies.push(TemplateAnomaly {
+                kind,
+                template_id: id,
+                template: self
+                    .state
+                    .miner
+                    .cluster(id)
+                    .map(super::drain::LogCluster::template)
+                    .unwrap_or_default(),
+                count: counts.count,
+                baseline: self.state.stats.get(&id).map_or(0.0, |s| mean(&s.history)),
+                exemplars: counts.exemplars.clone(),
+                namespaces: counts.namespaces.iter().cloned().collect(),
+                affected_pods: counts.pods.iter().cloned().collect(),
+                affected_coderuns: counts.coderuns.iter().cloned().collect(),
+            });
+        }
+
+        // Record this window, including zeros for templates that went quiet
+        for (&id, counts) in &window {
+            let stats = self.state.stats.entry(id).or_insert_with(|| TemplateStats {
+                first_seen: now,
+                last_seen: now,
+                total: 0,
+                history: VecDeque::new(),
+            });
+            stats.last_seen = now;
+            stats.total += counts.count;
+        }
+        for (id, stats) in &mut self.state.stats {
+            stats
+                .history
+                .push_back(window.get(id).map_or(0, |c| c.count));
+            while stats.history.len() > self.config.history_windows {
+                stats.history.pop_front();
+            }
+        }
+        self.state.windows_observed += 1;
+        self.evict();
+
+        anomalies.sort_by(|a, b| {
+            (a.kind != AnomalyKind::New)
+                .cmp(&(b.kind != AnomalyKind::New))
+                .then(b.count.cmp(&a.count))
+                .then(a.template_id.cmp(&b.template_id))
+        });
+        anomalies
+    }
+}
+
+impl TemplateTracker {
+    /// Forget templates idle for `idle_windows`, then the least recently
+    /// seen beyond `max_templates`.
+    fn evict(&mut self) {
+        let idle_windows = self.config.idle_windows.min(self.config.history_windows);
+        let mut evicted: HashSet<u64> = self
+            .state
+            .stats
+            .iter()
+            .filter(|(_, stats)| {
+                stats.history.len() >= idle_windows
+                    && stats
+                        .history
+                        .iter()
+                        .rev()
+                        .take(idle_windows)
+                        .all(|&c| c == 0)
+            })
+            .map(|(&id, _)| id)
+            .collect();
+
+        let mut remaining: Vec<(&u64, &TemplateStats)> = self
+            .state
+            .stats
+            .iter()
+            .filter(|(id, _)| !evicted.contains(id))
+            .collect();
+        if remaining.len() > self.config.max_templates {
+            remaining.sort_by_key(|(&id, stats)| (stats.last_seen, stats.total, id));
+            let excess = remaining.len() - self.config.max_templates;
+            evicted.extend(remaining.iter().take(excess).map(|(&id, _)| id));
+        }
+
+        self.state.stats.retain(|id, _| !evicted.contains(id));
+        self.state.miner.remove(&evicted);
+    }
+}
+
+fn label<'a>(entry: &'a LogEntry, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|k| entry.labels.get(*k))
+        .map(String::as_str)
+        .filter(|v| !v.is_empty())
+}
+
+#[allow(clippy::cast_precision_loss)] // Window counts are small
+fn mean(history: &VecDeque<u64>) -> f64 {
+    if history.is_empty() {
+        0.0
+    } else {
+        history.iter().sum::<u64>() as f64 / history.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse a fixture of `<namespace>/<pod>[ coderun=<name>] | <line>` rows.
+    fn fixture_entries(fixture: &str) -> Vec<LogEntry> {
+        fixture
+            .lines()
+            .filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+            .map(|row| {
+                let (source, line) = row.split_once(" | ").expect("fixture row");
+                let mut parts = source.split_whitespace();
+                let (namespace, pod) = parts
+                    .next()
+                    .and_then(|s| s.split_once('/'))
+                    .expect("namespace/pod");
+                let mut labels = HashMap::from([
+                    ("namespace".to_string(), namespace.to_string()),
+                    ("pod".to_string(), pod.to_string()),
+                ]);
+                if let Some(coderun) = parts.next().and_then(|p| p.strip_prefix("coderun=")) {
+                    labels.insert("coderun".to_string(), coderun.to_string());
+                }
+                LogEntry {
+                    timestamp: Utc::now(),
+                    line: line.to_string(),
+                    labels,
+                }
+            })
+            .collect()
+    }
+
+    fn baseline() -> Vec<LogEntry> {
+        fixture_entries(include_str!("../../tests/fixtures/logs/baseline.log"))
+    }
+
+    fn incident() -> Vec<LogEntry> {
+        fixture_entries(include_str!("../../tests/fixtures/logs/incident.log"))
+    }
+
+    #[test]
+    fn test_warmup_window_raises_no_alerts() {
+        let mut tracker = TemplateTracker::new(AnomalyConfig::default());
+        assert!(tracker.observe_window(&baseline()).is_empty());
+        assert!(tracker.template_count() > 0);
+    }
+
+    #[test]
+    fn test_steady_traffic_is_not_anomalous() {
+        let mut tracker = TemplateTracker::new(AnomalyConfig::default());
+        for _ in 0..3 {
+            assert!(tracker.observe_window(&baseline()).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_incident_flags_new_and_spiking_templates() {
+        let mut tracker = TemplateTracker::new(AnomalyConfig::default());
+        tracker.observe_window(&baseline());
+        tracker.observe_window(&baseline());
+
+        let anomalies = tracker.observe_window(&incident());
+        assert_eq!(anomalies.len(), 2, "{anomalies:#?}");
+
+        let new = &anomalies[0];
+        assert_eq!(new.kind, AnomalyKind::New);
+        assert_eq!(
+            new.template,
+            "ERROR failed to mount workspace PVC <*> volume node affinity conflict"
+        );
+        assert_eq!(new.count, 3);
+        assert_eq!(new.exemplars.len(), 3);
+        assert_eq!(
+            new.affected_coderuns,
+            vec!["play-task-4-rex", "play-task-5-blaze"]
+        );
+        assert_eq!(new.namespaces, vec!["cto"]);
+
+        let spike = &anomalies[1];
+        assert_eq!(spike.kind, AnomalyKind::Spike);
+        assert!(spike.template.starts_with("ERROR upstream request to"));
+        assert_eq!(spike.count, 12);
+        assert!((spike.baseline - 1.0).abs() < f64::EPSILON);
+        assert_eq!(
+            spike.affected_pods,
+            vec!["cto-tools-7d9f8-abcde", "cto-tools-7d9f8-fghij"]
+        );
+    }
+
+    #[test]
+    fn test_idle_and_excess_templates_are_forgotten() {
+        let entries = |lines: &[&str]| -> Vec<LogEntry> {
+            lines
+                .iter()
+                .map(|line| LogEntry {
+                    timestamp: Utc::now(),
+                    line: (*line).to_string(),
+                    labels: HashMap::new(),
+                })
+                .collect()
+        };
+        let config = AnomalyConfig {
+            idle_windows: 2,
+            max_templates: 2,
+            ..AnomalyConfig::default()
+        };
+        let mut tracker = TemplateTracker::new(config);
+
+        tracker.observe_window(&entries(&["ERROR disk full", "ERROR webhook rejected"]));
+        tracker.observe_window(&entries(&["ERROR disk full"]));
+        assert_eq!(tracker.template_count(), 2);
+        // Two windows without the webhook line: forgotten, and new again
+        tracker.observe_window(&entries(&["ERROR disk full"]));
+        assert_eq!(tracker.template_count(), 1);
+        let anomalies = tracker.observe_window(&entries(&["ERROR webhook rejected"]));
+        assert_eq!(anomalies[0].kind, AnomalyKind::New);
+
+        // Past the cap, the least recently seen template goes
+        tracker.observe_window(&entries(&["panic in worker thread main"]));
+        assert_eq!(tracker.template_count(), 2);
+        assert!(tracker
+            .state()
+            .miner
+            .clusters()
+            .iter()
+            .all(|c| c.template() != "ERROR disk full"));
+    }
+
+    #[test]
+    fn test_state_round_trips_between_runs() {
+        let mut tracker = TemplateTracker::new(AnomalyConfig::default());
+        tracker.observe_window(&baseline());
+
+        let json = serde_json::to_string(tracker.state()).unwrap();
+        let state: TemplateState = serde_json::from_str(&json).unwrap();
+        let mut resumed = TemplateTracker::with_state(AnomalyConfig::default(), state);
+
+        assert!(resumed.observe_window(&baseline()).is_empty());
+        assert_eq!(resumed.template_count(), tracker.template_count());
+    }
+}
@@ -0,0 +1,390 @@
+//! Drain-style log template mining.
+//!
+//! Lines are masked (numbers, IDs, addresses become `<*>`), tokenized on
+//! whitespace and routed through a fixed-depth prefix tree keyed by token
+//! count and leading tokens. Each leaf holds clusters; a line joins the most
+//! similar cluster above the threshold, generalizing differing tokens to
+//! `<*>`, or starts a new one. Cluster IDs are stable for the miner's
+//! lifetime, so templates can be tracked across scans even as they widen.
+//!
+//! Based on He et al., "Drain: An Online Log Parsing Approach with Fixed
+//! Depth Tree" (ICWS 2017).
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Placeholder for a variable token.
+pub const WILDCARD: &str = "<*>";
+
+/// Patterns for variable parts of a line, applied in order.
+const MASK_PATTERNS: &[&str] = &[
+    // UUIDs
+    r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+    // RFC 3339 timestamps
+    r"\b\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?\b",
+    // IPv4 addresses with optional port
+    r"\b\d{1,3}(?:\.\d{1,3}){3}(?::\d+)?\b",
+    // Hex literals and long hex strings (commit SHAs, hashes)
+    r"\b0x[0-9a-fA-F]+\b",
+    r"\b[0-9a-fA-F]{12,}\b",
+    // Numbers, optionally with a unit suffix (30s, 1.5ms, 512Mi)
+    r"\b\d+(?:\.\d+)?[a-zA-Z]{0,3}\b",
+];
+
+fn get_mask_regexes() -> &'static Vec<Regex> {
+    static REGEXES: OnceLock<Vec<Regex>> = OnceLock::new();
+    REGEXES.get_or_init(|| {
+        MASK_PATTERNS
+            .iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect()
+    })
+}
+
+/// Replace variable parts of a line with [`WILDCARD`].
+#[must_use]
+pub fn mask_line(line: &str) -> String {
+    get_mask_regexes().iter().fold(line.to_string(), |acc, re| {
+        re.replace_all(&acc, WILDCARD).into_owned()
+    })
+}
+
+/// Tuning for the template miner.
+#[derive(Debug, Clone)]
+pub struct DrainConfig {
+    /// Tree depth, including the token-count level and the leaf level
+    pub depth: usize,
+    /// Minimum fraction of matching tokens to join an existing cluster
+    pub similarity_threshold: f64,
+    /// Maximum children per internal node before tokens fall into `<*>`
+    pub max_children: usize,
+}
+
+impl Default for DrainConfig {
+    fn default() -> Self {
+        Self {
+            depth: 4,
+            similarity_threshold: 0.4,
+            max_children: 100,
+        }
+    }
+}
+
+/// A cluster of log lines sharing one template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogCluster {
+    /// Stable cluster ID
+    pub id: u64,
+    /// Template tokens, with [`WILDCARD`] for variable positions
+    pub tokens: Vec<String>,
+    /// Number of lines matched
+    pub size: u64,
+}
+
+impl LogCluster {
+    /// The template as a single line.
+    #[must_use]
+    pub fn template(&self) -> String {
+        self.tokens.join(" ")
+    }
+}
+
+/// How a line changed the miner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusterChange {
+    /// The line started a new cluster
+    Created,
+    /// The line joined a cluster and widened its template
+    Generalized,
+    /// The line matched an existing template as-is
+    Unchanged,
+}
+
+/// Result of adding a line to the miner.
+#[derive(Debug, Clone)]
+pub struct TemplateMatch {
+    /// Cluster the line was assigned to
+    pub cluster_id: u64,
+    /// Template after the line was added
+    pub template: String,
+    /// Effect on the cluster
+    pub change: ClusterChange,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct Node {
+    children: HashMap<String, Node>,
+    cluster_ids: Vec<u64>,
+}
+
+/// Online log template miner.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TemplateMiner {
+    #[serde(skip)]
+    config: DrainConfig,
+    /// First tree level, keyed by token count
+    roots: HashMap<usize, Node>,
+    clusters: HashMap<u64, LogCluster>,
+    next_id: u64,
+}
+
+impl TemplateMiner {
+    /// Create a miner with the given tuning.
+    #[must_use]
+    pub fn new(config: DrainConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Replace the tuning, e.g. after restoring a persisted miner.
+    pub fn set_config(&mut self, config: DrainConfig) {
+        self.config = config;
+    }
+
+    /// Number of clusters.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
+
+    /// Whether no lines have been added.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    /// Look up a cluster by ID.
+    #[must_use]
+    pub fn cluster(&self, id: u64) -> Option<&LogCluster> {
+        self.clusters.get(&id)
+    }
+
+    /// All clusters, largest first.
+    #[must_use]
+    pub fn clusters(&self) -> Vec<&LogCluster> {
+        let mut clusters: Vec<&LogCluster> = self.clusters.values().collect();
+        clusters.sort_by(|a, b| b.size.cmp(&a.size).then(a.id.cmp(&b.id)));
+        clusters
+    }
+
+    /// Add a line, returning the cluster it joined. Blank lines are ignored.
+    pub fn add_line(&mut self, line: &str) -> Option<TemplateMatch> {
+        let masked = mask_line(line);
+        let tokens: Vec<String> = masked.split_whitespace().map(String::from).collect();
+        if tokens.is_empty() {
+            return None;
+        }
+
+        if let Some(id) = self.find_cluster(&tokens) {
+            let cluster = self.clusters.get_mut(&id).expect("leaf references cluster");
+            cluster.size += 1;
+            let mut change = ClusterChange::Unchanged;
+            for (template, token) in cluster.tokens.iter_mut().zip(&tokens) {
+                if template != token && template != WILDCARD {
+                    *template = WILDCARD.to_string();
+                    change = ClusterChange::Generalized;
+                }
+            }
+            return Some(TemplateMatch {
+                cluster_id: id,
+                template: cluster.template(),
+                change,
+            });
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let cluster = LogCluster {
+            id,
+            tokens,
+            size: 1,
+        };
+        let template = cluster.template();
+        self.insert_into_tree(&cluster.tokens, id);
+        self.clusters.insert(id, cluster);
+        Some(TemplateMatch {
+            cluster_id: id,
+            template,
+            change: ClusterChange::Created,
+        })
+    }
+
+    /// Forget clusters, e.g. templates that stopped appearing. Their IDs are
+    /// not reused.
+    pub fn remove(&mut self, ids: &HashSet<u64>) {
+        if ids.is_empty() {
+            return;
+        }
+        self.clusters.retain(|id, _| !ids.contains(id));
+        self.roots.retain(|_, root| !prune_node(root, ids));
+    }
+
+    /// Number of token levels between the token-count level and the leaf.
+    fn prefix_depth(&self, token_count: usize) -> usize {
+        self.config.depth.saturating_sub(2).min(token_count)
+    }
+
+    fn find_cluster(&self, tokens: &[String]) -> Option<u64> {
+        let mut node = self.roots.get(&tokens.len())?;
+        for token in &tokens[..self.prefix_depth(tokens.len())] {
+            node = node
+                .children
+                .get(token)
+                .or_else(|| node.children.get(WILDCARD))?;
+        }
+
+        // Best similarity wins; ties go to the more specific template
+        node.cluster_ids
+            .iter()
+            .filter_map(|id| self.clusters.get(id))
+            .map(|cluster| {
+                let (similarity, wildcards) = similarity(&cluster.tokens, tokens);
+                (cluster.id, similarity, wildcards)
+            })
+            .filter(|(_, similarity, _)| *similarity >= self.config.similarity_threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.2.cmp(&a.2)))
+            .map(|(id, _, _)| id)
+    }
+
+    fn insert_into_tree(&mut self, tokens: &[String], id: u64) {
+        let depth = self.prefix_depth(tokens.len());
+        let max_children = self.config.max_children;
+        let mut node = self.roots.entry(tokens.len()).or_default();
+        for token in &tokens[..depth] {
+            let key = if token.chars().any(|c| c.is_ascii_digit())
+                || (!node.children.contains_key(token) && node.children.len() + 1 >= max_children)
+            {
+                WILDCARD
+            } else {
+                token.as_str()
+            };
+            node = node.children.entry(key.to_string()).or_default();
+        }
+        node.cluster_ids.push(id);
+    }
+}
+
+/// Drop `ids` from the leaves under `node` and remove emptied branches.
+/// Returns whether `node` is now empty.
+fn prune_node(node: &mut Node, ids: &HashSet<u64>) -> bool {
+    node.cluster_ids.retain(|id| !ids.contains(id));
+    node.children.retain(|_, child| !prune_node(child, ids));
+    node.cluster_ids.is_empty() && node.children.is_empty()
+}
+
+/// Fraction of template positions matching `tokens` exactly, and the number
+/// of wildcards in the template.
+#[allow(clippy::cast_precision_loss)] // Token counts are small
+fn similarity(template: &[String], tokens: &[String]) -> (f64, usize) {
+    let mut matching = 0usize;
+    let mut wildcards = 0usize;
+    for (t, token) in template.iter().zip(tokens) {
+        if t == WILDCARD {
+            wildcards += 1;
+        } else if t == token {
+            matching += 1;
+        }
+    }
+    (matching as f64 / template.len() as f64, wildcards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_line_replaces_variables() {
+        assert_eq!(
+            mask_line("timeout after 30s connecting to 10.0.0.12:8080"),
+            "timeout after <*> connecting to <*>"
+        );
+        assert_eq!(
+            mask_line("run 3f2b9c1e-0d4a-4b7e-9a51-2c6d8e0f1a2b failed at 2026-01-04T10:15:00Z"),
+            "run <*> failed at <*>"
+        );
+        assert_eq!(
+            mask_line("commit deadbeefcafe1234 missing"),
+            "commit <*> missing"
+        );
+    }
+
+    #[test]
+    fn test_similar_lines_share_a_cluster() {
+        let mut miner = TemplateMiner::new(DrainConfig::default());
+        let first = miner
+            .add_line("failed to reconcile CodeRun play-task-4-abc: connection refused")
+            .unwrap();
+        assert_eq!(first.change, ClusterChange::Created);
+
+        let second = miner
+            .add_line("failed to reconcile CodeRun atlas-guardian-xyz: connection refused")
+            .unwrap();
+        assert_eq!(second.cluster_id, first.cluster_id);
+        assert_eq!(second.change, ClusterChange::Generalized);
+        assert_eq!(
+            second.template,
+            "failed to reconcile CodeRun <*> connection refused"
+        );
+
+        let third = miner
+            .add_line("failed to reconcile CodeRun rex-docs-42: connection refused")
+            .unwrap();
+        assert_eq!(third.cluster_id, first.cluster_id);
+        assert_eq!(third.change, ClusterChange::Unchanged);
+        assert_eq!(miner.cluster(first.cluster_id).unwrap().size, 3);
+    }
+
+    #[test]
+    fn test_dissimilar_lines_get_separate_clusters() {
+        let mut miner = TemplateMiner::new(DrainConfig::default());
+        let a = miner.add_line("ERROR database pool exhausted").unwrap();
+        let b = miner.add_line("ERROR webhook signature invalid").unwrap();
+        let c = miner.add_line("panic in worker thread main").unwrap();
+        assert_ne!(a.cluster_id, b.cluster_id);
+        assert_ne!(a.cluster_id, c.cluster_id);
+        assert_eq!(miner.len(), 3);
+        assert!(miner.add_line("   ").is_none());
+    }
+
+    #[test]
+    fn test_removed_clusters_leave_the_tree() {
+        let mut miner = TemplateMiner::new(DrainConfig::default());
+        let gone = miner.add_line("ERROR pod 12 OOMKilled").unwrap().cluster_id;
+        let kept = miner.add_line("ERROR webhook signature invalid").unwrap();
+
+        miner.remove(&HashSet::from([gone]));
+        assert_eq!(miner.len(), 1);
+        assert!(miner.cluster(gone).is_none());
+        assert_eq!(
+            miner
+                .add_line("ERROR webhook signature invalid")
+                .unwrap()
+                .cluster_id,
+            kept.cluster_id
+        );
+        let recreated = miner.add_line("ERROR pod 99 OOMKilled").unwrap();
+        assert_eq!(recreated.change, ClusterChange::Created);
+        assert_ne!(recreated.cluster_id, gone);
+    }
+
+    #[test]
+    fn test_persisted_miner_keeps_cluster_ids() {
+        let mut miner = TemplateMiner::new(DrainConfig::default());
+        let id = miner.add_line("ERROR pod 12 OOMKilled").unwrap().cluster_id;
+        miner.add_line("ERROR webhook signature invalid");
+
+        let json = serde_json::to_string(&miner).unwrap();
+        let mut restored: TemplateMiner = serde_json::from_str(&json).unwrap();
+        restored.set_config(DrainConfig::default());
+
+        let matched = restored.add_line("ERROR pod 99 OOMKilled").unwrap();
+        assert_eq!(matched.cluster_id, id);
+        assert_eq!(matched.change, ClusterChange::Unchanged);
+        let created = restored.add_line("brand new failure mode").unwrap();
+        assert_eq!(created.cluster_id, 2);
+    }
+}
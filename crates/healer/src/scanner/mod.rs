@@ -7,15 +7,24 @@
 //! - INFO-level messages containing the word "error" as a command name
 //! - JSON field names like "errorMessages" regardless of value
 //! - Empty error arrays that indicate success, not failure
+//!
+//! Beyond the fixed patterns, error lines are mined into templates
+//! ([`drain`]) and tracked across scans ([`anomaly`]) so previously unseen or
+//! suddenly frequent error types are surfaced as [`TemplateAnomaly`]s.
+
+pub mod anomaly;
+pub mod drain;
 
 use anyhow::Result;
 use chrono::{DateTime, Duration, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 use tracing::{debug, info};
 
+pub use anomaly::{AnomalyConfig, AnomalyKind, TemplateAnomaly, TemplateState, TemplateTracker};
+
 use crate::loki::{LogEntry, LokiClient};
 
 /// Patterns that indicate an actual error log level (not just keyword matches)
@@ -209,6 +218,8 @@ pub struct ScannerConfig {
     pub warn_threshold: u32,
     /// Whether to include resolved (info-level) entries
     pub include_info: bool,
+    /// Log template anomaly detection
+    pub anomaly: AnomalyConfig,
 }
 
 impl Default for ScannerConfig {
@@ -222,6 +233,7 @@ impl Default for ScannerConfig {
             error_threshold: 3,
             warn_threshold: 5,
             include_info: false,
+            anomaly: AnomalyConfig::default(),
         }
     }
 }
@@ -266,6 +278,9 @@ pub struct ScanReport {
     pub remediation_recommended: bool,
     /// Reason for recommendation
     pub recommendation_reason: Option<String>,
+    /// Error templates that are new or suddenly frequent
+    #[serde(default)]
+    pub template_anomalies: Vec<TemplateAnomaly>,
 }
 
 /// A candidate for automated remediation
@@ -466,22 +481,48 @@ fn truncate_line(line: &str, max_len: usize) -> String {
 pub struct LogScanner {
     loki: LokiClient,
     config: ScannerConfig,
+    templates: Mutex<TemplateTracker>,
 }
 
 impl LogScanner {
     /// Create a new log scanner with default configuration.
     #[must_use]
     pub fn new(loki: LokiClient) -> Self {
+        Self::with_config(loki, ScannerConfig::default())
+    }
+
+    /// Create a new log scanner with custom configuration.
+    #[must_use]
+    pub fn with_config(loki: LokiClient, config: ScannerConfig) -> Self {
+        let templates = Mutex::new(TemplateTracker::new(config.anomaly.clone()));
         Self {
             loki,
-            config: ScannerConfig::default(),
+            config,
+            templates,
         }
     }
 
-    /// Create a new log scanner with custom configuration.
+    /// Resume template tracking from state saved by a previous run.
     #[must_use]
-    pub fn with_config(loki: LokiClient, config: ScannerConfig) -> Self {
-        Self { loki, config }
+    pub fn with_template_state(self, state: TemplateState) -> Self {
+        let tracker = TemplateTracker::with_state(self.config.anomaly.clone(), state);
+        Self {
+            templates: Mutex::new(tracker),
+            ..self
+        }
+    }
+
+    /// Snapshot of the template tracker, for persisting between runs.
+    ///
+    /// # Panics
+    /// Panics if the tracker lock is poisoned.
+    #[must_use]
+    pub fn template_state(&self) -> TemplateState {
+        self.templates
+            .lock()
+            .expect("template tracker lock poisoned")
+            .state()
+            .clone()
     }
 
     /// Scan logs for errors and warnings in the given time window.
@@ -494,6 +535,9 @@ impl LogScanner {
     ///
     /// # Errors
     /// Returns an error if Loki queries fail.
+    ///
+    /// # Panics
+    /// Panics if the template tracker lock is poisoned.
     #[allow(clippy::cast_sign_loss, clippy::too_many_lines)] // Scan window seconds are always positive
     pub async fn scan(&self, window: Duration) -> Result<ScanReport> {
         let end = Utc::now();
@@ -509,6 +553,7 @@ impl LogScanner {
         let mut all_issues: HashMap<String, ServiceIssue> = HashMap::new();
         let mut total_errors = 0u32;
         let mut total_warnings = 0u32;
+        let mut window_errors: Vec<LogEntry> = Vec::new();
 
         for namespace in &self.config.namespaces {
             debug!("Scanning namespace: {}", namespace);
@@ -635,20 +680,28 @@ impl LogScanner {
 
             total_errors += u32::try_from(error_entries.len()).unwrap_or(u32::MAX);
             total_warnings += u32::try_from(warn_entries.len()).unwrap_or(u32::MAX);
+            window_errors.extend(error_entries);
         }
 
-        // Filter to only services above threshold
-        let services_with_issues: Vec<ServiceIssue> = all_issues
-            .into_values()
-            .filter(|issue| {
-                issue.error_count >= self.config.error_threshold
-                    || issue.warn_count >= self.config.warn_threshold
-            })
-            .collect();
+        let template_anomalies = self
+            .templates
+            .lock()
+            .expect("template tracker lock poisoned")
+            .observe_window(&window_errors);
+        if !template_anomalies.is_empty() {
+            info!(
+                "Detected {} log template anomalies",
+                template_anomalies.len()
+            );
+        }
+
+        // Filter to services above threshold or logging an anomalous template
+        let services_with_issues =
+            Self::select_services(&self.config, all_issues, &template_anomalies);
 
         // Determine if remediation is recommended
         let (remediation_recommended, recommendation_reason) =
-            Self::analyze_for_remediation(&services_with_issues);
+            Self::analyze_for_remediation(&services_with_issues, &template_anomalies);
 
         Ok(ScanReport {
             scan_time: end,
@@ -659,6 +712,7 @@ impl LogScanner {
             total_warnings,
             remediation_recommended,
             recommendation_reason,
+            template_anomalies,
         })
     }
 
@@ -742,9 +796,29 @@ impl LogScanner {
         }
     }
 
+    /// Keep services above the error or warning threshold, plus services
+    /// that logged an anomalous template even if their counts are low.
+    fn select_services(
+        config: &ScannerConfig,
+        issues: HashMap<String, ServiceIssue>,
+        anomalies: &[TemplateAnomaly],
+    ) -> Vec<ServiceIssue> {
+        issues
+            .into_values()
+            .filter(|issue| {
+                issue.error_count >= config.error_threshold
+                    || issue.warn_count >= config.warn_threshold
+                    || template_anomaly_for(issue, anomalies).is_some()
+            })
+            .collect()
+    }
+
     /// Analyze issues to determine if remediation should be triggered.
-    fn analyze_for_remediation(issues: &[ServiceIssue]) -> (bool, Option<String>) {
-        if issues.is_empty() {
+    fn analyze_for_remediation(
+        issues: &[ServiceIssue],
+        anomalies: &[TemplateAnomaly],
+    ) -> (bool, Option<String>) {
+        if issues.is_empty() && anomalies.is_empty() {
             return (false, None);
         }
 
@@ -798,6 +872,15 @@ impl LogScanner {
             );
         }
 
+        // New error templates are flagged before spikes
+        let anomaly = anomalies
+            .iter()
+            .find(|a| a.kind == AnomalyKind::New)
+            .or_else(|| anomalies.first());
+        if let Some(anomaly) = anomaly {
+            return (true, Some(describe_anomaly(anomaly)));
+        }
+
         (false, None)
     }
 
@@ -807,7 +890,8 @@ impl LogScanner {
         let mut candidates = Vec::new();
 
         for issue in &report.services_with_issues {
-            if issue.error_count < self.config.error_threshold {
+            let anomaly = template_anomaly_for(issue, &report.template_anomalies);
+            if issue.error_count < self.config.error_threshold && anomaly.is_none() {
                 continue;
             }
 
@@ -829,11 +913,16 @@ impl LogScanner {
                 service: issue.service.clone(),
                 namespace: issue.namespace.clone(),
                 severity: severity.to_string(),
-                reason: format!(
-                    "{} errors detected from {} pods",
-                    issue.error_count,
-                    issue.affected_pods.len()
-                ),
+                reason: match anomaly {
+                    Some(anomaly) if issue.error_count < self.config.error_threshold => {
+                        describe_anomaly(anomaly)
+                    }
+                    _ => format!(
+                        "{} errors detected from {} pods",
+                        issue.error_count,
+                        issue.affected_pods.len()
+                    ),
+                },
                 suggested_agent,
                 log_context,
             });
@@ -854,6 +943,35 @@ impl LogScanner {
     }
 }
 
+/// The anomalous template a service logged in this window, if any.
+fn template_anomaly_for<'a>(
+    issue: &ServiceIssue,
+    anomalies: &'a [TemplateAnomaly],
+) -> Option<&'a TemplateAnomaly> {
+    anomalies.iter().find(|anomaly| {
+        anomaly.namespaces.contains(&issue.namespace)
+            && anomaly
+                .affected_pods
+                .iter()
+                .any(|pod| issue.affected_pods.contains(pod))
+    })
+}
+
+/// One-line reason for remediating a template anomaly.
+fn describe_anomaly(anomaly: &TemplateAnomaly) -> String {
+    match anomaly.kind {
+        AnomalyKind::New => format!(
+            "New error template in {} pods: {}",
+            anomaly.affected_pods.len(),
+            anomaly.template
+        ),
+        AnomalyKind::Spike => format!(
+            "Error template spiked to {} lines (baseline {:.1}): {}",
+            anomaly.count, anomaly.baseline, anomaly.template
+        ),
+    }
+}
+
 /// Extract service name from pod name (strips random suffixes).
 #[must_use]
 pub fn extract_service_name(pod_name: &str) -> String {
@@ -1022,6 +1140,42 @@ pub fn format_report_text(report: &ScanReport) -> String {
         }
     }
 
+    if !report.template_anomalies.is_empty() {
+        writeln!(output).unwrap();
+        writeln!(
+            output,
+            "Template Anomalies ({}):",
+            report.template_anomalies.len()
+        )
+        .unwrap();
+        for anomaly in &report.template_anomalies {
+            let kind = match anomaly.kind {
+                AnomalyKind::New => "NEW",
+                AnomalyKind::Spike => "SPIKE",
+            };
+            writeln!(
+                output,
+                "  - [{kind}] {} ({} lines, baseline {:.1}, {} pods)",
+                anomaly.template,
+                anomaly.count,
+                anomaly.baseline,
+                anomaly.affected_pods.len()
+            )
+            .unwrap();
+            if !anomaly.affected_coderuns.is_empty() {
+                writeln!(
+                    output,
+                    "      CodeRuns: {}",
+                    anomaly.affected_coderuns.join(", ")
+                )
+                .unwrap();
+            }
+            for (i, exemplar) in anomaly.exemplars.iter().take(2).enumerate() {
+                writeln!(output, "      [{}] {}", i + 1, exemplar).unwrap();
+            }
+        }
+    }
+
     writeln!(output).unwrap();
     if report.remediation_recommended {
         writeln!(output, "⚠️  REMEDIATION RECOMMENDED").unwrap();
@@ -1085,6 +1239,62 @@ mod tests {
         assert!(config.namespaces.contains(&"cto".to_string()));
     }
 
+    fn pod_entry(pod: &str, line: &str) -> LogEntry {
+        LogEntry {
+            timestamp: Utc::now(),
+            line: line.to_string(),
+            labels: HashMap::from([
+                ("namespace".to_string(), "cto".to_string()),
+                ("pod".to_string(), pod.to_string()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_new_template_triggers_remediation_below_threshold() {
+        let steady = vec![pod_entry(
+            "controller-7b9f8c6d5-abc12",
+            "ERROR reconcile requeued for coderun play-task-1",
+        )];
+        let incident = vec![pod_entry(
+            "cto-tools-7d9f8-abcde",
+            "ERROR failed to mount workspace PVC ws-1 volume node affinity conflict",
+        )];
+
+        let mut tracker = TemplateTracker::new(AnomalyConfig::default());
+        tracker.observe_window(&steady);
+        let anomalies = tracker.observe_window(&incident);
+        assert_eq!(anomalies.len(), 1);
+
+        let config = ScannerConfig::default();
+        let mut issues = HashMap::new();
+        LogScanner::process_entries("cto", &incident, "error", &mut issues);
+        let services = LogScanner::select_services(&config, issues, &anomalies);
+        assert_eq!(
+            services.len(),
+            1,
+            "single error line is kept for its new template"
+        );
+        assert!(services[0].error_count < config.error_threshold);
+
+        let (recommended, reason) = LogScanner::analyze_for_remediation(&services, &anomalies);
+        assert!(recommended);
+        assert_eq!(
+            reason.as_deref(),
+            Some("New error template in 1 pods: ERROR failed to mount workspace PVC ws-<*> volume node affinity conflict")
+        );
+
+        // Without the anomaly the same window is below every threshold.
+        let mut issues = HashMap::new();
+        LogScanner::process_entries("cto", &incident, "error", &mut issues);
+        let services = LogScanner::select_services(&config, issues, &[]);
+        assert!(services.is_empty());
+        assert_eq!(
+            LogScanner::analyze_for_remediation(&services, &[]),
+            (false, None)
+        );
+    }
+
     // Tests for false positive detection
     #[test]
     fn test_is_false_positive_command_registration() {
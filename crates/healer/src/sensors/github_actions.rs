@@ -1,6 +1,8 @@
 //! GitHub Actions workflow failure sensor.
 //!
 //! Polls GitHub Actions for workflow failures and triggers CI remediation.
+//! Passing runs are forwarded to the CI remediation server (when
+//! `ci_server_url` is set) so flaky-test history and incidents see them.
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -42,6 +44,10 @@ pub struct SensorConfig {
     /// Kubernetes namespace for `CodeRuns`
     #[serde(default = "default_namespace")]
     pub namespace: String,
+    /// CI remediation server endpoint passing runs are forwarded to, e.g.
+    /// `http://healer:8080/api/remediate/ci-failure`
+    #[serde(default)]
+    pub ci_server_url: Option<String>,
 }
 
 fn default_poll_interval() -> u64 {
@@ -75,6 +81,7 @@ impl Default for SensorConfig {
             excluded_workflows: vec![],
             max_per_poll: default_max_per_poll(),
             namespace: default_namespace(),
+            ci_server_url: None,
         }
     }
 }
@@ -131,6 +138,26 @@ impl WorkflowFailure {
             raw_event: None,
         }
     }
+
+    /// GitHub `workflow_run` event payload for this run, as the CI
+    /// remediation server receives it from webhooks.
+    #[must_use]
+    pub fn to_workflow_run_event(&self) -> serde_json::Value {
+        serde_json::json!({
+            "action": "completed",
+            "workflow_run": {
+                "id": self.run_id,
+                "name": self.workflow_name,
+                "conclusion": self.conclusion,
+                "head_branch": self.branch,
+                "head_sha": self.head_sha,
+                "head_commit": { "message": self.commit_message },
+                "html_url": self.html_url,
+                "actor": { "login": self.actor },
+            },
+            "repository": { "full_name": self.repository },
+        })
+    }
 }
 
 /// GitHub Actions workflow failure sensor.
@@ -141,6 +168,9 @@ pub struct GitHubActionsSensor {
     processed_runs: HashSet<u64>,
     /// Last poll timestamp
     last_poll: Option<DateTime<Utc>>,
+    /// Passing runs waiting to be forwarded to the CI server
+    passing_runs: Vec<WorkflowFailure>,
+    http: reqwest::Client,
 }
 
 /// Configuration for retry behavior.
@@ -157,9 +187,22 @@ impl GitHubActionsSensor {
             remediation_config,
             processed_runs: HashSet::new(),
             last_poll: None,
+            passing_runs: Vec::new(),
+            http: reqwest::Client::new(),
         }
     }
 
+    /// Run IDs already processed, for persisting across leader changes.
+    #[must_use]
+    pub fn processed_runs(&self) -> &HashSet<u64> {
+        &self.processed_runs
+    }
+
+    /// Mark runs as already processed, e.g. ones a previous leader handled.
+    pub fn mark_processed(&mut self, run_ids: impl IntoIterator<Item = u64>) {
+        self.processed_runs.extend(run_ids);
+    }
+
     /// Execute a command with retry logic and exponential backoff.
     ///
     /// Retries the command up to `MAX_RETRIES` times if it fails with a
@@ -267,6 +310,7 @@ impl GitHubActionsSensor {
             if let Err(e) = self.poll_once() {
                 error!("Sensor poll failed: {e}");
             }
+            self.forward_passing_runs().await;
 
             tokio::time::sleep(tokio::time::Duration::from_secs(
                 self.config.poll_interval_secs,
@@ -285,11 +329,17 @@ impl GitHubActionsSensor {
 
         for repo in &self.config.repositories.clone() {
             match self.poll_repository(repo) {
-                Ok(failures) => {
+                Ok(runs) => {
+                    let (failures, passing): (Vec<_>, Vec<_>) = runs
+                        .into_iter()
+                        .partition(|run| run.conclusion == "failure");
                     if !failures.is_empty() {
                         info!("Found {} new failure(s) in {}", failures.len(), repo);
                     }
                     all_failures.extend(failures);
+                    if self.config.ci_server_url.is_some() {
+                        self.passing_runs.extend(passing);
+                    }
                 }
                 Err(e) => {
                     error!("Error polling {}: {}", repo, e);
@@ -319,7 +369,36 @@ impl GitHubActionsSensor {
         Ok(processed)
     }
 
-    /// Poll a single repository for failures.
+    /// Forward passing runs found by [`Self::poll_once`] to the CI server.
+    /// Runs that cannot be delivered are retried on the next call.
+    pub async fn forward_passing_runs(&mut self) {
+        let Some(url) = self.config.ci_server_url.clone() else {
+            return;
+        };
+        let mut undelivered = Vec::new();
+        for run in std::mem::take(&mut self.passing_runs) {
+            let result = self
+                .http
+                .post(&url)
+                .json(&run.to_workflow_run_event())
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            match result {
+                Ok(_) => debug!(
+                    "Forwarded passing run {} ({}) to {url}",
+                    run.run_id, run.workflow_name
+                ),
+                Err(e) => {
+                    warn!("Failed to forward passing run {}: {e}", run.run_id);
+                    undelivered.push(run);
+                }
+            }
+        }
+        self.passing_runs = undelivered;
+    }
+
+    /// Poll a single repository for completed runs that failed or passed.
     fn poll_repository(&mut self, repository: &str) -> Result<Vec<WorkflowFailure>> {
         debug!("Polling repository: {}", repository);
 
@@ -331,11 +410,11 @@ impl GitHubActionsSensor {
             "--repo",
             repository,
             "--status",
-            "failure",
+            "completed",
             "--json",
             "databaseId,name,headBranch,headSha,url,conclusion,createdAt,workflowName,event",
             "--limit",
-            "50",
+            "100",
         ])?;
 
         if !output.status.success() {
@@ -356,6 +435,12 @@ impl GitHubActionsSensor {
                 continue;
             }
 
+            // Cancelled and skipped runs say nothing about the code
+            let conclusion = run["conclusion"].as_str().unwrap_or("");
+            if !matches!(conclusion, "failure" | "success") {
+                continue;
+            }
+
             // Parse created_at timestamp
             let created_at_str = run["createdAt"].as_str().unwrap_or("");
             let created_at = DateTime::parse_from_rfc3339(created_at_str)
@@ -404,7 +489,7 @@ impl GitHubActionsSensor {
                 actor: "unknown".to_string(), // Will be fetched via run view
                 run_started_at: created_at,
                 detected_at: Utc::now(),
-                conclusion: run["conclusion"].as_str().unwrap_or("failure").to_string(),
+                conclusion: conclusion.to_string(),
             };
 
             failures.push(failure);
@@ -479,7 +564,9 @@ impl GitHubActionsSensor {
 
         // Classify and route
         let router = CiRouter::new();
-        let failure_type = router.classify_failure(&ci_failure, &logs);
+        let failures = ci::parsers::parse_ci_output(&logs);
+        let classification = router.classify(&ci_failure, &failures, &logs);
+        let failure_type = classification.failure_type;
 
         // Build remediation context for routing
         let routing_ctx = ci::types::RemediationContext {
@@ -487,6 +574,8 @@ impl GitHubActionsSensor {
             security_alert: None,
             failure_type: Some(failure_type.clone()),
             workflow_logs: logs.clone(),
+            failures,
+            classification_confidence: Some(classification.confidence),
             pr: None,
             changed_files: vec![],
             argocd_status: None,
@@ -499,7 +588,10 @@ impl GitHubActionsSensor {
             changes_made_so_far: vec![],
         };
         let agent = router.route(&routing_ctx);
-        info!("Classified as {:?}, routing to {:?}", failure_type, agent);
+        info!(
+            "Classified as {:?} (confidence {:.2}), routing to {:?}",
+            failure_type, classification.confidence, agent
+        );
 
         // Create GitHub issue if configured
         if self.config.create_issues {
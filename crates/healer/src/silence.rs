@@ -0,0 +1,486 @@
+//! Alert silences and recurring maintenance windows.
+//!
+//! A [`Silence`] matches alerts by alert id, namespace, repository, service
+//! and labels. While it is active, matching alerts are neither remediated
+//! nor notified, but every suppressed alert is still counted on the silence.
+//! A silence with a [`MaintenanceSchedule`] is only active inside its
+//! recurring window (e.g. Saturdays 02:00-04:00 UTC) until it expires.
+//!
+//! Silences come from two places:
+//! - a static YAML/JSON file (planned maintenance windows, checked in)
+//! - the healer HTTP API, persisted in the shared state ConfigMap so the
+//!   alert watch and other replicas see them
+
+use anyhow::{bail, Context as _, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::ha::StateStore;
+
+/// State store key prefix for API-created silences.
+const STATE_KEY_PREFIX: &str = "silence-";
+
+/// How long expired silences stay listed before they are pruned.
+const EXPIRED_RETENTION_HOURS: i64 = 24;
+
+/// What a silence applies to. Unset fields match anything; `labels` must
+/// all be present with equal values.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SilenceMatcher {
+    /// Alert id or rule id (e.g. `A2`, `a9-stuck-coderun`), case-insensitive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Repository (`owner/name`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+impl SilenceMatcher {
+    /// Whether `target` falls under this matcher.
+    pub fn matches(&self, target: &AlertTarget) -> bool {
+        let field = |expected: &Option<String>, actual: Option<&str>| {
+            expected
+                .as_deref()
+                .is_none_or(|expected| actual == Some(expected))
+        };
+        self.alert_id
+            .as_deref()
+            .is_none_or(|id| id.eq_ignore_ascii_case(&target.alert_id))
+            && field(&self.namespace, target.namespace.as_deref())
+            && field(&self.repository, target.repository.as_deref())
+            && field(&self.service, target.service.as_deref())
+            && self
+                .labels
+                .iter()
+                .all(|(key, value)| target.labels.get(key) == Some(value))
+    }
+}
+
+/// Recurring window inside which a silence is active, in UTC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceSchedule {
+    /// Days the window starts on (`Mon`, `Tue`, ...); empty means every day
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// Window start (`HH:MM:SS`)
+    pub start: NaiveTime,
+    /// Window length; may run past midnight
+    pub duration_mins: u32,
+}
+
+impl MaintenanceSchedule {
+    /// Whether `now` falls inside a window that started today or yesterday.
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let length = Duration::minutes(i64::from(self.duration_mins));
+        [0, 1].into_iter().any(|days_ago| {
+            let day = now.date_naive() - Duration::days(days_ago);
+            let scheduled = self.days.is_empty() || self.days.contains(&day.weekday());
+            let start = day.and_time(self.start).and_utc();
+            scheduled && start <= now && now < start + length
+        })
+    }
+}
+
+/// A silence (or recurring maintenance window).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Silence {
+    pub id: String,
+    #[serde(default)]
+    pub matcher: SilenceMatcher,
+    /// Why alerts are silenced (e.g. "Node pool upgrade")
+    pub reason: String,
+    #[serde(default)]
+    pub created_by: String,
+    pub starts_at: DateTime<Utc>,
+    /// When the silence expires
+    pub ends_at: DateTime<Utc>,
+    /// Restrict the silence to a recurring window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<MaintenanceSchedule>,
+    /// Alerts suppressed so far
+    #[serde(default)]
+    pub suppressed: u64,
+}
+
+impl Silence {
+    /// Whether the silence suppresses alerts at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now
+            && now < self.ends_at
+            && self.schedule.as_ref().is_none_or(|s| s.contains(now))
+    }
+
+    /// Whether the silence has expired.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.ends_at <= now
+    }
+}
+
+/// Request to create a silence.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewSilence {
+    #[serde(default)]
+    pub matcher: SilenceMatcher,
+    pub reason: String,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Defaults to now
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Expiry; either this or `duration_mins` is required
+    #[serde(default)]
+    pub ends_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub duration_mins: Option<u32>,
+    #[serde(default)]
+    pub schedule: Option<MaintenanceSchedule>,
+}
+
+impl NewSilence {
+    /// Validate and build the silence.
+    pub fn into_silence(self, now: DateTime<Utc>) -> Result<Silence> {
+        if self.reason.trim().is_empty() {
+            bail!("a silence needs a reason");
+        }
+        let starts_at = self.starts_at.unwrap_or(now);
+        let ends_at = match (self.ends_at, self.duration_mins) {
+            (Some(ends_at), _) => ends_at,
+            (None, Some(mins)) => starts_at + Duration::minutes(i64::from(mins)),
+            (None, None) => bail!("a silence needs ends_at or duration_mins"),
+        };
+        if ends_at <= starts_at {
+            bail!("a silence must end after it starts");
+        }
+        if self.schedule.as_ref().is_some_and(|s| s.duration_mins == 0) {
+            bail!("a maintenance window needs a non-zero duration");
+        }
+        Ok(Silence {
+            id: uuid::Uuid::new_v4().to_string(),
+            matcher: self.matcher,
+            reason: self.reason,
+            created_by: self.created_by.unwrap_or_else(|| "unknown".to_string()),
+            starts_at,
+            ends_at,
+            schedule: self.schedule,
+            suppressed: 0,
+        })
+    }
+}
+
+/// The alert being checked against silences.
+#[derive(Debug, Clone, Default)]
+pub struct AlertTarget {
+    pub alert_id: String,
+    pub namespace: Option<String>,
+    pub repository: Option<String>,
+    pub service: Option<String>,
+    pub labels: HashMap<String, String>,
+}
+
+impl AlertTarget {
+    /// Target for an alert on a pod or `CodeRun` with `labels`. Service and
+    /// repository come from the usual workload labels.
+    pub fn for_workload(alert_id: &str, namespace: &str, labels: &HashMap<String, String>) -> Self {
+        let label = |keys: &[&str]| keys.iter().find_map(|k| labels.get(*k).cloned());
+        Self {
+            alert_id: alert_id.to_string(),
+            namespace: Some(namespace.to_string()),
+            repository: label(&["repository", "github-repo"]),
+            service: label(&["service", "app.kubernetes.io/name", "app"]),
+            labels: labels.clone(),
+        }
+    }
+}
+
+/// Silence file format: a list of silences (ids and counters optional).
+#[derive(Debug, Deserialize)]
+struct SilenceFile {
+    #[serde(default)]
+    silences: Vec<NewSilence>,
+}
+
+#[derive(Debug, Default)]
+struct SilenceState {
+    /// From the silence file; counted in memory only
+    configured: Vec<Silence>,
+    /// Created through the API; persisted when a store is configured
+    created: Vec<Silence>,
+}
+
+/// Registry of silences.
+#[derive(Debug, Default)]
+pub struct Silences {
+    state: RwLock<SilenceState>,
+    store: Option<StateStore>,
+}
+
+impl Silences {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist API-created silences (and their counters) in `store`.
+    #[must_use]
+    pub fn with_state_store(mut self, store: StateStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Add silences from a YAML or JSON file with a top-level `silences`
+    /// list in the [`NewSilence`] format.
+    pub fn with_file(mut self, path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read silences from {}", path.display()))?;
+        let file: SilenceFile = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse silences in {}", path.display()))?;
+        let now = Utc::now();
+        let configured = file
+            .silences
+            .into_iter()
+            .map(|s| s.into_silence(now))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("Invalid silence in {}", path.display()))?;
+        info!(
+            "Loaded {} silences from {}",
+            configured.len(),
+            path.display()
+        );
+        self.state.get_mut().configured = configured;
+        Ok(self)
+    }
+
+    /// Reload API-created silences from the state store, e.g. ones created
+    /// on another replica.
+    pub async fn refresh(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        let created: Vec<Silence> = store.load_all(STATE_KEY_PREFIX).await?;
+        self.state.write().await.created = created;
+        Ok(())
+    }
+
+    /// All silences, configured first, including expired ones not yet pruned.
+    pub async fn list(&self) -> Vec<Silence> {
+        let state = self.state.read().await;
+        state
+            .configured
+            .iter()
+            .chain(&state.created)
+            .cloned()
+            .collect()
+    }
+
+    /// Create a silence.
+    pub async fn create(&self, request: NewSilence) -> Result<Silence> {
+        let now = Utc::now();
+        let silence = request.into_silence(now)?;
+        self.persist(&silence).await?;
+        let mut state = self.state.write().await;
+        state.created.push(silence.clone());
+        let retention = Duration::hours(EXPIRED_RETENTION_HOURS);
+        let pruned: Vec<String> = state
+            .created
+            .iter()
+            .filter(|s| s.ends_at + retention <= now)
+            .map(|s| s.id.clone())
+            .collect();
+        state.created.retain(|s| !pruned.contains(&s.id));
+        drop(state);
+        for id in pruned {
+            self.remove_persisted(&id).await;
+        }
+        info!(
+            "Created silence {} until {} ({}): {:?}",
+            silence.id, silence.ends_at, silence.reason, silence.matcher
+        );
+        Ok(silence)
+    }
+
+    /// Expire a silence now. Returns `false` if no API-created silence has
+    /// that id (configured silences are changed in their file).
+    pub async fn expire(&self, id: &str) -> Result<bool> {
+        let now = Utc::now();
+        let mut state = self.state.write().await;
+        let Some(silence) = state.created.iter_mut().find(|s| s.id == id) else {
+            return Ok(false);
+        };
+        silence.ends_at = silence.ends_at.min(now);
+        let snapshot = silence.clone();
+        drop(state);
+        self.persist(&snapshot).await?;
+        info!("Expired silence {id}");
+        Ok(true)
+    }
+
+    /// The silence suppressing `target` right now, if any. Counts the
+    /// suppression on that silence.
+    pub async fn check(&self, target: &AlertTarget) -> Option<Silence> {
+        self.check_at(target, Utc::now()).await
+    }
+
+    async fn check_at(&self, target: &AlertTarget, now: DateTime<Utc>) -> Option<Silence> {
+        let mut state = self.state.write().await;
+        let matches = |s: &Silence| s.is_active(now) && s.matcher.matches(target);
+        if let Some(silence) = state.configured.iter_mut().find(|s| matches(s)) {
+            silence.suppressed += 1;
+            return Some(silence.clone());
+        }
+        let silence = state.created.iter_mut().find(|s| matches(s))?;
+        silence.suppressed += 1;
+        let snapshot = silence.clone();
+        drop(state);
+        if let Err(e) = self.persist(&snapshot).await {
+            warn!("Failed to persist suppression count: {e:#}");
+        }
+        Some(snapshot)
+    }
+
+    async fn persist(&self, silence: &Silence) -> Result<()> {
+        match &self.store {
+            Some(store) => {
+                store
+                    .save(&format!("{STATE_KEY_PREFIX}{}", silence.id), silence)
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn remove_persisted(&self, id: &str) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.remove(&format!("{STATE_KEY_PREFIX}{id}")).await {
+                warn!("Failed to remove expired silence {id}: {e:#}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn pod_target(alert_id: &str) -> AlertTarget {
+        let labels = HashMap::from([
+            ("service".to_string(), "cto-controller".to_string()),
+            ("tier".to_string(), "platform".to_string()),
+        ]);
+        AlertTarget::for_workload(alert_id, "cto", &labels)
+    }
+
+    fn new_silence(matcher: SilenceMatcher) -> NewSilence {
+        NewSilence {
+            matcher,
+            reason: "Node pool upgrade".to_string(),
+            created_by: Some("ops".to_string()),
+            starts_at: None,
+            ends_at: None,
+            duration_mins: Some(60),
+            schedule: None,
+        }
+    }
+
+    #[test]
+    fn test_matcher_fields_and_labels() {
+        let target = pod_target("A2");
+        assert!(SilenceMatcher::default().matches(&target));
+        let matcher = SilenceMatcher {
+            alert_id: Some("a2".to_string()),
+            namespace: Some("cto".to_string()),
+            service: Some("cto-controller".to_string()),
+            labels: BTreeMap::from([("tier".to_string(), "platform".to_string())]),
+            ..SilenceMatcher::default()
+        };
+        assert!(matcher.matches(&target));
+        assert!(!matcher.matches(&pod_target("A7")));
+        let other_repo = SilenceMatcher {
+            repository: Some("5dlabs/cto".to_string()),
+            ..SilenceMatcher::default()
+        };
+        assert!(!other_repo.matches(&target));
+    }
+
+    #[test]
+    fn test_maintenance_schedule_crosses_midnight() {
+        let schedule = MaintenanceSchedule {
+            days: vec![Weekday::Sat],
+            start: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            duration_mins: 120,
+        };
+        // 2026-10-17 is a Saturday
+        let at = |d, h| Utc.with_ymd_and_hms(2026, 10, d, h, 30, 0).unwrap();
+        assert!(schedule.contains(at(17, 23)));
+        assert!(schedule.contains(at(18, 0)));
+        assert!(!schedule.contains(at(18, 1)));
+        assert!(!schedule.contains(at(17, 22)));
+        assert!(!schedule.contains(at(10, 21)));
+    }
+
+    #[test]
+    fn test_new_silence_validation() {
+        let now = Utc::now();
+        let silence = new_silence(SilenceMatcher::default())
+            .into_silence(now)
+            .unwrap();
+        assert_eq!(silence.ends_at - silence.starts_at, Duration::minutes(60));
+        assert!(silence.is_active(now));
+        assert!(!silence.is_active(now + Duration::minutes(61)));
+
+        let mut no_expiry = new_silence(SilenceMatcher::default());
+        no_expiry.duration_mins = None;
+        assert!(no_expiry.into_silence(now).is_err());
+        let mut no_reason = new_silence(SilenceMatcher::default());
+        no_reason.reason = " ".to_string();
+        assert!(no_reason.into_silence(now).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_counts_and_expire() {
+        let silences = Silences::new();
+        let silence = silences
+            .create(new_silence(SilenceMatcher {
+                alert_id: Some("A2".to_string()),
+                ..SilenceMatcher::default()
+            }))
+            .await
+            .unwrap();
+
+        assert!(silences.check(&pod_target("A7")).await.is_none());
+        assert!(silences.check(&pod_target("A2")).await.is_some());
+        let counted = silences.check(&pod_target("A2")).await.unwrap();
+        assert_eq!(counted.suppressed, 2);
+
+        assert!(silences.expire(&silence.id).await.unwrap());
+        assert!(silences.check(&pod_target("A2")).await.is_none());
+        assert!(!silences.expire("missing").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_silences_shared_through_state_store() {
+        let cluster = Arc::new(crate::clients::FakeCluster::new());
+        let store = || StateStore::new(cluster.clone(), "cto", "healer-state");
+        let api = Silences::new().with_state_store(store());
+        api.create(new_silence(SilenceMatcher::default()))
+            .await
+            .unwrap();
+
+        // The alert watch picks the silence up and its counts flow back
+        let watch = Silences::new().with_state_store(store());
+        watch.refresh().await.unwrap();
+        assert!(watch.check(&pod_target("A9")).await.is_some());
+        api.refresh().await.unwrap();
+        assert_eq!(api.list().await[0].suppressed, 1);
+    }
+}
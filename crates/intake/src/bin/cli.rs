@@ -1373,12 +1373,13 @@ async fn run(cli: Cli) -> Result<(), TasksError> {
             ui::print_info(&format!("  Repository: {}", repository));
             ui::print_info(&format!("  Service: {}", service));
 
-            let cto_config = intake::domain::generate_cto_config(
+            let cto_config = intake::domain::generate_cto_config_for_repository(
                 &tasks,
                 &repository,
                 &service,
                 &docs_repository,
                 &docs_project_directory,
+                &project_path,
             );
 
             let output_dir = output.unwrap_or_else(|| project_path.clone());
@@ -21,6 +21,7 @@ pub use config::{
     capitalize,
     default_remote_tools,
     derive_service_name,
+    generate_config_with_repository,
     generate_config_with_tasks,
     generate_project_config,
     generate_project_config_json,
@@ -84,6 +85,48 @@ pub fn generate_cto_config(
     service: &str,
     docs_repository: &str,
     docs_project_directory: &str,
+) -> CtoConfig {
+    build_cto_config(
+        tasks,
+        repository,
+        service,
+        docs_repository,
+        docs_project_directory,
+        None,
+    )
+}
+
+/// Generate CTO config from tasks and the manifests of a local checkout.
+///
+/// Like [`generate_cto_config`], but dependencies declared in `repo_root`
+/// (Cargo.toml, package.json, go.mod, pyproject.toml, docker-compose) also
+/// contribute agent tools.
+#[must_use]
+pub fn generate_cto_config_for_repository(
+    tasks: &[Task],
+    repository: &str,
+    service: &str,
+    docs_repository: &str,
+    docs_project_directory: &str,
+    repo_root: &Path,
+) -> CtoConfig {
+    build_cto_config(
+        tasks,
+        repository,
+        service,
+        docs_repository,
+        docs_project_directory,
+        Some(repo_root),
+    )
+}
+
+fn build_cto_config(
+    tasks: &[Task],
+    repository: &str,
+    service: &str,
+    docs_repository: &str,
+    docs_project_directory: &str,
+    repo_root: Option<&Path>,
 ) -> CtoConfig {
     let input = ProjectConfigInput {
         repository_url: Some(format!("https://github.com/{repository}")),
@@ -95,7 +138,10 @@ pub fn generate_cto_config(
     };
 
     // Use the shared crate's implementation
-    let mut config = generate_config_with_tasks(&input, tasks);
+    let mut config = match repo_root {
+        Some(root) => generate_config_with_repository(&input, tasks, root),
+        None => generate_config_with_tasks(&input, tasks),
+    };
 
     // Override with exact values (the shared crate normalizes repository URL)
     config.defaults.play.repository = repository.to_string();
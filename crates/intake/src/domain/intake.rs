@@ -18,7 +18,7 @@ use crate::errors::{TasksError, TasksResult};
 use crate::progress::{emit_progress, ProgressEvent};
 use crate::storage::Storage;
 
-use super::cto_config::{generate_cto_config, save_cto_config};
+use super::cto_config::{generate_cto_config_for_repository, save_cto_config};
 use super::docs::{generate_all_docs, DocsGenerationResult};
 use super::tasks::routing::infer_agent_hint_with_deps_str;
 use super::AIDomain;
@@ -636,14 +636,6 @@ impl IntakeDomain {
                 .clone()
                 .unwrap_or_else(|| service.clone());
 
-            let cto_config = generate_cto_config(
-                &tasks,
-                &repository,
-                &service,
-                &docs_repository,
-                &docs_project_directory,
-            );
-
             // Save cto-config.json in the output directory (project root, not .tasks)
             // The parent of output_dir (.tasks) is typically the project root
             let project_root = config
@@ -652,6 +644,16 @@ impl IntakeDomain {
                 .unwrap_or(&config.output_dir)
                 .to_path_buf();
 
+            // Manifests in the project root refine the per-agent tools
+            let cto_config = generate_cto_config_for_repository(
+                &tasks,
+                &repository,
+                &service,
+                &docs_repository,
+                &docs_project_directory,
+                &project_root,
+            );
+
             if let Err(e) = save_cto_config(&cto_config, &project_root).await {
                 tracing::warn!("Failed to save cto-config.json: {}", e);
             } else {
@@ -38,7 +38,9 @@ pub mod docs {
 
 pub use ai::AIDomain;
 pub use config::ConfigDomain;
-pub use cto_config::{generate_cto_config, save_cto_config, CtoConfig};
+pub use cto_config::{
+    generate_cto_config, generate_cto_config_for_repository, save_cto_config, CtoConfig,
+};
 pub use delta::{compute_task_delta, get_task_changes, tasks_are_equal, TaskChanges, TaskDelta};
 pub use intake::{create_deploy_task, has_deploy_task, IntakeConfig, IntakeDomain, IntakeResult};
 pub use linear_parser::{parse_linear_issue, ParsedLinearTask};
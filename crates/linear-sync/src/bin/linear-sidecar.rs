@@ -17,6 +17,9 @@
 //!   WORKSPACE_PATH: Path to agent workspace (for reading task context)
 //!   TASK_PROMPT_PATH: Path to task prompt file (default: $WORKSPACE_PATH/prompt.md)
 //!   TASK_ACCEPTANCE_PATH: Path to acceptance criteria (default: $WORKSPACE_PATH/acceptance-criteria.md)
+//!   CTO_COST_LEDGER_URL: Controller endpoint for token usage (see `linear_sink::sidecar::usage`)
+//!   CTO_COST_LEDGER_TOKEN: Bearer token for the usage endpoint
+//!   CTO_COST_LEDGER_PATH: Local SQLite cost ledger, when no URL is set
 
 use anyhow::{Context, Result};
 use axum::{
@@ -32,10 +35,12 @@ use std::env;
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+use linear_sink::sidecar::UsageRecorder;
+
 /// Safely truncate a UTF-8 string to a maximum number of characters.
 /// Avoids panics from slicing mid-character by using char boundaries.
 fn safe_truncate(s: &str, max_chars: usize) -> String {
@@ -794,6 +799,8 @@ struct AppState {
     memory_namespace: String,
     memory_max_fingerprints: usize,
     memory_stats: MemoryStats,
+    // Cost ledger recording (None when CTO_COST_LEDGER_PATH is unset)
+    usage_recorder: Option<Mutex<UsageRecorder>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -2238,10 +2245,65 @@ async fn health() -> &'static str {
     "OK"
 }
 
+/// Record token usage reported by the entry into the cost ledger
+///
+/// With a remote ledger, queued calls (including ones that failed to send
+/// earlier) are posted to the controller.
+async fn record_usage(state: &AppState, entry: &LogEntry) {
+    let Some(recorder) = &state.usage_recorder else {
+        return;
+    };
+    let Ok(line) = serde_json::to_string(entry) else {
+        return;
+    };
+    let (calls, outbox) = match recorder.lock() {
+        Ok(mut recorder) => {
+            let calls = recorder.observe_line(&line);
+            let outbox = recorder
+                .ledger()
+                .cloned()
+                .map(|ledger| (ledger, recorder.take_unsent()));
+            (calls, outbox)
+        }
+        Err(e) => {
+            warn!("Usage recorder lock poisoned: {}", e);
+            return;
+        }
+    };
+    for call in calls {
+        info!(
+            "💰 Recorded usage: {} {} in={} out={} cached={} ${:.4}",
+            call.provider,
+            call.model,
+            call.input_tokens,
+            call.output_tokens,
+            call.cached_tokens,
+            call.estimated_cost_usd
+        );
+    }
+
+    let Some((ledger, unsent)) = outbox.filter(|(_, unsent)| !unsent.is_empty()) else {
+        return;
+    };
+    if let Err(e) = ledger.submit(&unsent).await {
+        warn!(
+            "Failed to send {} usage record(s) to {}: {}",
+            unsent.len(),
+            ledger.url(),
+            e
+        );
+        if let Ok(mut recorder) = recorder.lock() {
+            recorder.requeue(unsent);
+        }
+    }
+}
+
 /// Ingest endpoint - receives logs from FluentD
 async fn ingest(State(state): State<Arc<AppState>>, Json(entry): Json<LogEntry>) -> StatusCode {
     debug!("Received log entry: {:?}", entry);
 
+    record_usage(&state, &entry).await;
+
     let mut session = state.session.write().await;
     session.total_entries += 1;
 
@@ -2746,6 +2808,21 @@ async fn main() -> Result<()> {
         issue_identifier, agent_name, log_source
     );
 
+    let usage_recorder = match UsageRecorder::from_env() {
+        Ok(Some(recorder)) => {
+            info!("💰 Recording token usage for {:?}", recorder.context());
+            Some(Mutex::new(recorder))
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!(
+                "Cost ledger unavailable, token usage will not be recorded: {}",
+                e
+            );
+            None
+        }
+    };
+
     // Create shared state
     let state = Arc::new(AppState {
         session: RwLock::new(SessionState::default()),
@@ -2763,6 +2840,7 @@ async fn main() -> Result<()> {
         memory_namespace,
         memory_max_fingerprints,
         memory_stats: MemoryStats::default(),
+        usage_recorder,
     });
 
     match log_source.as_str() {
@@ -74,4 +74,5 @@ pub use emitter::{AgentActivityEmitter, LinearAgentEmitter};
 // Re-export parser types
 pub use parsers::{
     ArtifactOperation, InitInfo, ParseResult, ParsedActivity, StreamParser, StreamStats,
+    UsageRecord,
 };
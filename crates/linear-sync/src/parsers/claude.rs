@@ -12,16 +12,26 @@
 //! {"type":"system","model":"claude-opus-4","tools":["read_file","write_file"]}
 //! {"type":"assistant","message":{"content":[{"type":"tool_use","name":"read_file","input":{"path":"src/lib.rs"}}]}}
 //! {"type":"user","tool_use_result":"file contents here..."}
-//! {"type":"result","duration_ms":5000,"total_cost_usd":0.05,"num_turns":3}
+//! {"type":"result","duration_ms":5000,"total_cost_usd":0.05,"num_turns":3,"usage":{"input_tokens":10,"output_tokens":20}}
 //! ```
+//!
+//! Token usage is taken from the `result` event: the per-model
+//! `modelUsage` breakdown when present, otherwise the aggregate `usage`
+//! attributed to the model from the `system` event.
+
+use std::collections::BTreeMap;
 
 use serde::Deserialize;
 use serde_json::Value;
 
 use super::{
     init_log, ArtifactOperation, InitInfo, ParseResult, ParsedActivity, StreamParser, StreamStats,
+    UsageRecord,
 };
 
+/// Provider recorded for Claude usage.
+const CLAUDE_PROVIDER: &str = "anthropic";
+
 /// Claude stream event types (from `stream-json` output)
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -71,12 +81,53 @@ pub enum ClaudeStreamEvent {
         num_turns: Option<u32>,
         /// Result text
         result: Option<String>,
+        /// Aggregate token usage for the session
+        #[serde(default)]
+        usage: Option<ClaudeUsage>,
+        /// Token usage per model
+        #[serde(default, rename = "modelUsage")]
+        model_usage: Option<BTreeMap<String, ClaudeUsage>>,
         /// Session ID
         #[serde(default)]
         session_id: Option<String>,
     },
 }
 
+/// Token usage from a `result` event.
+///
+/// Used for both the aggregate `usage` object (`snake_case`) and the
+/// per-model `modelUsage` entries (camelCase). Claude reports cache reads
+/// and cache writes separately from `input_tokens`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ClaudeUsage {
+    /// Uncached input tokens
+    #[serde(default, alias = "inputTokens")]
+    pub input_tokens: u64,
+    /// Output tokens
+    #[serde(default, alias = "outputTokens")]
+    pub output_tokens: u64,
+    /// Input tokens read from the prompt cache
+    #[serde(default, alias = "cacheReadInputTokens")]
+    pub cache_read_input_tokens: u64,
+    /// Input tokens written to the prompt cache
+    #[serde(default, alias = "cacheCreationInputTokens")]
+    pub cache_creation_input_tokens: u64,
+}
+
+impl ClaudeUsage {
+    /// Normalise into a usage record whose input total includes cache
+    /// reads and writes.
+    fn to_record(&self, model: Option<String>) -> UsageRecord {
+        UsageRecord::new(
+            CLAUDE_PROVIDER,
+            self.input_tokens + self.cache_read_input_tokens + self.cache_creation_input_tokens,
+            self.output_tokens,
+        )
+        .with_model(model)
+        .with_cached(self.cache_read_input_tokens)
+    }
+}
+
 /// Assistant message content
 #[derive(Debug, Clone, Deserialize)]
 pub struct AssistantMessage {
@@ -132,6 +183,7 @@ struct ToolState {
 pub struct ClaudeParser {
     stats: StreamStats,
     tool_state: ToolState,
+    model: Option<String>,
 }
 
 impl ClaudeParser {
@@ -141,6 +193,7 @@ impl ClaudeParser {
         Self {
             stats: StreamStats::new(),
             tool_state: ToolState::default(),
+            model: None,
         }
     }
 
@@ -150,6 +203,9 @@ impl ClaudeParser {
         model: &Option<String>,
         tools: &Option<Vec<String>>,
     ) -> ParseResult {
+        if model.is_some() {
+            self.model.clone_from(model);
+        }
         let model_name = model.clone().unwrap_or_else(|| "unknown".to_string());
         let tool_count = tools.as_ref().map_or(0, Vec::len);
 
@@ -306,6 +362,34 @@ impl ClaudeParser {
 
         ParseResult::with_activity(activity)
     }
+
+    /// Extract usage records from a Result event.
+    ///
+    /// The session duration is attached to the record with the most output
+    /// tokens so summing durations across records stays meaningful.
+    fn usage_records(
+        &self,
+        usage: Option<&ClaudeUsage>,
+        model_usage: Option<&BTreeMap<String, ClaudeUsage>>,
+        duration_ms: Option<u64>,
+    ) -> Vec<UsageRecord> {
+        let mut records: Vec<UsageRecord> = match model_usage {
+            Some(models) if !models.is_empty() => models
+                .iter()
+                .map(|(model, usage)| usage.to_record(Some(model.clone())))
+                .collect(),
+            _ => usage
+                .map(|usage| usage.to_record(self.model.clone()))
+                .into_iter()
+                .collect(),
+        };
+        records.retain(UsageRecord::has_tokens);
+
+        if let Some(primary) = records.iter_mut().max_by_key(|r| r.output_tokens) {
+            primary.duration_ms = duration_ms;
+        }
+        records
+    }
 }
 
 impl Default for ClaudeParser {
@@ -351,10 +435,19 @@ impl StreamParser for ClaudeParser {
                 total_cost_usd,
                 num_turns,
                 subtype,
+                usage,
+                model_usage,
                 ..
             } => {
                 self.stats.increment_turns();
-                self.parse_result_event(duration_ms, total_cost_usd, num_turns, &subtype)
+                let records = self.usage_records(usage.as_ref(), model_usage.as_ref(), duration_ms);
+                let mut result =
+                    self.parse_result_event(duration_ms, total_cost_usd, num_turns, &subtype);
+                for record in records {
+                    self.stats.add_usage(&record);
+                    result.add_usage(record);
+                }
+                result
             }
         }
     }
@@ -366,6 +459,7 @@ impl StreamParser for ClaudeParser {
     fn reset(&mut self) {
         self.stats = StreamStats::new();
         self.tool_state = ToolState::default();
+        self.model = None;
     }
 }
 
@@ -473,6 +567,44 @@ mod tests {
         assert!((stats.total_cost - 0.05).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn test_result_usage_falls_back_to_session_model() {
+        let mut parser = ClaudeParser::new();
+        parser.parse_line(r#"{"type":"system","subtype":"init","model":"claude-sonnet-4-5"}"#);
+
+        let line = r#"{"type":"result","duration_ms":4000,"usage":{"input_tokens":100,"cache_read_input_tokens":900,"cache_creation_input_tokens":50,"output_tokens":200}}"#;
+        let result = parser.parse_line(line);
+
+        assert_eq!(
+            result.usage,
+            vec![UsageRecord {
+                provider: "anthropic".to_string(),
+                model: Some("claude-sonnet-4-5".to_string()),
+                input_tokens: 1050,
+                output_tokens: 200,
+                cached_tokens: 900,
+                duration_ms: Some(4000),
+            }]
+        );
+        let stats = parser.get_stats();
+        assert_eq!(stats.input_tokens, Some(1050));
+        assert_eq!(stats.output_tokens, Some(200));
+    }
+
+    #[test]
+    fn test_result_usage_prefers_per_model_breakdown() {
+        let mut parser = ClaudeParser::new();
+        let line = r#"{"type":"result","duration_ms":9000,"usage":{"input_tokens":30,"output_tokens":70},"modelUsage":{"claude-haiku-4-5":{"inputTokens":10,"outputTokens":5},"claude-opus-4-5":{"inputTokens":20,"outputTokens":65,"cacheReadInputTokens":100}}}"#;
+        let result = parser.parse_line(line);
+
+        assert_eq!(result.usage.len(), 2);
+        let opus = &result.usage[1];
+        assert_eq!(opus.model.as_deref(), Some("claude-opus-4-5"));
+        assert_eq!((opus.input_tokens, opus.cached_tokens), (120, 100));
+        assert_eq!(opus.duration_ms, Some(9000));
+        assert_eq!(result.usage[0].duration_ms, None);
+    }
+
     #[test]
     fn test_truncate_chars() {
         assert_eq!(truncate_chars("hello", 10), "hello");
@@ -5,6 +5,10 @@
 //! - `model`: Model name used
 //! - `usage`: Token usage statistics
 //!
+//! `codex exec --json` also emits `turn.completed` events that carry only
+//! `usage`; those deserialize into the same structure and are recorded
+//! against the last model seen on the stream.
+//!
 //! # Example Output
 //!
 //! ```json
@@ -22,8 +26,12 @@ use serde_json::Value;
 
 use super::{
     init_log, ArtifactOperation, InitInfo, ParseResult, ParsedActivity, StreamParser, StreamStats,
+    UsageRecord,
 };
 
+/// Provider recorded for Codex usage.
+const CODEX_PROVIDER: &str = "openai";
+
 /// Codex JSON output structure
 #[derive(Debug, Clone, Deserialize)]
 pub struct CodexOutput {
@@ -53,16 +61,20 @@ pub struct CodexCommand {
 /// Usage statistics
 #[derive(Debug, Clone, Deserialize)]
 pub struct UsageStats {
-    /// Input tokens
+    /// Input tokens (including cached input)
     pub input_tokens: Option<u64>,
     /// Output tokens
     pub output_tokens: Option<u64>,
+    /// Cached input tokens
+    #[serde(default, alias = "cached_tokens")]
+    pub cached_input_tokens: Option<u64>,
 }
 
 /// Codex stream parser implementation
 pub struct CodexParser {
     stats: StreamStats,
     cli_id: &'static str,
+    model: Option<String>,
 }
 
 impl CodexParser {
@@ -72,6 +84,7 @@ impl CodexParser {
         Self {
             stats: StreamStats::new(),
             cli_id: "codex",
+            model: None,
         }
     }
 
@@ -81,6 +94,7 @@ impl CodexParser {
         Self {
             stats: StreamStats::new(),
             cli_id: "code",
+            model: None,
         }
     }
 
@@ -161,10 +175,23 @@ impl StreamParser for CodexParser {
             return ParseResult::empty();
         };
 
+        if output.model.is_some() {
+            self.model.clone_from(&output.model);
+        }
+
         // Update stats from usage
-        if let Some(usage) = &output.usage {
-            if let (Some(input), Some(output_tokens)) = (usage.input_tokens, usage.output_tokens) {
-                self.stats.add_tokens(input, output_tokens);
+        let mut usage = Vec::new();
+        if let Some(stats) = &output.usage {
+            let record = UsageRecord::new(
+                CODEX_PROVIDER,
+                stats.input_tokens.unwrap_or(0),
+                stats.output_tokens.unwrap_or(0),
+            )
+            .with_model(self.model.clone())
+            .with_cached(stats.cached_input_tokens.unwrap_or(0));
+            if record.has_tokens() {
+                self.stats.add_usage(&record);
+                usage.push(record);
             }
         }
 
@@ -189,6 +216,7 @@ impl StreamParser for CodexParser {
         ParseResult {
             activities: all_activities,
             artifact_ops: artifacts,
+            usage,
         }
     }
 
@@ -198,6 +226,7 @@ impl StreamParser for CodexParser {
 
     fn reset(&mut self) {
         self.stats = StreamStats::new();
+        self.model = None;
     }
 }
 
@@ -277,4 +306,21 @@ mod tests {
         assert_eq!(stats.input_tokens, Some(100));
         assert_eq!(stats.output_tokens, Some(200));
     }
+
+    #[test]
+    fn test_turn_completed_usage_uses_last_model() {
+        let mut parser = CodexParser::new();
+        parser.parse_line(r#"{"commands":[],"model":"gpt-5-codex"}"#);
+
+        let line = r#"{"type":"turn.completed","usage":{"input_tokens":1000,"cached_input_tokens":600,"output_tokens":50}}"#;
+        let result = parser.parse_line(line);
+
+        assert!(result.activities.is_empty());
+        assert_eq!(
+            result.usage,
+            vec![UsageRecord::new("openai", 1000, 50)
+                .with_model(Some("gpt-5-codex".to_string()))
+                .with_cached(600)]
+        );
+    }
 }
@@ -1,14 +1,21 @@
 //! Gemini CLI stream parser.
 //!
 //! Parses Gemini's JSONL output format similar to Codex.
+//!
+//! Token usage is read from `usage` or the `stats` object on result events;
+//! Gemini's input count already includes cached tokens.
 
 use serde::Deserialize;
 use serde_json::Value;
 
 use super::{
     init_log, ArtifactOperation, InitInfo, ParseResult, ParsedActivity, StreamParser, StreamStats,
+    UsageRecord,
 };
 
+/// Provider recorded for Gemini usage.
+const GEMINI_PROVIDER: &str = "google";
+
 /// Gemini output structure
 #[derive(Debug, Clone, Deserialize)]
 pub struct GeminiOutput {
@@ -18,6 +25,7 @@ pub struct GeminiOutput {
     /// Model name
     pub model: Option<String>,
     /// Usage statistics
+    #[serde(alias = "stats")]
     pub usage: Option<UsageStats>,
     /// Result text
     pub result: Option<String>,
@@ -47,11 +55,18 @@ pub struct UsageStats {
     /// Output tokens
     #[serde(alias = "outputTokens")]
     pub output_tokens: Option<u64>,
+    /// Cached input tokens
+    #[serde(default, alias = "cachedTokens", alias = "cached")]
+    pub cached_tokens: Option<u64>,
+    /// Duration in milliseconds
+    #[serde(default, alias = "durationMs")]
+    pub duration_ms: Option<u64>,
 }
 
 /// Gemini stream parser implementation
 pub struct GeminiParser {
     stats: StreamStats,
+    model: Option<String>,
 }
 
 impl GeminiParser {
@@ -60,6 +75,7 @@ impl GeminiParser {
     pub fn new() -> Self {
         Self {
             stats: StreamStats::new(),
+            model: None,
         }
     }
 }
@@ -92,11 +108,25 @@ impl StreamParser for GeminiParser {
 
         let mut activities = Vec::new();
         let mut artifacts = Vec::new();
+        let mut usage = Vec::new();
+
+        if output.model.is_some() {
+            self.model.clone_from(&output.model);
+        }
 
         // Update stats
-        if let Some(usage) = &output.usage {
-            if let (Some(input), Some(output_tokens)) = (usage.input_tokens, usage.output_tokens) {
-                self.stats.add_tokens(input, output_tokens);
+        if let Some(stats) = &output.usage {
+            let record = UsageRecord::new(
+                GEMINI_PROVIDER,
+                stats.input_tokens.unwrap_or(0),
+                stats.output_tokens.unwrap_or(0),
+            )
+            .with_model(self.model.clone())
+            .with_cached(stats.cached_tokens.unwrap_or(0))
+            .with_duration(stats.duration_ms);
+            if record.has_tokens() {
+                self.stats.add_usage(&record);
+                usage.push(record);
             }
         }
 
@@ -146,6 +176,7 @@ impl StreamParser for GeminiParser {
         ParseResult {
             activities,
             artifact_ops: artifacts,
+            usage,
         }
     }
 
@@ -155,6 +186,7 @@ impl StreamParser for GeminiParser {
 
     fn reset(&mut self) {
         self.stats = StreamStats::new();
+        self.model = None;
     }
 }
 
@@ -187,4 +219,22 @@ mod tests {
         let result = parser.parse_line(line);
         assert_eq!(result.activities.len(), 1);
     }
+
+    #[test]
+    fn test_parse_result_stats_usage() {
+        let mut parser = GeminiParser::new();
+        parser.parse_line(r#"{"commands":[],"model":"gemini-2.5-pro"}"#);
+
+        let line = r#"{"result":"done","stats":{"inputTokens":800,"outputTokens":120,"cachedTokens":300,"durationMs":2500}}"#;
+        let result = parser.parse_line(line);
+
+        assert_eq!(
+            result.usage,
+            vec![UsageRecord::new("google", 800, 120)
+                .with_model(Some("gemini-2.5-pro".to_string()))
+                .with_cached(300)
+                .with_duration(Some(2500))]
+        );
+        assert_eq!(parser.get_stats().output_tokens, Some(120));
+    }
 }
@@ -95,13 +95,16 @@ impl InitInfo {
 
 /// Result of parsing a stream line.
 ///
-/// Contains activities to emit and artifact operations for tracking.
+/// Contains activities to emit, artifact operations for tracking and
+/// any token usage the CLI reported.
 #[derive(Debug, Clone, Default)]
 pub struct ParseResult {
     /// Activities to emit to Linear
     pub activities: Vec<ParsedActivity>,
     /// Artifact operations for file tracking
     pub artifact_ops: Vec<ArtifactOperation>,
+    /// Token usage records for the cost ledger
+    pub usage: Vec<UsageRecord>,
 }
 
 impl ParseResult {
@@ -114,10 +117,7 @@ impl ParseResult {
     /// Create a result with a single activity
     #[must_use]
     pub fn with_activity(activity: ParsedActivity) -> Self {
-        Self {
-            activities: vec![activity],
-            artifact_ops: Vec::new(),
-        }
+        Self::with_activities(vec![activity])
     }
 
     /// Create a result with multiple activities
@@ -125,7 +125,7 @@ impl ParseResult {
     pub fn with_activities(activities: Vec<ParsedActivity>) -> Self {
         Self {
             activities,
-            artifact_ops: Vec::new(),
+            ..Self::default()
         }
     }
 
@@ -134,10 +134,76 @@ impl ParseResult {
         self.artifact_ops.push(op);
     }
 
+    /// Add a usage record
+    pub fn add_usage(&mut self, usage: UsageRecord) {
+        self.usage.push(usage);
+    }
+
     /// Check if the result is empty
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.activities.is_empty() && self.artifact_ops.is_empty()
+        self.activities.is_empty() && self.artifact_ops.is_empty() && self.usage.is_empty()
+    }
+}
+
+/// Token usage reported by a CLI for one model.
+///
+/// `input_tokens` includes `cached_tokens`, matching how the cost ledger
+/// prices calls. Parsers normalise CLIs that report cache reads separately
+/// (e.g. Claude) into this shape.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageRecord {
+    /// Provider the CLI talks to by default (e.g., "anthropic", "openai")
+    pub provider: String,
+    /// Model name if reported
+    pub model: Option<String>,
+    /// Input tokens, including cached tokens
+    pub input_tokens: u64,
+    /// Output tokens generated
+    pub output_tokens: u64,
+    /// Input tokens served from the prompt cache
+    pub cached_tokens: u64,
+    /// Wall-clock duration in milliseconds, if reported
+    pub duration_ms: Option<u64>,
+}
+
+impl UsageRecord {
+    /// Create a usage record for `provider` with token counts.
+    #[must_use]
+    pub fn new(provider: impl Into<String>, input_tokens: u64, output_tokens: u64) -> Self {
+        Self {
+            provider: provider.into(),
+            input_tokens,
+            output_tokens,
+            ..Self::default()
+        }
+    }
+
+    /// Set the model name
+    #[must_use]
+    pub fn with_model(mut self, model: Option<String>) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Set the cached token count (clamped to `input_tokens`)
+    #[must_use]
+    pub fn with_cached(mut self, cached_tokens: u64) -> Self {
+        self.cached_tokens = cached_tokens.min(self.input_tokens);
+        self
+    }
+
+    /// Set the duration
+    #[must_use]
+    pub fn with_duration(mut self, duration_ms: Option<u64>) -> Self {
+        self.duration_ms = duration_ms;
+        self
+    }
+
+    /// Whether the record carries any tokens
+    #[must_use]
+    pub fn has_tokens(&self) -> bool {
+        self.input_tokens > 0 || self.output_tokens > 0
     }
 }
 
@@ -316,6 +382,11 @@ impl StreamStats {
         self.output_tokens = Some(self.output_tokens.unwrap_or(0) + output);
     }
 
+    /// Add token usage from a usage record
+    pub fn add_usage(&mut self, usage: &UsageRecord) {
+        self.add_tokens(usage.input_tokens, usage.output_tokens);
+    }
+
     /// Format as a summary string
     #[must_use]
     pub fn to_summary(&self) -> String {
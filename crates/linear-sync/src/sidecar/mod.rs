@@ -3,6 +3,9 @@
 //! These modules will be populated when refactoring status-sync.rs.
 
 pub mod config;
+pub mod usage;
+
+pub use usage::{LedgerClient, UsageRecorder};
 
 // Placeholder modules - will be populated in refactor-sidecar phase
 // pub mod status_sync;
@@ -0,0 +1,438 @@
+//! Forward token usage parsed from the CLI stream into the cost ledger.
+//!
+//! The sidecar feeds every stream line to a [`UsageRecorder`], which runs it
+//! through the CLI's [`StreamParser`] and records each [`UsageRecord`] as a
+//! tracked call tagged with the `CodeRun`'s project, task, agent and
+//! iteration.
+//!
+//! The ledger lives in the controller, which enforces budgets against it, so
+//! in a cluster the calls are posted to the controller's usage endpoint with a
+//! [`LedgerClient`]. A local `SQLite` ledger can be used instead for
+//! development.
+//!
+//! Environment:
+//!   `CTO_COST_LEDGER_URL`: Controller endpoint to post recorded calls to
+//!   `CTO_COST_LEDGER_TOKEN`: Bearer token the controller requires on posts
+//!   `CTO_COST_LEDGER_PATH`: Local `SQLite` ledger (used when no URL is set;
+//!   recording is off when neither is set)
+//!   `CTO_SERVICE`: Project (service) the run belongs to
+//!   `TASK_ID`: Task being worked on
+//!   `CTO_GITHUB_APP`: Agent (GitHub App) running the task
+//!   `CODERUN_NAME`: Session grouping the run's calls
+//!   `CTO_ATTEMPT_NUMBER`: Iteration number of the run
+//!   `RESOLVED_PROVIDER`: Provider actually serving the model (overrides the CLI default)
+//!   `CLI_TYPE`: Parser selection (auto-detected when unset)
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use cto_cost::tracking::{CostTracker, SessionId, TrackedCall, TrackingContext};
+use cto_cost::CostError;
+
+use crate::parsers::{ParserRegistry, StreamParser, UsageRecord};
+
+/// Environment variable naming the cost ledger path.
+pub const COST_LEDGER_PATH_ENV: &str = "CTO_COST_LEDGER_PATH";
+
+/// Environment variable naming the controller's usage endpoint.
+pub const COST_LEDGER_URL_ENV: &str = "CTO_COST_LEDGER_URL";
+
+/// Environment variable holding the usage endpoint's bearer token.
+pub const COST_LEDGER_TOKEN_ENV: &str = "CTO_COST_LEDGER_TOKEN";
+
+/// Upper bound on calls kept for resending while the ledger is unreachable.
+const MAX_UNSENT_CALLS: usize = 1000;
+
+/// Posts tracked calls to the controller's cost ledger.
+#[derive(Debug, Clone)]
+pub struct LedgerClient {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl LedgerClient {
+    /// Create a client posting to `url`.
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            token: None,
+        }
+    }
+
+    /// Authenticate posts with `token`.
+    #[must_use]
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Endpoint calls are posted to.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Post `calls` to the ledger.
+    ///
+    /// The ledger ignores call IDs it already has, so resending is safe.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the ledger rejects it.
+    pub async fn submit(&self, calls: &[TrackedCall]) -> Result<(), reqwest::Error> {
+        let mut request = self.client.post(&self.url).json(calls);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Records token usage from a CLI stream into a [`CostTracker`].
+pub struct UsageRecorder {
+    tracker: CostTracker,
+    context: TrackingContext,
+    provider: Option<String>,
+    parser: Option<Box<dyn StreamParser>>,
+    ledger: Option<LedgerClient>,
+    unsent: Vec<TrackedCall>,
+    /// Times each stream line (by hash) has been seen
+    seen_lines: HashMap<u64, u32>,
+}
+
+impl UsageRecorder {
+    /// Create a recorder that tags calls with `context`.
+    ///
+    /// The parser is auto-detected from the first line it can handle unless
+    /// set with [`with_parser`](Self::with_parser).
+    #[must_use]
+    pub fn new(tracker: CostTracker, context: TrackingContext) -> Self {
+        Self {
+            tracker,
+            context,
+            provider: None,
+            parser: None,
+            ledger: None,
+            unsent: Vec::new(),
+            seen_lines: HashMap::new(),
+        }
+    }
+
+    /// Use `parser` instead of auto-detecting one.
+    #[must_use]
+    pub fn with_parser(mut self, parser: Box<dyn StreamParser>) -> Self {
+        self.parser = Some(parser);
+        self
+    }
+
+    /// Record calls against `provider` instead of the CLI's default.
+    ///
+    /// Needed when a CLI talks to a compatible endpoint, e.g. Claude Code
+    /// served by Fireworks.
+    #[must_use]
+    pub fn with_provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    /// Queue recorded calls for `ledger` as well as the local tracker.
+    ///
+    /// Queued calls are collected with [`take_unsent`](Self::take_unsent).
+    #[must_use]
+    pub fn with_ledger(mut self, ledger: LedgerClient) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Build a recorder from the sidecar environment.
+    ///
+    /// With `CTO_COST_LEDGER_URL` set, calls are priced locally and queued
+    /// for the controller's ledger; otherwise they are recorded into the
+    /// `SQLite` ledger at `CTO_COST_LEDGER_PATH`. Returns `Ok(None)` when
+    /// neither is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ledger cannot be opened.
+    pub fn from_env() -> Result<Option<Self>, CostError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Option<Self>, CostError> {
+        let var = |name: &str| lookup(name).filter(|value| !value.trim().is_empty());
+
+        let ledger = var(COST_LEDGER_URL_ENV).map(|url| {
+            let ledger = LedgerClient::new(url);
+            match var(COST_LEDGER_TOKEN_ENV) {
+                Some(token) => ledger.with_token(token),
+                None => ledger,
+            }
+        });
+        let tracker = match (&ledger, var(COST_LEDGER_PATH_ENV)) {
+            (Some(_), _) => CostTracker::new(),
+            (None, Some(ledger_path)) => CostTracker::open(ledger_path)?,
+            (None, None) => return Ok(None),
+        };
+
+        let mut context = TrackingContext::new();
+        if let Some(project) = var("CTO_SERVICE") {
+            context = context.with_project(project);
+        }
+        if let Some(task) = var("TASK_ID") {
+            context = context.with_task(task);
+        }
+        if let Some(agent) = var("CTO_GITHUB_APP") {
+            context = context.with_agent(agent);
+        }
+        if let Some(session) = var("CODERUN_NAME") {
+            context = context.with_session(SessionId::new(session));
+        }
+        if let Some(iteration) = var("CTO_ATTEMPT_NUMBER").and_then(|v| v.parse().ok()) {
+            context = context.with_iteration(iteration);
+        }
+
+        let mut recorder = Self::new(tracker, context);
+        if let Some(ledger) = ledger {
+            recorder = recorder.with_ledger(ledger);
+        }
+        if let Some(provider) = var("RESOLVED_PROVIDER") {
+            recorder = recorder.with_provider(provider);
+        }
+        if let Some(parser) =
+            var("CLI_TYPE").and_then(|cli_type| ParserRegistry::new().get_by_cli_type(&cli_type))
+        {
+            recorder = recorder.with_parser(parser);
+        }
+        Ok(Some(recorder))
+    }
+
+    /// Tracking context attached to recorded calls.
+    #[must_use]
+    pub fn context(&self) -> &TrackingContext {
+        &self.context
+    }
+
+    /// Remote ledger recorded calls are queued for, if any.
+    #[must_use]
+    pub fn ledger(&self) -> Option<&LedgerClient> {
+        self.ledger.as_ref()
+    }
+
+    /// Take the calls queued for the remote ledger.
+    pub fn take_unsent(&mut self) -> Vec<TrackedCall> {
+        std::mem::take(&mut self.unsent)
+    }
+
+    /// Put calls that failed to send back in the queue.
+    ///
+    /// The oldest calls are dropped once the queue is full.
+    pub fn requeue(&mut self, mut calls: Vec<TrackedCall>) {
+        calls.append(&mut self.unsent);
+        self.unsent = calls;
+        self.cap_unsent();
+    }
+
+    fn cap_unsent(&mut self) {
+        let excess = self.unsent.len().saturating_sub(MAX_UNSENT_CALLS);
+        if excess > 0 {
+            tracing::warn!("Dropping {excess} unsent usage record(s)");
+            self.unsent.drain(..excess);
+        }
+    }
+
+    /// Parse a stream line and record any usage it reports.
+    ///
+    /// Returns the calls recorded for this line.
+    pub fn observe_line(&mut self, line: &str) -> Vec<TrackedCall> {
+        if self.parser.is_none() {
+            self.parser = ParserRegistry::new().detect_parser(line);
+        }
+        let Some(parser) = self.parser.as_mut() else {
+            return Vec::new();
+        };
+        let usage = parser.parse_line(line).usage;
+        if usage.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hasher = DefaultHasher::new();
+        line.hash(&mut hasher);
+        let line_hash = hasher.finish();
+        let occurrence = self.seen_lines.entry(line_hash).or_default();
+        *occurrence += 1;
+        let line_key = format!("{line_hash:016x}-{occurrence}");
+        self.record(&usage, &line_key)
+    }
+
+    /// Record usage records reported by one stream line as tracked calls.
+    ///
+    /// Call IDs combine the session, the attempt and `line_key`, so replaying
+    /// a stream after a sidecar restart does not double count while a retried
+    /// attempt or a restarted sidecar that only sees new lines does not
+    /// collide with calls already in the ledger.
+    pub fn record(&mut self, usage: &[UsageRecord], line_key: &str) -> Vec<TrackedCall> {
+        let calls: Vec<TrackedCall> = usage
+            .iter()
+            .enumerate()
+            .map(|(index, record)| {
+                let provider = self.provider.as_deref().unwrap_or(&record.provider);
+                let mut builder = self
+                    .tracker
+                    .builder()
+                    .context(self.context.clone())
+                    .provider(provider)
+                    .model(record.model.as_deref().unwrap_or("unknown"))
+                    .input_tokens(saturating_i64(record.input_tokens))
+                    .output_tokens(saturating_i64(record.output_tokens))
+                    .cached_tokens(saturating_i64(record.cached_tokens));
+                if let Some(duration) = record.duration_ms {
+                    builder = builder.duration_ms(duration);
+                }
+                if let Some(session) = &self.context.session_id {
+                    let attempt = self.context.iteration.unwrap_or(1);
+                    builder = builder.id(format!("{session}:{attempt}:usage:{line_key}:{index}"));
+                }
+                builder.record()
+            })
+            .collect();
+        if self.ledger.is_some() {
+            self.unsent.extend(calls.iter().cloned());
+            self.cap_unsent();
+        }
+        calls
+    }
+}
+
+fn saturating_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::ClaudeParser;
+    use cto_cost::tracking::SqliteStore;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    const RESULT_LINE: &str =
+        r#"{"type":"result","duration_ms":1000,"usage":{"input_tokens":1000,"output_tokens":100}}"#;
+
+    fn sqlite_tracker() -> CostTracker {
+        CostTracker::with_store(Arc::new(SqliteStore::in_memory().unwrap()))
+    }
+
+    #[test]
+    fn test_records_usage_with_run_context() {
+        let tracker = sqlite_tracker();
+        let context = TrackingContext::new()
+            .with_project("cto")
+            .with_task("42")
+            .with_agent("5DLabs-Rex")
+            .with_iteration(2);
+        let mut recorder = UsageRecorder::new(tracker.clone(), context);
+
+        recorder.observe_line(r#"{"type":"system","subtype":"init","model":"claude-sonnet-4-5"}"#);
+        let calls = recorder.observe_line(RESULT_LINE);
+
+        assert_eq!(calls.len(), 1);
+        let call = &calls[0];
+        assert_eq!(call.provider, "anthropic");
+        assert_eq!(call.model, "claude-sonnet-4-5");
+        assert_eq!(call.context.iteration, Some(2));
+        assert!(call.estimated_cost_usd > 0.0);
+        assert_eq!(tracker.task_metrics("42").total_tokens, 1100);
+    }
+
+    #[test]
+    fn test_replayed_stream_is_not_double_counted() {
+        let tracker = sqlite_tracker();
+        let context = TrackingContext::new().with_session(SessionId::new("coderun-a"));
+
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let mut recorder = UsageRecorder::new(tracker.clone(), context.clone())
+                .with_parser(Box::new(ClaudeParser::new()))
+                .with_provider("fireworks");
+            let calls = recorder.observe_line(RESULT_LINE);
+            assert!(calls[0].id.starts_with("coderun-a:1:usage:"));
+            assert_eq!(calls[0].provider, "fireworks");
+            ids.push(calls[0].id.clone());
+        }
+        assert_eq!(ids[0], ids[1]);
+        assert_eq!(tracker.len(), 1);
+    }
+
+    #[test]
+    fn test_retries_and_new_lines_after_restart_are_kept() {
+        let tracker = sqlite_tracker();
+        let session = TrackingContext::new().with_session(SessionId::new("coderun-a"));
+        let recorder = |context: &TrackingContext| {
+            UsageRecorder::new(tracker.clone(), context.clone())
+                .with_parser(Box::new(ClaudeParser::new()))
+        };
+
+        let mut first = recorder(&session.clone().with_iteration(1));
+        first.observe_line(RESULT_LINE);
+        // The same usage reported twice in one stream is two calls.
+        first.observe_line(RESULT_LINE);
+        assert_eq!(tracker.len(), 2);
+
+        // A restarted sidecar that only sees new lines.
+        let mut restarted = recorder(&session.clone().with_iteration(1));
+        restarted.observe_line(
+            r#"{"type":"result","duration_ms":500,"usage":{"input_tokens":10,"output_tokens":5}}"#,
+        );
+        assert_eq!(tracker.len(), 3);
+
+        // A retry of the same CodeRun replays identical output.
+        let mut retry = recorder(&session.with_iteration(2));
+        retry.observe_line(RESULT_LINE);
+        assert_eq!(tracker.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_calls_are_queued_for_the_remote_ledger() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/usage"))
+            .and(wiremock::matchers::header("authorization", "Bearer s3cret"))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let env: HashMap<&str, String> = HashMap::from([
+            (COST_LEDGER_URL_ENV, format!("{}/api/usage", server.uri())),
+            (COST_LEDGER_TOKEN_ENV, "s3cret".to_string()),
+            ("CODERUN_NAME", "coderun-a".to_string()),
+            ("CLI_TYPE", "claude".to_string()),
+        ]);
+        let mut recorder = UsageRecorder::from_lookup(|k| env.get(k).cloned())
+            .unwrap()
+            .unwrap();
+        recorder.observe_line(RESULT_LINE);
+
+        let unsent = recorder.take_unsent();
+        assert_eq!(unsent.len(), 1);
+        assert!(recorder.take_unsent().is_empty());
+        let ledger = recorder.ledger().unwrap().clone();
+        ledger.submit(&unsent).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let posted: Vec<TrackedCall> = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(posted[0].id, unsent[0].id);
+    }
+
+    #[test]
+    fn test_from_env_requires_ledger_path() {
+        let env: HashMap<&str, &str> = HashMap::from([("TASK_ID", "7")]);
+        let recorder = UsageRecorder::from_lookup(|k| env.get(k).map(ToString::to_string));
+        assert!(recorder.unwrap().is_none());
+    }
+}
@@ -126,6 +126,24 @@ impl DiscordChannel {
             } => {
                 format!("Starting remediation on `{repository}`\n**Reason:** {reason}")
             }
+
+            NotifyEvent::BudgetThreshold {
+                scope,
+                period,
+                spent_usd,
+                limit_usd,
+                exhausted,
+                ..
+            } => {
+                let action = if *exhausted {
+                    "\nNew CodeRuns for this scope are blocked until the window resets."
+                } else {
+                    ""
+                };
+                format!(
+                    "**{scope}** has spent ${spent_usd:.2} of its ${limit_usd:.2} {period} budget{action}"
+                )
+            }
         }
     }
 
@@ -206,6 +224,19 @@ impl DiscordChannel {
                 DiscordField::inline("Iteration", iteration.to_string()),
                 DiscordField::inline("Repository", repository),
             ],
+
+            NotifyEvent::BudgetThreshold {
+                budget,
+                period,
+                spent_usd,
+                threshold_usd,
+                ..
+            } => vec![
+                DiscordField::inline("Budget", budget),
+                DiscordField::inline("Period", period),
+                DiscordField::inline("Spent", format!("${spent_usd:.2}")),
+                DiscordField::inline("Threshold", format!("${threshold_usd:.2}")),
+            ],
         }
     }
 }
@@ -146,6 +146,24 @@ impl SlackChannel {
             } => {
                 format!("Starting remediation on `{repository}`\n*Reason:* {reason}")
             }
+
+            NotifyEvent::BudgetThreshold {
+                scope,
+                period,
+                spent_usd,
+                limit_usd,
+                exhausted,
+                ..
+            } => {
+                let action = if *exhausted {
+                    "\nNew CodeRuns for this scope are blocked until the window resets."
+                } else {
+                    ""
+                };
+                format!(
+                    "*{scope}* has spent ${spent_usd:.2} of its ${limit_usd:.2} {period} budget{action}"
+                )
+            }
         }
     }
 
@@ -226,6 +244,19 @@ impl SlackChannel {
                 ("Iteration".to_string(), iteration.to_string()),
                 ("Repository".to_string(), repository.clone()),
             ],
+
+            NotifyEvent::BudgetThreshold {
+                budget,
+                period,
+                spent_usd,
+                threshold_usd,
+                ..
+            } => vec![
+                ("Budget".to_string(), budget.clone()),
+                ("Period".to_string(), period.clone()),
+                ("Spent".to_string(), format!("${spent_usd:.2}")),
+                ("Threshold".to_string(), format!("${threshold_usd:.2}")),
+            ],
         }
     }
 }
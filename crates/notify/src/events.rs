@@ -120,6 +120,22 @@ pub enum NotifyEvent {
         #[serde(default = "Utc::now")]
         timestamp: DateTime<Utc>,
     },
+
+    // =========================================================================
+    // Cost events
+    // =========================================================================
+    /// A spending budget crossed its soft or hard threshold
+    BudgetThreshold {
+        budget: String,
+        scope: String,
+        period: String,
+        spent_usd: f64,
+        limit_usd: f64,
+        threshold_usd: f64,
+        exhausted: bool,
+        #[serde(default = "Utc::now")]
+        timestamp: DateTime<Utc>,
+    },
 }
 
 impl NotifyEvent {
@@ -158,6 +174,12 @@ impl NotifyEvent {
             } => {
                 format!("HEAL Remediation #{iteration}: Task #{task_id}")
             }
+            Self::BudgetThreshold {
+                budget, exhausted, ..
+            } => {
+                let status = if *exhausted { "Exhausted" } else { "Warning" };
+                format!("Budget {status}: {budget}")
+            }
         }
     }
 
@@ -179,6 +201,13 @@ impl NotifyEvent {
 
             Self::HealAlert { severity, .. } => *severity,
             Self::HealRemediation { .. } => Severity::Warning,
+            Self::BudgetThreshold { exhausted, .. } => {
+                if *exhausted {
+                    Severity::Critical
+                } else {
+                    Severity::Warning
+                }
+            }
         }
     }
 
@@ -192,7 +221,8 @@ impl NotifyEvent {
             | Self::AgentStarted { timestamp, .. }
             | Self::AgentCompleted { timestamp, .. }
             | Self::HealAlert { timestamp, .. }
-            | Self::HealRemediation { timestamp, .. } => *timestamp,
+            | Self::HealRemediation { timestamp, .. }
+            | Self::BudgetThreshold { timestamp, .. } => *timestamp,
         }
     }
 }
@@ -1,3 +1,4 @@
+use cto_config::{ConfigLayer, ConfigResolver, EffectiveConfig, BUILT_IN_ORIGIN};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
@@ -169,16 +170,23 @@ impl PlayConfig {
         let config: Self = serde_yaml::from_str(&content)?;
         Ok(config)
     }
+
+    /// Load the raw document, without serde defaults, for provenance tracking.
+    pub fn load_value(path: &Path) -> anyhow::Result<serde_json::Value> {
+        let content = std::fs::read_to_string(path)?;
+        let value: serde_json::Value = serde_yaml::from_str(&content)?;
+        Ok(value)
+    }
 }
 
 impl CtoConfig {
-    pub fn load(path: &Path) -> anyhow::Result<Self> {
+    /// Load the raw document with `$ref` pointers resolved.
+    pub fn load_value(path: &Path) -> anyhow::Result<serde_json::Value> {
         let content = std::fs::read_to_string(path)?;
         let mut raw: serde_json::Value = serde_json::from_str(&content)?;
         // Resolve $ref pointers in defaults.play
         Self::resolve_refs(&mut raw, path)?;
-        let config: Self = serde_json::from_value(raw)?;
-        Ok(config)
+        Ok(raw)
     }
 
     /// Resolve `{"$ref": "file.json#/path"}` references relative to the config file
@@ -215,98 +223,225 @@ impl CtoConfig {
     }
 }
 
+/// A lobster argument and where it is configured.
+struct PlaySetting {
+    /// Key in the args-json passed to lobster (also the CLI override key).
+    arg: &'static str,
+    /// Shared resolver key, matching `cto-config.json` naming.
+    key: &'static str,
+    /// Location in `.tasks/play-config.yaml`, if the repo can set it.
+    repo_path: Option<&'static str>,
+    /// Fallback when no layer sets a value.
+    fallback: Option<&'static str>,
+}
+
+const PLAY_SETTINGS: &[PlaySetting] = &[
+    PlaySetting {
+        arg: "repo_url",
+        key: "repository",
+        repo_path: Some("project.repoUrl"),
+        fallback: None,
+    },
+    PlaySetting {
+        arg: "namespace",
+        key: "namespace",
+        repo_path: Some("kubeconfig.namespace"),
+        fallback: Some("cto"),
+    },
+    PlaySetting {
+        arg: "base_branch",
+        key: "baseBranch",
+        repo_path: Some("project.baseBranch"),
+        fallback: Some("main"),
+    },
+    PlaySetting {
+        arg: "cli",
+        key: "cli",
+        repo_path: Some("defaults.cli"),
+        fallback: Some("claude"),
+    },
+    PlaySetting {
+        arg: "provider",
+        key: "provider",
+        repo_path: Some("defaults.provider"),
+        fallback: Some("fireworks"),
+    },
+    PlaySetting {
+        arg: "model",
+        key: "model",
+        repo_path: Some("defaults.model"),
+        fallback: Some("accounts/fireworks/models/kimi-k2p6"),
+    },
+    PlaySetting {
+        arg: "harness_agent",
+        key: "harnessAgent",
+        repo_path: Some("defaults.harnessAgent"),
+        fallback: Some("openclaw"),
+    },
+    PlaySetting {
+        arg: "github_app_prefix",
+        key: "githubAppPrefix",
+        repo_path: Some("defaults.githubAppPrefix"),
+        fallback: Some("5DLabs"),
+    },
+    PlaySetting {
+        arg: "service",
+        key: "service",
+        repo_path: Some("project.service"),
+        fallback: None,
+    },
+    PlaySetting {
+        arg: "docs_repository_url",
+        key: "docsRepository",
+        repo_path: Some("project.docsRepositoryUrl"),
+        fallback: None,
+    },
+    PlaySetting {
+        arg: "working_directory",
+        key: "workingDirectory",
+        repo_path: Some("project.workingDirectory"),
+        fallback: Some("."),
+    },
+    PlaySetting {
+        arg: "auto_merge",
+        key: "autoMerge",
+        repo_path: Some("defaults.autoMerge"),
+        fallback: Some("true"),
+    },
+    PlaySetting {
+        arg: "enable_docker",
+        key: "enableDocker",
+        repo_path: Some("defaults.enableDocker"),
+        fallback: Some("true"),
+    },
+    PlaySetting {
+        arg: "discord_enabled",
+        key: "discord.enabled",
+        repo_path: Some("discord.enabled"),
+        fallback: Some("false"),
+    },
+    PlaySetting {
+        arg: "discord_bridge_url",
+        key: "discord.bridgeUrl",
+        repo_path: Some("discord.bridgeUrl"),
+        fallback: None,
+    },
+    PlaySetting {
+        arg: "linear_session_id",
+        key: "linear.sessionId",
+        repo_path: None,
+        fallback: None,
+    },
+    PlaySetting {
+        arg: "linear_team_id",
+        key: "linear.teamId",
+        repo_path: None,
+        fallback: None,
+    },
+];
+
+/// A loaded config document and the file it came from.
+pub struct ConfigSource<'a> {
+    pub origin: String,
+    pub document: &'a serde_json::Value,
+}
+
+/// Layer built-in fallbacks, CTO play defaults, the per-repo play config and
+/// CLI overrides (lowest to highest precedence) into effective settings.
+pub fn resolve_play_settings(
+    play_config: &ConfigSource<'_>,
+    cto_config: Option<&ConfigSource<'_>>,
+    overrides: &HashMap<String, String>,
+) -> EffectiveConfig {
+    let mut resolver = ConfigResolver::new();
+
+    for setting in PLAY_SETTINGS {
+        if let Some(fallback) = setting.fallback {
+            resolver.set(
+                ConfigLayer::BuiltIn,
+                BUILT_IN_ORIGIN,
+                setting.key,
+                "",
+                fallback,
+            );
+        }
+    }
+
+    // Play runs are not tied to one agent, so only `defaults.play` applies,
+    // and only for the settings lobster takes.
+    if let Some(cto) = cto_config {
+        for setting in PLAY_SETTINGS {
+            let pointer = format!("/defaults/play/{}", setting.key.replace('.', "/"));
+            if let Some(value) = cto.document.pointer(&pointer) {
+                resolver.set(
+                    ConfigLayer::PlayDefaults,
+                    &cto.origin,
+                    setting.key,
+                    &format!("defaults.play.{}", setting.key),
+                    value.clone(),
+                );
+            }
+        }
+    }
+
+    for setting in PLAY_SETTINGS {
+        let Some(repo_path) = setting.repo_path else {
+            continue;
+        };
+        let pointer = format!("/{}", repo_path.replace('.', "/"));
+        if let Some(value) = play_config.document.pointer(&pointer) {
+            resolver.set(
+                ConfigLayer::RepoConfig,
+                &play_config.origin,
+                setting.key,
+                repo_path,
+                value.clone(),
+            );
+        }
+    }
+
+    for setting in PLAY_SETTINGS {
+        if let Some(value) = overrides.get(setting.arg) {
+            resolver.set(
+                ConfigLayer::Override,
+                &format!("--{}", setting.arg.replace('_', "-")),
+                setting.key,
+                "",
+                value.as_str(),
+            );
+        }
+    }
+
+    resolver.resolve()
+}
+
+/// Describe where each lobster argument came from.
+pub fn explain_play_settings(effective: &EffectiveConfig) -> String {
+    effective.explain_keys(PLAY_SETTINGS.iter().map(|setting| setting.key))
+}
+
 /// Build the merged args-json for lobster run
 pub fn build_args_json(
-    play_config: &PlayConfig,
+    effective: &EffectiveConfig,
     cto_config: &Option<CtoConfig>,
-    overrides: &HashMap<String, String>,
 ) -> serde_json::Value {
-    let pc = play_config;
     let cto_play = cto_config
         .as_ref()
         .and_then(|c| c.defaults.as_ref())
         .and_then(|d| d.play.as_ref());
 
-    // Helper: override > play-config > cto-default > fallback
-    let resolve_str =
-        |key: &str, play_val: &str, cto_val: Option<&str>, fallback: &str| -> String {
-            if let Some(v) = overrides.get(key) {
-                return v.clone();
-            }
-            if !play_val.is_empty() {
-                return play_val.to_string();
-            }
-            if let Some(v) = cto_val {
-                if !v.is_empty() {
-                    return v.to_string();
-                }
-            }
-            fallback.to_string()
+    let mut args = serde_json::Map::new();
+    for setting in PLAY_SETTINGS {
+        // Lobster templates compare against "true"/"false" strings.
+        let value = match effective.value(setting.key) {
+            Some(serde_json::Value::String(v)) => v.clone(),
+            Some(v) => v.to_string(),
+            None => String::new(),
         };
-
-    let repo_url = resolve_str(
-        "repo_url",
-        &pc.project.repo_url,
-        cto_play.map(|c| c.repository.as_str()),
-        "",
-    );
-    let namespace = resolve_str("namespace", &pc.kubeconfig.namespace, None, "cto");
-    let base_branch = resolve_str("base_branch", &pc.project.base_branch, None, "main");
-    let cli = resolve_str("cli", &pc.defaults.cli, None, "claude");
-    let provider = resolve_str("provider", &pc.defaults.provider, None, "fireworks");
-    let model = resolve_str(
-        "model",
-        &pc.defaults.model,
-        None,
-        "accounts/fireworks/models/kimi-k2p6",
-    );
-    let harness_agent = resolve_str(
-        "harness_agent",
-        &pc.defaults.harness_agent,
-        None,
-        "openclaw",
-    );
-    let github_app_prefix = resolve_str(
-        "github_app_prefix",
-        &pc.defaults.github_app_prefix,
-        None,
-        "5DLabs",
-    );
-    let _service = resolve_str(
-        "service",
-        &pc.project.service,
-        cto_play.map(|c| c.service.as_str()),
-        "",
-    );
-    let docs_repo = resolve_str(
-        "docs_repository_url",
-        &pc.project.docs_repository_url,
-        cto_play.map(|c| c.docs_repository.as_str()),
-        "",
-    );
-    let working_directory = resolve_str(
-        "working_directory",
-        &pc.project.working_directory,
-        cto_play.map(|c| c.working_directory.as_str()),
-        ".",
-    );
-    let discord_enabled = if overrides.contains_key("discord_enabled") {
-        overrides["discord_enabled"] == "true"
-    } else {
-        pc.discord.enabled
-    };
-    let discord_bridge_url = resolve_str("discord_bridge_url", &pc.discord.bridge_url, None, "");
-
-    // Auto-merge: override > play-config > cto-config > true
-    let auto_merge = if let Some(v) = overrides.get("auto_merge") {
-        v == "true"
-    } else if let Some(v) = pc.defaults.auto_merge {
-        v
-    } else if let Some(v) = cto_play.and_then(|c| c.auto_merge) {
-        v
-    } else {
-        true
-    };
+        args.insert(setting.arg.to_string(), serde_json::Value::String(value));
+    }
+    // The service is resolved for explain output but lobster derives its own.
+    args.remove("service");
 
     // Agent harness config — serialized as JSON string for lobster template consumption
     let agent_harness_json = cto_play
@@ -326,25 +461,8 @@ pub fn build_args_json(
         .map(|v| serde_json::to_string(v).unwrap_or_default())
         .unwrap_or_default();
 
-    serde_json::json!({
-        "repo_url": repo_url,
-        "namespace": namespace,
-        "base_branch": base_branch,
-        "cli": cli,
-        "provider": provider,
-        "model": model,
-        "harness_agent": harness_agent,
-        "github_app_prefix": github_app_prefix,
-        "working_directory": working_directory,
-        "auto_merge": if auto_merge { "true" } else { "false" },
-        "enable_docker": if pc.defaults.enable_docker { "true" } else { "false" },
-        "linear_session_id": overrides.get("linear_session_id").cloned().unwrap_or_default(),
-        "linear_team_id": overrides.get("linear_team_id").cloned().unwrap_or_default(),
-        "docs_repository_url": docs_repo,
-        "discord_enabled": if discord_enabled { "true" } else { "false" },
-        "discord_bridge_url": discord_bridge_url,
-        "agent_harness_json": agent_harness_json,
-        "openclaw_json": openclaw_json,
-        "acp_json": acp_json,
-    })
+    args.insert("agent_harness_json".into(), agent_harness_json.into());
+    args.insert("openclaw_json".into(), openclaw_json.into());
+    args.insert("acp_json".into(), acp_json.into());
+    serde_json::Value::Object(args)
 }
@@ -6,7 +6,10 @@ use clap::Parser;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-use config::{build_args_json, CtoConfig, PlayConfig};
+use config::{
+    build_args_json, explain_play_settings, resolve_play_settings, ConfigSource, CtoConfig,
+    PlayConfig,
+};
 use launcher::{find_play_config, find_play_yaml, run_lobster};
 
 #[derive(Parser, Debug)]
@@ -80,6 +83,10 @@ struct Cli {
     /// Show what would be passed to lobster without executing
     #[arg(long)]
     dry_run: bool,
+
+    /// Print each effective setting with the layer and file it came from, then exit
+    #[arg(long)]
+    explain: bool,
 }
 
 #[tokio::main]
@@ -95,12 +102,14 @@ async fn main() -> Result<()> {
     let play_config_path = find_play_config(&repo_path)?;
     let play_config = PlayConfig::load(&play_config_path)
         .with_context(|| format!("Failed to parse {}", play_config_path.display()))?;
+    let play_config_value = PlayConfig::load_value(&play_config_path)
+        .with_context(|| format!("Failed to parse {}", play_config_path.display()))?;
     eprintln!("Loaded play config: {}", play_config_path.display());
 
     // Load CTO config (optional — falls back to play-config defaults only)
-    let cto_config = load_cto_config(&cli.cto_config);
-    if cto_config.is_some() {
-        eprintln!("Loaded CTO config");
+    let cto_loaded = load_cto_config(&cli.cto_config);
+    if let Some((path, _, _)) = &cto_loaded {
+        eprintln!("Loaded CTO config: {}", path.display());
     }
 
     // Build overrides from CLI flags
@@ -143,7 +152,21 @@ async fn main() -> Result<()> {
     }
 
     // Merge configs and build args JSON
-    let args_json = build_args_json(&play_config, &cto_config, &overrides);
+    let play_source = ConfigSource {
+        origin: play_config_path.display().to_string(),
+        document: &play_config_value,
+    };
+    let cto_source = cto_loaded.as_ref().map(|(path, value, _)| ConfigSource {
+        origin: path.display().to_string(),
+        document: value,
+    });
+    let effective = resolve_play_settings(&play_source, cto_source.as_ref(), &overrides);
+    if cli.explain {
+        print!("{}", explain_play_settings(&effective));
+        return Ok(());
+    }
+    let cto_config = cto_loaded.map(|(_, _, config)| config);
+    let args_json = build_args_json(&effective, &cto_config);
 
     // Find and run play.lobster.yaml
     let play_yaml = find_play_yaml(&repo_path)?;
@@ -162,7 +185,9 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-fn load_cto_config(explicit_path: &Option<PathBuf>) -> Option<CtoConfig> {
+fn load_cto_config(
+    explicit_path: &Option<PathBuf>,
+) -> Option<(PathBuf, serde_json::Value, CtoConfig)> {
     // Priority: explicit flag > CTO_CONFIG env > /etc/cto/config.json > ./cto-config.json
     let candidates: Vec<PathBuf> = if let Some(p) = explicit_path {
         vec![p.clone()]
@@ -178,8 +203,12 @@ fn load_cto_config(explicit_path: &Option<PathBuf>) -> Option<CtoConfig> {
 
     for path in candidates {
         if path.exists() {
-            match CtoConfig::load(&path) {
-                Ok(config) => return Some(config),
+            let loaded = CtoConfig::load_value(&path).and_then(|value| {
+                let config: CtoConfig = serde_json::from_value(value.clone())?;
+                Ok((value, config))
+            });
+            match loaded {
+                Ok((value, config)) => return Some((path, value, config)),
                 Err(e) => {
                     eprintln!(
                         "Warning: could not parse CTO config at {}: {}",
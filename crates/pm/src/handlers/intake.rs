@@ -31,6 +31,7 @@ use crate::models::{
     ProjectCreateInput,
 };
 use crate::LinearClient;
+use config::{ConfigLayer, ConfigResolver, EffectiveConfig};
 
 /// Agent assignments for different task types.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -129,6 +130,12 @@ const KNOWN_AGENT_CONFIGS: &[(&str, &str, &str)] = &[
 /// Legacy: Known CLI options (kept for backwards compatibility with flat labels)
 const KNOWN_CLIS: &[&str] = &["claude", "codex", "cursor", "opencode", "gemini", "factory"];
 
+/// Provenance labels for the layers that make up the intake agent settings.
+const SERVER_DEFAULTS_ORIGIN: &str = "pm intake defaults";
+const ISSUE_ORIGIN: &str = "issue labels/frontmatter";
+const ISSUE_LABELS_ORIGIN: &str = "issue labels";
+const ISSUE_FRONTMATTER_ORIGIN: &str = "issue frontmatter";
+
 /// Legacy: Known model shortcuts (kept for backwards compatibility with flat labels)
 const KNOWN_MODEL_LABELS: &[(&str, &str)] = &[
     ("opus", "claude-opus-4-5-20251101"),
@@ -291,17 +298,99 @@ pub fn extract_config_from_labels(labels: &[Label]) -> CtoConfig {
 /// Frontmatter values override label values.
 #[must_use]
 pub fn extract_cto_config(issue: &Issue) -> CtoConfig {
+    let mut resolver = ConfigResolver::new();
+
     // 1. Extract from labels
-    let mut config = extract_config_from_labels(&issue.labels);
+    record_cto_config(
+        &mut resolver,
+        ISSUE_LABELS_ORIGIN,
+        &extract_config_from_labels(&issue.labels),
+    );
+
+    // 2. Parse frontmatter (recorded later in the same layer, so it overrides labels)
+    if let Some(fm_config) = issue.description.as_deref().and_then(parse_cto_frontmatter) {
+        record_cto_config(&mut resolver, ISSUE_FRONTMATTER_ORIGIN, &fm_config);
+    }
+
+    let effective = resolver.resolve();
+    if effective.keys().next().is_some() {
+        debug!(
+            issue = %issue.identifier,
+            "Resolved CTO config from issue:\n{}",
+            effective.explain()
+        );
+    }
+    CtoConfig {
+        cli: effective.str("cli").map(str::to_string),
+        model: effective.str("model").map(str::to_string),
+        prompt_style: effective.str("promptStyle").map(str::to_string),
+    }
+}
 
-    // 2. Parse frontmatter (overrides labels)
-    if let Some(desc) = &issue.description {
-        if let Some(fm_config) = parse_cto_frontmatter(desc) {
-            config.merge(&fm_config);
+/// Record the values set in an issue-level config as per-run overrides.
+fn record_cto_config(resolver: &mut ConfigResolver, origin: &str, config: &CtoConfig) {
+    for (key, value) in [
+        ("cli", &config.cli),
+        ("model", &config.model),
+        ("promptStyle", &config.prompt_style),
+    ] {
+        if let Some(value) = value {
+            resolver.set(ConfigLayer::Override, origin, key, key, value.as_str());
         }
     }
+}
 
-    config
+/// Resolve the intake agent's CLI, model and GitHub App.
+///
+/// Server defaults sit in the defaults layer; the issue's labels/frontmatter
+/// and then the project's `cto-config.json` are recorded as overrides, so the
+/// project config wins over the issue, which wins over the server defaults.
+fn resolve_intake_agent(
+    config: &IntakeConfig,
+    issue: &CtoConfig,
+    project: Option<(&str, &ProjectConfig)>,
+) -> EffectiveConfig {
+    let mut resolver = ConfigResolver::new();
+    resolver
+        .set(
+            ConfigLayer::Defaults,
+            SERVER_DEFAULTS_ORIGIN,
+            "cli",
+            "cli",
+            config.cli.as_str(),
+        )
+        .set(
+            ConfigLayer::Defaults,
+            SERVER_DEFAULTS_ORIGIN,
+            "model",
+            "primaryModel",
+            config.primary_model.as_str(),
+        )
+        .set(
+            ConfigLayer::Defaults,
+            SERVER_DEFAULTS_ORIGIN,
+            "githubApp",
+            "githubApp",
+            config.github_app.as_str(),
+        );
+    record_cto_config(&mut resolver, ISSUE_ORIGIN, issue);
+
+    if let Some((origin, project)) = project {
+        for (key, path, value) in [
+            ("cli", "defaults.intake.cli", &project.cli),
+            ("model", "defaults.intake.models.primary", &project.model),
+            (
+                "githubApp",
+                "defaults.intake.githubApp",
+                &project.github_app,
+            ),
+        ] {
+            if let Some(value) = value {
+                resolver.set(ConfigLayer::Override, origin, key, path, value.as_str());
+            }
+        }
+    }
+    resolver.resolve()
 }
 
 /// Extract tech stack from issue labels.
@@ -884,8 +973,18 @@ pub async fn read_project_config(
     let data = cm.data?;
     let json_content = data.get("cto-config.json")?;
 
-    match config::CtoConfig::from_json(json_content) {
-        Ok(config) => {
+    match config::CtoConfig::from_json_migrated(json_content) {
+        Ok((config, migration)) => {
+            if migration.migrated() {
+                warn!(
+                    configmap_name = %configmap_name,
+                    from_version = %migration.from_version,
+                    to_version = %migration.to_version,
+                    notes = ?migration.notes,
+                    "Project config uses an older schema version; migrated in memory"
+                );
+            }
+
             // Extract Morgan's tools configuration if present
             let morgan_tools = config.agents.get("morgan").map(|agent| {
                 debug!(
@@ -1004,22 +1103,29 @@ pub async fn submit_intake_coderun(
     let project_name_for_repo = request.project_name.as_deref().unwrap_or(&request.title);
 
     // Determine CLI and model - priority: project config > issue labels/frontmatter > server defaults
-    let cli = project_config
-        .as_ref()
-        .and_then(|c| c.cli.clone())
-        .or_else(|| request.cto_config.cli.clone())
-        .unwrap_or_else(|| config.cli.clone());
-
-    let primary_model = project_config
-        .as_ref()
-        .and_then(|c| c.model.clone())
-        .or_else(|| request.cto_config.model.clone())
-        .unwrap_or_else(|| config.primary_model.clone());
-
-    let github_app = project_config
+    let project_origin = request
+        .existing_project
         .as_ref()
-        .and_then(|c| c.github_app.clone())
-        .unwrap_or_else(|| config.github_app.clone());
+        .map(|project| configmap_name_for_project(&project.id));
+    let effective = resolve_intake_agent(
+        config,
+        &request.cto_config,
+        project_origin.as_deref().zip(project_config.as_ref()),
+    );
+    debug!(
+        prd_identifier = %request.prd_identifier,
+        "Effective intake agent config:\n{}",
+        effective.explain()
+    );
+    let cli = effective.str("cli").unwrap_or(&config.cli).to_string();
+    let primary_model = effective
+        .str("model")
+        .unwrap_or(&config.primary_model)
+        .to_string();
+    let github_app = effective
+        .str("githubApp")
+        .unwrap_or(&config.github_app)
+        .to_string();
 
     let source_branch = project_config
         .as_ref()
@@ -1284,12 +1390,9 @@ pub async fn submit_intake_workflow(
         .unwrap_or_else(|| "main".to_string());
 
     // Apply CTO config overrides from labels/frontmatter
-    let cli = request.cto_config.cli.as_deref().unwrap_or(&config.cli);
-    let primary_model = request
-        .cto_config
-        .model
-        .as_deref()
-        .unwrap_or(&config.primary_model);
+    let effective = resolve_intake_agent(config, &request.cto_config, None);
+    let cli = effective.str("cli").unwrap_or(&config.cli);
+    let primary_model = effective.str("model").unwrap_or(&config.primary_model);
 
     if !request.cto_config.is_empty() {
         info!(
@@ -1297,6 +1400,7 @@ pub async fn submit_intake_workflow(
             model = %primary_model,
             "Using CTO config overrides from issue"
         );
+        debug!("Effective intake agent config:\n{}", effective.explain());
     }
 
     // Prepare config JSON for the workflow.
@@ -1886,7 +1990,7 @@ pub async fn create_project_cto_config_document(
 /// Uses the shared `cto-config` crate for consistent config generation.
 #[must_use]
 pub fn generate_project_cto_config(request: &IntakeRequest) -> String {
-    use config::{generate_project_config, ProjectConfigInput};
+    use config::{generate_project_config_with_roster, ProjectConfigInput};
 
     // Build input for the shared config generator
     let input = ProjectConfigInput {
@@ -1902,12 +2006,24 @@ pub fn generate_project_cto_config(request: &IntakeRequest) -> String {
     };
 
     // Generate config using shared crate
-    let config = generate_project_config(&input);
+    let config = generate_project_config_with_roster(&input, &agent_roster());
 
     // Serialize to JSON
     config.to_json().unwrap_or_else(|_| "{}".to_string())
 }
 
+/// Load the agent roster from `CTO_AGENT_ROSTER_DIRS`.
+///
+/// Falls back to the built-in agents if a roster file is invalid, so a bad
+/// definition never blocks intake.
+#[must_use]
+pub fn agent_roster() -> config::AgentRoster {
+    config::AgentRoster::from_env().unwrap_or_else(|e| {
+        warn!("Ignoring agent roster, using built-in agents: {e}");
+        config::AgentRoster::default()
+    })
+}
+
 /// Derive a service name from a project name (lowercase, hyphenated).
 ///
 /// Re-exports from the shared `cto-config` crate for consistency.
@@ -2323,6 +2439,43 @@ Content here";
         assert_eq!(config.model, Some("gpt-4.1".to_string()));
     }
 
+    #[test]
+    fn test_resolve_intake_agent_layers() {
+        let config = IntakeConfig {
+            cli: "claude".to_string(),
+            ..IntakeConfig::default()
+        };
+        let issue = CtoConfig {
+            cli: Some("cursor".to_string()),
+            model: Some("claude-opus-4-5-20251101".to_string()),
+            prompt_style: None,
+        };
+        let project = ProjectConfig {
+            cli: None,
+            model: Some("gpt-5.1-codex".to_string()),
+            github_app: None,
+            repository: None,
+            service: None,
+            source_branch: None,
+            morgan_tools: None,
+            multi_model: None,
+        };
+
+        let effective =
+            resolve_intake_agent(&config, &issue, Some(("cto-config-project-abc", &project)));
+
+        assert_eq!(effective.str("cli"), Some("cursor"));
+        assert_eq!(effective.str("model"), Some("gpt-5.1-codex"));
+        assert_eq!(
+            effective.get("model").unwrap().source.origin,
+            "cto-config-project-abc"
+        );
+        assert_eq!(effective.str("githubApp"), Some(config.github_app.as_str()));
+        assert!(effective
+            .explain_keys(["cli"])
+            .contains("overrides \"claude\" from defaults (pm intake defaults: cli)"));
+    }
+
     #[test]
     fn test_extract_config_from_labels_empty() {
         let labels: Vec<Label> = vec![];
@@ -848,7 +848,10 @@ async fn handle_intake_setup(
             docs_repository: None,
             docs_project_directory: None,
         };
-        let config = config::generate_project_config(&config_input);
+        let config = config::generate_project_config_with_roster(
+            &config_input,
+            &crate::handlers::intake::agent_roster(),
+        );
         let config_json = config.to_json().unwrap_or_else(|_| "{}".to_string());
 
         // Derive service name for display
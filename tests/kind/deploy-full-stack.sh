@@ -597,7 +597,8 @@ spec:
           memory: "256Mi"
           cpu: "300m"
   dependencies:
-    - name: workflow-failure
+    # Passing runs are forwarded too: they feed flaky-test history and close incidents
+    - name: workflow-completed
       eventSourceName: github
       eventName: org
       filters:
@@ -610,11 +611,11 @@ spec:
             value: ["completed"]
           - path: body.workflow_run.conclusion
             type: string
-            value: ["failure"]
+            value: ["failure", "success"]
           - path: body.repository.full_name
             type: string
             value: ["5dlabs/cto"]
-    - name: job-failure
+    - name: job-completed
       eventSourceName: github
       eventName: org
       filters:
@@ -627,7 +628,7 @@ spec:
             value: ["completed"]
           - path: body.workflow_job.conclusion
             type: string
-            value: ["failure"]
+            value: ["failure", "success"]
           - path: body.repository.full_name
             type: string
             value: ["5dlabs/cto"]
@@ -647,8 +648,8 @@ spec:
             value: ["5dlabs/cto"]
   triggers:
     - template:
-        name: healer-workflow-failure
-        conditions: "workflow-failure"
+        name: healer-workflow-completed
+        conditions: "workflow-completed"
         http:
           url: http://healer.cto.svc.cluster.local:8080/api/remediate/ci-failure
           method: POST
@@ -656,7 +657,7 @@ spec:
             Content-Type: application/json
           payload:
             - src:
-                dependencyName: workflow-failure
+                dependencyName: workflow-completed
                 dataKey: body
               dest: ""
           timeout: 30s
@@ -664,8 +665,8 @@ spec:
           steps: 3
           duration: "10s"
     - template:
-        name: healer-job-failure
-        conditions: "job-failure"
+        name: healer-job-completed
+        conditions: "job-completed"
         http:
           url: http://healer.cto.svc.cluster.local:8080/api/remediate/ci-failure
           method: POST
@@ -673,7 +674,7 @@ spec:
             Content-Type: application/json
           payload:
             - src:
-                dependencyName: job-failure
+                dependencyName: job-completed
                 dataKey: body
               dest: ""
           timeout: 30s